@@ -6,6 +6,7 @@
 //! Managed device with configuration and state tracking.
 
 use serde::{Deserialize, Serialize};
+use tasmor_lib::Capabilities;
 use uuid::Uuid;
 
 use crate::device_model::DeviceModel;
@@ -47,6 +48,11 @@ pub struct DeviceConfig {
     pub username: Option<String>,
     /// Optional password for authentication
     pub password: Option<String>,
+    /// Explicit capabilities overriding the model defaults
+    ///
+    /// Used to pin capabilities for devices whose model preset is wrong.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<Capabilities>,
 }
 
 impl DeviceConfig {
@@ -62,6 +68,7 @@ impl DeviceConfig {
             topic: None,
             username: None,
             password: None,
+            capabilities: None,
         }
     }
 
@@ -77,6 +84,7 @@ impl DeviceConfig {
             topic: Some(topic),
             username: None,
             password: None,
+            capabilities: None,
         }
     }
 
@@ -87,6 +95,34 @@ impl DeviceConfig {
         self.password = Some(password);
         self
     }
+
+    /// Removes the authentication credentials.
+    #[must_use]
+    pub fn without_credentials(mut self) -> Self {
+        self.username = None;
+        self.password = None;
+        self
+    }
+
+    /// Pins explicit capabilities, overriding the model defaults.
+    ///
+    /// Config files set them through the `capabilities` field instead.
+    #[must_use]
+    #[allow(dead_code)]
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Returns the effective capabilities for this device.
+    ///
+    /// Explicit capabilities take precedence over the model defaults.
+    #[must_use]
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+            .clone()
+            .unwrap_or_else(|| self.model.capabilities())
+    }
 }
 
 /// Connection status of a device.
@@ -99,12 +135,22 @@ pub enum ConnectionStatus {
     Connecting,
     /// Connected and ready
     Connected,
-    /// Connection error (reserved for error handling implementation)
-    #[allow(dead_code)]
+    /// Connection error (e.g. the device stopped answering watchdog pings)
     Error,
 }
 
 impl ConnectionStatus {
+    /// Returns a short label for this status.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Disconnected => "disconnected",
+            Self::Connecting => "connecting",
+            Self::Connected => "connected",
+            Self::Error => "error",
+        }
+    }
+
     /// Returns the display color for this status.
     #[must_use]
     pub const fn color(self) -> egui::Color32 {
@@ -131,6 +177,10 @@ pub struct ManagedDevice {
     pub state: tasmor_lib::state::DeviceState,
     /// Last error message
     pub error: Option<String>,
+    /// Firmware version, once queried
+    pub firmware: Option<String>,
+    /// Last time the device answered or reported state
+    pub last_seen: Option<chrono::DateTime<chrono::Local>>,
 }
 
 impl ManagedDevice {
@@ -142,9 +192,16 @@ impl ManagedDevice {
             status: ConnectionStatus::Disconnected,
             state: tasmor_lib::state::DeviceState::new(),
             error: None,
+            firmware: None,
+            last_seen: None,
         }
     }
 
+    /// Records that the device was just heard from.
+    pub fn mark_seen(&mut self) {
+        self.last_seen = Some(chrono::Local::now());
+    }
+
     /// Returns the device model.
     #[must_use]
     pub const fn model(&self) -> DeviceModel {
@@ -341,13 +398,17 @@ pub enum StateUpdate {
     /// A device was removed (reserved for async device removal)
     #[allow(dead_code)]
     DeviceRemoved(uuid::Uuid),
-    /// A device's connection status changed (reserved for connection monitoring)
-    #[allow(dead_code)]
+    /// A device's connection status changed (MQTT connection or watchdog pings)
     ConnectionChanged {
         device_id: uuid::Uuid,
         status: ConnectionStatus,
         error: Option<String>,
     },
+    /// A device's free heap dropped dangerously low
+    LowHeap {
+        device_id: uuid::Uuid,
+        free_bytes: u32,
+    },
 }
 
 #[cfg(test)]
@@ -398,6 +459,72 @@ mod tests {
         assert_eq!(config.password, Some("password".to_string()));
     }
 
+    #[test]
+    fn capabilities_default_to_model() {
+        let config = DeviceConfig::new_http(
+            "Test Plug".to_string(),
+            DeviceModel::NousA1T,
+            "192.168.1.100".to_string(),
+        );
+
+        assert!(config.capabilities.is_none());
+        assert_eq!(config.capabilities(), DeviceModel::NousA1T.capabilities());
+    }
+
+    #[test]
+    fn explicit_capabilities_override_model() {
+        let config = DeviceConfig::new_http(
+            "Test Bulb".to_string(),
+            DeviceModel::AthomBulb5W7W,
+            "192.168.1.100".to_string(),
+        )
+        .with_capabilities(Capabilities::cct_light());
+
+        assert_eq!(config.capabilities(), Capabilities::cct_light());
+    }
+
+    #[test]
+    fn explicit_capabilities_round_trip() {
+        let config = DeviceConfig::new_mqtt(
+            "Test Plug".to_string(),
+            DeviceModel::NousA1T,
+            "mqtt://192.168.1.50:1883".to_string(),
+            "tasmota_plug".to_string(),
+        )
+        .with_capabilities(
+            tasmor_lib::CapabilitiesBuilder::new()
+                .power_channels(2)
+                .with_energy_monitoring()
+                .build(),
+        );
+
+        let json = serde_json::to_string(&config).unwrap();
+        let restored: DeviceConfig = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.capabilities, config.capabilities);
+        assert_eq!(restored.capabilities().power_channels(), 2);
+    }
+
+    #[test]
+    fn config_without_capabilities_field_loads() {
+        // Configs saved before explicit capabilities existed must still load
+        let json = r#"{
+            "id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "name": "Old Plug",
+            "model": "NousA1T",
+            "protocol": "Http",
+            "host": "192.168.1.100",
+            "topic": null,
+            "username": null,
+            "password": null
+        }"#;
+
+        let config: DeviceConfig = serde_json::from_str(json).unwrap();
+
+        assert!(config.capabilities.is_none());
+        assert_eq!(config.capabilities(), DeviceModel::NousA1T.capabilities());
+    }
+
     #[test]
     fn connection_status_colors() {
         assert_eq!(ConnectionStatus::Disconnected.color(), egui::Color32::GRAY);
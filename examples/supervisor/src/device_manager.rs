@@ -8,18 +8,36 @@
 //! This module provides device management using individual `Device` instances.
 //! MQTT devices share broker connections and receive real-time state updates via callbacks.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::mpsc;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use eframe::egui;
-use tasmor_lib::protocol::{HttpClient, SharedMqttClient};
+use tasmor_lib::command::StatusCommand;
+use tasmor_lib::protocol::{CommandResponse, HttpClient, SharedMqttClient};
+use tasmor_lib::response::{EnergyResponse, StatusFirmware, StatusResponse};
 use tasmor_lib::subscription::Subscribable;
+use tasmor_lib::types::{FirmwareVersion, PowerState};
 use tasmor_lib::{Device, MqttBroker};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::device_config::{ConnectionStatus, DeviceConfig, ManagedDevice, Protocol, StateUpdate};
+use crate::inventory::DeviceInventoryEntry;
+use crate::light_schedule::{self, LightSchedule};
+use crate::macro_library::MacroLibrary;
+use crate::vacation_mode::{VacationConfig, VacationMode};
+
+/// How long a queried firmware version is reused before asking again.
+const FIRMWARE_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// How long to wait for a device's `Status 2` answer.
+const FIRMWARE_QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Free heap below which the watchdog flags a device (ESP8266 builds
+/// typically have 20-30 KB free).
+const LOW_HEAP_BYTES: u32 = 10 * 1024;
 
 /// Key for identifying unique broker connections.
 #[derive(Hash, Eq, PartialEq, Clone, Debug)]
@@ -44,6 +62,7 @@ impl BrokerKey {
 }
 
 /// Wrapper for different device types.
+#[derive(Clone)]
 enum DeviceHandle {
     Http(Device<HttpClient>),
     /// MQTT device with reference to its broker key
@@ -71,10 +90,16 @@ pub struct DeviceManager {
     devices: Arc<RwLock<HashMap<Uuid, DeviceEntry>>>,
     /// Shared MQTT brokers keyed by connection info
     brokers: Arc<RwLock<HashMap<BrokerKey, MqttBroker>>>,
+    /// Named macros that can be run on any device
+    macros: Arc<RwLock<MacroLibrary>>,
     /// Channel sender for state updates to the UI
     update_tx: mpsc::Sender<StateUpdate>,
     /// Egui context for triggering repaints
     egui_ctx: egui::Context,
+    /// Running watchdog task, if started
+    watchdog: std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>,
+    /// Firmware versions by device, with the time they were queried
+    firmware_cache: RwLock<HashMap<Uuid, (Instant, FirmwareVersion)>>,
 }
 
 impl DeviceManager {
@@ -89,8 +114,11 @@ impl DeviceManager {
         Self {
             devices: Arc::new(RwLock::new(HashMap::new())),
             brokers: Arc::new(RwLock::new(HashMap::new())),
+            macros: Arc::new(RwLock::new(MacroLibrary::default())),
             update_tx,
             egui_ctx,
+            watchdog: std::sync::Mutex::new(None),
+            firmware_cache: RwLock::new(HashMap::new()),
         }
     }
 
@@ -135,7 +163,7 @@ impl DeviceManager {
     #[allow(clippy::too_many_lines)]
     pub async fn add_device(&self, config: DeviceConfig) -> Result<(), String> {
         let config_id = config.id;
-        let capabilities = config.model.capabilities();
+        let capabilities = config.capabilities();
 
         let (handle, initial_state) = match config.protocol {
             Protocol::Http => {
@@ -182,6 +210,7 @@ impl DeviceManager {
                         tokio::spawn(async move {
                             let mut devices = devices.write().await;
                             if let Some(entry) = devices.get_mut(&config_id) {
+                                entry.managed.mark_seen();
                                 if entry.managed.apply_state_change(&change) {
                                     tracing::debug!(
                                         device_id = %config_id,
@@ -276,6 +305,7 @@ impl DeviceManager {
         let mut managed = ManagedDevice::new(config);
         managed.status = ConnectionStatus::Connected;
         managed.state = initial_state;
+        managed.mark_seen();
 
         let entry = DeviceEntry { handle, managed };
         self.devices.write().await.insert(config_id, entry);
@@ -371,6 +401,77 @@ impl DeviceManager {
         Ok(broker)
     }
 
+    // =========================================================================
+    // Persistence
+    // =========================================================================
+
+    /// Exports the configurations of all managed devices, ordered by name.
+    ///
+    /// With `include_credentials` set to `false`, usernames and passwords are
+    /// stripped so the result can be shared or stored in plain text.
+    // Not wired into the UI yet
+    #[allow(dead_code)]
+    pub async fn export_configs(&self, include_credentials: bool) -> Vec<DeviceConfig> {
+        let mut configs: Vec<DeviceConfig> = self
+            .devices
+            .read()
+            .await
+            .values()
+            .map(|entry| {
+                let config = entry.managed.config.clone();
+                if include_credentials {
+                    config
+                } else {
+                    config.without_credentials()
+                }
+            })
+            .collect();
+        configs.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        configs
+    }
+
+    /// Lists all managed devices for documentation or auditing, ordered by
+    /// name.
+    ///
+    /// With `include_sensitive` set to `false`, usernames are left out so the
+    /// inventory can be shared; passwords are never included. Write the
+    /// result with [`inventory::to_json`](crate::inventory::to_json) or
+    /// [`inventory::to_csv`](crate::inventory::to_csv).
+    // Not wired into the UI yet
+    #[allow(dead_code)]
+    pub async fn inventory(&self, include_sensitive: bool) -> Vec<DeviceInventoryEntry> {
+        let mut entries: Vec<DeviceInventoryEntry> = self
+            .devices
+            .read()
+            .await
+            .values()
+            .map(|entry| DeviceInventoryEntry::from_managed(&entry.managed, include_sensitive))
+            .collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        entries
+    }
+
+    /// Imports device configurations, adding each device as
+    /// [`add_device`](Self::add_device) does.
+    ///
+    /// Configurations whose ID is already managed are skipped. A failing
+    /// device does not stop the import; its error is returned alongside its
+    /// ID so the caller can report it.
+    pub async fn import_configs(&self, configs: Vec<DeviceConfig>) -> Vec<(Uuid, String)> {
+        let mut failures = Vec::new();
+        for config in configs {
+            let id = config.id;
+            if self.devices.read().await.contains_key(&id) {
+                tracing::debug!(device_id = %id, "Skipping already managed device");
+                continue;
+            }
+            if let Err(e) = self.add_device(config).await {
+                failures.push((id, e));
+            }
+        }
+        failures
+    }
+
     // =========================================================================
     // Connection Management
     // =========================================================================
@@ -403,6 +504,188 @@ impl DeviceManager {
         }
     }
 
+    // =========================================================================
+    // Watchdog
+    // =========================================================================
+
+    /// Starts pinging connected devices to detect silent drop-offs.
+    ///
+    /// Every `interval`, each device that is connected (or already marked
+    /// unreachable) is sent a `Status 11`, with pings spread evenly over the
+    /// interval to avoid bursts. A ping fails if no answer arrives within
+    /// `timeout`. After `miss_threshold` consecutive failures the device is
+    /// marked [`ConnectionStatus::Error`] and [`StateUpdate::ConnectionChanged`]
+    /// is sent; its next answer sends it again with
+    /// [`ConnectionStatus::Connected`]. A recovered HTTP device is then
+    /// queried in full and whatever changed while it was offline is sent as
+    /// [`StateUpdate::StateChanged`], so HTTP devices report online/offline
+    /// like MQTT devices do through their LWT.
+    ///
+    /// The answer also carries the free heap. When it drops below
+    /// [`LOW_HEAP_BYTES`], [`StateUpdate::LowHeap`] is sent once, so a
+    /// device running out of memory is flagged before it crashes.
+    ///
+    /// Starting the watchdog again replaces the running one.
+    pub fn start_watchdog(&self, interval: Duration, timeout: Duration, miss_threshold: u32) {
+        let devices = Arc::clone(&self.devices);
+        let update_tx = self.update_tx.clone();
+        let egui_ctx = self.egui_ctx.clone();
+        let miss_threshold = miss_threshold.max(1);
+
+        let task = tokio::spawn(async move {
+            let mut misses: HashMap<Uuid, u32> = HashMap::new();
+            let mut low_heap: HashSet<Uuid> = HashSet::new();
+            let mut ticker = tokio::time::interval(interval);
+
+            loop {
+                ticker.tick().await;
+
+                let targets: Vec<(Uuid, DeviceHandle)> = devices
+                    .read()
+                    .await
+                    .iter()
+                    .filter(|(_, entry)| {
+                        matches!(
+                            entry.managed.status,
+                            ConnectionStatus::Connected | ConnectionStatus::Error
+                        )
+                    })
+                    .map(|(id, entry)| (*id, entry.handle.clone()))
+                    .collect();
+                misses.retain(|id, _| targets.iter().any(|(target, _)| target == id));
+                low_heap.retain(|id| targets.iter().any(|(target, _)| target == id));
+
+                let Ok(count) = u32::try_from(targets.len()) else {
+                    continue;
+                };
+                let stagger = interval.checked_div(count).unwrap_or_default();
+
+                for (i, (id, handle)) in targets.into_iter().enumerate() {
+                    if i > 0 {
+                        tokio::time::sleep(stagger).await;
+                    }
+
+                    let reply = ping(&handle, timeout).await;
+                    let answered = reply.is_some();
+
+                    if let Some(reply) = reply {
+                        if check_free_heap(&mut low_heap, &update_tx, id, &reply) {
+                            egui_ctx.request_repaint();
+                        }
+                    }
+
+                    let change =
+                        record_ping(misses.entry(id).or_default(), answered, miss_threshold);
+
+                    if answered {
+                        if let Some(entry) = devices.write().await.get_mut(&id) {
+                            entry.managed.mark_seen();
+                        }
+                    }
+                    if let Some((status, error)) = change {
+                        report_connection_change(&devices, &update_tx, id, &handle, status, error)
+                            .await;
+                        egui_ctx.request_repaint();
+                    }
+                }
+            }
+        });
+
+        let previous = self
+            .watchdog
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .replace(task);
+        if let Some(previous) = previous {
+            previous.abort();
+        }
+    }
+
+    /// Stops the watchdog, if running.
+    pub fn stop_watchdog(&self) {
+        let task = self
+            .watchdog
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take();
+        if let Some(task) = task {
+            task.abort();
+        }
+    }
+
+    // =========================================================================
+    // Firmware
+    // =========================================================================
+
+    /// Returns the firmware version of every managed device.
+    ///
+    /// Devices are queried concurrently with `Status 2`. A version is
+    /// cached for [`FIRMWARE_CACHE_TTL`], so planning an upgrade does not
+    /// query the whole fleet again each time. Devices that do not answer,
+    /// or report a version that cannot be parsed, map to `None` and are
+    /// queried again next time.
+    // Not wired into the UI yet
+    #[allow(dead_code)]
+    pub async fn firmware_versions(&self) -> HashMap<Uuid, Option<FirmwareVersion>> {
+        let handles: Vec<(Uuid, DeviceHandle)> = self
+            .devices
+            .read()
+            .await
+            .iter()
+            .map(|(id, entry)| (*id, entry.handle.clone()))
+            .collect();
+
+        let mut versions = HashMap::new();
+        let mut queries = tokio::task::JoinSet::new();
+        {
+            let cache = self.firmware_cache.read().await;
+            for (id, handle) in handles {
+                match cache.get(&id) {
+                    Some((fetched, version)) if fetched.elapsed() < FIRMWARE_CACHE_TTL => {
+                        versions.insert(id, Some(*version));
+                    }
+                    _ => {
+                        queries.spawn(async move { (id, query_firmware(&handle).await) });
+                    }
+                }
+            }
+        }
+
+        while let Some(result) = queries.join_next().await {
+            let Ok((id, version)) = result else {
+                continue;
+            };
+            if let Some(version) = version {
+                self.firmware_cache
+                    .write()
+                    .await
+                    .insert(id, (Instant::now(), version));
+            }
+            versions.insert(id, version);
+        }
+
+        versions
+    }
+
+    /// Returns the devices running firmware older than `version`.
+    ///
+    /// These are the candidates for an upgrade. Devices whose version is
+    /// unknown are left out. Uses [`firmware_versions`](Self::firmware_versions),
+    /// and so its cache.
+    // Not wired into the UI yet
+    #[allow(dead_code)]
+    pub async fn devices_below_version(&self, version: FirmwareVersion) -> Vec<Uuid> {
+        let mut outdated: Vec<Uuid> = self
+            .firmware_versions()
+            .await
+            .into_iter()
+            .filter(|(_, current)| current.is_some_and(|current| current < version))
+            .map(|(id, _)| id)
+            .collect();
+        outdated.sort_unstable();
+        outdated
+    }
+
     // =========================================================================
     // Device Commands
     // =========================================================================
@@ -743,6 +1026,212 @@ impl DeviceManager {
         Ok(())
     }
 
+    // =========================================================================
+    // Schedules
+    // =========================================================================
+
+    /// Drives a light to the target of `schedule` at time of day `now`.
+    ///
+    /// The target is interpolated between the surrounding keyframes and
+    /// restricted to what the device supports. Only the values that differ
+    /// from the device's current state are sent, as a single routine, so
+    /// calling this on a timer gives smooth all-day lighting. Taking `now`
+    /// as a parameter keeps the result deterministic.
+    ///
+    /// Returns the target that was applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the schedule is empty, the device is unknown or
+    /// not a light, or the device rejects the update.
+    // Not wired into the UI yet
+    #[allow(dead_code)]
+    pub async fn apply_schedule_now(
+        &self,
+        config_id: Uuid,
+        schedule: &LightSchedule,
+        now: chrono::NaiveTime,
+    ) -> Result<tasmor_lib::state::LightState, String> {
+        let target = schedule.target_at(now).ok_or("Schedule has no keyframes")?;
+
+        let (target, state) = {
+            let devices = self.devices.read().await;
+            let entry = devices.get(&config_id).ok_or("Device not found")?;
+
+            match &entry.handle {
+                DeviceHandle::Http(device) => {
+                    let target = light_schedule::supported_fields(target, device.capabilities());
+                    let state = device.update_light(|_| target).await;
+                    (target, state)
+                }
+                DeviceHandle::Mqtt { device, .. } => {
+                    let target = light_schedule::supported_fields(target, device.capabilities());
+                    let state = device.update_light(|_| target).await;
+                    (target, state)
+                }
+            }
+        };
+        let state = state.map_err(|e| e.to_string())?;
+
+        // Update local state for HTTP devices
+        let mut devices = self.devices.write().await;
+        if let Some(entry) = devices.get_mut(&config_id) {
+            if matches!(entry.handle, DeviceHandle::Http(_)) {
+                tasmor_lib::state::LightState::from_device_state(&state)
+                    .apply_to(&mut entry.managed.state);
+            }
+        }
+
+        Ok(target)
+    }
+
+    /// Runs vacation mode until `cancel` is set to `true` or its sender is
+    /// dropped.
+    ///
+    /// Every night the configured devices are switched on and off following
+    /// the next plan of a [`VacationMode`] generator, using the regular power
+    /// commands. A night already in progress is joined, skipping its past
+    /// events. A device that fails to switch is logged and left for its next
+    /// event.
+    ///
+    /// On cancellation the power states from before the run are restored if
+    /// the configuration asks for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a configured device is unknown.
+    // Not wired into the UI yet
+    #[allow(dead_code)]
+    pub async fn run_vacation_mode(
+        &self,
+        config: VacationConfig,
+        mut cancel: tokio::sync::watch::Receiver<bool>,
+    ) -> Result<(), String> {
+        let prior: Vec<(Uuid, Option<PowerState>)> = {
+            let devices = self.devices.read().await;
+            config
+                .device_ids()
+                .map(|id| {
+                    let entry = devices.get(&id).ok_or("Device not found")?;
+                    Ok((id, entry.managed.state.power(1)))
+                })
+                .collect::<Result<_, String>>()?
+        };
+        let window = config.window();
+        let restore = config.restores_on_cancel();
+        let mut mode = VacationMode::new(config);
+
+        let mut night_start = window.current_or_next_start(chrono::Local::now().naive_local());
+        'nights: loop {
+            for event in mode.next_night() {
+                let Ok(offset) = chrono::Duration::from_std(event.offset) else {
+                    continue;
+                };
+                let wait = (night_start + offset - chrono::Local::now().naive_local()).to_std();
+                // Events already past belong to a night in progress
+                let Ok(wait) = wait else {
+                    continue;
+                };
+
+                tokio::select! {
+                    () = tokio::time::sleep(wait) => {}
+                    _ = cancel.wait_for(|cancelled| *cancelled) => break 'nights,
+                }
+
+                tracing::debug!(device_id = %event.device_id, power = %event.power, "Vacation mode switch");
+                let result = match event.power {
+                    PowerState::On => self.power_on(event.device_id).await,
+                    _ => self.power_off(event.device_id).await,
+                };
+                if let Err(e) = result {
+                    tracing::warn!(device_id = %event.device_id, error = %e, "Vacation mode switch failed");
+                }
+            }
+
+            night_start += chrono::Duration::days(1);
+            let wait = (night_start - chrono::Local::now().naive_local())
+                .to_std()
+                .unwrap_or_default();
+            tokio::select! {
+                () = tokio::time::sleep(wait) => {}
+                _ = cancel.wait_for(|cancelled| *cancelled) => break 'nights,
+            }
+        }
+
+        if restore {
+            for (id, power) in prior {
+                let result = match power {
+                    Some(PowerState::On) => self.power_on(id).await,
+                    Some(PowerState::Off) => self.power_off(id).await,
+                    _ => continue,
+                };
+                if let Err(e) = result {
+                    tracing::warn!(device_id = %id, error = %e, "Failed to restore power state");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // =========================================================================
+    // Macros
+    // =========================================================================
+
+    /// Replaces the macro library (e.g. with the one loaded from the config).
+    pub async fn set_macro_library(&self, library: MacroLibrary) {
+        *self.macros.write().await = library;
+    }
+
+    /// Returns the names of the macros that take no arguments.
+    pub async fn runnable_macros(&self) -> Vec<String> {
+        self.macros
+            .read()
+            .await
+            .entries()
+            .filter(|(_, macro_def)| macro_def.params().is_empty())
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    /// Runs a named macro on a device.
+    ///
+    /// The macro is executed as a single routine (`Backlog0`). MQTT devices
+    /// report the resulting state changes through their callbacks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the macro or device is unknown, an argument is
+    /// missing or invalid, or the device rejects the routine.
+    pub async fn run_macro(
+        &self,
+        config_id: Uuid,
+        name: &str,
+        args: &HashMap<String, u32>,
+    ) -> Result<(), String> {
+        let routine = {
+            let macros = self.macros.read().await;
+            let macro_def = macros
+                .get(name)
+                .ok_or_else(|| format!("Unknown macro '{name}'"))?;
+            macro_def.to_routine(args)?
+        };
+
+        let devices = self.devices.read().await;
+        let entry = devices.get(&config_id).ok_or("Device not found")?;
+
+        match &entry.handle {
+            DeviceHandle::Http(device) => {
+                device.run(&routine).await.map_err(|e| e.to_string())?;
+            }
+            DeviceHandle::Mqtt { device, .. } => {
+                device.run(&routine).await.map_err(|e| e.to_string())?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Resets the total energy counter and returns the updated energy data.
     ///
     /// Returns the updated `DeviceState` with the new energy values including `TotalStartTime`.
@@ -799,6 +1288,7 @@ impl DeviceManager {
             DeviceHandle::Http(device) => device.get_power().await,
             DeviceHandle::Mqtt { device, .. } => device.get_power().await,
         };
+        let answered = power_result.is_ok();
         if let Ok(power_response) = power_result {
             if let Ok(power_state) = power_response.first_power_state() {
                 state.set_power(1, power_state);
@@ -844,23 +1334,7 @@ impl DeviceManager {
             DeviceHandle::Mqtt { device, .. } => device.energy().await,
         };
         if let Ok(energy_response) = energy_result {
-            if let Some(energy) = energy_response.energy() {
-                state.set_power_consumption(energy.power);
-                state.set_voltage(energy.voltage);
-                state.set_current(energy.current);
-                state.set_energy_today(energy.today);
-                state.set_energy_yesterday(energy.yesterday);
-                state.set_energy_total(energy.total);
-                state.set_apparent_power(energy.apparent_power);
-                state.set_reactive_power(energy.reactive_power);
-                state.set_power_factor(energy.factor);
-                if let Some(start_time) = &energy.total_start_time {
-                    state.set_total_start_time(start_time.clone());
-                }
-                if let Some(freq) = energy.frequency {
-                    state.set_frequency(freq);
-                }
-            }
+            apply_energy(&mut state, &energy_response);
         }
 
         // Query fade state if supported
@@ -885,11 +1359,28 @@ impl DeviceManager {
             }
         }
 
+        // Query firmware version
+        let firmware_cmd = StatusCommand::firmware();
+        let firmware_result = match &entry.handle {
+            DeviceHandle::Http(device) => device.send_command(&firmware_cmd).await,
+            DeviceHandle::Mqtt { device, .. } => device.send_command(&firmware_cmd).await,
+        };
+        let firmware = firmware_result
+            .ok()
+            .and_then(|response| response.parse::<StatusResponse>().ok())
+            .and_then(|status| status.firmware_version().map(str::to_string));
+
         // Update our local copy
         drop(devices);
         let mut devices = self.devices.write().await;
         if let Some(entry) = devices.get_mut(&config_id) {
             entry.managed.state = state.clone();
+            if firmware.is_some() {
+                entry.managed.firmware = firmware;
+            }
+            if answered {
+                entry.managed.mark_seen();
+            }
         }
 
         Ok(state)
@@ -900,6 +1391,7 @@ impl DeviceManager {
     /// Disconnects all devices and brokers cleanly.
     pub async fn shutdown(&self) {
         tracing::info!("Shutting down device manager");
+        self.stop_watchdog();
 
         // Disconnect all MQTT devices first
         let devices = self.devices.write().await;
@@ -922,6 +1414,185 @@ impl DeviceManager {
     }
 }
 
+/// Queries a device's firmware version with `Status 2`.
+async fn query_firmware(handle: &DeviceHandle) -> Option<FirmwareVersion> {
+    let cmd = StatusCommand::firmware();
+    let response = match handle {
+        DeviceHandle::Http(device) => {
+            tokio::time::timeout(FIRMWARE_QUERY_TIMEOUT, device.send_command(&cmd)).await
+        }
+        DeviceHandle::Mqtt { device, .. } => {
+            tokio::time::timeout(FIRMWARE_QUERY_TIMEOUT, device.send_command(&cmd)).await
+        }
+    }
+    .ok()?
+    .ok()?;
+
+    response
+        .parse::<StatusResponse>()
+        .ok()?
+        .firmware
+        .as_ref()
+        .and_then(StatusFirmware::parsed_version)
+}
+
+/// Copies the readings of an energy response into `state`.
+fn apply_energy(state: &mut tasmor_lib::state::DeviceState, response: &EnergyResponse) {
+    let Some(energy) = response.energy() else {
+        return;
+    };
+    state.set_power_consumption(energy.power);
+    state.set_voltage(energy.voltage);
+    state.set_current(energy.current);
+    state.set_energy_today(energy.today);
+    state.set_energy_yesterday(energy.yesterday);
+    state.set_energy_total(energy.total);
+    state.set_apparent_power(energy.apparent_power);
+    state.set_reactive_power(energy.reactive_power);
+    state.set_power_factor(energy.factor);
+    if let Some(start_time) = &energy.total_start_time {
+        state.set_total_start_time(start_time.clone());
+    }
+    if let Some(freq) = energy.frequency {
+        state.set_frequency(freq);
+    }
+}
+
+/// Sends a watchdog ping (`Status 11`), returning the answer if one arrives
+/// within `timeout`.
+async fn ping(handle: &DeviceHandle, timeout: Duration) -> Option<CommandResponse> {
+    let cmd = StatusCommand::state();
+    match handle {
+        DeviceHandle::Http(device) => {
+            tokio::time::timeout(timeout, device.send_command(&cmd)).await
+        }
+        DeviceHandle::Mqtt { device, .. } => {
+            tokio::time::timeout(timeout, device.send_command(&cmd)).await
+        }
+    }
+    .ok()
+    .and_then(Result::ok)
+}
+
+/// Updates a device's count of consecutive missed pings.
+///
+/// Returns the new connection status and error when the device just became
+/// unreachable (after `miss_threshold` misses) or reachable again.
+fn record_ping(
+    miss_count: &mut u32,
+    answered: bool,
+    miss_threshold: u32,
+) -> Option<(ConnectionStatus, Option<String>)> {
+    if answered {
+        let was_unreachable = *miss_count >= miss_threshold;
+        *miss_count = 0;
+        was_unreachable.then_some((ConnectionStatus::Connected, None))
+    } else {
+        *miss_count += 1;
+        (*miss_count == miss_threshold).then(|| {
+            (
+                ConnectionStatus::Error,
+                Some(format!("No response to {miss_threshold} pings")),
+            )
+        })
+    }
+}
+
+/// Sends [`StateUpdate::LowHeap`] once when the free heap in a ping answer
+/// drops below [`LOW_HEAP_BYTES`].
+///
+/// `low_heap` holds the devices already reported; a device leaves it when
+/// its heap recovers. Returns whether an update was sent.
+fn check_free_heap(
+    low_heap: &mut HashSet<Uuid>,
+    update_tx: &mpsc::Sender<StateUpdate>,
+    device_id: Uuid,
+    reply: &CommandResponse,
+) -> bool {
+    let Some(free_bytes) = reply
+        .parse::<StatusResponse>()
+        .ok()
+        .and_then(|status| status.free_heap())
+    else {
+        return false;
+    };
+    if free_bytes >= LOW_HEAP_BYTES {
+        low_heap.remove(&device_id);
+        return false;
+    }
+    if !low_heap.insert(device_id) {
+        return false;
+    }
+    tracing::warn!(%device_id, free_bytes, "Device low on heap");
+    let _ = update_tx.send(StateUpdate::LowHeap {
+        device_id,
+        free_bytes,
+    });
+    true
+}
+
+/// Records a connection status found by the watchdog and sends it as an
+/// update.
+///
+/// A recovered HTTP device is queried in full, since it pushes nothing while
+/// offline.
+async fn report_connection_change(
+    devices: &RwLock<HashMap<Uuid, DeviceEntry>>,
+    update_tx: &mpsc::Sender<StateUpdate>,
+    device_id: Uuid,
+    handle: &DeviceHandle,
+    status: ConnectionStatus,
+    error: Option<String>,
+) {
+    let recovered = status == ConnectionStatus::Connected;
+    if recovered {
+        tracing::info!(%device_id, "Device reachable again");
+    } else {
+        tracing::warn!(%device_id, ?error, "Device unreachable");
+    }
+    if let Some(entry) = devices.write().await.get_mut(&device_id) {
+        entry.managed.status = status;
+        entry.managed.error.clone_from(&error);
+    }
+    let _ = update_tx.send(StateUpdate::ConnectionChanged {
+        device_id,
+        status,
+        error,
+    });
+
+    if recovered {
+        if let DeviceHandle::Http(device) = handle {
+            refresh_state(devices, update_tx, device_id, device).await;
+        }
+    }
+}
+
+/// Queries an HTTP device's full state and sends what differs as updates.
+async fn refresh_state(
+    devices: &RwLock<HashMap<Uuid, DeviceEntry>>,
+    update_tx: &mpsc::Sender<StateUpdate>,
+    device_id: Uuid,
+    device: &Device<HttpClient>,
+) {
+    let state = match device.query_state().await {
+        Ok(state) => state,
+        Err(e) => {
+            tracing::warn!(device_id = %device_id, error = %e, "Failed to refresh device state");
+            return;
+        }
+    };
+
+    let mut devices = devices.write().await;
+    let Some(entry) = devices.get_mut(&device_id) else {
+        return;
+    };
+    for change in state.to_state_changes() {
+        if entry.managed.apply_state_change(&change) {
+            let _ = update_tx.send(StateUpdate::StateChanged { device_id, change });
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -981,4 +1652,173 @@ mod tests {
         assert!(removed);
         assert!(manager.devices().await.is_empty());
     }
+
+    #[tokio::test]
+    async fn export_and_import_configs() {
+        let (manager, _rx) = create_test_manager();
+
+        let bulb = DeviceConfig::new_http(
+            "Bulb".to_string(),
+            DeviceModel::AthomBulb5W7W,
+            "192.168.1.100".to_string(),
+        )
+        .with_credentials("admin".to_string(), "secret".to_string());
+        let plug = DeviceConfig::new_http(
+            "Plug".to_string(),
+            DeviceModel::NousA1T,
+            "192.168.1.101".to_string(),
+        );
+        manager.add_device(plug).await.unwrap();
+        manager.add_device(bulb).await.unwrap();
+
+        let exported = manager.export_configs(true).await;
+        assert_eq!(exported.len(), 2);
+        assert_eq!(exported[0].name, "Bulb");
+        assert_eq!(exported[0].password.as_deref(), Some("secret"));
+
+        let redacted = manager.export_configs(false).await;
+        assert!(redacted[0].username.is_none());
+        assert!(redacted[0].password.is_none());
+
+        // Round-trip through JSON into a fresh manager
+        let json = serde_json::to_string(&exported).unwrap();
+        let (restored, _rx) = create_test_manager();
+        let failures = restored
+            .import_configs(serde_json::from_str(&json).unwrap())
+            .await;
+        assert!(failures.is_empty());
+        assert_eq!(restored.export_configs(true).await.len(), 2);
+
+        // Importing the same devices again is a no-op
+        let failures = restored.import_configs(exported).await;
+        assert!(failures.is_empty());
+        assert_eq!(restored.devices().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn watchdog_reports_unreachable_device() {
+        let (manager, rx) = create_test_manager();
+
+        // Nothing listens on port 1, so every ping fails
+        let config = DeviceConfig::new_http(
+            "Gone".to_string(),
+            DeviceModel::NousA1T,
+            "127.0.0.1:1".to_string(),
+        );
+        let id = config.id;
+        manager.add_device(config).await.unwrap();
+
+        manager.start_watchdog(Duration::from_millis(20), Duration::from_millis(500), 2);
+
+        let mut update = None;
+        for _ in 0..250 {
+            if let Ok(received) = rx.try_recv() {
+                update = Some(received);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        manager.stop_watchdog();
+
+        assert!(matches!(
+            update,
+            Some(StateUpdate::ConnectionChanged {
+                device_id,
+                status: ConnectionStatus::Error,
+                error: Some(_),
+            }) if device_id == id
+        ));
+        assert_eq!(manager.devices().await[0].status, ConnectionStatus::Error);
+    }
+
+    #[test]
+    fn record_ping_reports_threshold_crossings() {
+        let mut miss_count = 0;
+        assert!(record_ping(&mut miss_count, false, 2).is_none());
+        assert!(matches!(
+            record_ping(&mut miss_count, false, 2),
+            Some((ConnectionStatus::Error, Some(_)))
+        ));
+        // Further misses do not report again
+        assert!(record_ping(&mut miss_count, false, 2).is_none());
+        assert!(matches!(
+            record_ping(&mut miss_count, true, 2),
+            Some((ConnectionStatus::Connected, None))
+        ));
+        assert_eq!(miss_count, 0);
+        assert!(record_ping(&mut miss_count, true, 2).is_none());
+    }
+
+    #[tokio::test]
+    async fn firmware_versions_of_unreachable_device_are_unknown() {
+        let (manager, _rx) = create_test_manager();
+
+        // Nothing listens on port 1, so the query fails
+        let config = DeviceConfig::new_http(
+            "Gone".to_string(),
+            DeviceModel::NousA1T,
+            "127.0.0.1:1".to_string(),
+        );
+        let id = config.id;
+        manager.add_device(config).await.unwrap();
+
+        let versions = manager.firmware_versions().await;
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[&id], None);
+
+        // Unknown versions are not upgrade candidates
+        let outdated = manager
+            .devices_below_version(FirmwareVersion::new(99, 0, 0))
+            .await;
+        assert!(outdated.is_empty());
+    }
+
+    #[tokio::test]
+    async fn runnable_macros_skip_parameterized() {
+        let (manager, _rx) = create_test_manager();
+
+        let library = serde_json::from_str(
+            r#"{
+                "goodnight": { "steps": [{ "action": "power_off", "relay": 1 }] },
+                "dim_to": { "steps": [{ "action": "dimmer", "level": "level" }] }
+            }"#,
+        )
+        .unwrap();
+        manager.set_macro_library(library).await;
+
+        assert_eq!(manager.runnable_macros().await, ["goodnight"]);
+    }
+
+    #[tokio::test]
+    async fn run_unknown_macro_fails() {
+        let (manager, _rx) = create_test_manager();
+
+        let err = manager
+            .run_macro(Uuid::new_v4(), "missing", &HashMap::new())
+            .await
+            .unwrap_err();
+        assert!(err.contains("missing"));
+    }
+
+    #[tokio::test]
+    async fn apply_schedule_requires_keyframes_and_device() {
+        let (manager, _rx) = create_test_manager();
+        let now = chrono::NaiveTime::from_hms_opt(12, 0, 0).unwrap();
+
+        let err = manager
+            .apply_schedule_now(Uuid::new_v4(), &LightSchedule::new(), now)
+            .await
+            .unwrap_err();
+        assert!(err.contains("keyframes"));
+
+        let schedule = LightSchedule::new().with_keyframe(
+            now,
+            tasmor_lib::state::LightState::new().with_power(tasmor_lib::PowerState::On),
+        );
+        let err = manager
+            .apply_schedule_now(Uuid::new_v4(), &schedule, now)
+            .await
+            .unwrap_err();
+        assert_eq!(err, "Device not found");
+    }
 }
@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Device inventory export.
+//!
+//! An inventory lists every managed device in one flat record, for
+//! documentation and auditing. It can be written as JSON or as CSV for a
+//! spreadsheet.
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::device_config::{ManagedDevice, Protocol};
+
+/// Column names of the CSV export, in order.
+const CSV_HEADER: [&str; 11] = [
+    "id",
+    "name",
+    "model",
+    "protocol",
+    "host",
+    "topic",
+    "capabilities",
+    "status",
+    "firmware",
+    "last_seen",
+    "username",
+];
+
+/// One device of an inventory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeviceInventoryEntry {
+    /// Device ID
+    pub id: Uuid,
+    /// User-friendly name
+    pub name: String,
+    /// Device model name
+    pub model: String,
+    /// Communication protocol
+    pub protocol: Protocol,
+    /// HTTP host or MQTT broker
+    pub host: String,
+    /// MQTT topic (only for MQTT protocol)
+    pub topic: Option<String>,
+    /// Names of the supported features
+    pub capabilities: Vec<String>,
+    /// Connection status label
+    pub status: String,
+    /// Firmware version, if known
+    pub firmware: Option<String>,
+    /// Last time the device was heard from, in RFC 3339
+    pub last_seen: Option<String>,
+    /// Username, only in exports that include sensitive fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+}
+
+impl DeviceInventoryEntry {
+    /// Builds the entry of a managed device.
+    ///
+    /// Passwords are never included. With `include_sensitive` set to
+    /// `false` the username is left out too, so the inventory can be shared.
+    #[must_use]
+    pub fn from_managed(device: &ManagedDevice, include_sensitive: bool) -> Self {
+        let config = &device.config;
+        Self {
+            id: config.id,
+            name: config.name.clone(),
+            model: config.model.name().to_string(),
+            protocol: config.protocol,
+            host: config.host.clone(),
+            topic: config.topic.clone(),
+            capabilities: config
+                .capabilities()
+                .features()
+                .map(str::to_string)
+                .collect(),
+            status: device.status.label().to_string(),
+            firmware: device.firmware.clone(),
+            last_seen: device.last_seen.map(|time| time.to_rfc3339()),
+            username: if include_sensitive {
+                config.username.clone()
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Returns the values of the CSV columns, in [`CSV_HEADER`] order.
+    fn csv_fields(&self) -> [String; 11] {
+        [
+            self.id.to_string(),
+            self.name.clone(),
+            self.model.clone(),
+            self.protocol.to_string(),
+            self.host.clone(),
+            self.topic.clone().unwrap_or_default(),
+            self.capabilities.join(";"),
+            self.status.clone(),
+            self.firmware.clone().unwrap_or_default(),
+            self.last_seen.clone().unwrap_or_default(),
+            self.username.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+/// Writes an inventory as pretty-printed JSON.
+///
+/// # Errors
+///
+/// Returns an error if serialization fails.
+// Not wired into the UI yet
+#[allow(dead_code)]
+pub fn to_json(entries: &[DeviceInventoryEntry]) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(entries)
+}
+
+/// Writes an inventory as CSV with a header row.
+///
+/// Capabilities are joined with `;`. Fields containing commas, quotes or
+/// line breaks are quoted.
+#[must_use]
+// Not wired into the UI yet
+#[allow(dead_code)]
+pub fn to_csv(entries: &[DeviceInventoryEntry]) -> String {
+    let mut csv = CSV_HEADER.join(",");
+    csv.push('\n');
+    for entry in entries {
+        let row: Vec<String> = entry.csv_fields().iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quotes a CSV field if needed.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device_config::{ConnectionStatus, DeviceConfig};
+    use crate::device_model::DeviceModel;
+
+    fn managed_device() -> ManagedDevice {
+        let config = DeviceConfig::new_mqtt(
+            "Desk, left".to_string(),
+            DeviceModel::AthomBulb5W7W,
+            "broker.lan".to_string(),
+            "desk".to_string(),
+        )
+        .with_credentials("admin".to_string(), "secret".to_string());
+        let mut device = ManagedDevice::new(config);
+        device.status = ConnectionStatus::Connected;
+        device.firmware = Some("14.2.0(release-tasmota)".to_string());
+        device
+    }
+
+    #[test]
+    fn sensitive_fields_are_optional() {
+        let device = managed_device();
+
+        let shared = DeviceInventoryEntry::from_managed(&device, false);
+        assert_eq!(shared.username, None);
+        let json = to_json(&[shared]).unwrap();
+        assert!(!json.contains("admin"));
+        assert!(!json.contains("secret"));
+
+        let full = DeviceInventoryEntry::from_managed(&device, true);
+        assert_eq!(full.username.as_deref(), Some("admin"));
+        assert!(!to_json(&[full]).unwrap().contains("secret"));
+    }
+
+    #[test]
+    fn entry_reflects_device() {
+        let mut device = managed_device();
+        device.mark_seen();
+        let entry = DeviceInventoryEntry::from_managed(&device, false);
+
+        assert_eq!(entry.protocol, Protocol::Mqtt);
+        assert_eq!(entry.topic.as_deref(), Some("desk"));
+        assert_eq!(entry.status, "connected");
+        assert!(entry.capabilities.contains(&"dimmer_control".to_string()));
+        assert!(entry.last_seen.is_some());
+    }
+
+    #[test]
+    fn csv_quotes_fields() {
+        let entry = DeviceInventoryEntry::from_managed(&managed_device(), false);
+        let csv = to_csv(&[entry]);
+        let mut lines = csv.lines();
+
+        assert_eq!(lines.next(), Some(CSV_HEADER.join(",").as_str()));
+        let row = lines.next().unwrap();
+        assert!(row.contains(",\"Desk, left\","));
+        assert!(row.contains(",MQTT,broker.lan,desk,"));
+        assert_eq!(lines.next(), None);
+    }
+}
@@ -0,0 +1,251 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Time-of-day light schedules.
+//!
+//! A [`LightSchedule`] maps times of day to target [`LightState`]s. Between
+//! two keyframes the target is interpolated, and the last keyframe of the day
+//! blends into the first one across midnight, so applying the schedule on a
+//! timer gives smooth all-day lighting.
+
+use std::collections::BTreeMap;
+
+use chrono::{NaiveTime, Timelike};
+use tasmor_lib::state::LightState;
+use tasmor_lib::types::{ColorTemperature, Dimmer, HsbColor};
+use tasmor_lib::Capabilities;
+
+/// Seconds in a day.
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+/// Light targets keyed by time of day.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LightSchedule {
+    keyframes: BTreeMap<NaiveTime, LightState>,
+}
+
+// Schedules are only built in code; the UI does not edit them yet
+#[allow(dead_code)]
+impl LightSchedule {
+    /// Creates an empty schedule.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a keyframe, replacing any keyframe at the same time.
+    #[must_use]
+    pub fn with_keyframe(mut self, time: NaiveTime, state: LightState) -> Self {
+        self.keyframes.insert(time, state);
+        self
+    }
+
+    /// Returns the target light state at `now`.
+    ///
+    /// Dimmer, color temperature and HSB color are interpolated linearly
+    /// between the surrounding keyframes (hue along the shortest path) when
+    /// both keyframes set them; otherwise the earlier keyframe's value holds.
+    /// Power switches at keyframes. Returns `None` for an empty schedule.
+    pub fn target_at(&self, now: NaiveTime) -> Option<LightState> {
+        let (&from_time, from) = self
+            .keyframes
+            .range(..=now)
+            .next_back()
+            .or_else(|| self.keyframes.iter().next_back())?;
+        let (&to_time, to) = self
+            .keyframes
+            .range(now..)
+            .find(|(&time, _)| time > now)
+            .or_else(|| self.keyframes.iter().next())?;
+
+        let span = match seconds_between(from_time, to_time) {
+            0 => DAY_SECS,
+            span => span,
+        };
+        let elapsed = seconds_between(from_time, now);
+
+        let mut target = LightState::new();
+        if let Some(power) = from.power() {
+            target = target.with_power(power);
+        }
+        if let Some(dimmer) = blend(from.dimmer(), to.dimmer(), |a, b| {
+            let level = lerp(a.value().into(), b.value().into(), elapsed, span);
+            Dimmer::clamped(u8::try_from(level).unwrap_or(u8::MAX))
+        }) {
+            target = target.with_dimmer(dimmer);
+        }
+        if let Some(ct) = blend(from.color_temperature(), to.color_temperature(), |a, b| {
+            let mireds = lerp(a.value().into(), b.value().into(), elapsed, span);
+            ColorTemperature::clamped(u16::try_from(mireds).unwrap_or(u16::MAX))
+        }) {
+            target = target.with_color_temperature(ct);
+        }
+        if let Some(color) = blend(from.hsb_color(), to.hsb_color(), |a, b| {
+            blend_color(a, b, elapsed, span)
+        }) {
+            target = target.with_hsb_color(color);
+        }
+        Some(target)
+    }
+}
+
+/// Keeps only the fields of `state` that a device with `capabilities` supports.
+pub fn supported_fields(state: LightState, capabilities: &Capabilities) -> LightState {
+    let mut supported = LightState::new();
+    if let Some(power) = state.power() {
+        supported = supported.with_power(power);
+    }
+    if let Some(dimmer) = state.dimmer() {
+        if capabilities.supports_dimmer_control() {
+            supported = supported.with_dimmer(dimmer);
+        }
+    }
+    if let Some(ct) = state.color_temperature() {
+        if capabilities.supports_color_temperature_control() {
+            supported = supported.with_color_temperature(ct);
+        }
+    }
+    if let Some(color) = state.hsb_color() {
+        if capabilities.supports_rgb_control() {
+            supported = supported.with_hsb_color(color);
+        }
+    }
+    supported
+}
+
+/// Interpolates when both values are set, otherwise keeps `from`.
+fn blend<T: Copy>(from: Option<T>, to: Option<T>, mix: impl Fn(T, T) -> T) -> Option<T> {
+    match (from, to) {
+        (Some(from), Some(to)) => Some(mix(from, to)),
+        (from, _) => from,
+    }
+}
+
+/// Interpolates two colors, taking the shortest way around the hue circle.
+fn blend_color(from: HsbColor, to: HsbColor, elapsed: i64, span: i64) -> HsbColor {
+    let hue_delta = (i64::from(to.hue()) - i64::from(from.hue()) + 540) % 360 - 180;
+    let hue = (i64::from(from.hue()) + lerp(0, hue_delta, elapsed, span)).rem_euclid(360);
+    let saturation = lerp(
+        from.saturation().into(),
+        to.saturation().into(),
+        elapsed,
+        span,
+    );
+    let brightness = lerp(
+        from.brightness().into(),
+        to.brightness().into(),
+        elapsed,
+        span,
+    );
+    // Interpolated values stay between valid endpoints
+    match (
+        u16::try_from(hue),
+        u8::try_from(saturation),
+        u8::try_from(brightness),
+    ) {
+        (Ok(hue), Ok(saturation), Ok(brightness)) => {
+            HsbColor::new(hue, saturation, brightness).unwrap_or(to)
+        }
+        _ => to,
+    }
+}
+
+/// Seconds from `from` forward to `to`, wrapping around midnight.
+fn seconds_between(from: NaiveTime, to: NaiveTime) -> i64 {
+    (i64::from(to.num_seconds_from_midnight()) - i64::from(from.num_seconds_from_midnight()))
+        .rem_euclid(DAY_SECS)
+}
+
+/// Linear interpolation from `from` to `to` after `elapsed` of `span`.
+fn lerp(from: i64, to: i64, elapsed: i64, span: i64) -> i64 {
+    from + (to - from) * elapsed / span
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tasmor_lib::types::PowerState;
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn white(dimmer: u8, ct: u16) -> LightState {
+        LightState::new()
+            .with_power(PowerState::On)
+            .with_dimmer(Dimmer::new(dimmer).unwrap())
+            .with_color_temperature(ColorTemperature::new(ct).unwrap())
+    }
+
+    fn day_schedule() -> LightSchedule {
+        LightSchedule::new()
+            .with_keyframe(at(7, 0), white(20, 400))
+            .with_keyframe(at(12, 0), white(100, 200))
+            .with_keyframe(at(22, 0), LightState::new().with_power(PowerState::Off))
+    }
+
+    #[test]
+    fn empty_schedule_has_no_target() {
+        assert_eq!(LightSchedule::new().target_at(at(12, 0)), None);
+    }
+
+    #[test]
+    fn keyframe_times_are_exact() {
+        assert_eq!(day_schedule().target_at(at(12, 0)), Some(white(100, 200)));
+    }
+
+    #[test]
+    fn values_are_interpolated_between_keyframes() {
+        // 9:30 is half way from 7:00 to 12:00
+        let target = day_schedule().target_at(at(9, 30)).unwrap();
+        assert_eq!(target, white(60, 300));
+    }
+
+    #[test]
+    fn earlier_value_holds_when_next_keyframe_omits_it() {
+        // 22:00 turns off without dimmer or CT: the noon values hold
+        let target = day_schedule().target_at(at(17, 0)).unwrap();
+        assert_eq!(target, white(100, 200));
+    }
+
+    #[test]
+    fn schedule_wraps_around_midnight() {
+        let schedule = LightSchedule::new()
+            .with_keyframe(at(6, 0), white(100, 200))
+            .with_keyframe(at(22, 0), white(20, 400));
+
+        // 2:00 is half way through the 8 hours from 22:00 to 6:00
+        assert_eq!(schedule.target_at(at(2, 0)), Some(white(60, 300)));
+        assert_eq!(schedule.target_at(at(23, 0)), Some(white(30, 375)));
+    }
+
+    #[test]
+    fn hue_takes_shortest_path() {
+        let schedule = LightSchedule::new()
+            .with_keyframe(
+                at(0, 0),
+                LightState::new().with_hsb_color(HsbColor::new(340, 100, 50).unwrap()),
+            )
+            .with_keyframe(
+                at(1, 0),
+                LightState::new().with_hsb_color(HsbColor::new(20, 50, 100).unwrap()),
+            );
+
+        let color = schedule.target_at(at(0, 30)).unwrap().hsb_color().unwrap();
+        assert_eq!(color, HsbColor::new(0, 75, 75).unwrap());
+    }
+
+    #[test]
+    fn single_keyframe_is_constant() {
+        let schedule = LightSchedule::new().with_keyframe(at(8, 0), white(50, 300));
+        assert_eq!(schedule.target_at(at(3, 0)), Some(white(50, 300)));
+    }
+
+    #[test]
+    fn unsupported_fields_are_dropped() {
+        let state = white(50, 300).with_hsb_color(HsbColor::red());
+        let supported = supported_fields(state, &Capabilities::cct_light());
+        assert_eq!(supported, white(50, 300));
+    }
+}
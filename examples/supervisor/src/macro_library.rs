@@ -0,0 +1,267 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Named macros: reusable action sequences stored client-side.
+//!
+//! A macro is a list of steps that is turned into a [`Routine`] when it is
+//! run, so it executes as a single `Backlog0` on the device. Step values can
+//! be literals or named parameters supplied when the macro is run, e.g. a
+//! `"dim_to"` macro taking a `level` argument.
+//!
+//! Macros are serialized as part of the application configuration:
+//!
+//! ```json
+//! {
+//!   "goodnight": {
+//!     "steps": [
+//!       { "action": "dimmer", "level": 10 },
+//!       { "action": "delay", "millis": 5000 },
+//!       { "action": "power_off", "relay": 1 }
+//!     ]
+//!   },
+//!   "dim_to": {
+//!     "steps": [{ "action": "dimmer", "level": "level" }]
+//!   }
+//! }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tasmor_lib::types::{ColorTemperature, Dimmer, HsbColor, PowerIndex, Scheme};
+use tasmor_lib::Routine;
+
+/// A step value: a literal number or the name of a parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MacroArg {
+    /// Fixed value.
+    Value(u32),
+    /// Value supplied by the caller under this name.
+    Param(String),
+}
+
+impl MacroArg {
+    /// Resolves the argument, looking up parameters in `args`.
+    fn resolve(&self, args: &HashMap<String, u32>) -> Result<u32, String> {
+        match self {
+            Self::Value(value) => Ok(*value),
+            Self::Param(name) => args
+                .get(name)
+                .copied()
+                .ok_or_else(|| format!("Missing macro argument '{name}'")),
+        }
+    }
+
+    /// Resolves the argument and narrows it to the target type.
+    fn resolve_as<T: TryFrom<u32>>(&self, args: &HashMap<String, u32>) -> Result<T, String> {
+        let value = self.resolve(args)?;
+        T::try_from(value).map_err(|_| format!("Macro argument {value} is out of range"))
+    }
+}
+
+/// A single action of a macro.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum MacroStep {
+    /// Turn a relay on.
+    PowerOn { relay: u8 },
+    /// Turn a relay off.
+    PowerOff { relay: u8 },
+    /// Toggle a relay.
+    PowerToggle { relay: u8 },
+    /// Set the dimmer level (0-100).
+    Dimmer { level: MacroArg },
+    /// Set the color temperature in mireds (153-500).
+    ColorTemperature { mireds: MacroArg },
+    /// Set the HSB color.
+    HsbColor {
+        hue: MacroArg,
+        saturation: MacroArg,
+        brightness: MacroArg,
+    },
+    /// Set the light scheme (0-4).
+    Scheme { scheme: MacroArg },
+    /// Enable fade transitions.
+    EnableFade,
+    /// Disable fade transitions.
+    DisableFade,
+    /// Wait before the next step.
+    Delay { millis: MacroArg },
+}
+
+impl MacroStep {
+    /// Returns the arguments of this step.
+    fn args(&self) -> Vec<&MacroArg> {
+        match self {
+            Self::Dimmer { level } => vec![level],
+            Self::ColorTemperature { mireds } => vec![mireds],
+            Self::HsbColor {
+                hue,
+                saturation,
+                brightness,
+            } => vec![hue, saturation, brightness],
+            Self::Scheme { scheme } => vec![scheme],
+            Self::Delay { millis } => vec![millis],
+            Self::PowerOn { .. }
+            | Self::PowerOff { .. }
+            | Self::PowerToggle { .. }
+            | Self::EnableFade
+            | Self::DisableFade => Vec::new(),
+        }
+    }
+}
+
+/// A named, reusable action sequence.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Macro {
+    /// Steps executed in order.
+    pub steps: Vec<MacroStep>,
+}
+
+impl Macro {
+    /// Returns the names of the parameters the macro expects.
+    #[must_use]
+    pub fn params(&self) -> BTreeSet<&str> {
+        self.steps
+            .iter()
+            .flat_map(MacroStep::args)
+            .filter_map(|arg| match arg {
+                MacroArg::Param(name) => Some(name.as_str()),
+                MacroArg::Value(_) => None,
+            })
+            .collect()
+    }
+
+    /// Builds the routine for this macro with the given arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an argument is missing or a value is invalid.
+    pub fn to_routine(&self, args: &HashMap<String, u32>) -> Result<Routine, String> {
+        let mut builder = Routine::builder();
+        for step in &self.steps {
+            builder = match step {
+                MacroStep::PowerOn { relay } => builder.power_on(power_index(*relay)?),
+                MacroStep::PowerOff { relay } => builder.power_off(power_index(*relay)?),
+                MacroStep::PowerToggle { relay } => builder.power_toggle(power_index(*relay)?),
+                MacroStep::Dimmer { level } => builder
+                    .set_dimmer(Dimmer::new(level.resolve_as(args)?).map_err(|e| e.to_string())?),
+                MacroStep::ColorTemperature { mireds } => builder.set_color_temperature(
+                    ColorTemperature::new(mireds.resolve_as(args)?).map_err(|e| e.to_string())?,
+                ),
+                MacroStep::HsbColor {
+                    hue,
+                    saturation,
+                    brightness,
+                } => builder.set_hsb_color(
+                    HsbColor::new(
+                        hue.resolve_as(args)?,
+                        saturation.resolve_as(args)?,
+                        brightness.resolve_as(args)?,
+                    )
+                    .map_err(|e| e.to_string())?,
+                ),
+                MacroStep::Scheme { scheme } => builder
+                    .set_scheme(Scheme::new(scheme.resolve_as(args)?).map_err(|e| e.to_string())?),
+                MacroStep::EnableFade => builder.enable_fade(),
+                MacroStep::DisableFade => builder.disable_fade(),
+                MacroStep::Delay { millis } => {
+                    builder.delay(Duration::from_millis(u64::from(millis.resolve(args)?)))
+                }
+            };
+        }
+        builder.build().map_err(|e| e.to_string())
+    }
+}
+
+/// Converts a relay number to a power index.
+fn power_index(relay: u8) -> Result<PowerIndex, String> {
+    PowerIndex::new(relay).map_err(|e| e.to_string())
+}
+
+/// Named macros, persisted with the application configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MacroLibrary {
+    macros: BTreeMap<String, Macro>,
+}
+
+impl MacroLibrary {
+    /// Returns a macro by name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Macro> {
+        self.macros.get(name)
+    }
+
+    /// Iterates over the macros in alphabetical order.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Macro)> {
+        self.macros
+            .iter()
+            .map(|(name, macro_def)| (name.as_str(), macro_def))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIBRARY_JSON: &str = r#"{
+        "goodnight": {
+            "steps": [
+                { "action": "dimmer", "level": 10 },
+                { "action": "delay", "millis": 5000 },
+                { "action": "power_off", "relay": 1 }
+            ]
+        },
+        "dim_to": {
+            "steps": [{ "action": "dimmer", "level": "level" }]
+        }
+    }"#;
+
+    fn library() -> MacroLibrary {
+        serde_json::from_str(LIBRARY_JSON).unwrap()
+    }
+
+    #[test]
+    fn literal_macro_builds_routine() {
+        let goodnight = library().get("goodnight").cloned().unwrap();
+        assert!(goodnight.params().is_empty());
+
+        let routine = goodnight.to_routine(&HashMap::new()).unwrap();
+        assert_eq!(routine.len(), 3);
+    }
+
+    #[test]
+    fn parameterized_macro() {
+        let dim_to = library().get("dim_to").cloned().unwrap();
+        assert_eq!(dim_to.params().into_iter().collect::<Vec<_>>(), ["level"]);
+
+        let args = HashMap::from([("level".to_string(), 40)]);
+        assert!(dim_to.to_routine(&args).is_ok());
+
+        let err = dim_to.to_routine(&HashMap::new()).unwrap_err();
+        assert!(err.contains("level"));
+
+        let args = HashMap::from([("level".to_string(), 400)]);
+        assert!(dim_to.to_routine(&args).is_err());
+    }
+
+    #[test]
+    fn library_round_trips_through_json() {
+        let library = library();
+        assert_eq!(
+            library.entries().map(|(name, _)| name).collect::<Vec<_>>(),
+            ["dim_to", "goodnight"]
+        );
+
+        let json = serde_json::to_string(&library).unwrap();
+        assert!(json.contains(r#""action":"dimmer","level":"level""#));
+
+        let restored: MacroLibrary = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, library);
+    }
+}
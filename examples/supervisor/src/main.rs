@@ -14,8 +14,12 @@
 mod device_config;
 mod device_manager;
 mod device_model;
+mod inventory;
+mod light_schedule;
+mod macro_library;
 mod persistence;
 mod ui;
+mod vacation_mode;
 
 use std::collections::HashMap;
 use std::sync::mpsc;
@@ -54,6 +58,8 @@ struct TasmotaSupervisor {
     broker_count: usize,
     /// Total MQTT subscription count
     subscription_count: usize,
+    /// Macros offered on device cards (those without arguments)
+    macro_names: Vec<String>,
 }
 
 impl TasmotaSupervisor {
@@ -68,13 +74,22 @@ impl TasmotaSupervisor {
 
         let rt = tokio::runtime::Handle::current();
 
+        // Make the saved macros available to the manager
+        rt.block_on(device_manager.set_macro_library(app_config.macros.clone()));
+        let macro_names = rt.block_on(device_manager.runnable_macros());
+
         // Add saved devices to the manager
-        for config in &app_config.devices {
-            if let Err(e) = rt.block_on(device_manager.add_device(config.clone())) {
-                tracing::warn!(device = %config.name, error = %e, "Failed to add saved device");
-            }
+        for (id, e) in rt.block_on(device_manager.import_configs(app_config.devices.clone())) {
+            tracing::warn!(device_id = %id, error = %e, "Failed to add saved device");
         }
 
+        // Detect devices that silently dropped off (HTTP devices have no LWT)
+        device_manager.start_watchdog(
+            app_config.watchdog_interval(),
+            std::time::Duration::from_secs(5),
+            3,
+        );
+
         // Get initial device list as HashMap
         let devices: HashMap<Uuid, DeviceState> = rt
             .block_on(device_manager.devices())
@@ -98,6 +113,7 @@ impl TasmotaSupervisor {
             error_message: None,
             broker_count,
             subscription_count,
+            macro_names,
         }
     }
 
@@ -141,6 +157,14 @@ impl TasmotaSupervisor {
                         device.error = error;
                     }
                 }
+                StateUpdate::LowHeap {
+                    device_id,
+                    free_bytes,
+                } => {
+                    if let Some(device) = self.devices.get_mut(&device_id) {
+                        device.error = Some(format!("Low free heap: {} KB", free_bytes / 1024));
+                    }
+                }
             }
         }
     }
@@ -388,21 +412,9 @@ impl TasmotaSupervisor {
                     }
 
                     // Display the queried state in console
-                    let power_str = state
-                        .power(1)
-                        .map_or("?".to_string(), |ps| ps.as_str().to_string());
-                    let dimmer_str = state
-                        .dimmer()
-                        .map_or(String::new(), |d| format!(", Dimmer={}", d.value()));
-                    let ct_str = state
-                        .color_temperature()
-                        .map_or(String::new(), |ct| format!(", CT={}", ct.value()));
                     self.log_to_console(
                         device_id,
-                        ConsoleEntry::success(
-                            "status()",
-                            &format!("Power={power_str}{dimmer_str}{ct_str}"),
-                        ),
+                        ConsoleEntry::success("status()", &state.summary()),
                     );
                 }
                 Err(e) => {
@@ -576,6 +588,29 @@ impl TasmotaSupervisor {
                 }
             }
         }
+
+        // Macro button
+        if let Some(name) = &response.macro_clicked {
+            let dm = &self.device_manager;
+            let call = format!("run_macro({name:?})");
+            match rt.block_on(dm.run_macro(device_id, name, &HashMap::new())) {
+                Ok(()) => {
+                    if is_http {
+                        self.log_to_console(
+                            device_id,
+                            ConsoleEntry::success(&call, &format!("Macro {name} executed")),
+                        );
+                    }
+                }
+                Err(e) => {
+                    if is_http {
+                        self.log_to_console(device_id, ConsoleEntry::error(&call, &e));
+                    } else {
+                        self.error_message = Some(e);
+                    }
+                }
+            }
+        }
     }
 
     /// Handles add device dialog.
@@ -719,6 +754,7 @@ impl TasmotaSupervisor {
                 topic: None,
                 username: None,
                 password: None,
+                capabilities: state.capabilities.clone(),
             }
         } else {
             DeviceConfig {
@@ -730,6 +766,7 @@ impl TasmotaSupervisor {
                 topic: Some(state.mqtt_topic.clone()),
                 username: None,
                 password: None,
+                capabilities: state.capabilities.clone(),
             }
         };
 
@@ -825,7 +862,8 @@ impl eframe::App for TasmotaSupervisor {
                             .values()
                             .map(|device| {
                                 let console_log = self.console_logs.get(&device.config.id);
-                                let response = ui::device_card(ui, device, console_log);
+                                let response =
+                                    ui::device_card(ui, device, console_log, &self.macro_names);
                                 ui.add_space(8.0);
                                 (device.config.id, device.config.protocol, response)
                             })
@@ -900,6 +938,7 @@ mod tests {
             error_message: None,
             broker_count: 0,
             subscription_count: 0,
+            macro_names: Vec::new(),
         }
     }
 
@@ -5,19 +5,38 @@
 use std::fs;
 use std::io;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
 use crate::device_config::DeviceConfig;
+use crate::macro_library::MacroLibrary;
 
 /// Application configuration that gets persisted.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AppConfig {
     /// List of configured devices.
     pub devices: Vec<DeviceConfig>,
+    /// Named macros, editable in the config file.
+    #[serde(default)]
+    pub macros: MacroLibrary,
+    /// Seconds between reachability checks; defaults to 30.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watchdog_interval_secs: Option<u64>,
 }
 
 impl AppConfig {
+    /// Default interval between reachability checks.
+    pub const DEFAULT_WATCHDOG_INTERVAL: Duration = Duration::from_secs(30);
+
+    /// Returns the interval between reachability checks.
+    #[must_use]
+    pub fn watchdog_interval(&self) -> Duration {
+        self.watchdog_interval_secs
+            .filter(|&secs| secs > 0)
+            .map_or(Self::DEFAULT_WATCHDOG_INTERVAL, Duration::from_secs)
+    }
+
     /// Returns the path to the configuration file.
     fn config_path() -> Option<PathBuf> {
         dirs::config_dir().map(|mut path| {
@@ -133,4 +152,23 @@ mod tests {
         let config = AppConfig::default();
         assert!(config.devices.is_empty());
     }
+
+    #[test]
+    fn config_without_macros_loads() {
+        let config: AppConfig = serde_json::from_str(r#"{"devices":[]}"#).unwrap();
+        assert!(config.macros.entries().next().is_none());
+    }
+
+    #[test]
+    fn watchdog_interval_defaults() {
+        let config = AppConfig::default();
+        assert_eq!(
+            config.watchdog_interval(),
+            AppConfig::DEFAULT_WATCHDOG_INTERVAL
+        );
+
+        let config: AppConfig =
+            serde_json::from_str(r#"{"devices":[],"watchdog_interval_secs":10}"#).unwrap();
+        assert_eq!(config.watchdog_interval(), Duration::from_secs(10));
+    }
 }
@@ -186,14 +186,21 @@ fn power_toggle(on: &mut bool, enabled: bool) -> impl Widget + '_ {
 ///
 /// Dispatches to protocol-specific rendering based on the device's protocol.
 /// For HTTP devices, requires a console log for request/response display.
+/// `macros` lists the macros offered as buttons on the card.
 pub fn device_card(
     ui: &mut Ui,
     device: &DeviceState,
     console_log: Option<&ConsoleLog>,
+    macros: &[String],
 ) -> DeviceCardResponse {
     match device.config.protocol {
-        Protocol::Http => http_device_card(ui, device, console_log.unwrap_or(&ConsoleLog::new())),
-        Protocol::Mqtt => mqtt_device_card(ui, device),
+        Protocol::Http => http_device_card(
+            ui,
+            device,
+            console_log.unwrap_or(&ConsoleLog::new()),
+            macros,
+        ),
+        Protocol::Mqtt => mqtt_device_card(ui, device, macros),
     }
 }
 
@@ -207,6 +214,7 @@ fn http_device_card(
     ui: &mut Ui,
     device: &DeviceState,
     console_log: &ConsoleLog,
+    macros: &[String],
 ) -> DeviceCardResponse {
     let mut response = DeviceCardResponse::default();
 
@@ -229,7 +237,7 @@ fn http_device_card(
                 ui.vertical(|ui| {
                     ui.heading(&device.config.name);
                     ui.label(RichText::new(device.model().name()).small().weak());
-                    let features: Vec<&str> = device.model().capabilities().features().collect();
+                    let features: Vec<&str> = device.config.capabilities().features().collect();
                     if !features.is_empty() {
                         ui.label(RichText::new(features.join(" · ")).small().weak().italics());
                     }
@@ -334,7 +342,7 @@ fn http_device_card(
                 });
 
                 if device
-                    .model()
+                    .config
                     .capabilities()
                     .supports_color_temperature_control()
                 {
@@ -424,6 +432,8 @@ fn http_device_card(
                 });
             }
 
+            render_macros_row(ui, macros, &mut response);
+
             ui.separator();
 
             // Console section
@@ -477,6 +487,22 @@ fn http_device_card(
     response
 }
 
+/// Renders one button per macro.
+fn render_macros_row(ui: &mut Ui, macros: &[String], response: &mut DeviceCardResponse) {
+    if macros.is_empty() {
+        return;
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Macros:");
+        for name in macros {
+            if ui.button(name).clicked() {
+                response.macro_clicked = Some(name.clone());
+            }
+        }
+    });
+}
+
 /// Renders a single console entry.
 fn render_console_entry(ui: &mut Ui, entry: &ConsoleEntry) {
     let timestamp_color = Color32::from_rgb(128, 128, 128);
@@ -537,7 +563,7 @@ fn render_console_entry(ui: &mut Ui, entry: &ConsoleEntry) {
 /// the current device state.
 #[allow(clippy::too_many_lines)]
 // UI rendering function with multiple sections - splitting would reduce readability
-fn mqtt_device_card(ui: &mut Ui, device: &DeviceState) -> DeviceCardResponse {
+fn mqtt_device_card(ui: &mut Ui, device: &DeviceState, macros: &[String]) -> DeviceCardResponse {
     let mut response = DeviceCardResponse::default();
 
     egui::Frame::new()
@@ -566,7 +592,7 @@ fn mqtt_device_card(ui: &mut Ui, device: &DeviceState) -> DeviceCardResponse {
                     ui.heading(&device.config.name);
                     ui.label(RichText::new(device.model().name()).small().weak());
                     // Show device capabilities
-                    let features: Vec<&str> = device.model().capabilities().features().collect();
+                    let features: Vec<&str> = device.config.capabilities().features().collect();
                     if !features.is_empty() {
                         ui.label(RichText::new(features.join(" · ")).small().weak().italics());
                     }
@@ -679,7 +705,7 @@ fn mqtt_device_card(ui: &mut Ui, device: &DeviceState) -> DeviceCardResponse {
 
                         // Color temperature slider (for RGBCCT lights)
                         if device
-                            .model()
+                            .config
                             .capabilities()
                             .supports_color_temperature_control()
                         {
@@ -757,6 +783,8 @@ fn mqtt_device_card(ui: &mut Ui, device: &DeviceState) -> DeviceCardResponse {
                         }
                     });
                 }
+
+                render_macros_row(ui, macros, &mut response);
             }
 
             // Error display
@@ -816,6 +844,8 @@ pub struct DeviceCardResponse {
     pub fade_toggle_clicked: bool,
     /// Fade duration changed (1-40)
     pub fade_duration_changed: Option<u8>,
+    /// Macro button was clicked (macro name)
+    pub macro_clicked: Option<String>,
 }
 
 /// Renders the add device dialog.
@@ -1082,6 +1112,8 @@ pub struct EditDeviceDialogState {
     pub username: String,
     /// Password for authentication
     pub password: String,
+    /// Explicit capabilities (not editable, preserved across edits)
+    pub capabilities: Option<tasmor_lib::Capabilities>,
 }
 
 impl EditDeviceDialogState {
@@ -1108,6 +1140,7 @@ impl EditDeviceDialogState {
             use_auth: config.username.is_some(),
             username: config.username.clone().unwrap_or_default(),
             password: config.password.clone().unwrap_or_default(),
+            capabilities: config.capabilities.clone(),
         }
     }
 
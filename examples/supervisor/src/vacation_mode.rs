@@ -0,0 +1,414 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Randomized "vacation mode" lighting.
+//!
+//! While away, devices are switched on and off at random times within an
+//! evening window to mimic occupancy. A [`VacationMode`] generates one
+//! night of [`VacationEvent`]s at a time from a seeded generator, so a
+//! given seed always produces the same plan. Each device can be limited
+//! to its own hours, so the bedroom does not light up in the afternoon.
+
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, NaiveTime, TimeDelta, Timelike};
+use tasmor_lib::types::PowerState;
+use uuid::Uuid;
+
+/// Seconds in a day.
+const DAY_SECS: u32 = 24 * 60 * 60;
+
+/// Hours of the day, wrapping around midnight when `end` is before `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hours {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+// Vacation mode is only started from code; the UI does not offer it yet
+#[allow(dead_code)]
+impl Hours {
+    /// Creates a range of hours. Equal `start` and `end` mean the whole day.
+    pub const fn new(start: NaiveTime, end: NaiveTime) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns when the occurrence of these hours containing `now` started,
+    /// or when the next one starts.
+    pub fn current_or_next_start(self, now: NaiveDateTime) -> NaiveDateTime {
+        let now = now.with_nanosecond(0).unwrap_or(now);
+        let elapsed = seconds_between(self.start, now.time());
+        if elapsed < self.length_secs() {
+            now - TimeDelta::seconds(elapsed.into())
+        } else {
+            now + TimeDelta::seconds((DAY_SECS - elapsed).into())
+        }
+    }
+
+    /// Returns the length of the range.
+    fn length_secs(self) -> u32 {
+        match seconds_between(self.start, self.end) {
+            0 => DAY_SECS,
+            length => length,
+        }
+    }
+
+    /// Returns how long the range still lasts at `time`, or `None` outside it.
+    fn remaining_at(self, time: NaiveTime) -> Option<u32> {
+        let elapsed = seconds_between(self.start, time);
+        let length = self.length_secs();
+        (elapsed < length).then(|| length - elapsed)
+    }
+}
+
+/// A device taking part in vacation mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct VacationDevice {
+    id: Uuid,
+    /// Hours during which the device may be on, if restricted.
+    hours: Option<Hours>,
+}
+
+/// Settings for vacation mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VacationConfig {
+    window: Hours,
+    devices: Vec<VacationDevice>,
+    on_duration: (Duration, Duration),
+    off_duration: (Duration, Duration),
+    seed: u64,
+    restore_on_cancel: bool,
+}
+
+// Vacation mode is only started from code; the UI does not offer it yet
+#[allow(dead_code)]
+impl VacationConfig {
+    /// Creates a configuration active every night within `window`.
+    ///
+    /// Devices stay on for 20 to 90 minutes and off for 10 to 60 minutes
+    /// between two on periods. Prior states are restored on cancellation.
+    pub fn new(window: Hours) -> Self {
+        Self {
+            window,
+            devices: Vec::new(),
+            on_duration: (Duration::from_secs(20 * 60), Duration::from_secs(90 * 60)),
+            off_duration: (Duration::from_secs(10 * 60), Duration::from_secs(60 * 60)),
+            seed: 0,
+            restore_on_cancel: true,
+        }
+    }
+
+    /// Adds a device that may be on at any time within the window.
+    #[must_use]
+    pub fn with_device(mut self, id: Uuid) -> Self {
+        self.devices.push(VacationDevice { id, hours: None });
+        self
+    }
+
+    /// Adds a device that may only be on within both the window and `hours`.
+    #[must_use]
+    pub fn with_device_hours(mut self, id: Uuid, hours: Hours) -> Self {
+        self.devices.push(VacationDevice {
+            id,
+            hours: Some(hours),
+        });
+        self
+    }
+
+    /// Sets how long a device stays on, between `min` and `max`.
+    #[must_use]
+    pub fn with_on_duration(mut self, min: Duration, max: Duration) -> Self {
+        self.on_duration = (min, max.max(min));
+        self
+    }
+
+    /// Sets how long a device stays off between two on periods.
+    #[must_use]
+    pub fn with_off_duration(mut self, min: Duration, max: Duration) -> Self {
+        self.off_duration = (min, max.max(min));
+        self
+    }
+
+    /// Sets the seed of the random generator.
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets whether cancelling restores the power states from before the run.
+    #[must_use]
+    pub fn with_restore_on_cancel(mut self, restore: bool) -> Self {
+        self.restore_on_cancel = restore;
+        self
+    }
+
+    /// Returns the nightly window.
+    pub fn window(&self) -> Hours {
+        self.window
+    }
+
+    /// Returns the IDs of the participating devices.
+    pub fn device_ids(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.devices.iter().map(|device| device.id)
+    }
+
+    /// Returns whether cancelling restores prior power states.
+    pub fn restores_on_cancel(&self) -> bool {
+        self.restore_on_cancel
+    }
+}
+
+/// A planned power switch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VacationEvent {
+    /// Time since the start of the night's window.
+    pub offset: Duration,
+    /// Device to switch.
+    pub device_id: Uuid,
+    /// Power state to set.
+    pub power: PowerState,
+}
+
+/// Generator of randomized nightly on/off plans.
+#[derive(Debug, Clone)]
+pub struct VacationMode {
+    config: VacationConfig,
+    rng: SplitMix64,
+}
+
+#[allow(dead_code)]
+impl VacationMode {
+    /// Creates a generator seeded from the configuration.
+    pub fn new(config: VacationConfig) -> Self {
+        let rng = SplitMix64(config.seed);
+        Self { config, rng }
+    }
+
+    /// Returns the configuration.
+    pub fn config(&self) -> &VacationConfig {
+        &self.config
+    }
+
+    /// Generates the events of the next night, sorted by offset.
+    ///
+    /// Every device alternates off and on periods of random length across
+    /// the window, and is only switched on at times its own hours allow.
+    /// An on period ends early rather than overrun the window or the
+    /// device's hours, so every device is off again when the window closes.
+    pub fn next_night(&mut self) -> Vec<VacationEvent> {
+        let window_secs = self.config.window.length_secs();
+        let (min_on, max_on) = duration_secs(self.config.on_duration);
+        let (min_off, max_off) = duration_secs(self.config.off_duration);
+        let mut events = Vec::new();
+
+        for device in &self.config.devices {
+            let mut at = self.rng.between(min_off, max_off);
+            while at < window_secs {
+                let time = time_after(self.config.window.start, at);
+                let allowed = device
+                    .hours
+                    .map_or(Some(DAY_SECS), |hours| hours.remaining_at(time));
+                let Some(allowed) = allowed else {
+                    // Check again a bit later
+                    at += self.rng.between(min_off, max_off).max(60);
+                    continue;
+                };
+
+                let on_for = self
+                    .rng
+                    .between(min_on, max_on)
+                    .min(allowed)
+                    .min(window_secs - at);
+                if on_for > 0 {
+                    events.push(event(at, device.id, PowerState::On));
+                    events.push(event(at + on_for, device.id, PowerState::Off));
+                }
+                at += on_for + self.rng.between(min_off, max_off).max(1);
+            }
+        }
+
+        events.sort_by_key(|event| event.offset);
+        events
+    }
+}
+
+fn event(offset_secs: u32, device_id: Uuid, power: PowerState) -> VacationEvent {
+    VacationEvent {
+        offset: Duration::from_secs(offset_secs.into()),
+        device_id,
+        power,
+    }
+}
+
+/// Converts a duration range to whole seconds, capped at a day.
+fn duration_secs((min, max): (Duration, Duration)) -> (u32, u32) {
+    let secs = |duration: Duration| {
+        u32::try_from(duration.as_secs()).map_or(DAY_SECS, |secs| secs.min(DAY_SECS))
+    };
+    (secs(min), secs(max))
+}
+
+/// Time of day `secs` seconds after `start`.
+fn time_after(start: NaiveTime, secs: u32) -> NaiveTime {
+    let secs = (start.num_seconds_from_midnight() + secs) % DAY_SECS;
+    NaiveTime::from_num_seconds_from_midnight_opt(secs, 0).unwrap_or(start)
+}
+
+/// Seconds from `from` forward to `to`, wrapping around midnight.
+fn seconds_between(from: NaiveTime, to: NaiveTime) -> u32 {
+    (to.num_seconds_from_midnight() + DAY_SECS - from.num_seconds_from_midnight()) % DAY_SECS
+}
+
+/// Small seedable random generator (`SplitMix64`).
+///
+/// Good enough for plausible light patterns and keeps plans reproducible
+/// across platforms and versions.
+#[derive(Debug, Clone)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in `min..=max`.
+    fn between(&mut self, min: u32, max: u32) -> u32 {
+        let span = u64::from(max.saturating_sub(min)) + 1;
+        // The remainder is below `span`, which fits in u32 + 1
+        min + u32::try_from(self.next_u64() % span).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn config(seed: u64) -> (VacationConfig, Uuid, Uuid) {
+        let living_room = Uuid::new_v4();
+        let bedroom = Uuid::new_v4();
+        let config = VacationConfig::new(Hours::new(at(17, 0), at(1, 0)))
+            .with_device(living_room)
+            .with_device_hours(bedroom, Hours::new(at(22, 0), at(0, 30)))
+            .with_seed(seed);
+        (config, living_room, bedroom)
+    }
+
+    #[test]
+    fn same_seed_gives_same_plan() {
+        let (config, _, _) = config(42);
+        let first = VacationMode::new(config.clone()).next_night();
+        let second = VacationMode::new(config).next_night();
+
+        assert!(!first.is_empty());
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn different_seeds_give_different_plans() {
+        let (config, _, _) = config(1);
+        let other = config.clone().with_seed(2);
+
+        assert_ne!(
+            VacationMode::new(config).next_night(),
+            VacationMode::new(other).next_night()
+        );
+    }
+
+    #[test]
+    fn consecutive_nights_differ() {
+        let (config, _, _) = config(7);
+        let mut mode = VacationMode::new(config);
+
+        assert_ne!(mode.next_night(), mode.next_night());
+    }
+
+    #[test]
+    fn events_stay_within_window_and_alternate() {
+        let (config, living_room, bedroom) = config(3);
+        let events = VacationMode::new(config).next_night();
+
+        assert!(events
+            .windows(2)
+            .all(|pair| pair[0].offset <= pair[1].offset));
+        for id in [living_room, bedroom] {
+            let powers: Vec<_> = events
+                .iter()
+                .filter(|event| event.device_id == id)
+                .inspect(|event| assert!(event.offset <= Duration::from_secs(8 * 60 * 60)))
+                .map(|event| event.power)
+                .collect();
+            assert_eq!(powers.len() % 2, 0);
+            for pair in powers.chunks(2) {
+                assert_eq!(pair, [PowerState::On, PowerState::Off]);
+            }
+        }
+    }
+
+    #[test]
+    fn device_hours_are_respected() {
+        let (config, _, bedroom) = config(11);
+        let window_start = config.window().start;
+        let bedroom_hours = Hours::new(at(22, 0), at(0, 30));
+
+        let mut mode = VacationMode::new(config);
+        for _ in 0..20 {
+            let events = mode.next_night();
+            for event in events.iter().filter(|event| event.device_id == bedroom) {
+                let secs = u32::try_from(event.offset.as_secs()).unwrap();
+                let time = time_after(window_start, secs);
+                match event.power {
+                    PowerState::On => assert!(bedroom_hours.remaining_at(time).is_some()),
+                    // Off may land exactly on the end of the hours
+                    _ => assert!(
+                        bedroom_hours.remaining_at(time).is_some() || time == at(0, 30),
+                        "bedroom off at {time}"
+                    ),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn night_in_progress_is_joined() {
+        let window = Hours::new(at(18, 0), at(1, 0));
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let next_day = day.succ_opt().unwrap();
+
+        assert_eq!(
+            window.current_or_next_start(next_day.and_time(at(0, 30))),
+            day.and_time(at(18, 0))
+        );
+        assert_eq!(
+            window.current_or_next_start(day.and_time(at(12, 0))),
+            day.and_time(at(18, 0))
+        );
+        assert_eq!(
+            window.current_or_next_start(next_day.and_time(at(2, 0))),
+            next_day.and_time(at(18, 0))
+        );
+    }
+
+    #[test]
+    fn hours_wrap_around_midnight() {
+        let hours = Hours::new(at(22, 0), at(2, 0));
+
+        assert_eq!(hours.remaining_at(at(23, 0)), Some(3 * 60 * 60));
+        assert_eq!(hours.remaining_at(at(1, 0)), Some(60 * 60));
+        assert_eq!(hours.remaining_at(at(15, 0)), None);
+        assert_eq!(
+            Hours::new(at(8, 0), at(8, 0)).remaining_at(at(7, 0)),
+            Some(60 * 60)
+        );
+    }
+}
@@ -18,8 +18,14 @@
 //!
 //! For faster startup or when auto-detection is not desired, capabilities
 //! can be manually specified using the builder pattern.
+//!
+//! # Custom Detection
+//!
+//! Hardware the built-in detection does not recognize can be handled by
+//! implementing [`CapabilityDetector`].
 
-use crate::response::StatusResponse;
+use crate::command::MAX_PWM_CHANNELS;
+use crate::response::{DeviceTemplate, StatusResponse};
 
 /// Capabilities of a Tasmota device.
 ///
@@ -47,12 +53,35 @@ use crate::response::StatusResponse;
 /// let neo_coolcam = Capabilities::neo_coolcam();
 /// assert!(neo_coolcam.supports_energy_monitoring());
 /// ```
+///
+/// # Serialization
+///
+/// Capabilities serialize to a flat map of named fields, suitable for
+/// hand-edited JSON or TOML configuration files. Missing fields fall back to
+/// [`Capabilities::default()`], and `power_channels` is clamped to 1-8 exactly
+/// like [`CapabilitiesBuilder::power_channels`].
+///
+/// ```
+/// use tasmor_lib::Capabilities;
+///
+/// let json = serde_json::to_string(&Capabilities::neo_coolcam()).unwrap();
+/// assert_eq!(
+///     json,
+///     r#"{"power_channels":1,"dimmer_control":false,"color_temperature_control":false,"rgb_control":false,"energy_monitoring":true,"shutter_control":false,"pwm_channels":0}"#
+/// );
+///
+/// // Only the enabled features need to be written
+/// let caps: Capabilities = serde_json::from_str(r#"{"energy_monitoring": true}"#).unwrap();
+/// assert_eq!(caps, Capabilities::neo_coolcam());
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 // Each boolean represents an independent device feature flag that cannot be
 // meaningfully combined into an enum or state machine.
 #[allow(clippy::struct_excessive_bools)]
 pub struct Capabilities {
     /// Number of power relay channels (1-8).
+    #[serde(deserialize_with = "deserialize_power_channels")]
     power_channels: u8,
 
     /// Supports dimmer/brightness control.
@@ -66,6 +95,18 @@ pub struct Capabilities {
 
     /// Supports energy monitoring (voltage, current, power).
     energy_monitoring: bool,
+
+    /// Supports shutter/cover position control.
+    shutter_control: bool,
+
+    /// Number of PWM outputs for raw duty-cycle control (0-16).
+    #[serde(deserialize_with = "deserialize_pwm_channels")]
+    pwm_channels: u8,
+
+    /// Number of relays counted from the `POWER` fields of the runtime
+    /// state, if detected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    relay_count: Option<u8>,
 }
 
 impl Capabilities {
@@ -93,16 +134,63 @@ impl Capabilities {
         self.rgb_control
     }
 
+    /// Returns whether the device drives RGB and both white channels
+    /// (RGBCCT), so all five channels can be set at once.
+    #[must_use]
+    pub const fn supports_rgbww(&self) -> bool {
+        self.rgb_control && self.color_temperature_control
+    }
+
     /// Returns whether the device supports energy monitoring.
     #[must_use]
     pub const fn supports_energy_monitoring(&self) -> bool {
         self.energy_monitoring
     }
 
+    /// Returns whether the device drives shutters (roller blinds, covers)
+    /// through Tasmota's shutter mode.
+    ///
+    /// A shutter uses two relays for its motor and is moved to a position
+    /// instead of being switched on and off.
+    #[must_use]
+    pub const fn supports_shutter_control(&self) -> bool {
+        self.shutter_control
+    }
+
+    /// Returns the number of PWM outputs available for raw duty-cycle
+    /// control (0-16).
+    #[must_use]
+    pub const fn pwm_channels(&self) -> u8 {
+        self.pwm_channels
+    }
+
+    /// Returns the number of relays detected on the device, if known.
+    ///
+    /// Counted from the `POWER` fields of the runtime state (`Status 11`)
+    /// by [`from_status`](Self::from_status). Unlike
+    /// [`power_channels`](Self::power_channels) it can be 0. Capabilities
+    /// built manually leave it unknown.
+    #[must_use]
+    pub const fn relay_count(&self) -> Option<u8> {
+        self.relay_count
+    }
+
+    /// Returns whether the device has PWM outputs for raw duty-cycle control.
+    ///
+    /// These are GPIOs configured as `PWM<n>` outside of a light, driving
+    /// e.g. fans, pumps or analog interfaces. Tasmota does not report them in
+    /// its status, so they are not detected automatically; declare them with
+    /// [`CapabilitiesBuilder::pwm_channels`].
+    #[must_use]
+    pub const fn supports_raw_pwm(&self) -> bool {
+        self.pwm_channels > 0
+    }
+
     /// Returns an iterator over the names of enabled features.
     ///
     /// This is useful for introspection and debugging. The returned names
-    /// are: `dimmer_control`, `color_temperature_control`, `rgb_control`, `energy_monitoring`.
+    /// are: `dimmer_control`, `color_temperature_control`, `rgb_control`,
+    /// `energy_monitoring`, `shutter_control`, `raw_pwm`.
     ///
     /// # Examples
     ///
@@ -123,6 +211,8 @@ impl Capabilities {
                 .then_some("color_temperature_control"),
             self.rgb_control.then_some("rgb_control"),
             self.energy_monitoring.then_some("energy_monitoring"),
+            self.shutter_control.then_some("shutter_control"),
+            self.supports_raw_pwm().then_some("raw_pwm"),
         ]
         .into_iter()
         .flatten()
@@ -137,6 +227,9 @@ impl Default for Capabilities {
             color_temperature_control: false,
             rgb_control: false,
             energy_monitoring: false,
+            shutter_control: false,
+            pwm_channels: 0,
+            relay_count: None,
         }
     }
 }
@@ -151,6 +244,9 @@ impl Capabilities {
             color_temperature_control: false,
             rgb_control: false,
             energy_monitoring: false,
+            shutter_control: false,
+            pwm_channels: 0,
+            relay_count: None,
         }
     }
 
@@ -166,6 +262,9 @@ impl Capabilities {
             color_temperature_control: false,
             rgb_control: false,
             energy_monitoring: true,
+            shutter_control: false,
+            pwm_channels: 0,
+            relay_count: None,
         }
     }
 
@@ -182,6 +281,9 @@ impl Capabilities {
             color_temperature_control: false,
             rgb_control: true,
             energy_monitoring: false,
+            shutter_control: false,
+            pwm_channels: 0,
+            relay_count: None,
         }
     }
 
@@ -199,6 +301,9 @@ impl Capabilities {
             color_temperature_control: true,
             rgb_control: true,
             energy_monitoring: false,
+            shutter_control: false,
+            pwm_channels: 0,
+            relay_count: None,
         }
     }
 
@@ -211,6 +316,27 @@ impl Capabilities {
             color_temperature_control: true,
             rgb_control: false,
             energy_monitoring: false,
+            shutter_control: false,
+            pwm_channels: 0,
+            relay_count: None,
+        }
+    }
+
+    /// Creates capabilities for a shutter controller.
+    ///
+    /// - Two relays (motor up and down)
+    /// - Shutter position control
+    #[must_use]
+    pub const fn shutter() -> Self {
+        Self {
+            power_channels: 2,
+            dimmer_control: false,
+            color_temperature_control: false,
+            rgb_control: false,
+            energy_monitoring: false,
+            shutter_control: true,
+            pwm_channels: 0,
+            relay_count: None,
         }
     }
 
@@ -221,6 +347,8 @@ impl Capabilities {
     /// - Dimmer support from Dimmer field in `StatusSTS`
     /// - Color support from `HSBColor`/CT fields in `StatusSTS`
     /// - Energy support from ENERGY block in `StatusSNS` or `StatusSTS`
+    /// - Shutter support from `Shutter<x>` blocks in `StatusSNS` or `StatusSTS`,
+    ///   which Tasmota reports once shutter mode is enabled (`SetOption80 1`)
     ///
     /// # Arguments
     ///
@@ -262,6 +390,7 @@ impl Capabilities {
             if state.get("ENERGY").is_some() {
                 caps.energy_monitoring = true;
             }
+            caps.relay_count = Some(count_relays(state));
         }
 
         // Also check for ENERGY in StatusSNS (Status 10 - sensor data)
@@ -274,6 +403,14 @@ impl Capabilities {
             caps.energy_monitoring = true;
         }
 
+        if [&status.sensors, &status.sensor_status]
+            .into_iter()
+            .flatten()
+            .any(has_shutter)
+        {
+            caps.shutter_control = true;
+        }
+
         caps
     }
 
@@ -296,6 +433,185 @@ impl Capabilities {
     }
 }
 
+impl Capabilities {
+    /// Compares these capabilities with `other`.
+    ///
+    /// Features enabled in `other` but not here are reported as added,
+    /// and the other way round as removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tasmor_lib::Capabilities;
+    ///
+    /// let diff = Capabilities::basic().diff(&Capabilities::neo_coolcam());
+    /// assert_eq!(diff.added(), ["energy_monitoring"]);
+    /// assert!(diff.removed().is_empty());
+    /// assert_eq!(diff.to_string(), "+energy_monitoring");
+    /// ```
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> CapabilitiesDiff {
+        let old: Vec<_> = self.features().collect();
+        let new: Vec<_> = other.features().collect();
+        CapabilitiesDiff {
+            added: new.iter().filter(|f| !old.contains(f)).copied().collect(),
+            removed: old.iter().filter(|f| !new.contains(f)).copied().collect(),
+            power_channels: (self.power_channels != other.power_channels)
+                .then_some((self.power_channels, other.power_channels)),
+        }
+    }
+}
+
+/// Differences between two sets of capabilities.
+///
+/// Returned by [`Capabilities::diff`] and by
+/// [`Device::reprobe_capabilities`](crate::Device::reprobe_capabilities),
+/// e.g. after a device was reflashed with a different template.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapabilitiesDiff {
+    added: Vec<&'static str>,
+    removed: Vec<&'static str>,
+    power_channels: Option<(u8, u8)>,
+}
+
+impl CapabilitiesDiff {
+    /// Returns the names of newly enabled features.
+    ///
+    /// Names are those returned by [`Capabilities::features`].
+    #[must_use]
+    pub fn added(&self) -> &[&'static str] {
+        &self.added
+    }
+
+    /// Returns the names of features that are no longer enabled.
+    #[must_use]
+    pub fn removed(&self) -> &[&'static str] {
+        &self.removed
+    }
+
+    /// Returns the old and new number of power channels, if it changed.
+    #[must_use]
+    pub fn power_channels_changed(&self) -> Option<(u8, u8)> {
+        self.power_channels
+    }
+
+    /// Returns `true` if nothing changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.power_channels.is_none()
+    }
+}
+
+impl std::fmt::Display for CapabilitiesDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return f.write_str("unchanged");
+        }
+        let mut parts: Vec<String> = self.added.iter().map(|name| format!("+{name}")).collect();
+        parts.extend(self.removed.iter().map(|name| format!("-{name}")));
+        if let Some((old, new)) = self.power_channels {
+            parts.push(format!("power_channels {old}->{new}"));
+        }
+        f.write_str(&parts.join(" "))
+    }
+}
+
+/// Detects a device's capabilities from its status and template.
+///
+/// Implement this trait for hardware the built-in detection does not
+/// recognize, and install it with `with_detector` on the builder returned
+/// by [`Device::http`](crate::Device::http). Delegate to
+/// [`DefaultDetector`] for the modules you do not handle.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::{DeviceTemplate, StatusResponse};
+/// use tasmor_lib::{Capabilities, CapabilitiesBuilder, CapabilityDetector, DefaultDetector};
+///
+/// /// Recognizes a metering plug by its `BL0937` GPIO (code 2720).
+/// struct MeteringPlugDetector;
+///
+/// impl CapabilityDetector for MeteringPlugDetector {
+///     fn detect(&self, status: &StatusResponse, template: &DeviceTemplate) -> Capabilities {
+///         if template.has_gpio(2720) {
+///             return CapabilitiesBuilder::new().with_energy_monitoring().build();
+///         }
+///         DefaultDetector.detect(status, template)
+///     }
+/// }
+/// ```
+pub trait CapabilityDetector: Send + Sync {
+    /// Returns the capabilities of the device.
+    ///
+    /// `status` is the `Status 0` reply and `template` the `Template` reply.
+    fn detect(&self, status: &StatusResponse, template: &DeviceTemplate) -> Capabilities;
+}
+
+/// The built-in capability detection.
+///
+/// Uses [`Capabilities::from_status`] and ignores the template.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultDetector;
+
+impl CapabilityDetector for DefaultDetector {
+    fn detect(&self, status: &StatusResponse, _template: &DeviceTemplate) -> Capabilities {
+        Capabilities::from_status(status)
+    }
+}
+
+/// Counts the `POWER` and `POWER<x>` fields of a runtime state section.
+pub(crate) fn count_relays(state: &serde_json::Value) -> u8 {
+    let count = state.as_object().map_or(0, |fields| {
+        fields
+            .keys()
+            .filter(|key| {
+                key.strip_prefix("POWER")
+                    .is_some_and(|suffix| suffix.is_empty() || suffix.parse::<u8>().is_ok())
+            })
+            .count()
+    });
+    u8::try_from(count).unwrap_or(u8::MAX)
+}
+
+/// Returns `true` if a status section reports a `Shutter<x>` block.
+fn has_shutter(section: &serde_json::Value) -> bool {
+    section.as_object().is_some_and(|fields| {
+        fields.iter().any(|(key, value)| {
+            value.is_object()
+                && key
+                    .get(..7)
+                    .is_some_and(|prefix| prefix.eq_ignore_ascii_case("Shutter"))
+                && key.len() > 7
+                && key[7..].chars().all(|c| c.is_ascii_digit())
+        })
+    })
+}
+
+/// Deserializes a power channel count, clamping it to the supported range.
+///
+/// Keeps deserialized capabilities consistent with
+/// [`CapabilitiesBuilder::power_channels`].
+fn deserialize_power_channels<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let count = <u8 as serde::Deserialize>::deserialize(deserializer)?;
+    Ok(count.clamp(1, 8))
+}
+
+/// Clamps `pwm_channels` to 0-16 when deserializing.
+///
+/// Keeps deserialized capabilities consistent with
+/// [`CapabilitiesBuilder::pwm_channels`].
+fn deserialize_pwm_channels<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let count = <u8 as serde::Deserialize>::deserialize(deserializer)?;
+    Ok(count.min(MAX_PWM_CHANNELS))
+}
+
 /// Builder for creating custom capabilities.
 #[derive(Debug, Default)]
 pub struct CapabilitiesBuilder {
@@ -344,6 +660,22 @@ impl CapabilitiesBuilder {
         self
     }
 
+    /// Enables shutter control support.
+    #[must_use]
+    pub fn with_shutter(mut self) -> Self {
+        self.inner.shutter_control = true;
+        self
+    }
+
+    /// Sets the number of PWM outputs for raw duty-cycle control.
+    ///
+    /// Values above 16 are clamped.
+    #[must_use]
+    pub fn pwm_channels(mut self, count: u8) -> Self {
+        self.inner.pwm_channels = count.min(MAX_PWM_CHANNELS);
+        self
+    }
+
     /// Builds the capabilities.
     #[must_use]
     pub fn build(self) -> Capabilities {
@@ -668,6 +1000,44 @@ mod tests {
         assert!(!caps.is_light());
     }
 
+    #[test]
+    fn from_status_detects_shutter() {
+        // With SetOption80 1, Tasmota reports each shutter in StatusSNS
+        let json = r#"{
+            "Status": {
+                "Module": 18,
+                "DeviceName": "Blinds",
+                "FriendlyName": ["Up", "Down"]
+            },
+            "StatusSNS": {
+                "Time": "2024-01-01T00:00:00",
+                "Shutter1": {"Position": 50, "Direction": 0, "Target": 50, "Tilt": 0}
+            }
+        }"#;
+
+        let status: StatusResponse = serde_json::from_str(json).unwrap();
+        let caps = Capabilities::from_status(&status);
+
+        assert!(caps.supports_shutter_control());
+        assert_eq!(caps.power_channels, 2);
+        assert!(!caps.is_light());
+    }
+
+    #[test]
+    fn shutter_preset_and_builder() {
+        let caps = Capabilities::shutter();
+        assert!(caps.supports_shutter_control());
+        assert_eq!(caps.power_channels(), 2);
+        assert_eq!(caps.features().collect::<Vec<_>>(), ["shutter_control"]);
+
+        let built = CapabilitiesBuilder::new()
+            .power_channels(2)
+            .with_shutter()
+            .build();
+        assert_eq!(built, caps);
+        assert!(!Capabilities::default().supports_shutter_control());
+    }
+
     #[test]
     fn from_status_power_channels_clamped_to_8() {
         // Tasmota supports max 8 relays (POWER1-POWER8)
@@ -689,10 +1059,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_status_counts_relays() {
+        let json = r#"{
+            "Status": {"Module": 18, "FriendlyName": ["Tasmota"]},
+            "StatusSTS": {"POWER1": "ON", "POWER2": "OFF", "POWERRetain": 0}
+        }"#;
+
+        let status: StatusResponse = serde_json::from_str(json).unwrap();
+        let caps = Capabilities::from_status(&status);
+
+        assert_eq!(caps.relay_count(), Some(2));
+        assert_eq!(Capabilities::basic().relay_count(), None);
+
+        // A relay-less sensor node reports no POWER field
+        let status: StatusResponse =
+            serde_json::from_str(r#"{"StatusSTS": {"Uptime": "0T01:00:00"}}"#).unwrap();
+        assert_eq!(Capabilities::from_status(&status).relay_count(), Some(0));
+    }
+
     #[test]
     fn from_status_empty_response() {
         // Handle gracefully when status response has no data
-        let json = r#"{}"#;
+        let json = r"{}";
 
         let status: StatusResponse = serde_json::from_str(json).unwrap();
         let caps = Capabilities::from_status(&status);
@@ -715,6 +1104,65 @@ mod tests {
         assert!(caps.is_light());
     }
 
+    #[test]
+    fn serde_round_trip() {
+        let caps = CapabilitiesBuilder::new()
+            .power_channels(4)
+            .with_dimmer_control()
+            .with_energy_monitoring()
+            .build();
+
+        let json = serde_json::to_string(&caps).unwrap();
+        let restored: Capabilities = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, caps);
+    }
+
+    #[test]
+    fn serde_uses_named_fields() {
+        let value = serde_json::to_value(Capabilities::rgbcct_light()).unwrap();
+
+        assert_eq!(value["power_channels"], 1);
+        assert_eq!(value["dimmer_control"], true);
+        assert_eq!(value["color_temperature_control"], true);
+        assert_eq!(value["rgb_control"], true);
+        assert_eq!(value["energy_monitoring"], false);
+        assert_eq!(value["pwm_channels"], 0);
+    }
+
+    #[test]
+    fn raw_pwm_channels() {
+        let caps = CapabilitiesBuilder::new().pwm_channels(3).build();
+        assert!(caps.supports_raw_pwm());
+        assert_eq!(caps.pwm_channels(), 3);
+        assert_eq!(caps.features().collect::<Vec<_>>(), ["raw_pwm"]);
+        assert!(!Capabilities::rgb_light().supports_raw_pwm());
+
+        let clamped: Capabilities = serde_json::from_str(r#"{"pwm_channels": 40}"#).unwrap();
+        assert_eq!(clamped.pwm_channels(), 16);
+    }
+
+    #[test]
+    fn serde_missing_fields_use_defaults() {
+        let caps: Capabilities = serde_json::from_str(r#"{"dimmer_control": true}"#).unwrap();
+
+        assert_eq!(
+            caps,
+            CapabilitiesBuilder::new().with_dimmer_control().build()
+        );
+    }
+
+    #[test]
+    fn serde_clamps_power_channels_like_builder() {
+        let high: Capabilities = serde_json::from_str(r#"{"power_channels": 12}"#).unwrap();
+        let zero: Capabilities = serde_json::from_str(r#"{"power_channels": 0}"#).unwrap();
+
+        assert_eq!(high, CapabilitiesBuilder::new().power_channels(12).build());
+        assert_eq!(zero, CapabilitiesBuilder::new().power_channels(0).build());
+        assert_eq!(high.power_channels(), 8);
+        assert_eq!(zero.power_channels(), 1);
+    }
+
     #[test]
     fn builder_with_rgb_control() {
         let caps = CapabilitiesBuilder::new().with_rgb_control().build();
@@ -722,4 +1170,27 @@ mod tests {
         assert!(caps.rgb_control);
         assert!(caps.is_light());
     }
+
+    #[test]
+    fn diff_reports_changes() {
+        let old = Capabilities::basic();
+        assert!(old.diff(&old).is_empty());
+        assert_eq!(old.diff(&old).to_string(), "unchanged");
+
+        let new = CapabilitiesBuilder::new()
+            .power_channels(2)
+            .with_energy_monitoring()
+            .build();
+        let diff = Capabilities::cct_light().diff(&new);
+        assert_eq!(diff.added(), ["energy_monitoring"]);
+        assert_eq!(
+            diff.removed(),
+            ["dimmer_control", "color_temperature_control"]
+        );
+        assert_eq!(diff.power_channels_changed(), Some((1, 2)));
+        assert_eq!(
+            diff.to_string(),
+            "+energy_monitoring -dimmer_control -color_temperature_control power_channels 1->2"
+        );
+    }
 }
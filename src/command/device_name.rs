@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Device name command.
+//!
+//! `DeviceName` is the name of the device as a whole, shown in the web UI
+//! and used by Home Assistant discovery. It is separate from the
+//! `FriendlyName<x>` labels of the individual relays.
+
+use crate::command::Command;
+use crate::error::ValueError;
+
+/// Maximum device name length accepted by Tasmota.
+pub const MAX_DEVICE_NAME_LENGTH: usize = 33;
+
+/// Command to read or set the device name.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, DeviceNameCommand};
+///
+/// let cmd = DeviceNameCommand::set("Kitchen Hub").unwrap();
+/// assert_eq!(cmd.to_http_command(), "DeviceName Kitchen Hub");
+///
+/// assert_eq!(DeviceNameCommand::Get.to_http_command(), "DeviceName");
+/// assert!(DeviceNameCommand::set("").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceNameCommand {
+    /// Query the device name.
+    Get,
+    /// Set the device name.
+    Set(String),
+}
+
+impl DeviceNameCommand {
+    /// Creates a command to set the device name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidDeviceName`] if the name is empty or
+    /// longer than [`MAX_DEVICE_NAME_LENGTH`] characters.
+    pub fn set(name: impl Into<String>) -> Result<Self, ValueError> {
+        let name = name.into();
+        if name.trim().is_empty() {
+            return Err(ValueError::InvalidDeviceName("empty".to_string()));
+        }
+        if name.chars().count() > MAX_DEVICE_NAME_LENGTH {
+            return Err(ValueError::InvalidDeviceName(format!(
+                "longer than {MAX_DEVICE_NAME_LENGTH} characters"
+            )));
+        }
+        Ok(Self::Set(name))
+    }
+}
+
+impl Command for DeviceNameCommand {
+    fn name(&self) -> String {
+        "DeviceName".to_string()
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Get => None,
+            Self::Set(name) => Some(name.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_accepts_names_up_to_the_limit() {
+        let name = "x".repeat(MAX_DEVICE_NAME_LENGTH);
+        let cmd = DeviceNameCommand::set(name.clone()).unwrap();
+        assert_eq!(cmd.payload(), Some(name));
+    }
+
+    #[test]
+    fn set_rejects_blank_and_long_names() {
+        assert!(matches!(
+            DeviceNameCommand::set("   "),
+            Err(ValueError::InvalidDeviceName(_))
+        ));
+        assert!(DeviceNameCommand::set("x".repeat(MAX_DEVICE_NAME_LENGTH + 1)).is_err());
+    }
+}
@@ -14,6 +14,7 @@
 //! Reference: <https://tasmota.github.io/docs/Commands/#management>
 
 use crate::command::Command;
+use crate::error::ValueError;
 
 /// Command to query energy monitoring data.
 ///
@@ -84,6 +85,43 @@ impl EnergyCommand {
     pub const fn reset_total() -> Self {
         Self::ResetTotal
     }
+
+    /// Creates a command to set the total counter to `kwh` kilowatt-hours.
+    ///
+    /// The value is rounded to whole watt-hours, the unit Tasmota stores.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidEnergyTotal`] if `kwh` is negative, not
+    /// finite, or too large for the device counter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tasmor_lib::command::{Command, EnergyCommand};
+    ///
+    /// let cmd = EnergyCommand::set_total_kwh(1234.5678).unwrap();
+    /// assert_eq!(cmd, EnergyCommand::SetTotal(1_234_568));
+    /// assert_eq!(cmd.payload(), Some("1234568".to_string()));
+    ///
+    /// assert!(EnergyCommand::set_total_kwh(-1.0).is_err());
+    /// ```
+    pub fn set_total_kwh(kwh: f64) -> Result<Self, ValueError> {
+        if !kwh.is_finite() || kwh < 0.0 {
+            return Err(ValueError::InvalidEnergyTotal(format!(
+                "{kwh} kWh is not a non-negative number"
+            )));
+        }
+        let wh = (kwh * 1000.0).round();
+        if wh > f64::from(u32::MAX) {
+            return Err(ValueError::InvalidEnergyTotal(format!(
+                "{kwh} kWh exceeds the device counter"
+            )));
+        }
+        // Range checked above
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Ok(Self::SetTotal(wh as u32))
+    }
 }
 
 impl Command for EnergyCommand {
@@ -137,6 +175,25 @@ mod tests {
         assert_eq!(EnergyCommand::ResetTotal.payload(), Some("0".to_string()));
     }
 
+    #[test]
+    fn energy_command_set_total_kwh() {
+        assert_eq!(
+            EnergyCommand::set_total_kwh(0.0).unwrap(),
+            EnergyCommand::SetTotal(0)
+        );
+        assert_eq!(
+            EnergyCommand::set_total_kwh(12.3456).unwrap(),
+            EnergyCommand::SetTotal(12_346)
+        );
+        assert!(matches!(
+            EnergyCommand::set_total_kwh(-0.5),
+            Err(ValueError::InvalidEnergyTotal(_))
+        ));
+        assert!(EnergyCommand::set_total_kwh(f64::NAN).is_err());
+        assert!(EnergyCommand::set_total_kwh(f64::INFINITY).is_err());
+        assert!(EnergyCommand::set_total_kwh(5_000_000.0).is_err());
+    }
+
     #[test]
     fn energy_command_set() {
         let cmd = EnergyCommand::SetToday(1500);
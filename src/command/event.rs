@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Rule event commands.
+//!
+//! This module provides the `Event` command, which fires an `Event#<name>`
+//! trigger in the device's rules. The rules themselves are defined on the
+//! device with the `Rule1`-`Rule3` commands, e.g.
+//! `Rule1 ON Event#night DO Backlog Power1 OFF; Dimmer 10 ENDON`; the
+//! value sent with the event is available in the rule as `%value%`.
+
+use crate::command::Command;
+use crate::error::ValueError;
+
+/// Command to fire a rule trigger on the device.
+///
+/// Only rules listening on `Event#<name>` react to the event. If no enabled
+/// rule listens for it, Tasmota still acknowledges the event and nothing
+/// happens.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, EventCommand};
+///
+/// let cmd = EventCommand::fire("night").unwrap();
+/// assert_eq!(cmd.to_http_command(), "Event night");
+///
+/// let cmd = EventCommand::fire_with_value("level", "42").unwrap();
+/// assert_eq!(cmd.to_http_command(), "Event level=42");
+///
+/// // Event names are limited to letters, digits and underscores
+/// assert!(EventCommand::fire("good night").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventCommand {
+    /// Fire an event, as `<name>` or `<name>=<value>`.
+    Fire(String),
+}
+
+impl EventCommand {
+    /// Creates a command to fire the event `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidEvent`] if `name` is empty or contains
+    /// characters other than ASCII letters, digits and underscores.
+    pub fn fire(name: &str) -> Result<Self, ValueError> {
+        validate_event_name(name)?;
+        Ok(Self::Fire(name.to_string()))
+    }
+
+    /// Creates a command to fire the event `name` with a value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidEvent`] if `name` is not a valid event
+    /// name, or `value` contains `;` or control characters.
+    pub fn fire_with_value(name: &str, value: &str) -> Result<Self, ValueError> {
+        validate_event_name(name)?;
+        if value.contains(';') || value.chars().any(char::is_control) {
+            return Err(ValueError::InvalidEvent(format!(
+                "value {value:?} contains ';' or control characters"
+            )));
+        }
+        Ok(Self::Fire(format!("{name}={value}")))
+    }
+}
+
+/// Checks that an event name can be matched by an `Event#<name>` trigger.
+fn validate_event_name(name: &str) -> Result<(), ValueError> {
+    let valid_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    if name.is_empty() || !name.chars().all(valid_char) {
+        return Err(ValueError::InvalidEvent(format!(
+            "name {name:?} must be letters, digits or '_'"
+        )));
+    }
+    Ok(())
+}
+
+impl Command for EventCommand {
+    fn name(&self) -> String {
+        "Event".to_string()
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Fire(event) => Some(event.clone()),
+        }
+    }
+
+    fn is_idempotent(&self) -> bool {
+        // A repeated event runs its rule again
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_name_is_validated() {
+        assert!(EventCommand::fire("Wake_up2").is_ok());
+        for name in ["", "a=b", "a;Restart 1", "Event#x", "été"] {
+            assert!(
+                matches!(EventCommand::fire(name), Err(ValueError::InvalidEvent(_))),
+                "{name:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn event_value_is_validated() {
+        assert_eq!(
+            EventCommand::fire_with_value("scene", "movie night")
+                .unwrap()
+                .payload(),
+            Some("scene=movie night".to_string())
+        );
+        assert!(EventCommand::fire_with_value("scene", "1; Restart 1").is_err());
+        assert!(EventCommand::fire_with_value("scene", "a\nb").is_err());
+        assert!(EventCommand::fire_with_value("bad name", "1").is_err());
+    }
+
+    #[test]
+    fn events_are_not_idempotent() {
+        assert!(!EventCommand::fire("night").unwrap().is_idempotent());
+    }
+}
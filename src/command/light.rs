@@ -6,10 +6,14 @@
 //! Light control commands.
 //!
 //! This module provides commands for controlling light brightness, color
-//! temperature, HSB color, and transition speed.
+//! temperature, HSB color, white level, raw channels, and transition speed.
 
 use crate::command::Command;
-use crate::types::{ColorTemperature, Dimmer, FadeDuration, HsbColor};
+use crate::error::ValueError;
+use crate::types::{ColorTemperature, Dimmer, FadeDuration, HsbColor, RgbColor, RgbwwColor};
+
+/// Highest light channel index (`Channel1`-`Channel5`).
+pub const MAX_LIGHT_CHANNELS: u8 = 5;
 
 /// Command to control dimmer/brightness level.
 ///
@@ -31,6 +35,10 @@ use crate::types::{ColorTemperature, Dimmer, FadeDuration, HsbColor};
 /// // Increase brightness by step
 /// let inc = DimmerCommand::Increase;
 /// assert_eq!(inc.payload(), Some("+".to_string()));
+///
+/// // Decrease brightness by 10 points
+/// let dec = DimmerCommand::DecreaseBy(10);
+/// assert_eq!(dec.payload(), Some("-10".to_string()));
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DimmerCommand {
@@ -42,6 +50,16 @@ pub enum DimmerCommand {
     Increase,
     /// Decrease brightness by `DimmerStep`.
     Decrease,
+    /// Increase brightness by the given points (1-100).
+    ///
+    /// Larger steps are sent as 100 and a step of 0 as 1. The device
+    /// stops at 100%.
+    IncreaseBy(u8),
+    /// Decrease brightness by the given points (1-100).
+    ///
+    /// Larger steps are sent as 100 and a step of 0 as 1. The device
+    /// stops at its minimum level.
+    DecreaseBy(u8),
     /// Decrease to minimum (1).
     Minimum,
     /// Increase to maximum (100).
@@ -69,11 +87,21 @@ impl Command for DimmerCommand {
             Self::Set(dim) => Some(dim.value().to_string()),
             Self::Increase => Some("+".to_string()),
             Self::Decrease => Some("-".to_string()),
+            Self::IncreaseBy(step) => Some(format!("+{}", (*step).clamp(1, 100))),
+            Self::DecreaseBy(step) => Some(format!("-{}", (*step).clamp(1, 100))),
             Self::Minimum => Some("<".to_string()),
             Self::Maximum => Some(">".to_string()),
             Self::Stop => Some("!".to_string()),
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        // A repeated step moves the level twice
+        !matches!(
+            self,
+            Self::Increase | Self::Decrease | Self::IncreaseBy(_) | Self::DecreaseBy(_)
+        )
+    }
 }
 
 /// Command to control color temperature.
@@ -126,6 +154,10 @@ impl Command for ColorTemperatureCommand {
             Self::Decrease => Some("-".to_string()),
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        matches!(self, Self::Get | Self::Set(_))
+    }
 }
 
 /// Command to control HSB (Hue, Saturation, Brightness) color.
@@ -207,6 +239,45 @@ impl Command for HsbColorCommand {
     }
 }
 
+/// Command to query or set all channels of a light (`Color`).
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{ColorCommand, Command};
+/// use tasmor_lib::types::{RgbColor, RgbwwColor};
+///
+/// let cmd = ColorCommand::Set(RgbwwColor::new(255, 0, 0, 0, 128));
+/// assert_eq!(cmd.to_http_command(), "Color FF00000080");
+///
+/// // RGB only, leaving the white channels to Tasmota
+/// let cmd = ColorCommand::SetRgb(RgbColor::new(255, 87, 51));
+/// assert_eq!(cmd.to_http_command(), "Color #FF5733");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorCommand {
+    /// Query the channels.
+    Get,
+    /// Set red, green, blue, cold white and warm white.
+    Set(RgbwwColor),
+    /// Set red, green and blue from a `#RRGGBB` hex value.
+    SetRgb(RgbColor),
+}
+
+impl Command for ColorCommand {
+    fn name(&self) -> String {
+        "Color".to_string()
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Get => None,
+            Self::Set(color) => Some(color.to_hex()),
+            Self::SetRgb(color) => Some(color.to_hex_with_hash()),
+        }
+    }
+}
+
 /// Command to control fade transition duration.
 ///
 /// # Examples
@@ -258,6 +329,184 @@ impl Command for FadeDurationCommand {
             Self::Decrease => Some("-".to_string()),
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        matches!(self, Self::Get | Self::Set(_))
+    }
+}
+
+/// One-shot transition settings that apply to the next light command only.
+///
+/// `Fade2` and `Speed2` behave like `Fade` and `Speed` but revert after the
+/// following command, so a single color change can be animated without
+/// touching the persistent fade settings. Requires Tasmota 9.2 or newer.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use tasmor_lib::command::{Command, TransitionCommand};
+/// use tasmor_lib::types::FadeDuration;
+///
+/// assert_eq!(TransitionCommand::Enable.to_http_command(), "Fade2 1");
+///
+/// let speed = TransitionCommand::Speed(FadeDuration::new(Duration::from_secs(3)).unwrap());
+/// assert_eq!(speed.to_http_command(), "Speed2 6");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionCommand {
+    /// Fade the next light change.
+    Enable,
+    /// Use this fade duration for the next light change.
+    Speed(FadeDuration),
+}
+
+impl Command for TransitionCommand {
+    fn name(&self) -> String {
+        match self {
+            Self::Enable => "Fade2".to_string(),
+            Self::Speed(_) => "Speed2".to_string(),
+        }
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Enable => Some("1".to_string()),
+            Self::Speed(duration) => Some(duration.value().to_string()),
+        }
+    }
+}
+
+/// Command to control a single light channel (`Channel<x>`).
+///
+/// Channels are the raw PWM outputs of a light (e.g. red, green, blue,
+/// cold white, warm white), each 0-100%.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{ChannelCommand, Command};
+///
+/// let cmd = ChannelCommand::set(4, 80).unwrap();
+/// assert_eq!(cmd.to_http_command(), "Channel4 80");
+///
+/// assert!(ChannelCommand::set(6, 80).is_err());
+/// assert!(ChannelCommand::set(1, 101).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelCommand {
+    /// Query a channel value.
+    Get {
+        /// Channel index (1-5).
+        index: u8,
+    },
+    /// Set a channel value.
+    Set {
+        /// Channel index (1-5).
+        index: u8,
+        /// Channel value (0-100).
+        value: u8,
+    },
+}
+
+impl ChannelCommand {
+    /// Creates a command to query a channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `index` is not 1-5.
+    pub fn get(index: u8) -> Result<Self, ValueError> {
+        validate_channel_index(index)?;
+        Ok(Self::Get { index })
+    }
+
+    /// Creates a command to set a channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `index` is not 1-5 or `value`
+    /// exceeds 100.
+    pub fn set(index: u8, value: u8) -> Result<Self, ValueError> {
+        validate_channel_index(index)?;
+        if value > 100 {
+            return Err(ValueError::OutOfRange {
+                min: 0,
+                max: 100,
+                actual: u16::from(value),
+            });
+        }
+        Ok(Self::Set { index, value })
+    }
+}
+
+fn validate_channel_index(index: u8) -> Result<(), ValueError> {
+    if (1..=MAX_LIGHT_CHANNELS).contains(&index) {
+        Ok(())
+    } else {
+        Err(ValueError::OutOfRange {
+            min: 1,
+            max: u16::from(MAX_LIGHT_CHANNELS),
+            actual: u16::from(index),
+        })
+    }
+}
+
+impl Command for ChannelCommand {
+    fn name(&self) -> String {
+        match self {
+            Self::Get { index } | Self::Set { index, .. } => format!("Channel{index}"),
+        }
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Get { .. } => None,
+            Self::Set { value, .. } => Some(value.to_string()),
+        }
+    }
+}
+
+/// Command to control the white channels of a light (`White`).
+///
+/// `White` sets the brightness of the white channels of an RGBW or RGBCCT
+/// light, switching it to white output at that level. This differs from
+/// [`DimmerCommand`], which scales whatever the light currently shows, and
+/// from [`ColorTemperatureCommand`], which sets the white tone.
+///
+/// Sending `White` replaces the RGB output: the color channels turn off, so
+/// a previously set `HSBColor` is no longer shown even though the device
+/// may still report its hue.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, WhiteCommand};
+/// use tasmor_lib::types::Dimmer;
+///
+/// let cmd = WhiteCommand::Set(Dimmer::new(50).unwrap());
+/// assert_eq!(cmd.to_http_command(), "White 50");
+///
+/// assert_eq!(WhiteCommand::Get.to_http_command(), "White");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhiteCommand {
+    /// Query the white channel brightness.
+    Get,
+    /// Switch to white output at this brightness.
+    Set(Dimmer),
+}
+
+impl Command for WhiteCommand {
+    fn name(&self) -> String {
+        "White".to_string()
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Get => None,
+            Self::Set(level) => Some(level.value().to_string()),
+        }
+    }
 }
 
 /// Command to query the current device state.
@@ -307,6 +556,18 @@ mod tests {
         assert_eq!(cmd.payload(), Some("50".to_string()));
     }
 
+    #[test]
+    fn white_command() {
+        let cmd = WhiteCommand::Set(Dimmer::new(50).unwrap());
+        assert_eq!(cmd.name(), "White");
+        assert_eq!(cmd.payload(), Some("50".to_string()));
+        assert_eq!(WhiteCommand::Get.payload(), None);
+        assert_eq!(
+            WhiteCommand::Set(Dimmer::new(100).unwrap()).to_http_command(),
+            "White 100"
+        );
+    }
+
     #[test]
     fn dimmer_command_adjustments() {
         assert_eq!(DimmerCommand::Increase.payload(), Some("+".to_string()));
@@ -316,6 +577,43 @@ mod tests {
         assert_eq!(DimmerCommand::Stop.payload(), Some("!".to_string()));
     }
 
+    #[test]
+    fn dimmer_command_relative_steps() {
+        assert_eq!(
+            DimmerCommand::IncreaseBy(10).to_http_command(),
+            "Dimmer +10"
+        );
+        assert_eq!(
+            DimmerCommand::DecreaseBy(25).to_http_command(),
+            "Dimmer -25"
+        );
+        assert_eq!(
+            DimmerCommand::IncreaseBy(150).payload(),
+            Some("+100".to_string())
+        );
+        assert_eq!(
+            DimmerCommand::DecreaseBy(0).payload(),
+            Some("-1".to_string())
+        );
+    }
+
+    #[test]
+    fn relative_adjustments_are_not_idempotent() {
+        for cmd in [
+            DimmerCommand::Increase,
+            DimmerCommand::Decrease,
+            DimmerCommand::IncreaseBy(10),
+            DimmerCommand::DecreaseBy(10),
+        ] {
+            assert!(!cmd.is_idempotent(), "{cmd:?} must not be retried");
+        }
+        assert!(DimmerCommand::Maximum.is_idempotent());
+        assert!(!ColorTemperatureCommand::Increase.is_idempotent());
+        assert!(!FadeDurationCommand::Decrease.is_idempotent());
+        assert!(ColorTemperatureCommand::Set(ColorTemperature::COOL).is_idempotent());
+        assert!(FadeDurationCommand::Get.is_idempotent());
+    }
+
     #[test]
     fn color_temp_command_set() {
         let cmd = ColorTemperatureCommand::Set(ColorTemperature::COOL);
@@ -342,6 +640,21 @@ mod tests {
         assert_eq!(cmd.payload(), Some("0,100,100".to_string()));
     }
 
+    #[test]
+    fn color_command() {
+        let cmd = ColorCommand::Set(RgbwwColor::new(255, 0, 0, 0, 128));
+        assert_eq!(cmd.name(), "Color");
+        assert_eq!(cmd.payload(), Some("FF00000080".to_string()));
+        assert_eq!(ColorCommand::Get.payload(), None);
+    }
+
+    #[test]
+    fn color_command_rgb_hex() {
+        let cmd = ColorCommand::SetRgb(RgbColor::new(255, 87, 51));
+        assert_eq!(cmd.name(), "Color");
+        assert_eq!(cmd.payload(), Some("#FF5733".to_string()));
+    }
+
     #[test]
     fn hsb_color_command_individual() {
         assert_eq!(HsbColorCommand::SetHue(120).name(), "HSBColor1");
@@ -382,6 +695,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn transition_command_is_one_shot() {
+        assert_eq!(TransitionCommand::Enable.to_http_command(), "Fade2 1");
+        let cmd = TransitionCommand::Speed(FadeDuration::new(Duration::from_millis(2500)).unwrap());
+        assert_eq!(cmd.name(), "Speed2");
+        assert_eq!(cmd.payload(), Some("5".to_string()));
+    }
+
+    #[test]
+    fn channel_command() {
+        let cmd = ChannelCommand::get(2).unwrap();
+        assert_eq!(cmd.to_http_command(), "Channel2");
+
+        let cmd = ChannelCommand::set(5, 0).unwrap();
+        assert_eq!(cmd.mqtt_topic_suffix(), "Channel5");
+        assert_eq!(cmd.mqtt_payload(), "0");
+
+        assert!(ChannelCommand::get(0).is_err());
+        assert!(ChannelCommand::set(1, 100).is_ok());
+    }
+
     #[test]
     fn state_command() {
         let cmd = StateCommand;
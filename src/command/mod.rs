@@ -13,15 +13,39 @@
 //! | Command Type | Purpose | Example |
 //! |-------------|---------|---------|
 //! | [`PowerCommand`] | Control relay power state | On, Off, Toggle |
+//! | [`PulseTimeCommand`] | Switch a relay off after a set time | `PulseTime1 1000` |
+//! | [`TimerCommand`] | Schedule a relay by time of day and weekday (1-16) | `Timer1 {"Time":"07:00",...}` |
+//! | [`ShutterCommand`] | Move a shutter to a position (0 closed, 100 open) | `ShutterPosition1 50` |
 //! | [`DimmerCommand`] | Adjust brightness (0-100) | Set to 75% |
 //! | [`ColorTemperatureCommand`] | Set white color temperature | Warm, Cool |
 //! | [`HsbColorCommand`] | Set RGB color in HSB format | Red, Blue |
+//! | [`ColorCommand`] | Set all channels of an RGBCCT light | `Color FF00000080` |
+//! | [`ChannelCommand`] | Set a raw light channel (0-100) | `Channel4 80` |
+//! | [`WhiteCommand`] | Switch to white at a brightness (0-100) | `White 50` |
 //! | [`SchemeCommand`] | Set light scheme/effect (0-4) | Wakeup, Random |
 //! | [`WakeupDurationCommand`] | Set wakeup duration (1-3000s) | 5 minutes |
 //! | [`FadeDurationCommand`] | Set fade transition duration (0.5-20s) | 2s, 10s |
 //! | [`FadeCommand`] | Enable/disable fade transitions | On, Off |
+//! | [`TransitionCommand`] | Fade the next light change only | `Speed2 6` |
+//! | [`PwmCommand`] | Set the duty cycle of a raw PWM output (0-1023) | `PWM1 512` |
 //! | [`EnergyCommand`] | Query energy consumption | Get, Reset |
 //! | [`StatusCommand`] | Query device status | Status 0-10 |
+//! | [`ModuleCommand`] | Select or list hardware modules, read the template | Sonoff Basic |
+//! | [`GpioCommand`] | Read the component assigned to GPIO pins | GPIO4 |
+//! | [`I2cScanCommand`] | List the devices on the I2C bus | `I2CScan` |
+//! | [`WebColorCommand`] | Set web UI color theme | Dark, Light |
+//! | [`SecurityCommand`] | Set passwords and CORS origin | `WebPassword` |
+//! | [`TuyaCommand`] | Send Tuya MCU data point values | DP 1 = on |
+//! | [`TuyaMcuCommand`] | Map Tuya data points to Tasmota functions | Dimmer on DP 2 |
+//! | [`TopicCommand`] | Query the MQTT topic layout | `FullTopic` |
+//! | [`RemoteTopicCommand`] | Read or set where buttons and switches publish | `ButtonTopic` |
+//! | [`MqttConfigCommand`] | Point the device at an MQTT broker | `MqttHost` |
+//! | [`NtpServerCommand`] | Read or set NTP servers (1-3) | `pool.ntp.org` |
+//! | [`DeviceNameCommand`] | Read or set the device name | Kitchen Hub |
+//! | [`SetOptionCommand`] | Read or set any `SetOption` | `SetOption20 1` |
+//! | [`SaveDataCommand`] | Set how often settings are saved to flash | `SaveData 300` |
+//! | [`EventCommand`] | Fire a rule trigger | `Event night` |
+//! | [`RuleCommand`] | Read, write or enable a rule set (1-3) | `Rule1 1` |
 //!
 //! # Command Structure
 //!
@@ -62,21 +86,57 @@
 //! assert_eq!(ct.name(), "CT");
 //! ```
 
+mod device_name;
 mod energy;
+mod event;
 mod light;
+mod module;
+mod mqtt;
 mod power;
+mod pulse_time;
+mod pwm;
 mod routine;
+mod rule;
+mod save_data;
 mod scheme;
+mod security;
+mod set_option;
+mod shutter;
 mod status;
+mod time;
+mod timer;
+mod topic;
+mod tuya;
+mod web;
 
+pub use device_name::{DeviceNameCommand, MAX_DEVICE_NAME_LENGTH};
 pub use energy::EnergyCommand;
+pub use event::EventCommand;
 pub use light::{
-    ColorTemperatureCommand, DimmerCommand, FadeDurationCommand, HsbColorCommand, StateCommand,
+    ChannelCommand, ColorCommand, ColorTemperatureCommand, DimmerCommand, FadeDurationCommand,
+    HsbColorCommand, MAX_LIGHT_CHANNELS, StateCommand, TransitionCommand, WhiteCommand,
 };
+pub use module::{
+    GpioCommand, I2cScanCommand, MAX_GPIO, MAX_GPIO_ESP8266, MAX_MODULE_ID, ModuleCommand,
+};
+pub use mqtt::{MAX_MQTT_USER_LENGTH, MqttConfigCommand};
 pub use power::{FadeCommand, PowerCommand, StartupFadeCommand};
+pub(crate) use pulse_time::decode_pulse_time;
+pub use pulse_time::{MAX_PULSE_TIME, PulseTimeCommand};
+pub use pwm::{MAX_PWM_CHANNELS, MAX_PWM_VALUE, PwmCommand};
 pub use routine::{MAX_ROUTINE_STEPS, Routine, RoutineBuilder};
+pub use rule::{MAX_RULE_LENGTH, MAX_RULE_SETS, Rule, RuleBuilder, RuleCommand, RuleTrigger};
+pub use save_data::{MAX_SAVE_DATA_INTERVAL, SaveDataCommand};
 pub use scheme::{SchemeCommand, WakeupDurationCommand};
+pub use security::{MAX_PASSWORD_LENGTH, SecurityCommand, validate_password};
+pub use set_option::{SetOptionCommand, SetOptionValue};
+pub use shutter::{MAX_SHUTTERS, ShutterCommand};
 pub use status::{StatusCommand, StatusType};
+pub use time::{MAX_NTP_SERVERS, NtpServerCommand};
+pub use timer::{MAX_TIMER_WINDOW, MAX_TIMERS, Timer, TimerAction, TimerCommand};
+pub use topic::{RemoteInput, RemoteTopicCommand, TopicCommand};
+pub use tuya::{MAX_TUYA_FUNCTION, TuyaCommand, TuyaMcuCommand};
+pub use web::WebColorCommand;
 
 use crate::protocol::ResponseSpec;
 
@@ -84,6 +144,36 @@ use crate::protocol::ResponseSpec;
 ///
 /// Commands are serialized to the Tasmota command format for transmission
 /// over HTTP or MQTT.
+///
+/// # Custom commands
+///
+/// Commands without first-class support can be added outside the crate:
+/// implement `name` and `payload`, which together form the command line
+/// (`<name> <payload>`), and send it with
+/// [`Device::send_command`](crate::Device::send_command) or parse the reply
+/// with [`Device::send_and_parse`](crate::Device::send_and_parse). Override
+/// [`response_spec`](Self::response_spec) only if the command answers with
+/// several MQTT messages.
+///
+/// ```
+/// use tasmor_lib::command::Command;
+///
+/// /// `LedPower1 <state>`: turns the status LED on or off.
+/// struct LedPower(bool);
+///
+/// impl Command for LedPower {
+///     fn name(&self) -> String {
+///         "LedPower1".to_string()
+///     }
+///
+///     fn payload(&self) -> Option<String> {
+///         Some(if self.0 { "1" } else { "0" }.to_string())
+///     }
+/// }
+///
+/// assert_eq!(LedPower(true).to_http_command(), "LedPower1 1");
+/// assert_eq!(LedPower(false).mqtt_topic_suffix(), "LedPower1");
+/// ```
 pub trait Command {
     /// Returns the command name with any index suffix.
     ///
@@ -98,6 +188,16 @@ pub trait Command {
     /// - `Dimmer 50` has payload `Some("50")`
     fn payload(&self) -> Option<String>;
 
+    /// Returns the shorthand names Tasmota also accepts for this command.
+    ///
+    /// For example, `SetOption20` can be sent as `SO20`. Tasmota matches
+    /// command names case-insensitively, so aliases are only needed for
+    /// genuinely different spellings. The default implementation returns
+    /// no aliases.
+    fn aliases(&self) -> Vec<String> {
+        Vec::new()
+    }
+
     /// Returns the full command string for HTTP requests.
     ///
     /// Format: `<name> <payload>` or just `<name>` if no payload.
@@ -132,6 +232,59 @@ pub trait Command {
     fn response_spec(&self) -> ResponseSpec {
         ResponseSpec::Single
     }
+
+    /// Returns how urgently this command is sent.
+    ///
+    /// Power changes are [`CommandPriority::High`], so a user turning a
+    /// light off is not held up by a backlog of color updates. The default
+    /// implementation returns [`CommandPriority::Normal`].
+    fn priority(&self) -> CommandPriority {
+        CommandPriority::Normal
+    }
+
+    /// Returns whether sending this command twice has the same effect as
+    /// sending it once.
+    ///
+    /// A retried request may reach the device even though the first attempt
+    /// already did, so non-idempotent commands such as `Power TOGGLE` are
+    /// only retried when the [`RetryPolicy`](crate::protocol::RetryPolicy)
+    /// allows it. The default implementation returns `true`; commands with
+    /// relative steps (`CT +`, `Speed -`) or side effects that add up, such
+    /// as firing an `Event`, must return `false`.
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+}
+
+/// How urgently a command is sent to the device.
+///
+/// A device and its clones send one command at a time, over HTTP as well as
+/// MQTT. While one is in flight, the others wait, and a `High` command is
+/// sent before every `Normal` command that is still waiting, even one that
+/// has waited longer. Commands of the same priority keep the order in which
+/// they were sent. A command already in flight is never interrupted, and a
+/// steady stream of `High` commands holds `Normal` ones back until it stops.
+/// A [`Routine`] takes the highest priority of its steps.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, CommandPriority, DimmerCommand, PowerCommand};
+/// use tasmor_lib::types::{Dimmer, PowerIndex};
+///
+/// assert_eq!(PowerCommand::off(PowerIndex::one()).priority(), CommandPriority::High);
+/// assert_eq!(
+///     DimmerCommand::Set(Dimmer::new(40).unwrap()).priority(),
+///     CommandPriority::Normal
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CommandPriority {
+    /// Cosmetic and query commands.
+    #[default]
+    Normal,
+    /// Commands that reflect a safety or user intent, such as power.
+    High,
 }
 
 #[cfg(test)]
@@ -165,4 +318,13 @@ mod tests {
         assert_eq!(cmd.mqtt_topic_suffix(), "Power1");
         assert_eq!(cmd.mqtt_payload(), "ON");
     }
+
+    #[test]
+    fn command_aliases() {
+        let cmd = PowerCommand::Get {
+            index: PowerIndex::one(),
+        };
+        assert!(cmd.aliases().is_empty());
+        assert_eq!(SetOptionCommand::query(20).aliases(), ["SO20"]);
+    }
 }
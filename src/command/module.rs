@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Hardware module commands.
+//!
+//! This module provides commands for selecting the device's hardware module
+//! (`Module`), listing the modules supported by the firmware (`Modules`),
+//! reading the user template (`Template`), reading the component assigned
+//! to individual GPIO pins (`Gpio`) and scanning the I2C bus (`I2CScan`).
+
+use crate::command::Command;
+use crate::error::ValueError;
+
+/// Highest module number accepted by Tasmota.
+///
+/// Tasmota stores the module number in a single byte. Module 0 selects the
+/// user template.
+pub const MAX_MODULE_ID: u16 = 255;
+
+/// Command to query, select or list hardware modules, or read the template.
+///
+/// Selecting a module makes the device restart with the new hardware
+/// configuration.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, ModuleCommand};
+///
+/// // Switch to Sonoff Basic
+/// let cmd = ModuleCommand::set(1).unwrap();
+/// assert_eq!(cmd.name(), "Module");
+/// assert_eq!(cmd.payload(), Some("1".to_string()));
+///
+/// // List supported modules
+/// let list = ModuleCommand::List;
+/// assert_eq!(list.name(), "Modules");
+/// assert_eq!(list.payload(), None);
+///
+/// // Out of range
+/// assert!(ModuleCommand::set(256).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleCommand {
+    /// Query the active module.
+    Get,
+    /// Select a module by number.
+    Set(u16),
+    /// List the modules supported by the firmware.
+    List,
+    /// Query the active template (GPIO assignment).
+    Template,
+}
+
+impl ModuleCommand {
+    /// Creates a command to select a module.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `id` exceeds [`MAX_MODULE_ID`].
+    pub fn set(id: u16) -> Result<Self, ValueError> {
+        if id > MAX_MODULE_ID {
+            return Err(ValueError::OutOfRange {
+                min: 0,
+                max: MAX_MODULE_ID,
+                actual: id,
+            });
+        }
+        Ok(Self::Set(id))
+    }
+}
+
+impl Command for ModuleCommand {
+    fn name(&self) -> String {
+        match self {
+            Self::Get | Self::Set(_) => "Module".to_string(),
+            Self::List => "Modules".to_string(),
+            Self::Template => "Template".to_string(),
+        }
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Get | Self::List | Self::Template => None,
+            Self::Set(id) => Some(id.to_string()),
+        }
+    }
+}
+
+/// Highest GPIO pin number on ESP8266 devices.
+///
+/// Tasmota exposes GPIO0-GPIO16 and the analog input as GPIO17.
+pub const MAX_GPIO_ESP8266: u8 = 17;
+
+/// Highest GPIO pin number on any supported chip.
+///
+/// The ESP32-S3 has the widest range (GPIO0-GPIO48). Other ESP32 variants
+/// stop earlier; the device rejects pins it does not have.
+pub const MAX_GPIO: u8 = 48;
+
+/// `Gpio` payload that lists every pin, including unused ones.
+const ALL_GPIOS: &str = "255";
+
+/// Command to read the component assigned to GPIO pins.
+///
+/// Useful on DIY boards where a pin drives or reads hardware Tasmota does
+/// not otherwise expose.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, GpioCommand};
+///
+/// let cmd = GpioCommand::read(4).unwrap();
+/// assert_eq!(cmd.name(), "Gpio4");
+/// assert_eq!(cmd.payload(), None);
+///
+/// let all = GpioCommand::ReadAll;
+/// assert_eq!(all.name(), "Gpio");
+/// assert_eq!(all.payload(), Some("255".to_string()));
+///
+/// // No supported chip has GPIO60
+/// assert!(GpioCommand::read(60).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpioCommand {
+    /// Read a single pin.
+    Read(u8),
+    /// Read every pin of the chip.
+    ReadAll,
+}
+
+impl GpioCommand {
+    /// Creates a command to read a single pin.
+    ///
+    /// Pins up to [`MAX_GPIO_ESP8266`] exist on every chip; higher pins
+    /// only exist on ESP32 variants.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `pin` exceeds [`MAX_GPIO`].
+    pub fn read(pin: u8) -> Result<Self, ValueError> {
+        if pin > MAX_GPIO {
+            return Err(ValueError::OutOfRange {
+                min: 0,
+                max: MAX_GPIO.into(),
+                actual: pin.into(),
+            });
+        }
+        Ok(Self::Read(pin))
+    }
+}
+
+impl Command for GpioCommand {
+    fn name(&self) -> String {
+        match self {
+            Self::Read(pin) => format!("Gpio{pin}"),
+            Self::ReadAll => "Gpio".to_string(),
+        }
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Read(_) => None,
+            Self::ReadAll => Some(ALL_GPIOS.to_string()),
+        }
+    }
+}
+
+/// Command to scan the I2C bus for connected devices (`I2CScan`).
+///
+/// Useful to check that a sensor is wired correctly before relying on its
+/// telemetry. The firmware must be built with I2C support and have I2C pins
+/// configured.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, I2cScanCommand};
+///
+/// assert_eq!(I2cScanCommand.to_http_command(), "I2CScan");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct I2cScanCommand;
+
+impl Command for I2cScanCommand {
+    fn name(&self) -> String {
+        "I2CScan".to_string()
+    }
+
+    fn payload(&self) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_get() {
+        assert_eq!(ModuleCommand::Get.to_http_command(), "Module");
+    }
+
+    #[test]
+    fn module_set() {
+        let cmd = ModuleCommand::set(18).unwrap();
+        assert_eq!(cmd.to_http_command(), "Module 18");
+        assert_eq!(
+            ModuleCommand::set(0).unwrap().payload(),
+            Some("0".to_string())
+        );
+    }
+
+    #[test]
+    fn module_set_out_of_range() {
+        assert_eq!(
+            ModuleCommand::set(300),
+            Err(ValueError::OutOfRange {
+                min: 0,
+                max: 255,
+                actual: 300
+            })
+        );
+    }
+
+    #[test]
+    fn modules_list() {
+        assert_eq!(ModuleCommand::List.to_http_command(), "Modules");
+    }
+
+    #[test]
+    fn template_query() {
+        assert_eq!(ModuleCommand::Template.to_http_command(), "Template");
+    }
+
+    #[test]
+    fn gpio_read() {
+        assert_eq!(GpioCommand::read(0).unwrap().to_http_command(), "Gpio0");
+        assert_eq!(GpioCommand::read(48).unwrap().to_http_command(), "Gpio48");
+    }
+
+    #[test]
+    fn gpio_read_out_of_range() {
+        assert_eq!(
+            GpioCommand::read(49),
+            Err(ValueError::OutOfRange {
+                min: 0,
+                max: 48,
+                actual: 49
+            })
+        );
+    }
+
+    #[test]
+    fn gpio_read_all() {
+        assert_eq!(GpioCommand::ReadAll.to_http_command(), "Gpio 255");
+    }
+}
@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! MQTT connection commands.
+//!
+//! This module provides commands for pointing a device at an MQTT broker
+//! (`MqttHost`, `MqttPort`, `MqttUser`) and for enabling MQTT
+//! (`SetOption3`). The broker password is set with
+//! [`SecurityCommand::mqtt_password`](super::SecurityCommand::mqtt_password).
+
+use crate::command::Command;
+use crate::error::ValueError;
+
+use super::security::text_payload;
+use super::time::validate_hostname;
+
+/// Maximum MQTT user name length accepted by Tasmota.
+pub const MAX_MQTT_USER_LENGTH: usize = 32;
+
+/// Command to query or set the device's MQTT broker connection.
+///
+/// Tasmota restarts after any of the settings changes, then connects to the
+/// configured broker.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, MqttConfigCommand};
+///
+/// let cmd = MqttConfigCommand::host("broker.lan").unwrap();
+/// assert_eq!(cmd.to_http_command(), "MqttHost broker.lan");
+///
+/// let cmd = MqttConfigCommand::port(8883).unwrap();
+/// assert_eq!(cmd.to_http_command(), "MqttPort 8883");
+///
+/// // An empty user name is sent as "0", which clears it
+/// let cmd = MqttConfigCommand::user("").unwrap();
+/// assert_eq!(cmd.to_http_command(), "MqttUser 0");
+///
+/// // MQTT is enabled with SetOption3
+/// assert_eq!(MqttConfigCommand::SetEnabled(true).to_http_command(), "SetOption3 1");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MqttConfigCommand {
+    /// Query the broker host.
+    GetHost,
+    /// Set the broker host name or IP address.
+    SetHost(String),
+    /// Query the broker port.
+    GetPort,
+    /// Set the broker port.
+    SetPort(u16),
+    /// Query the MQTT user name.
+    GetUser,
+    /// Set the MQTT user name.
+    SetUser(String),
+    /// Query whether MQTT is enabled.
+    GetEnabled,
+    /// Enable or disable MQTT.
+    SetEnabled(bool),
+}
+
+impl MqttConfigCommand {
+    /// Creates a command to set the broker host.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidHostname`] if `host` is not a valid host
+    /// name or IPv4 address.
+    pub fn host(host: impl Into<String>) -> Result<Self, ValueError> {
+        let host = host.into();
+        validate_hostname(&host)?;
+        Ok(Self::SetHost(host))
+    }
+
+    /// Creates a command to set the broker port.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `port` is 0.
+    pub fn port(port: u16) -> Result<Self, ValueError> {
+        if port == 0 {
+            return Err(ValueError::OutOfRange {
+                min: 1,
+                max: u16::MAX,
+                actual: port,
+            });
+        }
+        Ok(Self::SetPort(port))
+    }
+
+    /// Creates a command to set the MQTT user name.
+    ///
+    /// An empty name clears it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidMqttUser`] if the name is longer than
+    /// [`MAX_MQTT_USER_LENGTH`] characters, has surrounding whitespace, or is
+    /// a single digit (which Tasmota reads as a shortcut).
+    pub fn user(user: impl Into<String>) -> Result<Self, ValueError> {
+        let user = user.into();
+        if user.chars().count() > MAX_MQTT_USER_LENGTH {
+            return Err(ValueError::InvalidMqttUser(format!(
+                "longer than {MAX_MQTT_USER_LENGTH} characters"
+            )));
+        }
+        if user.trim() != user || user.contains(';') {
+            return Err(ValueError::InvalidMqttUser(format!(
+                "{user:?} contains whitespace or ';'"
+            )));
+        }
+        if user.len() == 1 && user.as_bytes()[0].is_ascii_digit() {
+            return Err(ValueError::InvalidMqttUser(
+                "single digits are reserved by Tasmota".to_string(),
+            ));
+        }
+        Ok(Self::SetUser(user))
+    }
+}
+
+impl Command for MqttConfigCommand {
+    fn name(&self) -> String {
+        match self {
+            Self::GetHost | Self::SetHost(_) => "MqttHost",
+            Self::GetPort | Self::SetPort(_) => "MqttPort",
+            Self::GetUser | Self::SetUser(_) => "MqttUser",
+            Self::GetEnabled | Self::SetEnabled(_) => "SetOption3",
+        }
+        .to_string()
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::GetHost | Self::GetPort | Self::GetUser | Self::GetEnabled => None,
+            Self::SetHost(host) => Some(host.clone()),
+            Self::SetPort(port) => Some(port.to_string()),
+            Self::SetUser(user) => Some(text_payload(user)),
+            Self::SetEnabled(enabled) => Some(if *enabled { "1" } else { "0" }.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_have_no_payload() {
+        for cmd in [
+            MqttConfigCommand::GetHost,
+            MqttConfigCommand::GetPort,
+            MqttConfigCommand::GetUser,
+            MqttConfigCommand::GetEnabled,
+        ] {
+            assert_eq!(cmd.payload(), None);
+        }
+        assert_eq!(MqttConfigCommand::GetEnabled.name(), "SetOption3");
+    }
+
+    #[test]
+    fn host_is_validated() {
+        assert!(MqttConfigCommand::host("192.168.1.10").is_ok());
+        assert_eq!(
+            MqttConfigCommand::host("broker;Restart 1"),
+            Err(ValueError::InvalidHostname("broker;Restart 1".to_string()))
+        );
+    }
+
+    #[test]
+    fn port_zero_is_rejected() {
+        assert!(MqttConfigCommand::port(0).is_err());
+        assert_eq!(
+            MqttConfigCommand::port(1883).unwrap(),
+            MqttConfigCommand::SetPort(1883)
+        );
+    }
+
+    #[test]
+    fn user_is_validated() {
+        assert!(MqttConfigCommand::user("tasmota").is_ok());
+        for user in ["1", " tasmota", "a;Restart 1", &"u".repeat(33)] {
+            assert!(
+                matches!(
+                    MqttConfigCommand::user(user),
+                    Err(ValueError::InvalidMqttUser(_))
+                ),
+                "{user:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn disable_payload() {
+        assert_eq!(
+            MqttConfigCommand::SetEnabled(false).payload(),
+            Some("0".to_string())
+        );
+    }
+}
@@ -8,7 +8,7 @@
 //! This module provides commands for controlling device power state,
 //! fade transitions, and power-on behavior.
 
-use crate::command::Command;
+use crate::command::{Command, CommandPriority};
 use crate::types::{PowerIndex, PowerState};
 
 /// Command to control device power state.
@@ -104,6 +104,21 @@ impl Command for PowerCommand {
             Self::Toggle { .. } => Some(PowerState::Toggle.as_str().to_string()),
         }
     }
+
+    fn priority(&self) -> CommandPriority {
+        match self {
+            Self::Get { .. } => CommandPriority::Normal,
+            Self::Set { .. } | Self::Toggle { .. } => CommandPriority::High,
+        }
+    }
+
+    fn is_idempotent(&self) -> bool {
+        match self {
+            Self::Get { .. } => true,
+            Self::Set { state, .. } => !matches!(state, PowerState::Toggle | PowerState::Blink),
+            Self::Toggle { .. } => false,
+        }
+    }
 }
 
 /// Command to enable or disable fade transitions.
@@ -207,6 +222,8 @@ mod tests {
         let cmd = PowerCommand::toggle(PowerIndex::all());
         assert_eq!(cmd.name(), "Power");
         assert_eq!(cmd.payload(), Some("TOGGLE".to_string()));
+        assert!(!cmd.is_idempotent());
+        assert!(PowerCommand::on(PowerIndex::one()).is_idempotent());
     }
 
     #[test]
@@ -0,0 +1,193 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Relay pulse timer commands.
+//!
+//! `PulseTime<x>` switches relay `x` back off a fixed time after it was
+//! turned on. Tasmota encodes the time in one integer with two ranges:
+//!
+//! | Value | Time |
+//! |-------|------|
+//! | `0` | Timer disabled |
+//! | `1`-`111` | Tenths of a second (0.1s-11.1s) |
+//! | `112`-`64900` | Seconds plus 100 (12s-18h) |
+
+use std::time::Duration;
+
+use crate::command::Command;
+use crate::error::ValueError;
+use crate::types::PowerIndex;
+
+/// Longest pulse time Tasmota supports (18 hours).
+pub const MAX_PULSE_TIME: Duration = Duration::from_secs(64_800);
+
+/// Largest value encoded in tenths of a second.
+const MAX_DECISECONDS: u16 = 111;
+
+/// Offset of the values encoded in seconds.
+const SECONDS_OFFSET: u16 = 100;
+
+/// Command to query or set the pulse timer of a relay.
+///
+/// Durations that fall between two steps of the encoding are rounded to
+/// the nearest one: below 11.1s to 100ms, above 12s to whole seconds.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use tasmor_lib::command::{Command, PulseTimeCommand};
+/// use tasmor_lib::types::PowerIndex;
+///
+/// // A bathroom fan that runs for 15 minutes
+/// let cmd = PulseTimeCommand::set(PowerIndex::one(), Duration::from_secs(900)).unwrap();
+/// assert_eq!(cmd.to_http_command(), "PulseTime1 1000");
+///
+/// let cmd = PulseTimeCommand::set(PowerIndex::one(), Duration::from_millis(500)).unwrap();
+/// assert_eq!(cmd.to_http_command(), "PulseTime1 5");
+///
+/// let query = PulseTimeCommand::Get { index: PowerIndex::one() };
+/// assert_eq!(query.to_http_command(), "PulseTime1");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PulseTimeCommand {
+    /// Query the pulse timer of a relay.
+    Get {
+        /// The relay index.
+        index: PowerIndex,
+    },
+    /// Set the pulse timer of a relay; a zero duration disables it.
+    Set {
+        /// The relay index.
+        index: PowerIndex,
+        /// Time the relay stays on. Clamped to [`MAX_PULSE_TIME`].
+        duration: Duration,
+    },
+}
+
+impl PulseTimeCommand {
+    /// Creates a command to set the pulse timer of a relay.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `duration` exceeds
+    /// [`MAX_PULSE_TIME`].
+    pub fn set(index: PowerIndex, duration: Duration) -> Result<Self, ValueError> {
+        if duration > MAX_PULSE_TIME {
+            return Err(ValueError::OutOfRange {
+                min: 0,
+                max: u16::try_from(MAX_PULSE_TIME.as_secs()).unwrap_or(u16::MAX),
+                actual: u16::try_from(duration.as_secs()).unwrap_or(u16::MAX),
+            });
+        }
+        Ok(Self::Set { index, duration })
+    }
+
+    /// Creates a command to disable the pulse timer of a relay.
+    #[must_use]
+    pub const fn disable(index: PowerIndex) -> Self {
+        Self::Set {
+            index,
+            duration: Duration::ZERO,
+        }
+    }
+}
+
+impl Command for PulseTimeCommand {
+    fn name(&self) -> String {
+        match self {
+            Self::Get { index } | Self::Set { index, .. } => {
+                format!("PulseTime{}", index.command_suffix())
+            }
+        }
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Get { .. } => None,
+            Self::Set { duration, .. } => Some(encode_pulse_time(*duration).to_string()),
+        }
+    }
+}
+
+/// Encodes a duration in Tasmota's two-range `PulseTime` format.
+pub(crate) fn encode_pulse_time(duration: Duration) -> u16 {
+    let millis = duration.min(MAX_PULSE_TIME).as_millis();
+    if millis == 0 {
+        return 0;
+    }
+    // Halfway between 11.1s and 12s, the nearest step changes range
+    if millis < 11_550 {
+        let deciseconds = ((millis + 50) / 100).clamp(1, u128::from(MAX_DECISECONDS));
+        u16::try_from(deciseconds).unwrap_or(MAX_DECISECONDS)
+    } else {
+        let seconds = ((millis + 500) / 1000).clamp(12, u128::from(MAX_PULSE_TIME.as_secs()));
+        u16::try_from(seconds).map_or(u16::MAX, |s| s + SECONDS_OFFSET)
+    }
+}
+
+/// Decodes a `PulseTime` value into a duration.
+pub(crate) fn decode_pulse_time(value: u16) -> Duration {
+    if value <= MAX_DECISECONDS {
+        Duration::from_millis(u64::from(value) * 100)
+    } else {
+        Duration::from_secs(u64::from(value - SECONDS_OFFSET))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_both_ranges() {
+        assert_eq!(encode_pulse_time(Duration::ZERO), 0);
+        assert_eq!(encode_pulse_time(Duration::from_millis(10)), 1);
+        assert_eq!(encode_pulse_time(Duration::from_millis(100)), 1);
+        assert_eq!(encode_pulse_time(Duration::from_secs(1)), 10);
+        assert_eq!(encode_pulse_time(Duration::from_millis(11_000)), 110);
+        assert_eq!(encode_pulse_time(Duration::from_millis(11_100)), 111);
+        assert_eq!(encode_pulse_time(Duration::from_secs(12)), 112);
+        assert_eq!(encode_pulse_time(Duration::from_secs(13)), 113);
+        assert_eq!(encode_pulse_time(Duration::from_secs(3600)), 3700);
+        assert_eq!(encode_pulse_time(MAX_PULSE_TIME), 64_900);
+        assert_eq!(encode_pulse_time(Duration::from_secs(100_000)), 64_900);
+    }
+
+    #[test]
+    fn rounds_across_the_range_boundary() {
+        assert_eq!(encode_pulse_time(Duration::from_millis(11_149)), 111);
+        assert_eq!(encode_pulse_time(Duration::from_millis(11_549)), 111);
+        assert_eq!(encode_pulse_time(Duration::from_millis(11_550)), 112);
+        assert_eq!(encode_pulse_time(Duration::from_millis(11_900)), 112);
+        assert_eq!(encode_pulse_time(Duration::from_millis(12_499)), 112);
+        assert_eq!(encode_pulse_time(Duration::from_millis(12_500)), 113);
+    }
+
+    #[test]
+    fn decodes_both_ranges() {
+        assert_eq!(decode_pulse_time(0), Duration::ZERO);
+        assert_eq!(decode_pulse_time(5), Duration::from_millis(500));
+        assert_eq!(decode_pulse_time(111), Duration::from_millis(11_100));
+        assert_eq!(decode_pulse_time(112), Duration::from_secs(12));
+        assert_eq!(decode_pulse_time(64_900), MAX_PULSE_TIME);
+        for value in [1, 57, 111, 112, 500, 64_900] {
+            assert_eq!(encode_pulse_time(decode_pulse_time(value)), value);
+        }
+    }
+
+    #[test]
+    fn set_rejects_too_long_durations() {
+        assert!(PulseTimeCommand::set(PowerIndex::one(), MAX_PULSE_TIME).is_ok());
+        assert!(
+            PulseTimeCommand::set(PowerIndex::one(), MAX_PULSE_TIME + Duration::from_secs(1))
+                .is_err()
+        );
+        assert_eq!(
+            PulseTimeCommand::disable(PowerIndex::new(2).unwrap()).to_http_command(),
+            "PulseTime2 0"
+        );
+    }
+}
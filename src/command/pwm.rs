@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Raw PWM output commands.
+//!
+//! This module provides the `PWM<n>` command, which sets the duty cycle of a
+//! GPIO configured as a PWM output outside of a light (fans, pumps, servos,
+//! analog interfaces). Lights are controlled with the dimmer and color
+//! commands instead.
+
+use crate::command::Command;
+use crate::error::ValueError;
+
+/// Highest PWM output index (ESP32; ESP8266 devices have 5 outputs).
+pub const MAX_PWM_CHANNELS: u8 = 16;
+
+/// Highest PWM duty-cycle value with Tasmota's default `PWMRange`.
+pub const MAX_PWM_VALUE: u16 = 1023;
+
+/// Command to query or set a raw PWM output.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, PwmCommand};
+///
+/// let cmd = PwmCommand::set(1, 512).unwrap();
+/// assert_eq!(cmd.to_http_command(), "PWM1 512");
+///
+/// let query = PwmCommand::get(2).unwrap();
+/// assert_eq!(query.to_http_command(), "PWM2");
+///
+/// assert!(PwmCommand::set(1, 1024).is_err());
+/// assert!(PwmCommand::get(0).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PwmCommand {
+    /// Query the PWM outputs.
+    Get {
+        /// Output index (1-16).
+        channel: u8,
+    },
+    /// Set the duty cycle of a PWM output.
+    Set {
+        /// Output index (1-16).
+        channel: u8,
+        /// Duty cycle (0-1023).
+        value: u16,
+    },
+}
+
+impl PwmCommand {
+    /// Creates a command to query a PWM output.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `channel` is not 1-16.
+    pub fn get(channel: u8) -> Result<Self, ValueError> {
+        validate_channel(channel)?;
+        Ok(Self::Get { channel })
+    }
+
+    /// Creates a command to set the duty cycle of a PWM output.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `channel` is not 1-16 or
+    /// `value` exceeds 1023.
+    pub fn set(channel: u8, value: u16) -> Result<Self, ValueError> {
+        validate_channel(channel)?;
+        if value > MAX_PWM_VALUE {
+            return Err(ValueError::OutOfRange {
+                min: 0,
+                max: MAX_PWM_VALUE,
+                actual: value,
+            });
+        }
+        Ok(Self::Set { channel, value })
+    }
+}
+
+fn validate_channel(channel: u8) -> Result<(), ValueError> {
+    if (1..=MAX_PWM_CHANNELS).contains(&channel) {
+        Ok(())
+    } else {
+        Err(ValueError::OutOfRange {
+            min: 1,
+            max: u16::from(MAX_PWM_CHANNELS),
+            actual: u16::from(channel),
+        })
+    }
+}
+
+impl Command for PwmCommand {
+    fn name(&self) -> String {
+        match self {
+            Self::Get { channel } | Self::Set { channel, .. } => format!("PWM{channel}"),
+        }
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Get { .. } => None,
+            Self::Set { value, .. } => Some(value.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pwm_value_range() {
+        assert!(PwmCommand::set(1, 0).is_ok());
+        assert!(PwmCommand::set(1, 1023).is_ok());
+        assert_eq!(
+            PwmCommand::set(1, 1024),
+            Err(ValueError::OutOfRange {
+                min: 0,
+                max: 1023,
+                actual: 1024
+            })
+        );
+    }
+
+    #[test]
+    fn pwm_channel_range() {
+        assert!(PwmCommand::set(16, 10).is_ok());
+        assert!(PwmCommand::set(17, 10).is_err());
+        assert!(PwmCommand::get(0).is_err());
+    }
+}
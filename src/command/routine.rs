@@ -65,8 +65,9 @@
 use std::time::Duration;
 
 use crate::command::{
-    ColorTemperatureCommand, Command, DimmerCommand, FadeCommand, FadeDurationCommand,
-    HsbColorCommand, PowerCommand, SchemeCommand, StartupFadeCommand, WakeupDurationCommand,
+    ColorTemperatureCommand, Command, CommandPriority, DimmerCommand, FadeCommand,
+    FadeDurationCommand, HsbColorCommand, PowerCommand, SchemeCommand, StartupFadeCommand,
+    WakeupDurationCommand,
 };
 use crate::error::{DeviceError, Error};
 use crate::types::{
@@ -105,6 +106,8 @@ pub const MAX_ROUTINE_STEPS: usize = 30;
 pub struct Routine {
     /// Raw command strings that will be joined with semicolons.
     steps: Vec<String>,
+    /// Highest priority among the commands of the routine.
+    priority: CommandPriority,
 }
 
 impl Routine {
@@ -144,6 +147,15 @@ impl Routine {
     pub(crate) fn to_backlog_command(&self) -> String {
         format!("Backlog0 {}", self.steps.join("; "))
     }
+
+    /// Returns the priority the routine is sent with.
+    ///
+    /// A routine containing a [`CommandPriority::High`] command, such as a
+    /// power change, is sent with high priority.
+    #[must_use]
+    pub(crate) fn priority(&self) -> CommandPriority {
+        self.priority
+    }
 }
 
 /// Builder for constructing routines.
@@ -170,13 +182,14 @@ impl Routine {
 #[derive(Debug, Clone, Default)]
 pub struct RoutineBuilder {
     steps: Vec<String>,
+    priority: CommandPriority,
 }
 
 impl RoutineBuilder {
     /// Creates a new empty builder.
     #[must_use]
     pub fn new() -> Self {
-        Self { steps: Vec::new() }
+        Self::default()
     }
 
     // ========== Power Control ==========
@@ -538,7 +551,10 @@ impl RoutineBuilder {
             ))));
         }
 
-        Ok(Routine { steps: self.steps })
+        Ok(Routine {
+            steps: self.steps,
+            priority: self.priority,
+        })
     }
 
     /// Returns the current number of steps in the builder.
@@ -562,8 +578,11 @@ impl RoutineBuilder {
     // ========== Internal ==========
 
     /// Adds a command to the routine (internal helper).
-    fn add_command<C: Command>(mut self, cmd: &C) -> Self {
+    pub(crate) fn add_command<C: Command>(mut self, cmd: &C) -> Self {
         self.steps.push(cmd.to_http_command());
+        if cmd.priority() == CommandPriority::High {
+            self.priority = CommandPriority::High;
+        }
         self
     }
 }
@@ -572,6 +591,22 @@ impl RoutineBuilder {
 mod tests {
     use super::*;
 
+    #[test]
+    fn routine_priority_follows_its_steps() {
+        let routine = Routine::builder()
+            .set_dimmer(Dimmer::new(50).unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(routine.priority(), CommandPriority::Normal);
+
+        let routine = Routine::builder()
+            .set_dimmer(Dimmer::new(50).unwrap())
+            .power_off(PowerIndex::one())
+            .build()
+            .unwrap();
+        assert_eq!(routine.priority(), CommandPriority::High);
+    }
+
     #[test]
     fn empty_routine_fails() {
         let result = Routine::builder().build();
@@ -0,0 +1,475 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Rule commands and rule text builder.
+//!
+//! Tasmota has three rule sets, `Rule1`-`Rule3`, each holding one or more
+//! rules of the form `ON <trigger>[<op><value>] DO <action> ENDON`. The
+//! device accepts any text and only reports problems when a rule fails to
+//! fire, so [`RuleBuilder`] assembles the text from typed parts and checks it
+//! before it is sent.
+//!
+//! # Examples
+//!
+//! ```
+//! use tasmor_lib::command::{Command, PowerCommand, Routine, RuleBuilder, RuleCommand};
+//! use tasmor_lib::types::{Dimmer, PowerIndex};
+//!
+//! let evening = Routine::builder()
+//!     .set_dimmer(Dimmer::new(30).unwrap())
+//!     .build()
+//!     .unwrap();
+//!
+//! let rule = RuleBuilder::new()
+//!     .on("Power1#State")
+//!     .equals(1)
+//!     .then_backlog(&evening)
+//!     .on("Switch1#State")
+//!     .then(&PowerCommand::Toggle { index: PowerIndex::one() })
+//!     .build()
+//!     .unwrap();
+//!
+//! assert_eq!(
+//!     rule.as_str(),
+//!     "ON Power1#State=1 DO Backlog0 Dimmer 30 ENDON ON Switch1#State DO Power1 TOGGLE ENDON"
+//! );
+//!
+//! let cmd = RuleCommand::set(1, &rule).unwrap();
+//! assert!(cmd.to_http_command().starts_with("Rule1 ON Power1#State=1"));
+//! ```
+
+use std::fmt;
+
+use crate::command::{Command, Routine};
+use crate::error::ValueError;
+
+/// Number of rule sets (`Rule1`-`Rule3`).
+pub const MAX_RULE_SETS: u8 = 3;
+
+/// Longest rule set text Tasmota stores, in bytes.
+pub const MAX_RULE_LENGTH: usize = 511;
+
+/// Validated text of a rule set, built with [`RuleBuilder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    text: String,
+}
+
+impl Rule {
+    /// Creates a new rule builder.
+    #[must_use]
+    pub fn builder() -> RuleBuilder {
+        RuleBuilder::new()
+    }
+
+    /// Returns the rule text as sent to the device.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// One `ON ... DO ... ENDON` block.
+#[derive(Debug, Clone)]
+struct RuleEntry {
+    trigger: String,
+    condition: Option<(&'static str, String)>,
+    action: String,
+}
+
+/// Builder for the text of a rule set.
+///
+/// Each [`on`](Self::on) starts a rule; its optional comparison and the
+/// `then` action complete it. Mistakes such as a trigger with spaces or an
+/// action containing `ENDON` are reported by [`build`](Self::build) instead
+/// of being silently ignored by the device.
+#[derive(Debug, Clone, Default)]
+pub struct RuleBuilder {
+    entries: Vec<RuleEntry>,
+}
+
+impl RuleBuilder {
+    /// Creates a new empty builder.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a rule fired by `trigger`, e.g. `Power1#State`,
+    /// `Switch1#State`, `Tele-SI7021#Temperature`, `System#Boot` or
+    /// `Event#night`.
+    #[must_use]
+    pub fn on(self, trigger: &str) -> RuleTrigger {
+        RuleTrigger {
+            builder: self,
+            trigger: trigger.to_string(),
+            condition: None,
+        }
+    }
+
+    /// Builds the rule set text.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidRule`] if no rule was added, a trigger
+    /// or comparison value is malformed, an action contains control
+    /// characters or rule keywords, or the text exceeds
+    /// [`MAX_RULE_LENGTH`].
+    pub fn build(self) -> Result<Rule, ValueError> {
+        if self.entries.is_empty() {
+            return Err(ValueError::InvalidRule("rule set is empty".to_string()));
+        }
+
+        let mut rules = Vec::with_capacity(self.entries.len());
+        for entry in &self.entries {
+            validate_trigger(&entry.trigger)?;
+            let mut rule = format!("ON {}", entry.trigger);
+            if let Some((op, value)) = &entry.condition {
+                validate_value(value)?;
+                rule.push_str(op);
+                rule.push_str(value);
+            }
+            validate_action(&entry.action)?;
+            rule.push_str(" DO ");
+            rule.push_str(&entry.action);
+            rule.push_str(" ENDON");
+            rules.push(rule);
+        }
+
+        let text = rules.join(" ");
+        if text.len() > MAX_RULE_LENGTH {
+            return Err(ValueError::InvalidRule(format!(
+                "rule set is {} bytes, maximum is {MAX_RULE_LENGTH}",
+                text.len()
+            )));
+        }
+        Ok(Rule { text })
+    }
+
+    /// Returns the number of rules added so far.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no rule has been added.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// A rule being built: its trigger and optional comparison, waiting for an
+/// action.
+#[derive(Debug, Clone)]
+pub struct RuleTrigger {
+    builder: RuleBuilder,
+    trigger: String,
+    condition: Option<(&'static str, String)>,
+}
+
+impl RuleTrigger {
+    /// Fires only when the trigger value equals `value`.
+    #[must_use]
+    pub fn equals(self, value: impl fmt::Display) -> Self {
+        self.compare("=", value)
+    }
+
+    /// Fires only when the trigger value differs from `value`.
+    #[must_use]
+    pub fn not_equals(self, value: impl fmt::Display) -> Self {
+        self.compare("!=", value)
+    }
+
+    /// Fires only when the trigger value is greater than `value`.
+    #[must_use]
+    pub fn greater_than(self, value: impl fmt::Display) -> Self {
+        self.compare(">", value)
+    }
+
+    /// Fires only when the trigger value is less than `value`.
+    #[must_use]
+    pub fn less_than(self, value: impl fmt::Display) -> Self {
+        self.compare("<", value)
+    }
+
+    /// Fires only when the trigger value is at least `value`.
+    #[must_use]
+    pub fn at_least(self, value: impl fmt::Display) -> Self {
+        self.compare(">=", value)
+    }
+
+    /// Fires only when the trigger value is at most `value`.
+    #[must_use]
+    pub fn at_most(self, value: impl fmt::Display) -> Self {
+        self.compare("<=", value)
+    }
+
+    /// Runs `command` when the rule fires.
+    #[must_use]
+    pub fn then<C: Command>(self, command: &C) -> RuleBuilder {
+        self.then_raw(&command.to_http_command())
+    }
+
+    /// Runs a routine as one backlog when the rule fires.
+    #[must_use]
+    pub fn then_backlog(self, routine: &Routine) -> RuleBuilder {
+        self.then_raw(&routine.to_backlog_command())
+    }
+
+    /// Runs an action given as command text, e.g. `Publish stat/alarm ON`.
+    ///
+    /// The text may use rule variables such as `%value%` or `%var1%`.
+    #[must_use]
+    pub fn then_raw(self, action: &str) -> RuleBuilder {
+        let mut builder = self.builder;
+        builder.entries.push(RuleEntry {
+            trigger: self.trigger,
+            condition: self.condition,
+            // Only spaces are trimmed so that newlines are still rejected
+            action: action.trim_matches(' ').to_string(),
+        });
+        builder
+    }
+
+    fn compare(mut self, op: &'static str, value: impl fmt::Display) -> Self {
+        self.condition = Some((op, value.to_string()));
+        self
+    }
+}
+
+/// Checks that a trigger is one `<Name>[#<Field>]` token.
+fn validate_trigger(trigger: &str) -> Result<(), ValueError> {
+    let valid_char = |c: char| c.is_ascii_alphanumeric() || "#_-.|".contains(c);
+    if trigger.is_empty() || !trigger.chars().all(valid_char) {
+        return Err(ValueError::InvalidRule(format!(
+            "trigger {trigger:?} must be a single <Name>#<Field> token"
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that a comparison value is one token without operators.
+fn validate_value(value: &str) -> Result<(), ValueError> {
+    let invalid = |c: char| c.is_whitespace() || c.is_control() || "=<>!;".contains(c);
+    if value.is_empty() || value.chars().any(invalid) {
+        return Err(ValueError::InvalidRule(format!(
+            "comparison value {value:?} must be a single token"
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that an action cannot end its rule early.
+fn validate_action(action: &str) -> Result<(), ValueError> {
+    if action.is_empty() || action.chars().any(char::is_control) {
+        return Err(ValueError::InvalidRule(format!(
+            "action {action:?} is empty or contains control characters"
+        )));
+    }
+    // "ON" is also a power payload; a nested rule needs "DO" and "ENDON".
+    let keyword = action
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .find(|word| {
+            ["DO", "ENDON", "BREAK"]
+                .iter()
+                .any(|k| word.eq_ignore_ascii_case(k))
+        });
+    if let Some(word) = keyword {
+        return Err(ValueError::InvalidRule(format!(
+            "action {action:?} contains the keyword {word:?}"
+        )));
+    }
+    Ok(())
+}
+
+/// Command to read, write, enable or disable a rule set.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, RuleCommand};
+///
+/// assert_eq!(RuleCommand::enable(2).unwrap().to_http_command(), "Rule2 1");
+/// assert_eq!(RuleCommand::get(3).unwrap().to_http_command(), "Rule3");
+/// assert!(RuleCommand::get(4).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleCommand {
+    /// Query a rule set.
+    Get(u8),
+    /// Replace the text of a rule set.
+    Set {
+        /// Rule set number (1-3).
+        set: u8,
+        /// The new rule text.
+        rule: Rule,
+    },
+    /// Enable a rule set.
+    Enable(u8),
+    /// Disable a rule set.
+    Disable(u8),
+}
+
+impl RuleCommand {
+    /// Creates a command to query rule set `set`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `set` is not 1-3.
+    pub fn get(set: u8) -> Result<Self, ValueError> {
+        validate_set(set).map(Self::Get)
+    }
+
+    /// Creates a command to replace the text of rule set `set`.
+    ///
+    /// Tasmota keeps the set's enabled state; see [`enable`](Self::enable).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `set` is not 1-3.
+    pub fn set(set: u8, rule: &Rule) -> Result<Self, ValueError> {
+        validate_set(set).map(|set| Self::Set {
+            set,
+            rule: rule.clone(),
+        })
+    }
+
+    /// Creates a command to enable rule set `set`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `set` is not 1-3.
+    pub fn enable(set: u8) -> Result<Self, ValueError> {
+        validate_set(set).map(Self::Enable)
+    }
+
+    /// Creates a command to disable rule set `set`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `set` is not 1-3.
+    pub fn disable(set: u8) -> Result<Self, ValueError> {
+        validate_set(set).map(Self::Disable)
+    }
+}
+
+fn validate_set(set: u8) -> Result<u8, ValueError> {
+    if (1..=MAX_RULE_SETS).contains(&set) {
+        Ok(set)
+    } else {
+        Err(ValueError::OutOfRange {
+            min: 1,
+            max: u16::from(MAX_RULE_SETS),
+            actual: u16::from(set),
+        })
+    }
+}
+
+impl Command for RuleCommand {
+    fn name(&self) -> String {
+        match self {
+            Self::Get(set) | Self::Set { set, .. } | Self::Enable(set) | Self::Disable(set) => {
+                format!("Rule{set}")
+            }
+        }
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Get(_) => None,
+            Self::Set { rule, .. } => Some(rule.text.clone()),
+            Self::Enable(_) => Some("1".to_string()),
+            Self::Disable(_) => Some("0".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::PowerCommand;
+    use crate::types::PowerIndex;
+
+    #[test]
+    fn builds_conditions_and_actions() {
+        let rule = RuleBuilder::new()
+            .on("Tele-AM2301#Temperature")
+            .greater_than(25.5)
+            .then(&PowerCommand::Set {
+                index: PowerIndex::one(),
+                state: crate::types::PowerState::On,
+            })
+            .on("Event#level")
+            .at_most("%var1%")
+            .then_raw("Publish stat/level %value%")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            rule.as_str(),
+            "ON Tele-AM2301#Temperature>25.5 DO Power1 ON ENDON \
+             ON Event#level<=%var1% DO Publish stat/level %value% ENDON"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_parts() {
+        let cases = [
+            RuleBuilder::new(),
+            RuleBuilder::new().on("Power1 State").then_raw("Power2 ON"),
+            RuleBuilder::new().on("").then_raw("Power2 ON"),
+            RuleBuilder::new()
+                .on("Power1#State")
+                .equals("1 DO")
+                .then_raw("Power2 ON"),
+            RuleBuilder::new().on("Power1#State").then_raw(""),
+            RuleBuilder::new()
+                .on("Power1#State")
+                .then_raw("Power2 ON ENDON ON Power2#State DO Restart 1"),
+            RuleBuilder::new().on("Power1#State").then_raw("Power2 0\n"),
+        ];
+        for builder in cases {
+            assert!(
+                matches!(builder.clone().build(), Err(ValueError::InvalidRule(_))),
+                "{builder:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_overlong_rule_sets() {
+        let action = format!("Publish stat/long {}", "x".repeat(MAX_RULE_LENGTH));
+        let result = RuleBuilder::new()
+            .on("System#Boot")
+            .then_raw(&action)
+            .build();
+        assert!(matches!(result, Err(ValueError::InvalidRule(_))));
+    }
+
+    #[test]
+    fn rule_command_payloads() {
+        let rule = RuleBuilder::new()
+            .on("System#Boot")
+            .then_raw("Power1 OFF")
+            .build()
+            .unwrap();
+        assert_eq!(
+            RuleCommand::set(1, &rule).unwrap().to_http_command(),
+            "Rule1 ON System#Boot DO Power1 OFF ENDON"
+        );
+        assert_eq!(
+            RuleCommand::disable(3).unwrap().to_http_command(),
+            "Rule3 0"
+        );
+        assert!(RuleCommand::set(0, &rule).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Settings persistence commands.
+//!
+//! Tasmota keeps its settings, including the saved power state
+//! (`SetOption0`), in RAM and flushes them to flash periodically. `SaveData`
+//! sets how often that happens. Flash sectors wear out after roughly
+//! 100 000 erase cycles, so a device whose state changes many times a minute
+//! lasts longer with a longer interval, at the cost of losing the most
+//! recent changes on a power cut.
+
+use crate::command::Command;
+use crate::error::ValueError;
+
+/// Longest `SaveData` interval, in seconds.
+pub const MAX_SAVE_DATA_INTERVAL: u16 = 3600;
+
+/// Command to query or set how often settings are saved to flash.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, SaveDataCommand};
+///
+/// let cmd = SaveDataCommand::every(300).unwrap();
+/// assert_eq!(cmd.to_http_command(), "SaveData 300");
+///
+/// assert_eq!(SaveDataCommand::disable().to_http_command(), "SaveData 0");
+/// assert_eq!(SaveDataCommand::Get.to_http_command(), "SaveData");
+///
+/// assert!(SaveDataCommand::every(7200).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveDataCommand {
+    /// Query the current interval.
+    Get,
+    /// Set the interval in seconds; `0` disables periodic saving.
+    Set(u16),
+}
+
+impl SaveDataCommand {
+    /// Creates a command to save settings every `seconds` (1-3600).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `seconds` is 0 or greater than
+    /// [`MAX_SAVE_DATA_INTERVAL`].
+    pub fn every(seconds: u16) -> Result<Self, ValueError> {
+        if !(1..=MAX_SAVE_DATA_INTERVAL).contains(&seconds) {
+            return Err(ValueError::OutOfRange {
+                min: 1,
+                max: MAX_SAVE_DATA_INTERVAL,
+                actual: seconds,
+            });
+        }
+        Ok(Self::Set(seconds))
+    }
+
+    /// Creates a command to stop saving settings periodically.
+    ///
+    /// Settings are then only written on a controlled restart
+    /// (`Restart 1`), so every change since is lost on a power cut.
+    #[must_use]
+    pub const fn disable() -> Self {
+        Self::Set(0)
+    }
+}
+
+impl Command for SaveDataCommand {
+    fn name(&self) -> String {
+        "SaveData".to_string()
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Get => None,
+            Self::Set(seconds) => Some(seconds.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_is_validated() {
+        assert_eq!(SaveDataCommand::every(1), Ok(SaveDataCommand::Set(1)));
+        assert_eq!(
+            SaveDataCommand::every(MAX_SAVE_DATA_INTERVAL),
+            Ok(SaveDataCommand::Set(3600))
+        );
+        assert!(SaveDataCommand::every(0).is_err());
+        assert!(SaveDataCommand::every(3601).is_err());
+    }
+}
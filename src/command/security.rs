@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Security commands.
+//!
+//! This module provides commands for securing a device during provisioning:
+//! the web UI password, the MQTT password and the CORS origin.
+
+use std::fmt;
+
+use crate::command::Command;
+use crate::error::ValueError;
+
+/// Maximum password length accepted by Tasmota.
+pub const MAX_PASSWORD_LENGTH: usize = 32;
+
+/// Command to configure device passwords and CORS.
+///
+/// An empty password or origin clears the setting. Clearing the web password
+/// disables authentication for the web UI and the HTTP API.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, SecurityCommand};
+///
+/// let cmd = SecurityCommand::web_password("s3cret").unwrap();
+/// assert_eq!(cmd.to_http_command(), "WebPassword s3cret");
+///
+/// // An empty password is sent as "0", which clears it
+/// let cmd = SecurityCommand::mqtt_password("").unwrap();
+/// assert_eq!(cmd.to_http_command(), "MqttPassword 0");
+///
+/// // Allow cross-origin requests from a dashboard
+/// let cmd = SecurityCommand::cors("http://dashboard.local");
+/// assert_eq!(cmd.name(), "CORS");
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub enum SecurityCommand {
+    /// Set the web UI password.
+    SetWebPassword(String),
+    /// Set the MQTT broker password.
+    SetMqttPassword(String),
+    /// Query the allowed CORS origin.
+    GetCors,
+    /// Set the allowed CORS origin (`*` allows any origin).
+    SetCors(String),
+}
+
+impl SecurityCommand {
+    /// Creates a command to set the web UI password.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidPassword`] if the password cannot be
+    /// stored by Tasmota (see [`validate_password`]).
+    pub fn web_password(password: impl Into<String>) -> Result<Self, ValueError> {
+        let password = password.into();
+        validate_password(&password)?;
+        Ok(Self::SetWebPassword(password))
+    }
+
+    /// Creates a command to set the MQTT broker password.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidPassword`] if the password cannot be
+    /// stored by Tasmota (see [`validate_password`]).
+    pub fn mqtt_password(password: impl Into<String>) -> Result<Self, ValueError> {
+        let password = password.into();
+        validate_password(&password)?;
+        Ok(Self::SetMqttPassword(password))
+    }
+
+    /// Creates a command to set the allowed CORS origin.
+    ///
+    /// An empty origin disables CORS.
+    #[must_use]
+    pub fn cors(origin: impl Into<String>) -> Self {
+        Self::SetCors(origin.into())
+    }
+}
+
+// Passwords are masked so that commands can be logged safely
+impl fmt::Debug for SecurityCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SetWebPassword(_) => f.debug_tuple("SetWebPassword").field(&"****").finish(),
+            Self::SetMqttPassword(_) => f.debug_tuple("SetMqttPassword").field(&"****").finish(),
+            Self::GetCors => f.write_str("GetCors"),
+            Self::SetCors(origin) => f.debug_tuple("SetCors").field(origin).finish(),
+        }
+    }
+}
+
+/// Checks that Tasmota would store `password` as-is.
+///
+/// Passwords are limited to [`MAX_PASSWORD_LENGTH`] characters. Tasmota
+/// trims surrounding whitespace and reads the single digits `0`-`9` as
+/// shortcuts (clear, reset to default, ...), so such passwords are rejected.
+/// The empty password is allowed and clears the setting.
+///
+/// # Errors
+///
+/// Returns [`ValueError::InvalidPassword`] describing the problem. The
+/// password itself is never included in the error.
+pub fn validate_password(password: &str) -> Result<(), ValueError> {
+    if password.chars().count() > MAX_PASSWORD_LENGTH {
+        return Err(ValueError::InvalidPassword(format!(
+            "longer than {MAX_PASSWORD_LENGTH} characters"
+        )));
+    }
+    if password.trim() != password {
+        return Err(ValueError::InvalidPassword(
+            "leading or trailing whitespace".to_string(),
+        ));
+    }
+    if password.len() == 1 && password.as_bytes()[0].is_ascii_digit() {
+        return Err(ValueError::InvalidPassword(
+            "single digits are reserved by Tasmota".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the payload for a text setting, using `0` to clear it.
+pub(super) fn text_payload(value: &str) -> String {
+    if value.is_empty() {
+        "0".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+impl Command for SecurityCommand {
+    fn name(&self) -> String {
+        match self {
+            Self::SetWebPassword(_) => "WebPassword",
+            Self::SetMqttPassword(_) => "MqttPassword",
+            Self::GetCors | Self::SetCors(_) => "CORS",
+        }
+        .to_string()
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::SetWebPassword(value) | Self::SetMqttPassword(value) | Self::SetCors(value) => {
+                Some(text_payload(value))
+            }
+            Self::GetCors => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn web_password_command() {
+        let cmd = SecurityCommand::web_password("hunter22").unwrap();
+        assert_eq!(cmd.name(), "WebPassword");
+        assert_eq!(cmd.payload(), Some("hunter22".to_string()));
+    }
+
+    #[test]
+    fn empty_values_clear_the_setting() {
+        let cmd = SecurityCommand::web_password("").unwrap();
+        assert_eq!(cmd.to_http_command(), "WebPassword 0");
+
+        let cmd = SecurityCommand::cors("");
+        assert_eq!(cmd.to_http_command(), "CORS 0");
+    }
+
+    #[test]
+    fn cors_commands() {
+        assert_eq!(SecurityCommand::GetCors.to_http_command(), "CORS");
+        assert_eq!(SecurityCommand::cors("*").to_http_command(), "CORS *");
+    }
+
+    #[test]
+    fn password_validation() {
+        assert!(validate_password(&"x".repeat(MAX_PASSWORD_LENGTH)).is_ok());
+        assert!(validate_password(&"x".repeat(MAX_PASSWORD_LENGTH + 1)).is_err());
+        assert!(validate_password(" padded").is_err());
+        assert!(validate_password("1").is_err());
+        assert!(validate_password("12").is_ok());
+        assert!(SecurityCommand::mqtt_password("0").is_err());
+    }
+
+    #[test]
+    fn password_error_does_not_leak_password() {
+        let err = SecurityCommand::web_password("  secret  ").unwrap_err();
+        assert!(!err.to_string().contains("secret"));
+    }
+
+    #[test]
+    fn debug_masks_passwords() {
+        let cmd = SecurityCommand::web_password("hunter22").unwrap();
+        assert_eq!(format!("{cmd:?}"), r#"SetWebPassword("****")"#);
+
+        let cmd = SecurityCommand::mqtt_password("hunter22").unwrap();
+        assert!(!format!("{cmd:?}").contains("hunter22"));
+
+        let cmd = SecurityCommand::cors("*");
+        assert_eq!(format!("{cmd:?}"), r#"SetCors("*")"#);
+    }
+}
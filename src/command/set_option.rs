@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Generic `SetOption` commands.
+//!
+//! Options with a dedicated command, such as
+//! [`StartupFadeCommand`](super::StartupFadeCommand) (`SetOption91`), should
+//! be set with it. Reading many options is cheaper in one query with
+//! [`Device::get_all_set_options`](crate::Device::get_all_set_options).
+
+use std::fmt;
+
+use crate::command::Command;
+
+/// Value of a `SetOption`.
+///
+/// Most options are flags. `SetOption32`-`SetOption49` hold a number
+/// (0-255) instead, such as the boot loop threshold of `SetOption36`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SetOptionValue {
+    /// A flag option, sent as `0` or `1`.
+    Flag(bool),
+    /// A numeric option.
+    Number(u8),
+}
+
+impl From<bool> for SetOptionValue {
+    fn from(enabled: bool) -> Self {
+        Self::Flag(enabled)
+    }
+}
+
+impl From<u8> for SetOptionValue {
+    fn from(value: u8) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl fmt::Display for SetOptionValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Flag(enabled) => write!(f, "{}", u8::from(*enabled)),
+            Self::Number(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// Command to query or set any `SetOption`.
+///
+/// `value: None` queries the option; `Some` sets it.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, SetOptionCommand, SetOptionValue};
+///
+/// let cmd = SetOptionCommand::set(20, true);
+/// assert_eq!(cmd.to_http_command(), "SetOption20 1");
+///
+/// assert_eq!(SetOptionCommand::query(117).to_http_command(), "SetOption117");
+///
+/// // Home Assistant discovery off, boot loop recovery after 4 restarts
+/// let cmd = SetOptionCommand {
+///     number: 19,
+///     value: Some(SetOptionValue::Flag(false)),
+/// };
+/// assert_eq!(cmd.to_http_command(), "SetOption19 0");
+/// let cmd = SetOptionCommand::new(36, Some(SetOptionValue::Number(4)));
+/// assert_eq!(cmd.to_http_command(), "SetOption36 4");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetOptionCommand {
+    /// Option number.
+    pub number: u16,
+    /// The value to set, or `None` to query the option.
+    pub value: Option<SetOptionValue>,
+}
+
+impl SetOptionCommand {
+    /// Creates a command for option `number`: a query if `value` is `None`,
+    /// otherwise a command setting it.
+    #[must_use]
+    pub const fn new(number: u16, value: Option<SetOptionValue>) -> Self {
+        Self { number, value }
+    }
+
+    /// Creates a command querying option `number`.
+    #[must_use]
+    pub const fn query(number: u16) -> Self {
+        Self::new(number, None)
+    }
+
+    /// Creates a command setting option `number` to a flag or number.
+    #[must_use]
+    pub fn set(number: u16, value: impl Into<SetOptionValue>) -> Self {
+        Self::new(number, Some(value.into()))
+    }
+}
+
+impl Command for SetOptionCommand {
+    fn name(&self) -> String {
+        format!("SetOption{}", self.number)
+    }
+
+    fn aliases(&self) -> Vec<String> {
+        vec![format!("SO{}", self.number)]
+    }
+
+    fn payload(&self) -> Option<String> {
+        self.value.map(|value| value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_selects_query_or_set() {
+        let cmd = SetOptionCommand::query(3);
+        assert_eq!(cmd.value, None);
+        assert_eq!(cmd.payload(), None);
+
+        assert_eq!(
+            SetOptionCommand::set(65, true),
+            SetOptionCommand {
+                number: 65,
+                value: Some(SetOptionValue::Flag(true)),
+            }
+        );
+        let cmd = SetOptionCommand::set(32, 40);
+        assert_eq!(cmd.name(), "SetOption32");
+        assert_eq!(cmd.payload(), Some("40".to_string()));
+        assert_eq!(cmd.value, Some(SetOptionValue::Number(40)));
+    }
+}
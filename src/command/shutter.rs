@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Shutter (roller blind, cover) commands.
+//!
+//! In shutter mode (`SetOption80 1`), Tasmota drives a shutter motor with
+//! two relays and tracks its position as a percentage: **0 is closed and
+//! 100 is open**. Shutters are numbered from 1; ESP8266 builds support 4
+//! and ESP32 builds 16.
+
+use crate::command::{Command, CommandPriority};
+use crate::error::ValueError;
+
+/// Highest shutter number Tasmota supports (ESP32 builds).
+pub const MAX_SHUTTERS: u8 = 16;
+
+/// Command to move a shutter.
+///
+/// Positions follow Tasmota's convention: 0 is closed, 100 is open.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, ShutterCommand};
+///
+/// let cmd = ShutterCommand::set_position(1, 50).unwrap();
+/// assert_eq!(cmd.to_http_command(), "ShutterPosition1 50");
+///
+/// assert_eq!(ShutterCommand::open(2).unwrap().to_http_command(), "ShutterOpen2");
+/// assert_eq!(ShutterCommand::stop(1).unwrap().to_http_command(), "ShutterStop1");
+///
+/// assert!(ShutterCommand::set_position(1, 101).is_err());
+/// assert!(ShutterCommand::close(0).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutterCommand {
+    /// Move the shutter to a position.
+    SetPosition {
+        /// Shutter number (1-16).
+        index: u8,
+        /// Target position, 0 (closed) to 100 (open).
+        percent: u8,
+    },
+    /// Open the shutter fully.
+    Open {
+        /// Shutter number (1-16).
+        index: u8,
+    },
+    /// Close the shutter fully.
+    Close {
+        /// Shutter number (1-16).
+        index: u8,
+    },
+    /// Stop the shutter where it is.
+    Stop {
+        /// Shutter number (1-16).
+        index: u8,
+    },
+}
+
+impl ShutterCommand {
+    /// Creates a command to move shutter `index` to `percent`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `index` is not 1-16 or
+    /// `percent` exceeds 100.
+    pub fn set_position(index: u8, percent: u8) -> Result<Self, ValueError> {
+        let index = validate_index(index)?;
+        if percent > 100 {
+            return Err(ValueError::OutOfRange {
+                min: 0,
+                max: 100,
+                actual: u16::from(percent),
+            });
+        }
+        Ok(Self::SetPosition { index, percent })
+    }
+
+    /// Creates a command to open shutter `index` fully.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `index` is not 1-16.
+    pub fn open(index: u8) -> Result<Self, ValueError> {
+        validate_index(index).map(|index| Self::Open { index })
+    }
+
+    /// Creates a command to close shutter `index` fully.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `index` is not 1-16.
+    pub fn close(index: u8) -> Result<Self, ValueError> {
+        validate_index(index).map(|index| Self::Close { index })
+    }
+
+    /// Creates a command to stop shutter `index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `index` is not 1-16.
+    pub fn stop(index: u8) -> Result<Self, ValueError> {
+        validate_index(index).map(|index| Self::Stop { index })
+    }
+
+    /// Returns the shutter number.
+    #[must_use]
+    pub const fn index(&self) -> u8 {
+        match self {
+            Self::SetPosition { index, .. }
+            | Self::Open { index }
+            | Self::Close { index }
+            | Self::Stop { index } => *index,
+        }
+    }
+}
+
+fn validate_index(index: u8) -> Result<u8, ValueError> {
+    if (1..=MAX_SHUTTERS).contains(&index) {
+        Ok(index)
+    } else {
+        Err(ValueError::OutOfRange {
+            min: 1,
+            max: u16::from(MAX_SHUTTERS),
+            actual: u16::from(index),
+        })
+    }
+}
+
+impl Command for ShutterCommand {
+    fn name(&self) -> String {
+        let action = match self {
+            Self::SetPosition { .. } => "Position",
+            Self::Open { .. } => "Open",
+            Self::Close { .. } => "Close",
+            Self::Stop { .. } => "Stop",
+        };
+        format!("Shutter{action}{}", self.index())
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::SetPosition { percent, .. } => Some(percent.to_string()),
+            Self::Open { .. } | Self::Close { .. } | Self::Stop { .. } => None,
+        }
+    }
+
+    fn priority(&self) -> CommandPriority {
+        // Stopping a moving shutter goes before waiting commands
+        match self {
+            Self::Stop { .. } => CommandPriority::High,
+            Self::SetPosition { .. } | Self::Open { .. } | Self::Close { .. } => {
+                CommandPriority::Normal
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_names() {
+        let cmd = ShutterCommand::set_position(3, 0).unwrap();
+        assert_eq!(cmd.name(), "ShutterPosition3");
+        assert_eq!(cmd.payload(), Some("0".to_string()));
+
+        assert_eq!(ShutterCommand::close(1).unwrap().name(), "ShutterClose1");
+        assert_eq!(ShutterCommand::open(16).unwrap().payload(), None);
+    }
+
+    #[test]
+    fn stop_has_high_priority() {
+        assert_eq!(
+            ShutterCommand::stop(1).unwrap().priority(),
+            CommandPriority::High
+        );
+        assert_eq!(
+            ShutterCommand::open(1).unwrap().priority(),
+            CommandPriority::Normal
+        );
+    }
+
+    #[test]
+    fn validation() {
+        assert!(ShutterCommand::set_position(1, 100).is_ok());
+        assert!(ShutterCommand::set_position(17, 50).is_err());
+        assert!(ShutterCommand::stop(0).is_err());
+    }
+}
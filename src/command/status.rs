@@ -43,6 +43,8 @@ pub enum StatusType {
     Sensors = 10,
     /// Runtime state (POWER, Dimmer, CT, `HSBColor`, ENERGY).
     State = 11,
+    /// Stack dump of the last crash (`StatusSTK`).
+    StackDump = 12,
     /// Shutter configuration.
     Shutter = 13,
 }
@@ -64,6 +66,7 @@ impl StatusType {
             Self::PowerThresholds,
             Self::Sensors,
             Self::State,
+            Self::StackDump,
             Self::Shutter,
         ]
     }
@@ -173,6 +176,15 @@ impl StatusCommand {
         Self::new(StatusType::State)
     }
 
+    /// Query the stack dump of the last crash.
+    ///
+    /// The query is read-only. Tasmota has no command that only clears the
+    /// dump; it is discarded when the device restarts normally.
+    #[must_use]
+    pub const fn stack_dump() -> Self {
+        Self::new(StatusType::StackDump)
+    }
+
     /// Returns the status type being queried.
     #[must_use]
     pub const fn status_type(&self) -> StatusType {
@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Time synchronization commands.
+//!
+//! This module provides commands for reading and setting the NTP servers a
+//! device synchronizes its clock with (`NtpServer1`-`NtpServer3`).
+
+use crate::command::Command;
+use crate::error::ValueError;
+
+/// Number of NTP servers a device can be configured with.
+pub const MAX_NTP_SERVERS: u8 = 3;
+
+/// Maximum length of a host name.
+const MAX_HOSTNAME_LENGTH: usize = 253;
+
+/// Maximum length of a single host name label.
+const MAX_LABEL_LENGTH: usize = 63;
+
+/// Command to query or set one of the device's NTP servers.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, NtpServerCommand};
+///
+/// let cmd = NtpServerCommand::set(1, "ntp.internal.example").unwrap();
+/// assert_eq!(cmd.to_http_command(), "NtpServer1 ntp.internal.example");
+///
+/// let query = NtpServerCommand::get(2).unwrap();
+/// assert_eq!(query.to_http_command(), "NtpServer2");
+///
+/// // Only three servers, and host names must be valid
+/// assert!(NtpServerCommand::get(4).is_err());
+/// assert!(NtpServerCommand::set(1, "not a host").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NtpServerCommand {
+    /// Query a server (index 1-3).
+    Get(u8),
+    /// Set a server (index 1-3) to a host name or IP address.
+    Set(u8, String),
+}
+
+impl NtpServerCommand {
+    /// Creates a command to query a server.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `index` is not 1-3.
+    pub fn get(index: u8) -> Result<Self, ValueError> {
+        validate_index(index)?;
+        Ok(Self::Get(index))
+    }
+
+    /// Creates a command to set a server.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `index` is not 1-3, or
+    /// [`ValueError::InvalidHostname`] if `host` is not a valid host name
+    /// or IPv4 address.
+    pub fn set(index: u8, host: impl Into<String>) -> Result<Self, ValueError> {
+        validate_index(index)?;
+        let host = host.into();
+        validate_hostname(&host)?;
+        Ok(Self::Set(index, host))
+    }
+}
+
+impl Command for NtpServerCommand {
+    fn name(&self) -> String {
+        let (Self::Get(index) | Self::Set(index, _)) = self;
+        format!("NtpServer{index}")
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Get(_) => None,
+            Self::Set(_, host) => Some(host.clone()),
+        }
+    }
+}
+
+/// Checks that a server index is 1-3.
+fn validate_index(index: u8) -> Result<(), ValueError> {
+    if index == 0 || index > MAX_NTP_SERVERS {
+        return Err(ValueError::OutOfRange {
+            min: 1,
+            max: MAX_NTP_SERVERS.into(),
+            actual: index.into(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `host` is a valid host name (which includes IPv4 addresses).
+///
+/// Each dot-separated label must be 1-63 ASCII letters, digits or hyphens,
+/// not starting or ending with a hyphen.
+pub(super) fn validate_hostname(host: &str) -> Result<(), ValueError> {
+    let valid_label = |label: &str| {
+        !label.is_empty()
+            && label.len() <= MAX_LABEL_LENGTH
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+    if host.len() > MAX_HOSTNAME_LENGTH || !host.split('.').all(valid_label) {
+        return Err(ValueError::InvalidHostname(host.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ntp_server_index_range() {
+        assert!(NtpServerCommand::get(0).is_err());
+        assert!(NtpServerCommand::get(3).is_ok());
+        assert_eq!(
+            NtpServerCommand::set(4, "pool.ntp.org"),
+            Err(ValueError::OutOfRange {
+                min: 1,
+                max: 3,
+                actual: 4
+            })
+        );
+    }
+
+    #[test]
+    fn ntp_server_accepts_hosts_and_addresses() {
+        let cmd = NtpServerCommand::set(3, "192.168.1.1").unwrap();
+        assert_eq!(cmd.to_http_command(), "NtpServer3 192.168.1.1");
+        assert!(NtpServerCommand::set(1, "time-1.example.com").is_ok());
+    }
+
+    #[test]
+    fn ntp_server_rejects_invalid_hosts() {
+        for host in [
+            "",
+            "pool..ntp.org",
+            "-pool.ntp.org",
+            "pool;Restart 1",
+            "ntp_1.lan",
+        ] {
+            assert_eq!(
+                NtpServerCommand::set(1, host),
+                Err(ValueError::InvalidHostname(host.to_string()))
+            );
+        }
+    }
+}
@@ -0,0 +1,431 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Scheduled timer commands.
+//!
+//! Tasmota has 16 timers, `Timer1`-`Timer16`, each switching a relay at a
+//! time of day on selected weekdays. A timer is written and reported as a
+//! JSON object:
+//!
+//! ```json
+//! {"Enable":1,"Mode":0,"Time":"07:00","Window":0,"Days":"0111110","Repeat":1,"Output":1,"Action":1}
+//! ```
+//!
+//! `Days` lists Sunday to Saturday, `1` for the days the timer fires on.
+//! Timers only fire while the global `Timers` switch is on and the device
+//! knows the time (NTP).
+
+use chrono::{NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::command::Command;
+use crate::error::ValueError;
+use crate::types::PowerIndex;
+
+/// Number of timers (`Timer1`-`Timer16`).
+pub const MAX_TIMERS: u8 = 16;
+
+/// Largest random window around the timer time, in minutes.
+pub const MAX_TIMER_WINDOW: u8 = 15;
+
+/// What a timer does to its output when it fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimerAction {
+    /// Switch the output off.
+    Off,
+    /// Switch the output on.
+    On,
+    /// Toggle the output.
+    Toggle,
+    /// Leave the output alone and fire the rule trigger `Clock#Timer=<x>`.
+    Rule,
+}
+
+impl TimerAction {
+    /// Returns the numeric value Tasmota uses for this action.
+    #[must_use]
+    pub const fn as_u8(self) -> u8 {
+        match self {
+            Self::Off => 0,
+            Self::On => 1,
+            Self::Toggle => 2,
+            Self::Rule => 3,
+        }
+    }
+}
+
+impl TryFrom<u8> for TimerAction {
+    type Error = ValueError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Off),
+            1 => Ok(Self::On),
+            2 => Ok(Self::Toggle),
+            3 => Ok(Self::Rule),
+            _ => Err(ValueError::OutOfRange {
+                min: 0,
+                max: 3,
+                actual: u16::from(value),
+            }),
+        }
+    }
+}
+
+/// A timer fired at a fixed time of day.
+///
+/// Seconds of `time` are ignored, as Tasmota schedules by the minute.
+/// Timers relative to sunrise or sunset are not represented.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::NaiveTime;
+/// use tasmor_lib::command::{Timer, TimerAction};
+/// use tasmor_lib::types::PowerIndex;
+///
+/// let wake_up = Timer {
+///     days: Timer::WEEKDAYS,
+///     ..Timer::new(
+///         NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+///         PowerIndex::one(),
+///         TimerAction::On,
+///     )
+/// };
+///
+/// assert_eq!(
+///     serde_json::to_string(&wake_up).unwrap(),
+///     r#"{"Enable":1,"Mode":0,"Time":"07:00","Window":0,"Days":"0111110","Repeat":1,"Output":1,"Action":1}"#
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "RawTimer", try_from = "RawTimer")]
+pub struct Timer {
+    /// Whether the timer is armed.
+    pub enable: bool,
+    /// Time of day the timer fires at.
+    pub time: NaiveTime,
+    /// Random shift of up to this many minutes around `time` (0-15).
+    pub window: u8,
+    /// Days the timer fires on, Sunday first.
+    pub days: [bool; 7],
+    /// Whether the timer fires every week, or once and then disarms.
+    pub repeat: bool,
+    /// The relay the timer switches (1-8).
+    pub output: PowerIndex,
+    /// What the timer does to the relay.
+    pub action: TimerAction,
+}
+
+impl Timer {
+    /// Every day of the week.
+    pub const EVERY_DAY: [bool; 7] = [true; 7];
+
+    /// Monday to Friday.
+    pub const WEEKDAYS: [bool; 7] = [false, true, true, true, true, true, false];
+
+    /// Saturday and Sunday.
+    pub const WEEKEND: [bool; 7] = [true, false, false, false, false, false, true];
+
+    /// Creates an armed timer repeating every day, without random window.
+    #[must_use]
+    pub const fn new(time: NaiveTime, output: PowerIndex, action: TimerAction) -> Self {
+        Self {
+            enable: true,
+            time,
+            window: 0,
+            days: Self::EVERY_DAY,
+            repeat: true,
+            output,
+            action,
+        }
+    }
+
+    fn validate(&self) -> Result<(), ValueError> {
+        if self.window > MAX_TIMER_WINDOW {
+            return Err(ValueError::OutOfRange {
+                min: 0,
+                max: u16::from(MAX_TIMER_WINDOW),
+                actual: u16::from(self.window),
+            });
+        }
+        if self.output.value() == 0 {
+            return Err(ValueError::OutOfRange {
+                min: 1,
+                max: u16::from(PowerIndex::MAX),
+                actual: 0,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A timer as Tasmota writes it.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawTimer {
+    enable: u8,
+    #[serde(default)]
+    mode: u8,
+    time: String,
+    #[serde(default)]
+    window: u8,
+    days: String,
+    #[serde(default)]
+    repeat: u8,
+    output: u8,
+    action: u8,
+}
+
+impl From<Timer> for RawTimer {
+    fn from(timer: Timer) -> Self {
+        Self {
+            enable: u8::from(timer.enable),
+            mode: 0,
+            time: format!("{:02}:{:02}", timer.time.hour(), timer.time.minute()),
+            window: timer.window,
+            days: timer
+                .days
+                .iter()
+                .map(|&day| if day { '1' } else { '0' })
+                .collect(),
+            repeat: u8::from(timer.repeat),
+            output: timer.output.value(),
+            action: timer.action.as_u8(),
+        }
+    }
+}
+
+impl TryFrom<RawTimer> for Timer {
+    type Error = String;
+
+    fn try_from(raw: RawTimer) -> Result<Self, Self::Error> {
+        if raw.mode != 0 {
+            return Err(format!(
+                "timer mode {} (sunrise/sunset) is not supported",
+                raw.mode
+            ));
+        }
+        let time = NaiveTime::parse_from_str(&raw.time, "%H:%M")
+            .map_err(|e| format!("invalid timer time {:?}: {e}", raw.time))?;
+
+        // Tasmota writes '0'/'1' but accepts '-' for unset days on input
+        let mut days = [false; 7];
+        let mut chars = raw.days.chars();
+        for day in &mut days {
+            let c = chars
+                .next()
+                .ok_or_else(|| format!("invalid timer days {:?}", raw.days))?;
+            *day = c != '0' && c != '-';
+        }
+
+        let output = PowerIndex::new(raw.output).map_err(|e| e.to_string())?;
+        let action = TimerAction::try_from(raw.action).map_err(|e| e.to_string())?;
+        Ok(Self {
+            enable: raw.enable != 0,
+            time,
+            window: raw.window,
+            days,
+            repeat: raw.repeat != 0,
+            output,
+            action,
+        })
+    }
+}
+
+/// Command to query or set a timer.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::NaiveTime;
+/// use tasmor_lib::command::{Command, Timer, TimerAction, TimerCommand};
+/// use tasmor_lib::types::PowerIndex;
+///
+/// let lights_out = Timer::new(
+///     NaiveTime::from_hms_opt(23, 30, 0).unwrap(),
+///     PowerIndex::one(),
+///     TimerAction::Off,
+/// );
+/// let cmd = TimerCommand::set(2, lights_out).unwrap();
+/// assert_eq!(cmd.name(), "Timer2");
+/// assert!(cmd.payload().unwrap().contains(r#""Time":"23:30""#));
+///
+/// assert_eq!(TimerCommand::get(2).unwrap().to_http_command(), "Timer2");
+/// assert!(TimerCommand::get(17).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerCommand {
+    /// Query a timer.
+    Get(u8),
+    /// Replace a timer.
+    Set {
+        /// Timer number (1-16).
+        index: u8,
+        /// The new timer.
+        timer: Timer,
+    },
+}
+
+impl TimerCommand {
+    /// Creates a command to query timer `index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `index` is not 1-16.
+    pub fn get(index: u8) -> Result<Self, ValueError> {
+        validate_index(index).map(Self::Get)
+    }
+
+    /// Creates a command to replace timer `index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `index` is not 1-16, the
+    /// window exceeds [`MAX_TIMER_WINDOW`] or the output is
+    /// [`PowerIndex::all`].
+    pub fn set(index: u8, timer: Timer) -> Result<Self, ValueError> {
+        let index = validate_index(index)?;
+        timer.validate()?;
+        Ok(Self::Set { index, timer })
+    }
+}
+
+fn validate_index(index: u8) -> Result<u8, ValueError> {
+    if (1..=MAX_TIMERS).contains(&index) {
+        Ok(index)
+    } else {
+        Err(ValueError::OutOfRange {
+            min: 1,
+            max: u16::from(MAX_TIMERS),
+            actual: u16::from(index),
+        })
+    }
+}
+
+impl Command for TimerCommand {
+    fn name(&self) -> String {
+        match self {
+            Self::Get(index) | Self::Set { index, .. } => format!("Timer{index}"),
+        }
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Get(_) => None,
+            Self::Set { timer, .. } => serde_json::to_string(timer).ok(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn serializes_tasmota_json() {
+        let timer = Timer {
+            window: 5,
+            days: Timer::WEEKEND,
+            repeat: false,
+            ..Timer::new(
+                time(21, 5),
+                PowerIndex::new(2).unwrap(),
+                TimerAction::Toggle,
+            )
+        };
+
+        assert_eq!(
+            serde_json::to_string(&timer).unwrap(),
+            r#"{"Enable":1,"Mode":0,"Time":"21:05","Window":5,"Days":"1000001","Repeat":0,"Output":2,"Action":2}"#
+        );
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let timers = [
+            Timer::new(time(0, 0), PowerIndex::one(), TimerAction::Off),
+            Timer {
+                enable: false,
+                window: MAX_TIMER_WINDOW,
+                days: Timer::WEEKDAYS,
+                ..Timer::new(time(23, 59), PowerIndex::new(8).unwrap(), TimerAction::Rule)
+            },
+        ];
+
+        for timer in timers {
+            let json = serde_json::to_string(&timer).unwrap();
+            assert_eq!(serde_json::from_str::<Timer>(&json).unwrap(), timer);
+        }
+    }
+
+    #[test]
+    fn parses_device_json() {
+        let json = r#"{"Enable":1,"Mode":0,"Time":"06:45","Window":0,"Days":"-MTWTF-","Repeat":1,"Output":1,"Action":1}"#;
+        let timer: Timer = serde_json::from_str(json).unwrap();
+
+        assert_eq!(timer.time, time(6, 45));
+        assert_eq!(timer.days, Timer::WEEKDAYS);
+        assert_eq!(timer.action, TimerAction::On);
+    }
+
+    #[test]
+    fn rejects_unsupported_timers() {
+        let sunrise = r#"{"Enable":1,"Mode":1,"Time":"00:30","Window":0,"Days":"1111111","Repeat":1,"Output":1,"Action":1}"#;
+        assert!(serde_json::from_str::<Timer>(sunrise).is_err());
+
+        let short_days = r#"{"Enable":1,"Mode":0,"Time":"07:00","Window":0,"Days":"11","Repeat":1,"Output":1,"Action":1}"#;
+        assert!(serde_json::from_str::<Timer>(short_days).is_err());
+    }
+
+    #[test]
+    fn seconds_are_dropped() {
+        let timer = Timer::new(
+            NaiveTime::from_hms_opt(7, 30, 59).unwrap(),
+            PowerIndex::one(),
+            TimerAction::On,
+        );
+        let json = serde_json::to_string(&timer).unwrap();
+        assert!(json.contains(r#""Time":"07:30""#));
+    }
+
+    #[test]
+    fn command_validation() {
+        let timer = Timer::new(time(7, 0), PowerIndex::one(), TimerAction::On);
+
+        assert!(TimerCommand::set(0, timer).is_err());
+        assert!(TimerCommand::set(MAX_TIMERS, timer).is_ok());
+        assert!(TimerCommand::get(MAX_TIMERS + 1).is_err());
+
+        let wide = Timer {
+            window: MAX_TIMER_WINDOW + 1,
+            ..timer
+        };
+        assert!(TimerCommand::set(1, wide).is_err());
+
+        let all = Timer {
+            output: PowerIndex::all(),
+            ..timer
+        };
+        assert!(TimerCommand::set(1, all).is_err());
+    }
+
+    #[test]
+    fn command_format() {
+        let timer = Timer::new(time(7, 0), PowerIndex::one(), TimerAction::On);
+        let cmd = TimerCommand::set(3, timer).unwrap();
+
+        assert_eq!(cmd.name(), "Timer3");
+        assert_eq!(
+            cmd.payload().unwrap(),
+            r#"{"Enable":1,"Mode":0,"Time":"07:00","Window":0,"Days":"1111111","Repeat":1,"Output":1,"Action":1}"#
+        );
+        assert_eq!(TimerCommand::Get(3).payload(), None);
+    }
+}
@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! MQTT topic layout commands.
+//!
+//! This module provides commands for reading the device's `FullTopic`
+//! template and its `Prefix1`-`Prefix3` values, from which a
+//! [`TopicScheme`](crate::types::TopicScheme) is built, and its
+//! `GroupTopic`, and for configuring the `ButtonTopic` and `SwitchTopic`
+//! its inputs publish to.
+
+use crate::command::Command;
+use crate::types::{RemoteTopic, TopicPrefix};
+
+/// Command to query the MQTT topic layout.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, TopicCommand};
+/// use tasmor_lib::types::TopicPrefix;
+///
+/// assert_eq!(TopicCommand::FullTopic.to_http_command(), "FullTopic");
+///
+/// let cmd = TopicCommand::Prefix(TopicPrefix::Tele);
+/// assert_eq!(cmd.to_http_command(), "Prefix3");
+///
+/// assert_eq!(TopicCommand::GroupTopic.to_http_command(), "GroupTopic");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopicCommand {
+    /// Query the `FullTopic` template.
+    FullTopic,
+    /// Query one of the topic prefixes.
+    Prefix(TopicPrefix),
+    /// Query the first group topic (`GroupTopic1`, default `tasmotas`).
+    GroupTopic,
+}
+
+impl Command for TopicCommand {
+    fn name(&self) -> String {
+        match self {
+            Self::FullTopic => "FullTopic".to_string(),
+            Self::Prefix(prefix) => format!("Prefix{}", prefix.index()),
+            Self::GroupTopic => "GroupTopic".to_string(),
+        }
+    }
+
+    fn payload(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Local inputs whose presses can be published to a remote topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RemoteInput {
+    /// Push buttons (`ButtonTopic`).
+    Button,
+    /// Wall switches (`SwitchTopic`).
+    Switch,
+}
+
+/// Command to read or set the topic buttons or switches publish to.
+///
+/// See [`RemoteTopic`] for how a remote topic decouples inputs from relays.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, RemoteInput, RemoteTopicCommand};
+/// use tasmor_lib::types::RemoteTopic;
+///
+/// let query = RemoteTopicCommand::Get(RemoteInput::Switch);
+/// assert_eq!(query.to_http_command(), "SwitchTopic");
+///
+/// let cmd = RemoteTopicCommand::Set(RemoteInput::Button, RemoteTopic::new("hall_lights").unwrap());
+/// assert_eq!(cmd.to_http_command(), "ButtonTopic hall_lights");
+///
+/// let off = RemoteTopicCommand::Set(RemoteInput::Button, RemoteTopic::Disabled);
+/// assert_eq!(off.payload(), Some("0".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteTopicCommand {
+    /// Query the topic of an input type.
+    Get(RemoteInput),
+    /// Set the topic of an input type.
+    Set(RemoteInput, RemoteTopic),
+}
+
+impl Command for RemoteTopicCommand {
+    fn name(&self) -> String {
+        let (Self::Get(input) | Self::Set(input, _)) = self;
+        match input {
+            RemoteInput::Button => "ButtonTopic".to_string(),
+            RemoteInput::Switch => "SwitchTopic".to_string(),
+        }
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Get(_) => None,
+            Self::Set(_, topic) => Some(topic.payload().to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_commands() {
+        let names: Vec<String> = TopicPrefix::ALL
+            .into_iter()
+            .map(|p| TopicCommand::Prefix(p).name())
+            .collect();
+        assert_eq!(names, ["Prefix1", "Prefix2", "Prefix3"]);
+        assert_eq!(TopicCommand::FullTopic.payload(), None);
+    }
+
+    #[test]
+    fn remote_topic_commands() {
+        assert_eq!(
+            RemoteTopicCommand::Get(RemoteInput::Button).to_http_command(),
+            "ButtonTopic"
+        );
+        assert_eq!(
+            RemoteTopicCommand::Set(RemoteInput::Switch, RemoteTopic::DeviceTopic)
+                .to_http_command(),
+            "SwitchTopic 1"
+        );
+    }
+}
@@ -0,0 +1,246 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tuya MCU commands.
+//!
+//! Devices built around a Tuya MCU (many dimmers, fans and switches) expose
+//! their functions as numbered data points (DPs) on a serial link. Tasmota
+//! forwards values to the MCU with the `TuyaSend<type>` commands, and maps
+//! data points to its own functions (relays, dimmer, sensors) with `TuyaMCU`.
+
+use crate::command::Command;
+use crate::error::ValueError;
+use crate::types::{TuyaDpType, TuyaValue};
+
+/// Command to send a value to a Tuya MCU data point, or to ask the MCU to
+/// report all data points.
+///
+/// The command name encodes the value type: `TuyaSend1` (boolean),
+/// `TuyaSend2` (integer), `TuyaSend3` (string), `TuyaSend4` (enum) and
+/// `TuyaSend5` (raw hex). `TuyaSend0` queries all data points; the MCU
+/// answers with `TuyaReceived` telemetry.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, TuyaCommand};
+/// use tasmor_lib::types::TuyaValue;
+///
+/// // Turn on the switch on DP 1
+/// let cmd = TuyaCommand::send(1, TuyaValue::Bool(true)).unwrap();
+/// assert_eq!(cmd.to_http_command(), "TuyaSend1 1,1");
+///
+/// // Set the brightness on DP 2
+/// let cmd = TuyaCommand::send(2, TuyaValue::Int(500)).unwrap();
+/// assert_eq!(cmd.to_http_command(), "TuyaSend2 2,500");
+///
+/// // DP 0 does not exist
+/// assert!(TuyaCommand::send(0, TuyaValue::Enum(1)).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TuyaCommand {
+    /// Ask the MCU to report all data points.
+    Query,
+    /// Send a value to a data point.
+    Send {
+        /// Data point ID (1-255).
+        dp_id: u8,
+        /// Value, encoded according to its type.
+        value: TuyaValue,
+    },
+}
+
+impl TuyaCommand {
+    /// Creates a command to send a value to a data point.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `dp_id` is 0.
+    pub fn send(dp_id: u8, value: TuyaValue) -> Result<Self, ValueError> {
+        if dp_id == 0 {
+            return Err(ValueError::OutOfRange {
+                min: 1,
+                max: u16::from(u8::MAX),
+                actual: 0,
+            });
+        }
+        Ok(Self::Send { dp_id, value })
+    }
+}
+
+/// Returns the `TuyaSend` variant for a value type.
+fn send_type(dp_type: TuyaDpType) -> u8 {
+    match dp_type {
+        TuyaDpType::Bool => 1,
+        TuyaDpType::Value => 2,
+        TuyaDpType::String => 3,
+        TuyaDpType::Enum => 4,
+        TuyaDpType::Raw | TuyaDpType::Bitmap => 5,
+    }
+}
+
+impl Command for TuyaCommand {
+    fn name(&self) -> String {
+        match self {
+            Self::Query => "TuyaSend0".to_string(),
+            Self::Send { value, .. } => format!("TuyaSend{}", send_type(value.dp_type())),
+        }
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Query => None,
+            Self::Send { dp_id, value } => Some(format!("{dp_id},{}", value.to_payload())),
+        }
+    }
+}
+
+/// Highest Tasmota function ID accepted by `TuyaMCU`.
+pub const MAX_TUYA_FUNCTION: u8 = 99;
+
+/// Command to read or change the mapping of Tuya MCU data points to Tasmota
+/// functions.
+///
+/// Each mapping ties a function ID (e.g. 11 for relay 1, 21 for the dimmer,
+/// 71 for temperature) to the data point carrying its value. Tasmota
+/// answers with the complete mapping list, parsed by
+/// [`TuyaMcuResponse`](crate::response::TuyaMcuResponse).
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, TuyaMcuCommand};
+///
+/// // Dimmer (function 21) is carried by DP 2
+/// let cmd = TuyaMcuCommand::map(21, 2).unwrap();
+/// assert_eq!(cmd.to_http_command(), "TuyaMCU 21,2");
+///
+/// // Remove the mapping of function 21
+/// let cmd = TuyaMcuCommand::unmap(21).unwrap();
+/// assert_eq!(cmd.to_http_command(), "TuyaMCU 21,0");
+///
+/// assert_eq!(TuyaMcuCommand::Get.to_http_command(), "TuyaMCU");
+/// assert!(TuyaMcuCommand::map(100, 2).is_err());
+/// assert!(TuyaMcuCommand::map(21, 0).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuyaMcuCommand {
+    /// Query all mappings.
+    Get,
+    /// Map a function to a data point, or remove its mapping (`dp_id` 0).
+    Set {
+        /// Tasmota function ID (1-99).
+        function: u8,
+        /// Data point ID (1-255), or 0 to remove the mapping.
+        dp_id: u8,
+    },
+}
+
+impl TuyaMcuCommand {
+    /// Creates a command mapping `function` to data point `dp_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `function` is not 1-99 or
+    /// `dp_id` is 0 (use [`unmap`](Self::unmap) to remove a mapping).
+    pub fn map(function: u8, dp_id: u8) -> Result<Self, ValueError> {
+        validate_function(function)?;
+        if dp_id == 0 {
+            return Err(ValueError::OutOfRange {
+                min: 1,
+                max: u16::from(u8::MAX),
+                actual: 0,
+            });
+        }
+        Ok(Self::Set { function, dp_id })
+    }
+
+    /// Creates a command removing the mapping of `function`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `function` is not 1-99.
+    pub fn unmap(function: u8) -> Result<Self, ValueError> {
+        validate_function(function)?;
+        Ok(Self::Set { function, dp_id: 0 })
+    }
+}
+
+fn validate_function(function: u8) -> Result<(), ValueError> {
+    if (1..=MAX_TUYA_FUNCTION).contains(&function) {
+        Ok(())
+    } else {
+        Err(ValueError::OutOfRange {
+            min: 1,
+            max: u16::from(MAX_TUYA_FUNCTION),
+            actual: u16::from(function),
+        })
+    }
+}
+
+impl Command for TuyaMcuCommand {
+    fn name(&self) -> String {
+        "TuyaMCU".to_string()
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Get => None,
+            Self::Set { function, dp_id } => Some(format!("{function},{dp_id}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_all_data_points() {
+        assert_eq!(TuyaCommand::Query.to_http_command(), "TuyaSend0");
+    }
+
+    #[test]
+    fn send_encodes_value_type() {
+        let string = TuyaCommand::send(3, TuyaValue::String("eco".to_string())).unwrap();
+        assert_eq!(string.to_http_command(), "TuyaSend3 3,eco");
+
+        let enumeration = TuyaCommand::send(4, TuyaValue::Enum(2)).unwrap();
+        assert_eq!(enumeration.to_http_command(), "TuyaSend4 4,2");
+
+        let raw = TuyaCommand::send(101, TuyaValue::Raw(vec![0x01, 0xAB])).unwrap();
+        assert_eq!(raw.name(), "TuyaSend5");
+        assert_eq!(raw.mqtt_payload(), "101,01AB");
+    }
+
+    #[test]
+    fn tuya_mcu_mapping() {
+        let cmd = TuyaMcuCommand::map(11, 1).unwrap();
+        assert_eq!(cmd.mqtt_topic_suffix(), "TuyaMCU");
+        assert_eq!(cmd.mqtt_payload(), "11,1");
+
+        assert!(TuyaMcuCommand::map(99, 255).is_ok());
+        assert_eq!(
+            TuyaMcuCommand::unmap(0).unwrap_err(),
+            ValueError::OutOfRange {
+                min: 1,
+                max: 99,
+                actual: 0
+            }
+        );
+    }
+
+    #[test]
+    fn dp_id_zero_is_rejected() {
+        assert_eq!(
+            TuyaCommand::send(0, TuyaValue::Bool(false)).unwrap_err(),
+            ValueError::OutOfRange {
+                min: 1,
+                max: 255,
+                actual: 0
+            }
+        );
+    }
+}
@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Web UI commands.
+//!
+//! This module provides commands for configuring the device's built-in
+//! web interface.
+
+use crate::command::Command;
+use crate::types::WebColors;
+
+/// Command to query or set the web UI color theme.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::{Command, WebColorCommand};
+/// use tasmor_lib::types::WebColors;
+///
+/// // Apply the light theme
+/// let cmd = WebColorCommand::Set(WebColors::light());
+/// assert_eq!(cmd.name(), "WebColor");
+/// assert!(cmd.payload().unwrap().starts_with(r#"{"WebColor":["#));
+///
+/// // Query the current theme
+/// let query = WebColorCommand::Get;
+/// assert_eq!(query.payload(), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebColorCommand {
+    /// Query the current theme.
+    Get,
+    /// Set all theme colors.
+    Set(WebColors),
+}
+
+impl WebColorCommand {
+    /// Creates a command to set a theme.
+    #[must_use]
+    pub const fn set(colors: WebColors) -> Self {
+        Self::Set(colors)
+    }
+}
+
+impl Command for WebColorCommand {
+    fn name(&self) -> String {
+        "WebColor".to_string()
+    }
+
+    fn payload(&self) -> Option<String> {
+        match self {
+            Self::Get => None,
+            Self::Set(colors) => Some(colors.to_command_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn web_color_get() {
+        let cmd = WebColorCommand::Get;
+        assert_eq!(cmd.to_http_command(), "WebColor");
+    }
+
+    #[test]
+    fn web_color_set() {
+        let cmd = WebColorCommand::set(WebColors::dark());
+        let payload = cmd.payload().unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(value["WebColor"][0], "#EAEAEA");
+        assert_eq!(cmd.mqtt_payload(), payload);
+    }
+}
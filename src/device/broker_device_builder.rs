@@ -5,13 +5,16 @@
 
 //! Builder for creating devices via an `MqttBroker` connection.
 
+use rumqttc::QoS;
+
 use crate::capabilities::Capabilities;
-use crate::command::StatusCommand;
 use crate::device::Device;
+use crate::device::probe::probe_capabilities;
 use crate::error::Error;
-use crate::protocol::{MqttBroker, Protocol, SharedMqttClient};
-use crate::response::StatusResponse;
+use crate::protocol::{MqttBroker, SharedMqttClient};
 use crate::state::DeviceState;
+use crate::subscription::DEFAULT_STATE_STREAM_CAPACITY;
+use crate::types::TopicScheme;
 
 /// Builder for creating devices that share a broker's MQTT connection.
 ///
@@ -41,6 +44,10 @@ pub struct BrokerDeviceBuilder<'a> {
     broker: &'a MqttBroker,
     topic: String,
     capabilities: Option<Capabilities>,
+    subscription_qos: QoS,
+    topic_scheme: TopicScheme,
+    results_on_command_topic: Option<bool>,
+    state_stream_capacity: usize,
 }
 
 impl<'a> BrokerDeviceBuilder<'a> {
@@ -50,6 +57,10 @@ impl<'a> BrokerDeviceBuilder<'a> {
             broker,
             topic: topic.into(),
             capabilities: None,
+            subscription_qos: QoS::AtLeastOnce,
+            topic_scheme: TopicScheme::default(),
+            results_on_command_topic: None,
+            state_stream_capacity: DEFAULT_STATE_STREAM_CAPACITY,
         }
     }
 
@@ -63,6 +74,98 @@ impl<'a> BrokerDeviceBuilder<'a> {
         self
     }
 
+    /// Sets the `QoS` used to subscribe to the device's `stat` and `tele`
+    /// topics.
+    ///
+    /// Defaults to [`QoS::AtLeastOnce`], so state reports (`tele/STATE`,
+    /// `stat/RESULT`) are not lost when the broker or client is under load.
+    /// [`QoS::AtMostOnce`] trades that guarantee for less traffic.
+    ///
+    /// With `QoS` 1 a message may be delivered more than once. Tasmota reports
+    /// absolute values (e.g. `"POWER":"ON"`, not "toggled"), and parsing a
+    /// message twice yields the same state, so duplicates are harmless for
+    /// the device state. Callbacks, however, may be called again with the
+    /// same value.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::MqttBroker;
+    /// use tasmor_lib::protocol::QoS;
+    ///
+    /// # async fn example(broker: &MqttBroker) -> tasmor_lib::Result<()> {
+    /// // A relay whose state changes must never be missed
+    /// let (relay, _) = broker
+    ///     .device("tasmota_pump")
+    ///     .with_subscription_qos(QoS::ExactlyOnce)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_subscription_qos(mut self, qos: QoS) -> Self {
+        self.subscription_qos = qos;
+        self
+    }
+
+    /// Sets the layout of the device's MQTT topics.
+    ///
+    /// Needed when the device's `FullTopic` or `Prefix1`-`Prefix3` settings
+    /// differ from Tasmota's defaults; otherwise commands are published to
+    /// topics the device does not listen on. Use
+    /// [`Device::topic_scheme`] on an HTTP device to read the layout.
+    ///
+    /// [`build()`](Self::build) also reads the layout once the device is
+    /// reachable and switches to it if it differs, e.g. when only the
+    /// telemetry prefix was customized.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::{MqttBroker, TopicScheme};
+    ///
+    /// # async fn example(broker: &MqttBroker) -> tasmor_lib::Result<()> {
+    /// // Device configured with `FullTopic home/%topic%/%prefix%/`
+    /// let scheme = TopicScheme::new("home/%topic%/%prefix%/")?;
+    /// let (device, _) = broker
+    ///     .device("tasmota_kitchen")
+    ///     .with_topic_scheme(scheme)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_topic_scheme(mut self, scheme: TopicScheme) -> Self {
+        self.topic_scheme = scheme;
+        self
+    }
+
+    /// Sets whether the device publishes command results on per-command
+    /// topics (`SetOption4`).
+    ///
+    /// With `SetOption4 1`, Tasmota answers commands on
+    /// `stat/<topic>/<Command>` (e.g. `stat/<topic>/POWER`) instead of
+    /// `stat/<topic>/RESULT`. [`build()`](Self::build) detects this from the
+    /// device's `SetOption` values; set it explicitly to skip the detection
+    /// or when using [`build_without_probe()`](Self::build_without_probe).
+    #[must_use]
+    pub fn with_results_on_command_topic(mut self, enabled: bool) -> Self {
+        self.results_on_command_topic = Some(enabled);
+        self
+    }
+
+    /// Sets how many state changes each [`Device::state_stream`] receiver
+    /// buffers (default: [`DEFAULT_STATE_STREAM_CAPACITY`]).
+    ///
+    /// A receiver that falls further behind drops the oldest changes.
+    #[must_use]
+    pub fn with_state_stream_capacity(mut self, capacity: usize) -> Self {
+        self.state_stream_capacity = capacity;
+        self
+    }
+
     /// Builds the device with auto-detection of capabilities.
     ///
     /// This will query the device status to detect capabilities, then query
@@ -84,33 +187,50 @@ impl<'a> BrokerDeviceBuilder<'a> {
         let capabilities = if let Some(caps) = self.capabilities {
             caps
         } else {
-            // Query device parameters (Status 1) for FriendlyName count
-            let cmd = StatusCommand::device_parameters();
-            let response = client.send_command(&cmd).await.map_err(Error::Protocol)?;
-            let mut status: StatusResponse = response.parse().map_err(Error::Parse)?;
-
-            // Query runtime state (Status 11) for light/energy capabilities
-            let cmd_state = StatusCommand::state();
-            if let Ok(state_response) = client.send_command(&cmd_state).await
-                && let Ok(state_status) = state_response.parse::<StatusResponse>()
-            {
-                // Merge sensor_status from Status 11 into our status
-                status.sensor_status = state_status.sensor_status;
-            }
+            probe_capabilities(&client).await?
+        };
 
-            // Query sensor info (Status 10) for ENERGY data
-            let cmd_sensors = StatusCommand::sensors();
-            if let Ok(sensors_response) = client.send_command(&cmd_sensors).await
-                && let Ok(sensors_status) = sensors_response.parse::<StatusResponse>()
-            {
-                // Merge sensors from Status 10 into our status
-                status.sensors = sensors_status.sensors;
-            }
+        let device = Device::new(client, capabilities)
+            .with_state_stream_capacity(self.state_stream_capacity);
 
-            Capabilities::from_status(&status)
+        // Status replies use their own topics, but other commands are only
+        // correlated once the result topic layout is known
+        let results_on_command_topic = match self.results_on_command_topic {
+            Some(enabled) => enabled,
+            None => match device.get_all_set_options().await {
+                Ok(flags) => flags.results_on_command_topic().unwrap_or(false),
+                Err(e) => {
+                    tracing::debug!(topic = %self.topic, error = %e, "Could not read SetOption4");
+                    false
+                }
+            },
         };
+        if results_on_command_topic {
+            tracing::info!(topic = %self.topic, "Device publishes results on command topics");
+            self.broker
+                .set_results_on_command_topic(&self.topic, true)
+                .await;
+        }
 
-        let device = Device::new(client, capabilities);
+        // The device answered on the configured command and stat topics, but
+        // its telemetry prefix may still differ
+        match device.topic_scheme().await {
+            Ok(scheme) if scheme != self.topic_scheme => {
+                tracing::info!(
+                    topic = %self.topic,
+                    full_topic = %scheme.full_topic(),
+                    "Using the topic scheme reported by the device"
+                );
+                self.broker
+                    .update_device_topic_scheme(&self.topic, scheme)
+                    .await
+                    .map_err(Error::Protocol)?;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::debug!(topic = %self.topic, error = %e, "Could not read topic scheme");
+            }
+        }
 
         // Register callbacks with the MQTT client for message routing
         device.register_callbacks();
@@ -138,7 +258,14 @@ impl<'a> BrokerDeviceBuilder<'a> {
         let client = self.create_client().await?;
         let capabilities = self.capabilities.unwrap_or_default();
 
-        let device = Device::new(client, capabilities);
+        let device = Device::new(client, capabilities)
+            .with_state_stream_capacity(self.state_stream_capacity);
+
+        if self.results_on_command_topic == Some(true) {
+            self.broker
+                .set_results_on_command_topic(&self.topic, true)
+                .await;
+        }
 
         // Register callbacks with the MQTT client for message routing
         device.register_callbacks();
@@ -154,7 +281,11 @@ impl<'a> BrokerDeviceBuilder<'a> {
         // Add subscription to broker and get response channel
         let (response_rx, router) = self
             .broker
-            .add_device_subscription(self.topic.clone())
+            .add_device_subscription(
+                self.topic.clone(),
+                self.subscription_qos,
+                self.topic_scheme.clone(),
+            )
             .await
             .map_err(Error::Protocol)?;
 
@@ -164,6 +295,7 @@ impl<'a> BrokerDeviceBuilder<'a> {
         Ok(SharedMqttClient::new(
             self.broker.client().clone(),
             self.topic.clone(),
+            self.topic_scheme.clone(),
             response_rx,
             router,
             self.broker.clone(),
@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Response synthesis for dry-run mode.
+//!
+//! A dry-run device does not send commands that would change the device.
+//! Instead, it builds the response Tasmota would return had it accepted the
+//! command, echoing the requested values back under the usual field names.
+
+use serde_json::{Map, Value};
+
+use crate::command::Command;
+use crate::types::PowerState;
+
+/// Returns `true` if the command only reads state.
+///
+/// Queries cannot change the device, so dry-run mode still sends them.
+/// `Status` always carries a payload (the status type) but is read-only, as
+/// is `Gpio 255` (list all pins). `ShutterOpen`, `ShutterClose` and
+/// `ShutterStop` take no payload but move the shutter.
+pub(crate) fn is_query<C: Command + ?Sized>(command: &C) -> bool {
+    let name = command.name();
+    match command.payload() {
+        None => !is_shutter_action(&name),
+        Some(payload) => {
+            name.eq_ignore_ascii_case("Status")
+                || (name.eq_ignore_ascii_case("Gpio") && payload == "255")
+        }
+    }
+}
+
+/// How Tasmota shows passwords in its responses.
+const MASKED: &str = "****";
+
+/// Returns the payload as it may appear in logs, with passwords masked.
+pub(crate) fn loggable_payload<'a>(name: &str, payload: &'a str) -> &'a str {
+    if is_password(name) { MASKED } else { payload }
+}
+
+/// Builds the response body for a single accepted command.
+pub(crate) fn synthesize(name: &str, payload: &str) -> String {
+    let mut fields = Map::new();
+    echo(&mut fields, name, payload);
+    Value::Object(fields).to_string()
+}
+
+/// Builds the combined response body for a `Backlog0` command.
+///
+/// Each step is echoed as if sent individually; `Delay` steps are skipped.
+pub(crate) fn synthesize_backlog(backlog: &str) -> String {
+    let steps = backlog.strip_prefix("Backlog0").unwrap_or(backlog);
+
+    let mut fields = Map::new();
+    for step in steps.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+        let (name, payload) = step.split_once(' ').unwrap_or((step, ""));
+        if !name.eq_ignore_ascii_case("Delay") {
+            echo(&mut fields, name, payload.trim());
+        }
+    }
+    Value::Object(fields).to_string()
+}
+
+/// Inserts the fields Tasmota reports for `name` set to `payload`.
+fn echo(fields: &mut Map<String, Value>, name: &str, payload: &str) {
+    // JSON payloads (e.g. `WebColor`) already have the response's shape
+    if let Ok(Value::Object(object)) = serde_json::from_str(payload) {
+        fields.extend(object);
+        return;
+    }
+
+    if is_password(name) {
+        fields.insert(name.to_string(), Value::String(MASKED.to_string()));
+        return;
+    }
+
+    // Tuya MCU commands are acknowledged, not echoed
+    if name
+        .get(..8)
+        .is_some_and(|p| p.eq_ignore_ascii_case("TuyaSend"))
+    {
+        fields.insert(name.to_string(), Value::String("Done".to_string()));
+        return;
+    }
+
+    if let Some(reply) = shutter_action_reply(name) {
+        fields.insert(name.to_string(), reply);
+        return;
+    }
+
+    if is_power(name) {
+        let state = payload
+            .parse::<PowerState>()
+            .map_or_else(|_| payload.to_uppercase(), |s| s.as_str().to_string());
+        fields.insert(name.to_uppercase(), Value::String(state));
+        return;
+    }
+
+    if name.eq_ignore_ascii_case("HSBColor") {
+        // Tasmota also reports the brightness component as the dimmer level
+        if let Some(brightness) = payload.split(',').nth(2).and_then(|b| b.parse::<u8>().ok()) {
+            fields.insert("Dimmer".to_string(), Value::from(brightness));
+        }
+        fields.insert("HSBColor".to_string(), Value::String(payload.to_string()));
+        return;
+    }
+
+    // Note: Tasmota uses "WakeUpDuration" (capital U) in the response.
+    let key = if name.eq_ignore_ascii_case("WakeupDuration") {
+        "WakeUpDuration".to_string()
+    } else {
+        name.to_string()
+    };
+    fields.insert(key, echo_value(payload));
+}
+
+/// Returns `true` for `Power` and `Power1`-`Power8`.
+fn is_power(name: &str) -> bool {
+    name.get(..5)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("Power"))
+        && name[5..].chars().all(|c| c.is_ascii_digit())
+}
+
+/// Returns `true` for `ShutterOpen<x>`, `ShutterClose<x>` and `ShutterStop<x>`.
+fn is_shutter_action(name: &str) -> bool {
+    shutter_action_reply(name).is_some()
+}
+
+/// Returns Tasmota's reply to a shutter action without payload: the target
+/// position when opening or closing, `Done` when stopping.
+fn shutter_action_reply(name: &str) -> Option<Value> {
+    let action = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    if action.eq_ignore_ascii_case("ShutterOpen") {
+        Some(Value::from(100))
+    } else if action.eq_ignore_ascii_case("ShutterClose") {
+        Some(Value::from(0))
+    } else if action.eq_ignore_ascii_case("ShutterStop") {
+        Some(Value::String("Done".to_string()))
+    } else {
+        None
+    }
+}
+
+/// Returns `true` for commands whose payload is a secret.
+fn is_password(name: &str) -> bool {
+    name.eq_ignore_ascii_case("WebPassword") || name.eq_ignore_ascii_case("MqttPassword")
+}
+
+/// Echoes numeric payloads as JSON numbers and everything else as strings.
+fn echo_value(payload: &str) -> Value {
+    if let Ok(n) = payload.parse::<u64>() {
+        Value::from(n)
+    } else if let Ok(n) = payload.parse::<f64>() {
+        Value::from(n)
+    } else {
+        Value::String(payload.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{DimmerCommand, GpioCommand, PowerCommand, ShutterCommand, StatusCommand};
+    use crate::response::{DimmerResponse, HsbColorResponse, PowerResponse, RoutineResponse};
+    use crate::types::{Dimmer, PowerIndex};
+
+    #[test]
+    fn queries_are_not_intercepted() {
+        assert!(is_query(&DimmerCommand::Get));
+        assert!(is_query(&StatusCommand::all()));
+        assert!(is_query(&GpioCommand::ReadAll));
+        assert!(!is_query(&DimmerCommand::Set(Dimmer::new(50).unwrap())));
+        assert!(!is_query(&PowerCommand::Toggle {
+            index: PowerIndex::one()
+        }));
+        assert!(!is_query(&ShutterCommand::stop(1).unwrap()));
+    }
+
+    #[test]
+    fn shutter_actions_report_target() {
+        assert_eq!(synthesize("ShutterOpen1", ""), r#"{"ShutterOpen1":100}"#);
+        assert_eq!(synthesize("ShutterClose2", ""), r#"{"ShutterClose2":0}"#);
+        assert_eq!(synthesize("ShutterStop1", ""), r#"{"ShutterStop1":"Done"}"#);
+    }
+
+    #[test]
+    fn dimmer_echoes_requested_value() {
+        let body = synthesize("Dimmer", "50");
+        let response: DimmerResponse = serde_json::from_str(&body).unwrap();
+        assert_eq!(response.dimmer(), 50);
+    }
+
+    #[test]
+    fn power_uses_tasmota_field_name() {
+        let body = synthesize("Power2", "ON");
+        let response: PowerResponse = serde_json::from_str(&body).unwrap();
+        assert_eq!(response.power_state(2).unwrap(), Some(PowerState::On));
+    }
+
+    #[test]
+    fn hsb_color_reports_dimmer() {
+        let body = synthesize("HSBColor", "120,100,40");
+        let response: HsbColorResponse = serde_json::from_str(&body).unwrap();
+        assert_eq!(response.hsb_color().unwrap().hue(), 120);
+        assert_eq!(response.dimmer(), Some(40));
+    }
+
+    #[test]
+    fn wakeup_duration_uses_response_field_name() {
+        let body = synthesize("WakeupDuration", "300");
+        assert_eq!(body, r#"{"WakeUpDuration":300}"#);
+    }
+
+    #[test]
+    fn tuya_send_is_acknowledged() {
+        assert_eq!(synthesize("TuyaSend1", "1,1"), r#"{"TuyaSend1":"Done"}"#);
+    }
+
+    #[test]
+    fn passwords_are_masked() {
+        assert_eq!(
+            synthesize("WebPassword", "s3cret"),
+            r#"{"WebPassword":"****"}"#
+        );
+        assert_eq!(loggable_payload("MqttPassword", "s3cret"), "****");
+        assert_eq!(loggable_payload("Dimmer", "50"), "50");
+    }
+
+    #[test]
+    fn backlog_merges_steps_and_skips_delays() {
+        let body = synthesize_backlog("Backlog0 Power1 ON; Delay 20; Dimmer 75; CT 300");
+        let response: RoutineResponse = serde_json::from_str(&body).unwrap();
+
+        assert_eq!(response.get_as::<String>("POWER1").unwrap(), "ON");
+        assert_eq!(response.get_as::<u8>("Dimmer").unwrap(), 75);
+        assert_eq!(response.get_as::<u16>("CT").unwrap(), 300);
+        assert!(!response.contains_key("Delay"));
+    }
+}
@@ -0,0 +1,225 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Per-device command slot with priority admission.
+
+use std::collections::VecDeque;
+
+use parking_lot::Mutex;
+use tokio::sync::oneshot;
+
+use crate::command::CommandPriority;
+
+/// Lets one command at a time through to the device, high priority first.
+///
+/// Waiting commands queue in one FIFO per priority. When the slot is
+/// released it is handed directly to the oldest high priority waiter, or
+/// to the oldest normal one if none is waiting, so commands of the same
+/// priority are admitted in the order they arrived.
+#[derive(Debug, Default)]
+pub(crate) struct CommandGate {
+    state: Mutex<GateState>,
+}
+
+#[derive(Debug, Default)]
+struct GateState {
+    busy: bool,
+    high: VecDeque<oneshot::Sender<()>>,
+    normal: VecDeque<oneshot::Sender<()>>,
+}
+
+impl CommandGate {
+    /// Waits for the slot. The command may be sent while the guard lives.
+    pub(crate) async fn acquire(&self, priority: CommandPriority) -> Slot<'_> {
+        let rx = {
+            let mut state = self.state.lock();
+            if !state.busy {
+                state.busy = true;
+                return Slot(self);
+            }
+            let (tx, rx) = oneshot::channel();
+            match priority {
+                CommandPriority::High => state.high.push_back(tx),
+                CommandPriority::Normal => state.normal.push_back(tx),
+            }
+            rx
+        };
+        let mut waiter = Waiter {
+            gate: self,
+            rx,
+            admitted: false,
+        };
+        // The sender is only dropped after a hand-over or when the waiter
+        // was cancelled, so this resolves once the slot is ours
+        let _ = (&mut waiter.rx).await;
+        waiter.admitted = true;
+        Slot(self)
+    }
+
+    /// Hands the slot to the next waiter, or marks it free.
+    fn release(&self) {
+        let mut state = self.state.lock();
+        loop {
+            let Some(next) = state.high.pop_front().or_else(|| state.normal.pop_front()) else {
+                state.busy = false;
+                return;
+            };
+            // A cancelled waiter has closed its receiver; skip it
+            if next.send(()).is_ok() {
+                return;
+            }
+        }
+    }
+}
+
+/// Holds the slot; dropping it admits the next command.
+pub(crate) struct Slot<'a>(&'a CommandGate);
+
+impl Drop for Slot<'_> {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+/// A queued command. If its future is cancelled after the slot was
+/// handed to it, the slot is passed on instead of being lost.
+struct Waiter<'a> {
+    gate: &'a CommandGate,
+    rx: oneshot::Receiver<()>,
+    admitted: bool,
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        if self.admitted {
+            return;
+        }
+        self.rx.close();
+        if self.rx.try_recv().is_ok() {
+            self.gate.release();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn high_priority_overtakes_waiting_normal_commands() {
+        let gate = Arc::new(CommandGate::default());
+        let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
+
+        // Hold the slot, as a command in flight would
+        let in_flight = gate.acquire(CommandPriority::Normal).await;
+
+        let mut tasks = Vec::new();
+        for (name, priority) in [
+            ("color 1", CommandPriority::Normal),
+            ("color 2", CommandPriority::Normal),
+            ("off", CommandPriority::High),
+        ] {
+            let gate = Arc::clone(&gate);
+            let order = Arc::clone(&order);
+            tasks.push(tokio::spawn(async move {
+                let _slot = gate.acquire(priority).await;
+                order.lock().push(name);
+            }));
+            // Queue them in a known order
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        drop(in_flight);
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(*order.lock(), ["off", "color 1", "color 2"]);
+    }
+
+    #[tokio::test]
+    async fn same_priority_commands_keep_their_order() {
+        let gate = Arc::new(CommandGate::default());
+        let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
+
+        let in_flight = gate.acquire(CommandPriority::Normal).await;
+
+        let mut tasks = Vec::new();
+        for (name, priority) in [
+            ("color 1", CommandPriority::Normal),
+            ("on", CommandPriority::High),
+            ("color 2", CommandPriority::Normal),
+            ("color 3", CommandPriority::Normal),
+            ("off", CommandPriority::High),
+            ("color 4", CommandPriority::Normal),
+        ] {
+            let gate = Arc::clone(&gate);
+            let order = Arc::clone(&order);
+            tasks.push(tokio::spawn(async move {
+                let _slot = gate.acquire(priority).await;
+                order.lock().push(name);
+                // Let the other waiters run while the slot is held
+                tokio::task::yield_now().await;
+            }));
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        drop(in_flight);
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(
+            *order.lock(),
+            ["on", "off", "color 1", "color 2", "color 3", "color 4"]
+        );
+    }
+
+    #[tokio::test]
+    async fn cancelled_high_priority_command_releases_normal_ones() {
+        let gate = CommandGate::default();
+        let in_flight = gate.acquire(CommandPriority::Normal).await;
+
+        // Gives up while the slot is taken
+        let timed_out = tokio::time::timeout(
+            Duration::from_millis(10),
+            gate.acquire(CommandPriority::High),
+        )
+        .await;
+        assert!(timed_out.is_err());
+
+        drop(in_flight);
+        let acquired = tokio::time::timeout(
+            Duration::from_millis(100),
+            gate.acquire(CommandPriority::Normal),
+        )
+        .await;
+        assert!(acquired.is_ok());
+    }
+
+    #[tokio::test]
+    async fn cancelled_normal_command_is_skipped() {
+        let gate = CommandGate::default();
+        let in_flight = gate.acquire(CommandPriority::Normal).await;
+
+        let timed_out = tokio::time::timeout(
+            Duration::from_millis(10),
+            gate.acquire(CommandPriority::Normal),
+        )
+        .await;
+        assert!(timed_out.is_err());
+
+        drop(in_flight);
+        let acquired = tokio::time::timeout(
+            Duration::from_millis(100),
+            gate.acquire(CommandPriority::Normal),
+        )
+        .await;
+        assert!(acquired.is_ok());
+    }
+}
@@ -5,12 +5,18 @@
 
 //! HTTP device builder.
 
-use crate::capabilities::Capabilities;
-use crate::command::StatusCommand;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time::{Instant, timeout_at};
+
+use crate::capabilities::{Capabilities, CapabilityDetector};
+use crate::command::{ModuleCommand, StatusCommand};
 use crate::device::Device;
 use crate::error::Error;
 use crate::protocol::{HttpClient, HttpConfig, Protocol};
-use crate::response::StatusResponse;
+use crate::response::{DeviceTemplate, StatusResponse};
 use crate::state::DeviceState;
 
 /// Builder for creating HTTP-based devices.
@@ -22,6 +28,10 @@ use crate::state::DeviceState;
 /// Both `build()` and `build_without_probe()` return the device along with its
 /// initial state, containing current values for power, energy, colors, etc.
 ///
+/// The whole build runs under one deadline, set with
+/// [`with_build_timeout`](Self::with_build_timeout), so a device that stops
+/// answering cannot stall application startup.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -50,24 +60,41 @@ use crate::state::DeviceState;
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug)]
 pub struct HttpDeviceBuilder {
     config: HttpConfig,
     capabilities: Option<Capabilities>,
+    detector: Option<Arc<dyn CapabilityDetector>>,
+    build_timeout: Duration,
+}
+
+impl fmt::Debug for HttpDeviceBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpDeviceBuilder")
+            .field("config", &self.config)
+            .field("capabilities", &self.capabilities)
+            .field("custom_detector", &self.detector.is_some())
+            .field("build_timeout", &self.build_timeout)
+            .finish()
+    }
 }
 
 impl HttpDeviceBuilder {
+    /// Default deadline for the whole build (15 seconds).
+    pub const DEFAULT_BUILD_TIMEOUT: Duration = Duration::from_secs(15);
+
     /// Creates a new builder with the specified HTTP configuration.
     pub(crate) fn new(config: HttpConfig) -> Self {
         Self {
             config,
             capabilities: None,
+            detector: None,
+            build_timeout: Self::DEFAULT_BUILD_TIMEOUT,
         }
     }
 
     /// Sets authentication credentials.
     ///
-    /// This creates a new `HttpConfig` with the credentials added.
+    /// The credentials are added to the HTTP configuration, keeping its other settings.
     ///
     /// # Arguments
     ///
@@ -79,23 +106,7 @@ impl HttpDeviceBuilder {
         username: impl Into<String>,
         password: impl Into<String>,
     ) -> Self {
-        // Store current values before modifying
-        let host = self.config.host().to_string();
-        let port = self.config.port();
-        let timeout = self.config.timeout();
-        let use_https = self.config.use_https();
-
-        // Reconstruct the config with credentials
-        let mut new_config = HttpConfig::new(host)
-            .with_port(port)
-            .with_timeout(timeout)
-            .with_credentials(username, password);
-
-        if use_https {
-            new_config = new_config.with_https();
-        }
-
-        self.config = new_config;
+        self.config = self.config.with_credentials(username, password);
         self
     }
 
@@ -109,6 +120,83 @@ impl HttpDeviceBuilder {
         self
     }
 
+    /// Sets a custom capability detector used by [`build`](Self::build).
+    ///
+    /// The detector receives the `Status 0` and `Template` replies, so it
+    /// can recognize hardware from its GPIO assignment. The `Template` query
+    /// is only sent when a detector is set; if it fails, the detector gets
+    /// an empty template. Ignored when capabilities are set manually.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    /// use tasmor_lib::response::{DeviceTemplate, StatusResponse};
+    /// use tasmor_lib::{Capabilities, CapabilityDetector, DefaultDetector, Device};
+    ///
+    /// struct MyDetector;
+    ///
+    /// impl CapabilityDetector for MyDetector {
+    ///     fn detect(&self, status: &StatusResponse, template: &DeviceTemplate) -> Capabilities {
+    ///         if template.name() == "My Dimmer" {
+    ///             return Capabilities::rgbcct_light();
+    ///         }
+    ///         DefaultDetector.detect(status, template)
+    ///     }
+    /// }
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (device, _) = Device::http("192.168.1.100")
+    ///     .with_detector(Arc::new(MyDetector))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_detector(mut self, detector: Arc<dyn CapabilityDetector>) -> Self {
+        self.detector = Some(detector);
+        self
+    }
+
+    /// Sets the deadline for the whole build (default: 15 seconds).
+    ///
+    /// The deadline covers capability detection and the initial state
+    /// query together, on top of the per-request HTTP timeout. When it
+    /// passes, the build stops waiting:
+    /// - during capability detection, the build fails with
+    ///   [`Error::DeadlineExceeded`] naming the phase
+    /// - during the template query, the detector gets an empty template
+    /// - during the initial state query, the device is returned with an
+    ///   empty state, which can be refreshed later with
+    ///   [`Device::query_state`]
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tasmor_lib::Device;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (device, _) = Device::http("192.168.1.100")
+    ///     .with_build_timeout(Duration::from_secs(5))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_build_timeout(mut self, timeout: Duration) -> Self {
+        self.build_timeout = timeout;
+        self
+    }
+
+    /// Returns the deadline for the whole build.
+    #[must_use]
+    pub fn build_timeout(&self) -> Duration {
+        self.build_timeout
+    }
+
     /// Returns the currently set capabilities, if any.
     #[must_use]
     pub fn capabilities(&self) -> Option<&Capabilities> {
@@ -131,28 +219,88 @@ impl HttpDeviceBuilder {
     /// Returns error if:
     /// - Connection fails
     /// - Capability detection fails
-    /// - Initial state query fails
+    /// - Capability detection does not finish within the
+    ///   [build timeout](Self::with_build_timeout)
     pub async fn build(self) -> Result<(Device<HttpClient>, DeviceState), Error> {
+        let deadline = Instant::now() + self.build_timeout;
         let client = self.config.into_client().map_err(Error::Protocol)?;
 
         // Auto-detect capabilities if not set
         let capabilities = if let Some(caps) = self.capabilities {
             caps
         } else {
-            let cmd = StatusCommand::all();
-            let response = client.send_command(&cmd).await.map_err(Error::Protocol)?;
-            let status: StatusResponse = response.parse().map_err(Error::Parse)?;
-            Capabilities::from_status(&status)
+            let status = timeout_at(deadline, Self::query_status(&client))
+                .await
+                .map_err(|_| Self::deadline_exceeded("capability detection"))??;
+
+            match &self.detector {
+                Some(detector) => {
+                    let template = timeout_at(deadline, Self::query_template(&client))
+                        .await
+                        .unwrap_or_else(|_| {
+                            tracing::warn!(
+                                phase = "template query",
+                                "Build deadline exceeded, detecting without template"
+                            );
+                            DeviceTemplate::default()
+                        });
+                    detector.detect(&status, &template)
+                }
+                None => Capabilities::from_status(&status),
+            }
         };
 
         let device = Device::new(client, capabilities);
-
-        // Query initial state
-        let initial_state = device.query_state().await?;
+        let initial_state = Self::query_initial_state(&device, deadline).await?;
 
         Ok((device, initial_state))
     }
 
+    /// Queries `Status 0` for capability detection.
+    async fn query_status(client: &HttpClient) -> Result<StatusResponse, Error> {
+        let response = client
+            .send_command(&StatusCommand::all())
+            .await
+            .map_err(Error::Protocol)?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Queries the device template, falling back to an empty one.
+    async fn query_template(client: &HttpClient) -> DeviceTemplate {
+        let template = match client.send_command(&ModuleCommand::Template).await {
+            Ok(response) => response.parse().map_err(Error::Parse),
+            Err(e) => Err(Error::Protocol(e)),
+        };
+        template.unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Failed to query template for capability detection");
+            DeviceTemplate::default()
+        })
+    }
+
+    /// Queries the initial state, falling back to an empty one at the deadline.
+    async fn query_initial_state(
+        device: &Device<HttpClient>,
+        deadline: Instant,
+    ) -> Result<DeviceState, Error> {
+        if let Ok(state) = timeout_at(deadline, device.query_state()).await {
+            state
+        } else {
+            tracing::warn!(
+                phase = "initial state query",
+                "Build deadline exceeded, returning device with empty state"
+            );
+            Ok(DeviceState::new())
+        }
+    }
+
+    /// Creates the error for a phase that did not finish before the deadline.
+    fn deadline_exceeded(phase: &str) -> Error {
+        tracing::warn!(phase, "Build deadline exceeded");
+        Error::DeadlineExceeded {
+            phase: phase.to_string(),
+        }
+    }
+
     /// Builds the device without probing for capabilities.
     ///
     /// Use this when you've set capabilities manually via [`with_capabilities`](Self::with_capabilities).
@@ -163,16 +311,20 @@ impl HttpDeviceBuilder {
     ///
     /// If capabilities were not set, defaults to [`Capabilities::default()`].
     ///
+    /// The initial state query is bounded by the
+    /// [build timeout](Self::with_build_timeout); if it does not finish in
+    /// time, the state is returned empty.
+    ///
     /// # Errors
     ///
     /// Returns error if the HTTP client cannot be created or state query fails.
     pub async fn build_without_probe(self) -> Result<(Device<HttpClient>, DeviceState), Error> {
+        let deadline = Instant::now() + self.build_timeout;
         let client = self.config.into_client().map_err(Error::Protocol)?;
         let capabilities = self.capabilities.unwrap_or_default();
         let device = Device::new(client, capabilities);
 
-        // Query initial state
-        let initial_state = device.query_state().await?;
+        let initial_state = Self::query_initial_state(&device, deadline).await?;
 
         Ok((device, initial_state))
     }
@@ -207,6 +359,18 @@ mod tests {
         assert!(builder.capabilities().is_some());
     }
 
+    #[test]
+    fn builder_build_timeout() {
+        let builder = HttpDeviceBuilder::new(HttpConfig::new("192.168.1.100"));
+        assert_eq!(
+            builder.build_timeout(),
+            HttpDeviceBuilder::DEFAULT_BUILD_TIMEOUT
+        );
+
+        let builder = builder.with_build_timeout(Duration::from_secs(3));
+        assert_eq!(builder.build_timeout(), Duration::from_secs(3));
+    }
+
     // Note: build() and build_without_probe() tests are in integration tests
     // as they require network access to query initial state.
 }
@@ -60,8 +60,12 @@
 
 #[cfg(feature = "mqtt")]
 mod broker_device_builder;
+mod dry_run;
+mod gate;
 #[cfg(feature = "http")]
 mod http_builder;
+mod probe;
+mod sunrise;
 
 // Builders are used internally (Device::http, broker.device) and returned to users.
 // They're pub(crate) because users access them via return types, not direct imports.
@@ -71,28 +75,56 @@ pub(crate) use broker_device_builder::BrokerDeviceBuilder;
 pub(crate) use http_builder::HttpDeviceBuilder;
 
 use std::sync::Arc;
+use std::time::Duration;
 
-use crate::capabilities::Capabilities;
+use crate::capabilities::{Capabilities, CapabilitiesDiff};
 use crate::command::{
-    ColorTemperatureCommand, Command, DimmerCommand, EnergyCommand, FadeCommand,
-    FadeDurationCommand, HsbColorCommand, PowerCommand, SchemeCommand, StartupFadeCommand,
-    StatusCommand, WakeupDurationCommand,
+    ChannelCommand, ColorCommand, ColorTemperatureCommand, Command, CommandPriority,
+    DeviceNameCommand, DimmerCommand, EnergyCommand, EventCommand, FadeCommand,
+    FadeDurationCommand, GpioCommand, HsbColorCommand, I2cScanCommand, ModuleCommand,
+    NtpServerCommand, PowerCommand, PulseTimeCommand, PwmCommand, RemoteInput, RemoteTopicCommand,
+    RuleCommand, SaveDataCommand, SchemeCommand, SecurityCommand, SetOptionCommand, ShutterCommand,
+    StartupFadeCommand, StateCommand, StatusCommand, TimerCommand, TopicCommand, TransitionCommand,
+    TuyaCommand, TuyaMcuCommand, WakeupDurationCommand, WebColorCommand, WhiteCommand,
 };
-use crate::error::{DeviceError, Error};
+use crate::error::{DeviceError, Error, ParseError, ProtocolError};
 #[cfg(feature = "http")]
 use crate::protocol::HttpClient;
 use crate::protocol::{CommandResponse, Protocol};
 use crate::response::{
-    ColorTemperatureResponse, DimmerResponse, EnergyResponse, FadeDurationResponse, FadeResponse,
-    HsbColorResponse, PowerResponse, RgbColorResponse, SchemeResponse, StartupFadeResponse,
-    StatusResponse, WakeupDurationResponse,
+    ChannelResponse, ColorTemperatureResponse, DeviceNameResponse, DeviceReport, DeviceTemplate,
+    DimmerResponse, EnergyResponse, EventResponse, FadeDurationResponse, FadeResponse, GpioPin,
+    GpioResponse, HsbColorResponse, I2cScanResponse, ModuleListResponse, ModuleResponse, NtpConfig,
+    NtpServerResponse, PowerResponse, PulseTimeResponse, PwmResponse, RgbColorResponse,
+    RgbwwColorResponse, SaveDataResponse, SchemeResponse, SecurityResponse, SetOptionFlags,
+    SetOptionResponse, ShutterResponse, StartupFadeResponse, StatusMemory, StatusParameters,
+    StatusResponse, TimerResponse, TopicResponse, TuyaMcuResponse, WakeupDurationResponse,
+    WebColorResponse, WhiteResponse,
 };
-use crate::state::DeviceState;
+use crate::state::{DeviceState, LightChoice, LightSnapshot, LightState};
 use crate::subscription::CallbackRegistry;
+use crate::telemetry::{
+    EnergyReading, SensorKind, StatusSnsResponse, TelemetryState, WifiInfo, parse_sensor_kinds,
+};
 use crate::types::{
-    ColorTemperature, Dimmer, FadeDuration, HsbColor, PowerIndex, PowerState, RgbColor, Scheme,
-    WakeupDuration,
+    ColorTemperature, Dimmer, FadeDuration, FirmwareVersion, HsbColor, LightDefaults, PowerIndex,
+    PowerQuery, PowerState, RemoteTopic, RgbColor, RgbwwColor, Scheme, TasmotaDateTime,
+    TopicPrefix, TopicScheme, TuyaValue, WakeupDuration, WebColors,
 };
+use gate::CommandGate;
+
+/// Shortest interval between polls in [`Device::sample_energy`].
+const MIN_ENERGY_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// First firmware with the one-shot `Fade2` and `Speed2` commands.
+const ONE_SHOT_TRANSITION_VERSION: FirmwareVersion = FirmwareVersion::new(9, 2, 0);
+
+/// Shortest off duration handed to the device in [`Device::power_cycle_index`].
+const MIN_DEVICE_POWER_CYCLE: Duration = Duration::from_secs(1);
+
+/// Longest off duration handed to the device in [`Device::power_cycle_index`]
+/// (Tasmota's `Delay` limit).
+const MAX_DEVICE_POWER_CYCLE: Duration = Duration::from_millis(65_535 * 100);
 
 /// A Tasmota device that can be controlled via HTTP or MQTT.
 ///
@@ -170,6 +202,9 @@ pub struct Device<P: Protocol> {
     protocol: Arc<P>,
     capabilities: Capabilities,
     callbacks: Arc<CallbackRegistry>,
+    dry_run: bool,
+    validate_relay_index: bool,
+    gate: Arc<CommandGate>,
 }
 
 impl<P: Protocol> Clone for Device<P> {
@@ -178,6 +213,9 @@ impl<P: Protocol> Clone for Device<P> {
             protocol: Arc::clone(&self.protocol),
             capabilities: self.capabilities.clone(),
             callbacks: Arc::clone(&self.callbacks),
+            dry_run: self.dry_run,
+            validate_relay_index: self.validate_relay_index,
+            gate: Arc::clone(&self.gate),
         }
     }
 }
@@ -186,17 +224,23 @@ impl<P: Protocol> std::fmt::Debug for Device<P> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Device")
             .field("capabilities", &self.capabilities)
+            .field("dry_run", &self.dry_run)
+            .field("validate_relay_index", &self.validate_relay_index)
             .finish_non_exhaustive()
     }
 }
 
 impl<P: Protocol> Device<P> {
     /// Creates a new device with the specified protocol and capabilities.
+    #[cfg(any(feature = "http", feature = "mqtt"))]
     pub(crate) fn new(protocol: P, capabilities: Capabilities) -> Self {
         Self {
             protocol: Arc::new(protocol),
             capabilities,
             callbacks: Arc::new(CallbackRegistry::new()),
+            dry_run: false,
+            validate_relay_index: false,
+            gate: Arc::new(CommandGate::default()),
         }
     }
 
@@ -206,8 +250,192 @@ impl<P: Protocol> Device<P> {
         &self.capabilities
     }
 
+    /// Detects the device capabilities again and updates this handle.
+    ///
+    /// Useful after a device was reconfigured (new template, module or
+    /// firmware) while the handle was alive. The detection queries
+    /// `Status 1`, `Status 11` and `Status 10`, whatever the protocol.
+    /// Other clones of this device keep their previous capabilities.
+    ///
+    /// Returns what changed compared to the capabilities held before.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device cannot be queried. The capabilities are
+    /// left unchanged in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Device;
+    ///
+    /// # async fn example(device: &mut Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let diff = device.reprobe_capabilities().await?;
+    /// if !diff.is_empty() {
+    ///     println!("Capabilities changed: {diff}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reprobe_capabilities(&mut self) -> Result<CapabilitiesDiff, Error> {
+        let capabilities = probe::probe_capabilities(self.protocol.as_ref()).await?;
+        let diff = self.capabilities.diff(&capabilities);
+        if !diff.is_empty() {
+            tracing::info!(changes = %diff, "Device capabilities changed");
+        }
+        self.capabilities = capabilities;
+        Ok(diff)
+    }
+
+    /// Enables or disables dry-run mode on this handle.
+    ///
+    /// In dry-run mode, commands that would change the device are logged
+    /// (at `info` level via `tracing`) instead of being sent, and a success
+    /// response is synthesized from the request. The synthesized responses
+    /// echo the requested values, so `set_dimmer(50)` returns a response
+    /// reporting 50. Relative adjustments such as `Dimmer +` cannot be
+    /// predicted and are echoed verbatim.
+    ///
+    /// Read-only queries (`get_*` methods and `Status`) are still sent so
+    /// that automation logic can observe the device's real state.
+    ///
+    /// A dry-run handle does not dispatch state change callbacks, since the
+    /// device state does not actually change. Synthesized responses
+    /// can be recognized with [`CommandResponse::is_dry_run`] and
+    /// [`RoutineResponse::is_dry_run`](crate::response::RoutineResponse::is_dry_run).
+    ///
+    /// The flag applies to this handle only: clone the device first to keep
+    /// a live handle alongside the dry-run one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Device;
+    /// use tasmor_lib::types::Dimmer;
+    ///
+    /// # async fn example(device: &Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let preview = device.clone().dry_run(true);
+    ///
+    /// // Logged, not sent; the response reports the requested level
+    /// let response = preview.set_dimmer(Dimmer::new(50)?).await?;
+    /// assert_eq!(response.dimmer(), 50);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    /// Returns `true` if this handle is in dry-run mode.
+    ///
+    /// See [`dry_run`](Self::dry_run).
+    #[must_use]
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Enables or disables relay index validation on this handle.
+    ///
+    /// When enabled, power commands addressing a relay beyond
+    /// [`relay_count`](Self::relay_count) fail with
+    /// [`DeviceError::NoSuchRelay`] instead of being sent. Tasmota silently
+    /// ignores such commands, which otherwise hides addressing mistakes on
+    /// multi-relay devices. Index 0 (all relays) is always accepted.
+    ///
+    /// Disabled by default. Like [`dry_run`](Self::dry_run), the flag
+    /// applies to this handle only.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::{Device, PowerIndex};
+    ///
+    /// # async fn example(device: &Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let strict = device.clone().validate_relay_index(true);
+    ///
+    /// // Fails with DeviceError::NoSuchRelay on a single-relay plug
+    /// assert!(strict.power_on_index(PowerIndex::new(5)?).await.is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn validate_relay_index(mut self, enabled: bool) -> Self {
+        self.validate_relay_index = enabled;
+        self
+    }
+
+    /// Returns `true` if this handle validates relay indexes.
+    ///
+    /// See [`validate_relay_index`](Self::validate_relay_index).
+    #[must_use]
+    pub fn validates_relay_index(&self) -> bool {
+        self.validate_relay_index
+    }
+
+    /// Returns the number of relays on the device.
+    ///
+    /// Counted from the `POWER` fields reported by `Status 11`. Detected
+    /// capabilities already hold the count
+    /// ([`Capabilities::relay_count`]), which is refreshed by
+    /// [`reprobe_capabilities`](Self::reprobe_capabilities); with manually
+    /// declared capabilities the device is queried on each call. Devices
+    /// without relays report 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the status query fails.
+    pub async fn relay_count(&self) -> Result<u8, Error> {
+        if let Some(count) = self.capabilities.relay_count() {
+            return Ok(count);
+        }
+
+        let status: StatusResponse = self
+            .send_command(&StatusCommand::state())
+            .await?
+            .parse()
+            .map_err(Error::Parse)?;
+        Ok(status
+            .sensor_status
+            .as_ref()
+            .map_or(0, crate::capabilities::count_relays))
+    }
+
+    /// Fails if relay validation is enabled and `index` exceeds the relay count.
+    async fn check_relay_index(&self, index: PowerIndex) -> Result<(), Error> {
+        if !self.validate_relay_index || index.value() == 0 {
+            return Ok(());
+        }
+        let available = self.relay_count().await?;
+        if index.value() > available {
+            return Err(DeviceError::NoSuchRelay {
+                index: index.value(),
+                available,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Dispatches a state change to callbacks, unless in dry-run mode.
+    fn dispatch(&self, change: &crate::state::StateChange) {
+        if !self.dry_run {
+            self.callbacks.dispatch(change);
+        }
+    }
+
     /// Sends a command to the device.
     ///
+    /// In [dry-run mode](Self::dry_run), commands that would change the
+    /// device are not sent and a synthesized response is returned instead.
+    ///
+    /// Commands sent through a device and its clones go out one at a time,
+    /// in the order of their [`priority`](Command::priority): a power change
+    /// sent while an animation loop has color commands waiting is sent
+    /// right after the command in flight. See [`CommandPriority`] for the
+    /// ordering guarantees.
+    ///
     /// # Errors
     ///
     /// Returns error if the command fails.
@@ -215,12 +443,149 @@ impl<P: Protocol> Device<P> {
         &self,
         command: &C,
     ) -> Result<CommandResponse, Error> {
+        if self.dry_run && !dry_run::is_query(command) {
+            let name = command.name();
+            let payload = command.payload().unwrap_or_default();
+            tracing::info!(
+                command = %name,
+                payload = %dry_run::loggable_payload(&name, &payload),
+                "Dry run: command not sent"
+            );
+            return Ok(CommandResponse::dry_run(dry_run::synthesize(
+                &name, &payload,
+            )));
+        }
+
+        let _slot = self.gate.acquire(command.priority()).await;
         self.protocol
             .send_command(command)
             .await
             .map_err(Error::Protocol)
     }
 
+    /// Sends a command and parses the response into a type of your choice.
+    ///
+    /// This is the generic form of the typed methods: together with a custom
+    /// [`Command`] implementation and a response struct deriving
+    /// `Deserialize`, it covers Tasmota commands the crate has no
+    /// first-class support for yet. See [`Command`] for an example.
+    ///
+    /// No state change callbacks are dispatched.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails or the response cannot be parsed
+    /// as `R`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use serde::Deserialize;
+    /// use tasmor_lib::Device;
+    /// use tasmor_lib::command::Command;
+    ///
+    /// struct LedPower;
+    ///
+    /// impl Command for LedPower {
+    ///     fn name(&self) -> String {
+    ///         "LedPower1".to_string()
+    ///     }
+    ///
+    ///     fn payload(&self) -> Option<String> {
+    ///         None
+    ///     }
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct LedPowerResponse {
+    ///     #[serde(rename = "LedPower1")]
+    ///     state: String,
+    /// }
+    ///
+    /// # async fn example() -> Result<(), tasmor_lib::Error> {
+    /// let (device, _) = Device::http("192.168.1.100").build().await?;
+    /// let response: LedPowerResponse = device.send_and_parse(&LedPower).await?;
+    /// println!("Status LED is {}", response.state);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_and_parse<C, R>(&self, command: &C) -> Result<R, Error>
+    where
+        C: Command + Sync,
+        R: serde::de::DeserializeOwned,
+    {
+        let response = self.send_command(command).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Sends a command and checks that the device reports the expected value.
+    ///
+    /// The response is parsed as `R` and `extract_actual` reads the value the
+    /// device reports back. If it differs from `expected` (or is missing),
+    /// [`DeviceError::Unverified`] is returned. This catches commands that
+    /// were clamped, ignored or answered by a stale reply, which matters most
+    /// over lossy MQTT links.
+    ///
+    /// Verification is opt-in: the plain setters do not compare values. No
+    /// state change callbacks are dispatched; use a typed setter such as
+    /// [`set_dimmer_verified`](Self::set_dimmer_verified) for that.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] with [`DeviceError::Unverified`] if the
+    /// reported value does not match, or an error if the command fails or
+    /// the response cannot be parsed as `R`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tasmor_lib::command::WakeupDurationCommand;
+    /// use tasmor_lib::response::WakeupDurationResponse;
+    /// use tasmor_lib::types::WakeupDuration;
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let duration = WakeupDuration::new(Duration::from_secs(300))?;
+    /// device
+    ///     .set_verified(
+    ///         &WakeupDurationCommand::Set(duration),
+    ///         duration,
+    ///         |response: &WakeupDurationResponse| response.duration().ok(),
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`DeviceError::Unverified`]: crate::error::DeviceError::Unverified
+    pub async fn set_verified<C, R, V>(
+        &self,
+        command: &C,
+        expected: V,
+        extract_actual: impl FnOnce(&R) -> Option<V>,
+    ) -> Result<R, Error>
+    where
+        C: Command + Sync,
+        R: serde::de::DeserializeOwned,
+        V: PartialEq + std::fmt::Debug,
+    {
+        let parsed: R = self
+            .send_command(command)
+            .await?
+            .parse()
+            .map_err(Error::Parse)?;
+
+        let actual = extract_actual(&parsed);
+        if actual.as_ref() != Some(&expected) {
+            return Err(Error::Device(DeviceError::Unverified {
+                command: command.name(),
+                expected: format!("{expected:?}"),
+                actual: actual.map(|value| format!("{value:?}")),
+            }));
+        }
+        Ok(parsed)
+    }
+
     // ========== Power Control ==========
 
     /// Turns on the first relay (POWER1).
@@ -330,6 +695,7 @@ impl<P: Protocol> Device<P> {
     ///
     /// Returns [`Error::Protocol`] if the device is unreachable or returns an invalid response.
     pub async fn power_toggle_index(&self, index: PowerIndex) -> Result<PowerResponse, Error> {
+        self.check_relay_index(index).await?;
         let cmd = PowerCommand::Toggle { index };
         let response = self.send_command(&cmd).await?;
         let parsed: PowerResponse = response.parse().map_err(Error::Parse)?;
@@ -340,16 +706,98 @@ impl<P: Protocol> Device<P> {
         Ok(parsed)
     }
 
+    /// Power-cycles the first relay: off, wait, on.
+    ///
+    /// See [`power_cycle_index`](Self::power_cycle_index).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tasmor_lib::Device;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (plug, _) = Device::http("192.168.1.100").build().await?;
+    ///
+    /// // Reboot the router plugged into this smart plug
+    /// let response = plug.power_cycle(Duration::from_secs(10)).await?;
+    /// println!("Power is now: {:?}", response.first_power_state()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn power_cycle(&self, off_duration: Duration) -> Result<PowerResponse, Error> {
+        self.power_cycle_index(PowerIndex::one(), off_duration)
+            .await
+    }
+
+    /// Power-cycles a specific relay: off, wait, on.
+    ///
+    /// Off durations of at least one second are handed to the device as
+    /// `Backlog0 Power<x> OFF; Delay <n>; Power<x> ON`, so the relay comes
+    /// back on even if this client disconnects or crashes meanwhile. The
+    /// delay is then rounded down to 100ms steps. Tasmota's
+    /// [`PulseTime`](Self::set_pulse_time) cannot be used for this: it only
+    /// switches a relay back off after it was turned on. Shorter durations,
+    /// and durations beyond the ~109 minute `Delay` limit, use a client-side
+    /// delay between two power commands.
+    ///
+    /// Callbacks are dispatched for the off transition and for the final
+    /// state, which is queried (device-side cycle) or reported (client-side
+    /// cycle) once the off duration has elapsed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a command fails. If the relay was switched off by
+    /// a client-side cycle and switching it back on fails, it stays off.
+    pub async fn power_cycle_index(
+        &self,
+        index: PowerIndex,
+        off_duration: Duration,
+    ) -> Result<PowerResponse, Error> {
+        self.check_relay_index(index).await?;
+        if (MIN_DEVICE_POWER_CYCLE..=MAX_DEVICE_POWER_CYCLE).contains(&off_duration) {
+            let routine = crate::command::Routine::builder()
+                .power_off(index)
+                .delay(off_duration)
+                .power_on(index)
+                .build()?;
+            let response = self.run(&routine).await?;
+
+            // Backlog replies do not always carry the result of the first
+            // command
+            if index.value() != 0 && Self::routine_response_changes(&response).is_empty() {
+                self.dispatch(&crate::state::StateChange::power(
+                    index.value(),
+                    PowerState::Off,
+                ));
+            }
+
+            tokio::time::sleep(off_duration).await;
+            self.get_power_index(index).await
+        } else {
+            self.set_power(index, PowerState::Off).await?;
+            tokio::time::sleep(off_duration).await;
+            self.set_power(index, PowerState::On).await
+        }
+    }
+
     /// Sets the power state of a specific relay.
     ///
     /// # Errors
     ///
-    /// Returns error if the command fails.
+    /// Returns error if the command fails, or [`DeviceError::NoSuchRelay`]
+    /// if [relay validation](Self::validate_relay_index) is enabled and the
+    /// relay does not exist.
     pub async fn set_power(
         &self,
         index: PowerIndex,
         state: PowerState,
     ) -> Result<PowerResponse, Error> {
+        self.check_relay_index(index).await?;
         let cmd = PowerCommand::Set { index, state };
         let response = self.send_command(&cmd).await?;
         let parsed: PowerResponse = response.parse().map_err(Error::Parse)?;
@@ -385,584 +833,2982 @@ impl<P: Protocol> Device<P> {
         Ok(parsed)
     }
 
-    /// Dispatches power state changes from a response to callbacks.
-    fn apply_power_response(&self, response: &PowerResponse) {
-        for idx in 1..=8 {
-            if let Ok(Some(power_state)) = response.power_state(idx) {
-                let change = crate::state::StateChange::power(idx, power_state);
-                self.callbacks.dispatch(&change);
-            }
-        }
+    /// Queries the power state of the first relay, reporting no answer as
+    /// unknown.
+    ///
+    /// See [`query_power_index`](Self::query_power_index).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device answered with an unparseable reply, or
+    /// the request failed for a reason other than a missing answer.
+    pub async fn query_power(&self) -> Result<PowerQuery, Error> {
+        self.query_power_index(PowerIndex::one()).await
     }
 
-    // ========== Status ==========
+    /// Queries the power state of a specific relay, reporting no answer as
+    /// unknown.
+    ///
+    /// Unlike [`get_power_index`](Self::get_power_index), a timeout or an
+    /// unreachable device yields [`PowerQuery::Unknown`] instead of an
+    /// error, as does a reply that does not mention the relay. Index 0
+    /// reports the first relay.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device answered with an unparseable reply, or
+    /// the request failed for a reason other than a missing answer.
+    pub async fn query_power_index(&self, index: PowerIndex) -> Result<PowerQuery, Error> {
+        let parsed = match self.get_power_index(index).await {
+            Ok(parsed) => parsed,
+            Err(Error::Protocol(e)) if is_no_response(&e) => {
+                tracing::debug!(index = index.value(), error = %e, "No answer to power query");
+                return Ok(PowerQuery::Unknown);
+            }
+            Err(e) => return Err(e),
+        };
 
-    /// Gets the full device status.
+        let relay = index.value().max(1);
+        let state = parsed.power_state(relay).map_err(Error::Parse)?;
+        Ok(state.map_or(PowerQuery::Unknown, PowerQuery::from))
+    }
+
+    /// Sets the pulse timer of a relay (`PulseTime<x>`).
+    ///
+    /// Once set, the device switches the relay back off `duration` after
+    /// each time it is turned on, whether by this client, a button or a
+    /// rule. A zero duration disables the timer. Durations are rounded to
+    /// 100ms below 11.1s and to whole seconds above 12s.
     ///
     /// # Errors
     ///
-    /// Returns error if the command fails.
-    pub async fn status(&self) -> Result<StatusResponse, Error> {
-        let cmd = StatusCommand::all();
+    /// Returns [`Error::Value`] if `duration` exceeds
+    /// [`MAX_PULSE_TIME`](crate::command::MAX_PULSE_TIME), or an error if
+    /// the command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tasmor_lib::Device;
+    /// use tasmor_lib::types::PowerIndex;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (fan, _) = Device::http("192.168.1.100").build().await?;
+    ///
+    /// // The bathroom fan turns itself off after 15 minutes
+    /// fan.set_pulse_time(PowerIndex::one(), Duration::from_secs(15 * 60))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_pulse_time(
+        &self,
+        index: PowerIndex,
+        duration: Duration,
+    ) -> Result<PulseTimeResponse, Error> {
+        self.check_relay_index(index).await?;
+        let cmd = PulseTimeCommand::set(index, duration)?;
         let response = self.send_command(&cmd).await?;
         response.parse().map_err(Error::Parse)
     }
 
-    /// Gets the abbreviated device status.
+    /// Gets the pulse timer of a relay (`PulseTime<x>`).
     ///
     /// # Errors
     ///
-    /// Returns error if the command fails.
-    pub async fn status_abbreviated(&self) -> Result<StatusResponse, Error> {
-        let cmd = StatusCommand::abbreviated();
+    /// Returns error if the command fails or the response cannot be parsed.
+    pub async fn get_pulse_time(&self, index: PowerIndex) -> Result<PulseTimeResponse, Error> {
+        self.check_relay_index(index).await?;
+        let cmd = PulseTimeCommand::Get { index };
         let response = self.send_command(&cmd).await?;
         response.parse().map_err(Error::Parse)
     }
 
-    // ========== Dimmer ==========
-
-    /// Sets the dimmer level (brightness) for dimmable lights.
+    /// Replaces timer `index` (`Timer<x>`, 1-16).
     ///
-    /// The dimmer value must be between 0 and 100. Use the [`Dimmer`] type
-    /// to ensure valid values at compile time.
+    /// The timer switches its relay at a time of day on the selected
+    /// weekdays. Timers only fire while the device's global `Timers` switch
+    /// is on, reported by [`TimerResponse::timers_enabled`], and once the
+    /// device has synchronized its clock.
     ///
     /// # Errors
     ///
-    /// Returns [`Error::Device`] with [`DeviceError::UnsupportedCapability`] if
-    /// the device doesn't support dimming (check with
-    /// [`capabilities().supports_dimmer_control()`](Capabilities::supports_dimmer_control)).
-    ///
-    /// Returns [`Error::Protocol`] if the device is unreachable or returns an invalid response.
+    /// Returns [`Error::Value`] if `index` is not 1-16 or the timer is
+    /// invalid, an error if the timer's output is not a relay of this
+    /// device, or an error if the command fails.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use tasmor_lib::{Device, Dimmer};
+    /// use chrono::NaiveTime;
+    /// use tasmor_lib::Device;
+    /// use tasmor_lib::command::{Timer, TimerAction};
+    /// use tasmor_lib::types::PowerIndex;
     ///
     /// # async fn example() -> tasmor_lib::Result<()> {
-    /// let (device, _) = Device::http("192.168.1.100").build().await?;
-    ///
-    /// // Set brightness to 75%
-    /// let dimmer = Dimmer::new(75)?;
-    /// let response = device.set_dimmer(dimmer).await?;
-    /// println!("Dimmer set to {}%", response.dimmer());
+    /// let (plug, _) = Device::http("192.168.1.100").build().await?;
+    ///
+    /// // The coffee machine turns on at 6:45 on weekdays
+    /// let timer = Timer {
+    ///     days: Timer::WEEKDAYS,
+    ///     ..Timer::new(
+    ///         NaiveTime::from_hms_opt(6, 45, 0).unwrap(),
+    ///         PowerIndex::one(),
+    ///         TimerAction::On,
+    ///     )
+    /// };
+    /// plug.set_timer(1, timer).await?;
     /// # Ok(())
     /// # }
     /// ```
-    ///
-    /// [`DeviceError::UnsupportedCapability`]: crate::error::DeviceError::UnsupportedCapability
-    pub async fn set_dimmer(&self, value: Dimmer) -> Result<DimmerResponse, Error> {
-        self.check_capability("dimmer", self.capabilities.supports_dimmer_control())?;
-        let cmd = DimmerCommand::Set(value);
+    pub async fn set_timer(
+        &self,
+        index: u8,
+        timer: crate::command::Timer,
+    ) -> Result<TimerResponse, Error> {
+        let cmd = TimerCommand::set(index, timer)?;
+        self.check_relay_index(timer.output).await?;
         let response = self.send_command(&cmd).await?;
-        let parsed: DimmerResponse = response.parse().map_err(Error::Parse)?;
-
-        // Dispatch callbacks for state changes
-        self.apply_dimmer_response(&parsed);
-
-        Ok(parsed)
+        response.parse().map_err(Error::Parse)
     }
 
-    /// Gets the current dimmer level.
-    ///
-    /// This is a **point-in-time query** that fetches the current dimmer value
-    /// from the device. For continuous monitoring, use MQTT subscriptions via
-    /// [`on_dimmer_changed`](crate::subscription::Subscribable::on_dimmer_changed).
+    /// Gets timer `index` (`Timer<x>`, 1-16).
     ///
     /// # Errors
     ///
-    /// Returns [`Error::Device`] with [`DeviceError::UnsupportedCapability`] if
-    /// the device doesn't support dimming.
-    ///
+    /// Returns [`Error::Value`] if `index` is not 1-16, or an error if the
+    /// command fails or the timer cannot be parsed (e.g. it is relative to
+    /// sunrise or sunset).
+    pub async fn get_timer(&self, index: u8) -> Result<TimerResponse, Error> {
+        let cmd = TimerCommand::get(index)?;
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    // ========== Shutter Control ==========
+
+    /// Moves shutter `index` to a position (`ShutterPosition<x>`).
+    ///
+    /// Positions follow Tasmota's convention: 0 is closed, 100 is open.
+    /// The command returns once the move has started; the response reports
+    /// the target and, when available, the current position and direction.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Value`] if `index` is not 1-16 or `percent` exceeds
+    /// 100, an error if the device does not support shutters, or if the
+    /// command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::{Capabilities, Device};
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (blind, _) = Device::http("192.168.1.110")
+    ///     .with_capabilities(Capabilities::shutter())
+    ///     .build_without_probe()
+    ///     .await?;
+    ///
+    /// // Half open
+    /// let response = blind.set_shutter_position(1, 50).await?;
+    /// println!("Moving to {}%", response.target());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_shutter_position(
+        &self,
+        index: u8,
+        percent: u8,
+    ) -> Result<ShutterResponse, Error> {
+        self.check_capability("shutter", self.capabilities.supports_shutter_control())?;
+        let cmd = ShutterCommand::set_position(index, percent)?;
+        self.move_shutter(&cmd).await
+    }
+
+    /// Opens shutter `index` fully (`ShutterOpen<x>`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Value`] if `index` is not 1-16, an error if the
+    /// device does not support shutters, or if the command fails.
+    pub async fn open_shutter(&self, index: u8) -> Result<ShutterResponse, Error> {
+        self.check_capability("shutter", self.capabilities.supports_shutter_control())?;
+        let cmd = ShutterCommand::open(index)?;
+        self.move_shutter(&cmd).await
+    }
+
+    /// Closes shutter `index` fully (`ShutterClose<x>`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Value`] if `index` is not 1-16, an error if the
+    /// device does not support shutters, or if the command fails.
+    pub async fn close_shutter(&self, index: u8) -> Result<ShutterResponse, Error> {
+        self.check_capability("shutter", self.capabilities.supports_shutter_control())?;
+        let cmd = ShutterCommand::close(index)?;
+        self.move_shutter(&cmd).await
+    }
+
+    /// Stops shutter `index` where it is (`ShutterStop<x>`).
+    ///
+    /// Tasmota only acknowledges the command with `Done`, so no position
+    /// is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Value`] if `index` is not 1-16, an error if the
+    /// device does not support shutters, or if the command fails.
+    pub async fn stop_shutter(&self, index: u8) -> Result<(), Error> {
+        self.check_capability("shutter", self.capabilities.supports_shutter_control())?;
+        let cmd = ShutterCommand::stop(index)?;
+        self.send_command(&cmd).await?;
+        Ok(())
+    }
+
+    /// Sends a shutter move command and parses the reply.
+    async fn move_shutter(&self, cmd: &ShutterCommand) -> Result<ShutterResponse, Error> {
+        let response = self.send_command(cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Dispatches power state changes from a response to callbacks.
+    fn apply_power_response(&self, response: &PowerResponse) {
+        for idx in 1..=8 {
+            if let Ok(Some(power_state)) = response.power_state(idx) {
+                let change = crate::state::StateChange::power(idx, power_state);
+                self.dispatch(&change);
+            }
+        }
+    }
+
+    // ========== Status ==========
+
+    /// Gets the full device status.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn status(&self) -> Result<StatusResponse, Error> {
+        let cmd = StatusCommand::all();
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Gets the abbreviated device status.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn status_abbreviated(&self) -> Result<StatusResponse, Error> {
+        let cmd = StatusCommand::abbreviated();
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Gets everything about the device in one report.
+    ///
+    /// Reads `Status 0`, which carries most sections, and queries only the
+    /// sections it omitted with their targeted `Status` command. A section
+    /// the device does not report at all stays `None` in the report, e.g.
+    /// MQTT when it is disabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the `Status 0` query fails. Failed targeted queries
+    /// leave their section empty.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let report = device.full_report().await?;
+    /// if let (Some(firmware), Some(network)) = (report.firmware(), report.network()) {
+    ///     println!("{} at {}", firmware.version, network.ip_address);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn full_report(&self) -> Result<DeviceReport, Error> {
+        let mut report = DeviceReport::from_status(self.status().await?);
+
+        for cmd in report.missing_sections() {
+            let status = match self.send_command(&cmd).await {
+                Ok(response) => response.parse::<StatusResponse>().map_err(Error::Parse),
+                Err(e) => Err(e),
+            };
+            match status {
+                Ok(status) => report.merge(status),
+                Err(e) => {
+                    tracing::debug!(command = %cmd.to_http_command(), error = %e, "Status section unavailable");
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Gets the device parameters reported by `Status 1`.
+    ///
+    /// Includes the restart reason, boot and flash save counters, uptime and
+    /// startup time, which help analyze a device's reliability.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails or the response has no
+    /// `StatusPRM` section.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let params = device.status_parameters().await?;
+    /// println!(
+    ///     "Booted {} times, last restart: {}",
+    ///     params.boot_count, params.restart_reason
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn status_parameters(&self) -> Result<StatusParameters, Error> {
+        let status: StatusResponse = self
+            .send_command(&StatusCommand::device_parameters())
+            .await?
+            .parse()
+            .map_err(Error::Parse)?;
+        status
+            .status_prm
+            .ok_or_else(|| Error::Parse(ParseError::MissingField("StatusPRM".to_string())))
+    }
+
+    /// Gets the memory and flash information reported by `Status 4`.
+    ///
+    /// Cheaper than [`status`](Self::status) when only the memory section
+    /// is needed, e.g. to check before an OTA upgrade that the new firmware
+    /// fits with [`StatusMemory::fits_firmware`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails or the response has no
+    /// `StatusMEM` section.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let memory = device.status_memory().await?;
+    /// if !memory.fits_firmware(640) {
+    ///     println!("Only {} KB free, upgrade through tasmota-minimal", memory.free);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn status_memory(&self) -> Result<StatusMemory, Error> {
+        let status: StatusResponse = self
+            .send_command(&StatusCommand::memory())
+            .await?
+            .parse()
+            .map_err(Error::Parse)?;
+        status
+            .memory
+            .ok_or_else(|| Error::Parse(ParseError::MissingField("StatusMEM".to_string())))
+    }
+
+    /// Gets the Wi-Fi connection of the device from `Status 11`.
+    ///
+    /// Gathers the SSID, the BSSID of the access point, the channel and the
+    /// signal, both as raw dBm ([`WifiInfo::signal_dbm`]) and as a 0-100
+    /// quality ([`WifiInfo::quality`]). Reading it from each device while
+    /// walking through a building shows which access point serves which
+    /// area, and where coverage is weak.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails or the response has no Wi-Fi
+    /// section, e.g. on a device connected over Ethernet.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let wifi = device.wifi_info().await?;
+    /// println!(
+    ///     "{} via {}: {:?} dBm ({:?}%)",
+    ///     wifi.ssid.as_deref().unwrap_or("?"),
+    ///     wifi.bssid.as_deref().unwrap_or("?"),
+    ///     wifi.signal_dbm(),
+    ///     wifi.quality()
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn wifi_info(&self) -> Result<WifiInfo, Error> {
+        let status: StatusResponse = self
+            .send_command(&StatusCommand::state())
+            .await?
+            .parse()
+            .map_err(Error::Parse)?;
+        status
+            .sensor_status
+            .and_then(|value| serde_json::from_value::<TelemetryState>(value).ok())
+            .and_then(|state| state.wifi().cloned())
+            .ok_or_else(|| Error::Parse(ParseError::MissingField("Wifi".to_string())))
+    }
+
+    /// Lists the sensors the device reports in `Status 10`.
+    ///
+    /// Lets an application discover which sensor blocks (energy, BME280,
+    /// DS18B20, ...) a device has before subscribing to their readings.
+    /// See [`parse_sensor_kinds`](crate::telemetry::parse_sensor_kinds).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails or the response is not a JSON
+    /// object.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Device;
+    /// use tasmor_lib::telemetry::SensorKind;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (device, _) = Device::http("192.168.1.100").build().await?;
+    ///
+    /// for sensor in device.available_sensors().await? {
+    ///     match sensor {
+    ///         SensorKind::Energy => println!("Energy panel"),
+    ///         other => println!("Sensor panel: {other}"),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn available_sensors(&self) -> Result<Vec<SensorKind>, Error> {
+        let cmd = StatusCommand::sensors();
+        let response = self.send_command(&cmd).await?;
+        parse_sensor_kinds(response.body()).map_err(Error::Parse)
+    }
+
+    /// Gets all `SetOption` values in one query.
+    ///
+    /// Decodes the packed `SetOption` words of `Status 3`. Options the
+    /// firmware does not report are absent from the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails, the response has no `StatusLOG`
+    /// block, or a `SetOption` word is malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Device;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (device, _) = Device::http("192.168.1.100").build().await?;
+    ///
+    /// let flags = device.get_all_set_options().await?;
+    /// if flags.home_assistant_discovery() == Some(true) {
+    ///     println!("Legacy discovery enabled");
+    /// }
+    /// println!("SetOption145: {:?}", flags.get(145));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_all_set_options(&self) -> Result<SetOptionFlags, Error> {
+        let cmd = StatusCommand::logging();
+        let response = self.send_command(&cmd).await?;
+        let status: StatusResponse = response.parse().map_err(Error::Parse)?;
+        let logging = status
+            .logging
+            .ok_or_else(|| Error::Parse(ParseError::MissingField("StatusLOG".to_string())))?;
+        SetOptionFlags::from_words(&logging.set_option).map_err(Error::Parse)
+    }
+
+    // ========== Dimmer ==========
+
+    /// Sets the dimmer level (brightness) for dimmable lights.
+    ///
+    /// The dimmer value must be between 0 and 100. Use the [`Dimmer`] type
+    /// to ensure valid values at compile time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] with [`DeviceError::UnsupportedCapability`] if
+    /// the device doesn't support dimming (check with
+    /// [`capabilities().supports_dimmer_control()`](Capabilities::supports_dimmer_control)).
+    ///
+    /// Returns [`Error::Protocol`] if the device is unreachable or returns an invalid response.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::{Device, Dimmer};
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (device, _) = Device::http("192.168.1.100").build().await?;
+    ///
+    /// // Set brightness to 75%
+    /// let dimmer = Dimmer::new(75)?;
+    /// let response = device.set_dimmer(dimmer).await?;
+    /// println!("Dimmer set to {}%", response.dimmer());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`DeviceError::UnsupportedCapability`]: crate::error::DeviceError::UnsupportedCapability
+    pub async fn set_dimmer(&self, value: Dimmer) -> Result<DimmerResponse, Error> {
+        self.check_capability("dimmer", self.capabilities.supports_dimmer_control())?;
+        let cmd = DimmerCommand::Set(value);
+        let response = self.send_command(&cmd).await?;
+        let parsed: DimmerResponse = response.parse().map_err(Error::Parse)?;
+
+        // Dispatch callbacks for state changes
+        self.apply_dimmer_response(&parsed);
+
+        Ok(parsed)
+    }
+
+    /// Sets the dimmer level and checks that the device reports it back.
+    ///
+    /// Like [`set_dimmer`](Self::set_dimmer), but fails if the reply carries
+    /// a different level. See [`set_verified`](Self::set_verified).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] with [`DeviceError::Unverified`] if the
+    /// device reports another level, or the errors of
+    /// [`set_dimmer`](Self::set_dimmer).
+    ///
+    /// [`DeviceError::Unverified`]: crate::error::DeviceError::Unverified
+    pub async fn set_dimmer_verified(&self, value: Dimmer) -> Result<DimmerResponse, Error> {
+        self.check_capability("dimmer", self.capabilities.supports_dimmer_control())?;
+        let cmd = DimmerCommand::Set(value);
+        let parsed = self
+            .set_verified(&cmd, value.value(), |response: &DimmerResponse| {
+                Some(response.dimmer())
+            })
+            .await?;
+
+        self.apply_dimmer_response(&parsed);
+
+        Ok(parsed)
+    }
+
+    /// Gets the current dimmer level.
+    ///
+    /// This is a **point-in-time query** that fetches the current dimmer value
+    /// from the device. For continuous monitoring, use MQTT subscriptions via
+    /// [`on_dimmer_changed`](crate::subscription::Subscribable::on_dimmer_changed).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] with [`DeviceError::UnsupportedCapability`] if
+    /// the device doesn't support dimming.
+    ///
     /// Returns [`Error::Protocol`] if the device is unreachable or returns an invalid response.
     ///
-    /// [`DeviceError::UnsupportedCapability`]: crate::error::DeviceError::UnsupportedCapability
-    pub async fn get_dimmer(&self) -> Result<DimmerResponse, Error> {
-        self.check_capability("dimmer", self.capabilities.supports_dimmer_control())?;
-        let cmd = DimmerCommand::Get;
-        let response = self.send_command(&cmd).await?;
-        let parsed: DimmerResponse = response.parse().map_err(Error::Parse)?;
+    /// [`DeviceError::UnsupportedCapability`]: crate::error::DeviceError::UnsupportedCapability
+    pub async fn get_dimmer(&self) -> Result<DimmerResponse, Error> {
+        self.check_capability("dimmer", self.capabilities.supports_dimmer_control())?;
+        let cmd = DimmerCommand::Get;
+        let response = self.send_command(&cmd).await?;
+        let parsed: DimmerResponse = response.parse().map_err(Error::Parse)?;
+
+        // Dispatch callbacks for state changes
+        self.apply_dimmer_response(&parsed);
+
+        Ok(parsed)
+    }
+
+    /// Gets the current dimmer level, or `None` if dimming is not supported.
+    ///
+    /// Unlike [`get_dimmer`](Self::get_dimmer), an unsupported capability is
+    /// not an error, which suits code that reads whatever a device offers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Protocol`] if the device is unreachable or returns an invalid response.
+    pub async fn try_get_dimmer(&self) -> Result<Option<DimmerResponse>, Error> {
+        if !self.capabilities.supports_dimmer_control() {
+            return Ok(None);
+        }
+        self.get_dimmer().await.map(Some)
+    }
+
+    /// Changes the dimmer level by `delta` percentage points.
+    ///
+    /// Reads the current level, adds `delta` clamped to 0-100, and writes
+    /// it back. Suits "a bit brighter" style controls where absolute
+    /// values are not known.
+    ///
+    /// Returns the level the device reports afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] with [`DeviceError::UnsupportedCapability`] if
+    /// the device doesn't support dimming.
+    ///
+    /// Returns [`Error::Protocol`] or [`Error::Parse`] if reading or writing
+    /// the level fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// // A bit dimmer
+    /// let dimmer = device.adjust_dimmer(-10).await?;
+    /// println!("Dimmer is now {}%", dimmer.value());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`DeviceError::UnsupportedCapability`]: crate::error::DeviceError::UnsupportedCapability
+    pub async fn adjust_dimmer(&self, delta: i8) -> Result<Dimmer, Error> {
+        let current = self.get_dimmer().await?.dimmer();
+        let target = Dimmer::clamped(current.saturating_add_signed(delta));
+        let response = self.set_dimmer(target).await?;
+        Ok(Dimmer::clamped(response.dimmer()))
+    }
+
+    /// Raises the dimmer level by `step` percentage points (1-100).
+    ///
+    /// Sends `Dimmer +<step>`, so the device applies the step to its own
+    /// level in a single request, unlike [`adjust_dimmer`](Self::adjust_dimmer)
+    /// which reads the level first. Suits rotary encoders and other controls
+    /// that only know the direction. The device stops at 100%.
+    ///
+    /// Returns a typed response with the resulting level.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] with [`DeviceError::UnsupportedCapability`] if
+    /// the device doesn't support dimming.
+    ///
+    /// Returns [`Error::Protocol`] or [`Error::Parse`] if the command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let response = device.dimmer_up(10).await?;
+    /// println!("Dimmer is now {}%", response.dimmer());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`DeviceError::UnsupportedCapability`]: crate::error::DeviceError::UnsupportedCapability
+    pub async fn dimmer_up(&self, step: u8) -> Result<DimmerResponse, Error> {
+        self.step_dimmer(DimmerCommand::IncreaseBy(step)).await
+    }
+
+    /// Lowers the dimmer level by `step` percentage points (1-100).
+    ///
+    /// Sends `Dimmer -<step>`; see [`dimmer_up`](Self::dimmer_up).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] with [`DeviceError::UnsupportedCapability`] if
+    /// the device doesn't support dimming.
+    ///
+    /// Returns [`Error::Protocol`] or [`Error::Parse`] if the command fails.
+    ///
+    /// [`DeviceError::UnsupportedCapability`]: crate::error::DeviceError::UnsupportedCapability
+    pub async fn dimmer_down(&self, step: u8) -> Result<DimmerResponse, Error> {
+        self.step_dimmer(DimmerCommand::DecreaseBy(step)).await
+    }
+
+    /// Sends a relative dimmer command and dispatches the resulting level.
+    async fn step_dimmer(&self, cmd: DimmerCommand) -> Result<DimmerResponse, Error> {
+        self.check_capability("dimmer", self.capabilities.supports_dimmer_control())?;
+        let response = self.send_command(&cmd).await?;
+        let parsed: DimmerResponse = response.parse().map_err(Error::Parse)?;
+
+        // Dispatch callbacks for state changes
+        self.apply_dimmer_response(&parsed);
+
+        Ok(parsed)
+    }
+
+    /// Dispatches dimmer state changes from a response to callbacks.
+    fn apply_dimmer_response(&self, response: &DimmerResponse) {
+        if let Ok(dimmer) = Dimmer::new(response.dimmer()) {
+            let change = crate::state::StateChange::dimmer(dimmer);
+            self.dispatch(&change);
+        }
+
+        if let Ok(Some(power)) = response.power_state() {
+            let change = crate::state::StateChange::power(1, power);
+            self.dispatch(&change);
+        }
+    }
+
+    // ========== Color Temperature ==========
+
+    /// Sets the color temperature.
+    ///
+    /// Returns a typed response including the new color temperature and power state.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device doesn't support color temperature or the command fails.
+    pub async fn set_color_temperature(
+        &self,
+        value: ColorTemperature,
+    ) -> Result<ColorTemperatureResponse, Error> {
+        self.check_capability(
+            "color temperature",
+            self.capabilities.supports_color_temperature_control(),
+        )?;
+        let cmd = ColorTemperatureCommand::Set(value);
+        let response = self.send_command(&cmd).await?;
+        let parsed: ColorTemperatureResponse = response.parse().map_err(Error::Parse)?;
+
+        // Dispatch callbacks for state changes
+        self.apply_color_temperature_response(&parsed);
+
+        Ok(parsed)
+    }
+
+    /// Sets the color temperature and checks that the device reports it back.
+    ///
+    /// Like [`set_color_temperature`](Self::set_color_temperature), but fails
+    /// if the reply carries a different value, e.g. because the light
+    /// clamped it to its own range. See [`set_verified`](Self::set_verified).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] with [`DeviceError::Unverified`] if the
+    /// device reports another value, or the errors of
+    /// [`set_color_temperature`](Self::set_color_temperature).
+    ///
+    /// [`DeviceError::Unverified`]: crate::error::DeviceError::Unverified
+    pub async fn set_color_temperature_verified(
+        &self,
+        value: ColorTemperature,
+    ) -> Result<ColorTemperatureResponse, Error> {
+        self.check_capability(
+            "color temperature",
+            self.capabilities.supports_color_temperature_control(),
+        )?;
+        let cmd = ColorTemperatureCommand::Set(value);
+        let parsed = self
+            .set_verified(
+                &cmd,
+                value.value(),
+                |response: &ColorTemperatureResponse| Some(response.color_temperature()),
+            )
+            .await?;
+
+        self.apply_color_temperature_response(&parsed);
+
+        Ok(parsed)
+    }
+
+    /// Gets the current color temperature.
+    ///
+    /// Returns a typed response including the current color temperature and power state.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device doesn't support color temperature or the command fails.
+    pub async fn get_color_temperature(&self) -> Result<ColorTemperatureResponse, Error> {
+        self.check_capability(
+            "color temperature",
+            self.capabilities.supports_color_temperature_control(),
+        )?;
+        let cmd = ColorTemperatureCommand::Get;
+        let response = self.send_command(&cmd).await?;
+        let parsed: ColorTemperatureResponse = response.parse().map_err(Error::Parse)?;
+
+        // Dispatch callbacks for state changes
+        self.apply_color_temperature_response(&parsed);
+
+        Ok(parsed)
+    }
+
+    /// Gets the current color temperature, or `None` if color temperature
+    /// is not supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Protocol`] if the device is unreachable or returns an invalid response.
+    pub async fn try_get_color_temperature(
+        &self,
+    ) -> Result<Option<ColorTemperatureResponse>, Error> {
+        if !self.capabilities.supports_color_temperature_control() {
+            return Ok(None);
+        }
+        self.get_color_temperature().await.map(Some)
+    }
+
+    /// Dispatches color temperature state changes from a response to callbacks.
+    fn apply_color_temperature_response(&self, response: &ColorTemperatureResponse) {
+        if let Ok(ct) = ColorTemperature::new(response.color_temperature()) {
+            let change = crate::state::StateChange::color_temperature(ct);
+            self.dispatch(&change);
+        }
+
+        if let Ok(Some(power)) = response.power_state() {
+            let change = crate::state::StateChange::power(1, power);
+            self.dispatch(&change);
+        }
+    }
+
+    /// Switches the light to its white channels at `level` brightness.
+    ///
+    /// Sends `White`, which sets the white channels of an RGBW or RGBCCT
+    /// light without touching the stored hue. Unlike
+    /// [`set_dimmer`](Self::set_dimmer), which scales the current output,
+    /// this replaces the RGB output: the color channels turn off, so a
+    /// previously set HSB color is no longer shown. Applications tracking
+    /// the light state should treat the light as white after this call.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] if the device doesn't support color
+    /// temperature (and so has no white channels), or error if the command
+    /// fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Dimmer;
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let response = device.set_white(Dimmer::new(50)?).await?;
+    /// println!("White at {}%", response.white());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_white(&self, level: Dimmer) -> Result<WhiteResponse, Error> {
+        self.check_capability(
+            "color temperature",
+            self.capabilities.supports_color_temperature_control(),
+        )?;
+        let cmd = WhiteCommand::Set(level);
+        let response = self.send_command(&cmd).await?;
+        let parsed: WhiteResponse = response.parse().map_err(Error::Parse)?;
+
+        if let Some(dimmer_value) = parsed.dimmer()
+            && let Ok(dimmer) = Dimmer::new(dimmer_value)
+        {
+            self.dispatch(&crate::state::StateChange::dimmer(dimmer));
+        }
+        if let Ok(Some(power)) = parsed.power_state() {
+            self.dispatch(&crate::state::StateChange::power(1, power));
+        }
+
+        Ok(parsed)
+    }
+
+    /// Gets the white channel brightness.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] if the device doesn't support color
+    /// temperature, or error if the command fails.
+    pub async fn get_white(&self) -> Result<WhiteResponse, Error> {
+        self.check_capability(
+            "color temperature",
+            self.capabilities.supports_color_temperature_control(),
+        )?;
+        let response = self.send_command(&WhiteCommand::Get).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    // ========== HSB Color ==========
+
+    /// Sets the HSB color.
+    ///
+    /// Returns a typed response including the new HSB color, dimmer level, and power state.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device doesn't support RGB or the command fails.
+    pub async fn set_hsb_color(&self, color: HsbColor) -> Result<HsbColorResponse, Error> {
+        self.check_capability("RGB color", self.capabilities.supports_rgb_control())?;
+        let cmd = HsbColorCommand::Set(color);
+        let response = self.send_command(&cmd).await?;
+        let parsed: HsbColorResponse = response.parse().map_err(Error::Parse)?;
+
+        // Dispatch callbacks for state changes
+        self.apply_hsb_color_response(&parsed);
+
+        Ok(parsed)
+    }
+
+    /// Gets the current HSB color.
+    ///
+    /// Returns a typed response including the current HSB color, dimmer level, and power state.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device doesn't support RGB or the command fails.
+    pub async fn get_hsb_color(&self) -> Result<HsbColorResponse, Error> {
+        self.check_capability("RGB color", self.capabilities.supports_rgb_control())?;
+        let cmd = HsbColorCommand::Get;
+        let response = self.send_command(&cmd).await?;
+        let parsed: HsbColorResponse = response.parse().map_err(Error::Parse)?;
+
+        // Dispatch callbacks for state changes
+        self.apply_hsb_color_response(&parsed);
+
+        Ok(parsed)
+    }
+
+    /// Gets the current HSB color, or `None` if RGB color is not supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Protocol`] if the device is unreachable or returns an invalid response.
+    pub async fn try_get_hsb_color(&self) -> Result<Option<HsbColorResponse>, Error> {
+        if !self.capabilities.supports_rgb_control() {
+            return Ok(None);
+        }
+        self.get_hsb_color().await.map(Some)
+    }
+
+    /// Sets only the hue, keeping saturation and brightness (`HSBColor1`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Value`] if `hue` is above 360, or an error if the
+    /// device doesn't support RGB or the command fails.
+    pub async fn set_hue(&self, hue: u16) -> Result<HsbColorResponse, Error> {
+        self.check_capability("RGB color", self.capabilities.supports_rgb_control())?;
+        if hue > HsbColor::MAX_HUE {
+            return Err(crate::error::ValueError::InvalidHue(hue).into());
+        }
+        let response = self.send_command(&HsbColorCommand::hue(hue)).await?;
+        let parsed: HsbColorResponse = response.parse().map_err(Error::Parse)?;
+
+        self.apply_hsb_color_response(&parsed);
+
+        Ok(parsed)
+    }
+
+    /// Sets only the saturation, keeping hue and brightness (`HSBColor2`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Value`] if `saturation` is above 100, or an error if
+    /// the device doesn't support RGB or the command fails.
+    pub async fn set_saturation(&self, saturation: u8) -> Result<HsbColorResponse, Error> {
+        self.check_capability("RGB color", self.capabilities.supports_rgb_control())?;
+        if saturation > HsbColor::MAX_SATURATION {
+            return Err(crate::error::ValueError::InvalidSaturation(saturation).into());
+        }
+        let response = self
+            .send_command(&HsbColorCommand::saturation(saturation))
+            .await?;
+        let parsed: HsbColorResponse = response.parse().map_err(Error::Parse)?;
+
+        self.apply_hsb_color_response(&parsed);
+
+        Ok(parsed)
+    }
+
+    /// Shifts the hue by `delta` degrees.
+    ///
+    /// Reads the current color and sets the hue to the sum, wrapping around
+    /// the color circle (350 + 20 gives 10). Saturation and brightness are
+    /// kept.
+    ///
+    /// Returns the hue the device reports afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device doesn't support RGB, or reading or
+    /// writing the color fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// // Rotate towards yellow
+    /// let hue = device.adjust_hue(30).await?;
+    /// println!("Hue is now {hue}°");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn adjust_hue(&self, delta: i16) -> Result<u16, Error> {
+        let current = self.get_hsb_color().await?.hue().map_err(Error::Parse)?;
+        let step = delta.rem_euclid(360).unsigned_abs();
+        let target = (current + step) % HsbColor::MAX_HUE;
+        let response = self.set_hue(target).await?;
+        response.hue().map_err(Error::Parse)
+    }
+
+    /// Changes the saturation by `delta` percentage points.
+    ///
+    /// Reads the current color and sets the saturation to the sum, clamped
+    /// to 0-100. Hue and brightness are kept.
+    ///
+    /// Returns the saturation the device reports afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device doesn't support RGB, or reading or
+    /// writing the color fails.
+    pub async fn adjust_saturation(&self, delta: i8) -> Result<u8, Error> {
+        let current = self
+            .get_hsb_color()
+            .await?
+            .saturation()
+            .map_err(Error::Parse)?;
+        let target = current
+            .saturating_add_signed(delta)
+            .min(HsbColor::MAX_SATURATION);
+        let response = self.set_saturation(target).await?;
+        response.saturation().map_err(Error::Parse)
+    }
+
+    /// Dispatches HSB color state changes from a response to callbacks.
+    fn apply_hsb_color_response(&self, response: &HsbColorResponse) {
+        if let Ok(color) = response.hsb_color() {
+            let change = crate::state::StateChange::hsb_color(color);
+            self.dispatch(&change);
+        }
+
+        if let Some(dimmer_value) = response.dimmer()
+            && let Ok(dimmer) = Dimmer::new(dimmer_value)
+        {
+            let change = crate::state::StateChange::dimmer(dimmer);
+            self.dispatch(&change);
+        }
+
+        if let Ok(Some(power)) = response.power_state() {
+            let change = crate::state::StateChange::power(1, power);
+            self.dispatch(&change);
+        }
+    }
+
+    // ========== RGB Color ==========
+
+    /// Sets the RGB color.
+    ///
+    /// This is a convenience method that converts the RGB color to HSB internally
+    /// and sends an `HSBColor` command to the device. The response contains both
+    /// the RGB and HSB representations.
+    ///
+    /// HSB values are whole degrees and percents, so the color Tasmota ends up
+    /// driving may differ slightly from `color`; the response still reports
+    /// `color` itself. Use [`set_color_hex`](Self::set_color_hex) to send the
+    /// exact channel values instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device doesn't support RGB or the command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::RgbColor;
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// // Set color using hex string
+    /// let color = RgbColor::from_hex("#FF5733")?;
+    /// let response = device.set_rgb_color(color).await?;
+    /// println!("Color set to: {}", response.to_hex_with_hash());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_rgb_color(&self, color: RgbColor) -> Result<RgbColorResponse, Error> {
+        self.check_capability("RGB color", self.capabilities.supports_rgb_control())?;
+
+        // Convert RGB to HSB and send the command
+        let hsb = color.to_hsb();
+        let cmd = HsbColorCommand::Set(hsb);
+        let response = self.send_command(&cmd).await?;
+        let hsb_response: HsbColorResponse = response.parse().map_err(Error::Parse)?;
+
+        // Dispatch callbacks for state changes
+        self.apply_hsb_color_response(&hsb_response);
+
+        // Create RGB response preserving the original RGB value
+        let returned_hsb = hsb_response.hsb_color().unwrap_or(hsb);
+        Ok(RgbColorResponse::new(color, returned_hsb))
+    }
+
+    /// Sets the RGB color from its exact channel values.
+    ///
+    /// Sends a `Color #RRGGBB` command, so the light drives exactly the given
+    /// red, green and blue levels, without the rounding of the HSB conversion
+    /// done by [`set_rgb_color`](Self::set_rgb_color). The response is parsed
+    /// from the `Color` echo and reports the color Tasmota actually applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device doesn't support RGB or the command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::RgbColor;
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let color = RgbColor::from_hex("#FF5733")?;
+    /// let response = device.set_color_hex(color).await?;
+    /// println!("Color set to: {}", response.to_hex_with_hash());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_color_hex(&self, color: RgbColor) -> Result<RgbColorResponse, Error> {
+        self.check_capability("RGB color", self.capabilities.supports_rgb_control())?;
+
+        let cmd = ColorCommand::SetRgb(color);
+        let response = self.send_command(&cmd).await?;
+        let color_response: RgbColorResponse = response.parse().map_err(Error::Parse)?;
+
+        self.dispatch(&crate::state::StateChange::hsb_color(
+            color_response.hsb_color(),
+        ));
+        if let Some(dimmer_value) = color_response.dimmer()
+            && let Ok(dimmer) = Dimmer::new(dimmer_value)
+        {
+            self.dispatch(&crate::state::StateChange::dimmer(dimmer));
+        }
+        if let Some(power) = color_response.power_state() {
+            self.dispatch(&crate::state::StateChange::power(1, power));
+        }
+
+        Ok(color_response)
+    }
+
+    /// Sets all five channels of an RGBCCT light at once.
+    ///
+    /// Sends a `Color` command with the red, green, blue, cold white and
+    /// warm white levels, so color and white can be mixed in one step.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device doesn't drive both RGB and white channels
+    /// or the command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::RgbwwColor;
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// // Red mixed with half warm white
+    /// let color = RgbwwColor::new(255, 0, 0, 0, 128);
+    /// let response = device.set_rgbww_color(color).await?;
+    /// println!("Channels: {}", response.raw());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_rgbww_color(&self, color: RgbwwColor) -> Result<RgbwwColorResponse, Error> {
+        self.check_capability("RGBWW color", self.capabilities.supports_rgbww())?;
+
+        let cmd = ColorCommand::Set(color);
+        let response = self.send_command(&cmd).await?;
+        let color_response: RgbwwColorResponse = response.parse().map_err(Error::Parse)?;
+
+        if let Some(dimmer_value) = color_response.dimmer()
+            && let Ok(dimmer) = Dimmer::new(dimmer_value)
+        {
+            self.dispatch(&crate::state::StateChange::dimmer(dimmer));
+        }
+        if let Ok(Some(power)) = color_response.power_state() {
+            self.dispatch(&crate::state::StateChange::power(1, power));
+        }
+
+        Ok(color_response)
+    }
+
+    /// Sets a single light channel (`Channel<x>`).
+    ///
+    /// Channels are the raw outputs of the light, so this can drive the
+    /// cold and warm white channels of an RGBCCT controller independently
+    /// (`Channel4`, `Channel5`), which neither `HSBColor` nor `CT` can.
+    /// Setting a channel turns the light on.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`](crate::ValueError::OutOfRange) if
+    /// `index` is not 1-5, [`Error::Device`] if the device doesn't support
+    /// dimming, or error if the command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Dimmer;
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// // Cold white at 80%, warm white off
+    /// device.set_channel(4, Dimmer::new(80)?).await?;
+    /// let response = device.set_channel(5, Dimmer::new(0)?).await?;
+    /// println!("Channels: {:?}", response.channels());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_channel(&self, index: u8, value: Dimmer) -> Result<ChannelResponse, Error> {
+        self.check_capability("dimmer", self.capabilities.supports_dimmer_control())?;
+        let cmd = ChannelCommand::set(index, value.value())?;
+        let response = self.send_command(&cmd).await?;
+        let parsed: ChannelResponse = response.parse().map_err(Error::Parse)?;
+
+        if let Some(dimmer_value) = parsed.dimmer()
+            && let Ok(dimmer) = Dimmer::new(dimmer_value)
+        {
+            self.dispatch(&crate::state::StateChange::dimmer(dimmer));
+        }
+        if let Ok(Some(power)) = parsed.power_state() {
+            self.dispatch(&crate::state::StateChange::power(1, power));
+        }
+
+        Ok(parsed)
+    }
+
+    /// Gets the light channels (`Channel<x>`).
+    ///
+    /// Tasmota reports every channel at once; use
+    /// [`ChannelResponse::channel`] to read the one queried.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`](crate::ValueError::OutOfRange) if
+    /// `index` is not 1-5, [`Error::Device`] if the device doesn't support
+    /// dimming, or error if the command fails.
+    pub async fn get_channel(&self, index: u8) -> Result<ChannelResponse, Error> {
+        self.check_capability("dimmer", self.capabilities.supports_dimmer_control())?;
+        let cmd = ChannelCommand::get(index)?;
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Infers the kind of light fixture from a `Color` query.
+    ///
+    /// The reply reveals the channels the light drives: its `Channel`
+    /// array (or `Color` length) gives the channel count, and `HSBColor`
+    /// and `CT` are only reported by lights with RGB and tunable white
+    /// channels. See [`RgbwwColorResponse::light_type`].
+    ///
+    /// Unlike most methods, this does not check the capabilities first, as
+    /// it is meant to refine them when they are uncertain. A device that
+    /// is not a light, or a reply whose fields contradict each other,
+    /// gives [`LightType::Unknown`](crate::types::LightType::Unknown).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Protocol`] or [`Error::Parse`] if the query fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::LightType;
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// if device.detect_light_type().await? == LightType::Rgbcct {
+    ///     println!("Color and tunable white");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn detect_light_type(&self) -> Result<crate::types::LightType, Error> {
+        let response = self.send_command(&ColorCommand::Get).await?;
+        let color_response: RgbwwColorResponse = response.parse().map_err(Error::Parse)?;
+        let light_type = color_response.light_type();
+        tracing::debug!(%light_type, "Detected light type");
+        Ok(light_type)
+    }
+
+    /// Sets a color, sending near-white colors as a color temperature.
+    ///
+    /// Mixing white from RGB channels gives muddy whites on fixtures that
+    /// also have white LEDs. If the device supports color temperature and
+    /// [`HsbColor::as_color_temperature`] recognizes `color` as white, this
+    /// sends `CT` and `Dimmer` (the color's brightness) instead of
+    /// `HSBColor`. Any other color is sent with `HSBColor`.
+    ///
+    /// Both are sent as a single routine.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the color needs a capability the device lacks (RGB
+    /// for colors, dimmer for whites) or the command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::HsbColor;
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// // Warm white from a color picker: sent as CT 250 and Dimmer 80
+    /// device.set_color_smart(HsbColor::new(30, 36, 80)?).await?;
+    ///
+    /// // Saturated color: sent as HSBColor
+    /// device.set_color_smart(HsbColor::new(240, 100, 80)?).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_color_smart(
+        &self,
+        color: HsbColor,
+    ) -> Result<crate::response::RoutineResponse, Error> {
+        let white = color
+            .as_color_temperature()
+            .filter(|_| self.capabilities.supports_color_temperature_control());
+
+        let builder = crate::command::Routine::builder();
+        let routine = if let Some(ct) = white {
+            self.check_capability("dimmer", self.capabilities.supports_dimmer_control())?;
+            builder
+                .set_color_temperature(ct)
+                .set_dimmer(Dimmer::new(color.brightness())?)
+                .build()?
+        } else {
+            self.check_capability("RGB color", self.capabilities.supports_rgb_control())?;
+            builder.set_hsb_color(color).build()?
+        };
+
+        self.run(&routine).await
+    }
+
+    /// Changes the color with a fade of the given duration.
+    ///
+    /// The duration is rounded to the nearest 0.5 second (0.5-20 seconds).
+    /// On Tasmota 9.2 and newer, the fade is sent with the one-shot `Fade2`
+    /// and `Speed2` commands, leaving the device's fade settings untouched.
+    /// Older or unrecognized firmware falls back to `Fade 1` and `Speed`,
+    /// which stay in effect for later light changes.
+    ///
+    /// Tasmota scales the duration to the size of the change unless
+    /// `SetOption117` is enabled, in which case every change takes the full
+    /// duration.
+    ///
+    /// The firmware version is queried with `Status 2` before each change.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device doesn't support RGB, the duration is out
+    /// of range, or a command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tasmor_lib::HsbColor;
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// device
+    ///     .set_color_with_transition(HsbColor::blue(), Duration::from_secs(3))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_color_with_transition(
+        &self,
+        color: HsbColor,
+        duration: Duration,
+    ) -> Result<crate::response::RoutineResponse, Error> {
+        self.check_capability("RGB color", self.capabilities.supports_rgb_control())?;
+        let speed = FadeDuration::new(duration)?;
+
+        let status: StatusResponse = self
+            .send_command(&StatusCommand::firmware())
+            .await?
+            .parse()
+            .map_err(Error::Parse)?;
+        let one_shot = status
+            .firmware
+            .as_ref()
+            .and_then(crate::response::StatusFirmware::parsed_version)
+            .is_some_and(|version| version >= ONE_SHOT_TRANSITION_VERSION);
+
+        let builder = crate::command::Routine::builder();
+        let routine = if one_shot {
+            builder
+                .add_command(&TransitionCommand::Enable)
+                .add_command(&TransitionCommand::Speed(speed))
+                .set_hsb_color(color)
+                .build()?
+        } else {
+            builder
+                .enable_fade()
+                .set_fade_duration(speed)
+                .set_hsb_color(color)
+                .build()?
+        };
+
+        self.run(&routine).await
+    }
+
+    // ========== Scheme ==========
+
+    /// Sets the light scheme/effect.
+    ///
+    /// Tasmota supports several built-in light schemes:
+    /// - 0: Single (fixed color, default)
+    /// - 1: Wakeup (gradual brightness increase, uses [`WakeupDuration`])
+    /// - 2: Cycle Up (color cycling with increasing brightness)
+    /// - 3: Cycle Down (color cycling with decreasing brightness)
+    /// - 4: Random (random color changes)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Scheme;
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// // Set wakeup scheme
+    /// device.set_scheme(Scheme::WAKEUP).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_scheme(&self, scheme: Scheme) -> Result<SchemeResponse, Error> {
+        let cmd = SchemeCommand::Set(scheme);
+        let response = self.send_command(&cmd).await?;
+        let parsed: SchemeResponse = response.parse().map_err(Error::Parse)?;
+
+        // Dispatch callbacks for state changes
+        if let Ok(s) = parsed.scheme() {
+            let change = crate::state::StateChange::scheme(s);
+            self.dispatch(&change);
+        }
+
+        Ok(parsed)
+    }
+
+    /// Gets the current light scheme.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn get_scheme(&self) -> Result<SchemeResponse, Error> {
+        let cmd = SchemeCommand::Get;
+        let response = self.send_command(&cmd).await?;
+        let parsed: SchemeResponse = response.parse().map_err(Error::Parse)?;
+
+        // Dispatch callbacks for state changes
+        if let Ok(s) = parsed.scheme() {
+            let change = crate::state::StateChange::scheme(s);
+            self.dispatch(&change);
+        }
+
+        Ok(parsed)
+    }
+
+    // ========== Wakeup Duration ==========
+
+    /// Sets the wakeup duration.
+    ///
+    /// The wakeup duration controls how long Scheme 1 (Wakeup) takes to
+    /// gradually increase brightness from 0 to the current dimmer level.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tasmor_lib::WakeupDuration;
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// // Set wakeup duration to 5 minutes
+    /// let duration = WakeupDuration::new(Duration::from_secs(300))?;
+    /// device.set_wakeup_duration(duration).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_wakeup_duration(
+        &self,
+        duration: WakeupDuration,
+    ) -> Result<WakeupDurationResponse, Error> {
+        let cmd = WakeupDurationCommand::Set(duration);
+        let response = self.send_command(&cmd).await?;
+        let parsed: WakeupDurationResponse = response.parse().map_err(Error::Parse)?;
+
+        // Dispatch callbacks for state changes
+        if let Ok(d) = parsed.duration() {
+            let change = crate::state::StateChange::wakeup_duration(d);
+            self.dispatch(&change);
+        }
+
+        Ok(parsed)
+    }
+
+    /// Gets the current wakeup duration.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn get_wakeup_duration(&self) -> Result<WakeupDurationResponse, Error> {
+        let cmd = WakeupDurationCommand::Get;
+        let response = self.send_command(&cmd).await?;
+        let parsed: WakeupDurationResponse = response.parse().map_err(Error::Parse)?;
+
+        // Dispatch callbacks for state changes
+        if let Ok(d) = parsed.duration() {
+            let change = crate::state::StateChange::wakeup_duration(d);
+            self.dispatch(&change);
+        }
+
+        Ok(parsed)
+    }
+
+    /// Runs a gradual sunrise towards `target` over `duration`.
+    ///
+    /// The native wakeup scheme only ramps brightness. On color fixtures,
+    /// this instead runs a single routine that enables fade and steps
+    /// both brightness and color, starting at 1% in a warm tone:
+    ///
+    /// - if `target` has an HSB color, from deep orange to that color (RGB
+    ///   fixtures, up to 13 steps); a target dimmer overrides the color's
+    ///   brightness;
+    /// - otherwise on color temperature fixtures, from candle light
+    ///   (500 mireds) to the target color temperature, or
+    ///   [`ColorTemperature::NEUTRAL`] if unset (up to 8 steps).
+    ///
+    /// Steps are at least 1 second apart; the delay between them is rounded
+    /// down to 100ms. The device's `Fade` and `Speed` settings are left
+    /// changed afterwards.
+    ///
+    /// On fixtures with only a dimmer, this falls back to the native
+    /// [`Scheme::WAKEUP`]: `WakeupDuration`, the target `Dimmer` and
+    /// `Scheme 1`. The target dimmer defaults to 100%.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device has no dimmer, `target` needs a
+    /// capability the device lacks, the duration is outside the
+    /// [`WakeupDuration`] range on the native fallback, or the command
+    /// fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tasmor_lib::state::LightState;
+    /// use tasmor_lib::{ColorTemperature, Dimmer};
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let morning = LightState::new()
+    ///     .with_color_temperature(ColorTemperature::COOL)
+    ///     .with_dimmer(Dimmer::new(80)?);
+    /// device.sunrise(Duration::from_secs(15 * 60), morning).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn sunrise(
+        &self,
+        duration: Duration,
+        target: LightState,
+    ) -> Result<crate::response::RoutineResponse, Error> {
+        self.check_capability("dimmer", self.capabilities.supports_dimmer_control())?;
+        let dimmer = target.dimmer();
+
+        let routine = if let Some(color) = target.hsb_color() {
+            self.check_capability("RGB color", self.capabilities.supports_rgb_control())?;
+            let color = match dimmer {
+                Some(dimmer) => color.with_brightness(dimmer.value())?,
+                None => color,
+            };
+            sunrise::color_sunrise(duration, color)?
+        } else if let Some(ct) = target.color_temperature() {
+            self.check_capability(
+                "color temperature",
+                self.capabilities.supports_color_temperature_control(),
+            )?;
+            sunrise::white_sunrise(duration, ct, dimmer.unwrap_or(Dimmer::MAX))?
+        } else if self.capabilities.supports_color_temperature_control() {
+            sunrise::white_sunrise(
+                duration,
+                ColorTemperature::NEUTRAL,
+                dimmer.unwrap_or(Dimmer::MAX),
+            )?
+        } else {
+            crate::command::Routine::builder()
+                .set_wakeup_duration(WakeupDuration::new(duration)?)
+                .set_dimmer(dimmer.unwrap_or(Dimmer::MAX))
+                .set_scheme(Scheme::WAKEUP)
+                .build()?
+        };
+
+        self.run(&routine).await
+    }
+
+    // ========== Fade ==========
+
+    /// Enables fade transitions.
+    ///
+    /// Returns a typed response indicating whether fade is now enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn enable_fade(&self) -> Result<FadeResponse, Error> {
+        let cmd = FadeCommand::Enable;
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Disables fade transitions.
+    ///
+    /// Returns a typed response indicating whether fade is now disabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn disable_fade(&self) -> Result<FadeResponse, Error> {
+        let cmd = FadeCommand::Disable;
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Gets the current fade setting.
+    ///
+    /// Returns a typed response indicating whether fade is enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn get_fade(&self) -> Result<FadeResponse, Error> {
+        let cmd = FadeCommand::Get;
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Sets the fade transition duration.
+    ///
+    /// Returns a typed response with the new duration value.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn set_fade_duration(
+        &self,
+        duration: FadeDuration,
+    ) -> Result<FadeDurationResponse, Error> {
+        let cmd = FadeDurationCommand::Set(duration);
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Gets the current fade duration setting.
+    ///
+    /// Returns a typed response with the current duration value.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn get_fade_duration(&self) -> Result<FadeDurationResponse, Error> {
+        let cmd = FadeDurationCommand::Get;
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Enables fade at startup.
+    ///
+    /// Returns a typed response indicating whether startup fade is now enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn enable_fade_at_startup(&self) -> Result<StartupFadeResponse, Error> {
+        let cmd = StartupFadeCommand::Enable;
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Disables fade at startup.
+    ///
+    /// Returns a typed response indicating whether startup fade is now disabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn disable_fade_at_startup(&self) -> Result<StartupFadeResponse, Error> {
+        let cmd = StartupFadeCommand::Disable;
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Gets the current fade at startup setting.
+    ///
+    /// Returns a typed response indicating whether startup fade is enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn get_fade_at_startup(&self) -> Result<StartupFadeResponse, Error> {
+        let cmd = StartupFadeCommand::Get;
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    // ========== Light Defaults ==========
+
+    /// Applies light behavior settings.
+    ///
+    /// Only the settings set in `defaults` are sent, as one backlog of
+    /// `SetOption` commands; see [`LightDefaults`] for the option each
+    /// setting maps to. Does nothing if no setting is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::{Device, LightDefaults};
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (device, _) = Device::http("192.168.1.100").build().await?;
+    ///
+    /// // Let the UI preset a color while the light is off
+    /// let defaults = LightDefaults::new().with_power_on_with_change(false);
+    /// device.apply_light_defaults(&defaults).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn apply_light_defaults(&self, defaults: &LightDefaults) -> Result<(), Error> {
+        let mut builder = crate::command::Routine::builder();
+        for cmd in defaults.commands() {
+            builder = builder.add_command(&cmd);
+        }
+
+        if builder.is_empty() {
+            return Ok(());
+        }
+
+        let routine = builder.build()?;
+        self.run(&routine).await?;
+        Ok(())
+    }
+
+    /// Reads the light behavior settings.
+    ///
+    /// Settings the firmware does not report are `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails or the `SetOption` dump cannot be
+    /// parsed.
+    pub async fn read_light_defaults(&self) -> Result<LightDefaults, Error> {
+        let flags = self.get_all_set_options().await?;
+        Ok(LightDefaults::from_set_options(&flags))
+    }
+
+    // ========== Raw PWM ==========
+
+    /// Sets the duty cycle of a raw PWM output.
+    ///
+    /// `value` ranges from 0 (off) to 1023 (always on) with Tasmota's default
+    /// `PWMRange`. This drives GPIOs configured as `PWM<n>` directly, for
+    /// fans, pumps or analog interfaces; use the dimmer and color methods
+    /// for lights.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device has no [raw PWM outputs](Capabilities::supports_raw_pwm),
+    /// [`Error::Value`] if `channel` exceeds the device's PWM outputs or
+    /// `value` exceeds 1023, or an error if the command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::{CapabilitiesBuilder, Device};
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (fan, _) = Device::http("192.168.1.100")
+    ///     .with_capabilities(CapabilitiesBuilder::new().pwm_channels(1).build())
+    ///     .build()
+    ///     .await?;
+    ///
+    /// // Half speed
+    /// fan.set_pwm(1, 512).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_pwm(&self, channel: u8, value: u16) -> Result<PwmResponse, Error> {
+        self.check_capability("raw PWM", self.capabilities.supports_raw_pwm())?;
+        let cmd = PwmCommand::set(channel, value)?;
+        self.check_pwm_channel(channel)?;
+
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Gets the duty cycle of a raw PWM output.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the device has no [raw PWM outputs](Capabilities::supports_raw_pwm),
+    /// [`Error::Value`] if `channel` exceeds the device's PWM outputs, or an
+    /// error if the command fails or the device does not report the output.
+    pub async fn get_pwm(&self, channel: u8) -> Result<u16, Error> {
+        self.check_capability("raw PWM", self.capabilities.supports_raw_pwm())?;
+        let cmd = PwmCommand::get(channel)?;
+        self.check_pwm_channel(channel)?;
+
+        let response: PwmResponse = self
+            .send_command(&cmd)
+            .await?
+            .parse()
+            .map_err(Error::Parse)?;
+        response
+            .value(channel)
+            .ok_or_else(|| Error::Parse(ParseError::MissingField(format!("PWM{channel}"))))
+    }
+
+    /// Checks that `channel` is one of the device's PWM outputs.
+    fn check_pwm_channel(&self, channel: u8) -> Result<(), Error> {
+        let available = self.capabilities.pwm_channels();
+        if channel > available {
+            return Err(Error::Value(crate::error::ValueError::OutOfRange {
+                min: 1,
+                max: available.into(),
+                actual: channel.into(),
+            }));
+        }
+        Ok(())
+    }
+
+    // ========== Device Name ==========
+
+    /// Sets the device name.
+    ///
+    /// The device name identifies the device as a whole, in the web UI,
+    /// Home Assistant discovery and MQTT. It is separate from the
+    /// `FriendlyName` labels of the relays.
+    ///
+    /// Returns the name reported by the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the name is empty or longer than
+    /// [`MAX_DEVICE_NAME_LENGTH`](crate::command::MAX_DEVICE_NAME_LENGTH)
+    /// characters, or the command fails.
+    pub async fn set_device_name(&self, name: &str) -> Result<String, Error> {
+        let cmd = DeviceNameCommand::set(name)?;
+        self.device_name(&cmd).await
+    }
+
+    /// Gets the device name.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn get_device_name(&self) -> Result<String, Error> {
+        self.device_name(&DeviceNameCommand::Get).await
+    }
+
+    /// Sends a `DeviceName` command and returns the reported name.
+    async fn device_name(&self, cmd: &DeviceNameCommand) -> Result<String, Error> {
+        let response: DeviceNameResponse = self
+            .send_command(cmd)
+            .await?
+            .parse()
+            .map_err(Error::Parse)?;
+        Ok(response.device_name().to_string())
+    }
+
+    // ========== Web UI ==========
+
+    /// Sets the web UI color theme.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Device;
+    /// use tasmor_lib::types::{RgbColor, WebColors};
+    ///
+    /// # async fn example(device: &Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// // Dark theme with a custom button color
+    /// let mut colors = *WebColors::dark().colors();
+    /// colors[10] = RgbColor::from_hex("#8e44ad")?;
+    /// device.set_web_colors(WebColors::new(colors)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_web_colors(&self, colors: WebColors) -> Result<WebColorResponse, Error> {
+        let cmd = WebColorCommand::Set(colors);
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Gets the current web UI color theme.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn get_web_colors(&self) -> Result<WebColorResponse, Error> {
+        let cmd = WebColorCommand::Get;
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Applies Tasmota's default dark web UI theme.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn set_dark_theme(&self) -> Result<WebColorResponse, Error> {
+        self.set_web_colors(WebColors::dark()).await
+    }
+
+    /// Applies the light web UI theme.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn set_light_theme(&self) -> Result<WebColorResponse, Error> {
+        self.set_web_colors(WebColors::light()).await
+    }
+
+    // ========== Security ==========
+
+    /// Sets the web UI password.
+    ///
+    /// Once the device accepts the password, it is required for every HTTP
+    /// request; HTTP devices switch to the new password automatically so
+    /// subsequent commands keep working.
+    ///
+    /// **Warning:** an empty password disables authentication for the web UI
+    /// and the HTTP API.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Value`] if the password is not accepted by
+    /// [`validate_password`](crate::command::validate_password), or an error
+    /// if the command fails or the device does not confirm the change.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Device;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (device, _) = Device::http("192.168.1.100").build().await?;
+    /// device.set_web_password("s3cret").await?;
+    ///
+    /// // Sent with the new password
+    /// device.power_on().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_web_password(&self, password: &str) -> Result<SecurityResponse, Error> {
+        let cmd = SecurityCommand::web_password(password)?;
+        if password.is_empty() {
+            tracing::warn!("Clearing web password disables web UI and HTTP API authentication");
+        }
+
+        let response = self.send_command(&cmd).await?;
+        let parsed: SecurityResponse = response.parse().map_err(Error::Parse)?;
+        if parsed.web_password().is_none() {
+            return Err(Error::Parse(ParseError::MissingField(
+                "WebPassword".to_string(),
+            )));
+        }
+
+        if !response.is_dry_run() {
+            self.protocol.web_password_changed(password);
+        }
+        Ok(parsed)
+    }
+
+    /// Sets the password the device uses to connect to its MQTT broker.
+    ///
+    /// The device reconnects to the broker with the new password. An empty
+    /// password makes it connect without one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Value`] if the password is not accepted by
+    /// [`validate_password`](crate::command::validate_password), or an error
+    /// if the command fails or the device does not confirm the change.
+    pub async fn set_mqtt_password(&self, password: &str) -> Result<SecurityResponse, Error> {
+        let cmd = SecurityCommand::mqtt_password(password)?;
+        if password.is_empty() {
+            tracing::warn!("Clearing MQTT password, device will connect without authentication");
+        }
+
+        let response = self.send_command(&cmd).await?;
+        let parsed: SecurityResponse = response.parse().map_err(Error::Parse)?;
+        if parsed.mqtt_password().is_none() {
+            return Err(Error::Parse(ParseError::MissingField(
+                "MqttPassword".to_string(),
+            )));
+        }
+        Ok(parsed)
+    }
+
+    /// Sets the origin allowed to make cross-origin requests to the device.
+    ///
+    /// Use `*` to allow any origin, or an empty string to disable CORS.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails or the device does not confirm
+    /// the change.
+    pub async fn set_cors(&self, origin: &str) -> Result<SecurityResponse, Error> {
+        let cmd = SecurityCommand::cors(origin);
+        let response = self.send_command(&cmd).await?;
+        let parsed: SecurityResponse = response.parse().map_err(Error::Parse)?;
+        if parsed.cors().is_none() {
+            return Err(Error::Parse(ParseError::MissingField("CORS".to_string())));
+        }
+        Ok(parsed)
+    }
+
+    /// Gets the origin allowed to make cross-origin requests to the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn get_cors(&self) -> Result<SecurityResponse, Error> {
+        let cmd = SecurityCommand::GetCors;
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    // ========== MQTT Topics ==========
+
+    /// Reads the device's MQTT topic layout (`FullTopic` and
+    /// `Prefix1`-`Prefix3`).
+    ///
+    /// Pass the result to
+    /// [`BrokerDeviceBuilder::with_topic_scheme`](crate::device::BrokerDeviceBuilder::with_topic_scheme)
+    /// to reach a device whose `FullTopic` is not the default. An MQTT device
+    /// can only be queried once its scheme is known, so this is typically
+    /// called on an HTTP device during provisioning.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::{Device, MqttBroker};
+    ///
+    /// # async fn example(broker: &MqttBroker) -> tasmor_lib::Result<()> {
+    /// let (http_device, _) = Device::http("192.168.1.100").build().await?;
+    /// let scheme = http_device.topic_scheme().await?;
+    ///
+    /// let (device, _) = broker
+    ///     .device("tasmota_kitchen")
+    ///     .with_topic_scheme(scheme)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a query fails, or [`Error::Value`] if the device
+    /// reports a template this crate cannot route (e.g. one using
+    /// `%hostname%`).
+    pub async fn topic_scheme(&self) -> Result<TopicScheme, Error> {
+        let response: TopicResponse = self
+            .send_command(&TopicCommand::FullTopic)
+            .await?
+            .parse()
+            .map_err(Error::Parse)?;
+        let full_topic = response
+            .full_topic()
+            .ok_or_else(|| Error::Parse(ParseError::MissingField("FullTopic".to_string())))?;
+        let mut scheme = TopicScheme::new(full_topic)?;
+
+        for prefix in TopicPrefix::ALL {
+            let response: TopicResponse = self
+                .send_command(&TopicCommand::Prefix(prefix))
+                .await?
+                .parse()
+                .map_err(Error::Parse)?;
+            if let Some(value) = response.prefix(prefix) {
+                scheme = scheme.with_prefix(prefix, value)?;
+            }
+        }
+        Ok(scheme)
+    }
+
+    /// Reads the device's first group topic (default `tasmotas`).
+    ///
+    /// Devices sharing a group topic can be controlled together with
+    /// [`MqttBroker::group_command`](crate::MqttBroker::group_command).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails or the reply has no group topic.
+    pub async fn group_topic(&self) -> Result<String, Error> {
+        let response: TopicResponse = self
+            .send_command(&TopicCommand::GroupTopic)
+            .await?
+            .parse()
+            .map_err(Error::Parse)?;
+        response
+            .group_topic()
+            .map(str::to_string)
+            .ok_or_else(|| Error::Parse(ParseError::MissingField("GroupTopic1".to_string())))
+    }
 
-        // Dispatch callbacks for state changes
-        self.apply_dimmer_response(&parsed);
+    /// Reads the topic the device's buttons publish to.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails or the reply has no button topic.
+    pub async fn button_topic(&self) -> Result<RemoteTopic, Error> {
+        self.remote_topic(RemoteTopicCommand::Get(RemoteInput::Button))
+            .await
+    }
 
-        Ok(parsed)
+    /// Sets the topic the device's buttons publish to.
+    ///
+    /// With a topic set, pressing a button publishes a `POWER` toggle to that
+    /// topic instead of switching the device's own relay, so a wall switch
+    /// can drive lights elsewhere without a controller. Pass
+    /// [`RemoteTopic::Disabled`] to couple the buttons to the local relays
+    /// again.
+    ///
+    /// Returns the topic reported by the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails or the reply has no button topic.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::types::RemoteTopic;
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// // Make the wall switch toggle every light in the "hall_lights" group
+    /// device.set_button_topic(RemoteTopic::new("hall_lights")?).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_button_topic(&self, topic: RemoteTopic) -> Result<RemoteTopic, Error> {
+        self.remote_topic(RemoteTopicCommand::Set(RemoteInput::Button, topic))
+            .await
     }
 
-    /// Dispatches dimmer state changes from a response to callbacks.
-    fn apply_dimmer_response(&self, response: &DimmerResponse) {
-        if let Ok(dimmer) = Dimmer::new(response.dimmer()) {
-            let change = crate::state::StateChange::dimmer(dimmer);
-            self.callbacks.dispatch(&change);
-        }
+    /// Reads the topic the device's switches publish to.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails or the reply has no switch topic.
+    pub async fn switch_topic(&self) -> Result<RemoteTopic, Error> {
+        self.remote_topic(RemoteTopicCommand::Get(RemoteInput::Switch))
+            .await
+    }
 
-        if let Ok(Some(power)) = response.power_state() {
-            let change = crate::state::StateChange::power(1, power);
-            self.callbacks.dispatch(&change);
-        }
+    /// Sets the topic the device's switches publish to.
+    ///
+    /// Works like [`set_button_topic`](Self::set_button_topic) for inputs
+    /// configured as switches: state changes are published to the topic
+    /// instead of switching the local relay.
+    ///
+    /// Returns the topic reported by the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails or the reply has no switch topic.
+    pub async fn set_switch_topic(&self, topic: RemoteTopic) -> Result<RemoteTopic, Error> {
+        self.remote_topic(RemoteTopicCommand::Set(RemoteInput::Switch, topic))
+            .await
     }
 
-    // ========== Color Temperature ==========
+    /// Sends a `ButtonTopic` or `SwitchTopic` command and reads the reply.
+    async fn remote_topic(&self, cmd: RemoteTopicCommand) -> Result<RemoteTopic, Error> {
+        let response: TopicResponse = self
+            .send_command(&cmd)
+            .await?
+            .parse()
+            .map_err(Error::Parse)?;
+        let (RemoteTopicCommand::Get(input) | RemoteTopicCommand::Set(input, _)) = &cmd;
+        let topic = match input {
+            RemoteInput::Button => response.button_topic(),
+            RemoteInput::Switch => response.switch_topic(),
+        };
+        topic.ok_or_else(|| Error::Parse(ParseError::MissingField(cmd.name())))
+    }
 
-    /// Sets the color temperature.
+    // ========== Time Synchronization ==========
+
+    /// Reads the NTP servers and whether the device clock is synchronized.
     ///
-    /// Returns a typed response including the new color temperature and power state.
+    /// Sends `NtpServer1`-`NtpServer3` and `Status 7`. The clock counts as
+    /// synchronized when the device reports a plausible current date (see
+    /// [`NtpConfig::time_synced`]); timers and schedules are unreliable
+    /// until it is.
     ///
     /// # Errors
     ///
-    /// Returns error if the device doesn't support color temperature or the command fails.
-    pub async fn set_color_temperature(
-        &self,
-        value: ColorTemperature,
-    ) -> Result<ColorTemperatureResponse, Error> {
-        self.check_capability(
-            "color temperature",
-            self.capabilities.supports_color_temperature_control(),
-        )?;
-        let cmd = ColorTemperatureCommand::Set(value);
-        let response = self.send_command(&cmd).await?;
-        let parsed: ColorTemperatureResponse = response.parse().map_err(Error::Parse)?;
+    /// Returns error if a query fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let ntp = device.get_ntp_config().await?;
+    /// if !ntp.time_synced() {
+    ///     println!("Clock not synced, servers: {:?}", ntp.servers().collect::<Vec<_>>());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_ntp_config(&self) -> Result<NtpConfig, Error> {
+        let mut servers = Vec::new();
+        for index in 1..=crate::command::MAX_NTP_SERVERS {
+            let cmd = NtpServerCommand::get(index)?;
+            let response: NtpServerResponse = self
+                .send_command(&cmd)
+                .await?
+                .parse()
+                .map_err(Error::Parse)?;
+            servers.push(response.server(index).map(str::to_string));
+        }
 
-        // Dispatch callbacks for state changes
-        self.apply_color_temperature_response(&parsed);
+        let status: StatusResponse = self
+            .send_command(&StatusCommand::time())
+            .await?
+            .parse()
+            .map_err(Error::Parse)?;
+        let utc = status.time.map(|time| time.utc).unwrap_or_default();
 
-        Ok(parsed)
+        Ok(NtpConfig::new(servers, &utc))
     }
 
-    /// Gets the current color temperature.
+    /// Sets one of the device's NTP servers (index 1-3).
     ///
-    /// Returns a typed response including the current color temperature and power state.
+    /// The device resynchronizes its clock with the new server. Use
+    /// [`get_ntp_config`](Self::get_ntp_config) afterwards to confirm it
+    /// synced.
     ///
     /// # Errors
     ///
-    /// Returns error if the device doesn't support color temperature or the command fails.
-    pub async fn get_color_temperature(&self) -> Result<ColorTemperatureResponse, Error> {
-        self.check_capability(
-            "color temperature",
-            self.capabilities.supports_color_temperature_control(),
-        )?;
-        let cmd = ColorTemperatureCommand::Get;
+    /// Returns [`Error::Value`] if `index` is not 1-3 or `host` is not a valid
+    /// host name or IP address, or an error if the command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// device.set_ntp_server(1, "ntp.internal.example").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_ntp_server(
+        &self,
+        index: u8,
+        host: impl Into<String>,
+    ) -> Result<NtpServerResponse, Error> {
+        let cmd = NtpServerCommand::set(index, host)?;
         let response = self.send_command(&cmd).await?;
-        let parsed: ColorTemperatureResponse = response.parse().map_err(Error::Parse)?;
-
-        // Dispatch callbacks for state changes
-        self.apply_color_temperature_response(&parsed);
-
-        Ok(parsed)
+        response.parse().map_err(Error::Parse)
     }
 
-    /// Dispatches color temperature state changes from a response to callbacks.
-    fn apply_color_temperature_response(&self, response: &ColorTemperatureResponse) {
-        if let Ok(ct) = ColorTemperature::new(response.color_temperature()) {
-            let change = crate::state::StateChange::color_temperature(ct);
-            self.callbacks.dispatch(&change);
-        }
+    // ========== Hardware Module ==========
 
-        if let Ok(Some(power)) = response.power_state() {
-            let change = crate::state::StateChange::power(1, power);
-            self.callbacks.dispatch(&change);
+    /// Selects the device's hardware module.
+    ///
+    /// The device restarts after a module change, so the connection may drop
+    /// before it replies. If it drops after the request was sent, the change
+    /// is assumed to be accepted and the returned response only carries the
+    /// requested module number ([`ModuleResponse::name`] is `None`). A
+    /// connection failure or timeout is returned as an error, since the
+    /// device may not have received the command. Capabilities may differ
+    /// after the restart; rebuild the device to detect them again.
+    ///
+    /// Use [`list_modules`](Self::list_modules) to check that the firmware
+    /// supports a module before selecting it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Value`] if `id` is out of range, or an error if the
+    /// device cannot be reached, does not reply in time, or the command
+    /// fails for a reason other than the restart.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Device;
+    ///
+    /// # async fn example(device: &Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// // Switch to "Generic" if the firmware supports it
+    /// if device.list_modules().await?.contains(18) {
+    ///     device.set_module(18).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_module(&self, id: u16) -> Result<ModuleResponse, Error> {
+        let cmd = ModuleCommand::set(id)?;
+        match self.send_command(&cmd).await {
+            Ok(response) => response.parse().map_err(Error::Parse),
+            Err(Error::Protocol(e)) if is_dropped_by_restart(&e) => {
+                tracing::info!(module = id, error = %e, "Connection dropped after module change, device is restarting");
+                Ok(ModuleResponse::restarting(id))
+            }
+            Err(e) => Err(e),
         }
     }
 
-    // ========== HSB Color ==========
-
-    /// Sets the HSB color.
-    ///
-    /// Returns a typed response including the new HSB color, dimmer level, and power state.
+    /// Gets the active hardware module.
     ///
     /// # Errors
     ///
-    /// Returns error if the device doesn't support RGB or the command fails.
-    pub async fn set_hsb_color(&self, color: HsbColor) -> Result<HsbColorResponse, Error> {
-        self.check_capability("RGB color", self.capabilities.supports_rgb_control())?;
-        let cmd = HsbColorCommand::Set(color);
+    /// Returns error if the command fails.
+    pub async fn get_module(&self) -> Result<ModuleResponse, Error> {
+        let cmd = ModuleCommand::Get;
         let response = self.send_command(&cmd).await?;
-        let parsed: HsbColorResponse = response.parse().map_err(Error::Parse)?;
+        response.parse().map_err(Error::Parse)
+    }
 
-        // Dispatch callbacks for state changes
-        self.apply_hsb_color_response(&parsed);
+    /// Lists the hardware modules supported by the firmware.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn list_modules(&self) -> Result<ModuleListResponse, Error> {
+        let cmd = ModuleCommand::List;
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
 
-        Ok(parsed)
+    /// Gets the active template (GPIO assignment).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn template(&self) -> Result<DeviceTemplate, Error> {
+        let cmd = ModuleCommand::Template;
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
     }
 
-    /// Gets the current HSB color.
+    /// Reads the component assigned to a GPIO pin.
     ///
-    /// Returns a typed response including the current HSB color, dimmer level, and power state.
+    /// Pins 0-17 exist on every chip (see
+    /// [`MAX_GPIO_ESP8266`](crate::command::MAX_GPIO_ESP8266)); higher pins
+    /// only exist on ESP32 variants.
     ///
     /// # Errors
     ///
-    /// Returns error if the device doesn't support RGB or the command fails.
-    pub async fn get_hsb_color(&self) -> Result<HsbColorResponse, Error> {
-        self.check_capability("RGB color", self.capabilities.supports_rgb_control())?;
-        let cmd = HsbColorCommand::Get;
+    /// Returns [`Error::Value`] if `pin` exceeds
+    /// [`MAX_GPIO`](crate::command::MAX_GPIO), or an error if the command
+    /// fails or the device does not report the pin.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Device;
+    ///
+    /// # async fn example(device: &Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let pin = device.read_gpio(4).await?;
+    /// println!("GPIO4: {} ({})", pin.name(), pin.component());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_gpio(&self, pin: u8) -> Result<GpioPin, Error> {
+        let cmd = GpioCommand::read(pin)?;
         let response = self.send_command(&cmd).await?;
-        let parsed: HsbColorResponse = response.parse().map_err(Error::Parse)?;
-
-        // Dispatch callbacks for state changes
-        self.apply_hsb_color_response(&parsed);
-
-        Ok(parsed)
+        let gpios: GpioResponse = response.parse().map_err(Error::Parse)?;
+        gpios
+            .pin(pin)
+            .cloned()
+            .ok_or_else(|| Error::Parse(ParseError::MissingField(cmd.name().to_uppercase())))
     }
 
-    /// Dispatches HSB color state changes from a response to callbacks.
-    fn apply_hsb_color_response(&self, response: &HsbColorResponse) {
-        if let Ok(color) = response.hsb_color() {
-            let change = crate::state::StateChange::hsb_color(color);
-            self.callbacks.dispatch(&change);
-        }
-
-        if let Some(dimmer_value) = response.dimmer()
-            && let Ok(dimmer) = Dimmer::new(dimmer_value)
-        {
-            let change = crate::state::StateChange::dimmer(dimmer);
-            self.callbacks.dispatch(&change);
-        }
+    /// Reads the component assigned to every GPIO pin of the chip.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails.
+    pub async fn read_all_gpios(&self) -> Result<GpioResponse, Error> {
+        let cmd = GpioCommand::ReadAll;
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
 
-        if let Ok(Some(power)) = response.power_state() {
-            let change = crate::state::StateChange::power(1, power);
-            self.callbacks.dispatch(&change);
+    /// Scans the I2C bus and returns the addresses of the devices found.
+    ///
+    /// Helps checking that a sensor (e.g. a BME280 at `0x76` or an `SHT3x` at
+    /// `0x44`) is wired correctly before relying on its telemetry. Returns
+    /// an empty list when no device answers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DeviceError::CommandRejected`] if the bus reports an error,
+    /// or an error if the command fails or the firmware has no I2C support.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Device;
+    ///
+    /// # async fn example(device: &Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// if !device.i2c_scan().await?.contains(&0x76) {
+    ///     println!("BME280 not found, check the wiring");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn i2c_scan(&self) -> Result<Vec<u8>, Error> {
+        let response: I2cScanResponse = self
+            .send_command(&I2cScanCommand)
+            .await?
+            .parse()
+            .map_err(Error::Parse)?;
+        if let Some(error) = response.error() {
+            return Err(Error::Device(DeviceError::CommandRejected(format!(
+                "I2CScan: {error}"
+            ))));
         }
+        Ok(response.addresses())
     }
 
-    // ========== RGB Color ==========
+    // ========== Tuya MCU ==========
 
-    /// Sets the RGB color.
+    /// Sends a value to a Tuya MCU data point.
     ///
-    /// This is a convenience method that converts the RGB color to HSB internally
-    /// and sends an `HSBColor` command to the device. The response contains both
-    /// the RGB and HSB representations.
+    /// The value is encoded according to its type (see [`TuyaCommand`]).
+    /// Data point numbers and types are device specific; check the device's
+    /// documentation or watch the `TuyaReceived` reports with
+    /// [`on_tuya_received`](crate::subscription::Subscribable::on_tuya_received)
+    /// on an MQTT device.
     ///
     /// # Errors
     ///
-    /// Returns error if the device doesn't support RGB or the command fails.
+    /// Returns [`Error::Value`] if `dp_id` is 0, or an error if the command
+    /// fails or is not acknowledged (e.g. the device has no Tuya MCU).
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use tasmor_lib::RgbColor;
+    /// use tasmor_lib::Device;
+    /// use tasmor_lib::types::TuyaValue;
     ///
-    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
-    /// // Set color using hex string
-    /// let color = RgbColor::from_hex("#FF5733")?;
-    /// let response = device.set_rgb_color(color).await?;
-    /// println!("Color set to: {}", response.to_hex_with_hash());
+    /// # async fn example(device: &Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// // Turn on the fan (DP 1) at speed 3 (DP 3)
+    /// device.tuya_send(1, TuyaValue::Bool(true)).await?;
+    /// device.tuya_send(3, TuyaValue::Enum(3)).await?;
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn set_rgb_color(&self, color: RgbColor) -> Result<RgbColorResponse, Error> {
-        self.check_capability("RGB color", self.capabilities.supports_rgb_control())?;
+    pub async fn tuya_send(&self, dp_id: u8, value: TuyaValue) -> Result<(), Error> {
+        let cmd = TuyaCommand::send(dp_id, value)?;
+        let response = self.send_command(&cmd).await?;
+        check_done(&response, &cmd.name())
+    }
 
-        // Convert RGB to HSB and send the command
-        let hsb = color.to_hsb();
-        let cmd = HsbColorCommand::Set(hsb);
+    /// Asks the Tuya MCU to report all its data points.
+    ///
+    /// The values arrive as `TuyaReceived` telemetry, which MQTT devices
+    /// dispatch to [`on_tuya_received`](crate::subscription::Subscribable::on_tuya_received)
+    /// callbacks.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails or is not acknowledged.
+    pub async fn tuya_query(&self) -> Result<(), Error> {
+        let cmd = TuyaCommand::Query;
         let response = self.send_command(&cmd).await?;
-        let hsb_response: HsbColorResponse = response.parse().map_err(Error::Parse)?;
+        check_done(&response, &cmd.name())
+    }
 
-        // Dispatch callbacks for state changes
-        self.apply_hsb_color_response(&hsb_response);
+    /// Gets the mapping of Tuya MCU data points to Tasmota functions.
+    ///
+    /// The mapping tells which Tasmota function (relay, dimmer, sensor)
+    /// each data point of a `TuyaReceived` report carries.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the command fails or the response cannot be parsed
+    /// (e.g. the device has no Tuya MCU).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let mappings = device.get_tuya_mappings().await?;
+    /// for (dp_id, function) in mappings.dp_map() {
+    ///     println!("DP {dp_id} -> function {function}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_tuya_mappings(&self) -> Result<TuyaMcuResponse, Error> {
+        let response = self.send_command(&TuyaMcuCommand::Get).await?;
+        response.parse().map_err(Error::Parse)
+    }
 
-        // Create RGB response preserving the original RGB value
-        let returned_hsb = hsb_response.hsb_color().unwrap_or(hsb);
-        Ok(RgbColorResponse::new(color, returned_hsb))
+    /// Maps a Tasmota function to a Tuya MCU data point.
+    ///
+    /// Pass `None` as `dp_id` to remove the mapping of `function`. Returns
+    /// the complete mapping list after the change.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Value`] if `function` is not 1-99 or `dp_id` is 0,
+    /// or an error if the command fails or the response cannot be parsed.
+    pub async fn set_tuya_mapping(
+        &self,
+        function: u8,
+        dp_id: Option<u8>,
+    ) -> Result<TuyaMcuResponse, Error> {
+        let cmd = match dp_id {
+            Some(dp_id) => TuyaMcuCommand::map(function, dp_id)?,
+            None => TuyaMcuCommand::unmap(function)?,
+        };
+        let response = self.send_command(&cmd).await?;
+        response.parse().map_err(Error::Parse)
     }
 
-    // ========== Scheme ==========
+    // ========== Energy Monitoring ==========
 
-    /// Sets the light scheme/effect.
+    /// Gets energy monitoring data (voltage, current, power consumption).
     ///
-    /// Tasmota supports several built-in light schemes:
-    /// - 0: Single (fixed color, default)
-    /// - 1: Wakeup (gradual brightness increase, uses [`WakeupDuration`])
-    /// - 2: Cycle Up (color cycling with increasing brightness)
-    /// - 3: Cycle Down (color cycling with decreasing brightness)
-    /// - 4: Random (random color changes)
+    /// Returns comprehensive energy data including instantaneous readings and
+    /// accumulated totals. Only available on devices with energy monitoring
+    /// capabilities (e.g., smart plugs with power metering).
     ///
     /// # Errors
     ///
-    /// Returns error if the command fails.
+    /// Returns [`Error::Device`] with [`DeviceError::UnsupportedCapability`] if
+    /// the device doesn't support energy monitoring (check with
+    /// [`capabilities().supports_energy_monitoring()`](Capabilities::supports_energy_monitoring)).
+    ///
+    /// Returns [`Error::Protocol`] if the device is unreachable or returns an invalid response.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use tasmor_lib::Scheme;
+    /// use tasmor_lib::Device;
     ///
-    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
-    /// // Set wakeup scheme
-    /// device.set_scheme(Scheme::WAKEUP).await?;
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (device, _) = Device::http("192.168.1.100").build().await?;
+    ///
+    /// if device.capabilities().supports_energy_monitoring() {
+    ///     let response = device.energy().await?;
+    ///     if let Some(energy) = response.energy() {
+    ///         println!("Power: {} W", energy.power);
+    ///         println!("Voltage: {} V", energy.voltage);
+    ///         println!("Today: {} kWh", energy.today);
+    ///     }
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn set_scheme(&self, scheme: Scheme) -> Result<SchemeResponse, Error> {
-        let cmd = SchemeCommand::Set(scheme);
+    ///
+    /// [`DeviceError::UnsupportedCapability`]: crate::error::DeviceError::UnsupportedCapability
+    pub async fn energy(&self) -> Result<EnergyResponse, Error> {
+        self.check_capability(
+            "energy monitoring",
+            self.capabilities.supports_energy_monitoring(),
+        )?;
+        let cmd = EnergyCommand::Get;
         let response = self.send_command(&cmd).await?;
-        let parsed: SchemeResponse = response.parse().map_err(Error::Parse)?;
+        response.parse().map_err(Error::Parse)
+    }
 
-        // Dispatch callbacks for state changes
-        if let Ok(s) = parsed.scheme() {
-            let change = crate::state::StateChange::scheme(s);
-            self.callbacks.dispatch(&change);
+    /// Gets energy monitoring data, or `None` if energy monitoring is not
+    /// supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Protocol`] if the device is unreachable or returns an invalid response.
+    pub async fn try_get_energy(&self) -> Result<Option<EnergyResponse>, Error> {
+        if !self.capabilities.supports_energy_monitoring() {
+            return Ok(None);
         }
-
-        Ok(parsed)
+        self.energy().await.map(Some)
     }
 
-    /// Gets the current light scheme.
+    /// Resets the total energy counter to zero and returns the updated energy data.
+    ///
+    /// This resets both the total energy value and the `TotalStartTime` to the current time,
+    /// then queries the device to return the updated energy data.
     ///
     /// # Errors
     ///
-    /// Returns error if the command fails.
-    pub async fn get_scheme(&self) -> Result<SchemeResponse, Error> {
-        let cmd = SchemeCommand::Get;
-        let response = self.send_command(&cmd).await?;
-        let parsed: SchemeResponse = response.parse().map_err(Error::Parse)?;
+    /// Returns error if the device doesn't support energy monitoring or the command fails.
+    pub async fn reset_energy_total(&self) -> Result<EnergyResponse, Error> {
+        self.check_capability(
+            "energy monitoring",
+            self.capabilities.supports_energy_monitoring(),
+        )?;
 
-        // Dispatch callbacks for state changes
-        if let Ok(s) = parsed.scheme() {
-            let change = crate::state::StateChange::scheme(s);
-            self.callbacks.dispatch(&change);
-        }
+        // Send the reset command
+        let cmd = EnergyCommand::ResetTotal;
+        self.send_command(&cmd).await?;
 
-        Ok(parsed)
+        // Query and return the updated energy data
+        let query_cmd = EnergyCommand::Get;
+        let response = self.send_command(&query_cmd).await?;
+        response.parse().map_err(Error::Parse)
     }
 
-    // ========== Wakeup Duration ==========
+    /// Sets the total energy counter and returns the updated energy data.
+    ///
+    /// Use this to align the device with a utility meter reading, e.g. when
+    /// replacing an older meter. The value is given in kilowatt-hours and
+    /// stored by Tasmota in whole watt-hours.
+    ///
+    /// The counter is written to flash, so this is meant for occasional
+    /// corrections, not for frequent calls.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Value`] if `kwh` is negative or not
+    /// finite, or an error if the device doesn't support energy monitoring
+    /// or the command fails.
+    pub async fn set_energy_total(&self, kwh: f64) -> Result<EnergyResponse, Error> {
+        self.check_capability(
+            "energy monitoring",
+            self.capabilities.supports_energy_monitoring(),
+        )?;
 
-    /// Sets the wakeup duration.
+        let cmd = EnergyCommand::set_total_kwh(kwh)?;
+        self.send_command(&cmd).await?;
+
+        // Query and return the updated energy data
+        let query_cmd = EnergyCommand::Get;
+        let response = self.send_command(&query_cmd).await?;
+        response.parse().map_err(Error::Parse)
+    }
+
+    /// Samples energy readings over a time window.
     ///
-    /// The wakeup duration controls how long Scheme 1 (Wakeup) takes to
-    /// gradually increase brightness from 0 to the current dimmer level.
+    /// Polls the device every `interval` for `duration` and returns the
+    /// readings with the device's timestamp for each. The first sample is
+    /// taken immediately. Intervals shorter than one second are raised to
+    /// one second to avoid overloading the device.
+    ///
+    /// MQTT devices are polled too: their periodic telemetry (`TelePeriod`,
+    /// 300 seconds by default) is usually too sparse for a short measurement.
+    ///
+    /// If the device stops responding after the first sample, sampling ends
+    /// early and the readings collected so far are returned. Readings without
+    /// a timestamp or energy data are skipped.
     ///
     /// # Errors
     ///
-    /// Returns error if the command fails.
+    /// Returns error if the device doesn't support energy monitoring, the
+    /// first poll fails, or a response cannot be parsed.
     ///
     /// # Examples
     ///
     /// ```no_run
     /// use std::time::Duration;
-    /// use tasmor_lib::WakeupDuration;
+    /// use tasmor_lib::Device;
     ///
-    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
-    /// // Set wakeup duration to 5 minutes
-    /// let duration = WakeupDuration::new(Duration::from_secs(300))?;
-    /// device.set_wakeup_duration(duration).await?;
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (device, _) = Device::http("192.168.1.100").build().await?;
+    ///
+    /// // Measure an appliance for a minute
+    /// let samples = device
+    ///     .sample_energy(Duration::from_secs(60), Duration::from_secs(5))
+    ///     .await?;
+    /// for (time, reading) in &samples {
+    ///     println!("{time}: {:?} W", reading.power);
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn set_wakeup_duration(
+    pub async fn sample_energy(
         &self,
-        duration: WakeupDuration,
-    ) -> Result<WakeupDurationResponse, Error> {
-        let cmd = WakeupDurationCommand::Set(duration);
-        let response = self.send_command(&cmd).await?;
-        let parsed: WakeupDurationResponse = response.parse().map_err(Error::Parse)?;
-
-        // Dispatch callbacks for state changes
-        if let Ok(d) = parsed.duration() {
-            let change = crate::state::StateChange::wakeup_duration(d);
-            self.callbacks.dispatch(&change);
-        }
-
-        Ok(parsed)
-    }
+        duration: Duration,
+        interval: Duration,
+    ) -> Result<Vec<(TasmotaDateTime, EnergyReading)>, Error> {
+        self.check_capability(
+            "energy monitoring",
+            self.capabilities.supports_energy_monitoring(),
+        )?;
 
-    /// Gets the current wakeup duration.
-    ///
-    /// # Errors
-    ///
-    /// Returns error if the command fails.
-    pub async fn get_wakeup_duration(&self) -> Result<WakeupDurationResponse, Error> {
-        let cmd = WakeupDurationCommand::Get;
-        let response = self.send_command(&cmd).await?;
-        let parsed: WakeupDurationResponse = response.parse().map_err(Error::Parse)?;
+        let interval = interval.max(MIN_ENERGY_SAMPLE_INTERVAL);
+        let deadline = tokio::time::Instant::now() + duration;
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        let mut samples = Vec::new();
+        let mut polled = false;
+        loop {
+            ticker.tick().await;
+
+            let response = match self.send_command(&EnergyCommand::Get).await {
+                Ok(response) => response,
+                Err(Error::Protocol(e)) if polled => {
+                    tracing::warn!(error = %e, samples = samples.len(), "Device stopped responding, ending energy sampling early");
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+            polled = true;
+
+            let status: StatusSnsResponse = response.parse().map_err(Error::Parse)?;
+            let sample = status.sensor_data().and_then(|data| {
+                let time = data.time().and_then(TasmotaDateTime::parse)?;
+                Some((time, data.energy()?.clone()))
+            });
+            if let Some(sample) = sample {
+                samples.push(sample);
+            } else {
+                tracing::debug!("Skipping energy sample without timestamp or energy data");
+            }
 
-        // Dispatch callbacks for state changes
-        if let Ok(d) = parsed.duration() {
-            let change = crate::state::StateChange::wakeup_duration(d);
-            self.callbacks.dispatch(&change);
+            if tokio::time::Instant::now() + interval > deadline {
+                break;
+            }
         }
 
-        Ok(parsed)
+        Ok(samples)
     }
 
-    // ========== Fade ==========
+    // ========== Persistence ==========
 
-    /// Enables fade transitions.
+    /// Sets whether the relay states survive a restart (`SetOption0`).
     ///
-    /// Returns a typed response indicating whether fade is now enabled.
+    /// When enabled, every power change is written to the settings, which
+    /// reach flash at the next [`SaveData`](Self::set_save_data) flush. On a
+    /// device toggled many times a day this adds flash wear; disabling it
+    /// leaves the relays in their `PowerOnState` after a restart.
+    ///
+    /// Returns the value the device confirmed.
     ///
     /// # Errors
     ///
-    /// Returns error if the command fails.
-    pub async fn enable_fade(&self) -> Result<FadeResponse, Error> {
-        let cmd = FadeCommand::Enable;
+    /// Returns error if the command fails or the response cannot be parsed.
+    pub async fn set_persist_power_state(&self, persist: bool) -> Result<bool, Error> {
+        let cmd = SetOptionCommand::set(0, persist);
         let response = self.send_command(&cmd).await?;
-        response.parse().map_err(Error::Parse)
+        let response: SetOptionResponse = response.parse().map_err(Error::Parse)?;
+        Ok(response.is_enabled())
     }
 
-    /// Disables fade transitions.
-    ///
-    /// Returns a typed response indicating whether fade is now disabled.
+    /// Returns whether the relay states survive a restart (`SetOption0`).
     ///
     /// # Errors
     ///
-    /// Returns error if the command fails.
-    pub async fn disable_fade(&self) -> Result<FadeResponse, Error> {
-        let cmd = FadeCommand::Disable;
+    /// Returns error if the command fails or the response cannot be parsed.
+    pub async fn get_persist_power_state(&self) -> Result<bool, Error> {
+        let cmd = SetOptionCommand::query(0);
         let response = self.send_command(&cmd).await?;
-        response.parse().map_err(Error::Parse)
+        let response: SetOptionResponse = response.parse().map_err(Error::Parse)?;
+        Ok(response.is_enabled())
     }
 
-    /// Gets the current fade setting.
+    /// Sets how often changed settings are saved to flash (`SaveData`).
     ///
-    /// Returns a typed response indicating whether fade is enabled.
+    /// `seconds` is 1-3600, or 0 to save only on a controlled restart.
+    /// Each flush erases a flash sector, which survives roughly 100 000
+    /// erase cycles; a device whose state changes often lasts longer with a
+    /// longer interval, at the cost of losing the changes since the last
+    /// flush on a power cut.
     ///
     /// # Errors
     ///
-    /// Returns error if the command fails.
-    pub async fn get_fade(&self) -> Result<FadeResponse, Error> {
-        let cmd = FadeCommand::Get;
+    /// Returns [`ValueError::OutOfRange`](crate::ValueError::OutOfRange) if
+    /// `seconds` is greater than 3600, or error if the command fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// // A relay switched every few seconds: flush at most once an hour
+    /// device.set_save_data(3600).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_save_data(&self, seconds: u16) -> Result<SaveDataResponse, Error> {
+        let cmd = if seconds == 0 {
+            SaveDataCommand::disable()
+        } else {
+            SaveDataCommand::every(seconds)?
+        };
         let response = self.send_command(&cmd).await?;
         response.parse().map_err(Error::Parse)
     }
 
-    /// Sets the fade transition duration.
-    ///
-    /// Returns a typed response with the new duration value.
+    /// Gets how often changed settings are saved to flash (`SaveData`).
     ///
     /// # Errors
     ///
-    /// Returns error if the command fails.
-    pub async fn set_fade_duration(
-        &self,
-        duration: FadeDuration,
-    ) -> Result<FadeDurationResponse, Error> {
-        let cmd = FadeDurationCommand::Set(duration);
-        let response = self.send_command(&cmd).await?;
+    /// Returns error if the command fails or the response cannot be parsed.
+    pub async fn get_save_data(&self) -> Result<SaveDataResponse, Error> {
+        let response = self.send_command(&SaveDataCommand::Get).await?;
         response.parse().map_err(Error::Parse)
     }
 
-    /// Gets the current fade duration setting.
+    // ========== Buttons ==========
+
+    /// Detaches the buttons from the relays (`SetOption73`), or reattaches
+    /// them.
     ///
-    /// Returns a typed response with the current duration value.
+    /// While detached, a button press no longer toggles its relay. Instead
+    /// the device reports the press, e.g. `{"Button1":{"Action":"SINGLE"}}`,
+    /// which MQTT devices deliver to
+    /// [`on_button_action`](crate::subscription::Subscribable::on_button_action).
+    /// This lets the application decide what each single, double, triple or
+    /// held press does.
     ///
     /// # Errors
     ///
     /// Returns error if the command fails.
-    pub async fn get_fade_duration(&self) -> Result<FadeDurationResponse, Error> {
-        let cmd = FadeDurationCommand::Get;
-        let response = self.send_command(&cmd).await?;
-        response.parse().map_err(Error::Parse)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::MqttBroker;
+    /// use tasmor_lib::subscription::Subscribable;
+    /// use tasmor_lib::types::ButtonAction;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let broker = MqttBroker::builder().host("192.168.1.50").build().await?;
+    /// let (device, _) = broker.device("tasmota_switch").build().await?;
+    ///
+    /// device.set_buttons_detached(true).await?;
+    /// device.on_button_action(|index, action| {
+    ///     if index == 1 && action == ButtonAction::Double {
+    ///         println!("Scene: movie night");
+    ///     }
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_buttons_detached(&self, detached: bool) -> Result<(), Error> {
+        let cmd = SetOptionCommand::set(73, detached);
+        self.send_command(&cmd).await?;
+        Ok(())
     }
 
-    /// Enables fade at startup.
-    ///
-    /// Returns a typed response indicating whether startup fade is now enabled.
+    /// Returns whether the buttons are detached from the relays
+    /// (`SetOption73`).
     ///
     /// # Errors
     ///
-    /// Returns error if the command fails.
-    pub async fn enable_fade_at_startup(&self) -> Result<StartupFadeResponse, Error> {
-        let cmd = StartupFadeCommand::Enable;
-        let response = self.send_command(&cmd).await?;
-        response.parse().map_err(Error::Parse)
+    /// Returns error if the command fails or the firmware does not report
+    /// the option.
+    pub async fn buttons_detached(&self) -> Result<bool, Error> {
+        let flags = self.get_all_set_options().await?;
+        flags
+            .buttons_detached()
+            .ok_or_else(|| Error::Parse(ParseError::MissingField("SetOption73".to_string())))
     }
 
-    /// Disables fade at startup.
+    // ========== Rule Events ==========
+
+    /// Fires the rule trigger `Event#<name>` on the device.
     ///
-    /// Returns a typed response indicating whether startup fade is now disabled.
+    /// The controller decides when something happens, and the device's
+    /// rules decide what it does. Rules are defined on the device with
+    /// [`set_rule`](Self::set_rule). An event no enabled rule listens for is
+    /// still acknowledged, and does nothing.
+    ///
+    /// Events are not retried by a [`RetryPolicy`](crate::protocol::RetryPolicy)
+    /// unless it retries commands that are not idempotent, since a repeated
+    /// event runs its rule twice.
     ///
     /// # Errors
     ///
-    /// Returns error if the command fails.
-    pub async fn disable_fade_at_startup(&self) -> Result<StartupFadeResponse, Error> {
-        let cmd = StartupFadeCommand::Disable;
-        let response = self.send_command(&cmd).await?;
-        response.parse().map_err(Error::Parse)
+    /// Returns [`Error::Value`] if `name` is not a valid event name, or an
+    /// error if the command fails or the device does not acknowledge the
+    /// event.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Device;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (device, _) = Device::http("192.168.1.100").build().await?;
+    ///
+    /// // Runs the device's "ON Event#night DO ... ENDON" rule
+    /// device.fire_event("night").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fire_event(&self, name: &str) -> Result<(), Error> {
+        let cmd = EventCommand::fire(name)?;
+        self.event(&cmd).await
     }
 
-    /// Gets the current fade at startup setting.
+    /// Fires the rule trigger `Event#<name>` with a value.
     ///
-    /// Returns a typed response indicating whether startup fade is enabled.
+    /// The value is available to the rule as `%value%`, and can be compared
+    /// in the trigger, e.g. `ON Event#level>50 DO ... ENDON`.
     ///
     /// # Errors
     ///
-    /// Returns error if the command fails.
-    pub async fn get_fade_at_startup(&self) -> Result<StartupFadeResponse, Error> {
-        let cmd = StartupFadeCommand::Get;
-        let response = self.send_command(&cmd).await?;
-        response.parse().map_err(Error::Parse)
+    /// Returns [`Error::Value`] if `name` is not a valid event name or
+    /// `value` contains `;` or control characters, or an error if the
+    /// command fails or the device does not acknowledge the event.
+    pub async fn fire_event_with_value(&self, name: &str, value: &str) -> Result<(), Error> {
+        let cmd = EventCommand::fire_with_value(name, value)?;
+        self.event(&cmd).await
     }
 
-    // ========== Energy Monitoring ==========
-
-    /// Gets energy monitoring data (voltage, current, power consumption).
+    /// Stores `rule` as rule set `set` (1-3) and enables it.
     ///
-    /// Returns comprehensive energy data including instantaneous readings and
-    /// accumulated totals. Only available on devices with energy monitoring
-    /// capabilities (e.g., smart plugs with power metering).
+    /// The set's previous rules are replaced.
     ///
     /// # Errors
     ///
-    /// Returns [`Error::Device`] with [`DeviceError::UnsupportedCapability`] if
-    /// the device doesn't support energy monitoring (check with
-    /// [`capabilities().supports_energy_monitoring()`](Capabilities::supports_energy_monitoring)).
-    ///
-    /// Returns [`Error::Protocol`] if the device is unreachable or returns an invalid response.
+    /// Returns [`Error::Value`] if `set` is not 1-3, or an error if a
+    /// command fails.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use tasmor_lib::Device;
+    /// use tasmor_lib::{Device, RuleBuilder};
     ///
     /// # async fn example() -> tasmor_lib::Result<()> {
     /// let (device, _) = Device::http("192.168.1.100").build().await?;
     ///
-    /// if device.capabilities().supports_energy_monitoring() {
-    ///     let response = device.energy().await?;
-    ///     if let Some(energy) = response.energy() {
-    ///         println!("Power: {} W", energy.power);
-    ///         println!("Voltage: {} V", energy.voltage);
-    ///         println!("Today: {} kWh", energy.today);
-    ///     }
-    /// }
+    /// let rule = RuleBuilder::new()
+    ///     .on("Event#night")
+    ///     .then_raw("Power1 OFF")
+    ///     .build()?;
+    /// device.set_rule(1, &rule).await?;
     /// # Ok(())
     /// # }
     /// ```
-    ///
-    /// [`DeviceError::UnsupportedCapability`]: crate::error::DeviceError::UnsupportedCapability
-    pub async fn energy(&self) -> Result<EnergyResponse, Error> {
-        self.check_capability(
-            "energy monitoring",
-            self.capabilities.supports_energy_monitoring(),
-        )?;
-        let cmd = EnergyCommand::Get;
-        let response = self.send_command(&cmd).await?;
-        response.parse().map_err(Error::Parse)
+    pub async fn set_rule(&self, set: u8, rule: &crate::command::Rule) -> Result<(), Error> {
+        self.send_command(&RuleCommand::set(set, rule)?).await?;
+        self.send_command(&RuleCommand::enable(set)?).await?;
+        Ok(())
     }
 
-    /// Resets the total energy counter to zero and returns the updated energy data.
-    ///
-    /// This resets both the total energy value and the `TotalStartTime` to the current time,
-    /// then queries the device to return the updated energy data.
+    /// Disables rule set `set` (1-3), keeping its rules.
     ///
     /// # Errors
     ///
-    /// Returns error if the device doesn't support energy monitoring or the command fails.
-    pub async fn reset_energy_total(&self) -> Result<EnergyResponse, Error> {
-        self.check_capability(
-            "energy monitoring",
-            self.capabilities.supports_energy_monitoring(),
-        )?;
-
-        // Send the reset command
-        let cmd = EnergyCommand::ResetTotal;
-        self.send_command(&cmd).await?;
+    /// Returns [`Error::Value`] if `set` is not 1-3, or an error if the
+    /// command fails.
+    pub async fn disable_rule(&self, set: u8) -> Result<(), Error> {
+        self.send_command(&RuleCommand::disable(set)?).await?;
+        Ok(())
+    }
 
-        // Query and return the updated energy data
-        let query_cmd = EnergyCommand::Get;
-        let response = self.send_command(&query_cmd).await?;
-        response.parse().map_err(Error::Parse)
+    /// Sends an `Event` command and checks the acknowledgement.
+    async fn event(&self, cmd: &EventCommand) -> Result<(), Error> {
+        let response: EventResponse = self
+            .send_command(cmd)
+            .await?
+            .parse()
+            .map_err(Error::Parse)?;
+        if response.is_done() {
+            Ok(())
+        } else {
+            Err(Error::Device(DeviceError::CommandRejected(format!(
+                "Event: {}",
+                response.status()
+            ))))
+        }
     }
 
     // ========== Routines ==========
@@ -1034,11 +3880,21 @@ impl<P: Protocol> Device<P> {
             "Running routine"
         );
 
-        let response = self
-            .protocol
-            .send_raw(&backlog_cmd)
-            .await
-            .map_err(Error::Protocol)?;
+        if self.dry_run {
+            tracing::info!(raw = %backlog_cmd, "Dry run: routine not sent");
+            let response = CommandResponse::dry_run(dry_run::synthesize_backlog(&backlog_cmd));
+            let parsed: crate::response::RoutineResponse =
+                response.parse().map_err(Error::Parse)?;
+            return Ok(parsed.into_dry_run());
+        }
+
+        let response = {
+            let _slot = self.gate.acquire(routine.priority()).await;
+            self.protocol
+                .send_raw(&backlog_cmd)
+                .await
+                .map_err(Error::Protocol)?
+        };
 
         let parsed: crate::response::RoutineResponse = response.parse().map_err(Error::Parse)?;
 
@@ -1048,8 +3904,196 @@ impl<P: Protocol> Device<P> {
         Ok(parsed)
     }
 
+    /// Sends commands one at a time and returns the result of each.
+    ///
+    /// Unlike [`run`](Self::run), which sends a single `Backlog0` and merges
+    /// the replies, each command gets its own request and response, so the
+    /// results show which step failed and what every step returned. Sending
+    /// stops at the first failure: the last result is then the error and the
+    /// remaining commands were not sent. This suits setup sequences where a
+    /// command relies on the previous one having succeeded.
+    ///
+    /// After each successful step, state change callbacks are dispatched
+    /// from the same response fields as [`run`](Self::run).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::command::{DimmerCommand, PowerCommand};
+    /// use tasmor_lib::types::{Dimmer, PowerIndex};
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let power = PowerCommand::on(PowerIndex::one());
+    /// let dimmer = DimmerCommand::Set(Dimmer::new(40)?);
+    ///
+    /// let results = device.run_sequential(&[&power, &dimmer]).await;
+    /// if let Some(Err(e)) = results.last() {
+    ///     eprintln!("Step {} of 2 failed: {e}", results.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn run_sequential(
+        &self,
+        commands: &[&(dyn Command + Sync)],
+    ) -> Vec<Result<CommandResponse, Error>> {
+        let mut results = Vec::with_capacity(commands.len());
+        for &command in commands {
+            let result = self.send_command(&DynCommand(command)).await;
+            let failed = match &result {
+                Ok(response) => {
+                    if let Ok(parsed) = response.parse::<crate::response::RoutineResponse>() {
+                        self.apply_routine_response(&parsed);
+                    }
+                    false
+                }
+                Err(e) => {
+                    tracing::debug!(
+                        step = results.len() + 1,
+                        command = %command.name(),
+                        error = %e,
+                        "Sequence stopped"
+                    );
+                    true
+                }
+            };
+            results.push(result);
+            if failed {
+                break;
+            }
+        }
+        results
+    }
+
+    /// Applies a list of state changes as a single `Backlog0` routine.
+    ///
+    /// This is the counterpart of
+    /// [`TelemetryState::to_state_changes`](crate::telemetry::TelemetryState::to_state_changes):
+    /// changes observed on one device, computed by a diff or stored as a
+    /// scene can be replayed as instructions. Each change becomes the command
+    /// that sets it, and the commands run in the order of `changes`
+    /// ([`StateChange::Batch`](crate::state::StateChange::Batch) entries are
+    /// expanded in place), so a later change to the same value wins.
+    ///
+    /// Changes the device cannot apply are skipped with a warning instead of
+    /// failing the batch: light settings on a device without the matching
+    /// capability, power changes for relays it does not have, and energy
+    /// readings, which are observations only. If every change is skipped,
+    /// nothing is sent and an empty response is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] if more than
+    /// [`MAX_ROUTINE_STEPS`](crate::command::MAX_ROUTINE_STEPS) commands
+    /// remain, or [`Error::Protocol`] or [`Error::Parse`] if sending fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::state::StateChange;
+    /// use tasmor_lib::types::{ColorTemperature, Dimmer};
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let scene = [
+    ///     StateChange::dimmer(Dimmer::new(30)?),
+    ///     StateChange::color_temperature(ColorTemperature::WARM),
+    ///     StateChange::power_on(),
+    /// ];
+    /// device.apply_changes(&scene).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn apply_changes(
+        &self,
+        changes: &[crate::state::StateChange],
+    ) -> Result<crate::response::RoutineResponse, Error> {
+        let mut builder = crate::command::Routine::builder();
+        for change in changes {
+            builder = self.add_change(builder, change);
+        }
+
+        if builder.is_empty() {
+            tracing::debug!("No applicable state changes, nothing to send");
+            return Ok(crate::response::RoutineResponse::new());
+        }
+
+        let routine = builder.build()?;
+        self.run(&routine).await
+    }
+
+    /// Appends the command for `change`, or skips it if the device cannot apply it.
+    fn add_change(
+        &self,
+        builder: crate::command::RoutineBuilder,
+        change: &crate::state::StateChange,
+    ) -> crate::command::RoutineBuilder {
+        use crate::state::StateChange;
+
+        let skip = |reason: &str| {
+            tracing::warn!(?change, reason, "Skipping state change");
+        };
+
+        match change {
+            StateChange::Power { index, state } => match PowerIndex::new(*index) {
+                Ok(power_index) if *index <= self.capabilities.power_channels() => {
+                    builder.set_power(power_index, *state)
+                }
+                _ => {
+                    skip("relay not present");
+                    builder
+                }
+            },
+            StateChange::Dimmer(dimmer) if self.capabilities.supports_dimmer_control() => {
+                builder.set_dimmer(*dimmer)
+            }
+            StateChange::HsbColor(color) if self.capabilities.supports_rgb_control() => {
+                builder.set_hsb_color(*color)
+            }
+            StateChange::ColorTemperature(ct)
+                if self.capabilities.supports_color_temperature_control() =>
+            {
+                builder.set_color_temperature(*ct)
+            }
+            StateChange::Scheme(scheme) if self.capabilities.is_light() => {
+                builder.set_scheme(*scheme)
+            }
+            StateChange::WakeupDuration(duration) if self.capabilities.is_light() => {
+                builder.set_wakeup_duration(*duration)
+            }
+            StateChange::FadeEnabled(true) if self.capabilities.is_light() => builder.enable_fade(),
+            StateChange::FadeEnabled(false) if self.capabilities.is_light() => {
+                builder.disable_fade()
+            }
+            StateChange::FadeDuration(duration) if self.capabilities.is_light() => {
+                builder.set_fade_duration(*duration)
+            }
+            StateChange::Energy { .. } => {
+                skip("energy readings cannot be set");
+                builder
+            }
+            StateChange::Batch(changes) => changes
+                .iter()
+                .fold(builder, |builder, change| self.add_change(builder, change)),
+            _ => {
+                skip("capability not supported");
+                builder
+            }
+        }
+    }
+
     /// Dispatches state change callbacks based on routine response fields.
     fn apply_routine_response(&self, response: &crate::response::RoutineResponse) {
+        for change in Self::routine_response_changes(response) {
+            self.dispatch(&change);
+        }
+    }
+
+    /// Extracts the state changes reported in a routine response.
+    fn routine_response_changes(
+        response: &crate::response::RoutineResponse,
+    ) -> Vec<crate::state::StateChange> {
+        let mut changes = Vec::new();
+
         // Parse power states: POWER, POWER1-POWER8
         for idx in 1..=8u8 {
             let keys = if idx == 1 {
@@ -1062,8 +4106,7 @@ impl<P: Protocol> Device<P> {
                 if let Some(state_str) = response.try_get_as::<String>(&key)
                     && let Ok(state) = state_str.parse::<PowerState>()
                 {
-                    let change = crate::state::StateChange::power(idx, state);
-                    self.callbacks.dispatch(&change);
+                    changes.push(crate::state::StateChange::power(idx, state));
                     break; // Found state for this index, no need to check other keys
                 }
             }
@@ -1073,8 +4116,7 @@ impl<P: Protocol> Device<P> {
         if let Some(dimmer_value) = response.try_get_as::<u8>("Dimmer")
             && let Ok(dimmer) = Dimmer::new(dimmer_value)
         {
-            let change = crate::state::StateChange::dimmer(dimmer);
-            self.callbacks.dispatch(&change);
+            changes.push(crate::state::StateChange::dimmer(dimmer));
         }
 
         // Parse HSBColor if present (format: "hue,sat,bri")
@@ -1088,8 +4130,7 @@ impl<P: Protocol> Device<P> {
                     parts[2].parse::<u8>(),
                 ) {
                     if let Ok(color) = HsbColor::new(h, s, b) {
-                        let change = crate::state::StateChange::hsb_color(color);
-                        self.callbacks.dispatch(&change);
+                        changes.push(crate::state::StateChange::hsb_color(color));
                     } else {
                         tracing::warn!(value = %hsb_str, "HSBColor values out of range in sequence response");
                     }
@@ -1101,21 +4142,323 @@ impl<P: Protocol> Device<P> {
             }
         }
 
-        // Parse CT (color temperature) if present
-        if let Some(ct_value) = response.try_get_as::<u16>("CT")
-            && let Ok(ct) = ColorTemperature::new(ct_value)
-        {
-            let change = crate::state::StateChange::color_temperature(ct);
-            self.callbacks.dispatch(&change);
+        // Parse CT (color temperature) if present
+        if let Some(ct_value) = response.try_get_as::<u16>("CT")
+            && let Ok(ct) = ColorTemperature::new(ct_value)
+        {
+            changes.push(crate::state::StateChange::color_temperature(ct));
+        }
+
+        // Parse Scheme if present
+        if let Some(scheme_value) = response.try_get_as::<u8>("Scheme")
+            && let Ok(scheme) = Scheme::new(scheme_value)
+        {
+            changes.push(crate::state::StateChange::scheme(scheme));
+        }
+
+        changes
+    }
+
+    // ========== Light State ==========
+
+    /// Reads the light state, applies `update`, and writes back what changed.
+    ///
+    /// The current power, dimmer, HSB color and color temperature are read
+    /// into a [`LightState`] (only for supported capabilities). The closure
+    /// returns the desired state, and only the fields that differ are sent to
+    /// the device, together, as a single `Backlog0` routine. If nothing
+    /// changed, no command is sent.
+    ///
+    /// This replaces manual get-then-set sequences for relative adjustments
+    /// such as "dim the current color by 20%".
+    ///
+    /// Returns the resulting [`DeviceState`]: the values read from the device,
+    /// overlaid with the written values and whatever the device reported back.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] with [`DeviceError::UnsupportedCapability`] if
+    /// the device is not a light, or if the closure changes a field the device
+    /// does not support.
+    ///
+    /// Returns [`Error::Protocol`] or [`Error::Parse`] if reading or writing
+    /// the light state fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Dimmer;
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// // Dim the current color by 20 points
+    /// let state = device
+    ///     .update_light(|light| match light.dimmer() {
+    ///         Some(dimmer) => light.with_dimmer(Dimmer::clamped(dimmer.value().saturating_sub(20))),
+    ///         None => light,
+    ///     })
+    ///     .await?;
+    /// println!("Dimmer is now {:?}", state.dimmer());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`DeviceError::UnsupportedCapability`]: crate::error::DeviceError::UnsupportedCapability
+    pub async fn update_light<F>(&self, update: F) -> Result<DeviceState, Error>
+    where
+        F: FnOnce(LightState) -> LightState,
+    {
+        self.check_capability("light control", self.capabilities.is_light())?;
+
+        let current = self.read_light_state().await?;
+        let updated = update(current);
+        self.write_light_state(current, updated).await
+    }
+
+    /// Reads the light state and switches to whichever of `a` and `b` it
+    /// does not match.
+    ///
+    /// The current state is compared to both with
+    /// [`LightState::distance`]: if it matches `a` (allowing for rounding),
+    /// `b` is applied, otherwise `a` is. A light matching neither state is
+    /// set to `a`. This covers two-state buttons such as "movie mode /
+    /// normal mode" without tracking which state is active.
+    ///
+    /// Only the fields that differ from the current state are sent, as a
+    /// single `Backlog0` routine, like [`update_light`](Self::update_light).
+    ///
+    /// Returns which state was applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] with [`DeviceError::UnsupportedCapability`] if
+    /// the device is not a light, or if the applied state sets a field the
+    /// device does not support.
+    ///
+    /// Returns [`Error::Protocol`] or [`Error::Parse`] if reading or writing
+    /// the light state fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::state::LightState;
+    /// use tasmor_lib::{ColorTemperature, Dimmer, LightChoice};
+    ///
+    /// # async fn example(device: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>) -> tasmor_lib::Result<()> {
+    /// let normal = LightState::new()
+    ///     .with_dimmer(Dimmer::MAX)
+    ///     .with_color_temperature(ColorTemperature::NEUTRAL);
+    /// let movie = LightState::new()
+    ///     .with_dimmer(Dimmer::new(20)?)
+    ///     .with_color_temperature(ColorTemperature::CANDLE);
+    ///
+    /// if device.toggle_between(&normal, &movie).await? == LightChoice::B {
+    ///     println!("Movie mode");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`DeviceError::UnsupportedCapability`]: crate::error::DeviceError::UnsupportedCapability
+    pub async fn toggle_between(
+        &self,
+        a: &LightState,
+        b: &LightState,
+    ) -> Result<LightChoice, Error> {
+        self.check_capability("light control", self.capabilities.is_light())?;
+
+        let current = self.read_light_state().await?;
+        let choice = LightState::toggle_target(&current, a, b);
+        let target = match choice {
+            LightChoice::A => a,
+            LightChoice::B => b,
+        };
+        tracing::debug!(?choice, "Toggling light state");
+
+        self.write_light_state(current, *target).await?;
+        Ok(choice)
+    }
+
+    /// Sends the fields of `updated` that differ from `current`.
+    async fn write_light_state(
+        &self,
+        current: LightState,
+        updated: LightState,
+    ) -> Result<DeviceState, Error> {
+        let changes = updated.changes_from(&current);
+
+        let mut state = DeviceState::new();
+        current.apply_to(&mut state);
+
+        if changes.is_empty() {
+            tracing::debug!("Light state unchanged, nothing to write");
+            return Ok(state);
+        }
+
+        let mut builder = crate::command::Routine::builder();
+        for change in &changes {
+            builder = match change {
+                crate::state::StateChange::Power { state: power, .. } => {
+                    builder.set_power(PowerIndex::one(), *power)
+                }
+                crate::state::StateChange::Dimmer(dimmer) => {
+                    self.check_capability("dimmer", self.capabilities.supports_dimmer_control())?;
+                    builder.set_dimmer(*dimmer)
+                }
+                crate::state::StateChange::HsbColor(color) => {
+                    self.check_capability("RGB color", self.capabilities.supports_rgb_control())?;
+                    builder.set_hsb_color(*color)
+                }
+                crate::state::StateChange::ColorTemperature(ct) => {
+                    self.check_capability(
+                        "color temperature",
+                        self.capabilities.supports_color_temperature_control(),
+                    )?;
+                    builder.set_color_temperature(*ct)
+                }
+                // LightState only produces the light changes handled above
+                _ => builder,
+            };
+        }
+        let routine = builder.build()?;
+
+        let response = self.run(&routine).await?;
+
+        updated.apply_to(&mut state);
+        for change in Self::routine_response_changes(&response) {
+            state.apply(&change);
+        }
+
+        Ok(state)
+    }
+
+    /// Reads the current light state for all supported light capabilities.
+    async fn read_light_state(&self) -> Result<LightState, Error> {
+        let mut light = LightState::new();
+
+        let power = self.get_power().await?;
+        light = light.with_power(power.first_power_state().map_err(Error::Parse)?);
+
+        if self.capabilities.supports_dimmer_control() {
+            let dimmer = self.get_dimmer().await?;
+            light = light.with_dimmer(Dimmer::new(dimmer.dimmer())?);
+        }
+
+        if self.capabilities.supports_color_temperature_control() {
+            let ct = self.get_color_temperature().await?;
+            light = light.with_color_temperature(ct.to_color_temperature().map_err(Error::Parse)?);
+        }
+
+        if self.capabilities.supports_rgb_control() {
+            let hsb = self.get_hsb_color().await?;
+            light = light.with_hsb_color(hsb.hsb_color().map_err(Error::Parse)?);
+        }
+
+        Ok(light)
+    }
+
+    /// Captures the light output exactly, in a single `State` query.
+    ///
+    /// The snapshot holds power, dimmer, the raw `Channel` values, HSB
+    /// color, color temperature and scheme. Use it with
+    /// [`apply_light_snapshot`](Self::apply_light_snapshot) to restore the
+    /// output later or mirror it on another light.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] with [`DeviceError::UnsupportedCapability`] if
+    /// the device is not a light.
+    ///
+    /// Returns [`Error::Protocol`] or [`Error::Parse`] if the query fails.
+    ///
+    /// [`DeviceError::UnsupportedCapability`]: crate::error::DeviceError::UnsupportedCapability
+    pub async fn get_light_snapshot(&self) -> Result<LightSnapshot, Error> {
+        self.check_capability("light control", self.capabilities.is_light())?;
+        let response = self.send_command(&StateCommand).await?;
+        let state: TelemetryState = response.parse().map_err(Error::Parse)?;
+
+        for change in state.to_state_changes() {
+            self.dispatch(&change);
+        }
+
+        Ok(LightSnapshot::from_telemetry(&state))
+    }
+
+    /// Replays a light snapshot as a single `Backlog0` routine.
+    ///
+    /// When this light has as many channels as the snapshot, every channel
+    /// is set to its captured value, reproducing the output exactly. When
+    /// the channel counts differ (e.g. mirroring an RGBCCT bulb on an RGB
+    /// strip), the snapshot falls back to CT and dimmer for white output or
+    /// lights without RGB, and to HSB color and dimmer otherwise.
+    ///
+    /// The scheme is set first and the power state last, since setting a
+    /// channel or color turns the light on.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] with [`DeviceError::UnsupportedCapability`] if
+    /// the device is not a light.
+    ///
+    /// Returns [`Error::Protocol`] or [`Error::Parse`] if reading the
+    /// channel layout or sending the routine fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn example(
+    /// #     source: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>,
+    /// #     mirror: &tasmor_lib::Device<impl tasmor_lib::protocol::Protocol>,
+    /// # ) -> tasmor_lib::Result<()> {
+    /// let snapshot = source.get_light_snapshot().await?;
+    /// mirror.apply_light_snapshot(&snapshot).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`DeviceError::UnsupportedCapability`]: crate::error::DeviceError::UnsupportedCapability
+    pub async fn apply_light_snapshot(
+        &self,
+        snapshot: &LightSnapshot,
+    ) -> Result<crate::response::RoutineResponse, Error> {
+        let current = self.get_light_snapshot().await?;
+        let channels = snapshot.channels();
+
+        let mut builder = crate::command::Routine::builder();
+        if let Some(scheme) = snapshot.scheme() {
+            builder = builder.set_scheme(scheme);
+        }
+
+        if !channels.is_empty() && channels.len() == current.channels().len() {
+            for (index, &value) in (1u8..).zip(channels) {
+                builder = builder.add_command(&ChannelCommand::set(index, value)?);
+            }
+        } else {
+            let rgb = self.capabilities.supports_rgb_control();
+            let white = snapshot.is_white() || !rgb;
+            if white
+                && let Some(ct) = snapshot.color_temperature()
+                && self.capabilities.supports_color_temperature_control()
+            {
+                builder = builder.set_color_temperature(ct);
+            }
+            if !white
+                && let Some(color) = snapshot.hsb_color()
+                && rgb
+            {
+                builder = builder.set_hsb_color(color);
+            }
+            if let Some(dimmer) = snapshot.dimmer()
+                && self.capabilities.supports_dimmer_control()
+            {
+                builder = builder.set_dimmer(dimmer);
+            }
         }
 
-        // Parse Scheme if present
-        if let Some(scheme_value) = response.try_get_as::<u8>("Scheme")
-            && let Ok(scheme) = Scheme::new(scheme_value)
-        {
-            let change = crate::state::StateChange::scheme(scheme);
-            self.callbacks.dispatch(&change);
+        if let Some(power) = snapshot.power() {
+            builder = builder.set_power(PowerIndex::one(), power);
         }
+
+        self.run(&builder.build()?).await
     }
 
     // ========== Initial State Query ==========
@@ -1294,6 +4637,10 @@ impl<P: Protocol> Device<P> {
                 if !sys_info.is_empty() {
                     state.set_system_info(sys_info);
                 }
+
+                if let Some(name) = status_response.device_name().filter(|n| !n.is_empty()) {
+                    state.set_device_name(name);
+                }
             }
             Err(e) => tracing::debug!(error = %e, "Failed to get status for system info"),
         }
@@ -1378,6 +4725,88 @@ impl Device<HttpClient> {
     }
 }
 
+// ========== HTTP Provisioning ==========
+
+#[cfg(feature = "http")]
+impl Device<HttpClient> {
+    /// Points the device at an MQTT broker and enables MQTT.
+    ///
+    /// Sends `MqttHost`, `MqttPort`, `MqttUser`, `MqttPassword` and
+    /// `SetOption3 1` as one backlog. This is the step that moves a device
+    /// only reachable over HTTP into an MQTT fleet: once it has connected,
+    /// control it through [`MqttBroker::device`](crate::MqttBroker::device)
+    /// using its topic.
+    ///
+    /// Tasmota restarts shortly after these settings change, then connects
+    /// to the new broker; the HTTP API stays available on the same address.
+    /// If the connection drops after the commands were sent but before the
+    /// device replies, the returned response is not confirmed
+    /// ([`MqttConfigResponse::is_confirmed`](crate::response::MqttConfigResponse::is_confirmed)
+    /// is `false`) and carries no fields. An empty user or password clears
+    /// it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Value`] if the host, port, user or password cannot be
+    /// stored by Tasmota, or an error if the device cannot be reached, does
+    /// not reply in time, or the command fails for a reason other than the
+    /// restart.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::Device;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let (device, _) = Device::http("192.168.1.100").build().await?;
+    /// let confirmed = device
+    ///     .configure_mqtt("broker.lan", 1883, "tasmota", "s3cret")
+    ///     .await?;
+    /// println!("Connecting to {:?}", confirmed.host());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn configure_mqtt(
+        &self,
+        host: &str,
+        port: u16,
+        user: &str,
+        password: &str,
+    ) -> Result<crate::response::MqttConfigResponse, Error> {
+        use crate::command::MqttConfigCommand;
+
+        // A ';' would split the backlog
+        if password.contains(';') {
+            return Err(Error::Value(crate::error::ValueError::InvalidPassword(
+                "contains ';'".to_string(),
+            )));
+        }
+        let routine = crate::command::Routine::builder()
+            .add_command(&MqttConfigCommand::host(host)?)
+            .add_command(&MqttConfigCommand::port(port)?)
+            .add_command(&MqttConfigCommand::user(user)?)
+            .add_command(&SecurityCommand::mqtt_password(password)?)
+            .add_command(&MqttConfigCommand::SetEnabled(true))
+            .build()?;
+
+        let response = match self.run(&routine).await {
+            Ok(response) => response,
+            Err(Error::Protocol(e)) if is_dropped_by_restart(&e) => {
+                tracing::info!(host, port, error = %e, "Connection dropped after MQTT change, device is restarting");
+                return Ok(crate::response::MqttConfigResponse::unconfirmed());
+            }
+            Err(e) => return Err(e),
+        };
+
+        let fields: serde_json::Map<String, serde_json::Value> = response
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        serde_json::from_value(serde_json::Value::Object(fields))
+            .map_err(|e| Error::Parse(e.into()))
+    }
+}
+
 // ========== MQTT Device Subscriptions ==========
 
 #[cfg(feature = "mqtt")]
@@ -1385,7 +4814,11 @@ use crate::protocol::SharedMqttClient;
 #[cfg(feature = "mqtt")]
 use crate::state::StateChange;
 #[cfg(feature = "mqtt")]
-use crate::subscription::{EnergyData, Subscribable, SubscriptionId};
+use crate::subscription::{EnergyData, Subscribable, SubscriptionGuard, SubscriptionId, Throttle};
+#[cfg(feature = "mqtt")]
+use crate::telemetry::TuyaDataPoint;
+#[cfg(feature = "mqtt")]
+use crate::types::ButtonAction;
 
 #[cfg(feature = "mqtt")]
 impl Device<SharedMqttClient> {
@@ -1396,6 +4829,58 @@ impl Device<SharedMqttClient> {
         self.protocol.register_callbacks(&self.callbacks);
     }
 
+    /// Replaces the callback registry with one whose state streams buffer
+    /// `capacity` changes.
+    ///
+    /// Must be called before [`register_callbacks`](Self::register_callbacks).
+    pub(crate) fn with_state_stream_capacity(mut self, capacity: usize) -> Self {
+        self.callbacks = Arc::new(CallbackRegistry::with_state_stream_capacity(capacity));
+        self
+    }
+
+    /// Returns a stream of the device's state changes.
+    ///
+    /// The receiver gets every [`StateChange`](crate::state::StateChange)
+    /// dispatched after this call, through the same path as the
+    /// [`Subscribable`] callbacks: telemetry, command results and the
+    /// responses of this device's own commands. Each receiver is
+    /// independent, so several tasks can follow the same device.
+    ///
+    /// A receiver buffers up to the capacity set with
+    /// [`BrokerDeviceBuilder::with_state_stream_capacity`](crate::device::BrokerDeviceBuilder::with_state_stream_capacity)
+    /// (default [`DEFAULT_STATE_STREAM_CAPACITY`](crate::subscription::DEFAULT_STATE_STREAM_CAPACITY)).
+    /// A receiver that falls further behind drops the oldest changes: its
+    /// next `recv()` returns `RecvError::Lagged` with the number skipped,
+    /// then continues with the oldest change still buffered.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::MqttBroker;
+    /// use tasmor_lib::state::StateChange;
+    /// use tokio::sync::broadcast::error::RecvError;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let broker = MqttBroker::builder().host("192.168.1.50").build().await?;
+    /// let (device, _) = broker.device("tasmota_bulb").build().await?;
+    ///
+    /// let mut changes = device.state_stream();
+    /// loop {
+    ///     match changes.recv().await {
+    ///         Ok(StateChange::Power { index, state }) => println!("POWER{index} {state}"),
+    ///         Ok(change) => println!("{change:?}"),
+    ///         Err(RecvError::Lagged(skipped)) => println!("Missed {skipped} changes"),
+    ///         Err(RecvError::Closed) => break,
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn state_stream(&self) -> tokio::sync::broadcast::Receiver<crate::state::StateChange> {
+        self.callbacks.state_stream()
+    }
+
     /// Disconnects and cleans up MQTT subscriptions.
     ///
     /// This unsubscribes from device topics on the broker. The shared
@@ -1403,6 +4888,12 @@ impl Device<SharedMqttClient> {
     ///
     /// This method is idempotent - calling it multiple times is safe.
     ///
+    /// Dropping the last clone of a device without calling this spawns the
+    /// same cleanup on the current Tokio runtime, so subscriptions are not
+    /// leaked. That cleanup runs at an unspecified later time (and not at
+    /// all without a runtime), so prefer awaiting `disconnect` when the
+    /// cleanup must be finished, e.g. before shutting down.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -1458,6 +4949,260 @@ impl Device<SharedMqttClient> {
     pub fn topic(&self) -> &str {
         self.protocol.topic()
     }
+
+    /// Pauses state change callbacks, e.g. during a scene transition.
+    ///
+    /// Messages keep arriving and being parsed, so the device's state stays
+    /// current; only the notifications are held back. When
+    /// [`resume_updates`](Self::resume_updates) is called, each field that
+    /// changed in the meantime fires once, with its latest value, so
+    /// intermediate values never reach the UI.
+    ///
+    /// Connection, button, Tuya and RESULT callbacks are not paused. The
+    /// pause applies to all clones of this device.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::MqttBroker;
+    /// use tasmor_lib::types::Dimmer;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let broker = MqttBroker::builder().host("192.168.1.50").build().await?;
+    /// let (device, _) = broker.device("tasmota_bulb").build().await?;
+    ///
+    /// device.pause_updates();
+    /// for level in [20, 40, 60, 80] {
+    ///     device.set_dimmer(Dimmer::new(level)?).await?;
+    /// }
+    /// // Dimmer callbacks fire once, with 80
+    /// device.resume_updates();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pause_updates(&self) {
+        self.callbacks.pause();
+    }
+
+    /// Resumes state change callbacks paused by
+    /// [`pause_updates`](Self::pause_updates).
+    ///
+    /// Each field that changed while paused fires once, with its latest
+    /// value.
+    pub fn resume_updates(&self) {
+        self.callbacks.resume();
+    }
+
+    /// Returns `true` if state change callbacks are paused.
+    #[must_use]
+    pub fn updates_paused(&self) -> bool {
+        self.callbacks.is_paused()
+    }
+
+    // ========== Scoped Subscriptions ==========
+
+    /// Wraps a subscription in a guard that unsubscribes it on drop.
+    fn scoped(&self, id: SubscriptionId) -> SubscriptionGuard {
+        SubscriptionGuard::new(&self.callbacks, id)
+    }
+
+    /// Subscribes to power state changes for as long as the returned guard
+    /// is alive.
+    ///
+    /// Scoped variant of [`on_power_changed`](Subscribable::on_power_changed):
+    /// the callback is removed when the [`SubscriptionGuard`] is dropped,
+    /// including on early return or panic.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::MqttBroker;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let broker = MqttBroker::builder().host("192.168.1.50").build().await?;
+    /// let (device, _) = broker.device("tasmota_plug").build().await?;
+    ///
+    /// {
+    ///     let _guard = device.subscribe_power_scoped(|index, state| {
+    ///         println!("Relay {index} is now {state:?}");
+    ///     });
+    ///     device.power_toggle().await?;
+    /// } // Unsubscribed here
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn subscribe_power_scoped<F>(&self, callback: F) -> SubscriptionGuard
+    where
+        F: Fn(u8, PowerState) + Send + Sync + 'static,
+    {
+        self.scoped(self.on_power_changed(callback))
+    }
+
+    /// Subscribes to dimmer value changes for as long as the returned guard is alive.
+    ///
+    /// Scoped variant of [`on_dimmer_changed`](Subscribable::on_dimmer_changed).
+    pub fn subscribe_dimmer_scoped<F>(&self, callback: F) -> SubscriptionGuard
+    where
+        F: Fn(Dimmer) + Send + Sync + 'static,
+    {
+        self.scoped(self.on_dimmer_changed(callback))
+    }
+
+    /// Subscribes to HSB color changes for as long as the returned guard is alive.
+    ///
+    /// Scoped variant of [`on_color_changed`](Subscribable::on_color_changed).
+    pub fn subscribe_color_scoped<F>(&self, callback: F) -> SubscriptionGuard
+    where
+        F: Fn(HsbColor) + Send + Sync + 'static,
+    {
+        self.scoped(self.on_color_changed(callback))
+    }
+
+    /// Subscribes to color temperature changes for as long as the returned guard is alive.
+    ///
+    /// Scoped variant of [`on_color_temp_changed`](Subscribable::on_color_temp_changed).
+    pub fn subscribe_color_temp_scoped<F>(&self, callback: F) -> SubscriptionGuard
+    where
+        F: Fn(ColorTemperature) + Send + Sync + 'static,
+    {
+        self.scoped(self.on_color_temp_changed(callback))
+    }
+
+    /// Subscribes to scheme changes for as long as the returned guard is alive.
+    ///
+    /// Scoped variant of [`on_scheme_changed`](Subscribable::on_scheme_changed).
+    pub fn subscribe_scheme_scoped<F>(&self, callback: F) -> SubscriptionGuard
+    where
+        F: Fn(Scheme) + Send + Sync + 'static,
+    {
+        self.scoped(self.on_scheme_changed(callback))
+    }
+
+    /// Subscribes to energy monitoring changes for as long as the returned guard is alive.
+    ///
+    /// Scoped variant of [`on_energy_changed`](Subscribable::on_energy_changed).
+    pub fn subscribe_energy_scoped<F>(&self, callback: F) -> SubscriptionGuard
+    where
+        F: Fn(EnergyData) + Send + Sync + 'static,
+    {
+        self.scoped(self.on_energy_changed(callback))
+    }
+
+    /// Subscribes to energy monitoring changes, calling `callback` at most
+    /// once per `min_interval`.
+    ///
+    /// With a low `TelePeriod` or `PowerDelta`, energy telemetry can arrive
+    /// many times per second. Updates arriving within `min_interval` of the
+    /// last call are coalesced, and the latest one is delivered once the
+    /// interval has passed, so the final reading is never lost. This limits
+    /// callback frequency on the client only; the device keeps publishing
+    /// at its configured rate.
+    ///
+    /// Unsubscribe with [`unsubscribe`](Subscribable::unsubscribe) as for
+    /// [`on_energy_changed`](Subscribable::on_energy_changed); a reading
+    /// already held back may still be delivered once.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tasmor_lib::MqttBroker;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let broker = MqttBroker::builder().host("192.168.1.50").build().await?;
+    /// let (device, _) = broker.device("tasmota_plug").build().await?;
+    ///
+    /// // Refresh the power gauge at most twice per second
+    /// device.on_energy_changed_throttled(Duration::from_millis(500), |energy| {
+    ///     println!("Power: {:?} W", energy.power);
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_energy_changed_throttled<F>(
+        &self,
+        min_interval: Duration,
+        callback: F,
+    ) -> SubscriptionId
+    where
+        F: Fn(EnergyData) + Send + Sync + 'static,
+    {
+        let throttle = Throttle::new(min_interval, Arc::new(callback));
+        self.on_energy_changed(move |energy| throttle.push(energy))
+    }
+
+    /// Subscribes to connection events for as long as the returned guard is alive.
+    ///
+    /// Scoped variant of [`on_connected`](Subscribable::on_connected).
+    pub fn subscribe_connected_scoped<F>(&self, callback: F) -> SubscriptionGuard
+    where
+        F: Fn(&DeviceState) + Send + Sync + 'static,
+    {
+        self.scoped(self.on_connected(callback))
+    }
+
+    /// Subscribes to disconnection events for as long as the returned guard is alive.
+    ///
+    /// Scoped variant of [`on_disconnected`](Subscribable::on_disconnected).
+    pub fn subscribe_disconnected_scoped<F>(&self, callback: F) -> SubscriptionGuard
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.scoped(self.on_disconnected(callback))
+    }
+
+    /// Subscribes to reconnection events for as long as the returned guard is alive.
+    ///
+    /// Scoped variant of [`on_reconnected`](Subscribable::on_reconnected).
+    pub fn subscribe_reconnected_scoped<F>(&self, callback: F) -> SubscriptionGuard
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.scoped(self.on_reconnected(callback))
+    }
+
+    /// Subscribes to all state changes for as long as the returned guard is alive.
+    ///
+    /// Scoped variant of [`on_state_changed`](Subscribable::on_state_changed).
+    pub fn subscribe_state_scoped<F>(&self, callback: F) -> SubscriptionGuard
+    where
+        F: Fn(&StateChange) + Send + Sync + 'static,
+    {
+        self.scoped(self.on_state_changed(callback))
+    }
+
+    /// Subscribes to Tuya MCU data point reports for as long as the returned
+    /// guard is alive.
+    ///
+    /// Scoped variant of [`on_tuya_received`](Subscribable::on_tuya_received).
+    pub fn subscribe_tuya_scoped<F>(&self, callback: F) -> SubscriptionGuard
+    where
+        F: Fn(&TuyaDataPoint) + Send + Sync + 'static,
+    {
+        self.scoped(self.on_tuya_received(callback))
+    }
+
+    /// Subscribes to presses of detached buttons for as long as the returned
+    /// guard is alive.
+    ///
+    /// Scoped variant of [`on_button_action`](Subscribable::on_button_action).
+    pub fn subscribe_button_scoped<F>(&self, callback: F) -> SubscriptionGuard
+    where
+        F: Fn(u8, ButtonAction) + Send + Sync + 'static,
+    {
+        self.scoped(self.on_button_action(callback))
+    }
+
+    /// Subscribes to raw command results for as long as the returned guard
+    /// is alive.
+    ///
+    /// Scoped variant of [`on_result`](Subscribable::on_result).
+    pub fn subscribe_result_scoped<F>(&self, callback: F) -> SubscriptionGuard
+    where
+        F: Fn(&str, &serde_json::Value) + Send + Sync + 'static,
+    {
+        self.scoped(self.on_result(callback))
+    }
 }
 
 #[cfg(feature = "mqtt")]
@@ -1469,6 +5214,17 @@ impl Subscribable for Device<SharedMqttClient> {
         self.callbacks.on_power_changed(callback)
     }
 
+    fn on_power_index_changed<F>(
+        &self,
+        index: u8,
+        callback: F,
+    ) -> Result<SubscriptionId, crate::error::ValueError>
+    where
+        F: Fn(PowerState) + Send + Sync + 'static,
+    {
+        self.callbacks.on_power_index_changed(index, callback)
+    }
+
     fn on_dimmer_changed<F>(&self, callback: F) -> SubscriptionId
     where
         F: Fn(Dimmer) + Send + Sync + 'static,
@@ -1532,16 +5288,170 @@ impl Subscribable for Device<SharedMqttClient> {
         self.callbacks.on_state_changed(callback)
     }
 
+    fn on_tuya_received<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&TuyaDataPoint) + Send + Sync + 'static,
+    {
+        self.callbacks.on_tuya_received(callback)
+    }
+
+    fn on_button_action<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(u8, ButtonAction) + Send + Sync + 'static,
+    {
+        self.callbacks.on_button_action(callback)
+    }
+
+    fn on_result<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&str, &serde_json::Value) + Send + Sync + 'static,
+    {
+        self.callbacks.on_result(callback)
+    }
+
     fn unsubscribe(&self, id: SubscriptionId) -> bool {
         self.callbacks.unsubscribe(id)
     }
 }
 
+/// Checks that a command was acknowledged with `{"<command>":"Done"}`.
+fn check_done(response: &CommandResponse, command: &str) -> Result<(), Error> {
+    let body: serde_json::Value = response.parse().map_err(Error::Parse)?;
+    if body.get(command).and_then(serde_json::Value::as_str) == Some("Done") {
+        Ok(())
+    } else {
+        Err(Error::Device(DeviceError::CommandRejected(
+            response.body().to_string(),
+        )))
+    }
+}
+
+/// Returns `true` if an error means the device dropped the connection
+/// after receiving the request, as happens when it restarts while replying.
+///
+/// Connection failures and timeouts are excluded: an HTTP timeout also
+/// covers connecting, so the command may never have reached the device.
+fn is_dropped_by_restart(error: &ProtocolError) -> bool {
+    match error {
+        #[cfg(feature = "http")]
+        ProtocolError::Http(e) => {
+            !e.is_connect() && !e.is_timeout() && (e.is_request() || e.is_body())
+        }
+        _ => false,
+    }
+}
+
+/// Returns `true` if an error means the device never answered.
+fn is_no_response(error: &ProtocolError) -> bool {
+    match error {
+        ProtocolError::Timeout(_) => true,
+        #[cfg(feature = "http")]
+        ProtocolError::Http(e) => e.is_timeout() || e.is_connect(),
+        _ => false,
+    }
+}
+
+/// A `dyn Command` passed to the generic send path.
+struct DynCommand<'a>(&'a (dyn Command + Sync));
+
+impl Command for DynCommand<'_> {
+    fn name(&self) -> String {
+        self.0.name()
+    }
+
+    fn payload(&self) -> Option<String> {
+        self.0.payload()
+    }
+
+    fn aliases(&self) -> Vec<String> {
+        self.0.aliases()
+    }
+
+    fn to_http_command(&self) -> String {
+        self.0.to_http_command()
+    }
+
+    fn mqtt_topic_suffix(&self) -> String {
+        self.0.mqtt_topic_suffix()
+    }
+
+    fn mqtt_payload(&self) -> String {
+        self.0.mqtt_payload()
+    }
+
+    fn response_spec(&self) -> crate::protocol::ResponseSpec {
+        self.0.response_spec()
+    }
+
+    fn priority(&self) -> CommandPriority {
+        self.0.priority()
+    }
+
+    fn is_idempotent(&self) -> bool {
+        self.0.is_idempotent()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::protocol::HttpConfig;
 
+    /// Protocol answering `{"POWER":"ON"}`, except that the `fail_on`-th
+    /// command times out.
+    struct FlakyProtocol {
+        sent: parking_lot::Mutex<Vec<String>>,
+        fail_on: usize,
+    }
+
+    impl Protocol for FlakyProtocol {
+        async fn send_command<C: Command + Sync>(
+            &self,
+            command: &C,
+        ) -> Result<CommandResponse, ProtocolError> {
+            self.send_raw(&command.to_http_command()).await
+        }
+
+        async fn send_raw(&self, command: &str) -> Result<CommandResponse, ProtocolError> {
+            let mut sent = self.sent.lock();
+            sent.push(command.to_string());
+            if sent.len() == self.fail_on {
+                return Err(ProtocolError::Timeout(100));
+            }
+            Ok(CommandResponse::new(r#"{"POWER":"ON"}"#.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn run_sequential_stops_at_first_failure() {
+        let protocol = FlakyProtocol {
+            sent: parking_lot::Mutex::new(Vec::new()),
+            fail_on: 2,
+        };
+        let device = Device::new(protocol, Capabilities::basic());
+        let power_changes = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&power_changes);
+        device.callbacks.on_power_changed(move |_, _| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        });
+
+        let on = PowerCommand::on(PowerIndex::one());
+        let query = PowerCommand::Get {
+            index: PowerIndex::one(),
+        };
+        let off = PowerCommand::off(PowerIndex::one());
+        let results = device.run_sequential(&[&on, &query, &off]).await;
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(matches!(
+            results[1],
+            Err(Error::Protocol(ProtocolError::Timeout(100)))
+        ));
+        assert_eq!(device.protocol.sent.lock().len(), 2);
+        assert_eq!(power_changes.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
     #[test]
     fn http_device_builder_from_config() {
         let config = HttpConfig::new("192.168.1.100").with_credentials("admin", "pass");
@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Capability detection.
+
+use crate::capabilities::Capabilities;
+use crate::command::StatusCommand;
+use crate::error::Error;
+use crate::protocol::Protocol;
+use crate::response::StatusResponse;
+
+/// Detects a device's capabilities from its status reports.
+///
+/// Device parameters (`Status 1`) are required; runtime state (`Status 11`)
+/// and sensor data (`Status 10`) are merged in when available.
+pub(crate) async fn probe_capabilities<P: Protocol>(protocol: &P) -> Result<Capabilities, Error> {
+    // Query device parameters (Status 1) for FriendlyName count
+    let cmd = StatusCommand::device_parameters();
+    let response = protocol.send_command(&cmd).await.map_err(Error::Protocol)?;
+    let mut status: StatusResponse = response.parse().map_err(Error::Parse)?;
+
+    // Query runtime state (Status 11) for light/energy capabilities
+    let cmd_state = StatusCommand::state();
+    if let Ok(state_response) = protocol.send_command(&cmd_state).await
+        && let Ok(state_status) = state_response.parse::<StatusResponse>()
+    {
+        // Merge sensor_status from Status 11 into our status
+        status.sensor_status = state_status.sensor_status;
+    }
+
+    // Query sensor info (Status 10) for ENERGY data
+    let cmd_sensors = StatusCommand::sensors();
+    if let Ok(sensors_response) = protocol.send_command(&cmd_sensors).await
+        && let Ok(sensors_status) = sensors_response.parse::<StatusResponse>()
+    {
+        // Merge sensors from Status 10 into our status
+        status.sensors = sensors_status.sensors;
+    }
+
+    Ok(Capabilities::from_status(&status))
+}
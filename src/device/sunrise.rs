@@ -0,0 +1,209 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Sunrise routine planning.
+//!
+//! A sunrise is a single `Backlog0` routine: fade is enabled, the light
+//! starts at the lowest level in a warm tone, then `Delay` steps move it
+//! towards the target while the device fades between them.
+
+use std::time::Duration;
+
+use crate::command::{MAX_ROUTINE_STEPS, Routine, RoutineBuilder};
+use crate::error::Error;
+use crate::types::{ColorTemperature, Dimmer, FadeDuration, HsbColor};
+
+/// Shortest interval between two sunrise steps.
+const MIN_SUNRISE_STEP: Duration = Duration::from_secs(1);
+
+/// Hue a color sunrise starts from (deep orange).
+const START_HUE: u16 = 15;
+
+/// Color temperature a white sunrise starts from.
+const START_CT: ColorTemperature = ColorTemperature::CANDLE;
+
+/// Brightness a sunrise starts from.
+const START_BRIGHTNESS: u8 = 1;
+
+/// Builds a sunrise ramping hue, saturation and brightness to `target`.
+///
+/// Each step is a `Delay` and an `HSBColor`, allowing up to 13 steps.
+pub(crate) fn color_sunrise(duration: Duration, target: HsbColor) -> Result<Routine, Error> {
+    let (steps, delay) = plan(duration, 3, 2);
+    let brightness_step = u32::from(target.brightness().saturating_sub(START_BRIGHTNESS)) / steps;
+    let hue_delta = (i32::from(target.hue()) - i32::from(START_HUE) + 540) % 360 - 180;
+
+    let color_at = |i: u32| {
+        let hue = (i32::from(START_HUE) + lerp(0, hue_delta, i, steps)).rem_euclid(360);
+        let saturation = lerp(100, i32::from(target.saturation()), i, steps);
+        let brightness = lerp(
+            i32::from(START_BRIGHTNESS),
+            i32::from(target.brightness()),
+            i,
+            steps,
+        );
+        // Interpolated values stay between valid endpoints
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        HsbColor::new(hue as u16, saturation as u8, brightness as u8)
+    };
+
+    let mut builder = start(delay, brightness_step)?.set_hsb_color(color_at(0)?);
+    for i in 1..=steps {
+        builder = builder.delay(delay).set_hsb_color(color_at(i)?);
+    }
+    builder.build()
+}
+
+/// Builds a sunrise ramping from candle light to `target_ct` and
+/// `target_dimmer`.
+///
+/// Each step is a `Delay`, a `CT` and a `Dimmer`, allowing up to 8 steps.
+pub(crate) fn white_sunrise(
+    duration: Duration,
+    target_ct: ColorTemperature,
+    target_dimmer: Dimmer,
+) -> Result<Routine, Error> {
+    let (steps, delay) = plan(duration, 4, 3);
+    let brightness_step = u32::from(target_dimmer.value().saturating_sub(START_BRIGHTNESS)) / steps;
+
+    let ct_at = |i: u32| {
+        let ct = lerp(
+            i32::from(START_CT.value()),
+            i32::from(target_ct.value()),
+            i,
+            steps,
+        );
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        ColorTemperature::clamped(ct as u16)
+    };
+    let dimmer_at = |i: u32| {
+        let level = lerp(
+            i32::from(START_BRIGHTNESS),
+            i32::from(target_dimmer.value()),
+            i,
+            steps,
+        );
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Dimmer::clamped(level as u8)
+    };
+
+    let mut builder = start(delay, brightness_step)?
+        .set_color_temperature(ct_at(0))
+        .set_dimmer(dimmer_at(0));
+    for i in 1..=steps {
+        builder = builder
+            .delay(delay)
+            .set_color_temperature(ct_at(i))
+            .set_dimmer(dimmer_at(i));
+    }
+    builder.build()
+}
+
+/// Returns the step count and the delay between steps.
+///
+/// Steps are at least [`MIN_SUNRISE_STEP`] apart and limited by the routine
+/// capacity left after `setup` commands. The delay is rounded down to the
+/// 100ms resolution of `Delay`.
+fn plan(duration: Duration, setup: usize, per_step: usize) -> (u32, Duration) {
+    let capacity = u32::try_from((MAX_ROUTINE_STEPS - setup) / per_step).unwrap_or(1);
+    let wanted =
+        u32::try_from(duration.as_millis() / MIN_SUNRISE_STEP.as_millis()).unwrap_or(u32::MAX);
+    let steps = wanted.clamp(1, capacity);
+
+    let delay_ms = u64::try_from(duration.as_millis() / u128::from(steps)).unwrap_or(u64::MAX);
+    let delay = Duration::from_millis((delay_ms / 100 * 100).max(100));
+    (steps, delay)
+}
+
+/// Starts the routine by enabling fade with a speed matching the steps.
+///
+/// `Speed` is the time of a full 0-100% fade, so it is scaled by the
+/// brightness change of one step to make each step fade over its delay.
+fn start(delay: Duration, brightness_step: u32) -> Result<RoutineBuilder, Error> {
+    let full_fade = delay * 100 / brightness_step.max(1);
+    let speed = FadeDuration::new(full_fade.clamp(FadeDuration::MIN, FadeDuration::MAX))?;
+    Ok(Routine::builder().enable_fade().set_fade_duration(speed))
+}
+
+/// Linear interpolation from `from` to `to` at step `i` of `steps`.
+fn lerp(from: i32, to: i32, i: u32, steps: u32) -> i32 {
+    let (i, steps) = (i64::from(i), i64::from(steps));
+    let value = i64::from(from) + (i64::from(to) - i64::from(from)) * i / steps;
+    i32::try_from(value).unwrap_or(to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(routine: &Routine) -> Vec<String> {
+        routine
+            .to_backlog_command()
+            .trim_start_matches("Backlog0 ")
+            .split("; ")
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn white_sunrise_ramps_ct_and_dimmer() {
+        let routine = white_sunrise(
+            Duration::from_secs(600),
+            ColorTemperature::NEUTRAL,
+            Dimmer::new(81).unwrap(),
+        )
+        .unwrap();
+        let steps = steps(&routine);
+
+        // Fade setup, start, then 8 steps of Delay + CT + Dimmer
+        assert_eq!(steps.len(), 4 + 8 * 3);
+        assert_eq!(steps[0], "Fade 1");
+        // 75s per step and 10 points per step: full fade would take 750s
+        assert_eq!(steps[1], "Speed 40");
+        assert_eq!(&steps[2..4], ["CT 500", "Dimmer 1"]);
+        assert_eq!(&steps[4..7], ["Delay 750", "CT 469", "Dimmer 11"]);
+        assert_eq!(&steps[25..], ["Delay 750", "CT 250", "Dimmer 81"]);
+    }
+
+    #[test]
+    fn color_sunrise_takes_shortest_hue_path() {
+        // From hue 15 to 300 goes down through red (-75 degrees)
+        let routine =
+            color_sunrise(Duration::from_secs(5), HsbColor::new(300, 50, 100).unwrap()).unwrap();
+        let steps = steps(&routine);
+
+        assert_eq!(steps.len(), 3 + 5 * 2);
+        // 1s per step and 19 points per step: full fade would take 5.3s
+        assert_eq!(steps[1], "Speed 11");
+        assert_eq!(steps[2], "HSBColor 15,100,1");
+        assert_eq!(&steps[3..5], ["Delay 10", "HSBColor 0,90,20"]);
+        assert_eq!(steps[12], "HSBColor 300,50,100");
+    }
+
+    #[test]
+    fn short_sunrise_uses_one_step() {
+        let routine = white_sunrise(
+            Duration::from_millis(250),
+            ColorTemperature::COOL,
+            Dimmer::MAX,
+        )
+        .unwrap();
+        let steps = steps(&routine);
+
+        assert_eq!(steps.len(), 4 + 3);
+        assert_eq!(steps[1], "Speed 1");
+        assert_eq!(&steps[4..], ["Delay 2", "CT 153", "Dimmer 100"]);
+    }
+
+    #[test]
+    fn delays_are_quantized() {
+        let (steps, delay) = plan(Duration::from_millis(10_550), 3, 2);
+        assert_eq!(steps, 10);
+        assert_eq!(delay, Duration::from_millis(1000));
+
+        let (steps, _) = plan(Duration::from_secs(3600), 3, 2);
+        assert_eq!(steps, 13);
+    }
+}
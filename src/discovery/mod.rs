@@ -72,11 +72,10 @@
 //! ```
 
 use std::collections::HashSet;
-use std::sync::Arc;
 use std::time::Duration;
 
 use rumqttc::QoS;
-use tokio::sync::RwLock;
+use tokio::task::JoinSet;
 
 use crate::device::Device;
 use crate::error::{Error, ProtocolError};
@@ -86,10 +85,17 @@ use crate::state::DeviceState;
 /// Default discovery timeout.
 const DEFAULT_DISCOVERY_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// Default number of devices created concurrently after discovery.
+const DEFAULT_CREATION_CONCURRENCY: usize = 4;
+
+/// A discovered device with its initial state.
+type DiscoveredDevice = (Device<SharedMqttClient>, DeviceState);
+
 /// Options for standalone MQTT device discovery.
 ///
 /// Use this when calling [`discover_devices`] without an existing broker connection.
-/// When using [`MqttBroker::discover_devices`], only the timeout is needed.
+/// When using [`MqttBroker::discover_devices_with_options`], the connection
+/// settings (credentials, port) are ignored.
 ///
 /// # Examples
 ///
@@ -99,7 +105,9 @@ const DEFAULT_DISCOVERY_TIMEOUT: Duration = Duration::from_secs(5);
 ///
 /// let options = DiscoveryOptions::new()
 ///     .with_timeout(Duration::from_secs(10))
-///     .with_credentials("user", "pass");
+///     .with_credentials("user", "pass")
+///     .with_max_devices(20)
+///     .with_creation_concurrency(8);
 /// ```
 #[derive(Debug, Clone, Default)]
 pub struct DiscoveryOptions {
@@ -109,6 +117,10 @@ pub struct DiscoveryOptions {
     credentials: Option<(String, String)>,
     /// MQTT broker port (default: 1883).
     port: Option<u16>,
+    /// Maximum number of device topics to collect.
+    max_devices: Option<usize>,
+    /// Maximum number of devices created concurrently.
+    creation_concurrency: Option<usize>,
 }
 
 impl DiscoveryOptions {
@@ -159,6 +171,28 @@ impl DiscoveryOptions {
         self
     }
 
+    /// Caps the number of devices discovered.
+    ///
+    /// Discovery stops listening as soon as `max` device topics have been
+    /// collected, without waiting for the timeout. A value of 0 is treated
+    /// as 1.
+    #[must_use]
+    pub fn with_max_devices(mut self, max: usize) -> Self {
+        self.max_devices = Some(max.max(1));
+        self
+    }
+
+    /// Sets how many discovered devices are created concurrently.
+    ///
+    /// Creating a device queries its status, so this bounds the number of
+    /// in-flight queries on the broker. Default is 4. A value of 0 is
+    /// treated as 1.
+    #[must_use]
+    pub fn with_creation_concurrency(mut self, concurrency: usize) -> Self {
+        self.creation_concurrency = Some(concurrency.max(1));
+        self
+    }
+
     /// Returns the discovery timeout.
     #[must_use]
     pub fn timeout(&self) -> Duration {
@@ -178,6 +212,19 @@ impl DiscoveryOptions {
     pub fn port(&self) -> u16 {
         self.port.unwrap_or(1883)
     }
+
+    /// Returns the maximum number of devices, if capped.
+    #[must_use]
+    pub fn max_devices(&self) -> Option<usize> {
+        self.max_devices
+    }
+
+    /// Returns the device creation concurrency.
+    #[must_use]
+    pub fn creation_concurrency(&self) -> usize {
+        self.creation_concurrency
+            .unwrap_or(DEFAULT_CREATION_CONCURRENCY)
+    }
 }
 
 impl MqttBroker {
@@ -190,7 +237,8 @@ impl MqttBroker {
     ///
     /// 1. Subscribes to wildcard topics (`tele/+/LWT`, `tele/+/STATE`)
     /// 2. Collects device topics from incoming messages during the timeout period
-    /// 3. Creates a [`Device`] instance for each discovered topic
+    /// 3. Creates a [`Device`] instance for each discovered topic, a few at a
+    ///    time (see [`DiscoveryOptions::with_creation_concurrency`])
     /// 4. Queries each device for capabilities and initial state
     ///
     /// # Arguments
@@ -231,10 +279,50 @@ impl MqttBroker {
         &self,
         timeout: Duration,
     ) -> Result<Vec<(Device<SharedMqttClient>, DeviceState)>, Error> {
+        self.discover_devices_with_options(&DiscoveryOptions::new().with_timeout(timeout))
+            .await
+    }
+
+    /// Discovers Tasmota devices on this broker with custom options.
+    ///
+    /// Like [`discover_devices`](Self::discover_devices), but honors the
+    /// timeout, [`max_devices`](DiscoveryOptions::with_max_devices) and
+    /// [`creation_concurrency`](DiscoveryOptions::with_creation_concurrency)
+    /// from `options`. Connection settings in `options` are ignored since the
+    /// broker is already connected.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if subscription to discovery topics fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::MqttBroker;
+    /// use tasmor_lib::discovery::DiscoveryOptions;
+    /// use std::time::Duration;
+    ///
+    /// # async fn example(broker: &MqttBroker) -> tasmor_lib::Result<()> {
+    /// let options = DiscoveryOptions::new()
+    ///     .with_timeout(Duration::from_secs(30))
+    ///     .with_max_devices(10)
+    ///     .with_creation_concurrency(4);
+    ///
+    /// let devices = broker.discover_devices_with_options(&options).await?;
+    /// assert!(devices.len() <= 10);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn discover_devices_with_options(
+        &self,
+        options: &DiscoveryOptions,
+    ) -> Result<Vec<(Device<SharedMqttClient>, DeviceState)>, Error> {
+        let timeout = options.timeout();
         tracing::info!(
             host = %self.host(),
             port = %self.port(),
             timeout_secs = timeout.as_secs(),
+            max_devices = ?options.max_devices(),
             "Starting MQTT device discovery"
         );
 
@@ -269,7 +357,9 @@ impl MqttBroker {
         tracing::debug!("Sent broadcast Status command to trigger device responses");
 
         // Collect device topics during the timeout period
-        let topics = self.collect_device_topics(timeout).await;
+        let topics = self
+            .collect_device_topics(timeout, options.max_devices())
+            .await;
 
         // Unsubscribe from discovery topics
         let _ = self.client().unsubscribe("tele/+/LWT").await;
@@ -282,21 +372,28 @@ impl MqttBroker {
             return Ok(Vec::new());
         }
 
-        // Create devices for each discovered topic
+        // Create devices for each discovered topic, a bounded number at a time
+        let concurrency = options.creation_concurrency();
         let mut devices = Vec::with_capacity(topics.len());
+        let mut tasks = JoinSet::new();
 
         for topic in topics {
+            if tasks.len() >= concurrency
+                && let Some(result) = tasks.join_next().await
+            {
+                Self::collect_created_device(result, &mut devices);
+            }
+
             tracing::debug!(topic = %topic, "Creating device for discovered topic");
+            let broker = self.clone();
+            tasks.spawn(async move {
+                let result = broker.create_device_for_topic(&topic).await;
+                (topic, result)
+            });
+        }
 
-            match self.create_device_for_topic(&topic).await {
-                Ok(device_and_state) => {
-                    tracing::info!(topic = %topic, "Successfully created device");
-                    devices.push(device_and_state);
-                }
-                Err(e) => {
-                    tracing::warn!(topic = %topic, error = %e, "Failed to create device, skipping");
-                }
-            }
+        while let Some(result) = tasks.join_next().await {
+            Self::collect_created_device(result, &mut devices);
         }
 
         tracing::info!(
@@ -308,27 +405,52 @@ impl MqttBroker {
     }
 
     /// Collects device topics by monitoring MQTT messages.
-    async fn collect_device_topics(&self, timeout: Duration) -> HashSet<String> {
+    ///
+    /// Returns when the timeout elapses or, if `max_devices` is set, as soon
+    /// as that many topics have been collected.
+    async fn collect_device_topics(
+        &self,
+        timeout: Duration,
+        max_devices: Option<usize>,
+    ) -> HashSet<String> {
         // Start discovery mode - the broker will send discovered topics to this channel
         let mut discovery_rx = self.start_discovery().await;
-        let discovered_topics: Arc<RwLock<HashSet<String>>> = Arc::new(RwLock::new(HashSet::new()));
+        let mut topics = HashSet::new();
 
-        // Spawn a task to collect topics from the channel
-        let topics_clone = discovered_topics.clone();
-        let collector = tokio::spawn(async move {
+        let collect = async {
             while let Some(topic) = discovery_rx.recv().await {
-                topics_clone.write().await.insert(topic);
+                topics.insert(topic);
+                if max_devices.is_some_and(|max| topics.len() >= max) {
+                    tracing::debug!(count = topics.len(), "Reached max devices, stopping early");
+                    break;
+                }
             }
-        });
-
-        // Wait for the timeout period
-        tokio::time::sleep(timeout).await;
+        };
+        let _ = tokio::time::timeout(timeout, collect).await;
 
-        // Stop discovery mode and wait for collector to finish
+        // Stop discovery mode
         self.stop_discovery().await;
-        collector.abort();
 
-        discovered_topics.read().await.clone()
+        topics
+    }
+
+    /// Records the outcome of a device creation task.
+    fn collect_created_device(
+        result: Result<(String, Result<DiscoveredDevice, Error>), tokio::task::JoinError>,
+        devices: &mut Vec<DiscoveredDevice>,
+    ) {
+        match result {
+            Ok((topic, Ok(device_and_state))) => {
+                tracing::info!(topic = %topic, "Successfully created device");
+                devices.push(device_and_state);
+            }
+            Ok((topic, Err(e))) => {
+                tracing::warn!(topic = %topic, error = %e, "Failed to create device, skipping");
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Device creation task failed, skipping");
+            }
+        }
     }
 
     /// Creates a Device for a discovered topic using the shared broker connection.
@@ -404,7 +526,7 @@ pub async fn discover_devices(
     let broker = builder.build().await?;
 
     // Perform discovery
-    let devices = broker.discover_devices(options.timeout()).await?;
+    let devices = broker.discover_devices_with_options(&options).await?;
 
     Ok((broker, devices))
 }
@@ -445,6 +567,28 @@ mod tests {
         assert_eq!(options.port(), 8883);
     }
 
+    #[test]
+    fn discovery_options_limits() {
+        let options = DiscoveryOptions::new();
+        assert_eq!(options.max_devices(), None);
+        assert_eq!(options.creation_concurrency(), 4);
+
+        let options = DiscoveryOptions::new()
+            .with_max_devices(12)
+            .with_creation_concurrency(2);
+        assert_eq!(options.max_devices(), Some(12));
+        assert_eq!(options.creation_concurrency(), 2);
+    }
+
+    #[test]
+    fn discovery_options_limits_are_at_least_one() {
+        let options = DiscoveryOptions::new()
+            .with_max_devices(0)
+            .with_creation_concurrency(0);
+        assert_eq!(options.max_devices(), Some(1));
+        assert_eq!(options.creation_concurrency(), 1);
+    }
+
     #[test]
     fn discovery_options_chained() {
         let options = DiscoveryOptions::new()
@@ -44,6 +44,13 @@ pub enum Error {
     /// Device does not support the requested capability.
     #[error("device does not support this capability")]
     CapabilityNotSupported,
+
+    /// An operation did not finish before its deadline.
+    #[error("deadline exceeded during {phase}")]
+    DeadlineExceeded {
+        /// The phase that was still running when the deadline passed.
+        phase: String,
+    },
 }
 
 /// Errors related to value validation and constraints.
@@ -82,6 +89,59 @@ pub enum ValueError {
     /// An invalid hex color string was provided.
     #[error("invalid hex color: {0}")]
     InvalidHexColor(String),
+
+    /// A color list does not have the expected number of entries.
+    #[error("expected {expected} colors, got {actual}")]
+    InvalidColorCount {
+        /// Number of colors required.
+        expected: usize,
+        /// Number of colors provided.
+        actual: usize,
+    },
+
+    /// A password cannot be sent to the device.
+    #[error("invalid password: {0}")]
+    InvalidPassword(String),
+
+    /// A `FullTopic` template or topic prefix is not usable.
+    #[error("invalid full topic: {0}")]
+    InvalidFullTopic(String),
+
+    /// A group topic contains characters that are not allowed.
+    #[error("invalid group topic: {0}")]
+    InvalidGroupTopic(String),
+
+    /// A button or switch topic cannot be sent to the device.
+    #[error("invalid remote topic: {0}")]
+    InvalidRemoteTopic(String),
+
+    /// A host name is not valid.
+    #[error("invalid host name: {0}")]
+    InvalidHostname(String),
+
+    /// Device name cannot be stored by Tasmota.
+    #[error("invalid device name: {0}")]
+    InvalidDeviceName(String),
+
+    /// Energy counter value cannot be stored by Tasmota.
+    #[error("invalid energy total: {0}")]
+    InvalidEnergyTotal(String),
+
+    /// MQTT user name cannot be stored by Tasmota.
+    #[error("invalid MQTT user: {0}")]
+    InvalidMqttUser(String),
+
+    /// A rule event name or value cannot be sent to the device.
+    #[error("invalid event: {0}")]
+    InvalidEvent(String),
+
+    /// A button action name is not reported by Tasmota.
+    #[error("invalid button action: {0}")]
+    InvalidButtonAction(String),
+
+    /// A rule cannot be stored by Tasmota.
+    #[error("invalid rule: {0}")]
+    InvalidRule(String),
 }
 
 /// Errors related to protocol communication (HTTP/MQTT).
@@ -113,6 +173,12 @@ pub enum ProtocolError {
     #[error("authentication failed")]
     AuthenticationFailed,
 
+    /// The device or a proxy in front of it is too busy to take the
+    /// command (HTTP 429 or `{"Command":"Busy"}`); retrying later may
+    /// succeed.
+    #[error("device is busy, retry later")]
+    Busy,
+
     /// Internal channel was closed.
     #[error("channel closed: {0}")]
     ChannelClosed(String),
@@ -164,6 +230,29 @@ pub enum DeviceError {
     /// Device configuration is invalid.
     #[error("invalid device configuration: {0}")]
     InvalidConfiguration(String),
+
+    /// The device did not report the value a command was meant to set.
+    #[error(
+        "{command} not verified: expected {expected}, device reported {}",
+        .actual.as_deref().unwrap_or("nothing")
+    )]
+    Unverified {
+        /// The command that was sent.
+        command: String,
+        /// The value the command should have set.
+        expected: String,
+        /// The value the device reported, if any.
+        actual: Option<String>,
+    },
+
+    /// A relay index beyond the relays the device has.
+    #[error("relay {index} does not exist, device has {available}")]
+    NoSuchRelay {
+        /// The requested relay index.
+        index: u8,
+        /// The number of relays on the device.
+        available: u8,
+    },
 }
 
 /// A specialized Result type for this library.
@@ -203,4 +292,36 @@ mod tests {
         };
         assert_eq!(err.to_string(), "device does not support energy monitoring");
     }
+
+    #[test]
+    fn unverified_error_display() {
+        let err = DeviceError::Unverified {
+            command: "CT".to_string(),
+            expected: "153".to_string(),
+            actual: Some("200".to_string()),
+        };
+        assert_eq!(
+            err.to_string(),
+            "CT not verified: expected 153, device reported 200"
+        );
+
+        let err = DeviceError::Unverified {
+            command: "Dimmer".to_string(),
+            expected: "75".to_string(),
+            actual: None,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Dimmer not verified: expected 75, device reported nothing"
+        );
+    }
+
+    #[test]
+    fn no_such_relay_error_display() {
+        let err = DeviceError::NoSuchRelay {
+            index: 5,
+            available: 1,
+        };
+        assert_eq!(err.to_string(), "relay 5 does not exist, device has 1");
+    }
 }
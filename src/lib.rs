@@ -26,6 +26,8 @@
 //!
 //! - `http` - Enables HTTP protocol support (enabled by default)
 //! - `mqtt` - Enables MQTT protocol support (enabled by default)
+//! - `testing` - Enables recording and replay of MQTT telemetry for offline
+//!   tests (`TelemetryRecorder`, `TelemetryReplayer`)
 //!
 //! Both features are enabled by default. To use only one protocol:
 //!
@@ -247,6 +249,7 @@ mod device;
 #[cfg(feature = "mqtt")]
 pub mod discovery;
 pub mod error;
+mod profile;
 pub mod protocol;
 pub mod response;
 pub mod state;
@@ -255,25 +258,33 @@ pub mod telemetry;
 pub mod types;
 
 // Core types
-pub use capabilities::{Capabilities, CapabilitiesBuilder};
+pub use capabilities::{
+    Capabilities, CapabilitiesBuilder, CapabilitiesDiff, CapabilityDetector, DefaultDetector,
+};
 pub use device::Device;
 pub use error::{DeviceError, Error, ParseError, ProtocolError, Result, ValueError};
-pub use state::{DeviceState, StateChange, SystemInfo};
+pub use profile::{DeviceProfile, ProfileCategory, ProfileChange, ProfileDiff};
+pub use state::{DeviceState, LightChoice, LightSnapshot, LightState, StateChange, SystemInfo};
 
 // Protocol configuration
-#[cfg(feature = "http")]
-pub use protocol::HttpConfig;
 #[cfg(feature = "mqtt")]
-pub use protocol::{MqttBroker, MqttBrokerBuilder};
+pub use protocol::{
+    BrokerStats, MqttBroker, MqttBrokerBuilder, MqttTransport, ReconnectionPolicy, TlsConfig,
+};
+#[cfg(feature = "http")]
+pub use protocol::{HttpConfig, RetryPolicy};
 
-// Command building (Routine only - other commands via Device methods)
-pub use command::{Routine, RoutineBuilder};
+// Command building (Routine and Rule only - other commands via Device methods)
+pub use command::{Routine, RoutineBuilder, Rule, RuleBuilder};
 
 // Response types (returned by Device methods)
 pub use response::{
-    ColorTemperatureResponse, DimmerResponse, EnergyResponse, FadeDurationResponse, FadeResponse,
-    HsbColorResponse, PowerResponse, RgbColorResponse, RoutineResponse, SchemeResponse,
-    StartupFadeResponse, StatusResponse, WakeupDurationResponse,
+    ChannelResponse, ColorTemperatureResponse, DimmerResponse, EnergyResponse,
+    FadeDurationResponse, FadeResponse, GpioResponse, HsbColorResponse, ModuleListResponse,
+    ModuleResponse, PowerResponse, PulseTimeResponse, PwmResponse, RgbColorResponse,
+    RoutineResponse, SaveDataResponse, SchemeResponse, SecurityResponse, SetOptionFlags,
+    ShutterResponse, StartupFadeResponse, StatusResponse, TimerResponse, TuyaMcuResponse,
+    WakeupDurationResponse, WebColorResponse, WhiteResponse,
 };
 
 // Subscriptions (MQTT only)
@@ -282,6 +293,7 @@ pub use subscription::{Subscribable, SubscriptionId};
 
 // Value types (parameters for commands and state)
 pub use types::{
-    ColorTemperature, DateTimeParseError, Dimmer, FadeDuration, HsbColor, PowerIndex, PowerState,
-    RgbColor, Scheme, TasmotaDateTime, WakeupDuration,
+    ColorTemperature, DateTimeParseError, Dimmer, FadeDuration, HsbColor, LightDefaults, LightType,
+    PowerIndex, PowerQuery, PowerState, RgbColor, RgbwwColor, Scheme, TasmotaDateTime, TopicScheme,
+    TuyaValue, WakeupDuration, WebColors,
 };
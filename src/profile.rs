@@ -0,0 +1,392 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Device configuration profiles.
+//!
+//! A [`DeviceProfile`] is a serializable snapshot of the settings that make
+//! devices of a fleet behave alike: `SetOption` values, general settings
+//! such as `Timezone` or `TelePeriod`, timers, rules and energy calibration.
+//! Comparing two profiles with [`DeviceProfile::diff`] shows how a device
+//! drifted from a baseline.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Serializable snapshot of a device's configuration.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::{DeviceProfile, ProfileCategory};
+///
+/// let baseline = DeviceProfile::new()
+///     .with_set_option(19, 1)
+///     .with_setting("TelePeriod", "300");
+/// let device = DeviceProfile::new()
+///     .with_set_option(19, 0)
+///     .with_setting("TelePeriod", "300");
+///
+/// let diff = baseline.diff(&device);
+/// assert_eq!(diff.len(), 1);
+/// assert_eq!(diff.changes()[0].category(), ProfileCategory::SetOption);
+/// assert_eq!(diff.to_string(), "SetOption19: 1 -> 0");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceProfile {
+    /// `SetOption` values by option number.
+    set_options: BTreeMap<u16, u32>,
+    /// General settings by command name (e.g. `Timezone`, `TelePeriod`).
+    settings: BTreeMap<String, String>,
+    /// `Timer<n>` definitions as reported by Tasmota.
+    timers: BTreeMap<u8, serde_json::Value>,
+    /// `Rule<n>` texts.
+    rules: BTreeMap<u8, String>,
+    /// Energy calibration values by command name (e.g. `PowerCal`).
+    calibration: BTreeMap<String, u32>,
+}
+
+impl DeviceProfile {
+    /// Creates an empty profile.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a `SetOption` value.
+    #[must_use]
+    pub fn with_set_option(mut self, option: u16, value: u32) -> Self {
+        self.set_options.insert(option, value);
+        self
+    }
+
+    /// Sets a general setting, keyed by its command name.
+    #[must_use]
+    pub fn with_setting(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.settings.insert(name.into(), value.into());
+        self
+    }
+
+    /// Sets a timer definition (the JSON object of a `Timer<n>` reply).
+    #[must_use]
+    pub fn with_timer(mut self, index: u8, timer: serde_json::Value) -> Self {
+        self.timers.insert(index, timer);
+        self
+    }
+
+    /// Sets a rule text.
+    #[must_use]
+    pub fn with_rule(mut self, index: u8, rule: impl Into<String>) -> Self {
+        self.rules.insert(index, rule.into());
+        self
+    }
+
+    /// Sets a calibration value, keyed by its command name.
+    #[must_use]
+    pub fn with_calibration(mut self, name: impl Into<String>, value: u32) -> Self {
+        self.calibration.insert(name.into(), value);
+        self
+    }
+
+    /// Returns a `SetOption` value.
+    #[must_use]
+    pub fn set_option(&self, option: u16) -> Option<u32> {
+        self.set_options.get(&option).copied()
+    }
+
+    /// Returns a general setting.
+    #[must_use]
+    pub fn setting(&self, name: &str) -> Option<&str> {
+        self.settings.get(name).map(String::as_str)
+    }
+
+    /// Returns a timer definition.
+    #[must_use]
+    pub fn timer(&self, index: u8) -> Option<&serde_json::Value> {
+        self.timers.get(&index)
+    }
+
+    /// Returns a rule text.
+    #[must_use]
+    pub fn rule(&self, index: u8) -> Option<&str> {
+        self.rules.get(&index).map(String::as_str)
+    }
+
+    /// Returns a calibration value.
+    #[must_use]
+    pub fn calibration(&self, name: &str) -> Option<u32> {
+        self.calibration.get(name).copied()
+    }
+
+    /// Compares this profile (the baseline) with `other`.
+    ///
+    /// Entries present in only one profile are reported with the missing
+    /// side as `None`. Changes are ordered by category, then by key.
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> ProfileDiff {
+        let mut changes = Vec::new();
+        diff_maps(
+            &mut changes,
+            ProfileCategory::Setting,
+            &self.settings,
+            &other.settings,
+            String::clone,
+        );
+        diff_maps(
+            &mut changes,
+            ProfileCategory::SetOption,
+            &self.set_options,
+            &other.set_options,
+            |option| format!("SetOption{option}"),
+        );
+        diff_maps(
+            &mut changes,
+            ProfileCategory::Timer,
+            &self.timers,
+            &other.timers,
+            |index| format!("Timer{index}"),
+        );
+        diff_maps(
+            &mut changes,
+            ProfileCategory::Rule,
+            &self.rules,
+            &other.rules,
+            |index| format!("Rule{index}"),
+        );
+        diff_maps(
+            &mut changes,
+            ProfileCategory::Calibration,
+            &self.calibration,
+            &other.calibration,
+            String::clone,
+        );
+        ProfileDiff { changes }
+    }
+}
+
+/// Appends the differences between two maps of one category.
+fn diff_maps<K: Ord, V: PartialEq + ToString>(
+    changes: &mut Vec<ProfileChange>,
+    category: ProfileCategory,
+    baseline: &BTreeMap<K, V>,
+    other: &BTreeMap<K, V>,
+    key_name: impl Fn(&K) -> String,
+) {
+    let mut keys: Vec<&K> = baseline.keys().chain(other.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    for key in keys {
+        let (left, right) = (baseline.get(key), other.get(key));
+        if left != right {
+            changes.push(ProfileChange {
+                category,
+                key: key_name(key),
+                baseline: left.map(ToString::to_string),
+                other: right.map(ToString::to_string),
+            });
+        }
+    }
+}
+
+/// Category of a configuration difference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ProfileCategory {
+    /// General setting (`Timezone`, `TelePeriod`, ...).
+    Setting,
+    /// `SetOption<n>`.
+    SetOption,
+    /// `Timer<n>`.
+    Timer,
+    /// `Rule<n>`.
+    Rule,
+    /// Energy calibration.
+    Calibration,
+}
+
+impl fmt::Display for ProfileCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Setting => "setting",
+            Self::SetOption => "SetOption",
+            Self::Timer => "timer",
+            Self::Rule => "rule",
+            Self::Calibration => "calibration",
+        })
+    }
+}
+
+/// A single configuration difference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileChange {
+    category: ProfileCategory,
+    key: String,
+    baseline: Option<String>,
+    other: Option<String>,
+}
+
+impl ProfileChange {
+    /// Returns the category of the setting.
+    #[must_use]
+    pub fn category(&self) -> ProfileCategory {
+        self.category
+    }
+
+    /// Returns the setting name (e.g. `SetOption19`, `Rule1`, `PowerCal`).
+    #[must_use]
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the value in the baseline profile, if set.
+    #[must_use]
+    pub fn baseline(&self) -> Option<&str> {
+        self.baseline.as_deref()
+    }
+
+    /// Returns the value in the compared profile, if set.
+    #[must_use]
+    pub fn other(&self) -> Option<&str> {
+        self.other.as_deref()
+    }
+}
+
+impl fmt::Display for ProfileChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.key,
+            self.baseline.as_deref().unwrap_or("(unset)"),
+            self.other.as_deref().unwrap_or("(unset)")
+        )
+    }
+}
+
+/// Differences between two device profiles.
+///
+/// Returned by [`DeviceProfile::diff`]. Displays one change per line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileDiff {
+    changes: Vec<ProfileChange>,
+}
+
+impl ProfileDiff {
+    /// Returns all changes.
+    #[must_use]
+    pub fn changes(&self) -> &[ProfileChange] {
+        &self.changes
+    }
+
+    /// Returns the changes of one category.
+    pub fn in_category(&self, category: ProfileCategory) -> impl Iterator<Item = &ProfileChange> {
+        self.changes
+            .iter()
+            .filter(move |change| change.category == category)
+    }
+
+    /// Returns the number of changes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Returns `true` if the profiles are identical.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for ProfileDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, change) in self.changes.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn baseline() -> DeviceProfile {
+        DeviceProfile::new()
+            .with_setting("Timezone", "99")
+            .with_setting("TelePeriod", "300")
+            .with_set_option(19, 1)
+            .with_timer(
+                1,
+                serde_json::json!({"Enable": 1, "Time": "06:30", "Action": 1}),
+            )
+            .with_rule(1, "ON Power1#State DO Publish stat/x %value% ENDON")
+            .with_calibration("PowerCal", 12530)
+    }
+
+    #[test]
+    fn identical_profiles() {
+        let diff = baseline().diff(&baseline());
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "");
+    }
+
+    #[test]
+    fn changes_are_categorized() {
+        let drifted = baseline()
+            .with_setting("TelePeriod", "60")
+            .with_set_option(19, 0)
+            .with_set_option(53, 1)
+            .with_timer(
+                1,
+                serde_json::json!({"Enable": 0, "Time": "06:30", "Action": 1}),
+            )
+            .with_calibration("PowerCal", 12000);
+
+        let diff = baseline().diff(&drifted);
+        let categories: Vec<_> = diff.changes().iter().map(ProfileChange::category).collect();
+        assert_eq!(
+            categories,
+            [
+                ProfileCategory::Setting,
+                ProfileCategory::SetOption,
+                ProfileCategory::SetOption,
+                ProfileCategory::Timer,
+                ProfileCategory::Calibration,
+            ]
+        );
+
+        let set_options: Vec<_> = diff.in_category(ProfileCategory::SetOption).collect();
+        assert_eq!(set_options[0].key(), "SetOption19");
+        assert_eq!(set_options[1].baseline(), None);
+        assert_eq!(set_options[1].other(), Some("1"));
+        assert_eq!(diff.in_category(ProfileCategory::Rule).count(), 0);
+    }
+
+    #[test]
+    fn missing_entries_are_reported() {
+        let diff = baseline().diff(&DeviceProfile::new().with_setting("Timezone", "99"));
+        assert_eq!(diff.len(), 5);
+        assert!(diff.to_string().contains("Rule1: ON Power1#State"));
+        assert!(diff.to_string().ends_with("PowerCal: 12530 -> (unset)"));
+    }
+
+    #[test]
+    fn profile_round_trips_through_json() {
+        let profile = baseline();
+        let json = serde_json::to_string(&profile).unwrap();
+        let restored: DeviceProfile = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, profile);
+
+        let partial: DeviceProfile = serde_json::from_str(r#"{"set_options":{"19":1}}"#).unwrap();
+        assert_eq!(partial.set_option(19), Some(1));
+        assert_eq!(partial.setting("Timezone"), None);
+    }
+}
@@ -5,13 +5,15 @@
 
 //! HTTP protocol implementation for Tasmota devices.
 
+use std::sync::Arc;
 use std::time::Duration;
 
+use parking_lot::RwLock;
 use reqwest::Client;
 
 use crate::command::Command;
 use crate::error::ProtocolError;
-use crate::protocol::{CommandResponse, Protocol};
+use crate::protocol::{CommandResponse, Protocol, RetryPolicy};
 
 // ============================================================================
 // HttpConfig - Configuration for HTTP devices (new device-centric API)
@@ -26,7 +28,7 @@ use crate::protocol::{CommandResponse, Protocol};
 /// # Examples
 ///
 /// ```
-/// use tasmor_lib::protocol::HttpConfig;
+/// use tasmor_lib::protocol::{HttpConfig, RetryPolicy};
 /// use std::time::Duration;
 ///
 /// // Simple configuration
@@ -35,17 +37,24 @@ use crate::protocol::{CommandResponse, Protocol};
 /// // With all options
 /// let config = HttpConfig::new("192.168.1.100")
 ///     .with_port(8080)
-///     .with_https()
+///     .with_https(true)
 ///     .with_credentials("admin", "password")
-///     .with_timeout(Duration::from_secs(5));
+///     .with_timeout(Duration::from_secs(5))
+///     .with_busy_retry(3, Duration::from_millis(500))
+///     .with_retry(RetryPolicy::new(3, Duration::from_millis(200)));
 /// ```
 #[derive(Debug, Clone)]
 pub struct HttpConfig {
     host: String,
-    port: u16,
+    /// `None` uses the default port of the scheme.
+    port: Option<u16>,
     use_https: bool,
+    accept_invalid_certs: bool,
     credentials: Option<(String, String)>,
     timeout: Duration,
+    busy_retries: u32,
+    busy_retry_delay: Duration,
+    retry: RetryPolicy,
 }
 
 impl HttpConfig {
@@ -65,29 +74,44 @@ impl HttpConfig {
     pub fn new(host: impl Into<String>) -> Self {
         Self {
             host: host.into(),
-            port: Self::DEFAULT_PORT,
+            port: None,
             use_https: false,
+            accept_invalid_certs: false,
             credentials: None,
             timeout: Self::DEFAULT_TIMEOUT,
+            busy_retries: 0,
+            busy_retry_delay: Duration::ZERO,
+            retry: RetryPolicy::none(),
         }
     }
 
     /// Sets a custom port.
     #[must_use]
     pub fn with_port(mut self, port: u16) -> Self {
-        self.port = port;
+        self.port = Some(port);
         self
     }
 
-    /// Enables HTTPS.
+    /// Selects the HTTPS (`true`) or HTTP (`false`) scheme.
     ///
-    /// If port hasn't been explicitly set, it will be changed to 443.
+    /// Unless a port is set with [`with_port`](Self::with_port), the
+    /// default port of the scheme is used: 443 for HTTPS, 80 for HTTP. An
+    /// explicitly set port is kept, even if it is 80.
     #[must_use]
-    pub fn with_https(mut self) -> Self {
-        self.use_https = true;
-        if self.port == Self::DEFAULT_PORT {
-            self.port = Self::DEFAULT_HTTPS_PORT;
-        }
+    pub fn with_https(mut self, https: bool) -> Self {
+        self.use_https = https;
+        self
+    }
+
+    /// Accepts any server certificate over HTTPS.
+    ///
+    /// Tasmota serves HTTPS with a self-signed certificate, which is
+    /// rejected by default. **This is dangerous**: anyone on the network path
+    /// can impersonate the device and read the credentials sent with each
+    /// command. Only enable it on a trusted network.
+    #[must_use]
+    pub fn with_danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
         self
     }
 
@@ -103,12 +127,38 @@ impl HttpConfig {
     }
 
     /// Sets the request timeout.
+    ///
+    /// Requests that take longer fail with [`ProtocolError::Timeout`].
     #[must_use]
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
         self
     }
 
+    /// Retries commands the device reports as busy.
+    ///
+    /// A command answered with HTTP 429 or `{"Command":"Busy"}` is sent
+    /// again after `delay`, up to `retries` times, before failing with
+    /// [`ProtocolError::Busy`]. By default busy commands are not retried.
+    #[must_use]
+    pub fn with_busy_retry(mut self, retries: u32, delay: Duration) -> Self {
+        self.busy_retries = retries;
+        self.busy_retry_delay = delay;
+        self
+    }
+
+    /// Retries requests that fail at the connection level.
+    ///
+    /// Applies to every command sent through the client, so all `Device`
+    /// methods benefit. See [`RetryPolicy`] for which failures are retried
+    /// and how commands that are not idempotent are handled. By default
+    /// failed requests are not retried.
+    #[must_use]
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
     /// Returns the host.
     #[must_use]
     pub fn host(&self) -> &str {
@@ -118,7 +168,11 @@ impl HttpConfig {
     /// Returns the port.
     #[must_use]
     pub fn port(&self) -> u16 {
-        self.port
+        self.port.unwrap_or(if self.use_https {
+            Self::DEFAULT_HTTPS_PORT
+        } else {
+            Self::DEFAULT_PORT
+        })
     }
 
     /// Returns whether HTTPS is enabled.
@@ -127,6 +181,12 @@ impl HttpConfig {
         self.use_https
     }
 
+    /// Returns whether invalid server certificates are accepted.
+    #[must_use]
+    pub fn accepts_invalid_certs(&self) -> bool {
+        self.accept_invalid_certs
+    }
+
     /// Returns the credentials if set.
     #[must_use]
     pub fn credentials(&self) -> Option<(&str, &str)> {
@@ -141,16 +201,38 @@ impl HttpConfig {
         self.timeout
     }
 
+    /// Returns how many times a busy command is retried.
+    #[must_use]
+    pub fn busy_retries(&self) -> u32 {
+        self.busy_retries
+    }
+
+    /// Returns the delay before retrying a busy command.
+    #[must_use]
+    pub fn busy_retry_delay(&self) -> Duration {
+        self.busy_retry_delay
+    }
+
+    /// Returns the retry policy for failed requests.
+    #[must_use]
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry
+    }
+
     /// Builds the base URL from this configuration.
     #[must_use]
     pub fn base_url(&self) -> String {
-        let scheme = if self.use_https { "https" } else { "http" };
-        let port_suffix =
-            if (self.use_https && self.port == 443) || (!self.use_https && self.port == 80) {
-                String::new()
-            } else {
-                format!(":{}", self.port)
-            };
+        let (scheme, default_port) = if self.use_https {
+            ("https", Self::DEFAULT_HTTPS_PORT)
+        } else {
+            ("http", Self::DEFAULT_PORT)
+        };
+        let port = self.port();
+        let port_suffix = if port == default_port {
+            String::new()
+        } else {
+            format!(":{port}")
+        };
         format!("{scheme}://{}{port_suffix}", self.host)
     }
 
@@ -164,6 +246,7 @@ impl HttpConfig {
 
         let client = Client::builder()
             .timeout(self.timeout)
+            .danger_accept_invalid_certs(self.accept_invalid_certs)
             .build()
             .map_err(ProtocolError::Http)?;
 
@@ -174,7 +257,11 @@ impl HttpConfig {
         Ok(HttpClient {
             base_url,
             client,
-            credentials,
+            credentials: Arc::new(RwLock::new(credentials)),
+            busy_retries: self.busy_retries,
+            busy_retry_delay: self.busy_retry_delay,
+            retry: self.retry,
+            timeout: self.timeout,
         })
     }
 }
@@ -204,9 +291,17 @@ impl HttpConfig {
 pub struct HttpClient {
     base_url: String,
     client: Client,
-    credentials: Option<Credentials>,
+    // Shared so a password change applies to every clone of the client
+    credentials: Arc<RwLock<Option<Credentials>>>,
+    busy_retries: u32,
+    busy_retry_delay: Duration,
+    retry: RetryPolicy,
+    timeout: Duration,
 }
 
+/// Username of the Tasmota web server.
+const DEFAULT_WEB_USERNAME: &str = "admin";
+
 /// HTTP authentication credentials.
 #[derive(Debug, Clone)]
 pub struct Credentials {
@@ -234,26 +329,31 @@ impl HttpClient {
             format!("http://{host}")
         };
 
+        let timeout = HttpConfig::DEFAULT_TIMEOUT;
         let client = Client::builder()
-            .timeout(Duration::from_secs(10))
+            .timeout(timeout)
             .build()
             .map_err(ProtocolError::Http)?;
 
         Ok(Self {
             base_url,
             client,
-            credentials: None,
+            credentials: Arc::new(RwLock::new(None)),
+            busy_retries: 0,
+            busy_retry_delay: Duration::ZERO,
+            retry: RetryPolicy::none(),
+            timeout,
         })
     }
 
     /// Sets authentication credentials.
     #[must_use]
     pub fn with_credentials(
-        mut self,
+        self,
         username: impl Into<String>,
         password: impl Into<String>,
     ) -> Self {
-        self.credentials = Some(Credentials {
+        *self.credentials.write() = Some(Credentials {
             username: username.into(),
             password: password.into(),
         });
@@ -270,7 +370,7 @@ impl HttpClient {
     fn build_url(&self, command: &str) -> String {
         let encoded_command = urlencoding::encode(command);
 
-        match &self.credentials {
+        match &*self.credentials.read() {
             Some(creds) => {
                 format!(
                     "{}/cm?user={}&password={}&cmnd={}",
@@ -285,17 +385,34 @@ impl HttpClient {
             }
         }
     }
-}
 
-impl Protocol for HttpClient {
-    async fn send_command<C: Command + Sync>(
-        &self,
-        command: &C,
-    ) -> Result<CommandResponse, ProtocolError> {
-        self.send_raw(&command.to_http_command()).await
+    /// Maps a request error, reporting an elapsed deadline as
+    /// [`ProtocolError::Timeout`].
+    fn request_error(&self, error: reqwest::Error) -> ProtocolError {
+        if error.is_timeout() {
+            ProtocolError::Timeout(u64::try_from(self.timeout.as_millis()).unwrap_or(u64::MAX))
+        } else {
+            ProtocolError::Http(error)
+        }
     }
 
-    async fn send_raw(&self, command: &str) -> Result<CommandResponse, ProtocolError> {
+    /// Sends a command, retrying while the device reports it is busy.
+    async fn send_with_busy_retry(&self, command: &str) -> Result<CommandResponse, ProtocolError> {
+        let mut attempt = 0;
+        loop {
+            match self.request(command).await {
+                Err(ProtocolError::Busy) if attempt < self.busy_retries => {
+                    attempt += 1;
+                    tracing::debug!(attempt, command, "Device busy, retrying");
+                    tokio::time::sleep(self.busy_retry_delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Sends a command once.
+    async fn request(&self, command: &str) -> Result<CommandResponse, ProtocolError> {
         let url = self.build_url(command);
 
         tracing::debug!(url = %url, "Sending HTTP command");
@@ -305,12 +422,16 @@ impl Protocol for HttpClient {
             .get(&url)
             .send()
             .await
-            .map_err(ProtocolError::Http)?;
+            .map_err(|e| self.request_error(e))?;
 
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
             return Err(ProtocolError::AuthenticationFailed);
         }
 
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(ProtocolError::Busy);
+        }
+
         if !response.status().is_success() {
             return Err(ProtocolError::ConnectionFailed(format!(
                 "HTTP {} - {}",
@@ -319,14 +440,66 @@ impl Protocol for HttpClient {
             )));
         }
 
-        let body = response.text().await.map_err(ProtocolError::Http)?;
+        let body = response.text().await.map_err(|e| self.request_error(e))?;
 
         tracing::debug!(body = %body, "Received HTTP response");
 
+        if is_busy_response(&body) {
+            return Err(ProtocolError::Busy);
+        }
+
         Ok(CommandResponse::new(body))
     }
 }
 
+/// Returns `true` if `body` is Tasmota's `{"Command":"Busy"}` reply.
+fn is_busy_response(body: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(body).is_ok_and(|value| {
+        value
+            .get("Command")
+            .and_then(serde_json::Value::as_str)
+            .is_some_and(|status| status.eq_ignore_ascii_case("Busy"))
+    })
+}
+
+impl Protocol for HttpClient {
+    async fn send_command<C: Command + Sync>(
+        &self,
+        command: &C,
+    ) -> Result<CommandResponse, ProtocolError> {
+        let command_str = command.to_http_command();
+        self.retry
+            .run(command.is_idempotent(), || {
+                self.send_with_busy_retry(&command_str)
+            })
+            .await
+    }
+
+    async fn send_raw(&self, command: &str) -> Result<CommandResponse, ProtocolError> {
+        // The effect of a raw command is unknown, so treat it as not idempotent
+        self.retry
+            .run(false, || self.send_with_busy_retry(command))
+            .await
+    }
+
+    fn web_password_changed(&self, password: &str) {
+        let mut credentials = self.credentials.write();
+        if password.is_empty() {
+            *credentials = None;
+            return;
+        }
+
+        // Tasmota's web user is always "admin" unless configured otherwise
+        let username = credentials
+            .take()
+            .map_or_else(|| DEFAULT_WEB_USERNAME.to_string(), |c| c.username);
+        *credentials = Some(Credentials {
+            username,
+            password: password.to_string(),
+        });
+    }
+}
+
 /// Builder for creating an HTTP client with custom configuration.
 #[derive(Debug, Default)]
 pub struct HttpClientBuilder {
@@ -334,6 +507,8 @@ pub struct HttpClientBuilder {
     username: Option<String>,
     password: Option<String>,
     timeout: Option<Duration>,
+    https: bool,
+    accept_invalid_certs: bool,
 }
 
 impl HttpClientBuilder {
@@ -365,6 +540,22 @@ impl HttpClientBuilder {
         self
     }
 
+    /// Uses HTTPS for a host given without a scheme (default: HTTP).
+    #[must_use]
+    pub fn https(mut self, https: bool) -> Self {
+        self.https = https;
+        self
+    }
+
+    /// Accepts any server certificate over HTTPS.
+    ///
+    /// See [`HttpConfig::with_danger_accept_invalid_certs`] for the risks.
+    #[must_use]
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
     /// Builds the HTTP client.
     ///
     /// # Errors
@@ -377,12 +568,16 @@ impl HttpClientBuilder {
 
         let base_url = if host.starts_with("http://") || host.starts_with("https://") {
             host
+        } else if self.https {
+            format!("https://{host}")
         } else {
             format!("http://{host}")
         };
 
+        let timeout = self.timeout.unwrap_or(HttpConfig::DEFAULT_TIMEOUT);
         let client = Client::builder()
-            .timeout(self.timeout.unwrap_or(Duration::from_secs(10)))
+            .timeout(timeout)
+            .danger_accept_invalid_certs(self.accept_invalid_certs)
             .build()
             .map_err(ProtocolError::Http)?;
 
@@ -394,7 +589,11 @@ impl HttpClientBuilder {
         Ok(HttpClient {
             base_url,
             client,
-            credentials,
+            credentials: Arc::new(RwLock::new(credentials)),
+            busy_retries: 0,
+            busy_retry_delay: Duration::ZERO,
+            retry: RetryPolicy::none(),
+            timeout,
         })
     }
 }
@@ -422,6 +621,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn web_password_change_updates_credentials() {
+        let client = HttpClient::new("192.168.1.100").unwrap();
+        let clone = client.clone();
+
+        client.web_password_changed("n3w");
+        assert_eq!(
+            clone.build_url("Power"),
+            "http://192.168.1.100/cm?user=admin&password=n3w&cmnd=Power"
+        );
+
+        client.web_password_changed("");
+        assert_eq!(
+            clone.build_url("Power"),
+            "http://192.168.1.100/cm?cmnd=Power"
+        );
+    }
+
+    #[test]
+    fn busy_response_detection() {
+        assert!(is_busy_response(r#"{"Command":"Busy"}"#));
+        assert!(!is_busy_response(r#"{"Command":"Unknown"}"#));
+        assert!(!is_busy_response(r#"{"POWER":"ON"}"#));
+        assert!(!is_busy_response("Busy"));
+    }
+
     #[test]
     fn build_url_with_https() {
         let client = HttpClient::new("https://192.168.1.100").unwrap();
@@ -443,7 +668,26 @@ mod tests {
             .build()
             .unwrap();
 
-        assert!(client.credentials.is_some());
+        assert!(client.credentials.read().is_some());
+    }
+
+    #[test]
+    fn builder_with_https() {
+        let client = HttpClientBuilder::new()
+            .host("192.168.1.100:8443")
+            .https(true)
+            .danger_accept_invalid_certs(true)
+            .build()
+            .unwrap();
+        assert_eq!(client.base_url(), "https://192.168.1.100:8443");
+
+        // An explicit scheme wins
+        let client = HttpClientBuilder::new()
+            .host("http://192.168.1.100")
+            .https(true)
+            .build()
+            .unwrap();
+        assert_eq!(client.base_url(), "http://192.168.1.100");
     }
 
     // =========================================================================
@@ -468,16 +712,20 @@ mod tests {
 
     #[test]
     fn http_config_with_https() {
-        let config = HttpConfig::new("192.168.1.100").with_https();
+        let config = HttpConfig::new("192.168.1.100").with_https(true);
         assert!(config.use_https());
         assert_eq!(config.port(), 443); // Port should change to 443
+
+        let config = config.with_https(false);
+        assert!(!config.use_https());
+        assert_eq!(config.port(), 80);
     }
 
     #[test]
     fn http_config_with_https_custom_port() {
         let config = HttpConfig::new("192.168.1.100")
             .with_port(8443)
-            .with_https();
+            .with_https(true);
         assert!(config.use_https());
         assert_eq!(config.port(), 8443); // Port should stay as explicitly set
     }
@@ -510,7 +758,7 @@ mod tests {
 
     #[test]
     fn http_config_base_url_https() {
-        let config = HttpConfig::new("192.168.1.100").with_https();
+        let config = HttpConfig::new("192.168.1.100").with_https(true);
         assert_eq!(config.base_url(), "https://192.168.1.100");
     }
 
@@ -518,8 +766,39 @@ mod tests {
     fn http_config_base_url_https_custom_port() {
         let config = HttpConfig::new("192.168.1.100")
             .with_port(8443)
-            .with_https();
+            .with_https(true);
         assert_eq!(config.base_url(), "https://192.168.1.100:8443");
+
+        // The port may be set after enabling HTTPS
+        let config = HttpConfig::new("192.168.1.100")
+            .with_https(true)
+            .with_port(8443);
+        assert_eq!(config.base_url(), "https://192.168.1.100:8443");
+    }
+
+    #[test]
+    fn http_config_base_url_https_explicit_default_ports() {
+        let config = HttpConfig::new("192.168.1.100")
+            .with_port(80)
+            .with_https(true);
+        assert_eq!(config.port(), 80);
+        assert_eq!(config.base_url(), "https://192.168.1.100:80");
+
+        let config = HttpConfig::new("192.168.1.100").with_port(443);
+        assert_eq!(config.base_url(), "http://192.168.1.100:443");
+    }
+
+    #[test]
+    fn http_config_accept_invalid_certs() {
+        let config = HttpConfig::new("192.168.1.100").with_https(true);
+        assert!(!config.accepts_invalid_certs());
+
+        let config = config.with_danger_accept_invalid_certs(true);
+        assert!(config.accepts_invalid_certs());
+        assert_eq!(
+            config.into_client().unwrap().base_url(),
+            "https://192.168.1.100"
+        );
     }
 
     #[test]
@@ -527,7 +806,7 @@ mod tests {
         let config = HttpConfig::new("192.168.1.100").with_credentials("user", "pass");
         let client = config.into_client().unwrap();
         assert_eq!(client.base_url(), "http://192.168.1.100");
-        assert!(client.credentials.is_some());
+        assert!(client.credentials.read().is_some());
     }
 
     #[test]
@@ -542,5 +821,72 @@ mod tests {
         assert!(!config.use_https());
         assert!(config.credentials().is_some());
         assert_eq!(config.timeout(), Duration::from_secs(5));
+        assert_eq!(config.retry_policy(), RetryPolicy::none());
+    }
+
+    #[test]
+    fn http_config_with_retry() {
+        let policy = RetryPolicy::new(4, Duration::from_millis(50));
+        let config = HttpConfig::new("192.168.1.100").with_retry(policy);
+        assert_eq!(config.retry_policy(), policy);
+        assert_eq!(config.into_client().unwrap().retry, policy);
+    }
+
+    #[tokio::test]
+    async fn elapsed_timeout_is_reported_as_timeout() {
+        use wiremock::{Mock, MockServer, ResponseTemplate, matchers::method};
+
+        // The reply is held back well past the client's deadline
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+            .mount(&mock_server)
+            .await;
+
+        let address = mock_server.address();
+        let client = HttpConfig::new(address.ip().to_string())
+            .with_port(address.port())
+            .with_timeout(Duration::from_millis(50))
+            .into_client()
+            .unwrap();
+
+        let result = client.send_raw("Power").await;
+        assert!(
+            matches!(result, Err(ProtocolError::Timeout(50))),
+            "unexpected result: {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn relative_dimmer_steps_are_not_retried() {
+        use crate::command::DimmerCommand;
+        use wiremock::{Mock, MockServer, ResponseTemplate, matchers::method};
+
+        // Every reply is lost, as if the device applied the step and the
+        // connection dropped before answering
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+            .expect(4)
+            .mount(&mock_server)
+            .await;
+
+        let address = mock_server.address();
+        let client = HttpConfig::new(address.ip().to_string())
+            .with_port(address.port())
+            .with_timeout(Duration::from_millis(50))
+            .with_retry(RetryPolicy::new(3, Duration::from_millis(1)))
+            .into_client()
+            .unwrap();
+
+        for cmd in [
+            DimmerCommand::Increase,
+            DimmerCommand::Decrease,
+            DimmerCommand::IncreaseBy(10),
+            DimmerCommand::DecreaseBy(10),
+        ] {
+            let result = client.send_command(&cmd).await;
+            assert!(matches!(result, Err(ProtocolError::Timeout(_))));
+        }
     }
 }
@@ -44,7 +44,12 @@ mod http;
 #[cfg(feature = "mqtt")]
 mod mqtt_broker;
 #[cfg(feature = "mqtt")]
+mod mqtt_tls;
+#[cfg(feature = "mqtt")]
 mod response_collector;
+mod response_spec;
+#[cfg(feature = "http")]
+mod retry;
 #[cfg(feature = "mqtt")]
 mod shared_mqtt_client;
 #[cfg(feature = "mqtt")]
@@ -54,7 +59,16 @@ mod topic_router;
 #[cfg(feature = "http")]
 pub use http::HttpConfig;
 #[cfg(feature = "mqtt")]
-pub use mqtt_broker::{MqttBroker, MqttBrokerBuilder};
+pub use mqtt_broker::{
+    BrokerStats, DEFAULT_CHANNEL_CAPACITY, MqttBroker, MqttBrokerBuilder, MqttTransport,
+    ReconnectionPolicy,
+};
+#[cfg(feature = "mqtt")]
+pub use mqtt_tls::TlsConfig;
+#[cfg(feature = "http")]
+pub use retry::RetryPolicy;
+#[cfg(feature = "mqtt")]
+pub use rumqttc::QoS;
 
 // Protocol clients - public because they're type parameters in Device<P>
 // Users typically don't import these directly; they use Device::http() or MqttBroker::device()
@@ -66,8 +80,7 @@ pub use shared_mqtt_client::SharedMqttClient;
 // Internal types - exposed for advanced usage but not re-exported at crate root
 #[cfg(feature = "mqtt")]
 pub use mqtt_broker::MqttBrokerConfig;
-#[cfg(feature = "mqtt")]
-pub use response_collector::ResponseSpec;
+pub use response_spec::ResponseSpec;
 #[cfg(feature = "mqtt")]
 pub use topic_router::TopicRouter;
 
@@ -79,13 +92,35 @@ use crate::error::ProtocolError;
 pub struct CommandResponse {
     /// The raw JSON response body.
     body: String,
+    /// Whether the response was synthesized by a dry-run device.
+    dry_run: bool,
 }
 
 impl CommandResponse {
     /// Creates a new command response with the given body.
     #[must_use]
     pub fn new(body: String) -> Self {
-        Self { body }
+        Self {
+            body,
+            dry_run: false,
+        }
+    }
+
+    /// Creates a response synthesized in dry-run mode.
+    pub(crate) fn dry_run(body: String) -> Self {
+        Self {
+            body,
+            dry_run: true,
+        }
+    }
+
+    /// Returns `true` if this response was synthesized in dry-run mode
+    /// rather than received from the device.
+    ///
+    /// See [`Device::dry_run`](crate::Device::dry_run).
+    #[must_use]
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
     }
 
     /// Returns the raw JSON response body.
@@ -96,11 +131,14 @@ impl CommandResponse {
 
     /// Parses the response as a specific type.
     ///
+    /// Object keys are matched against field names case-insensitively, so
+    /// firmware reporting `DIMMER` or `power` parses like `Dimmer`/`POWER`.
+    ///
     /// # Errors
     ///
     /// Returns error if the JSON cannot be parsed into the target type.
     pub fn parse<T: serde::de::DeserializeOwned>(&self) -> Result<T, crate::error::ParseError> {
-        serde_json::from_str(&self.body).map_err(Into::into)
+        crate::response::case_insensitive::from_str(&self.body).map_err(Into::into)
     }
 }
 
@@ -134,4 +172,12 @@ pub trait Protocol: Send + Sync {
     ///
     /// Returns `ProtocolError` if the command fails.
     async fn send_raw(&self, command: &str) -> Result<CommandResponse, ProtocolError>;
+
+    /// Called after the device accepted a new web password.
+    ///
+    /// Protocols that authenticate against the device's web server use this
+    /// to send the new password with subsequent requests. An empty password
+    /// means authentication was disabled. The default implementation does
+    /// nothing.
+    fn web_password_changed(&self, _password: &str) {}
 }
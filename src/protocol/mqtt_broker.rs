@@ -45,12 +45,14 @@
 //!    [`on_disconnected`](crate::subscription::Subscribable::on_disconnected)
 //!    callback is triggered for all devices.
 //!
-//! 2. **Automatic Reconnection**: The underlying MQTT client (rumqttc)
-//!    automatically attempts to reconnect to the broker.
+//! 2. **Automatic Reconnection**: The broker reconnects with an increasing
+//!    delay between attempts, as set by the [`ReconnectionPolicy`] passed to
+//!    [`MqttBrokerBuilder::with_auto_reconnect`]. A disabled policy, or one
+//!    whose attempts run out, leaves the broker disconnected.
 //!
 //! 3. **Topic Resubscription**: When the connection is restored, all device
-//!    topic subscriptions (`stat/<topic>/+` and `tele/<topic>/+`) are
-//!    automatically restored.
+//!    topic subscriptions (`stat/<topic>/+` and `tele/<topic>/+`, or their
+//!    equivalent in the device's [`TopicScheme`]) are automatically restored.
 //!
 //! 4. **Reconnection Notification**: The
 //!    [`on_reconnected`](crate::subscription::Subscribable::on_reconnected)
@@ -88,6 +90,7 @@
 //! ```
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
@@ -95,36 +98,357 @@ use std::time::Duration;
 use rumqttc::{AsyncClient, EventLoop, MqttOptions, QoS};
 use tokio::sync::{RwLock, mpsc, oneshot};
 
-use crate::error::ProtocolError;
+use crate::command::{Command, PowerCommand};
+use crate::error::{Error, ProtocolError, ValueError};
 use crate::protocol::TopicRouter;
+use crate::protocol::mqtt_tls::{DEFAULT_TLS_PORT, TlsConfig};
 use crate::protocol::response_collector::MqttMessage;
+use crate::subscription::SubscriptionId;
+use crate::types::{PowerIndex, PowerState, TopicPrefix, TopicScheme};
+
+/// Longest topic Tasmota accepts for `Topic` and `GroupTopic`.
+const MAX_GROUP_TOPIC_LEN: usize = 32;
 
 /// Global counter for generating unique client IDs.
 static BROKER_CLIENT_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
 
+/// Global counter for generating unique ping topics.
+static PING_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Topic prefix reserved for [`MqttBroker::ping`] round trips.
+const PING_TOPIC_PREFIX: &str = "tasmor/ping";
+
 /// Default timeout for MQTT command responses.
 pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// Default port of plain MQTT.
+const DEFAULT_PORT: u16 = 1883;
+
+/// Default number of responses buffered per device.
+///
+/// Large enough for multi-message responses such as `Status 0`.
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 20;
+
+/// Transport used to reach the MQTT broker.
+///
+/// The default is plain TCP to the configured host and port. The other
+/// variants map to the corresponding `rumqttc` transports:
+///
+/// | Variant | `rumqttc` transport | Address used |
+/// |---------|---------------------|--------------|
+/// | [`Tcp`](Self::Tcp) | `Transport::Tcp`, or `Transport::Tls` with [`TlsConfig`] | `host` + `port` |
+/// | [`Unix`](Self::Unix) (Unix only) | `Transport::Unix` | socket path, `host`/`port` ignored |
+///
+/// A Unix domain socket is useful when the broker runs as a sidecar that is
+/// only reachable through a mounted socket. Tunnels that expose the broker
+/// on a local port are covered by [`Tcp`](Self::Tcp) with the tunnel's
+/// local address.
+///
+/// An already-open stream cannot be passed in: `rumqttc` opens the
+/// connection itself, and opens it again on every reconnection. The enum is
+/// `#[non_exhaustive]` so that more transports can be added later.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tasmor_lib::MqttBroker;
+/// # #[cfg(unix)]
+/// use tasmor_lib::protocol::MqttTransport;
+///
+/// # async fn example() -> tasmor_lib::Result<()> {
+/// # #[cfg(unix)]
+/// let broker = MqttBroker::builder()
+///     .with_transport(MqttTransport::Unix("/run/mosquitto/mqtt.sock".into()))
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MqttTransport {
+    /// Plain TCP connection to `host:port`.
+    #[default]
+    Tcp,
+    /// Unix domain socket at the given path.
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+/// How the broker reconnects after losing its connection.
+///
+/// After a connection loss, the broker waits [`initial_delay`](Self::initial_delay)
+/// before the first attempt and doubles the delay after each failed attempt,
+/// up to [`max_delay`](Self::max_delay). Once connected again, device topics
+/// are resubscribed, which also makes the broker resend retained messages
+/// such as `tele/<topic>/LWT`.
+///
+/// The default policy retries forever, starting at 1 second and waiting at
+/// most 60 seconds between attempts.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tasmor_lib::MqttBroker;
+/// use tasmor_lib::protocol::ReconnectionPolicy;
+///
+/// # async fn example() -> tasmor_lib::Result<()> {
+/// let policy = ReconnectionPolicy::new(Duration::from_millis(500), Duration::from_secs(30))
+///     .with_max_attempts(20);
+/// assert_eq!(policy.delay(3), Duration::from_secs(2));
+///
+/// let broker = MqttBroker::builder()
+///     .host("192.168.1.50")
+///     .with_auto_reconnect(policy)
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectionPolicy {
+    enabled: bool,
+    initial_delay: Duration,
+    max_delay: Duration,
+    max_attempts: Option<u32>,
+}
+
+impl ReconnectionPolicy {
+    /// Default delay before the first reconnection attempt.
+    pub const DEFAULT_INITIAL_DELAY: Duration = Duration::from_secs(1);
+
+    /// Default upper bound of the delay between two attempts.
+    pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(60);
+
+    /// Creates a policy retrying forever with the given delays.
+    #[must_use]
+    pub fn new(initial_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            enabled: true,
+            initial_delay,
+            max_delay: max_delay.max(initial_delay),
+            max_attempts: None,
+        }
+    }
+
+    /// Creates a policy that never reconnects.
+    ///
+    /// The broker stays disconnected after the first connection loss;
+    /// build a new one to connect again.
+    #[must_use]
+    pub const fn disabled() -> Self {
+        Self {
+            enabled: false,
+            initial_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+            max_attempts: Some(0),
+        }
+    }
+
+    /// Gives up after `attempts` consecutive failed attempts.
+    #[must_use]
+    pub fn with_max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// Returns whether the broker reconnects at all.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the delay before the first attempt.
+    #[must_use]
+    pub fn initial_delay(&self) -> Duration {
+        self.initial_delay
+    }
+
+    /// Returns the upper bound of the delay between two attempts.
+    #[must_use]
+    pub fn max_delay(&self) -> Duration {
+        self.max_delay
+    }
+
+    /// Returns the number of consecutive failed attempts before giving up,
+    /// or `None` to retry forever.
+    #[must_use]
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    /// Returns the delay before the given attempt, counting from 1.
+    #[must_use]
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+    }
+
+    /// Returns whether another attempt may follow `failed` failed ones.
+    fn allows_attempt(&self, failed: u32) -> bool {
+        self.enabled && self.max_attempts.is_none_or(|max| failed < max)
+    }
+}
+
+impl Default for ReconnectionPolicy {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_INITIAL_DELAY, Self::DEFAULT_MAX_DELAY)
+    }
+}
+
+/// Snapshot of an MQTT broker connection's traffic counters.
+///
+/// Counters start at zero when the broker is built and are never reset.
+/// Compare two snapshots to get rates, e.g. to spot a connection that
+/// silently stops receiving messages.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tasmor_lib::protocol::MqttBroker;
+///
+/// # fn example(broker: &MqttBroker) {
+/// let stats = broker.stats();
+/// println!(
+///     "{} sent, {} received, {} reconnects",
+///     stats.messages_published(),
+///     stats.messages_received(),
+///     stats.reconnect_count()
+/// );
+/// if let Some(error) = stats.last_error() {
+///     println!("Last error: {error}");
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BrokerStats {
+    messages_published: u64,
+    messages_received: u64,
+    reconnect_count: u64,
+    messages_dropped: u64,
+    last_error: Option<String>,
+}
+
+impl BrokerStats {
+    /// Returns the number of messages published to the broker.
+    #[must_use]
+    pub fn messages_published(&self) -> u64 {
+        self.messages_published
+    }
+
+    /// Returns the number of messages received from the broker.
+    #[must_use]
+    pub fn messages_received(&self) -> u64 {
+        self.messages_received
+    }
+
+    /// Returns how often the connection was restored after being lost.
+    #[must_use]
+    pub fn reconnect_count(&self) -> u64 {
+        self.reconnect_count
+    }
+
+    /// Returns the number of device responses dropped because the device's
+    /// response buffer was full.
+    ///
+    /// A growing count means responses arrive faster than commands consume
+    /// them; raise
+    /// [`MqttBrokerBuilder::with_channel_capacity`](crate::protocol::MqttBrokerBuilder::with_channel_capacity).
+    #[must_use]
+    pub fn messages_dropped(&self) -> u64 {
+        self.messages_dropped
+    }
+
+    /// Returns the most recent connection error, if any occurred.
+    #[must_use]
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+}
+
+/// Live traffic counters updated by the event loop.
+#[derive(Default)]
+struct BrokerCounters {
+    messages_published: AtomicU64,
+    messages_received: AtomicU64,
+    reconnect_count: AtomicU64,
+    messages_dropped: AtomicU64,
+    last_error: parking_lot::Mutex<Option<String>>,
+}
+
 /// Configuration for an MQTT broker connection.
 #[derive(Debug, Clone)]
 pub struct MqttBrokerConfig {
     host: String,
-    port: u16,
+    /// `None` uses the default port of the transport.
+    port: Option<u16>,
+    transport: MqttTransport,
+    tls: Option<TlsConfig>,
     credentials: Option<(String, String)>,
     keep_alive: Duration,
     connection_timeout: Duration,
     command_timeout: Duration,
+    channel_capacity: usize,
+    reconnection: ReconnectionPolicy,
 }
 
 impl Default for MqttBrokerConfig {
     fn default() -> Self {
         Self {
             host: String::new(),
-            port: 1883,
+            port: None,
+            transport: MqttTransport::Tcp,
+            tls: None,
             credentials: None,
             keep_alive: Duration::from_secs(30),
             connection_timeout: Duration::from_secs(10),
             command_timeout: DEFAULT_COMMAND_TIMEOUT,
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+            reconnection: ReconnectionPolicy::default(),
+        }
+    }
+}
+
+impl MqttBrokerConfig {
+    /// Returns the configured port, or 8883 with TLS and 1883 without.
+    fn port(&self) -> u16 {
+        self.port.unwrap_or(if self.tls.is_some() {
+            DEFAULT_TLS_PORT
+        } else {
+            DEFAULT_PORT
+        })
+    }
+
+    /// Creates the `rumqttc` options for this configuration's transport.
+    fn mqtt_options(&self, client_id: &str) -> Result<MqttOptions, ProtocolError> {
+        match &self.transport {
+            MqttTransport::Tcp => {
+                if self.host.is_empty() {
+                    return Err(ProtocolError::InvalidAddress(
+                        "MQTT broker host is required".to_string(),
+                    ));
+                }
+                let mut options = MqttOptions::new(client_id, &self.host, self.port());
+                if let Some(tls) = &self.tls {
+                    options.set_transport(rumqttc::Transport::Tls(tls.configuration()?));
+                }
+                Ok(options)
+            }
+            #[cfg(unix)]
+            MqttTransport::Unix(path) => {
+                if path.as_os_str().is_empty() {
+                    return Err(ProtocolError::InvalidAddress(
+                        "MQTT broker socket path is required".to_string(),
+                    ));
+                }
+                // rumqttc uses the broker address as the socket path; the port is unused
+                let mut options = MqttOptions::new(client_id, path.to_string_lossy(), 0);
+                options.set_transport(rumqttc::Transport::Unix);
+                Ok(options)
+            }
         }
     }
 }
@@ -135,6 +459,14 @@ pub(crate) struct DeviceSubscription {
     pub response_tx: mpsc::Sender<MqttMessage>,
     /// Router for dispatching messages to callbacks.
     pub router: Arc<TopicRouter>,
+    /// `QoS` used to subscribe to the device topics, reused on reconnection.
+    pub qos: QoS,
+    /// Topic layout used to subscribe and to match incoming messages.
+    pub scheme: TopicScheme,
+    /// Whether the device publishes command results on
+    /// `stat/<topic>/<Command>` instead of `stat/<topic>/RESULT`
+    /// (`SetOption4`).
+    pub results_on_command_topic: bool,
 }
 
 /// An MQTT broker connection that can be shared across multiple devices.
@@ -149,6 +481,9 @@ pub struct MqttBroker {
     inner: Arc<MqttBrokerInner>,
 }
 
+/// Callback receiving errors of the broker connection.
+type ErrorCallback = Arc<dyn Fn(&ProtocolError) + Send + Sync>;
+
 struct MqttBrokerInner {
     /// The MQTT async client for publishing.
     client: AsyncClient,
@@ -161,8 +496,22 @@ struct MqttBrokerInner {
     /// Whether the initial connection has been established.
     /// Used to distinguish reconnections from the first connection.
     initial_connection_done: AtomicBool,
+    /// Set by [`MqttBroker::disconnect`] so the event loop stops instead of
+    /// reconnecting.
+    shutting_down: AtomicBool,
     /// Channel for sending discovered device topics during discovery.
     discovery_tx: RwLock<Option<mpsc::Sender<String>>>,
+    /// Traffic counters reported by [`MqttBroker::stats`].
+    counters: BrokerCounters,
+    /// Pending [`MqttBroker::ping`] round trips by reply topic.
+    pings: parking_lot::Mutex<HashMap<String, oneshot::Sender<()>>>,
+    /// Callbacks registered with [`MqttBroker::on_error`].
+    error_callbacks: parking_lot::RwLock<Vec<(SubscriptionId, ErrorCallback)>>,
+    /// Next ID handed out by [`MqttBroker::on_error`].
+    next_error_callback_id: AtomicU64,
+    /// Recorder capturing incoming messages, see [`MqttBroker::start_recording`].
+    #[cfg(feature = "testing")]
+    recorder: parking_lot::Mutex<Option<crate::telemetry::TelemetryRecorder>>,
 }
 
 impl MqttBroker {
@@ -172,6 +521,28 @@ impl MqttBroker {
         MqttBrokerBuilder::default()
     }
 
+    /// Creates a broker around a client whose event loop is not polled yet.
+    fn new(client: AsyncClient, config: MqttBrokerConfig) -> Self {
+        let inner = MqttBrokerInner {
+            client,
+            subscriptions: RwLock::new(HashMap::new()),
+            config,
+            connected: AtomicBool::new(false),
+            initial_connection_done: AtomicBool::new(false),
+            shutting_down: AtomicBool::new(false),
+            discovery_tx: RwLock::new(None),
+            counters: BrokerCounters::default(),
+            pings: parking_lot::Mutex::new(HashMap::new()),
+            error_callbacks: parking_lot::RwLock::new(Vec::new()),
+            next_error_callback_id: AtomicU64::new(0),
+            #[cfg(feature = "testing")]
+            recorder: parking_lot::Mutex::new(None),
+        };
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
     /// Returns whether the broker is currently connected.
     #[must_use]
     pub fn is_connected(&self) -> bool {
@@ -187,7 +558,19 @@ impl MqttBroker {
     /// Returns the port of the broker.
     #[must_use]
     pub fn port(&self) -> u16 {
-        self.inner.config.port
+        self.inner.config.port()
+    }
+
+    /// Returns whether the connection uses TLS.
+    #[must_use]
+    pub fn is_tls(&self) -> bool {
+        self.inner.config.tls.is_some()
+    }
+
+    /// Returns the transport used to reach the broker.
+    #[must_use]
+    pub fn transport(&self) -> &MqttTransport {
+        &self.inner.config.transport
     }
 
     /// Returns whether authentication is configured.
@@ -202,6 +585,257 @@ impl MqttBroker {
         self.inner.config.command_timeout
     }
 
+    /// Returns how the broker reconnects after losing its connection.
+    #[must_use]
+    pub fn reconnection_policy(&self) -> ReconnectionPolicy {
+        self.inner.config.reconnection
+    }
+
+    /// Registers a callback for errors of the broker connection.
+    ///
+    /// The callback runs whenever the connection to the broker fails or
+    /// drops (refused credentials, keep-alive timeouts, malformed packets,
+    /// network errors) and whenever a command cannot be published. Errors
+    /// are reported as [`ProtocolError::ConnectionFailed`] with the broker
+    /// address and the underlying `rumqttc` error, so the message alone
+    /// identifies the broker.
+    ///
+    /// Unlike the per-device
+    /// [`on_disconnected`](crate::subscription::Subscribable::on_disconnected)
+    /// callbacks, this reports every failed attempt while reconnecting, not
+    /// only the transition. Errors after [`disconnect`](Self::disconnect)
+    /// are not reported. The callback runs on the broker's event loop task,
+    /// so it should return quickly.
+    ///
+    /// Returns an ID for [`remove_error_callback`](Self::remove_error_callback).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::MqttBroker;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let broker = MqttBroker::builder().host("192.168.1.50").build().await?;
+    /// broker.on_error(|error| eprintln!("MQTT: {error}"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_error<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&ProtocolError) + Send + Sync + 'static,
+    {
+        let id = SubscriptionId::new(
+            self.inner
+                .next_error_callback_id
+                .fetch_add(1, Ordering::Relaxed),
+        );
+        self.inner
+            .error_callbacks
+            .write()
+            .push((id, Arc::new(callback)));
+        id
+    }
+
+    /// Removes a callback registered with [`on_error`](Self::on_error).
+    ///
+    /// Returns `true` if the callback was registered.
+    #[must_use]
+    pub fn remove_error_callback(&self, id: SubscriptionId) -> bool {
+        let mut callbacks = self.inner.error_callbacks.write();
+        let count = callbacks.len();
+        callbacks.retain(|(registered, _)| *registered != id);
+        callbacks.len() != count
+    }
+
+    /// Reports an error of the broker connection to the
+    /// [`on_error`](Self::on_error) callbacks.
+    pub(crate) fn report_error(&self, error: &dyn std::fmt::Display) {
+        // Clone so callbacks can register or remove callbacks
+        let callbacks: Vec<ErrorCallback> = self
+            .inner
+            .error_callbacks
+            .read()
+            .iter()
+            .map(|(_, callback)| Arc::clone(callback))
+            .collect();
+        if callbacks.is_empty() {
+            return;
+        }
+
+        let error =
+            ProtocolError::ConnectionFailed(format!("MQTT broker {}: {error}", self.address()));
+        for callback in callbacks {
+            callback(&error);
+        }
+    }
+
+    /// Reports a command that could not be published.
+    pub(crate) fn report_publish_error(&self, topic: &str, error: &rumqttc::ClientError) {
+        self.report_error(&format!("publishing to {topic} failed: {error}"));
+    }
+
+    /// Returns the broker address used in error messages.
+    fn address(&self) -> String {
+        let config = &self.inner.config;
+        match &config.transport {
+            MqttTransport::Tcp => format!("{}:{}", config.host, config.port()),
+            #[cfg(unix)]
+            MqttTransport::Unix(path) => path.display().to_string(),
+        }
+    }
+
+    /// Returns a snapshot of the connection's traffic counters.
+    ///
+    /// Reading the counters is cheap and does not wait on the connection.
+    #[must_use]
+    pub fn stats(&self) -> BrokerStats {
+        let counters = &self.inner.counters;
+        BrokerStats {
+            messages_published: counters.messages_published.load(Ordering::Relaxed),
+            messages_received: counters.messages_received.load(Ordering::Relaxed),
+            reconnect_count: counters.reconnect_count.load(Ordering::Relaxed),
+            messages_dropped: counters.messages_dropped.load(Ordering::Relaxed),
+            last_error: counters.last_error.lock().clone(),
+        }
+    }
+
+    /// Measures the round trip to the broker.
+    ///
+    /// Subscribes to a topic reserved for this call under `tasmor/ping/`,
+    /// publishes an empty non-retained message to it and waits for the
+    /// broker to deliver it back. The topic is unsubscribed afterwards, so no
+    /// state is left on the broker, and the message is never routed to
+    /// devices. Useful as a health check for long-running services.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProtocolError::ConnectionFailed`] if the broker is not
+    /// connected, [`ProtocolError::Timeout`] if the message does not come
+    /// back within the [command timeout](Self::command_timeout), or
+    /// [`ProtocolError::Mqtt`] if the request cannot be queued.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::MqttBroker;
+    ///
+    /// # async fn example(broker: &MqttBroker) -> tasmor_lib::Result<()> {
+    /// let latency = broker.ping().await?;
+    /// println!("Broker round trip: {latency:?}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn ping(&self) -> Result<Duration, ProtocolError> {
+        if !self.is_connected() {
+            return Err(ProtocolError::ConnectionFailed(
+                "MQTT broker is not connected".to_string(),
+            ));
+        }
+
+        let counter = PING_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let topic = format!("{PING_TOPIC_PREFIX}/{}_{counter}", std::process::id());
+        let (tx, rx) = oneshot::channel();
+        self.inner.pings.lock().insert(topic.clone(), tx);
+
+        let result = self.ping_round_trip(&topic, rx).await;
+
+        self.inner.pings.lock().remove(&topic);
+        if let Err(e) = self.inner.client.unsubscribe(&topic).await {
+            tracing::warn!(topic = %topic, error = %e, "Failed to unsubscribe from ping topic");
+        }
+        result
+    }
+
+    /// Publishes to the ping topic and waits for the message to come back.
+    async fn ping_round_trip(
+        &self,
+        topic: &str,
+        reply: oneshot::Receiver<()>,
+    ) -> Result<Duration, ProtocolError> {
+        let client = &self.inner.client;
+        client
+            .subscribe(topic, QoS::AtMostOnce)
+            .await
+            .map_err(ProtocolError::Mqtt)?;
+
+        // The broker handles the subscription before the publish that follows
+        // it on the same connection
+        let start = tokio::time::Instant::now();
+        client
+            .publish(topic, QoS::AtMostOnce, false, Vec::new())
+            .await
+            .map_err(ProtocolError::Mqtt)?;
+
+        let timeout = self.inner.config.command_timeout;
+        match tokio::time::timeout(timeout, reply).await {
+            Ok(Ok(())) => Ok(start.elapsed()),
+            Ok(Err(_)) => Err(ProtocolError::ChannelClosed(
+                "ping reply channel closed".to_string(),
+            )),
+            Err(_) => Err(ProtocolError::Timeout(
+                u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX),
+            )),
+        }
+    }
+
+    /// Completes a pending ping if `topic` is its reply topic.
+    ///
+    /// Returns `true` if the message was a ping reply.
+    fn complete_ping(&self, topic: &str) -> bool {
+        if !topic.starts_with(PING_TOPIC_PREFIX) {
+            return false;
+        }
+        if let Some(tx) = self.inner.pings.lock().remove(topic) {
+            let _ = tx.send(());
+        }
+        true
+    }
+
+    /// Starts writing every incoming message to `recorder`.
+    ///
+    /// Captures the traffic of the devices subscribed on this broker, to be
+    /// replayed later with a
+    /// [`TelemetryReplayer`](crate::telemetry::TelemetryReplayer). Replaces
+    /// any recording in progress. Requires the `testing` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::MqttBroker;
+    /// use tasmor_lib::telemetry::TelemetryRecorder;
+    ///
+    /// # async fn example(broker: &MqttBroker) -> Result<(), Box<dyn std::error::Error>> {
+    /// broker.start_recording(TelemetryRecorder::create("week.log")?);
+    /// // ... let the devices report for a while ...
+    /// broker.stop_recording();
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "testing")]
+    pub fn start_recording(&self, recorder: crate::telemetry::TelemetryRecorder) {
+        *self.inner.recorder.lock() = Some(recorder);
+    }
+
+    /// Stops recording and returns the flushed recorder, if one was active.
+    #[cfg(feature = "testing")]
+    pub fn stop_recording(&self) -> Option<crate::telemetry::TelemetryRecorder> {
+        let mut recorder = self.inner.recorder.lock().take()?;
+        if let Err(e) = recorder.flush() {
+            tracing::warn!(error = %e, "Failed to flush telemetry recording");
+        }
+        Some(recorder)
+    }
+
+    /// Writes an incoming message to the active recorder, if any.
+    #[cfg(feature = "testing")]
+    fn record_message(&self, topic: &str, payload: &str) {
+        if let Some(recorder) = self.inner.recorder.lock().as_mut()
+            && let Err(e) = recorder.record(topic, payload)
+        {
+            tracing::warn!(topic = %topic, error = %e, "Failed to record message");
+        }
+    }
+
     /// Returns the MQTT client for internal use.
     pub(crate) fn client(&self) -> &AsyncClient {
         &self.inner.client
@@ -236,11 +870,84 @@ impl MqttBroker {
         crate::device::BrokerDeviceBuilder::new(self, topic)
     }
 
+    /// Sends a command to all devices sharing a group topic.
+    ///
+    /// The command is published once on `cmnd/<group_topic>/<command>`, and
+    /// every device subscribed to that group topic (Tasmota `GroupTopic1`-
+    /// `GroupTopic4`, `tasmotas` by default) executes it. This is much
+    /// cheaper than sending the command to each device.
+    ///
+    /// Group commands have no correlated reply, so this returns as soon as
+    /// the message is published. Devices report their new state through
+    /// their own `stat` and `tele` topics, which reach the callbacks of the
+    /// [`Device`](crate::Device) handles created from this broker.
+    ///
+    /// The command topic uses the default `FullTopic` layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidGroupTopic`] if `group_topic` is empty,
+    /// longer than 32 characters or contains characters other than ASCII
+    /// letters, digits, `_`, `-` and `.`, or a protocol error if publishing
+    /// fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::MqttBroker;
+    /// use tasmor_lib::command::DimmerCommand;
+    /// use tasmor_lib::types::Dimmer;
+    ///
+    /// # async fn example(broker: &MqttBroker) -> tasmor_lib::Result<()> {
+    /// broker
+    ///     .group_command("living_room", &DimmerCommand::Set(Dimmer::new(30)?))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn group_command<C: Command + Sync>(
+        &self,
+        group_topic: &str,
+        command: &C,
+    ) -> Result<(), Error> {
+        validate_group_topic(group_topic)?;
+
+        let topic =
+            TopicScheme::default().topic(TopicPrefix::Command, group_topic, &command.name());
+        let payload = command.mqtt_payload();
+        tracing::debug!(topic = %topic, payload = %payload, "Publishing group command");
+
+        self.inner
+            .client
+            .publish(&topic, QoS::AtLeastOnce, false, payload)
+            .await
+            .map_err(|e| {
+                self.report_publish_error(&topic, &e);
+                Error::Protocol(ProtocolError::Mqtt(e))
+            })
+    }
+
+    /// Turns off all devices sharing a group topic.
+    ///
+    /// Publishes `Power OFF`, which switches the first relay of each device.
+    /// See [`group_command`](Self::group_command).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the group topic is invalid or publishing fails.
+    pub async fn group_power_off(&self, group_topic: &str) -> Result<(), Error> {
+        let cmd = PowerCommand::Set {
+            index: PowerIndex::all(),
+            state: PowerState::Off,
+        };
+        self.group_command(group_topic, &cmd).await
+    }
+
     /// Adds a subscription for a device topic.
     ///
-    /// Subscribes to:
-    /// - `stat/<topic>/+` for command responses
-    /// - `tele/<topic>/+` for telemetry
+    /// Subscribes with the given `QoS` to the device's stat topics (command
+    /// responses) and tele topics (telemetry), laid out according to
+    /// `scheme` (`stat/<topic>/+` and `tele/<topic>/+` by default).
     ///
     /// Returns a receiver channel for command responses (with topic suffix metadata).
     ///
@@ -250,38 +957,24 @@ impl MqttBroker {
     pub(crate) async fn add_device_subscription(
         &self,
         device_topic: String,
+        qos: QoS,
+        scheme: TopicScheme,
     ) -> Result<(mpsc::Receiver<MqttMessage>, Arc<TopicRouter>), ProtocolError> {
-        // Subscribe to stat/<topic>/+ for command responses
-        let stat_topic = format!("stat/{device_topic}/+");
-        self.inner
-            .client
-            .subscribe(&stat_topic, QoS::AtLeastOnce)
-            .await
-            .map_err(ProtocolError::Mqtt)?;
-
-        // Subscribe to tele/<topic>/+ for telemetry
-        let tele_topic = format!("tele/{device_topic}/+");
-        self.inner
-            .client
-            .subscribe(&tele_topic, QoS::AtLeastOnce)
-            .await
-            .map_err(ProtocolError::Mqtt)?;
-
-        tracing::debug!(
-            stat = %stat_topic,
-            tele = %tele_topic,
-            "Subscribed to device topics"
-        );
+        self.subscribe_device_topics(&device_topic, &scheme, qos)
+            .await?;
 
         // Create channels and router for this device
-        // Channel capacity increased to handle multi-message responses (e.g., Status 0)
-        let (response_tx, response_rx) = mpsc::channel::<MqttMessage>(20);
+        let (response_tx, response_rx) =
+            mpsc::channel::<MqttMessage>(self.inner.config.channel_capacity);
         let router = Arc::new(TopicRouter::new());
 
         // Register the subscription
         let subscription = DeviceSubscription {
             response_tx,
             router: Arc::clone(&router),
+            qos,
+            scheme,
+            results_on_command_topic: false,
         };
         self.inner
             .subscriptions
@@ -292,14 +985,110 @@ impl MqttBroker {
         Ok((response_rx, router))
     }
 
+    /// Replaces the topic scheme of a subscribed device.
+    ///
+    /// Unsubscribes from the topics of the previous scheme and subscribes
+    /// to those of the new one.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the MQTT subscription fails.
+    pub(crate) async fn update_device_topic_scheme(
+        &self,
+        device_topic: &str,
+        scheme: TopicScheme,
+    ) -> Result<(), ProtocolError> {
+        let qos = match self.inner.subscriptions.read().await.get(device_topic) {
+            Some(subscription) => subscription.qos,
+            None => return Ok(()),
+        };
+        self.subscribe_device_topics(device_topic, &scheme, qos)
+            .await?;
+
+        let previous = self
+            .inner
+            .subscriptions
+            .write()
+            .await
+            .get_mut(device_topic)
+            .map(|subscription| std::mem::replace(&mut subscription.scheme, scheme));
+        if let Some(previous) = previous {
+            self.unsubscribe_device_topics(device_topic, &previous)
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Sets whether a subscribed device publishes command results on
+    /// per-command topics (`SetOption4`).
+    pub(crate) async fn set_results_on_command_topic(&self, device_topic: &str, enabled: bool) {
+        if let Some(subscription) = self.inner.subscriptions.write().await.get_mut(device_topic) {
+            subscription.results_on_command_topic = enabled;
+        }
+    }
+
     /// Removes a subscription for a device topic.
-    pub(crate) async fn remove_device_subscription(&self, device_topic: &str) {
+    ///
+    /// Only the subscription owning `router` is removed: a cleanup that runs
+    /// late (e.g. spawned from `Drop`) must not remove the subscription of a
+    /// newer device created for the same topic meanwhile.
+    pub(crate) async fn remove_device_subscription(
+        &self,
+        device_topic: &str,
+        router: &Arc<TopicRouter>,
+    ) {
         // Remove from tracking
-        self.inner.subscriptions.write().await.remove(device_topic);
+        let removed = {
+            let mut subscriptions = self.inner.subscriptions.write().await;
+            match subscriptions.get(device_topic) {
+                Some(subscription) if Arc::ptr_eq(&subscription.router, router) => {
+                    subscriptions.remove(device_topic)
+                }
+                _ => None,
+            }
+        };
 
         // Unsubscribe from MQTT topics
-        let stat_topic = format!("stat/{device_topic}/+");
-        let tele_topic = format!("tele/{device_topic}/+");
+        if let Some(subscription) = removed {
+            self.unsubscribe_device_topics(device_topic, &subscription.scheme)
+                .await;
+        }
+    }
+
+    /// Subscribes to a device's stat and tele topics.
+    async fn subscribe_device_topics(
+        &self,
+        device_topic: &str,
+        scheme: &TopicScheme,
+        qos: QoS,
+    ) -> Result<(), ProtocolError> {
+        let stat_topic = scheme.topic(TopicPrefix::Stat, device_topic, "+");
+        self.inner
+            .client
+            .subscribe(&stat_topic, qos)
+            .await
+            .map_err(ProtocolError::Mqtt)?;
+
+        let tele_topic = scheme.topic(TopicPrefix::Tele, device_topic, "+");
+        self.inner
+            .client
+            .subscribe(&tele_topic, qos)
+            .await
+            .map_err(ProtocolError::Mqtt)?;
+
+        tracing::debug!(
+            stat = %stat_topic,
+            tele = %tele_topic,
+            qos = ?qos,
+            "Subscribed to device topics"
+        );
+        Ok(())
+    }
+
+    /// Unsubscribes from a device's stat and tele topics.
+    async fn unsubscribe_device_topics(&self, device_topic: &str, scheme: &TopicScheme) {
+        let stat_topic = scheme.topic(TopicPrefix::Stat, device_topic, "+");
+        let tele_topic = scheme.topic(TopicPrefix::Tele, device_topic, "+");
 
         if let Err(e) = self.inner.client.unsubscribe(&stat_topic).await {
             tracing::warn!(topic = %stat_topic, error = %e, "Failed to unsubscribe from stat topic");
@@ -318,22 +1107,87 @@ impl MqttBroker {
 
     /// Routes an incoming message to the appropriate device subscriber.
     async fn route_message(&self, topic: &str, payload: String) {
-        // Parse topic: stat/<device_topic>/<command> or tele/<device_topic>/<type>
-        let parts: Vec<&str> = topic.split('/').collect();
-        if parts.len() < 3 {
+        #[cfg(feature = "testing")]
+        self.record_message(topic, &payload);
+        self.capture_discovered_device(topic).await;
+
+        // Find the device whose topic scheme matches. Devices with the
+        // default layout are found directly by the second topic level;
+        // other layouts need a scan.
+        let subscriptions = self.inner.subscriptions.read().await;
+        let matched = topic
+            .split('/')
+            .nth(1)
+            .and_then(|candidate| subscriptions.get_key_value(candidate))
+            .into_iter()
+            .chain(subscriptions.iter())
+            .find_map(|(device_topic, sub)| {
+                let (prefix, suffix) = sub.scheme.parse(topic, device_topic)?;
+                Some((device_topic, sub, prefix, suffix))
+            });
+        let Some((device_topic, sub, prefix, suffix)) = matched else {
+            return;
+        };
+        if prefix == TopicPrefix::Command {
             return;
         }
+        let suffix = if prefix == TopicPrefix::Stat {
+            result_suffix(suffix, &payload, sub.results_on_command_topic)
+        } else {
+            suffix
+        };
 
-        let prefix = parts[0];
-        let device_topic = parts[1];
-        let suffix = parts[2];
+        // Route to callbacks via the topic router, which expects the
+        // default layout
+        let default_topic = format!("{}/{device_topic}/{suffix}", prefix.default_value());
+        sub.router.route(&default_topic, &payload);
+
+        // For stat messages, also send to response channel if it's a command response
+        if prefix == TopicPrefix::Stat {
+            // RESULT and STATUS* are JSON responses that go to the response channel
+            let is_json_response = suffix == "RESULT" || suffix.starts_with("STATUS");
+            if is_json_response {
+                tracing::debug!(
+                    topic = %topic,
+                    device = %device_topic,
+                    suffix = %suffix,
+                    "Routing response to device"
+                );
+                // Send as MqttMessage with topic suffix for multi-message collection.
+                // Never wait for room: a full buffer must not stall the event
+                // loop, which serves every device on the broker.
+                let msg = MqttMessage::new(suffix.to_string(), payload);
+                match sub.response_tx.try_send(msg) {
+                    Err(mpsc::error::TrySendError::Full(_)) => {
+                        self.inner
+                            .counters
+                            .messages_dropped
+                            .fetch_add(1, Ordering::Relaxed);
+                        tracing::warn!(
+                            device = %device_topic,
+                            suffix = %suffix,
+                            "Response buffer full, message dropped"
+                        );
+                    }
+                    // The device may have been dropped
+                    Err(mpsc::error::TrySendError::Closed(_)) | Ok(()) => {}
+                }
+            }
+        }
+    }
 
-        if prefix != "stat" && prefix != "tele" {
+    /// Reports the device topic of a discovery message while discovery is
+    /// active.
+    ///
+    /// Discovery listens on `tele/+/LWT`, `tele/+/STATE` and `stat/+/STATUS`,
+    /// so only devices using the default topic scheme are discovered.
+    async fn capture_discovered_device(&self, topic: &str) {
+        let parts: Vec<&str> = topic.split('/').collect();
+        if parts.len() < 3 {
             return;
         }
+        let (prefix, device_topic, suffix) = (parts[0], parts[1], parts[2]);
 
-        // Check for discovery mode - capture device topics from discovery messages
-        // tele/+/LWT, tele/+/STATE, or stat/+/STATUS
         let is_discovery_topic = (prefix == "tele" && (suffix == "LWT" || suffix == "STATE"))
             || (prefix == "stat" && suffix == "STATUS");
 
@@ -348,72 +1202,36 @@ impl MqttBroker {
             // Ignore send errors - discovery may have stopped
             let _ = discovery_tx.send(device_topic.to_string()).await;
         }
-
-        // Route to registered device subscriptions
-        let subscriptions = self.inner.subscriptions.read().await;
-        let Some(sub) = subscriptions.get(device_topic) else {
-            return;
-        };
-
-        // Route to callbacks via the topic router
-        sub.router.route(topic, &payload);
-
-        // For stat/ messages, also send to response channel if it's a command response
-        if prefix == "stat" {
-            // RESULT and STATUS* are JSON responses that go to the response channel
-            let is_json_response = suffix == "RESULT" || suffix.starts_with("STATUS");
-            if is_json_response {
-                tracing::debug!(
-                    topic = %topic,
-                    device = %device_topic,
-                    suffix = %suffix,
-                    "Routing response to device"
-                );
-                // Send as MqttMessage with topic suffix for multi-message collection
-                let msg = MqttMessage::new(suffix.to_string(), payload);
-                // Ignore send errors - the device may have been dropped
-                let _ = sub.response_tx.send(msg).await;
-            }
-        }
     }
 
     /// Handles reconnection by resubscribing to all device topics.
     ///
-    /// This is called automatically when the MQTT broker connection is restored
-    /// after a disconnection. It:
-    /// 1. Resubscribes to all device topics (`stat/<topic>/+` and `tele/<topic>/+`)
-    /// 2. Dispatches the `on_reconnected` callback to all devices
+    /// Spawned by the event loop when a `ConnAck` follows the first
+    /// connection. For each registered device it queues the subscriptions
+    /// to its `stat` and `tele` topics again, with the topic scheme and
+    /// `QoS` the device was added with, then dispatches its `on_reconnected`
+    /// callbacks.
+    ///
+    /// The subscribe requests are only queued, so the callbacks may run
+    /// before the broker acknowledges them. A failed resubscription is
+    /// logged and the device is still notified.
     async fn handle_reconnection(&self) {
         let subscriptions = self.inner.subscriptions.read().await;
 
         for (device_topic, subscription) in subscriptions.iter() {
             // Resubscribe to MQTT topics
-            let stat_topic = format!("stat/{device_topic}/+");
-            let tele_topic = format!("tele/{device_topic}/+");
-
-            if let Err(e) = self
-                .inner
-                .client
-                .subscribe(&stat_topic, QoS::AtLeastOnce)
-                .await
-            {
-                tracing::error!(topic = %stat_topic, error = %e, "Failed to resubscribe to stat topic");
-            }
-
             if let Err(e) = self
-                .inner
-                .client
-                .subscribe(&tele_topic, QoS::AtLeastOnce)
+                .subscribe_device_topics(device_topic, &subscription.scheme, subscription.qos)
                 .await
             {
-                tracing::error!(topic = %tele_topic, error = %e, "Failed to resubscribe to tele topic");
+                tracing::error!(device = %device_topic, error = %e, "Failed to resubscribe to device topics");
+            } else {
+                tracing::debug!(
+                    device = %device_topic,
+                    "Resubscribed to device topics"
+                );
             }
 
-            tracing::debug!(
-                device = %device_topic,
-                "Resubscribed to device topics"
-            );
-
             // Dispatch reconnected callback via router
             subscription.router.dispatch_reconnected_all();
         }
@@ -446,12 +1264,13 @@ impl MqttBroker {
     pub async fn disconnect(&self) -> Result<(), ProtocolError> {
         tracing::info!(
             host = %self.inner.config.host,
-            port = %self.inner.config.port,
+            port = %self.inner.config.port(),
             "Disconnecting from MQTT broker"
         );
 
         // Clear all subscriptions
         self.inner.subscriptions.write().await.clear();
+        self.inner.shutting_down.store(true, Ordering::Release);
 
         // Disconnect the client
         self.inner
@@ -485,11 +1304,24 @@ impl MqttBroker {
     }
 }
 
+/// Checks that a group topic is safe to publish to.
+fn validate_group_topic(group_topic: &str) -> Result<(), ValueError> {
+    let valid_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.');
+    if group_topic.is_empty()
+        || group_topic.len() > MAX_GROUP_TOPIC_LEN
+        || !group_topic.chars().all(valid_char)
+    {
+        return Err(ValueError::InvalidGroupTopic(group_topic.to_string()));
+    }
+    Ok(())
+}
+
 impl std::fmt::Debug for MqttBroker {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MqttBroker")
             .field("host", &self.inner.config.host)
-            .field("port", &self.inner.config.port)
+            .field("port", &self.inner.config.port())
+            .field("transport", &self.inner.config.transport)
             .field("connected", &self.is_connected())
             .finish()
     }
@@ -528,10 +1360,44 @@ impl MqttBrokerBuilder {
         self
     }
 
-    /// Sets the broker port (default: 1883).
+    /// Sets the broker port (default: 1883, or 8883 with TLS).
     #[must_use]
     pub fn port(mut self, port: u16) -> Self {
-        self.config.port = port;
+        self.config.port = Some(port);
+        self
+    }
+
+    /// Connects over TLS (default: plaintext).
+    ///
+    /// The default port becomes 8883 unless [`port`](Self::port) is set.
+    /// TLS only applies to [`MqttTransport::Tcp`].
+    #[must_use]
+    pub fn tls(mut self, tls: TlsConfig) -> Self {
+        self.config.tls = Some(tls);
+        self
+    }
+
+    /// Connects over TLS without verifying the broker certificate.
+    ///
+    /// **This is unsafe**: anyone able to intercept the connection can
+    /// impersonate the broker and read the credentials and device traffic.
+    /// Only use it to test against a broker with a self-signed certificate;
+    /// pass that certificate to [`TlsConfig::from_ca_pem`] instead wherever
+    /// the network is not fully trusted.
+    #[must_use]
+    pub fn tls_insecure(mut self) -> Self {
+        self.config.tls = Some(TlsConfig::insecure());
+        self
+    }
+
+    /// Sets the transport used to reach the broker (default: TCP).
+    ///
+    /// With [`MqttTransport::Unix`], the broker is reached through the socket
+    /// path and [`host`](Self::host)/[`port`](Self::port) are not required.
+    /// See [`MqttTransport`] for the supported transports.
+    #[must_use]
+    pub fn with_transport(mut self, transport: MqttTransport) -> Self {
+        self.config.transport = transport;
         self
     }
 
@@ -583,26 +1449,63 @@ impl MqttBrokerBuilder {
         self
     }
 
+    /// Sets how many responses are buffered per device (default:
+    /// [`DEFAULT_CHANNEL_CAPACITY`]).
+    ///
+    /// Responses (`RESULT`, `STATUS*`) wait in a per-device buffer until a
+    /// command collects them. When the buffer is full, further responses are
+    /// dropped rather than slowing down message handling for every device,
+    /// and counted in [`BrokerStats::messages_dropped`]. Raise the capacity
+    /// for devices that publish many results between commands, e.g. under a
+    /// low `TelePeriod` or with busy buttons. A capacity of 0 is treated
+    /// as 1.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tasmor_lib::MqttBroker;
+    ///
+    /// # async fn example() -> tasmor_lib::Result<()> {
+    /// let broker = MqttBroker::builder()
+    ///     .host("192.168.1.50")
+    ///     .with_channel_capacity(100)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_channel_capacity(mut self, capacity: usize) -> Self {
+        self.config.channel_capacity = capacity.max(1);
+        self
+    }
+
+    /// Sets how the broker reconnects after losing its connection (default:
+    /// [`ReconnectionPolicy::default`], retrying forever).
+    ///
+    /// Use [`ReconnectionPolicy::disabled`] to leave the broker disconnected
+    /// instead, e.g. when a supervisor restarts the whole service.
+    #[must_use]
+    pub fn with_auto_reconnect(mut self, policy: ReconnectionPolicy) -> Self {
+        self.config.reconnection = policy;
+        self
+    }
+
     /// Builds and connects to the MQTT broker.
     ///
     /// # Errors
     ///
     /// Returns error if:
-    /// - Host is not set
+    /// - Host is not set (TCP transport)
+    /// - Socket path is empty (Unix transport)
     /// - Connection fails
     /// - Connection times out
     pub async fn build(self) -> Result<MqttBroker, ProtocolError> {
-        if self.config.host.is_empty() {
-            return Err(ProtocolError::InvalidAddress(
-                "MQTT broker host is required".to_string(),
-            ));
-        }
-
         // Generate unique client ID
         let counter = BROKER_CLIENT_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
         let client_id = format!("tasmor_{}_{}", std::process::id(), counter);
 
-        let mut mqtt_options = MqttOptions::new(&client_id, &self.config.host, self.config.port);
+        let mut mqtt_options = self.config.mqtt_options(&client_id)?;
         mqtt_options.set_keep_alive(self.config.keep_alive);
         mqtt_options.set_clean_session(true);
 
@@ -612,18 +1515,7 @@ impl MqttBrokerBuilder {
 
         let (client, event_loop) = AsyncClient::new(mqtt_options, 10);
 
-        let inner = MqttBrokerInner {
-            client,
-            subscriptions: RwLock::new(HashMap::new()),
-            config: self.config.clone(),
-            connected: AtomicBool::new(false),
-            initial_connection_done: AtomicBool::new(false),
-            discovery_tx: RwLock::new(None),
-        };
-
-        let broker = MqttBroker {
-            inner: Arc::new(inner),
-        };
+        let broker = MqttBroker::new(client, self.config.clone());
 
         // Clone for event loop
         let broker_clone = broker.clone();
@@ -643,7 +1535,7 @@ impl MqttBrokerBuilder {
                 broker.inner.connected.store(true, Ordering::Release);
                 tracing::info!(
                     host = %self.config.host,
-                    port = %self.config.port,
+                    port = %self.config.port(),
                     "Connected to MQTT broker"
                 );
             }
@@ -674,24 +1566,33 @@ impl MqttBrokerBuilder {
 ///
 /// # Reconnection Behavior
 ///
-/// When the connection is lost and restored by rumqttc:
+/// rumqttc reconnects on the next poll after a connection error; the
+/// [`ReconnectionPolicy`] decides whether and after which delay that poll
+/// happens. When the connection is restored:
 /// 1. All device topic subscriptions are automatically restored
 /// 2. The `on_reconnected` callback is triggered for each device
 /// 3. Applications should call `query_state()` to refresh device state
+///
+/// The loop ends when the broker is disconnected, when the build it was
+/// started for gave up, or when the policy allows no further attempt.
 async fn handle_broker_events(
     mut event_loop: EventLoop,
     broker: MqttBroker,
     connack_tx: Option<oneshot::Sender<()>>,
 ) {
-    use rumqttc::{Event, Packet};
+    use rumqttc::{Event, Outgoing, Packet};
 
     let mut connack_tx = connack_tx;
+    let counters = &broker.inner.counters;
+    let policy = broker.inner.config.reconnection;
+    let mut failed_attempts: u32 = 0;
 
     loop {
         match event_loop.poll().await {
             Ok(Event::Incoming(Packet::ConnAck(connack))) => {
                 tracing::debug!(?connack, "MQTT broker connected");
                 broker.inner.connected.store(true, Ordering::Release);
+                failed_attempts = 0;
 
                 // Signal initial connection
                 if let Some(tx) = connack_tx.take() {
@@ -701,7 +1602,13 @@ async fn handle_broker_events(
                 // Handle reconnection (not the first connection)
                 if broker.inner.initial_connection_done.load(Ordering::Acquire) {
                     tracing::info!("MQTT broker reconnected, restoring subscriptions");
-                    broker.handle_reconnection().await;
+                    counters.reconnect_count.fetch_add(1, Ordering::Relaxed);
+                    // Subscribe requests are sent by this loop, so it must
+                    // keep polling while they are queued
+                    let broker = broker.clone();
+                    tokio::spawn(async move {
+                        broker.handle_reconnection().await;
+                    });
                 } else {
                     broker
                         .inner
@@ -713,6 +1620,10 @@ async fn handle_broker_events(
                 tracing::debug!(?suback, "MQTT subscription acknowledged");
             }
             Ok(Event::Incoming(Packet::Publish(publish))) => {
+                if broker.complete_ping(&publish.topic) {
+                    continue;
+                }
+                counters.messages_received.fetch_add(1, Ordering::Relaxed);
                 if let Ok(payload) = String::from_utf8(publish.payload.to_vec()) {
                     tracing::debug!(
                         topic = %publish.topic,
@@ -728,35 +1639,225 @@ async fn handle_broker_events(
                 broker.dispatch_disconnected_all().await;
                 // Don't break - let rumqttc attempt to reconnect
             }
+            Ok(Event::Outgoing(Outgoing::Publish(_))) => {
+                counters.messages_published.fetch_add(1, Ordering::Relaxed);
+            }
             Ok(_) => {}
             Err(e) => {
+                *counters.last_error.lock() = Some(e.to_string());
+
                 // Check if we were previously connected
                 let was_connected = broker.inner.connected.swap(false, Ordering::AcqRel);
 
+                if broker.inner.shutting_down.load(Ordering::Acquire) {
+                    tracing::debug!(error = %e, "MQTT event loop stopped after disconnect");
+                    break;
+                }
+                broker.report_error(&e);
                 if was_connected {
-                    tracing::warn!(error = %e, "MQTT connection lost, waiting for reconnection");
+                    tracing::warn!(error = %e, "MQTT connection lost");
                     broker.dispatch_disconnected_all().await;
                 } else {
                     tracing::debug!(error = %e, "MQTT connection error during reconnection attempt");
                 }
-                // Don't break - let rumqttc attempt to reconnect automatically
+
+                let connecting = !broker.inner.initial_connection_done.load(Ordering::Acquire);
+                if connecting {
+                    // The build waiting for the first connection timed out
+                    if connack_tx.as_ref().is_none_or(oneshot::Sender::is_closed) {
+                        break;
+                    }
+                } else if !policy.allows_attempt(failed_attempts) {
+                    tracing::error!(
+                        attempts = failed_attempts,
+                        "Giving up reconnecting to the MQTT broker"
+                    );
+                    break;
+                }
+
+                // rumqttc reconnects on the next poll
+                failed_attempts = failed_attempts.saturating_add(1);
+                let delay = policy.delay(failed_attempts);
+                tracing::debug!(
+                    attempt = failed_attempts,
+                    ?delay,
+                    "Reconnecting to MQTT broker"
+                );
+                tokio::time::sleep(delay).await;
             }
         }
     }
 }
 
+/// Returns the suffix a `stat` message is handled under.
+///
+/// With `SetOption4`, Tasmota publishes command results on
+/// `stat/<topic>/<Command>` instead of `stat/<topic>/RESULT`. Those results
+/// are handled as `RESULT`, so they are correlated with the command that
+/// caused them. Plain values published on the same topics (e.g. `ON` on
+/// `stat/<topic>/POWER`) and `STATUS*` replies keep their suffix.
+fn result_suffix<'a>(suffix: &'a str, payload: &str, results_on_command_topic: bool) -> &'a str {
+    if results_on_command_topic
+        && !suffix.starts_with("STATUS")
+        && payload.trim_start().starts_with('{')
+    {
+        "RESULT"
+    } else {
+        suffix
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn per_command_results_are_handled_as_result() {
+        assert_eq!(result_suffix("POWER", r#"{"POWER":"ON"}"#, true), "RESULT");
+        assert_eq!(result_suffix("DIMMER", r#"{"Dimmer":50}"#, true), "RESULT");
+
+        // Plain values and status replies are not command results
+        assert_eq!(result_suffix("POWER", "ON", true), "POWER");
+        assert_eq!(
+            result_suffix("STATUS11", r#"{"StatusSTS":{}}"#, true),
+            "STATUS11"
+        );
+
+        // Without SetOption4 only RESULT carries results
+        assert_eq!(result_suffix("POWER", r#"{"POWER":"ON"}"#, false), "POWER");
+    }
+
+    #[test]
+    fn group_topic_validation() {
+        assert!(validate_group_topic("tasmotas").is_ok());
+        assert!(validate_group_topic("living-room_1.lights").is_ok());
+
+        for invalid in ["", "a/b", "all+", "#", "with space", &"x".repeat(33)] {
+            assert_eq!(
+                validate_group_topic(invalid),
+                Err(ValueError::InvalidGroupTopic(invalid.to_string()))
+            );
+        }
+    }
+
     #[test]
     fn builder_default_values() {
         let builder = MqttBrokerBuilder::default();
-        assert_eq!(builder.config.port, 1883);
+        assert_eq!(builder.config.port(), 1883);
         assert!(builder.config.host.is_empty());
         assert!(builder.config.credentials.is_none());
         assert_eq!(builder.config.keep_alive, Duration::from_secs(30));
         assert_eq!(builder.config.connection_timeout, Duration::from_secs(10));
+        assert_eq!(builder.config.channel_capacity, DEFAULT_CHANNEL_CAPACITY);
+    }
+
+    #[test]
+    fn builder_with_channel_capacity() {
+        let builder = MqttBrokerBuilder::default().with_channel_capacity(128);
+        assert_eq!(builder.config.channel_capacity, 128);
+
+        let builder = MqttBrokerBuilder::default().with_channel_capacity(0);
+        assert_eq!(builder.config.channel_capacity, 1);
+    }
+
+    #[tokio::test]
+    async fn error_callbacks_receive_broker_address() {
+        let config = MqttBrokerBuilder::default()
+            .host("192.168.1.50")
+            .port(1884)
+            .config;
+        let (client, _event_loop) = AsyncClient::new(config.mqtt_options("client").unwrap(), 10);
+        let broker = MqttBroker::new(client, config);
+
+        let errors = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let captured = Arc::clone(&errors);
+        let id = broker.on_error(move |error| captured.lock().push(error.to_string()));
+
+        broker.report_error(&rumqttc::ConnectionError::NetworkTimeout);
+        {
+            let errors = errors.lock();
+            assert_eq!(errors.len(), 1);
+            assert!(
+                errors[0].contains("MQTT broker 192.168.1.50:1884"),
+                "{}",
+                errors[0]
+            );
+        }
+
+        assert!(broker.remove_error_callback(id));
+        assert!(!broker.remove_error_callback(id));
+        broker.report_error(&rumqttc::ConnectionError::NetworkTimeout);
+        assert_eq!(errors.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn removing_a_replaced_subscription_keeps_the_newer_one() {
+        let config = MqttBrokerBuilder::default().host("localhost").config;
+        let (client, _event_loop) = AsyncClient::new(config.mqtt_options("client").unwrap(), 10);
+        let broker = MqttBroker::new(client, config);
+
+        // Device B replaces device A on the same topic before A is cleaned up
+        let (_rx_a, router_a) = broker
+            .add_device_subscription(
+                "bedroom".to_string(),
+                QoS::AtLeastOnce,
+                TopicScheme::default(),
+            )
+            .await
+            .unwrap();
+        let (mut rx_b, router_b) = broker
+            .add_device_subscription(
+                "bedroom".to_string(),
+                QoS::AtLeastOnce,
+                TopicScheme::default(),
+            )
+            .await
+            .unwrap();
+
+        broker
+            .remove_device_subscription("bedroom", &router_a)
+            .await;
+
+        assert_eq!(broker.subscription_count().await, 1);
+        broker
+            .route_message("stat/bedroom/RESULT", r#"{"POWER":"ON"}"#.to_string())
+            .await;
+        let message = rx_b.try_recv().unwrap();
+        assert_eq!(message.topic_suffix, "RESULT");
+
+        broker
+            .remove_device_subscription("bedroom", &router_b)
+            .await;
+        assert_eq!(broker.subscription_count().await, 0);
+    }
+
+    #[test]
+    fn builder_with_auto_reconnect() {
+        let builder = MqttBrokerBuilder::default();
+        assert_eq!(builder.config.reconnection, ReconnectionPolicy::default());
+
+        let builder = builder.with_auto_reconnect(ReconnectionPolicy::disabled());
+        assert!(!builder.config.reconnection.is_enabled());
+    }
+
+    #[test]
+    fn reconnection_delay_doubles_up_to_the_limit() {
+        let policy = ReconnectionPolicy::new(Duration::from_secs(1), Duration::from_secs(10));
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(2), Duration::from_secs(2));
+        assert_eq!(policy.delay(4), Duration::from_secs(8));
+        assert_eq!(policy.delay(5), Duration::from_secs(10));
+        assert_eq!(policy.delay(100), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn reconnection_attempts() {
+        assert!(ReconnectionPolicy::default().allows_attempt(u32::MAX));
+        assert!(!ReconnectionPolicy::disabled().allows_attempt(0));
+
+        let policy = ReconnectionPolicy::default().with_max_attempts(3);
+        assert!(policy.allows_attempt(2));
+        assert!(!policy.allows_attempt(3));
     }
 
     #[test]
@@ -768,7 +1869,7 @@ mod tests {
     #[test]
     fn builder_with_port() {
         let builder = MqttBrokerBuilder::default().port(8883);
-        assert_eq!(builder.config.port, 8883);
+        assert_eq!(builder.config.port(), 8883);
     }
 
     #[test]
@@ -814,7 +1915,7 @@ mod tests {
             .command_timeout(Duration::from_secs(10));
 
         assert_eq!(builder.config.host, "192.168.1.50");
-        assert_eq!(builder.config.port, 8883);
+        assert_eq!(builder.config.port(), 8883);
         assert!(builder.config.credentials.is_some());
         assert_eq!(builder.config.keep_alive, Duration::from_secs(45));
         assert_eq!(builder.config.connection_timeout, Duration::from_secs(15));
@@ -833,7 +1934,72 @@ mod tests {
     fn config_default() {
         let config = MqttBrokerConfig::default();
         assert!(config.host.is_empty());
-        assert_eq!(config.port, 1883);
+        assert_eq!(config.port(), 1883);
+        assert!(config.tls.is_none());
+        assert_eq!(config.transport, MqttTransport::Tcp);
         assert!(config.credentials.is_none());
     }
+
+    #[test]
+    fn tcp_options_use_host_and_port() {
+        let builder = MqttBrokerBuilder::default().host("192.168.1.50").port(1884);
+        let options = builder.config.mqtt_options("client").unwrap();
+        assert_eq!(options.broker_address(), ("192.168.1.50".to_string(), 1884));
+    }
+
+    #[test]
+    fn tls_options_default_to_port_8883() {
+        // The CA is only parsed when connecting
+        let builder = MqttBrokerBuilder::default()
+            .host("broker.example.com")
+            .tls(TlsConfig::from_ca_pem("bogus CA"));
+        let options = builder.config.mqtt_options("client").unwrap();
+        assert_eq!(
+            options.broker_address(),
+            ("broker.example.com".to_string(), 8883)
+        );
+        assert!(matches!(options.transport(), rumqttc::Transport::Tls(_)));
+
+        let options = builder.port(18883).config.mqtt_options("client").unwrap();
+        assert_eq!(options.broker_address().1, 18883);
+    }
+
+    #[test]
+    fn builder_tls_insecure() {
+        let builder = MqttBrokerBuilder::default()
+            .host("localhost")
+            .tls_insecure();
+        assert!(!builder.config.tls.as_ref().unwrap().verifies_server());
+        let options = builder.config.mqtt_options("client").unwrap();
+        assert!(matches!(options.transport(), rumqttc::Transport::Tls(_)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn builder_with_unix_transport() {
+        let builder = MqttBrokerBuilder::default()
+            .with_transport(MqttTransport::Unix("/run/mosquitto/mqtt.sock".into()));
+        assert_eq!(
+            builder.config.transport,
+            MqttTransport::Unix("/run/mosquitto/mqtt.sock".into())
+        );
+
+        // No host is needed: the socket path is the broker address
+        let options = builder.config.mqtt_options("client").unwrap();
+        assert_eq!(
+            options.broker_address(),
+            ("/run/mosquitto/mqtt.sock".to_string(), 0)
+        );
+        assert!(matches!(options.transport(), rumqttc::Transport::Unix));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn builder_empty_socket_path_fails() {
+        let result = MqttBrokerBuilder::default()
+            .with_transport(MqttTransport::Unix(PathBuf::new()))
+            .build()
+            .await;
+        assert!(matches!(result, Err(ProtocolError::InvalidAddress(_))));
+    }
 }
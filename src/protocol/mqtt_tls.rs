@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! TLS settings for MQTT broker connections.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use rumqttc::TlsConfiguration;
+use rumqttc::tokio_rustls::rustls;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+
+use crate::error::ProtocolError;
+
+/// Default port of MQTT over TLS.
+pub const DEFAULT_TLS_PORT: u16 = 8883;
+
+/// PEM data given inline or read from a file when the broker is built.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pem {
+    Bytes(Vec<u8>),
+    File(PathBuf),
+}
+
+impl Pem {
+    fn load(&self, what: &str) -> Result<Vec<u8>, ProtocolError> {
+        match self {
+            Self::Bytes(bytes) => Ok(bytes.clone()),
+            Self::File(path) => std::fs::read(path).map_err(|e| {
+                ProtocolError::ConnectionFailed(format!(
+                    "cannot read {what} {}: {e}",
+                    path.display()
+                ))
+            }),
+        }
+    }
+}
+
+/// TLS settings for the connection to the MQTT broker.
+///
+/// The broker certificate is verified against the given CA certificate.
+/// Add a client certificate with [`with_client_auth_pem`](Self::with_client_auth_pem)
+/// or [`with_client_auth_files`](Self::with_client_auth_files) when the
+/// broker requires mutual TLS.
+///
+/// Certificates are only parsed when connecting, so an invalid one surfaces
+/// as a connection error from [`MqttBrokerBuilder::build`](super::MqttBrokerBuilder::build).
+///
+/// # Examples
+///
+/// ```no_run
+/// use tasmor_lib::MqttBroker;
+/// use tasmor_lib::protocol::TlsConfig;
+///
+/// # async fn example() -> tasmor_lib::Result<()> {
+/// let tls = TlsConfig::from_ca_file("/etc/mosquitto/ca.crt")
+///     .with_client_auth_files("/etc/tasmor/client.crt", "/etc/tasmor/client.key");
+///
+/// // Connects to port 8883 unless another port is set
+/// let broker = MqttBroker::builder()
+///     .host("broker.example.com")
+///     .tls(tls)
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// `None` skips server certificate verification.
+    ca: Option<Pem>,
+    client_auth: Option<(Pem, Pem)>,
+}
+
+impl TlsConfig {
+    /// Verifies the broker against a PEM-encoded CA certificate.
+    #[must_use]
+    pub fn from_ca_pem(ca: impl Into<Vec<u8>>) -> Self {
+        Self {
+            ca: Some(Pem::Bytes(ca.into())),
+            client_auth: None,
+        }
+    }
+
+    /// Verifies the broker against the PEM-encoded CA certificate at `path`.
+    ///
+    /// The file is read when the broker is built.
+    #[must_use]
+    pub fn from_ca_file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            ca: Some(Pem::File(path.into())),
+            client_auth: None,
+        }
+    }
+
+    /// Accepts any broker certificate.
+    ///
+    /// See [`MqttBrokerBuilder::tls_insecure`](super::MqttBrokerBuilder::tls_insecure).
+    pub(crate) fn insecure() -> Self {
+        Self {
+            ca: None,
+            client_auth: None,
+        }
+    }
+
+    /// Authenticates with a PEM-encoded client certificate and private key.
+    #[must_use]
+    pub fn with_client_auth_pem(
+        mut self,
+        cert: impl Into<Vec<u8>>,
+        key: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.client_auth = Some((Pem::Bytes(cert.into()), Pem::Bytes(key.into())));
+        self
+    }
+
+    /// Authenticates with the PEM-encoded client certificate and private key
+    /// at the given paths.
+    ///
+    /// The files are read when the broker is built.
+    #[must_use]
+    pub fn with_client_auth_files(
+        mut self,
+        cert: impl Into<PathBuf>,
+        key: impl Into<PathBuf>,
+    ) -> Self {
+        self.client_auth = Some((Pem::File(cert.into()), Pem::File(key.into())));
+        self
+    }
+
+    /// Returns whether the broker certificate is verified.
+    #[must_use]
+    pub fn verifies_server(&self) -> bool {
+        self.ca.is_some()
+    }
+
+    /// Returns whether a client certificate is configured.
+    #[must_use]
+    pub fn has_client_auth(&self) -> bool {
+        self.client_auth.is_some()
+    }
+
+    /// Creates the `rumqttc` TLS configuration, reading certificate files.
+    pub(crate) fn configuration(&self) -> Result<TlsConfiguration, ProtocolError> {
+        let Some(ca) = &self.ca else {
+            let config = rustls::ClientConfig::builder()
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(AcceptAnyServerCert))
+                .with_no_client_auth();
+            return Ok(TlsConfiguration::Rustls(Arc::new(config)));
+        };
+
+        let client_auth = match &self.client_auth {
+            Some((cert, key)) => Some((cert.load("client certificate")?, key.load("client key")?)),
+            None => None,
+        };
+        Ok(TlsConfiguration::Simple {
+            ca: ca.load("CA certificate")?,
+            alpn: None,
+            client_auth,
+        })
+    }
+}
+
+/// Certificate verifier accepting any server, for [`TlsConfig::insecure`].
+#[derive(Debug)]
+struct AcceptAnyServerCert;
+
+impl ServerCertVerifier for AcceptAnyServerCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        vec![
+            SignatureScheme::ECDSA_NISTP256_SHA256,
+            SignatureScheme::ECDSA_NISTP384_SHA384,
+            SignatureScheme::ECDSA_NISTP521_SHA512,
+            SignatureScheme::ED25519,
+            SignatureScheme::RSA_PSS_SHA256,
+            SignatureScheme::RSA_PSS_SHA384,
+            SignatureScheme::RSA_PSS_SHA512,
+            SignatureScheme::RSA_PKCS1_SHA256,
+            SignatureScheme::RSA_PKCS1_SHA384,
+            SignatureScheme::RSA_PKCS1_SHA512,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ca_bytes_are_passed_through() {
+        let tls = TlsConfig::from_ca_pem("not a certificate")
+            .with_client_auth_pem("client cert", "client key");
+        assert!(tls.verifies_server());
+        assert!(tls.has_client_auth());
+
+        let TlsConfiguration::Simple {
+            ca, client_auth, ..
+        } = tls.configuration().unwrap()
+        else {
+            panic!("expected a CA-based configuration");
+        };
+        assert_eq!(ca, b"not a certificate");
+        assert_eq!(
+            client_auth,
+            Some((b"client cert".to_vec(), b"client key".to_vec()))
+        );
+    }
+
+    #[test]
+    fn missing_ca_file_fails() {
+        let tls = TlsConfig::from_ca_file("/nonexistent/tasmor/ca.crt");
+        assert!(matches!(
+            tls.configuration(),
+            Err(ProtocolError::ConnectionFailed(_))
+        ));
+    }
+
+    #[test]
+    fn insecure_skips_verification() {
+        let tls = TlsConfig::insecure();
+        assert!(!tls.verifies_server());
+        assert!(matches!(
+            tls.configuration().unwrap(),
+            TlsConfiguration::Rustls(_)
+        ));
+    }
+}
@@ -18,11 +18,7 @@
 //! The [`ResponseSpec`] type describes what responses a command expects. This is
 //! used by the [`Command`](crate::command::Command) trait's `response_spec()` method.
 //!
-//! # Public API
-//!
-//! Only [`ResponseSpec`] is part of the public API. It is needed by users who
-//! implement custom commands via the [`Command`](crate::command::Command) trait.
-//! All other types in this module are internal implementation details.
+//! All types in this module are internal implementation details.
 
 use std::collections::HashSet;
 use std::time::Duration;
@@ -32,87 +28,7 @@ use tokio::sync::mpsc;
 use tokio::time::Instant;
 
 use crate::error::ProtocolError;
-
-/// Specification of expected responses from a command.
-///
-/// Most commands expect a single response, but some (like `Status 0`) expect
-/// multiple messages that should be aggregated into a single response.
-#[derive(Debug, Clone, Default)]
-pub enum ResponseSpec {
-    /// A single response is expected.
-    ///
-    /// This is the default for most commands.
-    #[default]
-    Single,
-
-    /// Multiple responses are expected, identified by topic suffixes.
-    ///
-    /// The responses will be collected and merged into a single JSON object.
-    /// Collection continues until all expected topics are received or timeout.
-    Multiple {
-        /// Expected topic suffixes (e.g., `["STATUS", "STATUS1", "STATUS5"]`).
-        expected_topics: Vec<String>,
-        /// Maximum time to wait for all responses.
-        /// If not all topics arrive within this duration, returns what was collected.
-        timeout: Duration,
-    },
-}
-
-impl ResponseSpec {
-    /// Creates a spec for a single response (the default).
-    #[must_use]
-    pub const fn single() -> Self {
-        Self::Single
-    }
-
-    /// Creates a spec for multiple responses with specific topic suffixes.
-    #[must_use]
-    pub fn multiple(expected_topics: Vec<String>, timeout: Duration) -> Self {
-        Self::Multiple {
-            expected_topics,
-            timeout,
-        }
-    }
-
-    /// Creates a spec for Status 0 which returns multiple STATUS* messages.
-    ///
-    /// Status 0 returns messages on these topic suffixes:
-    /// - STATUS (device name, friendly names)
-    /// - STATUS1 (device parameters)
-    /// - STATUS2 (firmware info)
-    /// - STATUS3 (logging settings)
-    /// - STATUS4 (memory info)
-    /// - STATUS5 (network info)
-    /// - STATUS6 (MQTT settings)
-    /// - STATUS7 (time info)
-    /// - STATUS10 (sensor info) - optional, only if sensors present
-    /// - STATUS11 (state info with uptime)
-    #[must_use]
-    pub fn status_all(timeout: Duration) -> Self {
-        Self::Multiple {
-            expected_topics: vec![
-                "STATUS".to_string(),
-                "STATUS1".to_string(),
-                "STATUS2".to_string(),
-                "STATUS3".to_string(),
-                "STATUS4".to_string(),
-                "STATUS5".to_string(),
-                "STATUS6".to_string(),
-                "STATUS7".to_string(),
-                // STATUS8 and STATUS9 are deprecated/unused
-                // STATUS10 is optional (sensors)
-                "STATUS11".to_string(),
-            ],
-            timeout,
-        }
-    }
-
-    /// Returns true if this spec expects multiple responses.
-    #[must_use]
-    pub const fn is_multiple(&self) -> bool {
-        matches!(self, Self::Multiple { .. })
-    }
-}
+use crate::protocol::ResponseSpec;
 
 /// An MQTT message with its topic suffix for routing.
 ///
@@ -314,36 +230,6 @@ pub(crate) async fn collect_responses(
 mod tests {
     use super::*;
 
-    #[test]
-    fn response_spec_default_is_single() {
-        let spec = ResponseSpec::default();
-        assert!(!spec.is_multiple());
-    }
-
-    #[test]
-    fn response_spec_multiple() {
-        let spec = ResponseSpec::multiple(
-            vec!["STATUS".to_string(), "STATUS1".to_string()],
-            Duration::from_secs(5),
-        );
-        assert!(spec.is_multiple());
-    }
-
-    #[test]
-    fn response_spec_status_all() {
-        let spec = ResponseSpec::status_all(Duration::from_secs(5));
-        if let ResponseSpec::Multiple {
-            expected_topics, ..
-        } = spec
-        {
-            assert!(expected_topics.contains(&"STATUS".to_string()));
-            assert!(expected_topics.contains(&"STATUS11".to_string()));
-            assert!(!expected_topics.contains(&"STATUS10".to_string())); // Optional, not included by default
-        } else {
-            panic!("Expected Multiple variant");
-        }
-    }
-
     #[test]
     fn collector_processes_expected_message() {
         let spec = ResponseSpec::multiple(
@@ -0,0 +1,259 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Retrying of transient HTTP failures.
+
+use std::future::Future;
+use std::time::Duration;
+
+use crate::error::ProtocolError;
+
+/// How often and how patiently a failed HTTP request is retried.
+///
+/// Only connection-level failures are retried: a refused or reset
+/// connection and an elapsed timeout. Responses the device did send, such
+/// as HTTP 4xx errors, a failed authentication or an unparseable body, are
+/// returned right away. Busy responses are covered separately by
+/// [`HttpConfig::with_busy_retry`](crate::protocol::HttpConfig::with_busy_retry).
+///
+/// # Idempotency
+///
+/// A failed request may still have reached the device: the connection can
+/// drop after Tasmota ran the command but before the response arrived.
+/// Sending the command again is harmless for `Power ON` or `Dimmer 50`, but
+/// a retried `Power TOGGLE` could toggle twice and leave the relay where it
+/// was. Commands that report themselves as not idempotent (see
+/// [`Command::is_idempotent`](crate::command::Command::is_idempotent)) and
+/// raw command strings, whose effect is unknown, are therefore sent only
+/// once unless [`with_non_idempotent_retries`](Self::with_non_idempotent_retries)
+/// is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use tasmor_lib::protocol::{HttpConfig, RetryPolicy};
+///
+/// // Up to 3 attempts, waiting 100ms then 200ms between them
+/// let policy = RetryPolicy::new(3, Duration::from_millis(100));
+/// assert_eq!(policy.backoff(1), Duration::from_millis(100));
+/// assert_eq!(policy.backoff(2), Duration::from_millis(200));
+///
+/// let config = HttpConfig::new("192.168.1.100").with_retry(policy);
+/// assert_eq!(config.retry_policy().max_attempts(), 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    retry_non_idempotent: bool,
+}
+
+impl RetryPolicy {
+    /// Default upper bound of the delay between two attempts.
+    pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+    /// Creates a policy making up to `max_attempts` attempts in total.
+    ///
+    /// The delay before the first retry is `initial_backoff` and doubles
+    /// with every further retry, up to [`Self::DEFAULT_MAX_BACKOFF`].
+    /// A `max_attempts` of 0 is treated as 1.
+    #[must_use]
+    pub fn new(max_attempts: u32, initial_backoff: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            initial_backoff,
+            max_backoff: Self::DEFAULT_MAX_BACKOFF.max(initial_backoff),
+            retry_non_idempotent: false,
+        }
+    }
+
+    /// Creates a policy that sends every request once.
+    #[must_use]
+    pub const fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            retry_non_idempotent: false,
+        }
+    }
+
+    /// Sets the upper bound of the delay between two attempts.
+    #[must_use]
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Also retries commands that are not idempotent.
+    ///
+    /// Disabled by default; see the [idempotency notes](Self#idempotency).
+    #[must_use]
+    pub fn with_non_idempotent_retries(mut self, enabled: bool) -> Self {
+        self.retry_non_idempotent = enabled;
+        self
+    }
+
+    /// Returns the maximum number of attempts, including the first one.
+    #[must_use]
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Returns the upper bound of the delay between two attempts.
+    #[must_use]
+    pub fn max_backoff(&self) -> Duration {
+        self.max_backoff
+    }
+
+    /// Returns whether commands that are not idempotent are retried.
+    #[must_use]
+    pub fn retries_non_idempotent(&self) -> bool {
+        self.retry_non_idempotent
+    }
+
+    /// Returns the delay before the given retry, counting from 1.
+    #[must_use]
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// Returns whether a request failing with `error` may be retried.
+    #[must_use]
+    pub fn is_retryable(error: &ProtocolError) -> bool {
+        match error {
+            ProtocolError::Timeout(_) => true,
+            ProtocolError::Http(e) => e.is_connect() || e.is_timeout() || e.is_request(),
+            _ => false,
+        }
+    }
+
+    /// Runs `operation`, retrying transient failures as this policy allows.
+    pub(crate) async fn run<T, F, Fut>(
+        &self,
+        idempotent: bool,
+        mut operation: F,
+    ) -> Result<T, ProtocolError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, ProtocolError>>,
+    {
+        let max_attempts = if idempotent || self.retry_non_idempotent {
+            self.max_attempts
+        } else {
+            1
+        };
+
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Err(e) if attempt < max_attempts && Self::is_retryable(&e) => {
+                    let delay = self.backoff(attempt);
+                    tracing::debug!(attempt, ?delay, error = %e, "Request failed, retrying");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    /// Fails with a timeout `failures` times, then succeeds.
+    fn flaky(failures: u32, calls: &AtomicU32) -> impl Future<Output = Result<u32, ProtocolError>> {
+        let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+        async move {
+            if call <= failures {
+                Err(ProtocolError::Timeout(1))
+            } else {
+                Ok(call)
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_until_success() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new(3, Duration::from_millis(100));
+
+        let result = policy.run(true, || flaky(2, &calls)).await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gives_up_after_max_attempts() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new(3, Duration::from_millis(100));
+
+        let result = policy.run(true, || flaky(5, &calls)).await;
+
+        assert!(matches!(result, Err(ProtocolError::Timeout(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn non_idempotent_commands_are_sent_once() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new(3, Duration::from_millis(100));
+        assert!(policy.run(false, || flaky(1, &calls)).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let calls = AtomicU32::new(0);
+        let policy = policy.with_non_idempotent_retries(true);
+        assert!(policy.run(false, || flaky(1, &calls)).await.is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn device_errors_are_not_retried() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new(3, Duration::from_millis(100));
+
+        let result: Result<(), _> = policy
+            .run(true, || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async {
+                    Err(ProtocolError::ConnectionFailed(
+                        "HTTP 404 - Not Found".into(),
+                    ))
+                }
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_limit() {
+        let policy = RetryPolicy::new(10, Duration::from_millis(100))
+            .with_max_backoff(Duration::from_millis(500));
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff(40), Duration::from_millis(500));
+        assert_eq!(RetryPolicy::none().max_attempts(), 1);
+    }
+}
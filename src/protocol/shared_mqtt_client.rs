@@ -18,9 +18,10 @@ use tokio::sync::{Mutex, mpsc};
 
 use crate::command::Command;
 use crate::error::ProtocolError;
-use crate::protocol::response_collector::{MqttMessage, ResponseSpec, collect_responses};
-use crate::protocol::{CommandResponse, Protocol};
+use crate::protocol::response_collector::{MqttMessage, collect_responses};
+use crate::protocol::{CommandResponse, Protocol, ResponseSpec};
 use crate::subscription::CallbackRegistry;
+use crate::types::{TopicPrefix, TopicScheme};
 
 use super::mqtt_broker::MqttBroker;
 use super::topic_router::TopicRouter;
@@ -55,6 +56,8 @@ pub struct SharedMqttClient {
     client: rumqttc::AsyncClient,
     /// The device topic (e.g., `tasmota_bulb`).
     topic: String,
+    /// Layout of the device's MQTT topics, used for the command topic.
+    scheme: TopicScheme,
     /// Channel for receiving command responses (with topic suffix metadata).
     response_rx: Arc<Mutex<mpsc::Receiver<MqttMessage>>>,
     /// Router for dispatching messages to callbacks.
@@ -74,6 +77,7 @@ impl SharedMqttClient {
     pub(crate) fn new(
         client: rumqttc::AsyncClient,
         topic: String,
+        scheme: TopicScheme,
         response_rx: mpsc::Receiver<MqttMessage>,
         router: Arc<TopicRouter>,
         broker: MqttBroker,
@@ -82,6 +86,7 @@ impl SharedMqttClient {
         Self {
             client,
             topic,
+            scheme,
             response_rx: Arc::new(Mutex::new(response_rx)),
             router,
             broker,
@@ -106,7 +111,9 @@ impl SharedMqttClient {
         if self.disconnected.swap(true, Ordering::SeqCst) {
             return; // Already disconnected
         }
-        self.broker.remove_device_subscription(&self.topic).await;
+        self.broker
+            .remove_device_subscription(&self.topic, &self.router)
+            .await;
         tracing::debug!(topic = %self.topic, "Device disconnected");
     }
 
@@ -123,14 +130,19 @@ impl SharedMqttClient {
 
     /// Publishes a message to the command topic.
     async fn publish_command(&self, command: &str, payload: &str) -> Result<(), ProtocolError> {
-        let topic = format!("cmnd/{}/{command}", self.topic);
+        let topic = self
+            .scheme
+            .topic(TopicPrefix::Command, &self.topic, command);
 
         tracing::debug!(topic = %topic, payload = %payload, "Publishing shared MQTT command");
 
         self.client
             .publish(&topic, QoS::AtLeastOnce, false, payload)
             .await
-            .map_err(ProtocolError::Mqtt)
+            .map_err(|e| {
+                self.broker.report_publish_error(&topic, &e);
+                ProtocolError::Mqtt(e)
+            })
     }
 
     /// Drains stale messages from the response channel.
@@ -206,11 +218,12 @@ impl Drop for SharedMqttClient {
 
         let topic = self.topic.clone();
         let broker = self.broker.clone();
+        let router = Arc::clone(&self.router);
 
         // Attempt async cleanup if we're in a tokio runtime
         if let Ok(handle) = tokio::runtime::Handle::try_current() {
             handle.spawn(async move {
-                broker.remove_device_subscription(&topic).await;
+                broker.remove_device_subscription(&topic, &router).await;
                 tracing::debug!(topic = %topic, "Device cleanup via Drop");
             });
         } else {
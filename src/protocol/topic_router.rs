@@ -30,9 +30,10 @@ use std::sync::{Arc, Weak};
 
 use parking_lot::RwLock;
 
+use crate::response::case_insensitive;
 use crate::state::StateChange;
 use crate::subscription::CallbackRegistry;
-use crate::telemetry::{SensorData, TelemetryState};
+use crate::telemetry::{SensorData, TelemetryState, parse_button_actions, parse_tuya_received};
 use crate::types::PowerState;
 
 /// Routes MQTT messages to device callback registries.
@@ -186,6 +187,12 @@ fn dispatch_message(callbacks: &CallbackRegistry, parsed: &ParsedTopic<'_>, payl
 
         // Command result: stat/<topic>/RESULT
         ("stat", "RESULT") => {
+            match serde_json::from_str::<serde_json::Value>(payload) {
+                Ok(value) => callbacks.dispatch_result(parsed.device_topic, &value),
+                Err(e) => {
+                    tracing::debug!(device = %parsed.device_topic, error = %e, "RESULT payload is not JSON");
+                }
+            }
             if let Some(changes) = parse_result_payload(payload) {
                 tracing::debug!(
                     device = %parsed.device_topic,
@@ -196,11 +203,12 @@ fn dispatch_message(callbacks: &CallbackRegistry, parsed: &ParsedTopic<'_>, payl
                     callbacks.dispatch(&change);
                 }
             }
+            dispatch_button_actions(callbacks, parsed, payload);
         }
 
         // Telemetry state: tele/<topic>/STATE
         ("tele", "STATE") => {
-            if let Ok(state) = serde_json::from_str::<TelemetryState>(payload) {
+            if let Ok(state) = case_insensitive::from_str::<TelemetryState>(payload) {
                 let changes = state.to_state_changes();
                 tracing::debug!(
                     device = %parsed.device_topic,
@@ -215,7 +223,7 @@ fn dispatch_message(callbacks: &CallbackRegistry, parsed: &ParsedTopic<'_>, payl
 
         // Sensor telemetry: tele/<topic>/SENSOR
         ("tele", "SENSOR") => {
-            if let Ok(sensor) = serde_json::from_str::<SensorData>(payload) {
+            if let Ok(sensor) = case_insensitive::from_str::<SensorData>(payload) {
                 let changes = sensor.to_state_changes();
                 if !changes.is_empty() {
                     tracing::debug!(
@@ -230,6 +238,26 @@ fn dispatch_message(callbacks: &CallbackRegistry, parsed: &ParsedTopic<'_>, payl
             }
         }
 
+        // Tuya MCU reports: tele/<topic>/RESULT with {"TuyaReceived":{...}}
+        ("tele", "RESULT") if payload.contains("\"TuyaReceived\"") => {
+            match parse_tuya_received(payload) {
+                Ok(points) => {
+                    for point in &points {
+                        tracing::debug!(
+                            device = %parsed.device_topic,
+                            dp_id = point.dp_id(),
+                            value = %point.value(),
+                            "Dispatching Tuya data point"
+                        );
+                        callbacks.dispatch_tuya(point);
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(device = %parsed.device_topic, error = %e, "Failed to parse TuyaReceived");
+                }
+            }
+        }
+
         // LWT (Last Will and Testament): tele/<topic>/LWT
         ("tele", "LWT") => {
             match payload {
@@ -256,6 +284,25 @@ fn dispatch_message(callbacks: &CallbackRegistry, parsed: &ParsedTopic<'_>, payl
     }
 }
 
+/// Dispatches detached button presses (`SetOption73`) reported in a
+/// `RESULT` payload, e.g. `{"Button1":{"Action":"SINGLE"}}`.
+fn dispatch_button_actions(callbacks: &CallbackRegistry, parsed: &ParsedTopic<'_>, payload: &str) {
+    if !payload.contains("\"Action\"") {
+        return;
+    }
+    if let Ok(actions) = parse_button_actions(payload) {
+        for (index, action) in actions {
+            tracing::debug!(
+                device = %parsed.device_topic,
+                index,
+                %action,
+                "Dispatching button action"
+            );
+            callbacks.dispatch_button(index, action);
+        }
+    }
+}
+
 /// Parsed MQTT topic components.
 #[derive(Debug)]
 struct ParsedTopic<'a> {
@@ -389,8 +436,8 @@ mod tests {
         assert_eq!(router.device_count(), 1);
 
         // Route a power message
-        let routed = router.route("stat/bedroom/POWER", "ON");
-        assert!(routed);
+        let was_routed = router.route("stat/bedroom/POWER", "ON");
+        assert!(was_routed);
         assert_eq!(counter.load(Ordering::SeqCst), 1);
     }
 
@@ -399,8 +446,8 @@ mod tests {
         let router = TopicRouter::new();
 
         // No devices registered, should not route
-        let routed = router.route("stat/unknown/POWER", "ON");
-        assert!(!routed);
+        let was_routed = router.route("stat/unknown/POWER", "ON");
+        assert!(!was_routed);
     }
 
     #[test]
@@ -416,8 +463,8 @@ mod tests {
         assert_eq!(router.device_count(), 0);
 
         // Should not route anymore
-        let routed = router.route("stat/bedroom/POWER", "ON");
-        assert!(!routed);
+        let was_routed = router.route("stat/bedroom/POWER", "ON");
+        assert!(!was_routed);
     }
 
     #[test]
@@ -456,12 +503,79 @@ mod tests {
 
         // Route a telemetry STATE message
         let payload = r#"{"POWER":"ON","Dimmer":75}"#;
-        let routed = router.route("tele/living_room/STATE", payload);
-        assert!(routed);
+        let was_routed = router.route("tele/living_room/STATE", payload);
+        assert!(was_routed);
         // Batch + power + dimmer = 3 calls to state_changed
         assert!(counter.load(Ordering::SeqCst) >= 1);
     }
 
+    #[test]
+    fn router_route_tuya_received() {
+        let router = TopicRouter::new();
+        let callbacks = Arc::new(CallbackRegistry::new());
+
+        let received = Arc::new(AtomicU32::new(0));
+        let received_clone = received.clone();
+        callbacks.on_tuya_received(move |point| {
+            assert_eq!(point.dp_id(), 2);
+            assert_eq!(point.value(), &crate::types::TuyaValue::Int(500));
+            received_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        router.register("fan", &callbacks);
+
+        let payload =
+            r#"{"TuyaReceived":{"Cmnd":7,"2":{"DpId":2,"DpIdType":2,"DpIdData":"000001F4"}}}"#;
+        assert!(router.route("tele/fan/RESULT", payload));
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn router_route_button_action() {
+        let router = TopicRouter::new();
+        let callbacks = Arc::new(CallbackRegistry::new());
+
+        let received = Arc::new(AtomicU32::new(0));
+        let received_clone = received.clone();
+        callbacks.on_button_action(move |index, action| {
+            assert_eq!(index, 1);
+            assert_eq!(action, crate::types::ButtonAction::Double);
+            received_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        router.register("switch", &callbacks);
+
+        assert!(router.route("stat/switch/RESULT", r#"{"Button1":{"Action":"DOUBLE"}}"#));
+        assert!(router.route("stat/switch/RESULT", r#"{"POWER":"ON"}"#));
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn router_route_raw_result() {
+        let router = TopicRouter::new();
+        let callbacks = Arc::new(CallbackRegistry::new());
+
+        let received = Arc::new(AtomicU32::new(0));
+        let received_clone = received.clone();
+        callbacks.on_result(move |device_topic, value| {
+            assert_eq!(device_topic, "plug");
+            assert_eq!(value["PulseTime1"]["Set"], 100);
+            received_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        router.register("plug", &callbacks);
+
+        // Not a recognized state field, still delivered raw
+        let payload = r#"{"PulseTime1":{"Set":100,"Remaining":0}}"#;
+        assert!(router.route("stat/plug/RESULT", payload));
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+
+        // Non-JSON payloads and other topics are not delivered
+        assert!(router.route("stat/plug/RESULT", "not json"));
+        assert!(router.route("tele/plug/STATE", r#"{"POWER":"ON"}"#));
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+    }
+
     #[test]
     fn router_route_lwt_offline() {
         let router = TopicRouter::new();
@@ -476,8 +590,8 @@ mod tests {
         router.register("device", &callbacks);
 
         // Route LWT offline
-        let routed = router.route("tele/device/LWT", "Offline");
-        assert!(routed);
+        let was_routed = router.route("tele/device/LWT", "Offline");
+        assert!(was_routed);
         assert_eq!(disconnected.load(Ordering::SeqCst), 1);
     }
 
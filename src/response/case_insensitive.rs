@@ -0,0 +1,201 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Case-insensitive JSON key matching.
+//!
+//! Tasmota itself treats command names case-insensitively, and some builds
+//! and forks report keys in a different case than upstream (`DIMMER`,
+//! `Power`, `Hostname` vs `HostName`). Responses are parsed with
+//! [`from_str`], which matches the keys of every JSON object against the
+//! field names of the struct being deserialized regardless of case. An
+//! exact match always wins; keys that match no field are left as they are,
+//! so maps (e.g. `GPIO<n>` entries) see the original keys.
+
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{DeserializeOwned, Deserializer, IntoDeserializer, Visitor};
+use serde_json::{Map, Value};
+
+/// Parses JSON into `T`, matching struct field names regardless of case.
+pub(crate) fn from_str<T: DeserializeOwned>(json: &str) -> Result<T, serde_json::Error> {
+    let value: Value = serde_json::from_str(json)?;
+    T::deserialize(CaseInsensitive(value))
+}
+
+/// A JSON value whose objects match struct fields case-insensitively.
+struct CaseInsensitive(Value);
+
+impl IntoDeserializer<'_, serde_json::Error> for CaseInsensitive {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> Deserializer<'de> for CaseInsensitive {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Object(map) => visit_object(map.into_iter(), visitor),
+            Value::Array(items) => visit_array(items, visitor),
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Object(map) => visit_object(match_fields(map, fields).into_iter(), visitor),
+            other => other.deserialize_struct(name, fields, visitor),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map identifier
+    }
+}
+
+/// Visits an object, keeping its values case-insensitive.
+fn visit_object<'de, V: Visitor<'de>>(
+    entries: impl Iterator<Item = (String, Value)>,
+    visitor: V,
+) -> Result<V::Value, serde_json::Error> {
+    let mut map = MapDeserializer::new(entries.map(|(key, value)| (key, CaseInsensitive(value))));
+    let value = visitor.visit_map(&mut map)?;
+    map.end()?;
+    Ok(value)
+}
+
+/// Visits an array, keeping its items case-insensitive.
+fn visit_array<'de, V: Visitor<'de>>(
+    items: Vec<Value>,
+    visitor: V,
+) -> Result<V::Value, serde_json::Error> {
+    let mut seq = SeqDeserializer::new(items.into_iter().map(CaseInsensitive));
+    let value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+/// Renames keys to the field they match regardless of case.
+///
+/// A key is only renamed if the object has no exact match for the field and
+/// no other key was renamed to it already.
+fn match_fields(map: Map<String, Value>, fields: &[&'static str]) -> Vec<(String, Value)> {
+    let mut claimed: Vec<&str> = fields
+        .iter()
+        .copied()
+        .filter(|field| map.contains_key(*field))
+        .collect();
+
+    map.into_iter()
+        .map(|(key, value)| {
+            if fields.contains(&key.as_str()) {
+                return (key, value);
+            }
+            match fields
+                .iter()
+                .copied()
+                .find(|field| field.eq_ignore_ascii_case(&key) && !claimed.contains(field))
+            {
+                Some(field) => {
+                    claimed.push(field);
+                    (field.to_string(), value)
+                }
+                None => (key, value),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Light {
+        #[serde(rename = "Dimmer")]
+        dimmer: u8,
+        #[serde(rename = "POWER", default)]
+        power: Option<String>,
+        #[serde(rename = "Channel", default)]
+        channels: Vec<u8>,
+        #[serde(rename = "Wifi", default)]
+        wifi: Option<Wifi>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Wifi {
+        ssid: String,
+    }
+
+    #[test]
+    fn matches_fields_regardless_of_case() {
+        let json = r#"{"DIMMER":75,"Power":"ON","channel":[1,2],"WIFI":{"SSID":"home"}}"#;
+        let light: Light = from_str(json).unwrap();
+
+        assert_eq!(light.dimmer, 75);
+        assert_eq!(light.power.as_deref(), Some("ON"));
+        assert_eq!(light.channels, [1, 2]);
+        assert_eq!(light.wifi.unwrap().ssid, "home");
+    }
+
+    #[test]
+    fn exact_match_wins() {
+        let light: Light = from_str(r#"{"dimmer":10,"Dimmer":20}"#).unwrap();
+        assert_eq!(light.dimmer, 20);
+    }
+
+    #[test]
+    fn map_keys_are_kept() {
+        let map: HashMap<String, u8> = from_str(r#"{"GPIO4":1,"gpio5":2}"#).unwrap();
+        assert_eq!(map["GPIO4"], 1);
+        assert_eq!(map["gpio5"], 2);
+    }
+
+    #[test]
+    fn missing_field_still_fails() {
+        assert!(from_str::<Light>(r#"{"Brightness":75}"#).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Light channel response parsing.
+
+use serde::Deserialize;
+
+use crate::error::ParseError;
+use crate::types::PowerState;
+
+/// Response from a `Channel<x>` command.
+///
+/// Tasmota answers with the whole light state, reporting every channel at
+/// once in the `Channel` array (0-100 each):
+///
+/// ```json
+/// {"POWER":"ON","Dimmer":100,"Color":"FFFF00CC00","Channel":[100,100,0,80,0]}
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::ChannelResponse;
+///
+/// let json = r#"{"POWER":"ON","Dimmer":100,"Channel":[100,100,0,80,0]}"#;
+/// let response: ChannelResponse = serde_json::from_str(json).unwrap();
+///
+/// assert_eq!(response.channel(4), Some(80));
+/// assert_eq!(response.channel(6), None);
+/// assert_eq!(response.channels(), [100, 100, 0, 80, 0]);
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelResponse {
+    /// Channel levels, first channel first.
+    #[serde(rename = "Channel")]
+    channels: Vec<u8>,
+
+    /// Dimmer level, if included.
+    #[serde(rename = "Dimmer", default)]
+    dimmer: Option<u8>,
+
+    /// Power state, if included.
+    #[serde(rename = "POWER", default)]
+    power: Option<String>,
+}
+
+impl ChannelResponse {
+    /// Returns the level (0-100) of channel `n`, counting from 1.
+    ///
+    /// Returns `None` if the light has fewer channels.
+    #[must_use]
+    pub fn channel(&self, n: u8) -> Option<u8> {
+        let index = usize::from(n).checked_sub(1)?;
+        self.channels.get(index).copied()
+    }
+
+    /// Returns the levels of all channels.
+    #[must_use]
+    pub fn channels(&self) -> &[u8] {
+        &self.channels
+    }
+
+    /// Returns the dimmer level if included in the response.
+    #[must_use]
+    pub fn dimmer(&self) -> Option<u8> {
+        self.dimmer
+    }
+
+    /// Returns the power state if included in the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if the power state string is invalid.
+    pub fn power_state(&self) -> Result<Option<PowerState>, ParseError> {
+        match &self.power {
+            Some(s) => s
+                .parse::<PowerState>()
+                .map(Some)
+                .map_err(|_| ParseError::InvalidValue {
+                    field: "POWER".to_string(),
+                    message: format!("invalid power state: {s}"),
+                }),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_all_channels() {
+        let json = r#"{"POWER":"ON","Dimmer":80,"Color":"CCCC00CC00","HSBColor":"60,100,80",
+            "White":80,"CT":153,"Channel":[80,80,0,80,0]}"#;
+        let response: ChannelResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(response.channels().len(), 5);
+        assert_eq!(response.channel(1), Some(80));
+        assert_eq!(response.channel(3), Some(0));
+        assert_eq!(response.channel(4), Some(80));
+        assert_eq!(response.dimmer(), Some(80));
+        assert_eq!(response.power_state().unwrap(), Some(PowerState::On));
+    }
+
+    #[test]
+    fn channels_outside_the_array_are_none() {
+        let response: ChannelResponse = serde_json::from_str(r#"{"Channel":[40,60]}"#).unwrap();
+
+        assert_eq!(response.channel(0), None);
+        assert_eq!(response.channel(2), Some(60));
+        assert_eq!(response.channel(3), None);
+        assert_eq!(response.dimmer(), None);
+    }
+
+    #[test]
+    fn missing_channel_array_fails() {
+        assert!(serde_json::from_str::<ChannelResponse>(r#"{"Dimmer":50}"#).is_err());
+    }
+}
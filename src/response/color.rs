@@ -3,12 +3,12 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-//! Color response parsing for HSB color and color temperature.
+//! Color response parsing for HSB color, RGBWW color and color temperature.
 
 use serde::Deserialize;
 
 use crate::error::ParseError;
-use crate::types::{ColorTemperature, HsbColor, PowerState};
+use crate::types::{ColorTemperature, HsbColor, LightType, PowerState, RgbwwColor};
 
 /// Response from an `HSBColor` command.
 ///
@@ -171,6 +171,172 @@ impl HsbColorResponse {
     }
 }
 
+/// Response from a `Color` command.
+///
+/// Tasmota reports all light channels as one hex string, red, green and
+/// blue first, then cold and warm white:
+/// - `{"Color": "FF00000080"}` for a 5-channel light
+/// - `{"Color": "FF00000080", "Dimmer": 100, "POWER": "ON"}` with state
+///
+/// Lights with fewer channels report a shorter string, which
+/// [`rgbww_color`](Self::rgbww_color) rejects.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::RgbwwColorResponse;
+///
+/// let json = r#"{"Color": "FF00000080", "POWER": "ON"}"#;
+/// let response: RgbwwColorResponse = serde_json::from_str(json).unwrap();
+/// let color = response.rgbww_color().unwrap();
+/// assert_eq!(color.red(), 255);
+/// assert_eq!(color.warm_white(), 128);
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct RgbwwColorResponse {
+    /// The channels as a hex string (e.g., "FF00000080").
+    #[serde(rename = "Color", default)]
+    color: String,
+
+    /// Optional HSB color, reported by lights with RGB channels.
+    #[serde(rename = "HSBColor", default)]
+    hsb_color: Option<String>,
+
+    /// Optional color temperature, reported by lights with tunable white.
+    #[serde(rename = "CT", default)]
+    ct: Option<u16>,
+
+    /// Optional per-channel levels (0-100).
+    #[serde(rename = "Channel", default)]
+    channels: Vec<u8>,
+
+    /// Optional dimmer level included in the response.
+    #[serde(rename = "Dimmer", default)]
+    dimmer: Option<u8>,
+
+    /// Optional power state included in the response.
+    #[serde(rename = "POWER", default)]
+    power: Option<String>,
+}
+
+impl RgbwwColorResponse {
+    /// Returns the reported color as an [`RgbwwColor`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if the light did not report exactly five
+    /// channels.
+    pub fn rgbww_color(&self) -> Result<RgbwwColor, ParseError> {
+        RgbwwColor::from_hex(&self.color).map_err(|e| ParseError::InvalidValue {
+            field: "Color".to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Returns the raw color string.
+    #[must_use]
+    pub fn raw(&self) -> &str {
+        &self.color
+    }
+
+    /// Returns the dimmer level if included in the response.
+    #[must_use]
+    pub fn dimmer(&self) -> Option<u8> {
+        self.dimmer
+    }
+
+    /// Returns the power state if included in the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if the power state string is invalid.
+    pub fn power_state(&self) -> Result<Option<PowerState>, ParseError> {
+        match &self.power {
+            Some(s) => s
+                .parse::<PowerState>()
+                .map(Some)
+                .map_err(|_| ParseError::InvalidValue {
+                    field: "POWER".to_string(),
+                    message: format!("invalid power state: {s}"),
+                }),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns `true` if the device is on according to the response.
+    ///
+    /// Returns `None` if power state was not included in the response.
+    #[must_use]
+    pub fn is_on(&self) -> Option<bool> {
+        self.power.as_ref().map(|s| s == "ON")
+    }
+
+    /// Infers the kind of fixture from the shape of the response.
+    ///
+    /// The channel count comes from the `Channel` array, or else from the
+    /// length of `Color`. Without either, the presence of `HSBColor` and
+    /// `CT` decides. Returns [`LightType::Unknown`] if the fields
+    /// contradict each other (e.g. `HSBColor` on a two-channel light) or
+    /// reveal nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tasmor_lib::response::RgbwwColorResponse;
+    /// use tasmor_lib::types::LightType;
+    ///
+    /// let json = r#"{"Color": "FF8000", "HSBColor": "30,100,100", "Channel": [100, 50, 0]}"#;
+    /// let response: RgbwwColorResponse = serde_json::from_str(json).unwrap();
+    /// assert_eq!(response.light_type(), LightType::Rgb);
+    /// ```
+    #[must_use]
+    pub fn light_type(&self) -> LightType {
+        let has_rgb = self.hsb_color.is_some();
+        let has_ct = self.ct.is_some();
+
+        let channel_count = if self.channels.is_empty() {
+            color_channel_count(&self.color)
+        } else {
+            Some(self.channels.len())
+        };
+
+        match channel_count.map(LightType::from_channel_count) {
+            Some(light) => {
+                // RGBW lights may emulate CT (SetOption106)
+                let ct_ok = light.has_color_temperature() || light == LightType::Rgbw;
+                if (has_rgb && !light.has_rgb()) || (has_ct && !ct_ok) {
+                    LightType::Unknown
+                } else {
+                    light
+                }
+            }
+            None => match (has_rgb, has_ct) {
+                (true, true) => LightType::Rgbcct,
+                (true, false) => LightType::Rgb,
+                (false, true) => LightType::Cct,
+                (false, false) => LightType::Unknown,
+            },
+        }
+    }
+}
+
+/// Returns the number of channels in a `Color` value.
+///
+/// Tasmota reports `Color` as hex (two digits per channel) or, with
+/// `SetOption17`, as comma-separated decimals.
+fn color_channel_count(color: &str) -> Option<usize> {
+    let color = color.trim();
+    if color.is_empty() {
+        None
+    } else if color.contains(',') {
+        Some(color.split(',').count())
+    } else if color.len().is_multiple_of(2) && color.bytes().all(|b| b.is_ascii_hexdigit()) {
+        Some(color.len() / 2)
+    } else {
+        None
+    }
+}
+
 /// Response from a `CT` (Color Temperature) command.
 ///
 /// Tasmota returns color temperature in JSON format like:
@@ -350,6 +516,33 @@ mod tests {
         assert!(response.as_tuple().is_err());
     }
 
+    // ========================================================================
+    // RgbwwColorResponse tests
+    // ========================================================================
+
+    #[test]
+    fn parse_rgbww_color_with_state() {
+        let json =
+            r#"{"POWER": "ON", "Dimmer": 100, "Color": "FF00000080", "HSBColor": "0,100,100"}"#;
+        let response: RgbwwColorResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            response.rgbww_color().unwrap(),
+            RgbwwColor::new(255, 0, 0, 0, 128)
+        );
+        assert_eq!(response.dimmer(), Some(100));
+        assert_eq!(response.is_on(), Some(true));
+    }
+
+    #[test]
+    fn parse_rgbww_color_rejects_rgb_only() {
+        let json = r#"{"Color": "FF0000"}"#;
+        let response: RgbwwColorResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(response.raw(), "FF0000");
+        assert!(response.rgbww_color().is_err());
+    }
+
     // ========================================================================
     // ColorTemperatureResponse tests
     // ========================================================================
@@ -412,4 +605,46 @@ mod tests {
         assert_eq!(response.color_temperature(), 327);
         assert_eq!(response.power_state().unwrap().unwrap(), PowerState::On);
     }
+
+    #[test]
+    fn light_type_from_channels() {
+        let json = r#"{"POWER": "ON", "Dimmer": 100, "Color": "FF00000000",
+            "HSBColor": "0,100,100", "Channel": [100, 0, 0, 0, 0], "CT": 153}"#;
+        let response: RgbwwColorResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.light_type(), LightType::Rgbcct);
+
+        let json = r#"{"Color": "FF80", "CT": 300, "Channel": [100, 50]}"#;
+        let response: RgbwwColorResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.light_type(), LightType::Cct);
+    }
+
+    #[test]
+    fn light_type_from_color_string() {
+        let json = r#"{"Color": "255,0,0,128"}"#;
+        let response: RgbwwColorResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.light_type(), LightType::Rgbw);
+
+        let json = r#"{"Color": "80"}"#;
+        let response: RgbwwColorResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.light_type(), LightType::Single);
+    }
+
+    #[test]
+    fn light_type_from_fields() {
+        let json = r#"{"HSBColor": "0,100,100", "CT": 250}"#;
+        let response: RgbwwColorResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.light_type(), LightType::Rgbcct);
+    }
+
+    #[test]
+    fn light_type_ambiguous() {
+        // HSBColor on a two-channel light contradicts the channel count
+        let json = r#"{"Color": "FF80", "HSBColor": "0,100,100"}"#;
+        let response: RgbwwColorResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.light_type(), LightType::Unknown);
+
+        let json = r#"{"POWER": "ON"}"#;
+        let response: RgbwwColorResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.light_type(), LightType::Unknown);
+    }
 }
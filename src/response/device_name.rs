@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Device name response parsing.
+
+use serde::Deserialize;
+
+/// Response from a `DeviceName` command.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::DeviceNameResponse;
+///
+/// let json = r#"{"DeviceName":"Kitchen Hub"}"#;
+/// let response: DeviceNameResponse = serde_json::from_str(json).unwrap();
+/// assert_eq!(response.device_name(), "Kitchen Hub");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DeviceNameResponse {
+    #[serde(rename = "DeviceName")]
+    device_name: String,
+}
+
+impl DeviceNameResponse {
+    /// Returns the device name.
+    #[must_use]
+    pub fn device_name(&self) -> &str {
+        &self.device_name
+    }
+}
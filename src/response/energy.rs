@@ -107,6 +107,30 @@ impl EnergyResponse {
     pub fn frequency(&self) -> Option<f32> {
         self.energy().and_then(|e| e.frequency)
     }
+
+    /// Returns the power of phase `n` in Watts, counting from 1.
+    ///
+    /// On single-phase devices only phase 1 is available.
+    #[must_use]
+    pub fn power_phase(&self, n: u8) -> Option<f32> {
+        self.energy().and_then(|e| e.power_phase(n))
+    }
+
+    /// Returns the voltage of phase `n` in Volts, counting from 1.
+    ///
+    /// On single-phase devices only phase 1 is available.
+    #[must_use]
+    pub fn voltage_phase(&self, n: u8) -> Option<f32> {
+        self.energy().and_then(|e| e.voltage_phase(n))
+    }
+
+    /// Returns the current of phase `n` in Amperes, counting from 1.
+    ///
+    /// On single-phase devices only phase 1 is available.
+    #[must_use]
+    pub fn current_phase(&self, n: u8) -> Option<f32> {
+        self.energy().and_then(|e| e.current_phase(n))
+    }
 }
 
 /// Sensor status wrapper containing energy and other sensor data.
@@ -123,52 +147,58 @@ pub struct SensorStatus {
 }
 
 /// Energy monitoring data.
+///
+/// Multi-phase meters report `Power`, `Voltage`, `Current` and `Factor` (and
+/// usually `ApparentPower`, `ReactivePower` and `Frequency`) as one value per
+/// phase, e.g. `"Voltage":[230,231,229]`. The scalar fields then hold phase 1
+/// and the `*_phases` fields hold every phase.
 #[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(from = "RawEnergyData")]
 pub struct EnergyData {
     /// Start time for total energy counting.
-    #[serde(default)]
     pub total_start_time: Option<TasmotaDateTime>,
 
     /// Total energy consumed in kWh.
-    #[serde(default)]
     pub total: f32,
 
     /// Yesterday's energy consumption in kWh.
-    #[serde(default)]
     pub yesterday: f32,
 
     /// Today's energy consumption in kWh.
-    #[serde(default)]
     pub today: f32,
 
     /// Current power consumption in Watts.
-    #[serde(default)]
     pub power: f32,
 
     /// Apparent power in `VA`.
-    #[serde(default)]
     pub apparent_power: f32,
 
     /// Reactive power in `VAr`.
-    #[serde(default)]
     pub reactive_power: f32,
 
     /// Power factor (0-1).
-    #[serde(default)]
     pub factor: f32,
 
     /// Voltage in Volts.
-    #[serde(default)]
     pub voltage: f32,
 
     /// Current in Amperes.
-    #[serde(default)]
     pub current: f32,
 
     /// AC frequency in Hz. `None` for DC monitors or devices that do not report it.
-    #[serde(default)]
     pub frequency: Option<f32>,
+
+    /// Power of each phase in Watts. Empty on single-phase devices.
+    pub power_phases: Vec<f32>,
+
+    /// Power factor of each phase. Empty on single-phase devices.
+    pub factor_phases: Vec<f32>,
+
+    /// Voltage of each phase in Volts. Empty on single-phase devices.
+    pub voltage_phases: Vec<f32>,
+
+    /// Current of each phase in Amperes. Empty on single-phase devices.
+    pub current_phases: Vec<f32>,
 }
 
 impl EnergyData {
@@ -190,6 +220,135 @@ impl EnergyData {
         let kwh_per_day = self.power * 24.0 / 1000.0;
         kwh_per_day * price_per_kwh
     }
+
+    /// Returns the number of phases reported, 1 for single-phase devices.
+    #[must_use]
+    pub fn phase_count(&self) -> usize {
+        [
+            &self.power_phases,
+            &self.factor_phases,
+            &self.voltage_phases,
+            &self.current_phases,
+        ]
+        .into_iter()
+        .map(Vec::len)
+        .max()
+        .unwrap_or_default()
+        .max(1)
+    }
+
+    /// Returns the power of phase `n` in Watts, counting from 1.
+    #[must_use]
+    pub fn power_phase(&self, n: u8) -> Option<f32> {
+        nth_phase(&self.power_phases, Some(self.power), n)
+    }
+
+    /// Returns the power factor of phase `n`, counting from 1.
+    #[must_use]
+    pub fn factor_phase(&self, n: u8) -> Option<f32> {
+        nth_phase(&self.factor_phases, Some(self.factor), n)
+    }
+
+    /// Returns the voltage of phase `n` in Volts, counting from 1.
+    #[must_use]
+    pub fn voltage_phase(&self, n: u8) -> Option<f32> {
+        nth_phase(&self.voltage_phases, Some(self.voltage), n)
+    }
+
+    /// Returns the current of phase `n` in Amperes, counting from 1.
+    #[must_use]
+    pub fn current_phase(&self, n: u8) -> Option<f32> {
+        nth_phase(&self.current_phases, Some(self.current), n)
+    }
+}
+
+/// `ENERGY` fields as reported, before splitting per-phase values.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawEnergyData {
+    #[serde(default)]
+    total_start_time: Option<TasmotaDateTime>,
+    #[serde(default)]
+    total: f32,
+    #[serde(default)]
+    yesterday: f32,
+    #[serde(default)]
+    today: f32,
+    #[serde(default)]
+    power: Option<Phased>,
+    #[serde(default)]
+    apparent_power: Option<Phased>,
+    #[serde(default)]
+    reactive_power: Option<Phased>,
+    #[serde(default)]
+    factor: Option<Phased>,
+    #[serde(default)]
+    voltage: Option<Phased>,
+    #[serde(default)]
+    current: Option<Phased>,
+    #[serde(default)]
+    frequency: Option<Phased>,
+}
+
+impl From<RawEnergyData> for EnergyData {
+    fn from(raw: RawEnergyData) -> Self {
+        let first = |value: Option<&Phased>| value.and_then(Phased::first);
+        Self {
+            total_start_time: raw.total_start_time,
+            total: raw.total,
+            yesterday: raw.yesterday,
+            today: raw.today,
+            power: first(raw.power.as_ref()).unwrap_or_default(),
+            apparent_power: first(raw.apparent_power.as_ref()).unwrap_or_default(),
+            reactive_power: first(raw.reactive_power.as_ref()).unwrap_or_default(),
+            factor: first(raw.factor.as_ref()).unwrap_or_default(),
+            voltage: first(raw.voltage.as_ref()).unwrap_or_default(),
+            current: first(raw.current.as_ref()).unwrap_or_default(),
+            frequency: first(raw.frequency.as_ref()),
+            power_phases: Phased::into_phases(raw.power),
+            factor_phases: Phased::into_phases(raw.factor),
+            voltage_phases: Phased::into_phases(raw.voltage),
+            current_phases: Phased::into_phases(raw.current),
+        }
+    }
+}
+
+/// A measurement reported once, or once per phase by multi-phase meters.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Phased {
+    /// A single value (single-phase device).
+    Single(f32),
+    /// One value per phase, phase 1 first.
+    PerPhase(Vec<f32>),
+}
+
+impl Phased {
+    /// Returns the single value, or the value of phase 1.
+    pub(crate) fn first(&self) -> Option<f32> {
+        match self {
+            Self::Single(value) => Some(*value),
+            Self::PerPhase(values) => values.first().copied(),
+        }
+    }
+
+    /// Returns the per-phase values, empty unless reported per phase.
+    pub(crate) fn into_phases(value: Option<Self>) -> Vec<f32> {
+        match value {
+            Some(Self::PerPhase(values)) => values,
+            Some(Self::Single(_)) | None => Vec::new(),
+        }
+    }
+}
+
+/// Returns the value of phase `n` (from 1), falling back to `single` for
+/// phase 1 when no per-phase values were reported.
+pub(crate) fn nth_phase(phases: &[f32], single: Option<f32>, n: u8) -> Option<f32> {
+    if phases.is_empty() {
+        single.filter(|_| n == 1)
+    } else {
+        phases.get(usize::from(n).checked_sub(1)?).copied()
+    }
 }
 
 #[cfg(test)]
@@ -272,6 +431,10 @@ mod tests {
             voltage: 230.0,
             current: 0.435,
             frequency: None,
+            power_phases: Vec::new(),
+            factor_phases: Vec::new(),
+            voltage_phases: Vec::new(),
+            current_phases: Vec::new(),
         };
 
         assert!(energy.is_consuming());
@@ -297,6 +460,10 @@ mod tests {
             voltage: 230.004,
             current: 0.435,
             frequency: None,
+            power_phases: Vec::new(),
+            factor_phases: Vec::new(),
+            voltage_phases: Vec::new(),
+            current_phases: Vec::new(),
         };
 
         assert!(energy.is_consuming());
@@ -417,6 +584,75 @@ mod tests {
         assert_abs_diff_eq!(energy.frequency.unwrap(), 50.1, epsilon = 0.01);
     }
 
+    #[test]
+    fn parse_single_phase_has_one_phase() {
+        let json = r#"{
+            "StatusSNS": {
+                "Time": "2024-01-01T12:00:00",
+                "ENERGY": {
+                    "TotalStartTime": "2023-01-01T00:00:00",
+                    "Total": 123.456,
+                    "Yesterday": 1.234,
+                    "Today": 0.567,
+                    "Power": 45,
+                    "Voltage": 230,
+                    "Current": 0.196
+                }
+            }
+        }"#;
+
+        let response: EnergyResponse = serde_json::from_str(json).unwrap();
+        let energy = response.energy().unwrap();
+
+        assert_eq!(energy.phase_count(), 1);
+        assert_eq!(response.voltage_phase(1), Some(230.0));
+        assert_eq!(response.voltage_phase(2), None);
+        assert_eq!(response.power_phase(1), Some(45.0));
+        assert!(energy.voltage_phases.is_empty());
+    }
+
+    #[test]
+    fn parse_three_phase_energy() {
+        let json = r#"{
+            "StatusSNS": {
+                "Time": "2024-01-01T12:00:00",
+                "ENERGY": {
+                    "TotalStartTime": "2023-06-01T10:00:00",
+                    "Total": 3105.628,
+                    "Yesterday": 12.345,
+                    "Today": 4.321,
+                    "Power": [512, 230, 1024],
+                    "ApparentPower": [540, 250, 1060],
+                    "ReactivePower": [171, 98, 275],
+                    "Factor": [0.95, 0.92, 0.97],
+                    "Frequency": 50.02,
+                    "Voltage": [230, 231, 229],
+                    "Current": [2.348, 1.082, 4.629]
+                }
+            }
+        }"#;
+
+        let response: EnergyResponse = serde_json::from_str(json).unwrap();
+        let energy = response.energy().unwrap();
+
+        assert_eq!(energy.phase_count(), 3);
+        assert_eq!(energy.voltage_phases, [230.0, 231.0, 229.0]);
+        assert_eq!(response.voltage_phase(2), Some(231.0));
+        assert_eq!(response.voltage_phase(3), Some(229.0));
+        assert_eq!(response.voltage_phase(4), None);
+        assert_eq!(response.voltage_phase(0), None);
+        assert_abs_diff_eq!(response.current_phase(3).unwrap(), 4.629, epsilon = 0.001);
+        assert_eq!(response.power_phase(3), Some(1024.0));
+        assert_abs_diff_eq!(energy.factor_phase(2).unwrap(), 0.92, epsilon = 0.001);
+
+        // Scalar accessors keep reporting phase 1
+        assert_eq!(response.voltage(), Some(230.0));
+        assert_eq!(response.power(), Some(512.0));
+        assert_abs_diff_eq!(energy.apparent_power, 540.0, epsilon = f32::EPSILON);
+        assert_abs_diff_eq!(response.frequency().unwrap(), 50.02, epsilon = 0.001);
+        assert_abs_diff_eq!(response.total_energy().unwrap(), 3105.628, epsilon = 0.01);
+    }
+
     #[test]
     fn parse_energy_without_frequency_yields_none() {
         let json = r#"{
@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Rule event response parsing.
+
+use serde::Deserialize;
+
+/// Response from an `Event` command.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::EventResponse;
+///
+/// let response: EventResponse = serde_json::from_str(r#"{"Event":"Done"}"#).unwrap();
+/// assert!(response.is_done());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EventResponse {
+    #[serde(rename = "Event")]
+    status: String,
+}
+
+impl EventResponse {
+    /// Returns the status reported by the device.
+    #[must_use]
+    pub fn status(&self) -> &str {
+        &self.status
+    }
+
+    /// Returns `true` if the device accepted the event.
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        self.status.eq_ignore_ascii_case("Done")
+    }
+}
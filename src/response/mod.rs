@@ -14,10 +14,17 @@
 //! | Response Type | Tasmota Commands | Description |
 //! |--------------|------------------|-------------|
 //! | [`PowerResponse`] | `Power`, `Power1`-`Power8` | Relay on/off state |
+//! | [`PulseTimeResponse`] | `PulseTime1`-`PulseTime8` | Relay auto-off timer |
+//! | [`TimerResponse`] | `Timer1`-`Timer16` | Scheduled relay timer |
+//! | [`ShutterResponse`] | `ShutterPosition1`-`ShutterPosition16` | Shutter position and direction |
+//! | [`PwmResponse`] | `PWM1`-`PWM16` | Raw PWM duty cycles |
 //! | [`DimmerResponse`] | `Dimmer` | Brightness level (0-100) |
 //! | [`HsbColorResponse`] | `HSBColor` | Color in HSB format |
 //! | [`RgbColorResponse`] | `HSBColor` (via RGB) | Color in RGB format |
+//! | [`RgbwwColorResponse`] | `Color` | All channels of an RGBCCT light |
+//! | [`ChannelResponse`] | `Channel1`-`Channel5` | Level of each light channel |
 //! | [`ColorTemperatureResponse`] | `CT` | White color temperature |
+//! | [`WhiteResponse`] | `White` | White channel brightness (0-100) |
 //! | [`SchemeResponse`] | `Scheme` | Light scheme/effect (0-4) |
 //! | [`WakeupDurationResponse`] | `WakeupDuration` | Wakeup effect duration |
 //! | [`FadeResponse`] | `Fade` | Fade transition enable/disable |
@@ -25,6 +32,22 @@
 //! | [`StartupFadeResponse`] | `SetOption91` | Fade at startup setting |
 //! | [`EnergyResponse`] | `Status 10` | Power consumption data |
 //! | [`StatusResponse`] | `Status 0` | Full device status |
+//! | [`DeviceReport`] | `Status 0` (+ targeted `Status`) | Device overview |
+//! | [`SetOptionFlags`] | `Status 3` | All `SetOption` values |
+//! | [`SetOptionResponse`] | `SetOption<n>` | One `SetOption` value |
+//! | [`SaveDataResponse`] | `SaveData` | Settings save interval |
+//! | [`ModuleResponse`] | `Module` | Active hardware module |
+//! | [`ModuleListResponse`] | `Modules` | Supported hardware modules |
+//! | [`I2cScanResponse`] | `I2CScan` | Devices on the I2C bus |
+//! | [`DeviceTemplate`] | `Template` | GPIO template |
+//! | [`GpioResponse`] | `Gpio`, `Gpio0`-`Gpio48` | Component assigned to each GPIO pin |
+//! | [`WebColorResponse`] | `WebColor` | Web UI color theme |
+//! | [`SecurityResponse`] | `WebPassword`, `MqttPassword`, `CORS` | Security settings confirmation |
+//! | [`MqttConfigResponse`] | `MqttHost`, `MqttPort`, `SetOption3` | MQTT connection settings |
+//! | [`TopicResponse`] | `FullTopic`, `Prefix1`-`Prefix3`, `GroupTopic`, `ButtonTopic`, `SwitchTopic` | MQTT topic layout |
+//! | [`TuyaMcuResponse`] | `TuyaMCU` | Tuya data point to function mappings |
+//! | [`EventResponse`] | `Event` | Rule event confirmation |
+//! | [`NtpServerResponse`] | `NtpServer1`-`NtpServer3` | Configured NTP servers |
 //!
 //! # Usage Pattern
 //!
@@ -43,25 +66,65 @@
 //! println!("Power is: {}", state);  // "ON"
 //! ```
 
+pub(crate) mod case_insensitive;
+mod channel;
 mod color;
+mod device_name;
 mod dimmer;
 mod energy;
+mod event;
 mod fade;
+mod module;
+mod mqtt;
 mod power;
+mod pulse_time;
+mod pwm;
+mod report;
 mod rgb_color;
 mod routine;
+mod save_data;
 mod scheme;
+mod security;
+mod set_option;
+mod shutter;
 mod status;
+mod time;
+mod timer;
+mod topic;
+mod tuya;
+mod web;
+mod white;
 
-pub use color::{ColorTemperatureResponse, HsbColorResponse};
+pub use channel::ChannelResponse;
+pub use color::{ColorTemperatureResponse, HsbColorResponse, RgbwwColorResponse};
+pub use device_name::DeviceNameResponse;
 pub use dimmer::DimmerResponse;
 pub use energy::EnergyResponse;
+pub(crate) use energy::{Phased, nth_phase};
+pub use event::EventResponse;
 pub use fade::{FadeDurationResponse, FadeResponse, StartupFadeResponse};
+pub use module::{
+    DeviceTemplate, GpioPin, GpioResponse, I2cScanResponse, ModuleListResponse, ModuleResponse,
+};
+pub use mqtt::MqttConfigResponse;
 pub use power::PowerResponse;
+pub use pulse_time::PulseTimeResponse;
+pub use pwm::PwmResponse;
+pub use report::DeviceReport;
 pub use rgb_color::RgbColorResponse;
 pub use routine::RoutineResponse;
+pub use save_data::SaveDataResponse;
 pub use scheme::{SchemeResponse, WakeupDurationResponse};
+pub use security::SecurityResponse;
+pub use set_option::{SetOptionFlags, SetOptionResponse};
+pub use shutter::{ShutterDirection, ShutterResponse};
 pub use status::{
-    StatusDeviceParameters, StatusFirmware, StatusMemory, StatusMqtt, StatusNetwork,
+    CrashInfo, StatusDeviceParameters, StatusFirmware, StatusMemory, StatusMqtt, StatusNetwork,
     StatusParameters, StatusResponse,
 };
+pub use time::{NtpConfig, NtpServerResponse};
+pub use timer::TimerResponse;
+pub use topic::TopicResponse;
+pub use tuya::{TuyaMapping, TuyaMcuResponse};
+pub use web::WebColorResponse;
+pub use white::WhiteResponse;
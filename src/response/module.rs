@@ -0,0 +1,486 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Hardware module response parsing.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+
+use crate::error::ParseError;
+
+/// Response from a `Module` command.
+///
+/// Tasmota returns the active module as `{"Module":{"1":"Sonoff Basic"}}`.
+/// Older firmware uses the `{"Module":"1 (Sonoff Basic)"}` form, which is
+/// also accepted.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::ModuleResponse;
+///
+/// let json = r#"{"Module":{"1":"Sonoff Basic"}}"#;
+/// let response: ModuleResponse = serde_json::from_str(json).unwrap();
+/// assert_eq!(response.id().unwrap(), 1);
+/// assert_eq!(response.name(), Some("Sonoff Basic"));
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModuleResponse {
+    #[serde(rename = "Module")]
+    module: ModuleValue,
+}
+
+/// Helper enum to handle both module response formats.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum ModuleValue {
+    Map(BTreeMap<String, String>),
+    Text(String),
+}
+
+impl ModuleResponse {
+    /// Creates a response for a module change whose reply was lost because
+    /// the device restarted.
+    pub(crate) fn restarting(id: u16) -> Self {
+        Self {
+            module: ModuleValue::Text(id.to_string()),
+        }
+    }
+
+    /// Returns the module number.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if the module number cannot be parsed.
+    pub fn id(&self) -> Result<u16, ParseError> {
+        let raw = match &self.module {
+            ModuleValue::Map(map) => map.keys().next().map_or("", String::as_str),
+            ModuleValue::Text(text) => text.split(" (").next().unwrap_or_default(),
+        };
+        raw.trim().parse().map_err(|_| ParseError::InvalidValue {
+            field: "Module".to_string(),
+            message: format!("invalid module number: '{raw}'"),
+        })
+    }
+
+    /// Returns the module name, if reported.
+    ///
+    /// Returns `None` when the device restarted before replying to a module
+    /// change.
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        match &self.module {
+            ModuleValue::Map(map) => map.values().next().map(String::as_str),
+            ModuleValue::Text(text) => text
+                .split_once(" (")
+                .and_then(|(_, rest)| rest.strip_suffix(')')),
+        }
+    }
+}
+
+/// Response from a `Modules` command.
+///
+/// Tasmota lists the supported modules as `{"Modules":{"1":"Sonoff Basic",...}}`.
+/// Older firmware splits the list into `Modules1`, `Modules2`, ... arrays of
+/// `"1 (Sonoff Basic)"` strings; all parts present are merged.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::ModuleListResponse;
+///
+/// let json = r#"{"Modules":{"0":"Generic","1":"Sonoff Basic"}}"#;
+/// let response: ModuleListResponse = serde_json::from_str(json).unwrap();
+/// assert_eq!(response.name(1), Some("Sonoff Basic"));
+/// assert!(!response.contains(99));
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "HashMap<String, serde_json::Value>")]
+pub struct ModuleListResponse {
+    modules: BTreeMap<u16, String>,
+}
+
+impl From<HashMap<String, serde_json::Value>> for ModuleListResponse {
+    fn from(fields: HashMap<String, serde_json::Value>) -> Self {
+        let mut modules = BTreeMap::new();
+        let parts = fields
+            .into_iter()
+            .filter(|(key, _)| key.starts_with("Modules"))
+            .map(|(_, value)| value);
+
+        for part in parts {
+            match part {
+                serde_json::Value::Object(map) => {
+                    for (id, name) in map {
+                        if let (Ok(id), Some(name)) = (id.parse(), name.as_str()) {
+                            modules.insert(id, name.to_string());
+                        }
+                    }
+                }
+                serde_json::Value::Array(entries) => {
+                    for entry in entries.iter().filter_map(serde_json::Value::as_str) {
+                        if let Some((id, name)) = entry.split_once(" (")
+                            && let Ok(id) = id.trim().parse()
+                        {
+                            modules.insert(id, name.trim_end_matches(')').to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self { modules }
+    }
+}
+
+impl ModuleListResponse {
+    /// Returns the supported modules, ordered by module number.
+    #[must_use]
+    pub fn modules(&self) -> &BTreeMap<u16, String> {
+        &self.modules
+    }
+
+    /// Returns the name of a module, if supported.
+    #[must_use]
+    pub fn name(&self, id: u16) -> Option<&str> {
+        self.modules.get(&id).map(String::as_str)
+    }
+
+    /// Returns `true` if the firmware supports the module.
+    #[must_use]
+    pub fn contains(&self, id: u16) -> bool {
+        self.modules.contains_key(&id)
+    }
+}
+
+/// Response from a `Template` command.
+///
+/// The template describes the device's GPIO assignment:
+/// `{"NAME":"Sonoff Basic","GPIO":[32,1,1,1,1,1,0,0,1,1,1,224,1,320],"FLAG":0,"BASE":1}`.
+/// `BASE` is the module the template is derived from.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::DeviceTemplate;
+///
+/// let json = r#"{"NAME":"Athom Plug","GPIO":[0,0,0,32,2720,2656,0,0,2624,320,224,0,0,0],"FLAG":0,"BASE":18}"#;
+/// let template: DeviceTemplate = serde_json::from_str(json).unwrap();
+/// assert_eq!(template.name(), "Athom Plug");
+/// assert_eq!(template.base(), 18);
+/// assert!(template.has_gpio(2720));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct DeviceTemplate {
+    #[serde(rename = "NAME", default)]
+    name: String,
+    #[serde(rename = "GPIO", default)]
+    gpio: Vec<u16>,
+    #[serde(rename = "FLAG", default)]
+    flag: u32,
+    #[serde(rename = "BASE", default)]
+    base: u16,
+}
+
+impl DeviceTemplate {
+    /// Returns the template name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the component assigned to each configurable GPIO.
+    ///
+    /// Values are Tasmota component codes (e.g. 224 = `Relay1`); 0 means
+    /// unused.
+    #[must_use]
+    pub fn gpio(&self) -> &[u16] {
+        &self.gpio
+    }
+
+    /// Returns `true` if a GPIO is assigned the given component code.
+    #[must_use]
+    pub fn has_gpio(&self, component: u16) -> bool {
+        self.gpio.contains(&component)
+    }
+
+    /// Returns the template flags.
+    #[must_use]
+    pub fn flag(&self) -> u32 {
+        self.flag
+    }
+
+    /// Returns the module the template is based on.
+    #[must_use]
+    pub fn base(&self) -> u16 {
+        self.base
+    }
+}
+
+/// Response from a `Gpio` command.
+///
+/// Tasmota reports each pin with its component code and name:
+/// `{"GPIO0":{"0":"None"},"GPIO4":{"224":"Relay1"}}`. Older firmware uses the
+/// `{"GPIO4":"21 (Relay1)"}` form, which is also accepted.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::GpioResponse;
+///
+/// let json = r#"{"GPIO0":{"0":"None"},"GPIO4":{"224":"Relay1"}}"#;
+/// let response: GpioResponse = serde_json::from_str(json).unwrap();
+/// assert_eq!(response.pin(4).unwrap().name(), "Relay1");
+/// assert!(!response.pin(0).unwrap().is_used());
+/// assert_eq!(response.used_pins().count(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(from = "HashMap<String, serde_json::Value>")]
+pub struct GpioResponse {
+    pins: BTreeMap<u8, GpioPin>,
+}
+
+/// The component assigned to a GPIO pin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpioPin {
+    component: u16,
+    name: String,
+}
+
+impl GpioPin {
+    /// Returns the Tasmota component code (e.g. 224 = `Relay1`); 0 means
+    /// unused.
+    #[must_use]
+    pub fn component(&self) -> u16 {
+        self.component
+    }
+
+    /// Returns the component name as shown by Tasmota.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if a component is assigned to the pin.
+    #[must_use]
+    pub fn is_used(&self) -> bool {
+        self.component != 0
+    }
+}
+
+impl From<HashMap<String, serde_json::Value>> for GpioResponse {
+    fn from(fields: HashMap<String, serde_json::Value>) -> Self {
+        let mut pins = BTreeMap::new();
+        for (key, value) in fields {
+            let Some(pin) = key
+                .strip_prefix("GPIO")
+                .and_then(|pin| pin.parse::<u8>().ok())
+            else {
+                continue;
+            };
+            let assignment = match value {
+                serde_json::Value::Object(map) => map
+                    .into_iter()
+                    .next()
+                    .and_then(|(id, name)| Some((id.parse().ok()?, name.as_str()?.to_string()))),
+                serde_json::Value::String(text) => text.split_once(" (").and_then(|(id, name)| {
+                    Some((
+                        id.trim().parse().ok()?,
+                        name.trim_end_matches(')').to_string(),
+                    ))
+                }),
+                _ => None,
+            };
+            if let Some((component, name)) = assignment {
+                pins.insert(pin, GpioPin { component, name });
+            }
+        }
+        Self { pins }
+    }
+}
+
+impl GpioResponse {
+    /// Returns the reported pins, ordered by pin number.
+    #[must_use]
+    pub fn pins(&self) -> &BTreeMap<u8, GpioPin> {
+        &self.pins
+    }
+
+    /// Returns the component assigned to a pin, if reported.
+    #[must_use]
+    pub fn pin(&self, pin: u8) -> Option<&GpioPin> {
+        self.pins.get(&pin)
+    }
+
+    /// Returns the pins with a component assigned.
+    pub fn used_pins(&self) -> impl Iterator<Item = (u8, &GpioPin)> {
+        self.pins
+            .iter()
+            .filter(|(_, assignment)| assignment.is_used())
+            .map(|(&pin, assignment)| (pin, assignment))
+    }
+}
+
+/// Response from an `I2CScan` command.
+///
+/// Tasmota answers with a free-form line, e.g.
+/// `{"I2CScan":"Device(s) found at 0x40 0x76"}`,
+/// `{"I2CScan":"No devices found"}`, or `{"I2CScan":"Error 4 at 0x20"}`
+/// when the bus fails.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::I2cScanResponse;
+///
+/// let json = r#"{"I2CScan":"Device(s) found at 0x40 0x76"}"#;
+/// let response: I2cScanResponse = serde_json::from_str(json).unwrap();
+/// assert_eq!(response.addresses(), vec![0x40, 0x76]);
+/// assert_eq!(response.error(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct I2cScanResponse {
+    #[serde(rename = "I2CScan")]
+    scan: String,
+}
+
+impl I2cScanResponse {
+    /// Returns the scan result as reported by the device.
+    #[must_use]
+    pub fn raw(&self) -> &str {
+        &self.scan
+    }
+
+    /// Returns the addresses of the devices found, in reported order.
+    ///
+    /// Every `0x..` token that is a valid 7-bit address is taken, so the
+    /// wording of the line does not matter. Empty when no devices were
+    /// found or the scan failed.
+    #[must_use]
+    pub fn addresses(&self) -> Vec<u8> {
+        if self.error().is_some() {
+            return Vec::new();
+        }
+        self.scan
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter_map(|token| {
+                let hex = token
+                    .strip_prefix("0x")
+                    .or_else(|| token.strip_prefix("0X"))?;
+                u8::from_str_radix(hex, 16).ok()
+            })
+            .filter(|address| *address <= 0x7F)
+            .collect()
+    }
+
+    /// Returns the error line if the scan failed.
+    #[must_use]
+    pub fn error(&self) -> Option<&str> {
+        self.scan
+            .trim_start()
+            .get(..5)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("error"))
+            .map(|_| self.scan.trim())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_legacy_module_format() {
+        let json = r#"{"Module":"18 (Generic)"}"#;
+        let response: ModuleResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.id().unwrap(), 18);
+        assert_eq!(response.name(), Some("Generic"));
+    }
+
+    #[test]
+    fn restarting_response_has_no_name() {
+        let response = ModuleResponse::restarting(5);
+        assert_eq!(response.id().unwrap(), 5);
+        assert_eq!(response.name(), None);
+    }
+
+    #[test]
+    fn parse_legacy_split_module_list() {
+        let json = r#"{
+            "Modules1": ["1 (Sonoff Basic)", "2 (Sonoff RF)"],
+            "Modules2": ["18 (Generic)"]
+        }"#;
+        let response: ModuleListResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(response.modules().len(), 3);
+        assert_eq!(response.name(2), Some("Sonoff RF"));
+        assert_eq!(response.name(18), Some("Generic"));
+    }
+
+    #[test]
+    fn module_list_is_ordered() {
+        let json = r#"{"Modules":{"18":"Generic","0":"Template","1":"Sonoff Basic"}}"#;
+        let response: ModuleListResponse = serde_json::from_str(json).unwrap();
+
+        let ids: Vec<u16> = response.modules().keys().copied().collect();
+        assert_eq!(ids, vec![0, 1, 18]);
+    }
+
+    #[test]
+    fn parse_template_with_missing_fields() {
+        let template: DeviceTemplate = serde_json::from_str(r#"{"NAME":"Custom"}"#).unwrap();
+        assert_eq!(template.name(), "Custom");
+        assert!(template.gpio().is_empty());
+        assert_eq!(template.base(), 0);
+    }
+
+    #[test]
+    fn parse_gpio_list() {
+        let json = r#"{"GPIO0":{"0":"None"},"GPIO12":{"224":"Relay1"},"GPIO13":{"320":"Led_i1"}}"#;
+        let response: GpioResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(response.pins().len(), 3);
+        assert_eq!(response.pin(12).unwrap().component(), 224);
+        assert_eq!(
+            response.used_pins().map(|(pin, _)| pin).collect::<Vec<_>>(),
+            [12, 13]
+        );
+    }
+
+    #[test]
+    fn parse_legacy_gpio_format() {
+        let json = r#"{"GPIO14":"21 (Relay1)"}"#;
+        let response: GpioResponse = serde_json::from_str(json).unwrap();
+
+        let pin = response.pin(14).unwrap();
+        assert_eq!(pin.component(), 21);
+        assert_eq!(pin.name(), "Relay1");
+    }
+
+    #[test]
+    fn gpio_ignores_unrelated_fields() {
+        let response: GpioResponse = serde_json::from_str(r#"{"Command":"Unknown"}"#).unwrap();
+        assert!(response.pins().is_empty());
+    }
+
+    #[test]
+    fn parse_i2c_scan() {
+        let parse = |scan: &str| I2cScanResponse {
+            scan: scan.to_string(),
+        };
+
+        assert_eq!(parse("Device(s) found at 0x76").addresses(), [0x76]);
+        assert_eq!(
+            parse("Device(s) found on bus1 at 0x3c 0x76").addresses(),
+            [0x3C, 0x76]
+        );
+        assert!(parse("No devices found").addresses().is_empty());
+
+        let failed = parse("Error 4 at 0x20");
+        assert_eq!(failed.error(), Some("Error 4 at 0x20"));
+        assert!(failed.addresses().is_empty());
+    }
+}
@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! MQTT connection response parsing.
+
+use serde::Deserialize;
+
+/// Response from `MqttHost`, `MqttPort`, `MqttUser`, `MqttPassword` or
+/// `SetOption3` commands.
+///
+/// Only the fields for the commands that were sent are present.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::MqttConfigResponse;
+///
+/// let json = r#"{"MqttHost":"broker.lan","MqttPort":1883,"SetOption3":"ON"}"#;
+/// let response: MqttConfigResponse = serde_json::from_str(json).unwrap();
+/// assert_eq!(response.host(), Some("broker.lan"));
+/// assert_eq!(response.port(), Some(1883));
+/// assert_eq!(response.enabled(), Some(true));
+/// assert_eq!(response.user(), None);
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MqttConfigResponse {
+    #[serde(rename = "MqttHost")]
+    host: Option<String>,
+    #[serde(rename = "MqttPort")]
+    port: Option<u16>,
+    #[serde(rename = "MqttUser")]
+    user: Option<String>,
+    #[serde(rename = "MqttPassword")]
+    password: Option<String>,
+    #[serde(rename = "SetOption3")]
+    enabled: Option<String>,
+    #[serde(skip)]
+    unconfirmed: bool,
+}
+
+impl MqttConfigResponse {
+    /// Creates a response for a change whose reply was lost because the
+    /// device restarted.
+    #[cfg(feature = "http")]
+    pub(crate) fn unconfirmed() -> Self {
+        Self {
+            unconfirmed: true,
+            ..Self::default()
+        }
+    }
+
+    /// Returns whether the device confirmed the change.
+    ///
+    /// `false` when the device dropped the connection after receiving the
+    /// commands, before replying; all fields are then `None`.
+    #[must_use]
+    pub fn is_confirmed(&self) -> bool {
+        !self.unconfirmed
+    }
+
+    /// Returns the broker host.
+    #[must_use]
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// Returns the broker port.
+    #[must_use]
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    /// Returns the MQTT user name.
+    #[must_use]
+    pub fn user(&self) -> Option<&str> {
+        self.user.as_deref()
+    }
+
+    /// Returns the (masked) MQTT password confirmation.
+    #[must_use]
+    pub fn password(&self) -> Option<&str> {
+        self.password.as_deref()
+    }
+
+    /// Returns whether MQTT is enabled (`SetOption3`).
+    #[must_use]
+    pub fn enabled(&self) -> Option<bool> {
+        self.enabled.as_deref().map(|value| value == "ON")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_user_and_masked_password() {
+        let json = r#"{"MqttUser":"tasmota","MqttPassword":"****"}"#;
+        let response: MqttConfigResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.user(), Some("tasmota"));
+        assert_eq!(response.password(), Some("****"));
+        assert_eq!(response.host(), None);
+    }
+
+    #[test]
+    fn parsed_response_is_confirmed() {
+        let response: MqttConfigResponse = serde_json::from_str(r#"{"MqttPort":1883}"#).unwrap();
+        assert!(response.is_confirmed());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn unconfirmed_response_is_not_confirmed() {
+        assert!(!MqttConfigResponse::unconfirmed().is_confirmed());
+    }
+
+    #[test]
+    fn parse_disabled_mqtt() {
+        let json = r#"{"SetOption3":"OFF"}"#;
+        let response: MqttConfigResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.enabled(), Some(false));
+    }
+}
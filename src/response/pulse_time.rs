@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Relay pulse timer response parsing.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::command::decode_pulse_time;
+
+/// Response from a `PulseTime<x>` command.
+///
+/// Tasmota reports the configured time and the time left before the relay
+/// switches off, both in the encoded `PulseTime` format:
+///
+/// ```json
+/// {"PulseTime1":{"Set":1000,"Remaining":740}}
+/// ```
+///
+/// Older firmware reports only the configured value, as
+/// `{"PulseTime1":1000}`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use tasmor_lib::response::PulseTimeResponse;
+///
+/// let json = r#"{"PulseTime1":{"Set":1000,"Remaining":740}}"#;
+/// let response: PulseTimeResponse = serde_json::from_str(json).unwrap();
+///
+/// assert_eq!(response.index(), 1);
+/// assert_eq!(response.duration(), Some(Duration::from_secs(900)));
+/// assert_eq!(response.remaining(), Some(Duration::from_secs(640)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PulseTimeResponse {
+    index: u8,
+    set: u16,
+    remaining: Option<u16>,
+}
+
+impl PulseTimeResponse {
+    /// Returns the relay index the timer belongs to.
+    #[must_use]
+    pub const fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Returns the time the relay stays on, or `None` if the timer is
+    /// disabled.
+    #[must_use]
+    pub fn duration(&self) -> Option<Duration> {
+        (self.set != 0).then(|| decode_pulse_time(self.set))
+    }
+
+    /// Returns the time left before the relay switches off, if reported.
+    ///
+    /// Zero when no pulse is running.
+    #[must_use]
+    pub fn remaining(&self) -> Option<Duration> {
+        self.remaining.map(decode_pulse_time)
+    }
+
+    /// Returns the raw encoded `PulseTime` value.
+    #[must_use]
+    pub const fn raw(&self) -> u16 {
+        self.set
+    }
+}
+
+impl<'de> Deserialize<'de> for PulseTimeResponse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map = BTreeMap::<String, Value>::deserialize(deserializer)?;
+        let (index, value) = map
+            .iter()
+            .find_map(|(key, value)| {
+                let suffix = key
+                    .get(..9)
+                    .filter(|prefix| prefix.eq_ignore_ascii_case("PulseTime"))
+                    .map(|_| &key[9..])?;
+                // "PulseTime" without index is relay 1
+                let index = if suffix.is_empty() {
+                    1
+                } else {
+                    suffix.parse::<u8>().ok()?
+                };
+                Some((index, value))
+            })
+            .ok_or_else(|| de::Error::missing_field("PulseTime"))?;
+
+        let number = |value: Option<&Value>, field: &str| -> Result<u16, D::Error> {
+            value
+                .and_then(Value::as_u64)
+                .and_then(|n| u16::try_from(n).ok())
+                .ok_or_else(|| de::Error::custom(format!("PulseTime{index}: invalid {field}")))
+        };
+        let (set, remaining) = match value {
+            Value::Object(fields) => (
+                number(fields.get("Set"), "Set")?,
+                Some(number(fields.get("Remaining"), "Remaining")?),
+            ),
+            other => (number(Some(other), "value")?, None),
+        };
+        Ok(Self {
+            index,
+            set,
+            remaining,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_legacy_and_disabled() {
+        let legacy: PulseTimeResponse = serde_json::from_str(r#"{"PulseTime2":5}"#).unwrap();
+        assert_eq!(legacy.index(), 2);
+        assert_eq!(legacy.duration(), Some(Duration::from_millis(500)));
+        assert_eq!(legacy.remaining(), None);
+
+        let disabled: PulseTimeResponse =
+            serde_json::from_str(r#"{"PulseTime1":{"Set":0,"Remaining":0}}"#).unwrap();
+        assert_eq!(disabled.duration(), None);
+        assert_eq!(disabled.remaining(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn parse_rejects_other_commands() {
+        assert!(serde_json::from_str::<PulseTimeResponse>(r#"{"POWER":"ON"}"#).is_err());
+        assert!(serde_json::from_str::<PulseTimeResponse>(r#"{"PulseTime1":"x"}"#).is_err());
+    }
+}
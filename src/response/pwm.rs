@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Raw PWM output response parsing.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+
+/// Response from a `PWM<n>` command.
+///
+/// Tasmota reports the configured outputs either at the top level or, on
+/// most firmware, nested in a `PWM` object. Both forms are accepted:
+///
+/// ```
+/// use tasmor_lib::response::PwmResponse;
+///
+/// let response: PwmResponse = serde_json::from_str(r#"{"PWM1":512}"#).unwrap();
+/// assert_eq!(response.value(1), Some(512));
+///
+/// let json = r#"{"PWM":{"PWM1":512,"PWM2":0}}"#;
+/// let response: PwmResponse = serde_json::from_str(json).unwrap();
+/// assert_eq!(response.value(2), Some(0));
+/// assert_eq!(response.value(3), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(from = "HashMap<String, serde_json::Value>")]
+pub struct PwmResponse {
+    values: BTreeMap<u8, u16>,
+}
+
+impl PwmResponse {
+    /// Returns the duty cycle of an output.
+    #[must_use]
+    pub fn value(&self, channel: u8) -> Option<u16> {
+        self.values.get(&channel).copied()
+    }
+
+    /// Returns the reported outputs and their duty cycles, by index.
+    #[must_use]
+    pub fn values(&self) -> &BTreeMap<u8, u16> {
+        &self.values
+    }
+}
+
+impl From<HashMap<String, serde_json::Value>> for PwmResponse {
+    fn from(mut fields: HashMap<String, serde_json::Value>) -> Self {
+        if let Some(serde_json::Value::Object(nested)) = fields.remove("PWM") {
+            fields.extend(nested);
+        }
+
+        let values = fields
+            .into_iter()
+            .filter_map(|(key, value)| {
+                let channel = key.strip_prefix("PWM")?.parse::<u8>().ok()?;
+                let value = u16::try_from(value.as_u64()?).ok()?;
+                Some((channel, value))
+            })
+            .collect();
+        Self { values }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_unrelated_fields() {
+        let json = r#"{"PWM":{"PWM1":1023},"PWMRange":1023,"POWER":"ON"}"#;
+        let response: PwmResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.values(), &BTreeMap::from([(1, 1023)]));
+    }
+}
@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Combined device report.
+
+use crate::command::StatusCommand;
+use crate::response::{
+    StatusDeviceParameters, StatusFirmware, StatusMqtt, StatusNetwork, StatusParameters,
+    StatusResponse,
+};
+use crate::telemetry::{SensorData, TelemetryState};
+
+/// Everything about a device, assembled from its status sections.
+///
+/// Built by [`Device::full_report`](crate::Device::full_report) from a
+/// single `Status 0` where possible. Sections the device did not report are
+/// `None`, e.g. [`mqtt`](Self::mqtt) when MQTT is disabled.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::{DeviceReport, StatusResponse};
+///
+/// let json = r#"{
+///     "Status": {"Module": 18, "DeviceName": "Kitchen"},
+///     "StatusNET": {"Hostname": "kitchen", "IPAddress": "192.168.1.100"},
+///     "StatusSTS": {"POWER": "ON", "Dimmer": 60}
+/// }"#;
+/// let status: StatusResponse = serde_json::from_str(json).unwrap();
+/// let report = DeviceReport::from_status(status);
+///
+/// assert_eq!(report.device().unwrap().device_name, "Kitchen");
+/// assert_eq!(report.network().unwrap().ip_address, "192.168.1.100");
+/// assert_eq!(report.state().unwrap().dimmer(), Some(60));
+/// assert!(report.firmware().is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DeviceReport {
+    device: Option<StatusDeviceParameters>,
+    parameters: Option<StatusParameters>,
+    firmware: Option<StatusFirmware>,
+    network: Option<StatusNetwork>,
+    mqtt: Option<StatusMqtt>,
+    state: Option<TelemetryState>,
+    sensors: Option<SensorData>,
+}
+
+impl DeviceReport {
+    /// Builds a report from a status response.
+    #[must_use]
+    pub fn from_status(status: StatusResponse) -> Self {
+        let mut report = Self::default();
+        report.merge(status);
+        report
+    }
+
+    /// Fills the sections still missing from another status response.
+    pub(crate) fn merge(&mut self, status: StatusResponse) {
+        self.device = self.device.take().or(status.status);
+        self.parameters = self.parameters.take().or(status.status_prm);
+        self.firmware = self.firmware.take().or(status.firmware);
+        self.network = self.network.take().or(status.network);
+        self.mqtt = self.mqtt.take().or(status.mqtt);
+        if self.state.is_none() {
+            self.state = status
+                .sensor_status
+                .and_then(|value| serde_json::from_value(value).ok());
+        }
+        if self.sensors.is_none() {
+            self.sensors = status
+                .sensors
+                .and_then(|value| serde_json::from_value(value).ok());
+        }
+    }
+
+    /// Returns the targeted queries for the sections still missing.
+    ///
+    /// The `Status` section has no targeted query, it is only part of
+    /// `Status 0`.
+    pub(crate) fn missing_sections(&self) -> Vec<StatusCommand> {
+        [
+            (
+                self.parameters.is_none(),
+                StatusCommand::device_parameters(),
+            ),
+            (self.firmware.is_none(), StatusCommand::firmware()),
+            (self.network.is_none(), StatusCommand::network()),
+            (self.mqtt.is_none(), StatusCommand::mqtt()),
+            (self.sensors.is_none(), StatusCommand::sensors()),
+            (self.state.is_none(), StatusCommand::state()),
+        ]
+        .into_iter()
+        .filter_map(|(missing, cmd)| missing.then_some(cmd))
+        .collect()
+    }
+
+    /// Returns the device identity: module, names and topic.
+    #[must_use]
+    pub fn device(&self) -> Option<&StatusDeviceParameters> {
+        self.device.as_ref()
+    }
+
+    /// Returns the device parameters: uptime, boot count, restart reason.
+    #[must_use]
+    pub fn parameters(&self) -> Option<&StatusParameters> {
+        self.parameters.as_ref()
+    }
+
+    /// Returns the firmware information.
+    #[must_use]
+    pub fn firmware(&self) -> Option<&StatusFirmware> {
+        self.firmware.as_ref()
+    }
+
+    /// Returns the network information.
+    #[must_use]
+    pub fn network(&self) -> Option<&StatusNetwork> {
+        self.network.as_ref()
+    }
+
+    /// Returns the MQTT configuration.
+    #[must_use]
+    pub fn mqtt(&self) -> Option<&StatusMqtt> {
+        self.mqtt.as_ref()
+    }
+
+    /// Returns the current state: power, light and Wi-Fi.
+    #[must_use]
+    pub fn state(&self) -> Option<&TelemetryState> {
+        self.state.as_ref()
+    }
+
+    /// Returns the current sensor readings.
+    #[must_use]
+    pub fn sensors(&self) -> Option<&SensorData> {
+        self.sensors.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(json: &str) -> StatusResponse {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn missing_sections_are_queried() {
+        let report = DeviceReport::from_status(status(
+            r#"{"StatusFWR":{"Version":"14.2.0"},"StatusNET":{"Hostname":"plug"}}"#,
+        ));
+
+        let missing = report.missing_sections();
+        assert!(missing.contains(&StatusCommand::mqtt()));
+        assert!(missing.contains(&StatusCommand::state()));
+        assert!(!missing.contains(&StatusCommand::firmware()));
+        assert!(!missing.contains(&StatusCommand::network()));
+    }
+
+    #[test]
+    fn merge_keeps_existing_sections() {
+        let mut report = DeviceReport::from_status(status(r#"{"StatusNET":{"Hostname":"plug"}}"#));
+        report.merge(status(
+            r#"{"StatusNET":{"Hostname":"other"},"StatusMQT":{"MqttHost":"broker.lan"}}"#,
+        ));
+
+        assert_eq!(report.network().unwrap().hostname, "plug");
+        assert_eq!(report.mqtt().unwrap().host, "broker.lan");
+    }
+}
@@ -5,16 +5,29 @@
 
 //! RGB color response.
 //!
-//! This module provides a response type that wraps the HSB response from
-//! Tasmota and converts it back to RGB for user convenience.
+//! This module provides a response type that exposes a light color both as
+//! RGB and as HSB, whether Tasmota was sent an `HSBColor` or a `Color`
+//! command.
 
-use crate::types::{HsbColor, RgbColor};
+use serde::{Deserialize, Deserializer, de};
+
+use crate::types::{HsbColor, PowerState, RgbColor};
 
 /// Response from an RGB color command.
 ///
-/// When setting an RGB color, the library internally converts it to HSB
-/// and sends an `HSBColor` command to Tasmota. This response wraps the
-/// HSB response and provides both the RGB and HSB representations.
+/// [`Device::set_rgb_color`](crate::Device::set_rgb_color) converts the RGB
+/// color to HSB and sends an `HSBColor` command; this response then wraps
+/// the HSB reply. [`Device::set_color_hex`](crate::Device::set_color_hex)
+/// sends a `Color` command instead, and the response is deserialized from
+/// the `Color` echo:
+///
+/// ```json
+/// {"POWER":"ON","Dimmer":100,"Color":"FF5733","HSBColor":"11,80,100"}
+/// ```
+///
+/// Only the first three channels of `Color` are read, so RGBW and RGBCCT
+/// lights parse as well. When `HSBColor` is missing, the HSB value is
+/// computed from the RGB channels.
 ///
 /// # Examples
 ///
@@ -33,11 +46,19 @@ use crate::types::{HsbColor, RgbColor};
 /// // Red in HSB (0°, 100%, 100%) should be close to RGB (255, 0, 0)
 /// assert_eq!(rgb.red(), 255);
 /// assert_eq!(hsb.hue(), 0);
+///
+/// // Parse the echo of a `Color` command
+/// let json = r#"{"POWER": "ON", "Dimmer": 100, "Color": "FF5733"}"#;
+/// let response: RgbColorResponse = serde_json::from_str(json).unwrap();
+/// assert_eq!(response.rgb_color(), RgbColor::new(255, 87, 51));
+/// assert_eq!(response.dimmer(), Some(100));
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct RgbColorResponse {
     rgb_color: RgbColor,
     hsb_color: HsbColor,
+    dimmer: Option<u8>,
+    power: Option<PowerState>,
 }
 
 impl RgbColorResponse {
@@ -50,6 +71,8 @@ impl RgbColorResponse {
         Self {
             rgb_color: RgbColor::from_hsb(&hsb),
             hsb_color: hsb,
+            dimmer: None,
+            power: None,
         }
     }
 
@@ -62,6 +85,8 @@ impl RgbColorResponse {
         Self {
             rgb_color,
             hsb_color,
+            dimmer: None,
+            power: None,
         }
     }
 
@@ -77,6 +102,18 @@ impl RgbColorResponse {
         self.hsb_color
     }
 
+    /// Returns the dimmer level if included in the `Color` echo.
+    #[must_use]
+    pub const fn dimmer(&self) -> Option<u8> {
+        self.dimmer
+    }
+
+    /// Returns the power state if included in the `Color` echo.
+    #[must_use]
+    pub const fn power_state(&self) -> Option<PowerState> {
+        self.power
+    }
+
     /// Returns the red component (0-255).
     #[must_use]
     pub const fn red(&self) -> u8 {
@@ -130,6 +167,72 @@ impl RgbColorResponse {
     }
 }
 
+/// Fields of a `Color` echo, before conversion.
+#[derive(Deserialize)]
+struct ColorEcho {
+    #[serde(rename = "Color")]
+    color: String,
+    #[serde(rename = "HSBColor", default)]
+    hsb_color: Option<String>,
+    #[serde(rename = "Dimmer", default)]
+    dimmer: Option<u8>,
+    #[serde(rename = "POWER", default)]
+    power: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for RgbColorResponse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let echo = ColorEcho::deserialize(deserializer)?;
+
+        let rgb_color = parse_rgb_channels(&echo.color)
+            .ok_or_else(|| de::Error::custom(format!("invalid Color value: {}", echo.color)))?;
+        let hsb_color = echo
+            .hsb_color
+            .as_deref()
+            .and_then(parse_hsb)
+            .unwrap_or_else(|| rgb_color.to_hsb());
+        let power = echo
+            .power
+            .map(|s| {
+                s.parse::<PowerState>()
+                    .map_err(|_| de::Error::custom(format!("invalid power state: {s}")))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            rgb_color,
+            hsb_color,
+            dimmer: echo.dimmer,
+            power,
+        })
+    }
+}
+
+/// Reads the red, green and blue channels of a `Color` value.
+///
+/// Tasmota reports `Color` as hex (two digits per channel) or, with
+/// `SetOption17`, as comma-separated decimals. Extra white channels are
+/// ignored.
+fn parse_rgb_channels(color: &str) -> Option<RgbColor> {
+    let color = color.trim();
+    if color.contains(',') {
+        let mut channels = color.split(',').map(|c| c.trim().parse::<u8>().ok());
+        let (red, green, blue) = (channels.next()??, channels.next()??, channels.next()??);
+        Some(RgbColor::new(red, green, blue))
+    } else {
+        RgbColor::from_hex(color.get(..6)?).ok()
+    }
+}
+
+/// Parses an `HSBColor` value such as `"11,80,100"`.
+fn parse_hsb(hsb: &str) -> Option<HsbColor> {
+    let mut parts = hsb.split(',').map(str::trim);
+    let hue = parts.next()?.parse().ok()?;
+    let saturation = parts.next()?.parse().ok()?;
+    let brightness = parts.next()?.parse().ok()?;
+    HsbColor::new(hue, saturation, brightness).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,6 +268,43 @@ mod tests {
         assert_eq!(response.to_hex_with_hash(), "#FF0000");
     }
 
+    #[test]
+    fn parse_color_echo() {
+        let json = r#"{"POWER":"ON","Dimmer":100,"Color":"FF5733","HSBColor":"11,80,100",
+            "Channel":[100,34,20]}"#;
+        let response: RgbColorResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(response.rgb_color(), RgbColor::new(255, 87, 51));
+        assert_eq!(response.hsb_color(), HsbColor::new(11, 80, 100).unwrap());
+        assert_eq!(response.dimmer(), Some(100));
+        assert_eq!(response.power_state(), Some(PowerState::On));
+    }
+
+    #[test]
+    fn parse_color_echo_without_hsb() {
+        let response: RgbColorResponse = serde_json::from_str(r#"{"Color":"FF0000"}"#).unwrap();
+
+        assert_eq!(response.rgb_color(), RgbColor::new(255, 0, 0));
+        assert_eq!(response.hsb_color(), HsbColor::red());
+        assert_eq!(response.dimmer(), None);
+        assert_eq!(response.power_state(), None);
+    }
+
+    #[test]
+    fn parse_color_echo_ignores_white_channels() {
+        let rgbcct: RgbColorResponse = serde_json::from_str(r#"{"Color":"FF573300CC"}"#).unwrap();
+        assert_eq!(rgbcct.rgb_color(), RgbColor::new(255, 87, 51));
+
+        let decimal: RgbColorResponse = serde_json::from_str(r#"{"Color":"255,87,51,0"}"#).unwrap();
+        assert_eq!(decimal.rgb_color(), RgbColor::new(255, 87, 51));
+    }
+
+    #[test]
+    fn parse_color_echo_rejects_short_color() {
+        assert!(serde_json::from_str::<RgbColorResponse>(r#"{"Color":"80"}"#).is_err());
+        assert!(serde_json::from_str::<RgbColorResponse>(r#"{"Dimmer":50}"#).is_err());
+    }
+
     #[test]
     fn rgb_color_response_green() {
         // Green: HSB(120, 100, 100) -> RGB(0, 255, 0)
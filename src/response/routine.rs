@@ -81,6 +81,8 @@ use crate::error::ParseError;
 pub struct RoutineResponse {
     #[serde(flatten)]
     fields: HashMap<String, serde_json::Value>,
+    #[serde(skip)]
+    dry_run: bool,
 }
 
 impl RoutineResponse {
@@ -92,6 +94,21 @@ impl RoutineResponse {
         Self::default()
     }
 
+    /// Marks the response as synthesized in dry-run mode.
+    pub(crate) fn into_dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Returns `true` if this response was synthesized in dry-run mode
+    /// rather than received from the device.
+    ///
+    /// See [`Device::dry_run`](crate::Device::dry_run).
+    #[must_use]
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
     /// Returns `true` if the response contains the specified field.
     ///
     /// # Examples
@@ -309,7 +326,7 @@ mod tests {
 
     #[test]
     fn empty_response() {
-        let json = r#"{}"#;
+        let json = r"{}";
         let response: RoutineResponse = serde_json::from_str(json).unwrap();
 
         assert!(response.is_empty());
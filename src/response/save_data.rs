@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Settings persistence response parsing.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::ParseError;
+
+/// Response from a `SaveData` command.
+///
+/// Tasmota reports the interval as `"OFF"` (periodic saving disabled),
+/// `"ON"` (every second) or `"Every <n> sec"`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use tasmor_lib::response::SaveDataResponse;
+///
+/// let response: SaveDataResponse =
+///     serde_json::from_str(r#"{"SaveData":"Every 300 sec"}"#).unwrap();
+/// assert_eq!(response.interval().unwrap(), Some(Duration::from_secs(300)));
+///
+/// let response: SaveDataResponse = serde_json::from_str(r#"{"SaveData":"OFF"}"#).unwrap();
+/// assert_eq!(response.interval().unwrap(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SaveDataResponse {
+    #[serde(rename = "SaveData")]
+    save_data: Value,
+}
+
+impl SaveDataResponse {
+    /// Returns how often settings are saved to flash, or `None` if periodic
+    /// saving is disabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError::InvalidValue`] if the reported value is not
+    /// recognized.
+    pub fn interval(&self) -> Result<Option<Duration>, ParseError> {
+        let seconds = match &self.save_data {
+            Value::Number(n) => n.as_u64(),
+            Value::String(s) if s.eq_ignore_ascii_case("OFF") => Some(0),
+            Value::String(s) if s.eq_ignore_ascii_case("ON") => Some(1),
+            Value::String(s) => s
+                .split_whitespace()
+                .find_map(|word| word.parse::<u64>().ok()),
+            _ => None,
+        };
+        let seconds = seconds.ok_or_else(|| ParseError::InvalidValue {
+            field: "SaveData".to_string(),
+            message: format!("unrecognized interval {}", self.save_data),
+        })?;
+        Ok((seconds > 0).then(|| Duration::from_secs(seconds)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> SaveDataResponse {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn parse_reported_intervals() {
+        assert_eq!(
+            parse(r#"{"SaveData":"ON"}"#).interval().unwrap(),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            parse(r#"{"SaveData":"Every 3600 sec"}"#)
+                .interval()
+                .unwrap(),
+            Some(Duration::from_secs(3600))
+        );
+        assert_eq!(parse(r#"{"SaveData":"OFF"}"#).interval().unwrap(), None);
+        assert_eq!(parse(r#"{"SaveData":0}"#).interval().unwrap(), None);
+        assert!(parse(r#"{"SaveData":"sometimes"}"#).interval().is_err());
+    }
+}
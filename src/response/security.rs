@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Security command response parsing.
+
+use serde::Deserialize;
+
+/// Response from a `WebPassword`, `MqttPassword` or `CORS` command.
+///
+/// Only the field for the command that was sent is present. Tasmota masks
+/// passwords in its replies (e.g. `{"WebPassword":"****"}`), so the password
+/// fields confirm that the command was accepted but not the value.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::SecurityResponse;
+///
+/// let json = r#"{"CORS":"http://dashboard.local"}"#;
+/// let response: SecurityResponse = serde_json::from_str(json).unwrap();
+/// assert_eq!(response.cors(), Some("http://dashboard.local"));
+/// assert_eq!(response.web_password(), None);
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct SecurityResponse {
+    #[serde(rename = "WebPassword")]
+    web_password: Option<String>,
+    #[serde(rename = "MqttPassword")]
+    mqtt_password: Option<String>,
+    #[serde(rename = "CORS")]
+    cors: Option<String>,
+}
+
+impl SecurityResponse {
+    /// Returns the (masked) web password confirmation.
+    #[must_use]
+    pub fn web_password(&self) -> Option<&str> {
+        self.web_password.as_deref()
+    }
+
+    /// Returns the (masked) MQTT password confirmation.
+    #[must_use]
+    pub fn mqtt_password(&self) -> Option<&str> {
+        self.mqtt_password.as_deref()
+    }
+
+    /// Returns the allowed CORS origin.
+    ///
+    /// An empty string means CORS is disabled.
+    #[must_use]
+    pub fn cors(&self) -> Option<&str> {
+        self.cors.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_masked_password() {
+        let json = r#"{"WebPassword":"****"}"#;
+        let response: SecurityResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.web_password(), Some("****"));
+        assert_eq!(response.mqtt_password(), None);
+        assert_eq!(response.cors(), None);
+    }
+
+    #[test]
+    fn parse_disabled_cors() {
+        let json = r#"{"CORS":""}"#;
+        let response: SecurityResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.cors(), Some(""));
+    }
+}
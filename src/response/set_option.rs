@@ -0,0 +1,343 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! `SetOption` response and dump parsing.
+
+use std::collections::BTreeMap;
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::error::ParseError;
+
+/// First option of each word of the `SetOption` dump.
+///
+/// Word 1 holds one byte per option (`SetOption32`-`SetOption49` take
+/// values, not flags); the other words are 32-bit flag fields.
+const WORD_BASES: [u16; 6] = [0, 32, 50, 82, 114, 146];
+
+/// Number of byte-valued options in word 1.
+const PARAM_COUNT: usize = 18;
+
+/// All `SetOption` values of a device.
+///
+/// Parsed from the `SetOption` array of `Status 3` (`StatusLOG`), where
+/// Tasmota packs the options into hex words:
+///
+/// ```json
+/// {"StatusLOG":{"SetOption":["00008009","2805C80001000600003C5A0A190000000000",
+///  "00000080","00006000","00004000","00000000"]}}
+/// ```
+///
+/// Flag options are `0` or `1`; `SetOption32`-`SetOption49` hold values
+/// (0-255). Options the firmware did not report are absent.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::SetOptionFlags;
+///
+/// let flags = SetOptionFlags::from_words(&["00000001", "2805", "00080000"]).unwrap();
+/// assert_eq!(flags.save_power_state(), Some(true));
+/// assert_eq!(flags.get(0), Some(1));
+/// assert_eq!(flags.get(32), Some(0x28));
+/// assert_eq!(flags.is_enabled(69), Some(true));
+/// assert_eq!(flags.get(150), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SetOptionFlags {
+    values: BTreeMap<u16, u8>,
+}
+
+impl SetOptionFlags {
+    /// Parses the hex words of a `SetOption` dump.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError::InvalidValue`] if a word is not valid hex or
+    /// a flag word is longer than 32 bits.
+    pub fn from_words<S: AsRef<str>>(words: &[S]) -> Result<Self, ParseError> {
+        let mut values = BTreeMap::new();
+
+        for (index, (word, base)) in words.iter().zip(WORD_BASES).enumerate() {
+            let word = word.as_ref();
+            let invalid = || ParseError::InvalidValue {
+                field: "SetOption".to_string(),
+                message: format!("invalid word {index}: '{word}'"),
+            };
+
+            if base == 32 {
+                let bytes = (0..word.len())
+                    .step_by(2)
+                    .map(|i| {
+                        word.get(i..i + 2)
+                            .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    })
+                    .collect::<Option<Vec<u8>>>()
+                    .ok_or_else(invalid)?;
+                for (offset, value) in (0u16..).zip(bytes.into_iter().take(PARAM_COUNT)) {
+                    values.insert(base + offset, value);
+                }
+            } else {
+                let bits = u32::from_str_radix(word, 16).map_err(|_| invalid())?;
+                for bit in 0..32u16 {
+                    values.insert(base + bit, u8::from(bits & (1 << bit) != 0));
+                }
+            }
+        }
+
+        Ok(Self { values })
+    }
+
+    /// Returns the value of an option, if reported.
+    #[must_use]
+    pub fn get(&self, option: u16) -> Option<u8> {
+        self.values.get(&option).copied()
+    }
+
+    /// Returns whether an option is non-zero, if reported.
+    #[must_use]
+    pub fn is_enabled(&self, option: u16) -> Option<bool> {
+        self.get(option).map(|value| value != 0)
+    }
+
+    /// Returns all reported options, by option number.
+    #[must_use]
+    pub fn raw(&self) -> &BTreeMap<u16, u8> {
+        &self.values
+    }
+
+    /// `SetOption0`: power state is saved and restored after a restart
+    /// (enabled by default).
+    #[must_use]
+    pub fn save_power_state(&self) -> Option<bool> {
+        self.is_enabled(0)
+    }
+
+    /// `SetOption3`: MQTT is enabled.
+    #[must_use]
+    pub fn mqtt_enabled(&self) -> Option<bool> {
+        self.is_enabled(3)
+    }
+
+    /// `SetOption4`: command results are published on `stat/<topic>/<Command>`
+    /// instead of `stat/<topic>/RESULT`.
+    #[must_use]
+    pub fn results_on_command_topic(&self) -> Option<bool> {
+        self.is_enabled(4)
+    }
+
+    /// `SetOption8`: temperatures are reported in Fahrenheit.
+    #[must_use]
+    pub fn temperature_fahrenheit(&self) -> Option<bool> {
+        self.is_enabled(8)
+    }
+
+    /// `SetOption17`: colors are reported as comma-separated decimals
+    /// instead of hex.
+    #[must_use]
+    pub fn decimal_color(&self) -> Option<bool> {
+        self.is_enabled(17)
+    }
+
+    /// `SetOption19`: Home Assistant auto-discovery (legacy) is enabled.
+    #[must_use]
+    pub fn home_assistant_discovery(&self) -> Option<bool> {
+        self.is_enabled(19)
+    }
+
+    /// `SetOption26`: power states are always indexed (`POWER1`), even on
+    /// single-relay devices.
+    #[must_use]
+    pub fn indexed_power(&self) -> Option<bool> {
+        self.is_enabled(26)
+    }
+
+    /// `SetOption36`: number of consecutive restarts that triggers boot
+    /// loop recovery (0 disables it).
+    #[must_use]
+    pub fn boot_loop_threshold(&self) -> Option<u8> {
+        self.get(36)
+    }
+
+    /// `SetOption59`: `tele/<topic>/STATE` is also sent on power changes.
+    #[must_use]
+    pub fn state_on_power_change(&self) -> Option<bool> {
+        self.is_enabled(59)
+    }
+
+    /// `SetOption65`: recovery by fast power cycling is disabled.
+    #[must_use]
+    pub fn fast_power_cycle_recovery_disabled(&self) -> Option<bool> {
+        self.is_enabled(65)
+    }
+
+    /// `SetOption73`: buttons are detached from relays.
+    #[must_use]
+    pub fn buttons_detached(&self) -> Option<bool> {
+        self.is_enabled(73)
+    }
+
+    /// `SetOption91`: fade is applied at startup.
+    #[must_use]
+    pub fn fade_at_startup(&self) -> Option<bool> {
+        self.is_enabled(91)
+    }
+
+    /// `SetOption114`: switches are detached from relays.
+    #[must_use]
+    pub fn switches_detached(&self) -> Option<bool> {
+        self.is_enabled(114)
+    }
+}
+
+/// Response from a `SetOption<n>` command.
+///
+/// Tasmota answers both queries and changes with the option's current
+/// value: `{"SetOption0":"ON"}` for a flag, `{"SetOption36":4}` for a
+/// numeric option.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::SetOptionResponse;
+///
+/// let response: SetOptionResponse = serde_json::from_str(r#"{"SetOption0":"OFF"}"#).unwrap();
+/// assert_eq!(response.option(), 0);
+/// assert!(!response.is_enabled());
+///
+/// let response: SetOptionResponse = serde_json::from_str(r#"{"SetOption36":4}"#).unwrap();
+/// assert_eq!(response.value(), 4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetOptionResponse {
+    option: u16,
+    value: u8,
+}
+
+impl SetOptionResponse {
+    /// Returns the option number.
+    #[must_use]
+    pub const fn option(&self) -> u16 {
+        self.option
+    }
+
+    /// Returns whether the option is enabled (non-zero).
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.value != 0
+    }
+
+    /// Returns the option value; `ON` and `OFF` are 1 and 0.
+    #[must_use]
+    pub const fn value(&self) -> u8 {
+        self.value
+    }
+}
+
+impl<'de> Deserialize<'de> for SetOptionResponse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map = BTreeMap::<String, Value>::deserialize(deserializer)?;
+        let (option, value) = map
+            .iter()
+            .find_map(|(key, value)| {
+                let option = key
+                    .get(..9)
+                    .filter(|prefix| prefix.eq_ignore_ascii_case("SetOption"))
+                    .and_then(|_| key[9..].parse::<u16>().ok())?;
+                Some((option, value))
+            })
+            .ok_or_else(|| de::Error::missing_field("SetOption"))?;
+        let value = match value {
+            Value::String(s) if s.eq_ignore_ascii_case("ON") => Some(1),
+            Value::String(s) if s.eq_ignore_ascii_case("OFF") => Some(0),
+            Value::String(s) => s.parse::<u8>().ok(),
+            Value::Number(n) => n.as_u64().and_then(|n| u8::try_from(n).ok()),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            de::Error::custom(format!(
+                "SetOption{option}: expected ON, OFF, or 0-255, got {value}"
+            ))
+        })?;
+        Ok(Self { option, value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMP: [&str; 6] = [
+        "00008009",
+        "2805C80001000600003C5A0A190000000000",
+        "00000080",
+        "00006000",
+        "00004000",
+        "00000000",
+    ];
+
+    #[test]
+    fn parse_full_dump() {
+        let flags = SetOptionFlags::from_words(&DUMP).unwrap();
+        assert_eq!(flags.raw().len(), 32 + 18 + 32 * 4);
+
+        // 0x8009: bits 0, 3 and 15
+        assert_eq!(flags.get(0), Some(1));
+        assert_eq!(flags.save_power_state(), Some(true));
+        assert_eq!(flags.mqtt_enabled(), Some(true));
+        assert_eq!(flags.is_enabled(15), Some(true));
+        assert_eq!(flags.home_assistant_discovery(), Some(false));
+
+        // Byte values for SetOption32-49
+        assert_eq!(flags.get(32), Some(0x28));
+        assert_eq!(flags.get(34), Some(0xC8));
+        assert_eq!(flags.boot_loop_threshold(), Some(1));
+        assert_eq!(flags.get(49), Some(0));
+
+        // 0x80 in word 2: bit 7 = SetOption57
+        assert_eq!(flags.is_enabled(57), Some(true));
+        // 0x6000 in word 3: bits 13, 14 = SetOption95, 96
+        assert_eq!(flags.is_enabled(95), Some(true));
+        assert_eq!(flags.fade_at_startup(), Some(false));
+        // 0x4000 in word 4: bit 14 = SetOption128
+        assert_eq!(flags.is_enabled(128), Some(true));
+        assert_eq!(flags.switches_detached(), Some(false));
+    }
+
+    #[test]
+    fn older_firmware_reports_fewer_words() {
+        let flags = SetOptionFlags::from_words(&DUMP[..3]).unwrap();
+        assert_eq!(flags.is_enabled(81), Some(false));
+        assert_eq!(flags.fade_at_startup(), None);
+        assert_eq!(flags.get(200), None);
+    }
+
+    #[test]
+    fn invalid_words_fail() {
+        assert!(SetOptionFlags::from_words(&["XYZ"]).is_err());
+        assert!(SetOptionFlags::from_words(&["0", "280"]).is_err());
+        assert!(SetOptionFlags::from_words(&["123456789"]).is_err());
+    }
+
+    #[test]
+    fn parse_set_option_response() {
+        let on: SetOptionResponse = serde_json::from_str(r#"{"SetOption0":"ON"}"#).unwrap();
+        assert_eq!(on.option(), 0);
+        assert!(on.is_enabled());
+
+        let off: SetOptionResponse = serde_json::from_str(r#"{"SetOption73":0}"#).unwrap();
+        assert_eq!(off.option(), 73);
+        assert!(!off.is_enabled());
+
+        let number: SetOptionResponse = serde_json::from_str(r#"{"SetOption32":"40"}"#).unwrap();
+        assert_eq!(number.value(), 40);
+        assert!(number.is_enabled());
+        assert!(serde_json::from_str::<SetOptionResponse>(r#"{"SetOption32":256}"#).is_err());
+
+        assert!(serde_json::from_str::<SetOptionResponse>(r#"{"SetOption0":"maybe"}"#).is_err());
+        assert!(serde_json::from_str::<SetOptionResponse>(r#"{"Power":"ON"}"#).is_err());
+    }
+}
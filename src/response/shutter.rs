@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Shutter response parsing.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde::de::{self, Deserializer};
+use serde_json::Value;
+
+/// Direction a shutter is moving in.
+///
+/// Tasmota reports it as `1` (opening), `-1` (closing) or `0` (stopped).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShutterDirection {
+    /// Not moving.
+    Stopped,
+    /// Moving towards 100 (open).
+    Opening,
+    /// Moving towards 0 (closed).
+    Closing,
+}
+
+impl ShutterDirection {
+    /// Returns Tasmota's numeric value for the direction.
+    #[must_use]
+    pub const fn as_i8(self) -> i8 {
+        match self {
+            Self::Stopped => 0,
+            Self::Opening => 1,
+            Self::Closing => -1,
+        }
+    }
+}
+
+impl TryFrom<i64> for ShutterDirection {
+    type Error = i64;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Stopped),
+            1 => Ok(Self::Opening),
+            -1 => Ok(Self::Closing),
+            other => Err(other),
+        }
+    }
+}
+
+/// Response from a shutter command.
+///
+/// Positions follow Tasmota's convention: 0 is closed, 100 is open.
+/// The shutter state is reported per shutter:
+///
+/// ```json
+/// {"Shutter1":{"Position":50,"Direction":0,"Target":50}}
+/// ```
+///
+/// A move command may instead be answered with the target only, such as
+/// `{"ShutterPosition1":50}` or `{"ShutterOpen1":100}`; position and
+/// direction are then `None`.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::{ShutterDirection, ShutterResponse};
+///
+/// let json = r#"{"Shutter1":{"Position":30,"Direction":1,"Target":80}}"#;
+/// let response: ShutterResponse = serde_json::from_str(json).unwrap();
+///
+/// assert_eq!(response.index(), 1);
+/// assert_eq!(response.position(), Some(30));
+/// assert_eq!(response.direction(), Some(ShutterDirection::Opening));
+/// assert_eq!(response.target(), 80);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutterResponse {
+    index: u8,
+    position: Option<u8>,
+    direction: Option<ShutterDirection>,
+    target: u8,
+}
+
+impl ShutterResponse {
+    /// Returns the shutter number.
+    #[must_use]
+    pub const fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Returns the current position (0 closed, 100 open), if reported.
+    #[must_use]
+    pub const fn position(&self) -> Option<u8> {
+        self.position
+    }
+
+    /// Returns the direction the shutter is moving in, if reported.
+    #[must_use]
+    pub const fn direction(&self) -> Option<ShutterDirection> {
+        self.direction
+    }
+
+    /// Returns the position the shutter is moving to (0 closed, 100 open).
+    #[must_use]
+    pub const fn target(&self) -> u8 {
+        self.target
+    }
+
+    /// Returns whether the shutter is moving, if the direction is reported.
+    #[must_use]
+    pub fn is_moving(&self) -> Option<bool> {
+        self.direction
+            .map(|direction| direction != ShutterDirection::Stopped)
+    }
+}
+
+/// The `Shutter<x>` state object.
+#[derive(Deserialize)]
+struct ShutterState {
+    #[serde(rename = "Position")]
+    position: u8,
+    #[serde(rename = "Direction")]
+    direction: i64,
+    #[serde(rename = "Target")]
+    target: u8,
+}
+
+/// Splits `Shutter<action><x>` into the action and the shutter number.
+fn split_key(key: &str) -> Option<(&str, u8)> {
+    if !key.get(..7)?.eq_ignore_ascii_case("Shutter") {
+        return None;
+    }
+    let rest = key.get(7..)?;
+    let action_len = rest.len() - rest.trim_start_matches(char::is_alphabetic).len();
+    let (action, index) = rest.split_at(action_len);
+    Some((action, index.parse().ok()?))
+}
+
+impl<'de> Deserialize<'de> for ShutterResponse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map = BTreeMap::<String, Value>::deserialize(deserializer)?;
+
+        // The full state, e.g. `Shutter1`
+        if let Some((index, value)) = map.iter().find_map(|(key, value)| {
+            let (action, index) = split_key(key)?;
+            (action.is_empty() && value.is_object()).then_some((index, value))
+        }) {
+            let state = ShutterState::deserialize(value)
+                .map_err(|e| de::Error::custom(format!("Shutter{index}: {e}")))?;
+            let direction = ShutterDirection::try_from(state.direction).map_err(|value| {
+                de::Error::custom(format!("Shutter{index}: invalid direction {value}"))
+            })?;
+            return Ok(Self {
+                index,
+                position: Some(state.position),
+                direction: Some(direction),
+                target: state.target,
+            });
+        }
+
+        // The target only, e.g. `ShutterPosition1`
+        map.iter()
+            .find_map(|(key, value)| {
+                let (_, index) = split_key(key)?;
+                let target = u8::try_from(value.as_u64()?).ok()?;
+                Some(Self {
+                    index,
+                    position: None,
+                    direction: None,
+                    target,
+                })
+            })
+            .ok_or_else(|| de::Error::missing_field("Shutter"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_directions() {
+        for (json_direction, expected) in [
+            (0, ShutterDirection::Stopped),
+            (1, ShutterDirection::Opening),
+            (-1, ShutterDirection::Closing),
+        ] {
+            let json = format!(
+                r#"{{"Shutter2":{{"Position":40,"Direction":{json_direction},"Target":0,"Tilt":0}}}}"#
+            );
+            let response: ShutterResponse = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(response.index(), 2);
+            assert_eq!(response.direction(), Some(expected));
+            assert_eq!(expected.as_i8(), json_direction);
+        }
+    }
+
+    #[test]
+    fn stopped_shutter_is_not_moving() {
+        let json = r#"{"Shutter1":{"Position":50,"Direction":0,"Target":50}}"#;
+        let response: ShutterResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(response.position(), Some(50));
+        assert_eq!(response.is_moving(), Some(false));
+    }
+
+    #[test]
+    fn invalid_direction_fails() {
+        let json = r#"{"Shutter1":{"Position":50,"Direction":2,"Target":50}}"#;
+        assert!(serde_json::from_str::<ShutterResponse>(json).is_err());
+    }
+
+    #[test]
+    fn parse_target_only_reply() {
+        let response: ShutterResponse = serde_json::from_str(r#"{"ShutterOpen3":100}"#).unwrap();
+
+        assert_eq!(response.index(), 3);
+        assert_eq!(response.target(), 100);
+        assert_eq!(response.position(), None);
+        assert_eq!(response.is_moving(), None);
+    }
+
+    #[test]
+    fn missing_shutter_fails() {
+        assert!(serde_json::from_str::<ShutterResponse>(r#"{"ShutterStop1":"Done"}"#).is_err());
+        assert!(serde_json::from_str::<ShutterResponse>(r#"{"POWER":"ON"}"#).is_err());
+    }
+}
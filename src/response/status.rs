@@ -9,7 +9,9 @@ use std::time::Duration;
 
 use serde::{Deserialize, Deserializer};
 
-use crate::types::parse_uptime;
+use crate::state::SystemInfo;
+use crate::telemetry::TelemetryState;
+use crate::types::{TasmotaDateTime, parse_uptime};
 
 /// Deserializes a value that can be either a number or a string representation of a number.
 /// This is needed because Tasmota sometimes returns numeric values as strings.
@@ -327,6 +329,12 @@ pub struct StatusResponse {
     /// State information (Status 11 / runtime state).
     #[serde(rename = "StatusSTS")]
     pub sensor_status: Option<serde_json::Value>,
+
+    /// Stack dump of the last crash (Status 12).
+    ///
+    /// Use [`crash_info()`](Self::crash_info) for a typed view.
+    #[serde(rename = "StatusSTK")]
+    pub stack_dump: Option<serde_json::Value>,
 }
 
 impl StatusResponse {
@@ -359,6 +367,242 @@ impl StatusResponse {
     pub fn hostname(&self) -> Option<&str> {
         self.network.as_ref().map(|n| n.hostname.as_str())
     }
+
+    /// Returns the free heap in bytes.
+    ///
+    /// Taken from `StatusMEM` (Status 4), falling back to the `Heap` of
+    /// `StatusSTS` (Status 11), so the answer to a `Status 11` ping carries
+    /// it too. Tasmota reports the heap in KB; the value is converted to
+    /// bytes. Returns `None` if neither section reports it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tasmor_lib::response::StatusResponse;
+    ///
+    /// let json = r#"{"StatusMEM": {"Heap": 25, "StackLowMark": 1}}"#;
+    /// let response: StatusResponse = serde_json::from_str(json).unwrap();
+    /// assert_eq!(response.free_heap(), Some(25 * 1024));
+    /// assert_eq!(response.stack_low_mark(), Some(1024));
+    /// ```
+    #[must_use]
+    pub fn free_heap(&self) -> Option<u32> {
+        self.memory
+            .as_ref()
+            .map(|m| m.heap)
+            .filter(|&kb| kb > 0)
+            .or_else(|| {
+                self.sensor_status
+                    .as_ref()
+                    .and_then(|sts| sts.get("Heap"))
+                    .and_then(serde_json::Value::as_u64)
+                    .and_then(|kb| u32::try_from(kb).ok())
+            })
+            .map(|kb| kb.saturating_mul(1024))
+    }
+
+    /// Returns the lowest amount of free stack seen since boot, in bytes.
+    ///
+    /// Taken from `StatusMEM` (Status 4). Returns `None` on firmware that
+    /// does not report it.
+    #[must_use]
+    pub fn stack_low_mark(&self) -> Option<u32> {
+        self.memory
+            .as_ref()
+            .map(|m| m.stack_low_mark)
+            .filter(|&kb| kb > 0)
+            .map(|kb| kb.saturating_mul(1024))
+    }
+
+    /// Returns the uptime, Wi-Fi signal and free heap as a [`SystemInfo`].
+    ///
+    /// This is the HTTP counterpart of
+    /// [`TelemetryState::to_system_info`](crate::telemetry::TelemetryState::to_system_info):
+    /// the values come from `StatusSTS` (Status 11), falling back to the
+    /// uptime of `StatusPRM`, the signal of `StatusNET` and the heap of
+    /// `StatusMEM`. Values the firmware did not report stay `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use tasmor_lib::response::StatusResponse;
+    ///
+    /// let json = r#"{"StatusSTS": {"UptimeSec": 3600, "Wifi": {"Signal": -62}}}"#;
+    /// let response: StatusResponse = serde_json::from_str(json).unwrap();
+    ///
+    /// let info = response.system_info();
+    /// assert_eq!(info.uptime(), Some(Duration::from_secs(3600)));
+    /// assert_eq!(info.wifi_rssi(), Some(-62));
+    /// assert_eq!(info.heap(), None);
+    /// ```
+    #[must_use]
+    pub fn system_info(&self) -> SystemInfo {
+        let state = self
+            .sensor_status
+            .as_ref()
+            .and_then(|sts| TelemetryState::deserialize(sts).ok())
+            .unwrap_or_default();
+        let mut info = state.to_system_info();
+
+        if info.uptime().is_none()
+            && let Some(uptime) = self.status_prm.as_ref().and_then(StatusParameters::uptime)
+        {
+            info = info.with_uptime(uptime);
+        }
+        // A missing Signal deserializes as 0, which no real link reports
+        if info.wifi_rssi().is_none()
+            && let Some(signal) = self.network.as_ref().map(|n| n.signal).filter(|&s| s != 0)
+        {
+            info = info.with_wifi_rssi(signal);
+        }
+        if let Some(bytes) = self.free_heap() {
+            info = info.with_heap(bytes / 1024);
+        }
+
+        info
+    }
+
+    /// Returns details about the last crash, if the device reports one.
+    ///
+    /// Crash data comes from the `StatusSTK` stack dump (Status 12) when
+    /// present, otherwise from a crash-like `RestartReason` in `StatusPRM`
+    /// (exception, watchdog, panic or brownout). Returns `None` after a
+    /// normal restart, which is the usual case. The crash record cannot be
+    /// cleared separately: a normal restart replaces it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tasmor_lib::response::StatusResponse;
+    ///
+    /// let json = r#"{"StatusPRM": {"RestartReason": "Hardware Watchdog"}}"#;
+    /// let response: StatusResponse = serde_json::from_str(json).unwrap();
+    /// assert_eq!(response.crash_info().unwrap().reason(), "Hardware Watchdog");
+    ///
+    /// let json = r#"{"StatusPRM": {"RestartReason": "Power On"}}"#;
+    /// let response: StatusResponse = serde_json::from_str(json).unwrap();
+    /// assert!(response.crash_info().is_none());
+    /// ```
+    #[must_use]
+    pub fn crash_info(&self) -> Option<CrashInfo> {
+        let restart_reason = self
+            .status_prm
+            .as_ref()
+            .map(|p| p.restart_reason.as_str())
+            .filter(|r| !r.is_empty());
+
+        if let Some(stack) = self.stack_dump.as_ref().and_then(|v| v.as_object())
+            && !stack.is_empty()
+        {
+            let reason = stack
+                .get("Reason")
+                .and_then(|v| v.as_str())
+                .or(restart_reason)
+                .unwrap_or_default();
+            return Some(CrashInfo {
+                reason: reason.to_string(),
+                exception: stack.get("Exception").and_then(value_as_u32),
+                exception_address: stack.get("EXCVADDR").and_then(value_as_string),
+                program_counters: stack.get("EPC").map(value_as_strings).unwrap_or_default(),
+                call_chain: stack
+                    .get("CallChain")
+                    .map(value_as_strings)
+                    .unwrap_or_default(),
+            });
+        }
+
+        restart_reason
+            .filter(|r| is_crash_reason(r))
+            .map(|reason| CrashInfo {
+                reason: reason.to_string(),
+                exception: None,
+                exception_address: None,
+                program_counters: Vec::new(),
+                call_chain: Vec::new(),
+            })
+    }
+}
+
+/// Details about a device crash.
+///
+/// Returned by [`StatusResponse::crash_info`]. Only the reason is always
+/// available; the stack fields are filled when the device reports a
+/// `StatusSTK` stack dump (ESP8266 builds).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashInfo {
+    reason: String,
+    exception: Option<u32>,
+    exception_address: Option<String>,
+    program_counters: Vec<String>,
+    call_chain: Vec<String>,
+}
+
+impl CrashInfo {
+    /// Returns the crash or restart reason (e.g. `"Exception"`,
+    /// `"Software Watchdog"`).
+    #[must_use]
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    /// Returns the exception cause code, if reported.
+    #[must_use]
+    pub fn exception(&self) -> Option<u32> {
+        self.exception
+    }
+
+    /// Returns the faulting address (`EXCVADDR`), if reported.
+    #[must_use]
+    pub fn exception_address(&self) -> Option<&str> {
+        self.exception_address.as_deref()
+    }
+
+    /// Returns the exception program counters (`EPC`).
+    #[must_use]
+    pub fn program_counters(&self) -> &[String] {
+        &self.program_counters
+    }
+
+    /// Returns the call chain addresses, if reported.
+    #[must_use]
+    pub fn call_chain(&self) -> &[String] {
+        &self.call_chain
+    }
+}
+
+/// Returns `true` if a restart reason indicates an abnormal restart.
+fn is_crash_reason(reason: &str) -> bool {
+    let reason = reason.to_lowercase();
+    ["exception", "watchdog", "panic", "brownout"]
+        .iter()
+        .any(|keyword| reason.contains(keyword))
+}
+
+/// Reads a JSON number or numeric string as `u32`.
+fn value_as_u32(value: &serde_json::Value) -> Option<u32> {
+    match value {
+        serde_json::Value::Number(n) => n.as_u64().and_then(|n| u32::try_from(n).ok()),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Reads a JSON string or number as a string.
+fn value_as_string(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Reads a JSON array (or single value) as a list of strings.
+fn value_as_strings(value: &serde_json::Value) -> Vec<String> {
+    match value {
+        serde_json::Value::Array(items) => items.iter().filter_map(value_as_string).collect(),
+        other => value_as_string(other).into_iter().collect(),
+    }
 }
 
 /// Device parameters from Status 1.
@@ -435,6 +679,27 @@ pub struct StatusParameters {
     /// Boot count.
     #[serde(default, deserialize_with = "deserialize_string_or_number_u32_opt")]
     pub boot_count: u32,
+
+    /// Startup time in UTC (format: "2024-01-15T08:30:00").
+    ///
+    /// Use [`startup_utc()`](Self::startup_utc) to get a parsed date instead.
+    #[serde(default, rename = "StartupUTC")]
+    pub startup_utc_string: String,
+
+    /// Sleep time in milliseconds between main loop iterations.
+    #[serde(default)]
+    pub sleep: Option<u16>,
+
+    /// Number of times the settings were saved to flash.
+    ///
+    /// A count rising quickly points to a configuration that wears the
+    /// flash, such as `SaveData` with frequent power changes.
+    #[serde(default)]
+    pub save_count: Option<u32>,
+
+    /// Flash address of the saved settings (hexadecimal).
+    #[serde(default)]
+    pub save_address: Option<String>,
 }
 
 impl StatusParameters {
@@ -465,6 +730,14 @@ impl StatusParameters {
         }
         parse_uptime(&self.uptime_string).ok()
     }
+
+    /// Returns the startup time in UTC.
+    ///
+    /// Returns `None` if the startup time is missing or cannot be parsed.
+    #[must_use]
+    pub fn startup_utc(&self) -> Option<TasmotaDateTime> {
+        TasmotaDateTime::parse(&self.startup_utc_string)
+    }
 }
 
 /// Firmware information from Status 2.
@@ -504,6 +777,14 @@ pub struct StatusFirmware {
     pub hardware: String,
 }
 
+impl StatusFirmware {
+    /// Returns the parsed firmware version, if recognized.
+    #[must_use]
+    pub fn parsed_version(&self) -> Option<crate::types::FirmwareVersion> {
+        crate::types::FirmwareVersion::parse(&self.version)
+    }
+}
+
 /// Logging settings from Status 3.
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -539,6 +820,11 @@ pub struct StatusLogging {
         deserialize_with = "deserialize_string_or_number_u16_opt"
     )]
     pub tele_period: u16,
+
+    /// Packed `SetOption` words, decoded by
+    /// [`SetOptionFlags`](crate::response::SetOptionFlags).
+    #[serde(default)]
+    pub set_option: Vec<String>,
 }
 
 /// Memory information from Status 4.
@@ -553,10 +839,18 @@ pub struct StatusMemory {
     #[serde(default, deserialize_with = "deserialize_string_or_number_u32_opt")]
     pub free: u32,
 
-    /// Heap size in bytes.
+    /// Free heap in KB.
+    ///
+    /// Use [`StatusResponse::free_heap`] for the value in bytes.
     #[serde(default, deserialize_with = "deserialize_string_or_number_u32_opt")]
     pub heap: u32,
 
+    /// Lowest amount of free stack seen since boot, in KB.
+    ///
+    /// Use [`StatusResponse::stack_low_mark`] for the value in bytes.
+    #[serde(default, deserialize_with = "deserialize_string_or_number_u32_opt")]
+    pub stack_low_mark: u32,
+
     /// Program flash size in KB.
     #[serde(
         default,
@@ -573,15 +867,53 @@ pub struct StatusMemory {
     #[serde(default, rename = "FlashChipId")]
     pub flash_chip_id: String,
 
+    /// Flash frequency in MHz.
+    #[serde(default, deserialize_with = "deserialize_string_or_number_u32_opt")]
+    pub flash_frequency: u32,
+
     /// Flash mode.
     #[serde(default, deserialize_with = "deserialize_string_or_number_opt")]
     pub flash_mode: u8,
 
+    /// PSRAM size in KB (ESP32 only, `None` without PSRAM).
+    #[serde(default, rename = "PsrMax")]
+    pub psram_size: Option<u32>,
+
+    /// Free PSRAM in KB (ESP32 only, `None` without PSRAM).
+    #[serde(default, rename = "PsrFree")]
+    pub psram_free: Option<u32>,
+
     /// Features list.
     #[serde(default)]
     pub features: Vec<String>,
 }
 
+impl StatusMemory {
+    /// Returns `true` if a firmware image of `size_kb` fits for an OTA
+    /// upgrade.
+    ///
+    /// The new image is written to the free program space before it
+    /// replaces the running one, so it must not be larger than
+    /// [`free`](Self::free). When it does not fit, upgrade through the
+    /// minimal firmware first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tasmor_lib::response::StatusResponse;
+    ///
+    /// let json = r#"{"StatusMEM": {"ProgramSize": 616, "Free": 384, "FlashSize": 1024}}"#;
+    /// let response: StatusResponse = serde_json::from_str(json).unwrap();
+    /// let memory = response.memory.unwrap();
+    /// assert!(memory.fits_firmware(380));
+    /// assert!(!memory.fits_firmware(620));
+    /// ```
+    #[must_use]
+    pub fn fits_firmware(&self, size_kb: u32) -> bool {
+        size_kb <= self.free
+    }
+}
+
 /// Network information from Status 5.
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -765,6 +1097,51 @@ mod tests {
         assert_eq!(response.device_name(), Some("Tasmota"));
     }
 
+    #[test]
+    fn crash_info_from_stack_dump() {
+        let json = r#"{
+            "StatusPRM": {"RestartReason": "Exception"},
+            "StatusSTK": {
+                "Exception": 28,
+                "Reason": "Exception",
+                "EPC": ["40220dbd", "00000000", "00000000"],
+                "EXCVADDR": "00000000",
+                "DEPC": "00000000",
+                "CallChain": ["40220dbd", "4021d2f8"]
+            }
+        }"#;
+
+        let response: StatusResponse = serde_json::from_str(json).unwrap();
+        let crash = response.crash_info().unwrap();
+
+        assert_eq!(crash.reason(), "Exception");
+        assert_eq!(crash.exception(), Some(28));
+        assert_eq!(crash.exception_address(), Some("00000000"));
+        assert_eq!(crash.program_counters().len(), 3);
+        assert_eq!(crash.call_chain(), ["40220dbd", "4021d2f8"]);
+    }
+
+    #[test]
+    fn crash_info_from_restart_reason() {
+        let json = r#"{"StatusPRM": {"RestartReason": "Software Watchdog"}}"#;
+        let response: StatusResponse = serde_json::from_str(json).unwrap();
+        let crash = response.crash_info().unwrap();
+
+        assert_eq!(crash.reason(), "Software Watchdog");
+        assert_eq!(crash.exception(), None);
+        assert!(crash.program_counters().is_empty());
+    }
+
+    #[test]
+    fn crash_info_none_after_normal_restart() {
+        let json = r#"{"StatusPRM": {"RestartReason": "Software/System restart"}}"#;
+        let response: StatusResponse = serde_json::from_str(json).unwrap();
+        assert!(response.crash_info().is_none());
+
+        let response = StatusResponse::default();
+        assert!(response.crash_info().is_none());
+    }
+
     #[test]
     fn parse_firmware_info() {
         let json = r#"{
@@ -824,4 +1201,148 @@ mod tests {
         assert_eq!(mqtt.host, "192.168.1.50");
         assert_eq!(mqtt.port, 1883);
     }
+
+    #[test]
+    fn parse_status_parameters() {
+        let json = r#"{
+            "StatusPRM": {
+                "Baudrate": 115200,
+                "SerialConfig": "8N1",
+                "GroupTopic": "tasmotas",
+                "OtaUrl": "http://ota.tasmota.com/tasmota/release/tasmota.bin.gz",
+                "RestartReason": "Software/System restart",
+                "Uptime": "0T02:13:45",
+                "StartupUTC": "2024-01-15T08:30:00",
+                "Sleep": 50,
+                "CfgHolder": 4617,
+                "BootCount": 23,
+                "BCResetTime": "2023-11-02T10:00:00",
+                "SaveCount": 412,
+                "SaveAddress": "F9000"
+            }
+        }"#;
+
+        let response: StatusResponse = serde_json::from_str(json).unwrap();
+        let params = response.status_prm.unwrap();
+        assert_eq!(params.group_topic, "tasmotas");
+        assert_eq!(params.boot_count, 23);
+        assert_eq!(params.sleep, Some(50));
+        assert_eq!(params.save_count, Some(412));
+        assert_eq!(params.save_address.as_deref(), Some("F9000"));
+        assert_eq!(
+            params.startup_utc().unwrap().to_string(),
+            "2024-01-15 08:30:00"
+        );
+    }
+
+    #[test]
+    fn status_parameters_missing_optional_fields() {
+        let json = r#"{"StatusPRM": {"Baudrate": 9600, "Uptime": "1T00:00:00"}}"#;
+
+        let response: StatusResponse = serde_json::from_str(json).unwrap();
+        let params = response.status_prm.unwrap();
+        assert_eq!(params.sleep, None);
+        assert_eq!(params.save_count, None);
+        assert_eq!(params.startup_utc(), None);
+    }
+
+    #[test]
+    fn memory_health() {
+        let json = r#"{
+            "StatusMEM": {"ProgramSize": 616, "Free": 384, "Heap": 21, "StackLowMark": 2},
+            "StatusSTS": {"Heap": 24}
+        }"#;
+
+        let response: StatusResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.free_heap(), Some(21 * 1024));
+        assert_eq!(response.stack_low_mark(), Some(2 * 1024));
+    }
+
+    #[test]
+    fn memory_health_from_state_only() {
+        let json = r#"{"StatusSTS": {"Heap": 24, "POWER": "ON"}}"#;
+
+        let response: StatusResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.free_heap(), Some(24 * 1024));
+        assert_eq!(response.stack_low_mark(), None);
+    }
+
+    #[test]
+    fn memory_health_missing() {
+        let json = r#"{"StatusMEM": {"ProgramSize": 616}}"#;
+
+        let response: StatusResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.free_heap(), None);
+        assert_eq!(response.stack_low_mark(), None);
+    }
+
+    #[test]
+    fn status_memory_esp32() {
+        let json = r#"{"StatusMEM": {
+            "ProgramSize": 1668, "Free": 1216, "Heap": 151, "StackLowMark": 3,
+            "PsrMax": 2047, "PsrFree": 2010, "ProgramFlashSize": 4096,
+            "FlashSize": 4096, "FlashChipId": "164020", "FlashFrequency": 40,
+            "FlashMode": 3, "Features": ["0809", "9F9AD7DF"]
+        }}"#;
+
+        let response: StatusResponse = serde_json::from_str(json).unwrap();
+        let memory = response.memory.unwrap();
+        assert_eq!(memory.program_size, 1668);
+        assert_eq!(memory.free, 1216);
+        assert_eq!(memory.flash_frequency, 40);
+        assert_eq!(memory.flash_chip_id, "164020");
+        assert_eq!(memory.psram_size, Some(2047));
+        assert_eq!(memory.psram_free, Some(2010));
+        assert!(memory.fits_firmware(1216));
+        assert!(!memory.fits_firmware(1217));
+    }
+
+    #[test]
+    fn status_memory_without_psram() {
+        let json = r#"{"StatusMEM": {"ProgramSize": 616, "Free": 384, "Heap": 25}}"#;
+
+        let memory = serde_json::from_str::<StatusResponse>(json)
+            .unwrap()
+            .memory
+            .unwrap();
+        assert_eq!(memory.psram_size, None);
+        assert_eq!(memory.flash_frequency, 0);
+    }
+
+    #[test]
+    fn system_info_from_status_0() {
+        // Status 0 of a Sonoff Basic R2 running Tasmota 13.4.0
+        let json = r#"{"Status":{"Module":1,"DeviceName":"Tasmota","FriendlyName":["Tasmota"],"Topic":"tasmota_8A1B2C","ButtonTopic":"0","Power":"1","PowerLock":"0","PowerOnState":3,"LedState":1,"LedMask":"FFFF","SaveData":1,"SaveState":1,"SwitchTopic":"0","SwitchMode":[0,0,0,0,0,0,0,0],"ButtonRetain":0,"SwitchRetain":0,"SensorRetain":0,"PowerRetain":0,"InfoRetain":0,"StateRetain":0,"StatusRetain":0},
+            "StatusPRM":{"Baudrate":115200,"SerialConfig":"8N1","GroupTopic":"tasmotas","OtaUrl":"http://ota.tasmota.com/tasmota/release/tasmota.bin.gz","RestartReason":"Software/System restart","Uptime":"2T03:14:07","StartupUTC":"2024-03-10T08:12:51","Sleep":50,"CfgHolder":4617,"BootCount":27,"BCResetTime":"2023-11-02T19:40:12","SaveCount":412,"SaveAddress":"F9000"},
+            "StatusFWR":{"Version":"13.4.0(release-tasmota)","BuildDateTime":"2024-02-15T09:34:51","Boot":31,"Core":"2_7_6","SDK":"2.2.2-dev(38a443e)","CpuFrequency":80,"Hardware":"ESP8285N08","CR":"378/699"},
+            "StatusLOG":{"SerialLog":0,"WebLog":2,"MqttLog":0,"SysLog":0,"LogHost":"","LogPort":514,"SSId":["HomeNet",""],"TelePeriod":300,"Resolution":"558180C0","SetOption":["00008009","2805C80001000600003C5A0A192800000000","00000080","00006000","00004000","00000000"]},
+            "StatusMEM":{"ProgramSize":629,"Free":372,"Heap":25,"ProgramFlashSize":1024,"FlashSize":1024,"FlashChipId":"144051","FlashFrequency":40,"FlashMode":"DOUT","Features":["0809","8F9AC787","04368001","000000CF","010013C0","C000F981","00004004","00001000","04000020"],"Drivers":"1,2,3,4,5,6,7,8,9,10,12,16,18,19,20,21,22,24,26,27,29,30,35,37,45,62","Sensors":"1,2,3,4,5,6"},
+            "StatusNET":{"Hostname":"tasmota-8A1B2C-6956","IPAddress":"192.168.1.87","Gateway":"192.168.1.1","Subnetmask":"255.255.255.0","DNSServer1":"192.168.1.1","DNSServer2":"0.0.0.0","Mac":"C4:5B:BE:8A:1B:2C","Webserver":2,"HTTP_API":1,"WifiConfig":4,"WifiPower":17.0},
+            "StatusMQT":{"MqttHost":"192.168.1.10","MqttPort":1883,"MqttClientMask":"DVES_%06X","MqttClient":"DVES_8A1B2C","MqttUser":"DVES_USER","MqttCount":3,"MAX_PACKET_SIZE":1200,"KEEPALIVE":30,"SOCKET_TIMEOUT":4},
+            "StatusTIM":{"UTC":"2024-03-12T11:27:00Z","Local":"2024-03-12T12:27:00","StartDST":"2024-03-31T02:00:00","EndDST":"2024-10-27T03:00:00","Timezone":"+01:00","Sunrise":"07:02","Sunset":"18:41"},
+            "StatusSNS":{"Time":"2024-03-12T12:27:00"},
+            "StatusSTS":{"Time":"2024-03-12T12:27:00","Uptime":"2T03:14:07","UptimeSec":184447,"Heap":24,"SleepMode":"Dynamic","Sleep":50,"LoadAvg":19,"MqttCount":3,"POWER":"ON","Wifi":{"AP":1,"SSId":"HomeNet","BSSId":"60:A4:B7:1F:22:D0","Channel":6,"Mode":"11n","RSSI":72,"Signal":-64,"LinkCount":2,"Downtime":"0T00:00:09"}}}"#;
+
+        let response: StatusResponse = serde_json::from_str(json).unwrap();
+        let info = response.system_info();
+
+        assert_eq!(info.uptime(), Some(Duration::from_secs(184_447)));
+        assert_eq!(info.wifi_rssi(), Some(-64));
+        assert_eq!(info.heap(), Some(25));
+    }
+
+    #[test]
+    fn system_info_missing_fields_are_none() {
+        let response: StatusResponse =
+            serde_json::from_str(r#"{"StatusNET": {"Hostname": "tasmota"}}"#).unwrap();
+        assert_eq!(response.system_info(), SystemInfo::new());
+
+        let response: StatusResponse = serde_json::from_str(
+            r#"{"StatusPRM": {"Uptime": "0T00:10:00"}, "StatusNET": {"Signal": -70}}"#,
+        )
+        .unwrap();
+        let info = response.system_info();
+        assert_eq!(info.uptime(), Some(Duration::from_secs(600)));
+        assert_eq!(info.wifi_rssi(), Some(-70));
+    }
 }
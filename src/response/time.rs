@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Time synchronization response parsing.
+
+use chrono::Datelike;
+use serde::Deserialize;
+
+use crate::command::MAX_NTP_SERVERS;
+use crate::types::TasmotaDateTime;
+
+/// Earliest year a synchronized clock can report.
+///
+/// Until NTP succeeds, Tasmota counts from the Unix epoch (1970), so any
+/// time before this year means the clock was never set.
+const MIN_SYNCED_YEAR: i32 = 2016;
+
+/// Response from an `NtpServer<n>` command.
+///
+/// Only the field for the command that was sent is present; an empty value
+/// means the server is not set.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::NtpServerResponse;
+///
+/// let json = r#"{"NtpServer1":"pool.ntp.org"}"#;
+/// let response: NtpServerResponse = serde_json::from_str(json).unwrap();
+/// assert_eq!(response.server(1), Some("pool.ntp.org"));
+/// assert_eq!(response.server(2), None);
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NtpServerResponse {
+    #[serde(rename = "NtpServer1")]
+    server1: Option<String>,
+    #[serde(rename = "NtpServer2")]
+    server2: Option<String>,
+    #[serde(rename = "NtpServer3")]
+    server3: Option<String>,
+}
+
+impl NtpServerResponse {
+    /// Returns the server at `index` (1-3), if reported and set.
+    #[must_use]
+    pub fn server(&self, index: u8) -> Option<&str> {
+        let server = match index {
+            1 => &self.server1,
+            2 => &self.server2,
+            3 => &self.server3,
+            _ => &None,
+        };
+        server.as_deref().filter(|host| !host.is_empty())
+    }
+}
+
+/// A device's NTP servers and clock synchronization state.
+///
+/// Returned by [`Device::get_ntp_config`](crate::Device::get_ntp_config).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NtpConfig {
+    servers: Vec<Option<String>>,
+    utc: Option<TasmotaDateTime>,
+}
+
+impl NtpConfig {
+    /// Creates a configuration from the servers (in index order) and the
+    /// device's reported UTC time.
+    pub(crate) fn new(servers: Vec<Option<String>>, utc: &str) -> Self {
+        Self {
+            servers,
+            utc: TasmotaDateTime::parse(utc),
+        }
+    }
+
+    /// Returns the server at `index` (1-3), if set.
+    #[must_use]
+    pub fn server(&self, index: u8) -> Option<&str> {
+        if index == 0 || index > MAX_NTP_SERVERS {
+            return None;
+        }
+        self.servers
+            .get(usize::from(index - 1))
+            .and_then(Option::as_deref)
+    }
+
+    /// Returns the configured servers, in priority order.
+    pub fn servers(&self) -> impl Iterator<Item = &str> {
+        self.servers.iter().filter_map(Option::as_deref)
+    }
+
+    /// Returns the device's current UTC time, if it could be parsed.
+    #[must_use]
+    pub fn utc(&self) -> Option<&TasmotaDateTime> {
+        self.utc.as_ref()
+    }
+
+    /// Returns `true` if the device clock has been synchronized.
+    ///
+    /// A clock that was never set counts up from 1970, so this checks that
+    /// the device reports a plausible current date.
+    #[must_use]
+    pub fn time_synced(&self) -> bool {
+        self.utc
+            .as_ref()
+            .is_some_and(|utc| utc.naive().year() >= MIN_SYNCED_YEAR)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_server_is_not_set() {
+        let response: NtpServerResponse = serde_json::from_str(r#"{"NtpServer3":""}"#).unwrap();
+        assert_eq!(response.server(3), None);
+    }
+
+    #[test]
+    fn synced_clock() {
+        let config = NtpConfig::new(
+            vec![
+                Some("ntp.lan".to_string()),
+                None,
+                Some("pool.ntp.org".to_string()),
+            ],
+            "2024-06-01T08:00:00",
+        );
+
+        assert!(config.time_synced());
+        assert_eq!(config.server(2), None);
+        assert_eq!(
+            config.servers().collect::<Vec<_>>(),
+            ["ntp.lan", "pool.ntp.org"]
+        );
+    }
+
+    #[test]
+    fn unsynced_clock() {
+        let config = NtpConfig::new(vec![None, None, None], "1970-01-01T00:05:12");
+        assert!(!config.time_synced());
+
+        let config = NtpConfig::new(vec![None, None, None], "");
+        assert!(!config.time_synced());
+        assert!(config.utc().is_none());
+    }
+}
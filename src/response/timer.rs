@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Scheduled timer response parsing.
+
+use std::collections::BTreeMap;
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::command::Timer;
+
+/// Response from a `Timer<x>` command.
+///
+/// Tasmota reports the timer together with the global `Timers` switch:
+///
+/// ```json
+/// {"Timers":"ON","Timer1":{"Enable":1,"Mode":0,"Time":"07:00","Window":0,"Days":"0111110","Repeat":1,"Output":1,"Action":1}}
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::command::TimerAction;
+/// use tasmor_lib::response::TimerResponse;
+///
+/// let json = r#"{"Timers":"ON","Timer3":{"Enable":1,"Mode":0,"Time":"07:00","Window":0,
+///     "Days":"0111110","Repeat":1,"Output":1,"Action":1}}"#;
+/// let response: TimerResponse = serde_json::from_str(json).unwrap();
+///
+/// assert_eq!(response.index(), 3);
+/// assert_eq!(response.timer().action, TimerAction::On);
+/// assert_eq!(response.timers_enabled(), Some(true));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerResponse {
+    index: u8,
+    timer: Timer,
+    timers_enabled: Option<bool>,
+}
+
+impl TimerResponse {
+    /// Returns the timer number (1-16).
+    #[must_use]
+    pub const fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Returns the timer.
+    #[must_use]
+    pub const fn timer(&self) -> Timer {
+        self.timer
+    }
+
+    /// Returns whether timers are globally enabled, if reported.
+    ///
+    /// No timer fires while this is off, whatever its own `enable` flag.
+    #[must_use]
+    pub const fn timers_enabled(&self) -> Option<bool> {
+        self.timers_enabled
+    }
+}
+
+impl<'de> Deserialize<'de> for TimerResponse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map = BTreeMap::<String, Value>::deserialize(deserializer)?;
+        let (index, value) = map
+            .iter()
+            .find_map(|(key, value)| {
+                let index = key
+                    .get(..5)
+                    .filter(|prefix| prefix.eq_ignore_ascii_case("Timer"))
+                    .and_then(|_| key[5..].parse::<u8>().ok())?;
+                Some((index, value))
+            })
+            .ok_or_else(|| de::Error::missing_field("Timer"))?;
+
+        let timer = Timer::deserialize(value)
+            .map_err(|e| de::Error::custom(format!("Timer{index}: {e}")))?;
+        let timers_enabled = map
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("Timers"))
+            .and_then(|(_, value)| value.as_str())
+            .map(|state| state.eq_ignore_ascii_case("ON"));
+
+        Ok(Self {
+            index,
+            timer,
+            timers_enabled,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+
+    use super::*;
+    use crate::command::TimerAction;
+    use crate::types::PowerIndex;
+
+    #[test]
+    fn parse_timer_reply() {
+        let json = r#"{"Timers":"OFF","Timer12":{"Enable":0,"Mode":0,"Time":"22:15","Window":3,
+            "Days":"1000001","Repeat":0,"Output":2,"Action":2}}"#;
+        let response: TimerResponse = serde_json::from_str(json).unwrap();
+        let timer = response.timer();
+
+        assert_eq!(response.index(), 12);
+        assert_eq!(response.timers_enabled(), Some(false));
+        assert!(!timer.enable);
+        assert_eq!(timer.time, NaiveTime::from_hms_opt(22, 15, 0).unwrap());
+        assert_eq!(timer.window, 3);
+        assert_eq!(timer.days, Timer::WEEKEND);
+        assert!(!timer.repeat);
+        assert_eq!(timer.output, PowerIndex::new(2).unwrap());
+        assert_eq!(timer.action, TimerAction::Toggle);
+    }
+
+    #[test]
+    fn round_trips_a_set_timer() {
+        let timer = Timer {
+            days: Timer::WEEKDAYS,
+            ..Timer::new(
+                NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+                PowerIndex::one(),
+                TimerAction::On,
+            )
+        };
+        let json = format!(r#"{{"Timer1":{}}}"#, serde_json::to_string(&timer).unwrap());
+        let response: TimerResponse = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(response.timer(), timer);
+        assert_eq!(response.timers_enabled(), None);
+    }
+
+    #[test]
+    fn missing_timer_fails() {
+        assert!(serde_json::from_str::<TimerResponse>(r#"{"Timers":"ON"}"#).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! MQTT topic layout response parsing.
+
+use serde::Deserialize;
+
+use crate::types::{RemoteTopic, TopicPrefix};
+
+/// Response from a `FullTopic`, `Prefix<n>`, `GroupTopic`, `ButtonTopic` or
+/// `SwitchTopic` command.
+///
+/// Only the field for the command that was sent is present.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::TopicResponse;
+/// use tasmor_lib::types::TopicPrefix;
+///
+/// let json = r#"{"FullTopic":"home/%topic%/%prefix%/"}"#;
+/// let response: TopicResponse = serde_json::from_str(json).unwrap();
+/// assert_eq!(response.full_topic(), Some("home/%topic%/%prefix%/"));
+///
+/// let json = r#"{"Prefix2":"stat"}"#;
+/// let response: TopicResponse = serde_json::from_str(json).unwrap();
+/// assert_eq!(response.prefix(TopicPrefix::Stat), Some("stat"));
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct TopicResponse {
+    #[serde(rename = "FullTopic")]
+    full_topic: Option<String>,
+    #[serde(rename = "Prefix1")]
+    prefix1: Option<String>,
+    #[serde(rename = "Prefix2")]
+    prefix2: Option<String>,
+    #[serde(rename = "Prefix3")]
+    prefix3: Option<String>,
+    /// Tasmota 8.2+ replies with `GroupTopic1`, older firmware with `GroupTopic`.
+    #[serde(rename = "GroupTopic1", alias = "GroupTopic")]
+    group_topic: Option<String>,
+    #[serde(rename = "ButtonTopic")]
+    button_topic: Option<String>,
+    #[serde(rename = "SwitchTopic")]
+    switch_topic: Option<String>,
+}
+
+impl TopicResponse {
+    /// Returns the `FullTopic` template.
+    #[must_use]
+    pub fn full_topic(&self) -> Option<&str> {
+        self.full_topic.as_deref()
+    }
+
+    /// Returns the value of a topic prefix.
+    #[must_use]
+    pub fn prefix(&self, prefix: TopicPrefix) -> Option<&str> {
+        match prefix {
+            TopicPrefix::Command => self.prefix1.as_deref(),
+            TopicPrefix::Stat => self.prefix2.as_deref(),
+            TopicPrefix::Tele => self.prefix3.as_deref(),
+        }
+    }
+
+    /// Returns the first group topic.
+    #[must_use]
+    pub fn group_topic(&self) -> Option<&str> {
+        self.group_topic.as_deref()
+    }
+
+    /// Returns the topic buttons publish to.
+    #[must_use]
+    pub fn button_topic(&self) -> Option<RemoteTopic> {
+        self.button_topic.as_deref().map(RemoteTopic::from_reported)
+    }
+
+    /// Returns the topic switches publish to.
+    #[must_use]
+    pub fn switch_topic(&self) -> Option<RemoteTopic> {
+        self.switch_topic.as_deref().map(RemoteTopic::from_reported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_prefix() {
+        let json = r#"{"Prefix3":"telemetry"}"#;
+        let response: TopicResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.prefix(TopicPrefix::Tele), Some("telemetry"));
+        assert_eq!(response.prefix(TopicPrefix::Command), None);
+        assert_eq!(response.full_topic(), None);
+    }
+
+    #[test]
+    fn parse_group_topic() {
+        for json in [
+            r#"{"GroupTopic1":"tasmotas"}"#,
+            r#"{"GroupTopic":"tasmotas"}"#,
+        ] {
+            let response: TopicResponse = serde_json::from_str(json).unwrap();
+            assert_eq!(response.group_topic(), Some("tasmotas"));
+        }
+    }
+
+    #[test]
+    fn parse_remote_topics() {
+        let response: TopicResponse = serde_json::from_str(r#"{"ButtonTopic":"0"}"#).unwrap();
+        assert_eq!(response.button_topic(), Some(RemoteTopic::Disabled));
+        assert_eq!(response.switch_topic(), None);
+
+        let response: TopicResponse =
+            serde_json::from_str(r#"{"SwitchTopic":"hall_lights"}"#).unwrap();
+        assert_eq!(
+            response.switch_topic(),
+            Some(RemoteTopic::Topic("hall_lights".to_string()))
+        );
+    }
+}
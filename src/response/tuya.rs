@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tuya MCU response parsing.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+/// Response from a `TuyaMCU` command.
+///
+/// Tasmota always answers with the complete mapping list:
+///
+/// ```json
+/// {"TuyaMCU":[{"fnId":11,"dpId":1},{"fnId":21,"dpId":2}]}
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::TuyaMcuResponse;
+///
+/// let json = r#"{"TuyaMCU":[{"fnId":11,"dpId":1},{"fnId":21,"dpId":2}]}"#;
+/// let response: TuyaMcuResponse = serde_json::from_str(json).unwrap();
+///
+/// assert_eq!(response.function_for(2), Some(21));
+/// assert_eq!(response.dp_for(11), Some(1));
+/// assert_eq!(response.function_for(9), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct TuyaMcuResponse {
+    #[serde(rename = "TuyaMCU")]
+    mappings: Vec<TuyaMapping>,
+}
+
+/// A single data point to function mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct TuyaMapping {
+    #[serde(rename = "fnId")]
+    function: u8,
+    #[serde(rename = "dpId")]
+    dp_id: u8,
+}
+
+impl TuyaMapping {
+    /// Returns the Tasmota function ID.
+    #[must_use]
+    pub fn function(&self) -> u8 {
+        self.function
+    }
+
+    /// Returns the data point ID.
+    #[must_use]
+    pub fn dp_id(&self) -> u8 {
+        self.dp_id
+    }
+}
+
+impl TuyaMcuResponse {
+    /// Returns the mappings in the order reported by the device.
+    #[must_use]
+    pub fn mappings(&self) -> &[TuyaMapping] {
+        &self.mappings
+    }
+
+    /// Returns the function a data point is mapped to.
+    #[must_use]
+    pub fn function_for(&self, dp_id: u8) -> Option<u8> {
+        self.mappings
+            .iter()
+            .find(|mapping| mapping.dp_id == dp_id)
+            .map(TuyaMapping::function)
+    }
+
+    /// Returns the data point a function is mapped to.
+    #[must_use]
+    pub fn dp_for(&self, function: u8) -> Option<u8> {
+        self.mappings
+            .iter()
+            .find(|mapping| mapping.function == function)
+            .map(TuyaMapping::dp_id)
+    }
+
+    /// Returns the mappings as a data point to function map.
+    ///
+    /// This is the lookup needed to interpret `TuyaReceived` reports.
+    #[must_use]
+    pub fn dp_map(&self) -> BTreeMap<u8, u8> {
+        self.mappings
+            .iter()
+            .map(|mapping| (mapping.dp_id, mapping.function))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_mappings() {
+        let json =
+            r#"{"TuyaMCU":[{"fnId":11,"dpId":1},{"fnId":21,"dpId":2},{"fnId":71,"dpId":101}]}"#;
+        let response: TuyaMcuResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(response.mappings().len(), 3);
+        assert_eq!(response.mappings()[2].function(), 71);
+        assert_eq!(
+            response.dp_map(),
+            BTreeMap::from([(1, 11), (2, 21), (101, 71)])
+        );
+    }
+
+    #[test]
+    fn parse_empty_mappings() {
+        let response: TuyaMcuResponse = serde_json::from_str(r#"{"TuyaMCU":[]}"#).unwrap();
+        assert!(response.mappings().is_empty());
+        assert_eq!(response.dp_for(11), None);
+    }
+}
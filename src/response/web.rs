@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Web UI response parsing.
+
+use serde::Deserialize;
+
+use crate::error::ParseError;
+use crate::types::{WEB_COLOR_COUNT, WebColors};
+
+/// Response from a `WebColor` command.
+///
+/// Tasmota returns the theme as an array of hex colors like
+/// `{"WebColor":["#eaeaea","#252525",...]}`.
+///
+/// Recent firmware appends the page title color as an extra entry after the
+/// [`WEB_COLOR_COUNT`] theme colors; it is kept in [`raw`](Self::raw) but not
+/// part of [`colors`](Self::colors).
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::WebColorResponse;
+/// use tasmor_lib::types::WebColors;
+///
+/// let json = WebColors::dark().to_command_string();
+/// let response: WebColorResponse = serde_json::from_str(&json).unwrap();
+/// assert_eq!(response.colors().unwrap(), WebColors::dark());
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebColorResponse {
+    #[serde(rename = "WebColor")]
+    web_color: Vec<String>,
+}
+
+impl WebColorResponse {
+    /// Returns the theme colors.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if fewer than [`WEB_COLOR_COUNT`] colors were
+    /// returned or an entry is not a valid hex color.
+    pub fn colors(&self) -> Result<WebColors, ParseError> {
+        let theme = self
+            .web_color
+            .get(..WEB_COLOR_COUNT)
+            .unwrap_or(&self.web_color);
+        WebColors::from_hex(theme).map_err(|e| ParseError::InvalidValue {
+            field: "WebColor".to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Returns the hex color strings exactly as reported by the device.
+    #[must_use]
+    pub fn raw(&self) -> &[String] {
+        &self.web_color
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RgbColor;
+
+    #[test]
+    fn parse_with_title_color() {
+        let mut colors = vec!["#000000"; WEB_COLOR_COUNT];
+        colors[1] = "#ffffff";
+        colors.push("#eaeaea");
+        let json = serde_json::json!({ "WebColor": colors }).to_string();
+
+        let response: WebColorResponse = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(response.raw().len(), WEB_COLOR_COUNT + 1);
+        assert_eq!(response.colors().unwrap().colors()[1], RgbColor::white());
+    }
+
+    #[test]
+    fn parse_too_few_colors_fails() {
+        let json = r##"{"WebColor":["#000000","#ffffff"]}"##;
+        let response: WebColorResponse = serde_json::from_str(json).unwrap();
+        assert!(response.colors().is_err());
+    }
+
+    #[test]
+    fn parse_invalid_hex_fails() {
+        let mut colors = vec!["#000000"; WEB_COLOR_COUNT];
+        colors[3] = "#zzzzzz";
+        let json = serde_json::json!({ "WebColor": colors }).to_string();
+
+        let response: WebColorResponse = serde_json::from_str(&json).unwrap();
+        assert!(response.colors().is_err());
+    }
+}
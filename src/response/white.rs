@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! White level response parsing.
+
+use serde::Deserialize;
+
+use crate::error::ParseError;
+use crate::types::PowerState;
+
+/// Response from a `White` command.
+///
+/// Tasmota returns the white channel brightness, usually with the rest of
+/// the light state:
+/// - `{"White": 50}` for a white-only response
+/// - `{"POWER": "ON", "Dimmer": 50, "White": 50, "CT": 327}` when the light
+///   state is included
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::response::WhiteResponse;
+///
+/// let json = r#"{"POWER": "ON", "Dimmer": 50, "White": 50}"#;
+/// let response: WhiteResponse = serde_json::from_str(json).unwrap();
+/// assert_eq!(response.white(), 50);
+/// assert_eq!(response.is_on(), Some(true));
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct WhiteResponse {
+    /// The white channel brightness (0-100).
+    #[serde(rename = "White")]
+    white: u8,
+
+    /// Optional dimmer level included in the response.
+    #[serde(rename = "Dimmer", default)]
+    dimmer: Option<u8>,
+
+    /// Optional power state included in the response.
+    #[serde(rename = "POWER", default)]
+    power: Option<String>,
+}
+
+impl WhiteResponse {
+    /// Returns the white channel brightness (0-100).
+    #[must_use]
+    pub fn white(&self) -> u8 {
+        self.white
+    }
+
+    /// Returns the dimmer level if included in the response.
+    #[must_use]
+    pub fn dimmer(&self) -> Option<u8> {
+        self.dimmer
+    }
+
+    /// Returns the power state if included in the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if the power state string is invalid.
+    pub fn power_state(&self) -> Result<Option<PowerState>, ParseError> {
+        match &self.power {
+            Some(s) => s
+                .parse::<PowerState>()
+                .map(Some)
+                .map_err(|_| ParseError::InvalidValue {
+                    field: "POWER".to_string(),
+                    message: format!("invalid power state: {s}"),
+                }),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns `true` if the device is on according to the response.
+    ///
+    /// Returns `None` if power state was not included in the response.
+    #[must_use]
+    pub fn is_on(&self) -> Option<bool> {
+        self.power.as_ref().map(|s| s == "ON")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_white_only() {
+        let response: WhiteResponse = serde_json::from_str(r#"{"White":50}"#).unwrap();
+
+        assert_eq!(response.white(), 50);
+        assert_eq!(response.dimmer(), None);
+        assert!(response.power_state().unwrap().is_none());
+    }
+
+    #[test]
+    fn parse_white_with_light_state() {
+        let json = r#"{
+            "POWER": "ON",
+            "Dimmer": 50,
+            "Color": "00000080",
+            "HSBColor": "0,0,0",
+            "White": 50,
+            "CT": 153,
+            "Channel": [0, 0, 0, 50]
+        }"#;
+        let response: WhiteResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(response.white(), 50);
+        assert_eq!(response.dimmer(), Some(50));
+        assert_eq!(response.power_state().unwrap(), Some(PowerState::On));
+    }
+
+    #[test]
+    fn missing_white_fails() {
+        assert!(serde_json::from_str::<WhiteResponse>(r#"{"Dimmer":50}"#).is_err());
+    }
+}
@@ -45,6 +45,7 @@
 //! assert_eq!(state.dimmer().map(|d| d.value()), Some(80));
 //! ```
 
+use std::fmt;
 use std::time::Duration;
 
 use crate::types::{
@@ -71,11 +72,11 @@ use super::StateChange;
 /// use tasmor_lib::state::SystemInfo;
 ///
 /// let info = SystemInfo::new()
-///     .with_uptime(Duration::from_secs(172800))
+///     .with_uptime(Duration::from_secs(172_800))
 ///     .with_wifi_rssi(-60)
 ///     .with_heap(25000);
 ///
-/// assert_eq!(info.uptime(), Some(Duration::from_secs(172800)));
+/// assert_eq!(info.uptime(), Some(Duration::from_secs(172_800)));
 /// ```
 #[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct SystemInfo {
@@ -160,7 +161,7 @@ impl SystemInfo {
     /// use std::time::Duration;
     /// use tasmor_lib::state::SystemInfo;
     ///
-    /// let info = SystemInfo::new().with_uptime(Duration::from_secs(172800));
+    /// let info = SystemInfo::new().with_uptime(Duration::from_secs(172_800));
     ///
     /// let uptime = info.uptime().unwrap();
     /// println!("Uptime: {} days", uptime.as_secs() / 86400);
@@ -271,6 +272,8 @@ pub struct DeviceState {
     ///
     /// This is read-only data that does **not** trigger callbacks.
     system_info: Option<SystemInfo>,
+    /// Device name (`DeviceName`), for display.
+    device_name: Option<String>,
 }
 
 impl DeviceState {
@@ -609,7 +612,7 @@ impl DeviceState {
     /// use tasmor_lib::state::{DeviceState, SystemInfo};
     ///
     /// let mut state = DeviceState::new();
-    /// state.set_system_info(SystemInfo::new().with_uptime(Duration::from_secs(172800)));
+    /// state.set_system_info(SystemInfo::new().with_uptime(Duration::from_secs(172_800)));
     ///
     /// if let Some(info) = state.system_info() {
     ///     let uptime = info.uptime().unwrap_or(Duration::ZERO);
@@ -649,7 +652,7 @@ impl DeviceState {
     /// use tasmor_lib::state::{DeviceState, SystemInfo};
     ///
     /// let mut state = DeviceState::new();
-    /// state.set_system_info(SystemInfo::new().with_uptime(Duration::from_secs(172800)));
+    /// state.set_system_info(SystemInfo::new().with_uptime(Duration::from_secs(172_800)));
     ///
     /// let uptime = state.uptime().unwrap();
     /// println!("Uptime: {} days", uptime.as_secs() / 86400);
@@ -659,6 +662,23 @@ impl DeviceState {
         self.system_info.as_ref().and_then(SystemInfo::uptime)
     }
 
+    // ========== Device Name ==========
+
+    /// Gets the device name.
+    ///
+    /// This is the name of the device as a whole (`DeviceName`), not the
+    /// per-relay `FriendlyName` labels. It does **not** trigger callbacks
+    /// when updated.
+    #[must_use]
+    pub fn device_name(&self) -> Option<&str> {
+        self.device_name.as_deref()
+    }
+
+    /// Sets the device name.
+    pub fn set_device_name(&mut self, name: impl Into<String>) {
+        self.device_name = Some(name.into());
+    }
+
     // ========== State Changes ==========
 
     /// Applies a state change and returns whether the state actually changed.
@@ -801,12 +821,194 @@ impl DeviceState {
     pub fn clear(&mut self) {
         *self = Self::new();
     }
+
+    /// Returns the known state as a list of changes.
+    ///
+    /// Applying the changes to an empty state reproduces this state (apart
+    /// from system info and device name, which are not state changes). This
+    /// lets a full refresh, such as [`Device::query_state`], be forwarded to
+    /// code that only consumes [`StateChange`]s.
+    ///
+    /// [`Device::query_state`]: crate::Device::query_state
+    #[must_use]
+    pub fn to_state_changes(&self) -> Vec<StateChange> {
+        let mut changes: Vec<StateChange> = self
+            .all_power_states()
+            .into_iter()
+            .map(|(index, state)| StateChange::power(index, state))
+            .collect();
+
+        changes.extend(self.dimmer.map(StateChange::Dimmer));
+        changes.extend(self.hsb_color.map(StateChange::HsbColor));
+        changes.extend(self.color_temperature.map(StateChange::ColorTemperature));
+        changes.extend(self.scheme.map(StateChange::Scheme));
+        changes.extend(self.wakeup_duration.map(StateChange::WakeupDuration));
+        changes.extend(self.fade_enabled.map(StateChange::FadeEnabled));
+        changes.extend(self.fade_duration.map(StateChange::FadeDuration));
+
+        let energy = StateChange::Energy {
+            power: self.power_consumption,
+            voltage: self.voltage,
+            current: self.current,
+            apparent_power: self.apparent_power,
+            reactive_power: self.reactive_power,
+            power_factor: self.power_factor,
+            energy_today: self.energy_today,
+            energy_yesterday: self.energy_yesterday,
+            energy_total: self.energy_total,
+            total_start_time: self.total_start_time.clone(),
+            frequency: self.frequency,
+        };
+        if DeviceState::new().apply(&energy) {
+            changes.push(energy);
+        }
+
+        changes
+    }
+
+    // ========== Formatting ==========
+
+    /// Returns a compact one-line summary of the known state.
+    ///
+    /// Fields are written as space-separated `Key=value` pairs using
+    /// Tasmota's field names, in a fixed order, so the output is easy to
+    /// grep in logs. Unknown fields are omitted. A single relay is shown as
+    /// `POWER`; when several relays are known each one is listed as
+    /// `POWER<n>`. This is the same text as the [`Display`](fmt::Display)
+    /// implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tasmor_lib::state::DeviceState;
+    /// use tasmor_lib::types::{ColorTemperature, Dimmer, PowerState};
+    ///
+    /// let mut state = DeviceState::new();
+    /// state.set_power(1, PowerState::On);
+    /// state.set_dimmer(Dimmer::new(75).unwrap());
+    /// state.set_color_temperature(ColorTemperature::new(300).unwrap());
+    /// state.set_power_consumption(150.0);
+    ///
+    /// assert_eq!(state.summary(), "POWER=ON Dimmer=75 CT=300 Energy=150W");
+    /// ```
+    #[must_use]
+    pub fn summary(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for DeviceState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fields: Vec<String> = Vec::new();
+
+        let powers = self.all_power_states();
+        match powers.as_slice() {
+            [(1, state)] => fields.push(format!("POWER={state}")),
+            _ => fields.extend(
+                powers
+                    .iter()
+                    .map(|(index, state)| format!("POWER{index}={state}")),
+            ),
+        }
+
+        if let Some(dimmer) = self.dimmer {
+            fields.push(format!("Dimmer={}", dimmer.value()));
+        }
+        if let Some(color) = self.hsb_color {
+            fields.push(format!(
+                "HSBColor={},{},{}",
+                color.hue(),
+                color.saturation(),
+                color.brightness()
+            ));
+        }
+        if let Some(ct) = self.color_temperature {
+            fields.push(format!("CT={}", ct.value()));
+        }
+        if let Some(scheme) = self.scheme {
+            fields.push(format!("Scheme={}", scheme.value()));
+        }
+        if let Some(fade) = self.fade_enabled {
+            let fade = if fade {
+                PowerState::On
+            } else {
+                PowerState::Off
+            };
+            fields.push(format!("Fade={fade}"));
+        }
+        if let Some(watts) = self.power_consumption {
+            fields.push(format!("Energy={watts}W"));
+        }
+        if let Some(volts) = self.voltage {
+            fields.push(format!("Voltage={volts}V"));
+        }
+        if let Some(amps) = self.current {
+            fields.push(format!("Current={amps}A"));
+        }
+        if let Some(kwh) = self.energy_today {
+            fields.push(format!("Today={kwh}kWh"));
+        }
+        if let Some(kwh) = self.energy_total {
+            fields.push(format!("Total={kwh}kWh"));
+        }
+
+        f.write_str(&fields.join(" "))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn to_state_changes_round_trip() {
+        let mut state = DeviceState::new();
+        state.set_power(1, PowerState::On);
+        state.set_power(2, PowerState::Off);
+        state.set_dimmer(Dimmer::new(40).unwrap());
+        state.set_color_temperature(ColorTemperature::WARM);
+        state.set_fade_enabled(true);
+        state.set_voltage(230.0);
+
+        let changes = state.to_state_changes();
+        assert_eq!(changes.len(), 6);
+
+        let mut rebuilt = DeviceState::new();
+        for change in &changes {
+            rebuilt.apply(change);
+        }
+        assert_eq!(rebuilt, state);
+        assert!(DeviceState::new().to_state_changes().is_empty());
+    }
+
+    #[test]
+    fn summary_of_empty_state_is_empty() {
+        assert_eq!(DeviceState::new().summary(), "");
+    }
+
+    #[test]
+    fn summary_lists_each_relay_when_several_are_known() {
+        let mut state = DeviceState::new();
+        state.set_power(1, PowerState::On);
+        state.set_power(3, PowerState::Off);
+        state.set_hsb_color(HsbColor::new(120, 100, 50).unwrap());
+        state.set_voltage(230.5);
+
+        assert_eq!(
+            state.summary(),
+            "POWER1=ON POWER3=OFF HSBColor=120,100,50 Voltage=230.5V"
+        );
+    }
+
+    #[test]
+    fn summary_names_single_relay_by_index_if_not_first() {
+        let mut state = DeviceState::new();
+        state.set_power(2, PowerState::On);
+        state.set_fade_enabled(true);
+
+        assert_eq!(state.to_string(), "POWER2=ON Fade=ON");
+    }
+
     #[test]
     fn new_state_is_empty() {
         let state = DeviceState::new();
@@ -1077,12 +1279,12 @@ mod tests {
     #[test]
     fn system_info_builder_pattern() {
         let info = SystemInfo::new()
-            .with_uptime(Duration::from_secs(172800))
+            .with_uptime(Duration::from_secs(172_800))
             .with_wifi_rssi(-55)
             .with_heap(25000);
 
         assert!(!info.is_empty());
-        assert_eq!(info.uptime(), Some(Duration::from_secs(172800)));
+        assert_eq!(info.uptime(), Some(Duration::from_secs(172_800)));
         assert_eq!(info.wifi_rssi(), Some(-55));
         assert_eq!(info.heap(), Some(25000));
     }
@@ -1130,11 +1332,11 @@ mod tests {
         assert!(state.uptime().is_none());
 
         // Set system info
-        let info = SystemInfo::new().with_uptime(Duration::from_secs(172800));
+        let info = SystemInfo::new().with_uptime(Duration::from_secs(172_800));
         state.set_system_info(info);
 
         assert!(state.system_info().is_some());
-        assert_eq!(state.uptime(), Some(Duration::from_secs(172800)));
+        assert_eq!(state.uptime(), Some(Duration::from_secs(172_800)));
     }
 
     #[test]
@@ -1158,7 +1360,7 @@ mod tests {
     #[test]
     fn device_state_clear_clears_system_info() {
         let mut state = DeviceState::new();
-        state.set_system_info(SystemInfo::new().with_uptime(Duration::from_secs(172800)));
+        state.set_system_info(SystemInfo::new().with_uptime(Duration::from_secs(172_800)));
 
         state.clear();
 
@@ -1168,7 +1370,7 @@ mod tests {
     #[test]
     fn system_info_serialization() {
         let info = SystemInfo::new()
-            .with_uptime(Duration::from_secs(172800))
+            .with_uptime(Duration::from_secs(172_800))
             .with_wifi_rssi(-55)
             .with_heap(25000);
 
@@ -1184,7 +1386,7 @@ mod tests {
         state.set_power(1, PowerState::On);
         state.set_system_info(
             SystemInfo::new()
-                .with_uptime(Duration::from_secs(172800))
+                .with_uptime(Duration::from_secs(172_800))
                 .with_wifi_rssi(-55),
         );
 
@@ -1192,6 +1394,6 @@ mod tests {
         let deserialized: DeviceState = serde_json::from_str(&json).unwrap();
 
         assert_eq!(state, deserialized);
-        assert_eq!(deserialized.uptime(), Some(Duration::from_secs(172800)));
+        assert_eq!(deserialized.uptime(), Some(Duration::from_secs(172_800)));
     }
 }
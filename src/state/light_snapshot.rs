@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Exact capture of a light's output.
+//!
+//! [`LightSnapshot`] records the raw `Channel` values of a light next to its
+//! power, dimmer, color, color temperature and scheme, so the output can be
+//! replayed precisely on the same fixture or mirrored on an identical one
+//! with [`Device::apply_light_snapshot`](crate::Device::apply_light_snapshot).
+
+use crate::telemetry::TelemetryState;
+use crate::types::{ColorTemperature, Dimmer, HsbColor, PowerState, Scheme};
+
+/// Everything needed to reproduce a light's output.
+///
+/// Unlike [`LightState`](crate::state::LightState), a snapshot keeps the raw
+/// channel values (e.g. red, green, blue, cold white, warm white), which
+/// reproduce mixed white and color outputs that HSB and CT alone cannot
+/// express.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::state::LightSnapshot;
+/// use tasmor_lib::types::{ColorTemperature, Dimmer};
+///
+/// let snapshot = LightSnapshot::new()
+///     .with_dimmer(Dimmer::new(60).unwrap())
+///     .with_color_temperature(ColorTemperature::WARM)
+///     .with_channels(vec![0, 0, 0, 12, 48]);
+///
+/// assert_eq!(snapshot.channels(), [0, 0, 0, 12, 48]);
+/// assert!(snapshot.is_white());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LightSnapshot {
+    power: Option<PowerState>,
+    dimmer: Option<Dimmer>,
+    channels: Vec<u8>,
+    hsb_color: Option<HsbColor>,
+    color_temperature: Option<ColorTemperature>,
+    scheme: Option<Scheme>,
+}
+
+impl LightSnapshot {
+    /// Creates an empty snapshot with all values unknown.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Extracts a snapshot from a `State` response.
+    ///
+    /// Power is taken from the first relay (POWER1). Out-of-range values
+    /// are treated as unknown.
+    #[must_use]
+    pub fn from_telemetry(state: &TelemetryState) -> Self {
+        Self {
+            power: state.power(),
+            dimmer: state.dimmer().and_then(|value| Dimmer::new(value).ok()),
+            channels: state.channels().map(<[u8]>::to_vec).unwrap_or_default(),
+            hsb_color: state.hsb_color(),
+            color_temperature: state
+                .color_temp()
+                .and_then(|value| ColorTemperature::new(value).ok()),
+            scheme: state.scheme().and_then(|value| Scheme::new(value).ok()),
+        }
+    }
+
+    /// Sets the power state.
+    #[must_use]
+    pub fn with_power(mut self, power: PowerState) -> Self {
+        self.power = Some(power);
+        self
+    }
+
+    /// Sets the dimmer level.
+    #[must_use]
+    pub fn with_dimmer(mut self, dimmer: Dimmer) -> Self {
+        self.dimmer = Some(dimmer);
+        self
+    }
+
+    /// Sets the raw channel values (0-100), in device order.
+    #[must_use]
+    pub fn with_channels(mut self, channels: Vec<u8>) -> Self {
+        self.channels = channels;
+        self
+    }
+
+    /// Sets the HSB color.
+    #[must_use]
+    pub fn with_hsb_color(mut self, color: HsbColor) -> Self {
+        self.hsb_color = Some(color);
+        self
+    }
+
+    /// Sets the color temperature.
+    #[must_use]
+    pub fn with_color_temperature(mut self, ct: ColorTemperature) -> Self {
+        self.color_temperature = Some(ct);
+        self
+    }
+
+    /// Sets the scheme.
+    #[must_use]
+    pub fn with_scheme(mut self, scheme: Scheme) -> Self {
+        self.scheme = Some(scheme);
+        self
+    }
+
+    /// Returns the power state.
+    #[must_use]
+    pub fn power(&self) -> Option<PowerState> {
+        self.power
+    }
+
+    /// Returns the dimmer level.
+    #[must_use]
+    pub fn dimmer(&self) -> Option<Dimmer> {
+        self.dimmer
+    }
+
+    /// Returns the raw channel values (0-100), empty if unknown.
+    #[must_use]
+    pub fn channels(&self) -> &[u8] {
+        &self.channels
+    }
+
+    /// Returns the HSB color.
+    #[must_use]
+    pub fn hsb_color(&self) -> Option<HsbColor> {
+        self.hsb_color
+    }
+
+    /// Returns the color temperature.
+    #[must_use]
+    pub fn color_temperature(&self) -> Option<ColorTemperature> {
+        self.color_temperature
+    }
+
+    /// Returns the scheme.
+    #[must_use]
+    pub fn scheme(&self) -> Option<Scheme> {
+        self.scheme
+    }
+
+    /// Returns `true` if the light outputs white only.
+    ///
+    /// One- and two-channel lights are always white. Lights with white
+    /// channels after RGB are white when the RGB channels are off. Without
+    /// channel values, a snapshot with a color temperature and no color is
+    /// considered white.
+    #[must_use]
+    pub fn is_white(&self) -> bool {
+        match self.channels.len() {
+            0 => self.hsb_color.is_none() && self.color_temperature.is_some(),
+            1 | 2 => true,
+            3 => false,
+            _ => self.channels[..3].iter().all(|&value| value == 0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_telemetry_reads_all_light_fields() {
+        let json = r#"{"POWER":"ON","Dimmer":40,"Color":"00000066A3","HSBColor":"0,0,0",
+            "Channel":[0,0,0,40,64],"CT":327,"Scheme":0}"#;
+        let state: TelemetryState = serde_json::from_str(json).unwrap();
+
+        let snapshot = LightSnapshot::from_telemetry(&state);
+
+        assert_eq!(snapshot.power(), Some(PowerState::On));
+        assert_eq!(snapshot.dimmer(), Some(Dimmer::new(40).unwrap()));
+        assert_eq!(snapshot.channels(), [0, 0, 0, 40, 64]);
+        assert_eq!(snapshot.color_temperature().map(|ct| ct.value()), Some(327));
+        assert_eq!(snapshot.scheme(), Some(Scheme::SINGLE));
+        assert!(snapshot.is_white());
+    }
+
+    #[test]
+    fn missing_fields_are_unknown() {
+        let state: TelemetryState = serde_json::from_str(r#"{"POWER":"OFF"}"#).unwrap();
+
+        let snapshot = LightSnapshot::from_telemetry(&state);
+
+        assert_eq!(snapshot.power(), Some(PowerState::Off));
+        assert!(snapshot.channels().is_empty());
+        assert_eq!(snapshot.dimmer(), None);
+        assert!(!snapshot.is_white());
+    }
+
+    #[test]
+    fn white_detection_by_channel_layout() {
+        let rgbww = LightSnapshot::new().with_channels(vec![100, 0, 0, 0, 0]);
+        assert!(!rgbww.is_white());
+
+        let rgb = LightSnapshot::new().with_channels(vec![0, 0, 0]);
+        assert!(!rgb.is_white());
+
+        let cct = LightSnapshot::new().with_channels(vec![30, 70]);
+        assert!(cct.is_white());
+    }
+}
@@ -0,0 +1,375 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Snapshot of a light's user-controllable state.
+//!
+//! [`LightState`] groups the power, dimmer, color and color temperature of a
+//! light so they can be read, modified and written back together. It is the
+//! value passed to [`Device::update_light`](crate::Device::update_light).
+
+use crate::state::{DeviceState, StateChange};
+use crate::types::{ColorTemperature, Dimmer, HsbColor, PowerState};
+
+/// Largest [`LightState::distance`] at which two states still match.
+///
+/// Allows for the rounding Tasmota applies when converting between color
+/// representations (e.g. a dimmer of 50 reported back as 49).
+const MATCH_TOLERANCE: u32 = 10;
+
+/// Which of two light states was chosen.
+///
+/// Returned by [`Device::toggle_between`](crate::Device::toggle_between).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LightChoice {
+    /// The first state.
+    A,
+    /// The second state.
+    B,
+}
+
+/// The controllable state of a light: power, dimmer, color and CT.
+///
+/// Fields are `None` when the value is unknown or the device does not support
+/// the corresponding capability.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::state::LightState;
+/// use tasmor_lib::types::{Dimmer, PowerState};
+///
+/// let current = LightState::new()
+///     .with_power(PowerState::On)
+///     .with_dimmer(Dimmer::new(80).unwrap());
+///
+/// // Dim by 20%
+/// let dimmed = current.with_dimmer(Dimmer::clamped(60));
+///
+/// // Only the dimmer differs
+/// assert_eq!(dimmed.changes_from(&current).len(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LightState {
+    power: Option<PowerState>,
+    dimmer: Option<Dimmer>,
+    hsb_color: Option<HsbColor>,
+    color_temperature: Option<ColorTemperature>,
+}
+
+impl LightState {
+    /// Creates an empty light state with all values unknown.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Extracts the light state from a device state.
+    ///
+    /// Power is taken from the first relay (POWER1).
+    #[must_use]
+    pub fn from_device_state(state: &DeviceState) -> Self {
+        Self {
+            power: state.power(1),
+            dimmer: state.dimmer(),
+            hsb_color: state.hsb_color(),
+            color_temperature: state.color_temperature(),
+        }
+    }
+
+    /// Sets the power state.
+    #[must_use]
+    pub fn with_power(mut self, power: PowerState) -> Self {
+        self.power = Some(power);
+        self
+    }
+
+    /// Sets the dimmer level.
+    #[must_use]
+    pub fn with_dimmer(mut self, dimmer: Dimmer) -> Self {
+        self.dimmer = Some(dimmer);
+        self
+    }
+
+    /// Sets the HSB color.
+    #[must_use]
+    pub fn with_hsb_color(mut self, color: HsbColor) -> Self {
+        self.hsb_color = Some(color);
+        self
+    }
+
+    /// Sets the color temperature.
+    #[must_use]
+    pub fn with_color_temperature(mut self, ct: ColorTemperature) -> Self {
+        self.color_temperature = Some(ct);
+        self
+    }
+
+    /// Returns the power state.
+    #[must_use]
+    pub fn power(&self) -> Option<PowerState> {
+        self.power
+    }
+
+    /// Returns the dimmer level.
+    #[must_use]
+    pub fn dimmer(&self) -> Option<Dimmer> {
+        self.dimmer
+    }
+
+    /// Returns the HSB color.
+    #[must_use]
+    pub fn hsb_color(&self) -> Option<HsbColor> {
+        self.hsb_color
+    }
+
+    /// Returns the color temperature.
+    #[must_use]
+    pub fn color_temperature(&self) -> Option<ColorTemperature> {
+        self.color_temperature
+    }
+
+    /// Returns the changes needed to go from `previous` to this state.
+    ///
+    /// Only fields that are set in `self` and differ from `previous` produce
+    /// a change. The changes are ordered so they can be sent to the device
+    /// as-is: turning on comes first, then color temperature, color and
+    /// dimmer (so an explicit dimmer wins over the color's brightness), and
+    /// turning off comes last.
+    #[must_use]
+    pub fn changes_from(&self, previous: &LightState) -> Vec<StateChange> {
+        let power_change = self
+            .power
+            .filter(|power| previous.power != Some(*power))
+            .map(|power| StateChange::power(1, power));
+        let (power_first, power_last) = match self.power {
+            Some(PowerState::Off) => (None, power_change),
+            _ => (power_change, None),
+        };
+
+        let ct = self
+            .color_temperature
+            .filter(|ct| previous.color_temperature != Some(*ct))
+            .map(StateChange::color_temperature);
+        let color = self
+            .hsb_color
+            .filter(|color| previous.hsb_color != Some(*color))
+            .map(StateChange::hsb_color);
+        let dimmer = self
+            .dimmer
+            .filter(|dimmer| previous.dimmer != Some(*dimmer))
+            .map(StateChange::dimmer);
+
+        [power_first, ct, color, dimmer, power_last]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Returns how far this state is from `other`.
+    ///
+    /// Only fields known in both states are compared. Each contributes its
+    /// difference in percentage points: 100 for a different power state, the
+    /// dimmer difference, the color temperature difference scaled to its
+    /// range, and the hue (scaled, taking the shortest way around the
+    /// circle), saturation and brightness differences. Identical states
+    /// have a distance of 0.
+    #[must_use]
+    pub fn distance(&self, other: &LightState) -> u32 {
+        let power = match (self.power, other.power) {
+            (Some(a), Some(b)) if a != b => 100,
+            _ => 0,
+        };
+        let dimmer = match (self.dimmer, other.dimmer) {
+            (Some(a), Some(b)) => u32::from(a.value().abs_diff(b.value())),
+            _ => 0,
+        };
+        let ct = match (self.color_temperature, other.color_temperature) {
+            (Some(a), Some(b)) => {
+                u32::from(a.value().abs_diff(b.value())) * 100
+                    / u32::from(ColorTemperature::MAX - ColorTemperature::MIN)
+            }
+            _ => 0,
+        };
+        let color = match (self.hsb_color, other.hsb_color) {
+            (Some(a), Some(b)) => {
+                let hue = a.hue().abs_diff(b.hue()) % HsbColor::MAX_HUE;
+                let hue = hue.min(HsbColor::MAX_HUE - hue);
+                u32::from(hue) * 100 / u32::from(HsbColor::MAX_HUE / 2)
+                    + u32::from(a.saturation().abs_diff(b.saturation()))
+                    + u32::from(a.brightness().abs_diff(b.brightness()))
+            }
+            _ => 0,
+        };
+        power + dimmer + ct + color
+    }
+
+    /// Returns `true` if `other` is within rounding distance of this state.
+    ///
+    /// See [`distance`](Self::distance).
+    #[must_use]
+    pub fn matches(&self, other: &LightState) -> bool {
+        self.distance(other) <= MATCH_TOLERANCE
+    }
+
+    /// Picks the state to switch to from `current`, toggling between `a`
+    /// and `b`.
+    ///
+    /// Returns [`LightChoice::A`] if `current` matches `b` more closely than
+    /// `a`, or matches neither, and [`LightChoice::B`] otherwise.
+    #[must_use]
+    pub fn toggle_target(current: &LightState, a: &LightState, b: &LightState) -> LightChoice {
+        let to_a = a.distance(current);
+        let to_b = b.distance(current);
+        if to_a <= MATCH_TOLERANCE && to_a <= to_b {
+            LightChoice::B
+        } else {
+            LightChoice::A
+        }
+    }
+
+    /// Writes the known fields of this light state into a device state.
+    ///
+    /// Fields that are `None` leave the device state untouched.
+    pub fn apply_to(&self, state: &mut DeviceState) {
+        if let Some(power) = self.power {
+            state.set_power(1, power);
+        }
+        if let Some(dimmer) = self.dimmer {
+            state.set_dimmer(dimmer);
+        }
+        if let Some(color) = self.hsb_color {
+            state.set_hsb_color(color);
+        }
+        if let Some(ct) = self.color_temperature {
+            state.set_color_temperature(ct);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on_at(dimmer: u8) -> LightState {
+        LightState::new()
+            .with_power(PowerState::On)
+            .with_dimmer(Dimmer::new(dimmer).unwrap())
+    }
+
+    #[test]
+    fn unchanged_state_has_no_changes() {
+        let state = on_at(50).with_hsb_color(HsbColor::red());
+        assert!(state.changes_from(&state).is_empty());
+    }
+
+    #[test]
+    fn only_differing_fields_are_reported() {
+        let current = on_at(80).with_hsb_color(HsbColor::blue());
+        let updated = current.with_dimmer(Dimmer::new(60).unwrap());
+
+        let changes = updated.changes_from(&current);
+
+        assert_eq!(changes, vec![StateChange::dimmer(Dimmer::new(60).unwrap())]);
+    }
+
+    #[test]
+    fn unknown_fields_are_not_written() {
+        let current = on_at(80).with_color_temperature(ColorTemperature::WARM);
+        let updated = LightState::new().with_dimmer(Dimmer::new(20).unwrap());
+
+        let changes = updated.changes_from(&current);
+
+        assert_eq!(changes, vec![StateChange::dimmer(Dimmer::new(20).unwrap())]);
+    }
+
+    #[test]
+    fn power_on_is_first_and_dimmer_after_color() {
+        let current = LightState::new().with_power(PowerState::Off);
+        let updated = LightState::new()
+            .with_power(PowerState::On)
+            .with_dimmer(Dimmer::new(30).unwrap())
+            .with_hsb_color(HsbColor::green())
+            .with_color_temperature(ColorTemperature::COOL);
+
+        let changes = updated.changes_from(&current);
+
+        assert_eq!(
+            changes,
+            vec![
+                StateChange::power(1, PowerState::On),
+                StateChange::color_temperature(ColorTemperature::COOL),
+                StateChange::hsb_color(HsbColor::green()),
+                StateChange::dimmer(Dimmer::new(30).unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn power_off_is_last() {
+        let current = on_at(80);
+        let updated = current
+            .with_dimmer(Dimmer::new(10).unwrap())
+            .with_power(PowerState::Off);
+
+        let changes = updated.changes_from(&current);
+
+        assert_eq!(
+            changes,
+            vec![
+                StateChange::dimmer(Dimmer::new(10).unwrap()),
+                StateChange::power(1, PowerState::Off),
+            ]
+        );
+    }
+
+    #[test]
+    fn distance_compares_known_fields() {
+        let state = on_at(50).with_hsb_color(HsbColor::red());
+
+        assert_eq!(state.distance(&state), 0);
+        assert_eq!(state.distance(&LightState::new()), 0);
+        assert_eq!(state.distance(&on_at(45)), 5);
+        assert_eq!(state.distance(&state.with_power(PowerState::Off)), 100);
+        // Hue 350 is 10 degrees from red, the short way round
+        let near_red = HsbColor::new(350, 100, 100).unwrap();
+        assert_eq!(state.distance(&state.with_hsb_color(near_red)), 5);
+    }
+
+    #[test]
+    fn toggle_target_picks_the_other_state() {
+        let movie = on_at(20).with_color_temperature(ColorTemperature::CANDLE);
+        let normal = on_at(100).with_color_temperature(ColorTemperature::NEUTRAL);
+
+        assert_eq!(
+            LightState::toggle_target(&movie, &movie, &normal),
+            LightChoice::B
+        );
+        assert_eq!(
+            LightState::toggle_target(&on_at(98), &movie, &normal),
+            LightChoice::A
+        );
+        // Matches neither: fall back to the first state
+        assert_eq!(
+            LightState::toggle_target(
+                &LightState::new().with_power(PowerState::Off),
+                &movie,
+                &normal
+            ),
+            LightChoice::A
+        );
+    }
+
+    #[test]
+    fn device_state_round_trip() {
+        let light = on_at(42)
+            .with_hsb_color(HsbColor::red())
+            .with_color_temperature(ColorTemperature::NEUTRAL);
+
+        let mut state = DeviceState::new();
+        light.apply_to(&mut state);
+
+        assert_eq!(LightState::from_device_state(&state), light);
+    }
+}
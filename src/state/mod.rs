@@ -25,7 +25,11 @@
 //! ```
 
 mod device_state;
+mod light_snapshot;
+mod light_state;
 mod state_change;
 
 pub use device_state::{DeviceState, SystemInfo};
+pub use light_snapshot::LightSnapshot;
+pub use light_state::{LightChoice, LightState};
 pub use state_change::StateChange;
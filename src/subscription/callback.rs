@@ -12,12 +12,17 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use tokio::sync::broadcast;
 
+use crate::error::ValueError;
 use crate::state::{DeviceState, StateChange};
-use crate::types::{ColorTemperature, Dimmer, HsbColor, PowerState, Scheme};
+use crate::telemetry::TuyaDataPoint;
+use crate::types::{
+    ButtonAction, ColorTemperature, Dimmer, HsbColor, PowerIndex, PowerState, Scheme,
+};
 
 /// Unique identifier for a subscription.
 ///
@@ -102,6 +107,15 @@ type ReconnectedCallback = Arc<dyn Fn() + Send + Sync>;
 /// Type alias for generic state change callbacks.
 type StateChangedCallback = Arc<dyn Fn(&StateChange) + Send + Sync>;
 
+/// Type alias for Tuya MCU data point callbacks.
+type TuyaCallback = Arc<dyn Fn(&TuyaDataPoint) + Send + Sync>;
+
+/// Type alias for button press callbacks (button number, action).
+type ButtonCallback = Arc<dyn Fn(u8, ButtonAction) + Send + Sync>;
+
+/// Type alias for raw RESULT callbacks (device topic, parsed payload).
+type ResultCallback = Arc<dyn Fn(&str, &serde_json::Value) + Send + Sync>;
+
 /// Energy data passed to energy callbacks.
 #[derive(Debug, Clone)]
 pub struct EnergyData {
@@ -119,6 +133,10 @@ pub struct EnergyData {
     pub frequency: Option<f32>,
 }
 
+/// Default number of state changes buffered for each
+/// [`state_stream`](CallbackRegistry::state_stream) receiver.
+pub const DEFAULT_STATE_STREAM_CAPACITY: usize = 64;
+
 /// Registry for managing device subscription callbacks.
 ///
 /// This is an internal type used by devices to store and dispatch callbacks.
@@ -152,12 +170,31 @@ pub struct CallbackRegistry {
     reconnected_callbacks: RwLock<HashMap<SubscriptionId, ReconnectedCallback>>,
     /// Generic state change callbacks (receives all changes).
     state_changed_callbacks: RwLock<HashMap<SubscriptionId, StateChangedCallback>>,
+    /// Tuya MCU data point callbacks.
+    tuya_callbacks: RwLock<HashMap<SubscriptionId, TuyaCallback>>,
+    /// Detached button press callbacks.
+    button_callbacks: RwLock<HashMap<SubscriptionId, ButtonCallback>>,
+    /// Raw RESULT message callbacks.
+    result_callbacks: RwLock<HashMap<SubscriptionId, ResultCallback>>,
+    /// Whether state change dispatch is paused.
+    paused: AtomicBool,
+    /// Latest state received while paused, dispatched on resume.
+    pending: Mutex<DeviceState>,
+    /// Sender feeding the state change streams.
+    state_tx: broadcast::Sender<StateChange>,
 }
 
 impl CallbackRegistry {
     /// Creates a new empty callback registry.
     #[must_use]
     pub fn new() -> Self {
+        Self::with_state_stream_capacity(DEFAULT_STATE_STREAM_CAPACITY)
+    }
+
+    /// Creates a new empty callback registry whose state streams buffer
+    /// up to `capacity` changes (at least 1).
+    #[must_use]
+    pub fn with_state_stream_capacity(capacity: usize) -> Self {
         Self {
             next_id: AtomicU64::new(1),
             power_callbacks: RwLock::new(HashMap::new()),
@@ -170,6 +207,12 @@ impl CallbackRegistry {
             disconnected_callbacks: RwLock::new(HashMap::new()),
             reconnected_callbacks: RwLock::new(HashMap::new()),
             state_changed_callbacks: RwLock::new(HashMap::new()),
+            tuya_callbacks: RwLock::new(HashMap::new()),
+            button_callbacks: RwLock::new(HashMap::new()),
+            result_callbacks: RwLock::new(HashMap::new()),
+            paused: AtomicBool::new(false),
+            pending: Mutex::new(DeviceState::new()),
+            state_tx: broadcast::channel(capacity.max(1)).0,
         }
     }
 
@@ -194,6 +237,35 @@ impl CallbackRegistry {
         id
     }
 
+    /// Registers a callback for power state changes of a single relay.
+    ///
+    /// Changes of other relays are filtered out before the callback runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `index` is not 1-8.
+    pub fn on_power_index_changed<F>(
+        &self,
+        index: u8,
+        callback: F,
+    ) -> Result<SubscriptionId, ValueError>
+    where
+        F: Fn(PowerState) + Send + Sync + 'static,
+    {
+        if !(1..=PowerIndex::MAX).contains(&index) {
+            return Err(ValueError::OutOfRange {
+                min: 1,
+                max: u16::from(PowerIndex::MAX),
+                actual: u16::from(index),
+            });
+        }
+        Ok(self.on_power_changed(move |changed, state| {
+            if changed == index {
+                callback(state);
+            }
+        }))
+    }
+
     /// Registers a callback for dimmer changes.
     pub fn on_dimmer_changed<F>(&self, callback: F) -> SubscriptionId
     where
@@ -331,6 +403,52 @@ impl CallbackRegistry {
         id
     }
 
+    /// Returns a receiver of every state change dispatched from now on.
+    ///
+    /// The changes are the ones [`on_state_changed`](Self::on_state_changed)
+    /// callbacks receive, in the same order. Each receiver buffers up to the
+    /// registry's capacity; a receiver that falls further behind loses the
+    /// oldest changes and its next `recv()` returns
+    /// [`RecvError::Lagged`](broadcast::error::RecvError::Lagged) with the
+    /// number of changes skipped, after which it continues with the oldest
+    /// change still buffered.
+    #[must_use]
+    pub fn state_stream(&self) -> broadcast::Receiver<StateChange> {
+        self.state_tx.subscribe()
+    }
+
+    /// Registers a callback for Tuya MCU data point reports.
+    pub fn on_tuya_received<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&TuyaDataPoint) + Send + Sync + 'static,
+    {
+        let id = self.next_id();
+        self.tuya_callbacks.write().insert(id, Arc::new(callback));
+        id
+    }
+
+    /// Registers a callback for presses of detached buttons.
+    ///
+    /// The callback receives the button number (1-32) and the action.
+    pub fn on_button_action<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(u8, ButtonAction) + Send + Sync + 'static,
+    {
+        let id = self.next_id();
+        self.button_callbacks.write().insert(id, Arc::new(callback));
+        id
+    }
+
+    /// Registers a callback for raw `stat/<topic>/RESULT` messages.
+    pub fn on_result<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&str, &serde_json::Value) + Send + Sync + 'static,
+    {
+        let id = self.next_id();
+        self.result_callbacks.write().insert(id, Arc::new(callback));
+        id
+    }
+
     // =========================================================================
     // Unsubscription
     // =========================================================================
@@ -370,6 +488,15 @@ impl CallbackRegistry {
         if self.state_changed_callbacks.write().remove(&id).is_some() {
             return true;
         }
+        if self.tuya_callbacks.write().remove(&id).is_some() {
+            return true;
+        }
+        if self.button_callbacks.write().remove(&id).is_some() {
+            return true;
+        }
+        if self.result_callbacks.write().remove(&id).is_some() {
+            return true;
+        }
         false
     }
 
@@ -385,17 +512,68 @@ impl CallbackRegistry {
         self.disconnected_callbacks.write().clear();
         self.reconnected_callbacks.write().clear();
         self.state_changed_callbacks.write().clear();
+        self.tuya_callbacks.write().clear();
+        self.button_callbacks.write().clear();
+        self.result_callbacks.write().clear();
     }
 
     // =========================================================================
     // Dispatch methods
     // =========================================================================
 
+    /// Pauses state change dispatch.
+    ///
+    /// Until [`resume`](Self::resume) is called, [`dispatch`](Self::dispatch)
+    /// only records the latest value of each field instead of calling the
+    /// callbacks. Other events (connection, buttons, Tuya, RESULT) are still
+    /// dispatched.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Resumes state change dispatch.
+    ///
+    /// Each field that changed while paused is dispatched once, with its
+    /// latest value. Does nothing if dispatch was not paused.
+    pub fn resume(&self) {
+        let pending = {
+            let mut pending = self.pending.lock();
+            if !self.paused.swap(false, Ordering::SeqCst) {
+                return;
+            }
+            std::mem::take(&mut *pending)
+        };
+        for change in pending.to_state_changes() {
+            self.dispatch(&change);
+        }
+    }
+
+    /// Returns `true` if state change dispatch is paused.
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
     /// Dispatches a state change to relevant callbacks.
     ///
     /// This method calls all registered callbacks that match the change type.
     /// Callbacks are called synchronously in an arbitrary order.
+    ///
+    /// While [paused](Self::pause), the change is recorded instead.
     pub fn dispatch(&self, change: &StateChange) {
+        if self.paused.load(Ordering::SeqCst) {
+            let mut pending = self.pending.lock();
+            // Checked again under the lock, so a concurrent resume cannot
+            // miss the change
+            if self.paused.load(Ordering::SeqCst) {
+                pending.apply(change);
+                return;
+            }
+        }
+
+        // Without receivers the send fails, which is fine
+        let _ = self.state_tx.send(change.clone());
+
         // Always dispatch to generic state_changed callbacks
         {
             let callbacks = self.state_changed_callbacks.read();
@@ -499,6 +677,30 @@ impl CallbackRegistry {
         }
     }
 
+    /// Dispatches a data point reported by a Tuya MCU.
+    pub fn dispatch_tuya(&self, data_point: &TuyaDataPoint) {
+        let callbacks = self.tuya_callbacks.read();
+        for callback in callbacks.values() {
+            callback(data_point);
+        }
+    }
+
+    /// Dispatches a press of a detached button.
+    pub fn dispatch_button(&self, index: u8, action: ButtonAction) {
+        let callbacks = self.button_callbacks.read();
+        for callback in callbacks.values() {
+            callback(index, action);
+        }
+    }
+
+    /// Dispatches a raw RESULT message received from `device_topic`.
+    pub fn dispatch_result(&self, device_topic: &str, payload: &serde_json::Value) {
+        let callbacks = self.result_callbacks.read();
+        for callback in callbacks.values() {
+            callback(device_topic, payload);
+        }
+    }
+
     // =========================================================================
     // Statistics
     // =========================================================================
@@ -516,6 +718,9 @@ impl CallbackRegistry {
             + self.disconnected_callbacks.read().len()
             + self.reconnected_callbacks.read().len()
             + self.state_changed_callbacks.read().len()
+            + self.tuya_callbacks.read().len()
+            + self.button_callbacks.read().len()
+            + self.result_callbacks.read().len()
     }
 
     /// Returns `true` if there are no registered callbacks.
@@ -605,6 +810,67 @@ mod tests {
         assert_eq!(counter.load(Ordering::SeqCst), 1);
     }
 
+    #[test]
+    fn registry_power_index_callback() {
+        let registry = CallbackRegistry::new();
+        let received = Arc::new(RwLock::new(Vec::new()));
+        let received_clone = received.clone();
+
+        registry
+            .on_power_index_changed(2, move |state| received_clone.write().push(state))
+            .unwrap();
+
+        registry.dispatch(&StateChange::power(1, PowerState::On));
+        registry.dispatch(&StateChange::power(2, PowerState::On));
+        registry.dispatch(&StateChange::power(3, PowerState::Off));
+        assert_eq!(*received.read(), vec![PowerState::On]);
+
+        assert!(registry.on_power_index_changed(0, |_| {}).is_err());
+        assert!(registry.on_power_index_changed(9, |_| {}).is_err());
+        assert_eq!(registry.callback_count(), 1);
+    }
+
+    #[test]
+    fn registry_pause_coalesces_changes() {
+        let registry = CallbackRegistry::new();
+        let received = Arc::new(RwLock::new(Vec::new()));
+        let received_clone = received.clone();
+
+        registry.on_dimmer_changed(move |dimmer| received_clone.write().push(dimmer.value()));
+
+        registry.pause();
+        assert!(registry.is_paused());
+        for value in [10, 40, 70] {
+            registry.dispatch(&StateChange::dimmer(Dimmer::new(value).unwrap()));
+        }
+        assert!(received.read().is_empty());
+
+        registry.resume();
+        assert!(!registry.is_paused());
+        assert_eq!(*received.read(), vec![70]);
+
+        // Resuming again does not replay anything
+        registry.resume();
+        registry.dispatch(&StateChange::dimmer(Dimmer::new(20).unwrap()));
+        assert_eq!(*received.read(), vec![70, 20]);
+    }
+
+    #[test]
+    fn registry_button_callback() {
+        let registry = CallbackRegistry::new();
+        let received = Arc::new(RwLock::new(Vec::new()));
+        let received_clone = received.clone();
+
+        let id = registry.on_button_action(move |index, action| {
+            received_clone.write().push((index, action));
+        });
+        registry.dispatch_button(2, ButtonAction::Hold);
+        assert_eq!(*received.read(), vec![(2, ButtonAction::Hold)]);
+
+        assert!(registry.unsubscribe(id));
+        assert!(registry.is_empty());
+    }
+
     #[test]
     fn registry_dimmer_callback() {
         let registry = CallbackRegistry::new();
@@ -840,4 +1106,41 @@ mod tests {
         assert!(debug.contains("EnergyData"));
         assert!(debug.contains("100.0"));
     }
+
+    #[tokio::test]
+    async fn state_stream_receives_dispatched_changes() {
+        let registry = CallbackRegistry::new();
+        let mut rx = registry.state_stream();
+
+        registry.dispatch(&StateChange::power(1, PowerState::On));
+        registry.dispatch(&StateChange::dimmer(Dimmer::new(40).unwrap()));
+
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            StateChange::power(1, PowerState::On)
+        );
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            StateChange::dimmer(Dimmer::new(40).unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn lagging_state_stream_drops_oldest_changes() {
+        let registry = CallbackRegistry::with_state_stream_capacity(2);
+        let mut rx = registry.state_stream();
+
+        for level in [10, 20, 30] {
+            registry.dispatch(&StateChange::dimmer(Dimmer::new(level).unwrap()));
+        }
+
+        assert!(matches!(
+            rx.recv().await,
+            Err(broadcast::error::RecvError::Lagged(1))
+        ));
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            StateChange::dimmer(Dimmer::new(20).unwrap())
+        );
+    }
 }
@@ -0,0 +1,404 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Rolling analysis of power readings.
+
+use std::collections::VecDeque;
+
+use super::EnergyData;
+
+/// Start or end of an appliance cycle detected by [`EnergyAnalyzer`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CycleTransition {
+    /// The average power rose above the "on" threshold.
+    Started {
+        /// Rolling average power in Watts when the cycle started.
+        power: f32,
+    },
+    /// The average power fell below the "off" threshold.
+    Finished {
+        /// Rolling average power in Watts when the cycle finished.
+        power: f32,
+    },
+}
+
+/// Turns a stream of power readings into appliance events.
+///
+/// Keeps the last `window_size` readings and answers questions about them:
+/// whether the latest reading is a spike over the recent average, whether
+/// the load has been idle, and when an appliance cycle (a fridge compressor
+/// run, a kettle boiling) starts or finishes. Cycles use two thresholds on
+/// the rolling average, so noise around a single threshold does not toggle
+/// the cycle back and forth.
+///
+/// Feed it from [`on_energy_changed`](super::Subscribable::on_energy_changed)
+/// with [`push_energy`](Self::push_energy), or with any other source of
+/// readings through [`push`](Self::push).
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::subscription::{CycleTransition, EnergyAnalyzer};
+///
+/// let mut kettle = EnergyAnalyzer::new(3).with_cycle_thresholds(100.0, 20.0);
+///
+/// for watts in [1.0, 1.0, 2000.0, 2000.0, 2000.0] {
+///     kettle.push(watts);
+/// }
+/// assert!(kettle.is_running());
+///
+/// let mut finished = None;
+/// for watts in [1.0, 1.0, 1.0] {
+///     finished = finished.or(kettle.push(watts));
+/// }
+/// assert!(matches!(finished, Some(CycleTransition::Finished { .. })));
+/// assert!(kettle.is_idle(5.0));
+/// ```
+///
+/// Feeding it from MQTT energy updates:
+///
+/// ```no_run
+/// use std::sync::{Arc, Mutex};
+/// use tasmor_lib::MqttBroker;
+/// use tasmor_lib::subscription::{CycleTransition, EnergyAnalyzer, Subscribable};
+///
+/// # async fn example() -> tasmor_lib::Result<()> {
+/// let broker = MqttBroker::builder().host("192.168.1.50").build().await?;
+/// let (washer, _) = broker.device("washer_plug").build().await?;
+///
+/// let analyzer = Arc::new(Mutex::new(EnergyAnalyzer::new(6)));
+/// washer.on_energy_changed(move |energy| {
+///     let mut analyzer = analyzer.lock().unwrap();
+///     if let Some(CycleTransition::Finished { .. }) = analyzer.push_energy(&energy) {
+///         println!("Laundry is done");
+///     }
+/// });
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct EnergyAnalyzer {
+    window: VecDeque<f32>,
+    window_size: usize,
+    spike_factor: f32,
+    min_spike: f32,
+    on_threshold: f32,
+    off_threshold: f32,
+    running: bool,
+    last_transition: Option<CycleTransition>,
+}
+
+impl EnergyAnalyzer {
+    /// Largest number of readings kept.
+    pub const MAX_WINDOW_SIZE: usize = u16::MAX as usize;
+
+    /// Default factor over the average for a reading to count as a spike.
+    pub const DEFAULT_SPIKE_FACTOR: f32 = 2.0;
+
+    /// Default minimum rise in Watts for a reading to count as a spike.
+    pub const DEFAULT_MIN_SPIKE: f32 = 20.0;
+
+    /// Default average power in Watts above which a cycle starts.
+    pub const DEFAULT_ON_THRESHOLD: f32 = 10.0;
+
+    /// Default average power in Watts below which a cycle finishes.
+    pub const DEFAULT_OFF_THRESHOLD: f32 = 5.0;
+
+    /// Creates an analyzer keeping the last `window_size` readings.
+    ///
+    /// A window size of 0 is raised to 1, and sizes above
+    /// [`MAX_WINDOW_SIZE`](Self::MAX_WINDOW_SIZE) are lowered to it.
+    #[must_use]
+    pub fn new(window_size: usize) -> Self {
+        let window_size = window_size.clamp(1, Self::MAX_WINDOW_SIZE);
+        Self {
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+            spike_factor: Self::DEFAULT_SPIKE_FACTOR,
+            min_spike: Self::DEFAULT_MIN_SPIKE,
+            on_threshold: Self::DEFAULT_ON_THRESHOLD,
+            off_threshold: Self::DEFAULT_OFF_THRESHOLD,
+            running: false,
+            last_transition: None,
+        }
+    }
+
+    /// Sets when a reading counts as a spike: at least `factor` times the
+    /// average of the earlier readings and at least `min_watts` above it.
+    #[must_use]
+    pub fn with_spike(mut self, factor: f32, min_watts: f32) -> Self {
+        self.spike_factor = factor;
+        self.min_spike = min_watts;
+        self
+    }
+
+    /// Sets the average power in Watts above which a cycle starts (`on`) and
+    /// below which it finishes (`off`).
+    ///
+    /// `off` is lowered to `on` if it is higher.
+    #[must_use]
+    pub fn with_cycle_thresholds(mut self, on: f32, off: f32) -> Self {
+        self.on_threshold = on;
+        self.off_threshold = off.min(on);
+        self
+    }
+
+    /// Adds a reading in Watts and returns the cycle transition it caused.
+    pub fn push(&mut self, power: f32) -> Option<CycleTransition> {
+        if self.window.len() == self.window_size {
+            self.window.pop_front();
+        }
+        self.window.push_back(power);
+
+        self.last_transition = self.average().and_then(|average| {
+            if !self.running && average > self.on_threshold {
+                self.running = true;
+                Some(CycleTransition::Started { power: average })
+            } else if self.running && average < self.off_threshold {
+                self.running = false;
+                Some(CycleTransition::Finished { power: average })
+            } else {
+                None
+            }
+        });
+        self.last_transition
+    }
+
+    /// Adds the power of an energy update, if it carries one.
+    pub fn push_energy(&mut self, energy: &EnergyData) -> Option<CycleTransition> {
+        energy.power.and_then(|power| self.push(power))
+    }
+
+    /// Returns the cycle transition caused by the latest reading.
+    #[must_use]
+    pub fn detect_cycle(&self) -> Option<CycleTransition> {
+        self.last_transition
+    }
+
+    /// Returns `true` while a cycle is in progress.
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Returns `true` if the latest reading is a spike over the average of
+    /// the earlier readings in the window.
+    ///
+    /// Needs at least two readings.
+    #[must_use]
+    pub fn is_spike(&self) -> bool {
+        let Some(latest) = self.latest() else {
+            return false;
+        };
+        let earlier = self.window.iter().take(self.window.len() - 1).copied();
+        let Some(baseline) = mean(earlier) else {
+            return false;
+        };
+        latest - baseline >= self.min_spike && latest >= baseline * self.spike_factor
+    }
+
+    /// Returns `true` if the window is full and no reading exceeds
+    /// `threshold` Watts.
+    ///
+    /// A small but non-zero threshold detects standby ("phantom") loads.
+    #[must_use]
+    pub fn is_idle(&self, threshold: f32) -> bool {
+        self.window.len() == self.window_size && self.window.iter().all(|&power| power <= threshold)
+    }
+
+    /// Returns the average of the readings in the window.
+    #[must_use]
+    pub fn average(&self) -> Option<f32> {
+        mean(self.readings())
+    }
+
+    /// Returns the latest reading.
+    #[must_use]
+    pub fn latest(&self) -> Option<f32> {
+        self.window.back().copied()
+    }
+
+    /// Returns the readings in the window, oldest first.
+    pub fn readings(&self) -> impl Iterator<Item = f32> + '_ {
+        self.window.iter().copied()
+    }
+
+    /// Forgets all readings and any cycle in progress.
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.running = false;
+        self.last_transition = None;
+    }
+}
+
+/// Mean of `values`, or `None` if empty.
+fn mean(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let (sum, count) = values.fold((0.0, 0_u16), |(sum, count), value| {
+        (sum + value, count.saturating_add(1))
+    });
+    (count > 0).then(|| sum / f32::from(count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(analyzer: &mut EnergyAnalyzer, readings: &[f32]) -> Vec<CycleTransition> {
+        readings
+            .iter()
+            .filter_map(|&power| analyzer.push(power))
+            .collect()
+    }
+
+    #[test]
+    fn average_covers_only_the_window() {
+        let mut analyzer = EnergyAnalyzer::new(3);
+        assert_eq!(analyzer.average(), None);
+
+        feed(&mut analyzer, &[100.0, 10.0, 20.0, 30.0]);
+
+        assert_eq!(analyzer.average(), Some(20.0));
+        assert_eq!(analyzer.readings().collect::<Vec<_>>(), [10.0, 20.0, 30.0]);
+        assert_eq!(analyzer.latest(), Some(30.0));
+    }
+
+    #[test]
+    fn zero_window_keeps_one_reading() {
+        let mut analyzer = EnergyAnalyzer::new(0);
+        feed(&mut analyzer, &[5.0, 7.0]);
+        assert_eq!(analyzer.readings().collect::<Vec<_>>(), [7.0]);
+    }
+
+    #[test]
+    fn spike_needs_factor_and_minimum_rise() {
+        let mut analyzer = EnergyAnalyzer::new(5);
+        feed(&mut analyzer, &[50.0, 50.0, 50.0]);
+        assert!(!analyzer.is_spike());
+
+        // Double the average but only 10 W more
+        let mut small = EnergyAnalyzer::new(5);
+        feed(&mut small, &[10.0, 10.0, 20.0]);
+        assert!(!small.is_spike());
+
+        analyzer.push(150.0);
+        assert!(analyzer.is_spike());
+
+        // Once the new level holds, it becomes the baseline
+        feed(&mut analyzer, &[150.0, 150.0]);
+        assert!(!analyzer.is_spike());
+    }
+
+    #[test]
+    fn single_reading_is_not_a_spike() {
+        let mut analyzer = EnergyAnalyzer::new(5);
+        analyzer.push(2000.0);
+        assert!(!analyzer.is_spike());
+    }
+
+    #[test]
+    fn custom_spike_settings() {
+        let mut analyzer = EnergyAnalyzer::new(4).with_spike(1.5, 5.0);
+        feed(&mut analyzer, &[10.0, 10.0, 16.0]);
+        assert!(analyzer.is_spike());
+    }
+
+    #[test]
+    fn idle_needs_full_window_below_threshold() {
+        let mut analyzer = EnergyAnalyzer::new(3);
+        feed(&mut analyzer, &[0.5, 0.4]);
+        assert!(!analyzer.is_idle(1.0));
+
+        analyzer.push(0.6);
+        assert!(analyzer.is_idle(1.0));
+        assert!(!analyzer.is_idle(0.5));
+
+        analyzer.push(40.0);
+        assert!(!analyzer.is_idle(1.0));
+    }
+
+    #[test]
+    fn fridge_compressor_cycles() {
+        let mut analyzer = EnergyAnalyzer::new(3).with_cycle_thresholds(40.0, 20.0);
+        let off = [2.0; 6];
+        let on = [90.0, 95.0, 88.0, 92.0, 90.0, 91.0];
+
+        let mut readings = Vec::new();
+        for _ in 0..2 {
+            readings.extend_from_slice(&off);
+            readings.extend_from_slice(&on);
+        }
+        readings.extend_from_slice(&off);
+
+        let transitions = feed(&mut analyzer, &readings);
+
+        assert_eq!(transitions.len(), 4);
+        assert!(matches!(transitions[0], CycleTransition::Started { .. }));
+        assert!(matches!(transitions[1], CycleTransition::Finished { .. }));
+        assert!(matches!(transitions[2], CycleTransition::Started { .. }));
+        assert!(matches!(transitions[3], CycleTransition::Finished { .. }));
+        assert!(!analyzer.is_running());
+    }
+
+    #[test]
+    fn noise_between_thresholds_does_not_toggle() {
+        let mut analyzer = EnergyAnalyzer::new(1).with_cycle_thresholds(40.0, 20.0);
+        let transitions = feed(&mut analyzer, &[50.0, 35.0, 25.0, 38.0, 22.0, 30.0]);
+
+        assert_eq!(transitions, [CycleTransition::Started { power: 50.0 }]);
+        assert!(analyzer.is_running());
+    }
+
+    #[test]
+    fn detect_cycle_reports_latest_reading_only() {
+        let mut analyzer = EnergyAnalyzer::new(1);
+
+        analyzer.push(100.0);
+        assert_eq!(
+            analyzer.detect_cycle(),
+            Some(CycleTransition::Started { power: 100.0 })
+        );
+
+        analyzer.push(100.0);
+        assert_eq!(analyzer.detect_cycle(), None);
+    }
+
+    #[test]
+    fn energy_updates_without_power_are_ignored() {
+        let mut analyzer = EnergyAnalyzer::new(2);
+        let energy = EnergyData {
+            power: None,
+            voltage: Some(230.0),
+            current: None,
+            energy_today: None,
+            energy_total: None,
+            frequency: None,
+        };
+
+        assert_eq!(analyzer.push_energy(&energy), None);
+        assert_eq!(analyzer.latest(), None);
+
+        let energy = EnergyData {
+            power: Some(1500.0),
+            ..energy
+        };
+        assert!(matches!(
+            analyzer.push_energy(&energy),
+            Some(CycleTransition::Started { .. })
+        ));
+    }
+
+    #[test]
+    fn reset_forgets_readings_and_cycle() {
+        let mut analyzer = EnergyAnalyzer::new(2);
+        feed(&mut analyzer, &[100.0, 100.0]);
+        assert!(analyzer.is_running());
+
+        analyzer.reset();
+
+        assert!(!analyzer.is_running());
+        assert_eq!(analyzer.average(), None);
+        assert_eq!(analyzer.detect_cycle(), None);
+    }
+}
@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Scoped subscriptions that unsubscribe on drop.
+
+use std::sync::{Arc, Weak};
+
+use crate::subscription::{CallbackRegistry, SubscriptionId};
+
+/// A subscription that is removed when the guard is dropped.
+///
+/// Returned by the `subscribe_*_scoped` methods of MQTT devices. The callback
+/// stays registered for as long as the guard is alive, so early returns and
+/// panics cannot leak it. Call [`detach`](Self::detach) to keep the
+/// subscription and manage it manually by its [`SubscriptionId`].
+///
+/// The guard does not keep the device alive: if the device and all its clones
+/// are dropped first, dropping the guard does nothing.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use tasmor_lib::subscription::{CallbackRegistry, SubscriptionGuard};
+///
+/// let registry = Arc::new(CallbackRegistry::new());
+///
+/// {
+///     let id = registry.on_power_changed(|_, _| {});
+///     let _guard = SubscriptionGuard::new(&registry, id);
+///     assert_eq!(registry.callback_count(), 1);
+/// }
+///
+/// // Unsubscribed when the guard went out of scope
+/// assert!(registry.is_empty());
+/// ```
+#[derive(Debug)]
+#[must_use = "dropping the guard immediately unsubscribes the callback"]
+pub struct SubscriptionGuard {
+    registry: Weak<CallbackRegistry>,
+    id: SubscriptionId,
+}
+
+impl SubscriptionGuard {
+    /// Creates a guard that removes `id` from `registry` when dropped.
+    pub fn new(registry: &Arc<CallbackRegistry>, id: SubscriptionId) -> Self {
+        Self {
+            registry: Arc::downgrade(registry),
+            id,
+        }
+    }
+
+    /// Returns the ID of the guarded subscription.
+    #[must_use]
+    pub fn id(&self) -> SubscriptionId {
+        self.id
+    }
+
+    /// Releases the guard without unsubscribing.
+    ///
+    /// The callback stays registered until it is removed manually with the
+    /// returned ID.
+    #[must_use]
+    pub fn detach(mut self) -> SubscriptionId {
+        self.registry = Weak::new();
+        self.id
+    }
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        if let Some(registry) = self.registry.upgrade() {
+            registry.unsubscribe(self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drop_unsubscribes() {
+        let registry = Arc::new(CallbackRegistry::new());
+        let guard = SubscriptionGuard::new(&registry, registry.on_dimmer_changed(|_| {}));
+        assert_eq!(registry.callback_count(), 1);
+
+        drop(guard);
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn detach_keeps_subscription() {
+        let registry = Arc::new(CallbackRegistry::new());
+        let guard = SubscriptionGuard::new(&registry, registry.on_dimmer_changed(|_| {}));
+
+        let id = guard.detach();
+        assert_eq!(registry.callback_count(), 1);
+        assert!(registry.unsubscribe(id));
+    }
+
+    #[test]
+    fn drop_after_registry_is_gone() {
+        let registry = Arc::new(CallbackRegistry::new());
+        let guard = SubscriptionGuard::new(&registry, registry.on_dimmer_changed(|_| {}));
+
+        drop(registry);
+        drop(guard);
+    }
+}
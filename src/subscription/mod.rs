@@ -14,8 +14,10 @@
 //! The subscription system consists of:
 //!
 //! - [`SubscriptionId`] - A unique identifier for a subscription, used to unsubscribe
+//! - [`SubscriptionGuard`] - A scoped subscription that unsubscribes when dropped
 //! - [`CallbackRegistry`] - Internal registry that manages callbacks and dispatches events
 //! - [`Subscribable`] - Trait for types that support event subscriptions
+//! - [`EnergyAnalyzer`] - Turns energy updates into spike, idle and appliance cycle events
 //!
 //! # Usage
 //!
@@ -54,7 +56,15 @@
 //! Attempting to call subscription methods on HTTP devices results in a compile-time error.
 
 mod callback;
+mod energy_analyzer;
+mod guard;
 mod subscribable;
+#[cfg(feature = "mqtt")]
+mod throttle;
 
-pub use callback::{CallbackRegistry, EnergyData, SubscriptionId};
+pub use callback::{CallbackRegistry, DEFAULT_STATE_STREAM_CAPACITY, EnergyData, SubscriptionId};
+pub use energy_analyzer::{CycleTransition, EnergyAnalyzer};
+pub use guard::SubscriptionGuard;
 pub use subscribable::Subscribable;
+#[cfg(feature = "mqtt")]
+pub(crate) use throttle::Throttle;
@@ -9,9 +9,11 @@
 //! support subscriptions because HTTP is a stateless protocol without
 //! persistent connections.
 
+use crate::error::ValueError;
 use crate::state::{DeviceState, StateChange};
 use crate::subscription::{EnergyData, SubscriptionId};
-use crate::types::{ColorTemperature, Dimmer, HsbColor, PowerState, Scheme};
+use crate::telemetry::TuyaDataPoint;
+use crate::types::{ButtonAction, ColorTemperature, Dimmer, HsbColor, PowerState, Scheme};
 
 /// Trait for types that support event subscriptions.
 ///
@@ -71,6 +73,23 @@ pub trait Subscribable {
     where
         F: Fn(u8, PowerState) + Send + Sync + 'static;
 
+    /// Subscribes to power state changes of a single relay.
+    ///
+    /// Like [`on_power_changed`](Self::on_power_changed), but the callback
+    /// only runs for relay `index` (1-8), so a widget controlling one relay
+    /// of a multi-gang switch is not woken by the others.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::OutOfRange`] if `index` is not 1-8.
+    fn on_power_index_changed<F>(
+        &self,
+        index: u8,
+        callback: F,
+    ) -> Result<SubscriptionId, ValueError>
+    where
+        F: Fn(PowerState) + Send + Sync + 'static;
+
     /// Subscribes to dimmer value changes.
     ///
     /// The callback is called whenever the dimmer level changes.
@@ -143,6 +162,39 @@ pub trait Subscribable {
     where
         F: Fn(&StateChange) + Send + Sync + 'static;
 
+    /// Subscribes to data points reported by a Tuya MCU.
+    ///
+    /// The callback is called for each data point in a `TuyaReceived`
+    /// message, i.e. whenever the MCU reports a value (after a change on the
+    /// device or a [`TuyaCommand::Query`](crate::command::TuyaCommand::Query)).
+    fn on_tuya_received<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&TuyaDataPoint) + Send + Sync + 'static;
+
+    /// Subscribes to presses of detached buttons.
+    ///
+    /// The callback receives the button number (1-32) and the action for
+    /// every press. Presses are only reported while buttons are detached
+    /// from the relays with `SetOption73`, see
+    /// [`Device::set_buttons_detached`](crate::Device::set_buttons_detached);
+    /// otherwise a press toggles its relay and is reported through
+    /// [`on_power_changed`](Self::on_power_changed).
+    fn on_button_action<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(u8, ButtonAction) + Send + Sync + 'static;
+
+    /// Subscribes to raw command results.
+    ///
+    /// The callback is called for every `stat/<topic>/RESULT` message with
+    /// the device topic and the parsed JSON payload, whether or not the
+    /// payload contains fields the library recognizes. Use it to observe
+    /// confirmations of commands sent with
+    /// [`Device::send_command`](crate::Device::send_command) or to debug
+    /// a command that did not take effect.
+    fn on_result<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&str, &serde_json::Value) + Send + Sync + 'static;
+
     /// Unsubscribes a callback by its subscription ID.
     ///
     /// Returns `true` if the subscription was found and removed.
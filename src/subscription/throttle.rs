@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Client-side rate limiting of callbacks.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tokio::time::Instant;
+
+/// Callback invoked with the latest value.
+type ThrottledCallback<T> = Arc<dyn Fn(T) + Send + Sync>;
+
+/// Coalesces rapid values and forwards at most one per interval.
+///
+/// The first value is forwarded immediately (leading edge). Values arriving
+/// within `min_interval` of the last forwarded one are held, newer ones
+/// replacing older ones, and the latest is forwarded once the interval has
+/// passed (trailing edge), so the final value is never lost.
+pub(crate) struct Throttle<T> {
+    min_interval: Duration,
+    callback: ThrottledCallback<T>,
+    state: Mutex<ThrottleState<T>>,
+}
+
+struct ThrottleState<T> {
+    /// When a value was last forwarded.
+    last_fired: Option<Instant>,
+    /// Latest value held back by the throttle.
+    pending: Option<T>,
+    /// Whether a trailing-edge delivery is scheduled.
+    timer_armed: bool,
+}
+
+impl<T: Send + 'static> Throttle<T> {
+    /// Creates a throttle forwarding to `callback`.
+    pub(crate) fn new(min_interval: Duration, callback: ThrottledCallback<T>) -> Arc<Self> {
+        Arc::new(Self {
+            min_interval,
+            callback,
+            state: Mutex::new(ThrottleState {
+                last_fired: None,
+                pending: None,
+                timer_armed: false,
+            }),
+        })
+    }
+
+    /// Offers a new value, forwarding it now or scheduling its delivery.
+    ///
+    /// The trailing-edge delivery runs on the current Tokio runtime. Without
+    /// one, the held value is forwarded with the next value after the
+    /// interval instead.
+    pub(crate) fn push(self: &Arc<Self>, value: T) {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+
+        if let Some(last) = state.last_fired
+            && now < last + self.min_interval
+        {
+            state.pending = Some(value);
+            if state.timer_armed {
+                return;
+            }
+            let Ok(handle) = tokio::runtime::Handle::try_current() else {
+                return;
+            };
+            state.timer_armed = true;
+            drop(state);
+
+            let throttle = Arc::clone(self);
+            handle.spawn(async move {
+                tokio::time::sleep_until(last + throttle.min_interval).await;
+                throttle.fire_pending();
+            });
+            return;
+        }
+
+        state.last_fired = Some(now);
+        state.pending = None;
+        drop(state);
+        (self.callback)(value);
+    }
+
+    /// Forwards the held value, if any (trailing edge).
+    fn fire_pending(&self) {
+        let pending = {
+            let mut state = self.state.lock();
+            state.timer_armed = false;
+            let pending = state.pending.take();
+            if pending.is_some() {
+                state.last_fired = Some(Instant::now());
+            }
+            pending
+        };
+        if let Some(value) = pending {
+            (self.callback)(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording_throttle(interval: Duration) -> (Arc<Throttle<u32>>, Arc<Mutex<Vec<u32>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        let throttle = Throttle::new(interval, Arc::new(move |value| sink.lock().push(value)));
+        (throttle, received)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn first_value_is_forwarded_immediately() {
+        let (throttle, received) = recording_throttle(Duration::from_secs(1));
+
+        throttle.push(1);
+
+        assert_eq!(*received.lock(), [1]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn burst_is_coalesced_to_latest_value() {
+        let (throttle, received) = recording_throttle(Duration::from_secs(1));
+
+        for value in 1..=5 {
+            throttle.push(value);
+            tokio::time::advance(Duration::from_millis(100)).await;
+        }
+        assert_eq!(*received.lock(), [1]);
+
+        // Trailing edge delivers the last value once the interval has passed
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(*received.lock(), [1, 5]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn values_after_interval_are_forwarded_immediately() {
+        let (throttle, received) = recording_throttle(Duration::from_secs(1));
+
+        throttle.push(1);
+        tokio::time::advance(Duration::from_secs(2)).await;
+        throttle.push(2);
+
+        assert_eq!(*received.lock(), [1, 2]);
+    }
+}
@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Parser for the button presses reported by detached buttons.
+
+use serde_json::Value;
+
+use crate::error::ParseError;
+use crate::types::{ButtonAction, MAX_BUTTONS};
+
+/// Parses the button presses of a `RESULT` message.
+///
+/// With `SetOption73` enabled, Tasmota publishes each press of a detached
+/// button on `stat/<topic>/RESULT`:
+///
+/// ```json
+/// {"Button1":{"Action":"SINGLE"}}
+/// ```
+///
+/// Returns the button number (1-32) and action of every press in the
+/// message, and an empty list for other messages. Unknown actions and button
+/// numbers out of range are skipped.
+///
+/// # Errors
+///
+/// Returns `ParseError` if the payload is not JSON.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::telemetry::parse_button_actions;
+/// use tasmor_lib::types::ButtonAction;
+///
+/// let actions = parse_button_actions(r#"{"Button2":{"Action":"DOUBLE"}}"#).unwrap();
+/// assert_eq!(actions, vec![(2, ButtonAction::Double)]);
+/// ```
+pub fn parse_button_actions(payload: &str) -> Result<Vec<(u8, ButtonAction)>, ParseError> {
+    let value: Value = serde_json::from_str(payload)?;
+    let Some(object) = value.as_object() else {
+        return Ok(Vec::new());
+    };
+
+    let mut actions = Vec::new();
+    for (key, press) in object {
+        let Some(index) = key
+            .strip_prefix("Button")
+            .and_then(|index| index.parse::<u8>().ok())
+        else {
+            continue;
+        };
+        let Some(action) = press.get("Action").and_then(Value::as_str) else {
+            continue;
+        };
+        if !(1..=MAX_BUTTONS).contains(&index) {
+            tracing::debug!(index, "Skipping press of out-of-range button");
+            continue;
+        }
+        if let Ok(parsed) = action.parse::<ButtonAction>() {
+            actions.push((index, parsed));
+        } else {
+            tracing::debug!(index, action, "Skipping unknown button action");
+        }
+    }
+    actions.sort_by_key(|(index, _)| *index);
+    Ok(actions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_single_press() {
+        let actions = parse_button_actions(r#"{"Button1":{"Action":"SINGLE"}}"#).unwrap();
+        assert_eq!(actions, vec![(1, ButtonAction::Single)]);
+    }
+
+    #[test]
+    fn parse_hold_and_triple() {
+        let json = r#"{"Button3":{"Action":"HOLD"},"Button1":{"Action":"TRIPLE"}}"#;
+        let actions = parse_button_actions(json).unwrap();
+        assert_eq!(
+            actions,
+            vec![(1, ButtonAction::Triple), (3, ButtonAction::Hold)]
+        );
+    }
+
+    #[test]
+    fn skips_invalid_presses() {
+        let json = r#"{"Button0":{"Action":"SINGLE"},"Button40":{"Action":"SINGLE"},
+            "Button2":{"Action":"CLEAR"},"POWER":"ON","Button5":"ON"}"#;
+        assert!(parse_button_actions(json).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_non_json() {
+        assert!(parse_button_actions("SINGLE").is_err());
+    }
+}
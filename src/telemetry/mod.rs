@@ -11,6 +11,12 @@
 //! - `tele/<topic>/STATE` - Device state (power, dimmer, color, wifi info)
 //! - `tele/<topic>/SENSOR` - Sensor readings (energy, temperature, humidity)
 //! - `tele/<topic>/LWT` - Last Will Testament (Online/Offline status)
+//! - `tele/<topic>/RESULT` - Tuya MCU data point reports (`TuyaReceived`),
+//!   parsed with [`parse_tuya_received`]
+//! - `stat/<topic>/RESULT` - Presses of detached buttons (`Button1`),
+//!   parsed with [`parse_button_actions`]
+//!
+//! Parsed readings can be exported for monitoring with [`to_prometheus`].
 //!
 //! # Examples
 //!
@@ -31,11 +37,23 @@
 //! }
 //! ```
 
+mod button_parser;
+mod prometheus;
+#[cfg(feature = "testing")]
+mod replay;
+mod sensor_kind;
 mod sensor_parser;
 mod state_parser;
+mod tuya_parser;
 
+pub use button_parser::parse_button_actions;
+pub use prometheus::to_prometheus;
+#[cfg(feature = "testing")]
+pub use replay::{RecordedMessage, ReplayPace, TelemetryRecorder, TelemetryReplayer};
+pub use sensor_kind::{SensorKind, parse_sensor_kinds};
 pub use sensor_parser::{EnergyReading, SensorData, StatusSnsResponse};
-pub use state_parser::TelemetryState;
+pub use state_parser::{TelemetryState, WifiInfo};
+pub use tuya_parser::{TuyaDataPoint, parse_tuya_received};
 
 use crate::error::ParseError;
 use crate::state::StateChange;
@@ -339,7 +357,7 @@ mod tests {
         let info = msg.to_system_info();
         assert!(info.is_some());
         let info = info.unwrap();
-        assert_eq!(info.uptime(), Some(Duration::from_secs(172800)));
+        assert_eq!(info.uptime(), Some(Duration::from_secs(172_800)));
         assert_eq!(info.wifi_rssi(), Some(-55));
     }
 
@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Prometheus text exposition of telemetry readings.
+
+use std::fmt::Write;
+
+use super::{SensorData, TelemetryState};
+
+/// Metric kind, rendered on the `# TYPE` line.
+#[derive(Clone, Copy)]
+enum MetricType {
+    Gauge,
+    Counter,
+}
+
+impl MetricType {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Gauge => "gauge",
+            Self::Counter => "counter",
+        }
+    }
+}
+
+/// Renders a device's latest readings in the Prometheus text exposition format.
+///
+/// Every sample carries a `device` label set to `device_topic`. Only the
+/// metrics the device actually reported are emitted, so a plain relay
+/// without a power monitor produces just the Wi-Fi and uptime samples.
+///
+/// The metric names are stable:
+///
+/// | Metric | Type | Source |
+/// |--------|------|--------|
+/// | `tasmota_power_watts` | gauge | `ENERGY.Power` |
+/// | `tasmota_voltage_volts` | gauge | `ENERGY.Voltage` |
+/// | `tasmota_current_amperes` | gauge | `ENERGY.Current` |
+/// | `tasmota_energy_today_kwh` | gauge | `ENERGY.Today` |
+/// | `tasmota_energy_total_kwh` | counter | `ENERGY.Total` |
+/// | `tasmota_temperature_celsius` | gauge | [`SensorData::temperature`] |
+/// | `tasmota_humidity_percent` | gauge | [`SensorData::humidity`] |
+/// | `tasmota_wifi_rssi_percent` | gauge | `Wifi.RSSI` |
+/// | `tasmota_wifi_signal_dbm` | gauge | `Wifi.Signal` |
+/// | `tasmota_uptime_seconds` | counter | [`TelemetryState::uptime`] |
+///
+/// Temperatures are exported as reported; a device configured for
+/// Fahrenheit (`SetOption8 1`) still lands in `tasmota_temperature_celsius`.
+/// Non-finite readings are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::telemetry::{SensorData, TelemetryState, to_prometheus};
+///
+/// let sensor: SensorData = serde_json::from_str(r#"{"ENERGY":{"Power":42.5}}"#).unwrap();
+/// let state: TelemetryState = serde_json::from_str(r#"{"UptimeSec":3600}"#).unwrap();
+///
+/// let text = to_prometheus("plug", &sensor, &state);
+/// assert!(text.contains("tasmota_power_watts{device=\"plug\"} 42.5\n"));
+/// assert!(text.contains("tasmota_uptime_seconds{device=\"plug\"} 3600\n"));
+/// ```
+#[must_use]
+pub fn to_prometheus(device_topic: &str, sensor: &SensorData, state: &TelemetryState) -> String {
+    use MetricType::{Counter, Gauge};
+
+    let energy = sensor.energy();
+    let wifi = state.wifi();
+    let samples = [
+        (
+            "tasmota_power_watts",
+            Gauge,
+            "Active power in watts.",
+            float(energy.and_then(|e| e.power)),
+        ),
+        (
+            "tasmota_voltage_volts",
+            Gauge,
+            "Supply voltage in volts.",
+            float(energy.and_then(|e| e.voltage)),
+        ),
+        (
+            "tasmota_current_amperes",
+            Gauge,
+            "Current draw in amperes.",
+            float(energy.and_then(|e| e.current)),
+        ),
+        (
+            "tasmota_energy_today_kwh",
+            Gauge,
+            "Energy consumed today in kilowatt-hours.",
+            float(energy.and_then(|e| e.today)),
+        ),
+        (
+            "tasmota_energy_total_kwh",
+            Counter,
+            "Total energy consumed in kilowatt-hours.",
+            float(energy.and_then(|e| e.total)),
+        ),
+        (
+            "tasmota_temperature_celsius",
+            Gauge,
+            "Temperature reading.",
+            float(sensor.temperature()),
+        ),
+        (
+            "tasmota_humidity_percent",
+            Gauge,
+            "Relative humidity in percent.",
+            float(sensor.humidity()),
+        ),
+        (
+            "tasmota_wifi_rssi_percent",
+            Gauge,
+            "Wi-Fi signal quality in percent.",
+            wifi.and_then(|w| w.rssi).map(|v| v.to_string()),
+        ),
+        (
+            "tasmota_wifi_signal_dbm",
+            Gauge,
+            "Wi-Fi signal strength in dBm.",
+            wifi.and_then(|w| w.signal).map(|v| v.to_string()),
+        ),
+        (
+            "tasmota_uptime_seconds",
+            Counter,
+            "Time since the device booted in seconds.",
+            state.uptime().map(|d| d.as_secs().to_string()),
+        ),
+    ];
+
+    let label = escape_label(device_topic);
+    let mut out = String::new();
+    for (name, kind, help, value) in samples {
+        let Some(value) = value else { continue };
+        // Writing to a String cannot fail.
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} {}", kind.as_str());
+        let _ = writeln!(out, "{name}{{device=\"{label}\"}} {value}");
+    }
+    out
+}
+
+/// Formats a float reading, dropping NaN and infinities, which the
+/// exposition format spells differently from Rust.
+fn float(value: Option<f32>) -> Option<String> {
+    value.filter(|v| v.is_finite()).map(|v| v.to_string())
+}
+
+/// Escapes a label value per the exposition format.
+fn escape_label(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_reported_metrics_only() {
+        let sensor: SensorData = serde_json::from_str(
+            r#"{"ENERGY":{"Total":12.5,"Today":0.3,"Power":150,"Voltage":230,"Current":0.65},
+                "DHT11":{"Temperature":21.5,"Humidity":40}}"#,
+        )
+        .unwrap();
+        let state: TelemetryState =
+            serde_json::from_str(r#"{"Uptime":"0T01:00:05","Wifi":{"RSSI":80,"Signal":-60}}"#)
+                .unwrap();
+
+        let text = to_prometheus("kitchen", &sensor, &state);
+
+        assert!(text.contains("# TYPE tasmota_power_watts gauge\n"));
+        assert!(text.contains("tasmota_power_watts{device=\"kitchen\"} 150\n"));
+        assert!(text.contains("tasmota_voltage_volts{device=\"kitchen\"} 230\n"));
+        assert!(text.contains("tasmota_energy_total_kwh{device=\"kitchen\"} 12.5\n"));
+        assert!(text.contains("# TYPE tasmota_energy_total_kwh counter\n"));
+        assert!(text.contains("tasmota_temperature_celsius{device=\"kitchen\"} 21.5\n"));
+        assert!(text.contains("tasmota_humidity_percent{device=\"kitchen\"} 40\n"));
+        assert!(text.contains("tasmota_wifi_rssi_percent{device=\"kitchen\"} 80\n"));
+        assert!(text.contains("tasmota_wifi_signal_dbm{device=\"kitchen\"} -60\n"));
+        assert!(text.contains("tasmota_uptime_seconds{device=\"kitchen\"} 3605\n"));
+    }
+
+    #[test]
+    fn missing_readings_are_omitted() {
+        let text = to_prometheus("relay", &SensorData::default(), &TelemetryState::default());
+        assert!(text.is_empty());
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        let state: TelemetryState = serde_json::from_str(r#"{"UptimeSec":1}"#).unwrap();
+        let text = to_prometheus("a\"b\\c", &SensorData::default(), &state);
+        assert!(text.contains(r#"tasmota_uptime_seconds{device="a\"b\\c"} 1"#));
+    }
+}
@@ -0,0 +1,428 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Recording and replay of MQTT telemetry for offline testing.
+//!
+//! A recording is a text file with one message per line:
+//!
+//! ```text
+//! <unix timestamp in ms>\t<topic>\t<payload>
+//! ```
+//!
+//! [`TelemetryRecorder`] writes such files, for example from live traffic
+//! with [`MqttBroker::start_recording`](crate::MqttBroker::start_recording).
+//! [`TelemetryReplayer`] reads them back and feeds the messages through
+//! [`parse_telemetry`] into a [`DeviceState`] or a [`CallbackRegistry`],
+//! at the recorded cadence or as fast as possible.
+//!
+//! Requires the `testing` feature.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::{TelemetryMessage, extract_device_topic, parse_telemetry};
+use crate::error::ParseError;
+use crate::state::DeviceState;
+use crate::subscription::CallbackRegistry;
+
+/// A single recorded MQTT message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedMessage {
+    timestamp_ms: u64,
+    topic: String,
+    payload: String,
+}
+
+impl RecordedMessage {
+    /// Creates a recorded message.
+    #[must_use]
+    pub fn new(timestamp_ms: u64, topic: impl Into<String>, payload: impl Into<String>) -> Self {
+        Self {
+            timestamp_ms,
+            topic: topic.into(),
+            payload: payload.into(),
+        }
+    }
+
+    /// Returns when the message was received, in milliseconds since the
+    /// Unix epoch.
+    #[must_use]
+    pub fn timestamp_ms(&self) -> u64 {
+        self.timestamp_ms
+    }
+
+    /// Returns the MQTT topic.
+    #[must_use]
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Returns the payload.
+    #[must_use]
+    pub fn payload(&self) -> &str {
+        &self.payload
+    }
+
+    /// Parses one line of a recording.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError::UnexpectedFormat` if the line does not have
+    /// the `<timestamp>\t<topic>\t<payload>` layout.
+    pub fn parse_line(line: &str) -> Result<Self, ParseError> {
+        let mut fields = line.splitn(3, '\t');
+        let (Some(timestamp), Some(topic), Some(payload)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(ParseError::UnexpectedFormat(format!(
+                "Invalid recording line: {line}"
+            )));
+        };
+        let timestamp_ms = timestamp.parse().map_err(|_| {
+            ParseError::UnexpectedFormat(format!("Invalid recording timestamp: {timestamp}"))
+        })?;
+        Ok(Self::new(timestamp_ms, topic, payload))
+    }
+}
+
+/// How fast a [`TelemetryReplayer`] feeds messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReplayPace {
+    /// Wait between messages as long as the recording did.
+    #[default]
+    Recorded,
+    /// Feed messages back to back.
+    Unthrottled,
+}
+
+/// Replays a telemetry recording.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::state::DeviceState;
+/// use tasmor_lib::telemetry::TelemetryReplayer;
+/// use tasmor_lib::types::PowerState;
+///
+/// let recording = "\
+/// 1700000000000\ttele/plug/STATE\t{\"POWER\":\"ON\"}
+/// 1700000060000\ttele/plug/STATE\t{\"POWER\":\"OFF\"}
+/// ";
+/// let replayer = TelemetryReplayer::from_reader(recording.as_bytes()).unwrap();
+///
+/// let mut state = DeviceState::new();
+/// replayer.replay_into_state(&mut state);
+/// assert_eq!(state.power(1), Some(PowerState::Off));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TelemetryReplayer {
+    messages: Vec<RecordedMessage>,
+}
+
+impl TelemetryReplayer {
+    /// Creates a replayer for the given messages, in order.
+    #[must_use]
+    pub fn new(messages: Vec<RecordedMessage>) -> Self {
+        Self { messages }
+    }
+
+    /// Reads a recording. Empty lines are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, or an `InvalidData` error naming
+    /// the line if a line is malformed.
+    pub fn from_reader(reader: impl BufRead) -> io::Result<Self> {
+        let mut messages = Vec::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            let message = RecordedMessage::parse_line(&line).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {e}", number + 1),
+                )
+            })?;
+            messages.push(message);
+        }
+        Ok(Self { messages })
+    }
+
+    /// Reads a recording file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is malformed.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Keeps only the messages of one device.
+    #[must_use]
+    pub fn for_device(mut self, device_topic: &str) -> Self {
+        self.messages
+            .retain(|message| extract_device_topic(&message.topic) == Some(device_topic));
+        self
+    }
+
+    /// Returns the recorded messages.
+    #[must_use]
+    pub fn messages(&self) -> &[RecordedMessage] {
+        &self.messages
+    }
+
+    /// Returns the time between the first and last message.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        match (self.messages.first(), self.messages.last()) {
+            (Some(first), Some(last)) => {
+                Duration::from_millis(last.timestamp_ms.saturating_sub(first.timestamp_ms))
+            }
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Feeds every parsable message to `on_message`.
+    ///
+    /// Messages on topics the telemetry parser does not handle are skipped.
+    /// With [`ReplayPace::Recorded`], the gaps between messages are waited
+    /// out with Tokio timers, so a paused test runtime replays a long
+    /// recording instantly while keeping its timing.
+    pub async fn replay<F>(&self, pace: ReplayPace, mut on_message: F)
+    where
+        F: FnMut(&TelemetryMessage),
+    {
+        let mut previous: Option<u64> = None;
+        for recorded in &self.messages {
+            if pace == ReplayPace::Recorded
+                && let Some(previous) = previous
+            {
+                let gap = recorded.timestamp_ms.saturating_sub(previous);
+                tokio::time::sleep(Duration::from_millis(gap)).await;
+            }
+            previous = Some(recorded.timestamp_ms);
+
+            match parse_telemetry(&recorded.topic, &recorded.payload) {
+                Ok(message) => on_message(&message),
+                Err(e) => tracing::debug!(topic = %recorded.topic, error = %e, "Skipping message"),
+            }
+        }
+    }
+
+    /// Applies the whole recording to `state`, without waiting.
+    ///
+    /// Returns the number of changes that modified the state.
+    pub fn replay_into_state(&self, state: &mut DeviceState) -> usize {
+        let mut applied = 0;
+        for recorded in &self.messages {
+            let Ok(message) = parse_telemetry(&recorded.topic, &recorded.payload) else {
+                continue;
+            };
+            for change in message.to_state_changes() {
+                if state.apply(&change) {
+                    applied += 1;
+                }
+            }
+        }
+        applied
+    }
+
+    /// Dispatches the recording to the callbacks of `callbacks`.
+    ///
+    /// State changes are dispatched as they would be for a live device, and
+    /// LWT messages trigger the connected and disconnected callbacks. The
+    /// state passed to connected callbacks is built from the messages
+    /// replayed so far.
+    pub async fn replay_to_callbacks(&self, callbacks: &CallbackRegistry, pace: ReplayPace) {
+        let mut state = DeviceState::new();
+        self.replay(pace, |message| match message {
+            TelemetryMessage::LastWill { online: true, .. } => {
+                callbacks.dispatch_connected(&state);
+            }
+            TelemetryMessage::LastWill { online: false, .. } => {
+                callbacks.dispatch_disconnected();
+            }
+            _ => {
+                for change in message.to_state_changes() {
+                    state.apply(&change);
+                    callbacks.dispatch(&change);
+                }
+            }
+        })
+        .await;
+    }
+}
+
+/// Writes MQTT messages to a recording.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::telemetry::{TelemetryRecorder, TelemetryReplayer};
+///
+/// let mut buffer = Vec::new();
+/// let mut recorder = TelemetryRecorder::new(&mut buffer);
+/// recorder.record("tele/plug/STATE", r#"{"POWER":"ON"}"#).unwrap();
+/// drop(recorder);
+///
+/// let replayer = TelemetryReplayer::from_reader(buffer.as_slice()).unwrap();
+/// assert_eq!(replayer.messages()[0].topic(), "tele/plug/STATE");
+/// ```
+pub struct TelemetryRecorder<W: Write = BufWriter<File>> {
+    writer: W,
+}
+
+impl TelemetryRecorder {
+    /// Creates a recording file, replacing any existing file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> TelemetryRecorder<W> {
+    /// Creates a recorder writing to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Records a message received now.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails or the topic or payload cannot be
+    /// stored on one line.
+    pub fn record(&mut self, topic: &str, payload: &str) -> io::Result<()> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let timestamp_ms = u64::try_from(now.as_millis()).unwrap_or(u64::MAX);
+        self.record_at(timestamp_ms, topic, payload)
+    }
+
+    /// Records a message with an explicit timestamp (ms since the Unix epoch).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails, if the topic contains a tab or
+    /// line break, or if the payload contains a line break.
+    pub fn record_at(&mut self, timestamp_ms: u64, topic: &str, payload: &str) -> io::Result<()> {
+        if topic.contains(['\t', '\n', '\r']) || payload.contains(['\n', '\r']) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("message on {topic} does not fit on one line"),
+            ));
+        }
+        writeln!(self.writer, "{timestamp_ms}\t{topic}\t{payload}")
+    }
+
+    /// Flushes buffered messages to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if flushing fails.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> std::fmt::Debug for TelemetryRecorder<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TelemetryRecorder").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use parking_lot::Mutex;
+
+    use super::*;
+    use crate::types::PowerState;
+
+    const RECORDING: &str = "\
+1700000000000\ttele/plug/LWT\tOnline
+1700000000500\ttele/plug/STATE\t{\"POWER\":\"ON\"}
+1700000060500\ttele/other/STATE\t{\"POWER\":\"ON\"}
+1700003600500\ttele/plug/STATE\t{\"POWER\":\"OFF\"}
+";
+
+    #[test]
+    fn malformed_lines_are_reported() {
+        let err = TelemetryReplayer::from_reader("1\ttele/plug/LWT\n".as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("line 1:"));
+
+        let err = RecordedMessage::parse_line("soon\ttele/plug/LWT\tOnline").unwrap_err();
+        assert!(matches!(err, ParseError::UnexpectedFormat(_)));
+    }
+
+    #[test]
+    fn recorder_output_round_trips() {
+        let mut recorder = TelemetryRecorder::new(Vec::new());
+        recorder
+            .record_at(42, "stat/plug/RESULT", r#"{"POWER": "ON"}"#)
+            .unwrap();
+        assert!(recorder.record_at(43, "tele/plug/STATE", "{\n}").is_err());
+
+        let replayer = TelemetryReplayer::from_reader(recorder.into_inner().as_slice()).unwrap();
+        assert_eq!(
+            replayer.messages(),
+            [RecordedMessage::new(
+                42,
+                "stat/plug/RESULT",
+                r#"{"POWER": "ON"}"#
+            )]
+        );
+    }
+
+    #[test]
+    fn replay_into_state_applies_changes_in_order() {
+        let replayer = TelemetryReplayer::from_reader(RECORDING.as_bytes())
+            .unwrap()
+            .for_device("plug");
+        assert_eq!(replayer.messages().len(), 3);
+        assert_eq!(replayer.duration(), Duration::from_millis(3_600_500));
+
+        let mut state = DeviceState::new();
+        assert_eq!(replayer.replay_into_state(&mut state), 2);
+        assert_eq!(state.power(1), Some(PowerState::Off));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay_keeps_recorded_cadence() {
+        let replayer = TelemetryReplayer::from_reader(RECORDING.as_bytes())
+            .unwrap()
+            .for_device("plug");
+        let callbacks = CallbackRegistry::new();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        callbacks.on_power_changed(move |index, state| sink.lock().push((index, state)));
+
+        let start = tokio::time::Instant::now();
+        replayer
+            .replay_to_callbacks(&callbacks, ReplayPace::Recorded)
+            .await;
+
+        assert_eq!(start.elapsed(), replayer.duration());
+        assert_eq!(
+            *received.lock(),
+            [(1, PowerState::On), (1, PowerState::Off)]
+        );
+    }
+}
@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Discovery of the sensor blocks reported by a device.
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::error::ParseError;
+
+/// Kind of a sensor block in a `SENSOR` or `StatusSNS` report.
+///
+/// Tasmota reports each sensor as a JSON object named after the driver,
+/// e.g. `"ENERGY"`, `"BME280"` or `"DS18B20"`. Drivers without a dedicated
+/// variant are reported as [`SensorKind::Other`] with their block name.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::telemetry::SensorKind;
+///
+/// assert_eq!(SensorKind::from_name("BME280"), SensorKind::Bme280);
+/// // Multiple sensors of one type are numbered by Tasmota
+/// assert_eq!(SensorKind::from_name("DS18B20-2"), SensorKind::Ds18b20);
+/// assert_eq!(
+///     SensorKind::from_name("SHT3X"),
+///     SensorKind::Other("SHT3X".to_string())
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SensorKind {
+    /// Energy monitoring (`ENERGY`).
+    Energy,
+    /// DS18B20 temperature sensor.
+    Ds18b20,
+    /// DHT11 temperature/humidity sensor.
+    Dht11,
+    /// AM2301 (DHT21) temperature/humidity sensor.
+    Am2301,
+    /// BME280 temperature/humidity/pressure sensor.
+    Bme280,
+    /// Any other sensor, by block name.
+    Other(String),
+}
+
+impl SensorKind {
+    /// Returns the kind for a sensor block name.
+    ///
+    /// A `-<n>` suffix added by Tasmota for multiple sensors of the same
+    /// type is ignored.
+    #[must_use]
+    pub fn from_name(name: &str) -> Self {
+        let base = name
+            .rsplit_once('-')
+            .filter(|(_, index)| !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()))
+            .map_or(name, |(base, _)| base);
+
+        match base {
+            "ENERGY" => Self::Energy,
+            "DS18B20" => Self::Ds18b20,
+            "DHT11" => Self::Dht11,
+            "AM2301" => Self::Am2301,
+            "BME280" => Self::Bme280,
+            _ => Self::Other(base.to_string()),
+        }
+    }
+
+    /// Returns the Tasmota block name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Energy => "ENERGY",
+            Self::Ds18b20 => "DS18B20",
+            Self::Dht11 => "DHT11",
+            Self::Am2301 => "AM2301",
+            Self::Bme280 => "BME280",
+            Self::Other(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for SensorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Lists the sensors present in a `SENSOR` or `Status 10` payload.
+///
+/// Both the bare telemetry layout and the `{"StatusSNS":{...}}` wrapper
+/// are accepted. Only object values are sensor blocks, so keys such as
+/// `Time` or `TempUnit` are ignored. Each kind is listed once, even when
+/// several sensors of that type are connected.
+///
+/// # Errors
+///
+/// Returns `ParseError` if the payload is not a JSON object.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::telemetry::{SensorKind, parse_sensor_kinds};
+///
+/// let json = r#"{"StatusSNS":{"Time":"2024-01-01T12:00:00",
+///     "DS18B20-1":{"Temperature":21.0},"DS18B20-2":{"Temperature":22.5},
+///     "ENERGY":{"Power":12},"TempUnit":"C"}}"#;
+///
+/// assert_eq!(
+///     parse_sensor_kinds(json).unwrap(),
+///     vec![SensorKind::Ds18b20, SensorKind::Energy]
+/// );
+/// ```
+pub fn parse_sensor_kinds(payload: &str) -> Result<Vec<SensorKind>, ParseError> {
+    let value: Value = serde_json::from_str(payload)?;
+    let object = value.as_object().ok_or_else(|| {
+        ParseError::UnexpectedFormat("sensor payload is not a JSON object".to_string())
+    })?;
+    let sensors = object
+        .get("StatusSNS")
+        .and_then(Value::as_object)
+        .unwrap_or(object);
+    Ok(sensor_kinds(sensors))
+}
+
+/// Collects the kinds of the object-valued entries of a sensor report.
+fn sensor_kinds(sensors: &Map<String, Value>) -> Vec<SensorKind> {
+    let mut kinds = Vec::new();
+    for (name, value) in sensors {
+        if !value.is_object() {
+            continue;
+        }
+        let kind = SensorKind::from_name(name);
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+    kinds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_names_round_trip() {
+        for kind in [
+            SensorKind::Energy,
+            SensorKind::Ds18b20,
+            SensorKind::Dht11,
+            SensorKind::Am2301,
+            SensorKind::Bme280,
+        ] {
+            assert_eq!(SensorKind::from_name(kind.name()), kind);
+        }
+        assert_eq!(
+            SensorKind::Other("PMS5003".to_string()).to_string(),
+            "PMS5003"
+        );
+        // Hyphenated driver names are kept
+        assert_eq!(
+            SensorKind::from_name("SR04-X"),
+            SensorKind::Other("SR04-X".to_string())
+        );
+    }
+
+    #[test]
+    fn bare_sensor_payload() {
+        let json =
+            r#"{"Time":"2024-01-01T12:00:00","BME280":{"Temperature":21.5},"PressureUnit":"hPa"}"#;
+        assert_eq!(parse_sensor_kinds(json).unwrap(), vec![SensorKind::Bme280]);
+    }
+
+    #[test]
+    fn payload_without_sensors() {
+        let json = r#"{"StatusSNS":{"Time":"2024-01-01T12:00:00"}}"#;
+        assert!(parse_sensor_kinds(json).unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid_payloads_fail() {
+        assert!(parse_sensor_kinds("not json").is_err());
+        assert!(parse_sensor_kinds("[1,2]").is_err());
+    }
+}
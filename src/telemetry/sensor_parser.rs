@@ -8,6 +8,7 @@
 use serde::Deserialize;
 
 use crate::error::ParseError;
+use crate::response::{Phased, nth_phase};
 use crate::state::StateChange;
 use crate::types::TasmotaDateTime;
 
@@ -73,53 +74,73 @@ pub struct SensorData {
 ///
 /// Fields correspond to Tasmota's ENERGY telemetry output.
 /// All fields are optional as not all devices report all values.
+///
+/// Multi-phase meters report electrical values as one entry per phase, e.g.
+/// `"Voltage":[230,231,229]`. The scalar fields then hold phase 1, and the
+/// `*_phases` fields and [`voltage_phase`](Self::voltage_phase)-style
+/// accessors give every phase.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::telemetry::EnergyReading;
+///
+/// let value = serde_json::json!({"Power": [512, 230, 1024], "Voltage": [230, 231, 229]});
+/// let energy = EnergyReading::from_value(&value).unwrap();
+///
+/// assert_eq!(energy.voltage, Some(230.0));
+/// assert_eq!(energy.voltage_phase(2), Some(231.0));
+/// assert_eq!(energy.phase_count(), 3);
+/// ```
 #[derive(Debug, Clone, Default, Deserialize)]
+#[serde(from = "RawEnergyReading")]
 pub struct EnergyReading {
     /// Timestamp when total energy counting started.
     ///
     /// Format: ISO 8601 datetime string (e.g., "2024-01-15T10:30:00").
-    #[serde(rename = "TotalStartTime", default)]
     pub total_start_time: Option<String>,
 
     /// Total energy consumed today (in kWh).
-    #[serde(rename = "Today", default)]
     pub today: Option<f32>,
 
     /// Total energy consumed yesterday (in kWh).
-    #[serde(rename = "Yesterday", default)]
     pub yesterday: Option<f32>,
 
     /// Total energy consumed (in kWh).
-    #[serde(rename = "Total", default)]
     pub total: Option<f32>,
 
     /// Current power consumption (in Watts).
-    #[serde(rename = "Power", default)]
     pub power: Option<f32>,
 
     /// Apparent power (in VA).
-    #[serde(rename = "ApparentPower", default)]
     pub apparent_power: Option<f32>,
 
     /// Reactive power (in `VAr`).
-    #[serde(rename = "ReactivePower", default)]
     pub reactive_power: Option<f32>,
 
     /// Power factor (0-1).
-    #[serde(rename = "Factor", default)]
     pub factor: Option<f32>,
 
     /// Voltage (in Volts).
-    #[serde(rename = "Voltage", default)]
     pub voltage: Option<f32>,
 
     /// Current (in Amps).
-    #[serde(rename = "Current", default)]
     pub current: Option<f32>,
 
     /// Frequency (in Hz).
-    #[serde(rename = "Frequency", default)]
     pub frequency: Option<f32>,
+
+    /// Power of each phase (in Watts). Empty on single-phase devices.
+    pub power_phases: Vec<f32>,
+
+    /// Power factor of each phase. Empty on single-phase devices.
+    pub factor_phases: Vec<f32>,
+
+    /// Voltage of each phase (in Volts). Empty on single-phase devices.
+    pub voltage_phases: Vec<f32>,
+
+    /// Current of each phase (in Amps). Empty on single-phase devices.
+    pub current_phases: Vec<f32>,
 }
 
 /// Temperature sensor reading.
@@ -338,6 +359,38 @@ impl SensorData {
 }
 
 impl EnergyReading {
+    /// Extracts an energy reading from any of the JSON shapes Tasmota uses.
+    ///
+    /// Accepts:
+    /// - `Status 10` replies: `{"StatusSNS":{"ENERGY":{...}}}`
+    ///   (or `StatusSTS` for devices reporting energy in `Status 11`)
+    /// - `tele/<topic>/SENSOR` payloads: `{"Time":"...","ENERGY":{...}}`
+    /// - the `ENERGY` object itself: `{"Power":150,"Voltage":230,...}`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError::MissingField`] if no energy data is found, or
+    /// [`ParseError::Json`] if the energy object has invalid field values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tasmor_lib::telemetry::EnergyReading;
+    ///
+    /// let status = serde_json::json!({"StatusSNS": {"ENERGY": {"Power": 150}}});
+    /// let sensor = serde_json::json!({"Time": "2024-01-01T12:00:00", "ENERGY": {"Power": 150}});
+    /// let bare = serde_json::json!({"Power": 150});
+    ///
+    /// for value in [status, sensor, bare] {
+    ///     assert_eq!(EnergyReading::from_value(&value).unwrap().power, Some(150.0));
+    /// }
+    /// ```
+    pub fn from_value(value: &serde_json::Value) -> Result<Self, ParseError> {
+        let energy =
+            locate_energy(value).ok_or_else(|| ParseError::MissingField("ENERGY".to_string()))?;
+        Self::deserialize(energy).map_err(ParseError::Json)
+    }
+
     /// Returns true if any power-related field is present.
     #[must_use]
     pub fn has_power_data(&self) -> bool {
@@ -349,11 +402,120 @@ impl EnergyReading {
     pub fn has_consumption_data(&self) -> bool {
         self.today.is_some() || self.yesterday.is_some() || self.total.is_some()
     }
+
+    /// Returns the number of phases reported, 1 for single-phase devices.
+    #[must_use]
+    pub fn phase_count(&self) -> usize {
+        [
+            &self.power_phases,
+            &self.factor_phases,
+            &self.voltage_phases,
+            &self.current_phases,
+        ]
+        .into_iter()
+        .map(Vec::len)
+        .max()
+        .unwrap_or_default()
+        .max(1)
+    }
+
+    /// Returns the power of phase `n` (in Watts), counting from 1.
+    #[must_use]
+    pub fn power_phase(&self, n: u8) -> Option<f32> {
+        nth_phase(&self.power_phases, self.power, n)
+    }
+
+    /// Returns the power factor of phase `n`, counting from 1.
+    #[must_use]
+    pub fn factor_phase(&self, n: u8) -> Option<f32> {
+        nth_phase(&self.factor_phases, self.factor, n)
+    }
+
+    /// Returns the voltage of phase `n` (in Volts), counting from 1.
+    #[must_use]
+    pub fn voltage_phase(&self, n: u8) -> Option<f32> {
+        nth_phase(&self.voltage_phases, self.voltage, n)
+    }
+
+    /// Returns the current of phase `n` (in Amps), counting from 1.
+    #[must_use]
+    pub fn current_phase(&self, n: u8) -> Option<f32> {
+        nth_phase(&self.current_phases, self.current, n)
+    }
+}
+
+/// `ENERGY` fields as reported, before splitting per-phase values.
+#[derive(Deserialize)]
+struct RawEnergyReading {
+    #[serde(rename = "TotalStartTime", default)]
+    total_start_time: Option<String>,
+    #[serde(rename = "Today", default)]
+    today: Option<f32>,
+    #[serde(rename = "Yesterday", default)]
+    yesterday: Option<f32>,
+    #[serde(rename = "Total", default)]
+    total: Option<f32>,
+    #[serde(rename = "Power", default)]
+    power: Option<Phased>,
+    #[serde(rename = "ApparentPower", default)]
+    apparent_power: Option<Phased>,
+    #[serde(rename = "ReactivePower", default)]
+    reactive_power: Option<Phased>,
+    #[serde(rename = "Factor", default)]
+    factor: Option<Phased>,
+    #[serde(rename = "Voltage", default)]
+    voltage: Option<Phased>,
+    #[serde(rename = "Current", default)]
+    current: Option<Phased>,
+    #[serde(rename = "Frequency", default)]
+    frequency: Option<Phased>,
+}
+
+impl From<RawEnergyReading> for EnergyReading {
+    fn from(raw: RawEnergyReading) -> Self {
+        let first = |value: Option<&Phased>| value.and_then(Phased::first);
+        Self {
+            total_start_time: raw.total_start_time,
+            today: raw.today,
+            yesterday: raw.yesterday,
+            total: raw.total,
+            power: first(raw.power.as_ref()),
+            apparent_power: first(raw.apparent_power.as_ref()),
+            reactive_power: first(raw.reactive_power.as_ref()),
+            factor: first(raw.factor.as_ref()),
+            voltage: first(raw.voltage.as_ref()),
+            current: first(raw.current.as_ref()),
+            frequency: first(raw.frequency.as_ref()),
+            power_phases: Phased::into_phases(raw.power),
+            factor_phases: Phased::into_phases(raw.factor),
+            voltage_phases: Phased::into_phases(raw.voltage),
+            current_phases: Phased::into_phases(raw.current),
+        }
+    }
+}
+
+/// Fields that identify a bare `ENERGY` object.
+const ENERGY_FIELDS: [&str; 6] = ["Power", "Voltage", "Current", "Total", "Today", "Yesterday"];
+
+/// Finds the `ENERGY` object in a status reply, a SENSOR payload, or
+/// returns the value itself if it already is one.
+fn locate_energy(value: &serde_json::Value) -> Option<&serde_json::Value> {
+    let sensors = ["StatusSNS", "StatusSTS"]
+        .into_iter()
+        .find_map(|wrapper| value.get(wrapper))
+        .unwrap_or(value);
+    if let Some(energy) = sensors.get("ENERGY") {
+        return Some(energy);
+    }
+    ENERGY_FIELDS
+        .into_iter()
+        .any(|field| value.get(field).is_some())
+        .then_some(value)
 }
 
 /// Parses a SENSOR telemetry JSON payload.
 pub(crate) fn parse_sensor(payload: &str) -> Result<SensorData, ParseError> {
-    serde_json::from_str(payload).map_err(ParseError::Json)
+    crate::response::case_insensitive::from_str(payload).map_err(ParseError::Json)
 }
 
 /// Response wrapper for `Status 10` command.
@@ -484,6 +646,99 @@ mod tests {
         assert_eq!(bme.dew_point(), Some(13.2));
     }
 
+    #[test]
+    fn energy_from_status_sns() {
+        let value = serde_json::json!({
+            "StatusSNS": {
+                "Time": "2024-01-01T12:00:00",
+                "ENERGY": { "Power": 182, "Voltage": 224, "Total": 1104.315 }
+            }
+        });
+        let energy = EnergyReading::from_value(&value).unwrap();
+        assert_eq!(energy.power, Some(182.0));
+        assert_eq!(energy.voltage, Some(224.0));
+    }
+
+    #[test]
+    fn energy_from_status_sts() {
+        let value = serde_json::json!({ "StatusSTS": { "POWER": "ON", "ENERGY": { "Power": 5 } } });
+        assert_eq!(EnergyReading::from_value(&value).unwrap().power, Some(5.0));
+    }
+
+    #[test]
+    fn energy_from_sensor_payload() {
+        let value = serde_json::json!({
+            "Time": "2024-01-01T12:00:00",
+            "ENERGY": { "Today": 1.5, "Current": 0.65 }
+        });
+        let energy = EnergyReading::from_value(&value).unwrap();
+        assert_eq!(energy.today, Some(1.5));
+        assert_eq!(energy.current, Some(0.65));
+    }
+
+    #[test]
+    fn energy_from_bare_object() {
+        let value = serde_json::json!({ "Power": 150, "Factor": 0.95 });
+        let energy = EnergyReading::from_value(&value).unwrap();
+        assert_eq!(energy.power, Some(150.0));
+        assert_eq!(energy.factor, Some(0.95));
+    }
+
+    #[test]
+    fn energy_from_value_without_energy() {
+        let value = serde_json::json!({ "Time": "2024-01-01T12:00:00", "Temperature": 21.0 });
+        assert!(matches!(
+            EnergyReading::from_value(&value),
+            Err(ParseError::MissingField(_))
+        ));
+
+        let value = serde_json::json!({ "ENERGY": { "Power": "high" } });
+        assert!(matches!(
+            EnergyReading::from_value(&value),
+            Err(ParseError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn energy_single_phase_payload() {
+        let json = r#"{"Time":"2024-01-15T10:30:00","ENERGY":{"TotalStartTime":"2023-11-02T18:42:11",
+            "Total":1104.315,"Yesterday":2.152,"Today":0.873,"Power":182,"ApparentPower":195,
+            "ReactivePower":70,"Factor":0.93,"Voltage":224,"Current":0.871}}"#;
+        let data: SensorData = serde_json::from_str(json).unwrap();
+        let energy = data.energy().unwrap();
+
+        assert_eq!(energy.phase_count(), 1);
+        assert_eq!(energy.voltage, Some(224.0));
+        assert_eq!(energy.voltage_phase(1), Some(224.0));
+        assert_eq!(energy.voltage_phase(2), None);
+        assert!(energy.voltage_phases.is_empty());
+    }
+
+    #[test]
+    fn energy_three_phase_payload() {
+        let json = r#"{"Time":"2024-01-15T10:30:00","ENERGY":{"TotalStartTime":"2023-06-01T10:00:00",
+            "Total":3105.628,"Yesterday":12.345,"Today":4.321,"Power":[512,230,1024],
+            "ApparentPower":[540,250,1060],"ReactivePower":[171,98,275],"Factor":[0.95,0.92,0.97],
+            "Frequency":50.02,"Voltage":[230,231,229],"Current":[2.348,1.082,4.629]}}"#;
+        let data: SensorData = serde_json::from_str(json).unwrap();
+        let energy = data.energy().unwrap();
+
+        assert_eq!(energy.phase_count(), 3);
+        assert_eq!(energy.voltage_phases, [230.0, 231.0, 229.0]);
+        assert_eq!(energy.voltage_phase(3), Some(229.0));
+        assert_eq!(energy.voltage_phase(4), None);
+        assert_eq!(energy.current_phase(2), Some(1.082));
+        assert_eq!(energy.power_phase(3), Some(1024.0));
+        assert_eq!(energy.factor_phase(1), Some(0.95));
+
+        // Scalar fields keep reporting phase 1
+        assert_eq!(energy.power, Some(512.0));
+        assert_eq!(energy.voltage, Some(230.0));
+        assert_eq!(energy.apparent_power, Some(540.0));
+        assert_eq!(energy.frequency, Some(50.02));
+        assert_eq!(energy.total, Some(3105.628));
+    }
+
     #[test]
     fn energy_has_power_data() {
         let energy = EnergyReading {
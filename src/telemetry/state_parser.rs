@@ -157,6 +157,10 @@ pub struct TelemetryState {
     #[serde(rename = "White", default)]
     white: Option<u8>,
 
+    /// Raw light channel values (0-100), one per PWM output.
+    #[serde(rename = "Channel", default)]
+    channel: Option<Vec<u8>>,
+
     /// Fade setting ("ON"/"OFF" or 0/1).
     #[serde(rename = "Fade", default, deserialize_with = "deserialize_bool_or_int")]
     fade: Option<bool>,
@@ -182,13 +186,30 @@ pub struct TelemetryState {
     wifi: Option<WifiInfo>,
 }
 
-/// Wi-Fi connection information from telemetry.
+/// Wi-Fi connection information from telemetry and `Status 11`.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::telemetry::WifiInfo;
+///
+/// let json = r#"{"SSId":"office","BSSId":"30:B5:C2:5D:70:72","Channel":6,"RSSI":78,"Signal":-61}"#;
+/// let wifi: WifiInfo = serde_json::from_str(json).unwrap();
+///
+/// assert_eq!(wifi.bssid.as_deref(), Some("30:B5:C2:5D:70:72"));
+/// assert_eq!(wifi.quality(), Some(78));
+/// assert_eq!(wifi.signal_dbm(), Some(-61));
+/// ```
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct WifiInfo {
     /// SSID of the connected network.
     #[serde(rename = "SSId", default)]
     pub ssid: Option<String>,
 
+    /// BSSID (MAC address) of the access point the device is connected to.
+    #[serde(rename = "BSSId", default)]
+    pub bssid: Option<String>,
+
     /// RSSI (signal strength percentage, 0-100).
     #[serde(rename = "RSSI", default)]
     pub rssi: Option<i32>,
@@ -206,6 +227,27 @@ pub struct WifiInfo {
     pub link_count: Option<u32>,
 }
 
+impl WifiInfo {
+    /// Returns the signal quality as a percentage (0-100), for gauges.
+    ///
+    /// Uses the `RSSI` Tasmota reports, which is already a quality
+    /// percentage, or derives it from the dBm signal the same way Tasmota
+    /// does (-100 dBm is 0%, -50 dBm and above is 100%).
+    #[must_use]
+    pub fn quality(&self) -> Option<u8> {
+        let quality = self
+            .rssi
+            .or_else(|| self.signal.map(|dbm| 2 * (dbm + 100)))?;
+        u8::try_from(quality.clamp(0, 100)).ok()
+    }
+
+    /// Returns the raw signal strength in dBm.
+    #[must_use]
+    pub const fn signal_dbm(&self) -> Option<i32> {
+        self.signal
+    }
+}
+
 impl TelemetryState {
     /// Returns the power state for the primary relay.
     #[must_use]
@@ -281,6 +323,12 @@ impl TelemetryState {
         self.white
     }
 
+    /// Returns the raw light channel values (0-100).
+    #[must_use]
+    pub fn channels(&self) -> Option<&[u8]> {
+        self.channel.as_deref()
+    }
+
     /// Returns whether fade is enabled.
     #[must_use]
     pub fn fade_enabled(&self) -> Option<bool> {
@@ -404,7 +452,7 @@ impl TelemetryState {
     /// let state: TelemetryState = serde_json::from_str(json).unwrap();
     ///
     /// let info = state.to_system_info();
-    /// assert_eq!(info.uptime(), Some(Duration::from_secs(172800)));
+    /// assert_eq!(info.uptime(), Some(Duration::from_secs(172_800)));
     /// assert_eq!(info.wifi_rssi(), Some(-55));
     /// ```
     #[must_use]
@@ -431,7 +479,7 @@ impl TelemetryState {
 
 /// Parses a STATE telemetry JSON payload.
 pub(crate) fn parse_state(payload: &str) -> Result<TelemetryState, ParseError> {
-    serde_json::from_str(payload).map_err(ParseError::Json)
+    crate::response::case_insensitive::from_str(payload).map_err(ParseError::Json)
 }
 
 #[cfg(test)]
@@ -551,6 +599,20 @@ mod tests {
         assert_eq!(wifi.link_count, Some(5));
     }
 
+    #[test]
+    fn wifi_quality_from_signal() {
+        let wifi: WifiInfo = serde_json::from_str(r#"{"Signal":-61}"#).unwrap();
+        assert_eq!(wifi.quality(), Some(78));
+
+        let wifi: WifiInfo = serde_json::from_str(r#"{"Signal":-40}"#).unwrap();
+        assert_eq!(wifi.quality(), Some(100));
+
+        let wifi: WifiInfo = serde_json::from_str(r#"{"Signal":-105}"#).unwrap();
+        assert_eq!(wifi.quality(), Some(0));
+
+        assert_eq!(WifiInfo::default().quality(), None);
+    }
+
     #[test]
     fn to_state_changes_single_power() {
         let json = r#"{"POWER":"ON"}"#;
@@ -698,7 +760,7 @@ mod tests {
         let state: TelemetryState = serde_json::from_str(json).unwrap();
 
         let info = state.to_system_info();
-        assert_eq!(info.uptime(), Some(Duration::from_secs(172800)));
+        assert_eq!(info.uptime(), Some(Duration::from_secs(172_800)));
         assert!(info.wifi_rssi().is_none());
         assert!(info.heap().is_none());
     }
@@ -719,7 +781,7 @@ mod tests {
         let state: TelemetryState = serde_json::from_str(json).unwrap();
 
         let info = state.to_system_info();
-        assert_eq!(info.uptime(), Some(Duration::from_secs(172800)));
+        assert_eq!(info.uptime(), Some(Duration::from_secs(172_800)));
         assert_eq!(info.wifi_rssi(), Some(-60)); // Uses Signal (dBm), not RSSI (%)
     }
 
@@ -746,7 +808,7 @@ mod tests {
         let state: TelemetryState = serde_json::from_str(json).unwrap();
 
         let info = state.to_system_info();
-        assert_eq!(info.uptime(), Some(Duration::from_secs(172018)));
+        assert_eq!(info.uptime(), Some(Duration::from_secs(172_018)));
         assert_eq!(info.wifi_rssi(), Some(-52));
         // Note: Heap is not extracted from TelemetryState (only available via Status)
         assert!(info.heap().is_none());
@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Parser for Tuya MCU `TuyaReceived` messages.
+
+use serde_json::{Map, Value};
+
+use crate::error::ParseError;
+use crate::types::{TuyaDpType, TuyaValue};
+
+/// A data point value reported by a Tuya MCU.
+///
+/// Tasmota publishes the MCU's reports on `tele/<topic>/RESULT`:
+///
+/// ```json
+/// {"TuyaReceived":{"Data":"55AA03070005020100010115","Cmnd":7,"CmndData":"0201000101",
+///  "DpType1Id2":1,"2":{"DpId":2,"DpIdType":1,"DpIdData":"01"}}}
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::telemetry::parse_tuya_received;
+/// use tasmor_lib::types::TuyaValue;
+///
+/// let json = r#"{"TuyaReceived":{"Cmnd":7,"2":{"DpId":2,"DpIdType":2,"DpIdData":"000001F4"}}}"#;
+/// let points = parse_tuya_received(json).unwrap();
+///
+/// assert_eq!(points[0].dp_id(), 2);
+/// assert_eq!(points[0].value(), &TuyaValue::Int(500));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TuyaDataPoint {
+    dp_id: u8,
+    value: TuyaValue,
+}
+
+impl TuyaDataPoint {
+    /// Creates a data point.
+    #[must_use]
+    pub fn new(dp_id: u8, value: TuyaValue) -> Self {
+        Self { dp_id, value }
+    }
+
+    /// Returns the data point ID.
+    #[must_use]
+    pub fn dp_id(&self) -> u8 {
+        self.dp_id
+    }
+
+    /// Returns the decoded value.
+    #[must_use]
+    pub fn value(&self) -> &TuyaValue {
+        &self.value
+    }
+}
+
+/// Parses the data points of a `TuyaReceived` message.
+///
+/// Returns an empty list for messages without data points (e.g. heartbeat
+/// replies). Data points with an unknown type are skipped.
+///
+/// # Errors
+///
+/// Returns `ParseError` if the payload is not JSON, has no `TuyaReceived`
+/// object, or a data point carries malformed hex data.
+pub fn parse_tuya_received(payload: &str) -> Result<Vec<TuyaDataPoint>, ParseError> {
+    let value: Value = serde_json::from_str(payload)?;
+    let received = value
+        .get("TuyaReceived")
+        .and_then(Value::as_object)
+        .ok_or_else(|| ParseError::MissingField("TuyaReceived".to_string()))?;
+
+    // Older firmware reports a single data point inline
+    let inline = std::iter::once(received);
+    let nested = received.values().filter_map(Value::as_object);
+
+    let mut points = Vec::new();
+    for object in inline.chain(nested) {
+        if let Some(point) = parse_data_point(object)? {
+            points.push(point);
+        }
+    }
+    Ok(points)
+}
+
+/// Parses a `{"DpId":..,"DpIdType":..,"DpIdData":".."}` object.
+fn parse_data_point(object: &Map<String, Value>) -> Result<Option<TuyaDataPoint>, ParseError> {
+    let (Some(dp_id), Some(dp_type), Some(data)) = (
+        object.get("DpId").and_then(Value::as_u64),
+        object.get("DpIdType").and_then(Value::as_u64),
+        object.get("DpIdData").and_then(Value::as_str),
+    ) else {
+        return Ok(None);
+    };
+
+    let dp_id = u8::try_from(dp_id).map_err(|_| ParseError::InvalidValue {
+        field: "DpId".to_string(),
+        message: format!("{dp_id} is out of range"),
+    })?;
+    let Some(dp_type) = u8::try_from(dp_type).ok().and_then(TuyaDpType::from_code) else {
+        tracing::debug!(dp_id, dp_type, "Skipping Tuya data point of unknown type");
+        return Ok(None);
+    };
+
+    let bytes = decode_hex(data).ok_or_else(|| ParseError::InvalidValue {
+        field: "DpIdData".to_string(),
+        message: format!("invalid hex data: {data}"),
+    })?;
+    Ok(Some(TuyaDataPoint::new(
+        dp_id,
+        TuyaValue::decode(dp_type, &bytes),
+    )))
+}
+
+/// Decodes a hex string into bytes.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_nested_data_points() {
+        let json = r#"{"TuyaReceived":{
+            "Data":"55AA03070005020100010115","Cmnd":7,"CmndData":"0201000101",
+            "DpType1Id1":1,"1":{"DpId":1,"DpIdType":1,"DpIdData":"01"},
+            "DpType3Id5":"eco","5":{"DpId":5,"DpIdType":3,"DpIdData":"65636F"}
+        }}"#;
+
+        let mut points = parse_tuya_received(json).unwrap();
+        points.sort_by_key(TuyaDataPoint::dp_id);
+
+        assert_eq!(
+            points,
+            vec![
+                TuyaDataPoint::new(1, TuyaValue::Bool(true)),
+                TuyaDataPoint::new(5, TuyaValue::String("eco".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_inline_data_point() {
+        let json = r#"{"TuyaReceived":{"Data":"55AA","DpId":2,"DpIdType":4,"DpIdData":"03"}}"#;
+        let points = parse_tuya_received(json).unwrap();
+        assert_eq!(points, vec![TuyaDataPoint::new(2, TuyaValue::Enum(3))]);
+    }
+
+    #[test]
+    fn heartbeat_has_no_data_points() {
+        let json = r#"{"TuyaReceived":{"Data":"55AA0300000102","Cmnd":0,"CmndData":"01"}}"#;
+        assert!(parse_tuya_received(json).unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed_payloads_fail() {
+        assert!(parse_tuya_received(r#"{"POWER":"ON"}"#).is_err());
+
+        let json = r#"{"TuyaReceived":{"1":{"DpId":1,"DpIdType":1,"DpIdData":"0"}}}"#;
+        assert!(parse_tuya_received(json).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Button press types.
+//!
+//! With `SetOption73` enabled, buttons are detached from the relays and
+//! Tasmota reports each press as `{"Button1":{"Action":"SINGLE"}}` instead
+//! of toggling a relay. See
+//! [`Device::set_buttons_detached`](crate::Device::set_buttons_detached).
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::ValueError;
+
+/// Highest button number reported by Tasmota (`Button1`-`Button32`).
+pub const MAX_BUTTONS: u8 = 32;
+
+/// A press reported by a detached button.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::types::ButtonAction;
+///
+/// let action: ButtonAction = "DOUBLE".parse().unwrap();
+/// assert_eq!(action, ButtonAction::Double);
+/// assert_eq!(action.press_count(), Some(2));
+/// assert_eq!(ButtonAction::Hold.press_count(), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum ButtonAction {
+    /// Pressed once.
+    Single,
+    /// Pressed twice in a row.
+    Double,
+    /// Pressed three times in a row.
+    Triple,
+    /// Pressed four times in a row.
+    Quad,
+    /// Pressed five times in a row.
+    Penta,
+    /// Held down longer than the `SetOption32` hold time (4 seconds by
+    /// default).
+    Hold,
+}
+
+impl ButtonAction {
+    /// Returns the action name used by Tasmota.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Single => "SINGLE",
+            Self::Double => "DOUBLE",
+            Self::Triple => "TRIPLE",
+            Self::Quad => "QUAD",
+            Self::Penta => "PENTA",
+            Self::Hold => "HOLD",
+        }
+    }
+
+    /// Returns the number of presses, or `None` for [`Hold`](Self::Hold).
+    #[must_use]
+    pub const fn press_count(&self) -> Option<u8> {
+        match self {
+            Self::Single => Some(1),
+            Self::Double => Some(2),
+            Self::Triple => Some(3),
+            Self::Quad => Some(4),
+            Self::Penta => Some(5),
+            Self::Hold => None,
+        }
+    }
+}
+
+impl fmt::Display for ButtonAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ButtonAction {
+    type Err = ValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "SINGLE" => Ok(Self::Single),
+            "DOUBLE" => Ok(Self::Double),
+            "TRIPLE" => Ok(Self::Triple),
+            "QUAD" => Ok(Self::Quad),
+            "PENTA" => Ok(Self::Penta),
+            "HOLD" => Ok(Self::Hold),
+            _ => Err(ValueError::InvalidButtonAction(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_round_trip() {
+        for action in [
+            ButtonAction::Single,
+            ButtonAction::Double,
+            ButtonAction::Triple,
+            ButtonAction::Quad,
+            ButtonAction::Penta,
+            ButtonAction::Hold,
+        ] {
+            assert_eq!(action.to_string().parse::<ButtonAction>(), Ok(action));
+        }
+        assert_eq!("single".parse::<ButtonAction>(), Ok(ButtonAction::Single));
+    }
+
+    #[test]
+    fn parse_unknown_action() {
+        assert_eq!(
+            "CLEAR".parse::<ButtonAction>(),
+            Err(ValueError::InvalidButtonAction("CLEAR".to_string()))
+        );
+    }
+}
@@ -309,6 +309,62 @@ impl HsbColor {
         Self::new(self.hue, self.saturation, brightness)
     }
 
+    /// Highest saturation still treated as white by
+    /// [`as_color_temperature`](Self::as_color_temperature).
+    ///
+    /// Blackbody light reaches this saturation around 2650K; warmer tints
+    /// are closer to orange than to white.
+    pub const WHITE_MAX_SATURATION: u8 = 70;
+
+    /// Approximates this color as a white color temperature.
+    ///
+    /// Returns `Some` only for colors close to the blackbody curve:
+    ///
+    /// - saturation up to 5% counts as neutral white, whatever the hue,
+    ///   and maps to [`ColorTemperature::COOL`];
+    /// - otherwise the hue must be in the warm band 20°-40° and the
+    ///   saturation at most [`WHITE_MAX_SATURATION`](Self::WHITE_MAX_SATURATION).
+    ///   The saturation sets the temperature, from cool (low saturation) to
+    ///   about 2650K (370 mireds) at the threshold.
+    ///
+    /// Brightness is ignored. Saturated or non-orange colors return `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tasmor_lib::types::{ColorTemperature, HsbColor};
+    ///
+    /// assert_eq!(HsbColor::white().as_color_temperature(), Some(ColorTemperature::COOL));
+    ///
+    /// // Warm white (about 4000K)
+    /// let warm = HsbColor::new(30, 36, 80).unwrap();
+    /// assert_eq!(warm.as_color_temperature().unwrap().value(), 250);
+    ///
+    /// // Saturated colors stay colors
+    /// assert_eq!(HsbColor::red().as_color_temperature(), None);
+    /// assert_eq!(HsbColor::new(200, 30, 100).unwrap().as_color_temperature(), None);
+    /// ```
+    #[must_use]
+    pub fn as_color_temperature(&self) -> Option<ColorTemperature> {
+        /// Saturation of blackbody light (hue about 30°) by temperature in mireds.
+        const BLACKBODY: [(u8, u16); 5] = [(0, 153), (19, 200), (36, 250), (58, 333), (70, 370)];
+
+        if self.saturation <= 5 {
+            return Some(ColorTemperature::COOL);
+        }
+        if !(20..=40).contains(&self.hue) || self.saturation > Self::WHITE_MAX_SATURATION {
+            return None;
+        }
+
+        BLACKBODY.windows(2).find_map(|pair| {
+            let ((s0, m0), (s1, m1)) = (pair[0], pair[1]);
+            (s0..=s1).contains(&self.saturation).then(|| {
+                let offset = u16::from(self.saturation - s0) * (m1 - m0) / u16::from(s1 - s0);
+                ColorTemperature::clamped(m0 + offset)
+            })
+        })
+    }
+
     /// Converts this HSB color to RGB format.
     ///
     /// Note: Due to rounding in the conversion, converting HSB to RGB and back
@@ -491,4 +547,26 @@ mod tests {
         let result: Result<HsbColor, _> = (180u16, 101u8, 50u8).try_into();
         assert!(result.is_err());
     }
+
+    #[test]
+    fn hsb_color_as_color_temperature() {
+        let ct = |h, s| HsbColor::new(h, s, 100).unwrap().as_color_temperature();
+
+        // Neutral whites, any hue
+        assert_eq!(ct(0, 0), Some(ColorTemperature::COOL));
+        assert_eq!(ct(220, 5), Some(ColorTemperature::COOL));
+
+        // Warmer as saturation grows along the blackbody hue band
+        assert_eq!(ct(30, 19).map(|c| c.value()), Some(200));
+        assert_eq!(ct(25, 58).map(|c| c.value()), Some(333));
+        assert_eq!(ct(40, 70).map(|c| c.value()), Some(370));
+        let values: Vec<u16> = (6..=70).map(|s| ct(30, s).unwrap().value()).collect();
+        assert!(values.windows(2).all(|w| w[0] <= w[1]));
+
+        // Too saturated or wrong hue
+        assert_eq!(ct(30, 71), None);
+        assert_eq!(ct(19, 30), None);
+        assert_eq!(ct(41, 30), None);
+        assert_eq!(ct(240, 20), None);
+    }
 }
@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tasmota firmware version numbers.
+
+use std::fmt;
+
+/// A Tasmota firmware version, used to gate features on newer firmware.
+///
+/// Versions compare numerically: `major.minor.patch.build`, where `build`
+/// is the development build number (0 for releases).
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::types::FirmwareVersion;
+///
+/// let version = FirmwareVersion::parse("13.1.0(release-tasmota)").unwrap();
+/// assert_eq!(version, FirmwareVersion::new(13, 1, 0));
+/// assert!(version >= FirmwareVersion::new(9, 2, 0));
+///
+/// let dev = FirmwareVersion::parse("9.1.0.2(tasmota)").unwrap();
+/// assert!(dev < FirmwareVersion::new(9, 2, 0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FirmwareVersion {
+    major: u16,
+    minor: u16,
+    patch: u16,
+    build: u16,
+}
+
+impl FirmwareVersion {
+    /// Creates a release version.
+    #[must_use]
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            build: 0,
+        }
+    }
+
+    /// Parses the version reported in `Status 2` (e.g. `"13.1.0(tasmota)"`).
+    ///
+    /// The build variant in parentheses is ignored. Returns `None` if the
+    /// string does not start with a dotted version number.
+    #[must_use]
+    pub fn parse(version: &str) -> Option<Self> {
+        let number = version.split('(').next()?.trim();
+        let mut parts = number.split('.').map(str::parse::<u16>);
+        let major = parts.next()?.ok()?;
+        let minor = parts.next()?.ok()?;
+        let patch = parts.next().transpose().ok()?.unwrap_or(0);
+        let build = parts.next().transpose().ok()?.unwrap_or(0);
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            major,
+            minor,
+            patch,
+            build,
+        })
+    }
+
+    /// Returns the major version.
+    #[must_use]
+    pub const fn major(&self) -> u16 {
+        self.major
+    }
+
+    /// Returns the minor version.
+    #[must_use]
+    pub const fn minor(&self) -> u16 {
+        self.minor
+    }
+
+    /// Returns the patch version.
+    #[must_use]
+    pub const fn patch(&self) -> u16 {
+        self.patch
+    }
+
+    /// Returns the development build number (0 for releases).
+    #[must_use]
+    pub const fn build(&self) -> u16 {
+        self.build
+    }
+}
+
+impl fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if self.build > 0 {
+            write!(f, ".{}", self.build)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_release_and_development_versions() {
+        assert_eq!(
+            FirmwareVersion::parse("14.2.0(tasmota32)"),
+            Some(FirmwareVersion::new(14, 2, 0))
+        );
+        let dev = FirmwareVersion::parse("12.4.0.3(tasmota)").unwrap();
+        assert_eq!(dev.build(), 3);
+        assert_eq!(dev.to_string(), "12.4.0.3");
+        assert!(dev > FirmwareVersion::new(12, 4, 0));
+    }
+
+    #[test]
+    fn parse_rejects_garbage() {
+        assert_eq!(FirmwareVersion::parse(""), None);
+        assert_eq!(FirmwareVersion::parse("tasmota"), None);
+        assert_eq!(FirmwareVersion::parse("1.2.x"), None);
+    }
+}
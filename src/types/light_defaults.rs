@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Light behavior settings.
+
+use crate::command::SetOptionCommand;
+use crate::response::SetOptionFlags;
+
+/// `SetOption20`: update dimmer, color and CT without turning power on.
+const UPDATE_WITHOUT_POWER: u16 = 20;
+
+/// `SetOption82`: reduce the CT range to 200-380 mireds.
+const REDUCED_CT_RANGE: u16 = 82;
+
+/// `SetOption91`: fade at startup.
+const FADE_AT_STARTUP: u16 = 91;
+
+/// `SetOption117`: fade in a fixed duration instead of at a fixed rate.
+const FIXED_FADE_DURATION: u16 = 117;
+
+/// How a light behaves on power-up and on changes.
+///
+/// Groups the light-related `SetOption`s under descriptive names:
+///
+/// | Setting | `SetOption` | Tasmota default |
+/// |---------|-------------|-----------------|
+/// | [`power_on_with_change`](Self::power_on_with_change) | `SetOption20` (inverted) | on |
+/// | [`reduced_ct_range`](Self::reduced_ct_range) | `SetOption82` | off |
+/// | [`fade_at_startup`](Self::fade_at_startup) | `SetOption91` | off |
+/// | [`fixed_fade_duration`](Self::fixed_fade_duration) | `SetOption117` | off |
+///
+/// Settings left unset are not changed by
+/// [`Device::apply_light_defaults`](crate::Device::apply_light_defaults),
+/// and are the ones the firmware did not report when read with
+/// [`Device::read_light_defaults`](crate::Device::read_light_defaults).
+///
+/// Tasmota restores the last brightness and color after a restart on its
+/// own; no `SetOption` selects a startup brightness or turns color
+/// persistence off, so neither is part of these settings.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::types::LightDefaults;
+///
+/// // Adjust the color of a switched-off light without turning it on
+/// let defaults = LightDefaults::new()
+///     .with_power_on_with_change(false)
+///     .with_fade_at_startup(true);
+///
+/// assert_eq!(defaults.power_on_with_change(), Some(false));
+/// assert_eq!(defaults.reduced_ct_range(), None);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LightDefaults {
+    power_on_with_change: Option<bool>,
+    reduced_ct_range: Option<bool>,
+    fade_at_startup: Option<bool>,
+    fixed_fade_duration: Option<bool>,
+}
+
+impl LightDefaults {
+    /// Creates settings that change nothing.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether changing brightness, color or CT turns the light on
+    /// (`SetOption20 0`).
+    #[must_use]
+    pub fn with_power_on_with_change(mut self, enabled: bool) -> Self {
+        self.power_on_with_change = Some(enabled);
+        self
+    }
+
+    /// Sets whether the CT range is reduced to 200-380 mireds, the range of
+    /// Alexa (`SetOption82`).
+    #[must_use]
+    pub fn with_reduced_ct_range(mut self, enabled: bool) -> Self {
+        self.reduced_ct_range = Some(enabled);
+        self
+    }
+
+    /// Sets whether the light fades in at startup (`SetOption91`).
+    #[must_use]
+    pub fn with_fade_at_startup(mut self, enabled: bool) -> Self {
+        self.fade_at_startup = Some(enabled);
+        self
+    }
+
+    /// Sets whether every fade takes the full `Speed` duration instead of
+    /// moving at a fixed rate (`SetOption117`).
+    #[must_use]
+    pub fn with_fixed_fade_duration(mut self, enabled: bool) -> Self {
+        self.fixed_fade_duration = Some(enabled);
+        self
+    }
+
+    /// Returns whether changing brightness, color or CT turns the light on.
+    #[must_use]
+    pub fn power_on_with_change(&self) -> Option<bool> {
+        self.power_on_with_change
+    }
+
+    /// Returns whether the CT range is reduced to 200-380 mireds.
+    #[must_use]
+    pub fn reduced_ct_range(&self) -> Option<bool> {
+        self.reduced_ct_range
+    }
+
+    /// Returns whether the light fades in at startup.
+    #[must_use]
+    pub fn fade_at_startup(&self) -> Option<bool> {
+        self.fade_at_startup
+    }
+
+    /// Returns whether fades take a fixed duration.
+    #[must_use]
+    pub fn fixed_fade_duration(&self) -> Option<bool> {
+        self.fixed_fade_duration
+    }
+
+    /// Reads the settings from a `SetOption` dump.
+    #[must_use]
+    pub fn from_set_options(flags: &SetOptionFlags) -> Self {
+        Self {
+            power_on_with_change: flags.is_enabled(UPDATE_WITHOUT_POWER).map(|off| !off),
+            reduced_ct_range: flags.is_enabled(REDUCED_CT_RANGE),
+            fade_at_startup: flags.is_enabled(FADE_AT_STARTUP),
+            fixed_fade_duration: flags.is_enabled(FIXED_FADE_DURATION),
+        }
+    }
+
+    /// Returns the commands applying the settings that are set.
+    pub(crate) fn commands(self) -> Vec<SetOptionCommand> {
+        [
+            (
+                UPDATE_WITHOUT_POWER,
+                self.power_on_with_change.map(|on| !on),
+            ),
+            (REDUCED_CT_RANGE, self.reduced_ct_range),
+            (FADE_AT_STARTUP, self.fade_at_startup),
+            (FIXED_FADE_DURATION, self.fixed_fade_duration),
+        ]
+        .into_iter()
+        .filter_map(|(number, enabled)| Some(SetOptionCommand::set(number, enabled?)))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Command;
+
+    #[test]
+    fn only_set_options_are_applied() {
+        let defaults = LightDefaults::new()
+            .with_power_on_with_change(false)
+            .with_fixed_fade_duration(true);
+        let commands: Vec<String> = defaults
+            .commands()
+            .iter()
+            .map(Command::to_http_command)
+            .collect();
+
+        assert_eq!(commands, ["SetOption20 1", "SetOption117 1"]);
+        assert!(LightDefaults::new().commands().is_empty());
+    }
+
+    #[test]
+    fn read_from_set_options() {
+        // Word 3 starts at SetOption82: bit 9 is SetOption91
+        let flags =
+            SetOptionFlags::from_words(&["00000000", "00", "00000000", "00000200"]).unwrap();
+        let defaults = LightDefaults::from_set_options(&flags);
+
+        assert_eq!(defaults.power_on_with_change(), Some(true));
+        assert_eq!(defaults.reduced_ct_range(), Some(false));
+        assert_eq!(defaults.fade_at_startup(), Some(true));
+        // Word 4 was not reported
+        assert_eq!(defaults.fixed_fade_duration(), None);
+    }
+}
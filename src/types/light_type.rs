@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Kind of light fixture, by the channels it drives.
+//!
+//! # Device Methods
+//!
+//! - [`detect_light_type()`](crate::Device::detect_light_type) - Infer the
+//!   fixture type from a `Color` query
+
+use std::fmt;
+
+/// Kind of light fixture, following Tasmota's 1 to 5 channel light types.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::types::LightType;
+///
+/// let light = LightType::from_channel_count(5);
+/// assert_eq!(light, LightType::Rgbcct);
+/// assert!(light.has_rgb() && light.has_color_temperature());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LightType {
+    /// One channel: a dimmable white light.
+    Single,
+    /// Two channels: cold and warm white (tunable white).
+    Cct,
+    /// Three channels: red, green and blue.
+    Rgb,
+    /// Four channels: RGB plus one white channel.
+    Rgbw,
+    /// Five channels: RGB plus cold and warm white.
+    Rgbcct,
+    /// The fixture type could not be determined.
+    Unknown,
+}
+
+impl LightType {
+    /// Returns the light type driving `count` channels.
+    ///
+    /// Counts outside 1-5 give [`LightType::Unknown`].
+    #[must_use]
+    pub const fn from_channel_count(count: usize) -> Self {
+        match count {
+            1 => Self::Single,
+            2 => Self::Cct,
+            3 => Self::Rgb,
+            4 => Self::Rgbw,
+            5 => Self::Rgbcct,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Returns the number of channels, or `None` for an unknown type.
+    #[must_use]
+    pub const fn channel_count(self) -> Option<u8> {
+        match self {
+            Self::Single => Some(1),
+            Self::Cct => Some(2),
+            Self::Rgb => Some(3),
+            Self::Rgbw => Some(4),
+            Self::Rgbcct => Some(5),
+            Self::Unknown => None,
+        }
+    }
+
+    /// Returns `true` if the fixture has RGB channels.
+    #[must_use]
+    pub const fn has_rgb(self) -> bool {
+        matches!(self, Self::Rgb | Self::Rgbw | Self::Rgbcct)
+    }
+
+    /// Returns `true` if the fixture has tunable white (`CT`).
+    #[must_use]
+    pub const fn has_color_temperature(self) -> bool {
+        matches!(self, Self::Cct | Self::Rgbcct)
+    }
+
+    /// Returns the type's short name, e.g. `"RGBCCT"`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Single => "Single",
+            Self::Cct => "CCT",
+            Self::Rgb => "RGB",
+            Self::Rgbw => "RGBW",
+            Self::Rgbcct => "RGBCCT",
+            Self::Unknown => "Unknown",
+        }
+    }
+}
+
+impl fmt::Display for LightType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_count_round_trip() {
+        for count in 1..=5 {
+            let light = LightType::from_channel_count(count);
+            assert_eq!(light.channel_count().map(usize::from), Some(count));
+        }
+        assert_eq!(LightType::from_channel_count(0), LightType::Unknown);
+        assert_eq!(LightType::from_channel_count(6), LightType::Unknown);
+    }
+
+    #[test]
+    fn features() {
+        assert!(LightType::Rgbw.has_rgb());
+        assert!(!LightType::Rgbw.has_color_temperature());
+        assert!(LightType::Cct.has_color_temperature());
+        assert!(!LightType::Single.has_rgb());
+        assert_eq!(LightType::Rgbcct.to_string(), "RGBCCT");
+    }
+}
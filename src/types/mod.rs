@@ -23,6 +23,11 @@
 //! | [`WakeupDuration`] | 1-3000 seconds | Duration for wakeup effect |
 //! | [`FadeDuration`] | 0.5-20 seconds | Duration for fade transitions |
 //! | [`TasmotaDateTime`] | ISO 8601 | Datetime from telemetry |
+//! | [`WebColors`] | 18 hex colors | Web UI color theme |
+//! | [`TuyaValue`] | Per [`TuyaDpType`] | Tuya MCU data point value |
+//! | [`TopicScheme`] | `FullTopic` template | MQTT topic layout of a device |
+//! | [`ButtonAction`] | Single-Penta/Hold | Press of a detached button |
+//! | [`LightType`] | 1-5 channels | Kind of light fixture |
 //!
 //! # Construction Patterns
 //!
@@ -64,18 +69,34 @@
 //! # Ok::<(), tasmor_lib::ValueError>(())
 //! ```
 
+mod button;
 mod color;
 mod datetime;
 mod dimmer;
+mod firmware;
+mod light_defaults;
+mod light_type;
 mod power;
 mod rgb_color;
+mod rgbww_color;
 mod scheme;
 mod time;
+mod topic_scheme;
+mod tuya;
+mod web_colors;
 
+pub use button::{ButtonAction, MAX_BUTTONS};
 pub use color::{ColorTemperature, HsbColor};
 pub use datetime::{DateTimeParseError, TasmotaDateTime};
 pub use dimmer::Dimmer;
-pub use power::{PowerIndex, PowerState};
+pub use firmware::FirmwareVersion;
+pub use light_defaults::LightDefaults;
+pub use light_type::LightType;
+pub use power::{PowerIndex, PowerQuery, PowerState};
 pub use rgb_color::RgbColor;
+pub use rgbww_color::RgbwwColor;
 pub use scheme::Scheme;
 pub use time::{FadeDuration, WakeupDuration, parse_uptime};
+pub use topic_scheme::{DEFAULT_FULL_TOPIC, RemoteTopic, TopicPrefix, TopicScheme};
+pub use tuya::{TuyaDpType, TuyaValue};
+pub use web_colors::{WEB_COLOR_COUNT, WebColors};
@@ -103,6 +103,60 @@ impl From<bool> for PowerState {
     }
 }
 
+/// Outcome of asking a device for a relay's power state.
+///
+/// Unlike a plain [`PowerState`], this keeps "the device did not answer"
+/// apart from "the device answered off", so a UI can show an unreachable
+/// device as unknown instead of off.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::types::{PowerQuery, PowerState};
+///
+/// assert_eq!(PowerQuery::from(PowerState::On).state(), Some(PowerState::On));
+/// assert_eq!(PowerQuery::Unknown.state(), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum PowerQuery {
+    /// The device reported the relay on.
+    On,
+    /// The device reported the relay off.
+    Off,
+    /// The device did not answer in time, or did not report this relay.
+    Unknown,
+}
+
+impl PowerQuery {
+    /// Returns the reported power state, or `None` if unknown.
+    #[must_use]
+    pub const fn state(&self) -> Option<PowerState> {
+        match self {
+            Self::On => Some(PowerState::On),
+            Self::Off => Some(PowerState::Off),
+            Self::Unknown => None,
+        }
+    }
+
+    /// Returns `true` if the device reported a state.
+    #[must_use]
+    pub const fn is_known(&self) -> bool {
+        !matches!(self, Self::Unknown)
+    }
+}
+
+impl From<PowerState> for PowerQuery {
+    /// Converts a reported state. Only `On` and `Off` are reported by
+    /// devices; other states convert to `Unknown`.
+    fn from(state: PowerState) -> Self {
+        match state {
+            PowerState::On => Self::On,
+            PowerState::Off => Self::Off,
+            _ => Self::Unknown,
+        }
+    }
+}
+
 /// Index of a power channel on a multi-relay device.
 ///
 /// Tasmota devices can have up to 8 relays, indexed from 1 to 8.
@@ -226,6 +280,16 @@ mod tests {
         assert_eq!("toggle".parse::<PowerState>().unwrap(), PowerState::Toggle);
     }
 
+    #[test]
+    fn power_query_from_state() {
+        assert_eq!(PowerQuery::from(PowerState::On), PowerQuery::On);
+        assert_eq!(PowerQuery::from(PowerState::Off), PowerQuery::Off);
+        assert_eq!(PowerQuery::from(PowerState::Toggle), PowerQuery::Unknown);
+        assert!(PowerQuery::Off.is_known());
+        assert!(!PowerQuery::Unknown.is_known());
+        assert_eq!(PowerQuery::Off.state(), Some(PowerState::Off));
+    }
+
     #[test]
     fn power_state_from_str_invalid() {
         let result = "invalid".parse::<PowerState>();
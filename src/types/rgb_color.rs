@@ -300,7 +300,7 @@ fn parse_hex_char(c: char) -> Result<u8, ValueError> {
 }
 
 // Helper function to parse a two-character hex pair
-fn parse_hex_pair(s: &str) -> Result<u8, ValueError> {
+pub(super) fn parse_hex_pair(s: &str) -> Result<u8, ValueError> {
     u8::from_str_radix(s, 16).map_err(|_| ValueError::InvalidHexColor(s.to_string()))
 }
 
@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! RGB color with white channels for 5-channel (RGBCCT) lights.
+//!
+//! # Device Methods
+//!
+//! Use [`RgbwwColor`] with this [`Device`](crate::Device) method:
+//! - [`set_rgbww_color()`](crate::Device::set_rgbww_color) - Set all five channels
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::ValueError;
+
+use super::RgbColor;
+use super::rgb_color::parse_hex_pair;
+
+/// Color of a 5-channel light: red, green, blue, cold white and warm white,
+/// each 0-255.
+///
+/// Unlike [`RgbColor`], which is sent as `HSBColor` and leaves the white
+/// LEDs to the device, this sets every channel at once, so an RGB accent
+/// can be mixed with a tuned white.
+///
+/// The hex form is the one of Tasmota's `Color` command, `RRGGBBCCWW`:
+/// Tasmota orders the cold white channel before the warm white one.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::types::RgbwwColor;
+///
+/// // Dim red accent over warm white
+/// let color = RgbwwColor::new(64, 0, 0, 0, 200);
+/// assert_eq!(color.to_hex(), "40000000C8");
+///
+/// let parsed = RgbwwColor::from_hex("#40000000C8").unwrap();
+/// assert_eq!(parsed, color);
+/// assert_eq!(parsed.warm_white(), 200);
+///
+/// // All five channels are required
+/// assert!(RgbwwColor::from_hex("#FF0000").is_err());
+/// ```
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct RgbwwColor {
+    red: u8,
+    green: u8,
+    blue: u8,
+    cold_white: u8,
+    warm_white: u8,
+}
+
+impl RgbwwColor {
+    /// Creates a color from its channels, in Tasmota's channel order.
+    #[must_use]
+    pub const fn new(red: u8, green: u8, blue: u8, cold_white: u8, warm_white: u8) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            cold_white,
+            warm_white,
+        }
+    }
+
+    /// Creates a color from an RGB color and white levels.
+    #[must_use]
+    pub const fn from_rgb(rgb: RgbColor, cold_white: u8, warm_white: u8) -> Self {
+        Self::new(rgb.red(), rgb.green(), rgb.blue(), cold_white, warm_white)
+    }
+
+    /// Parses a color from a 10-digit hex string, `RRGGBBCCWW` or
+    /// `#RRGGBBCCWW`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidHexColor`] if the string is not 10 hex
+    /// digits.
+    pub fn from_hex(hex: &str) -> Result<Self, ValueError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if digits.len() != 10 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ValueError::InvalidHexColor(hex.to_string()));
+        }
+
+        let channel = |i: usize| parse_hex_pair(&digits[i * 2..i * 2 + 2]);
+        Ok(Self::new(
+            channel(0)?,
+            channel(1)?,
+            channel(2)?,
+            channel(3)?,
+            channel(4)?,
+        ))
+    }
+
+    /// Returns the red channel.
+    #[must_use]
+    pub const fn red(&self) -> u8 {
+        self.red
+    }
+
+    /// Returns the green channel.
+    #[must_use]
+    pub const fn green(&self) -> u8 {
+        self.green
+    }
+
+    /// Returns the blue channel.
+    #[must_use]
+    pub const fn blue(&self) -> u8 {
+        self.blue
+    }
+
+    /// Returns the cold white channel.
+    #[must_use]
+    pub const fn cold_white(&self) -> u8 {
+        self.cold_white
+    }
+
+    /// Returns the warm white channel.
+    #[must_use]
+    pub const fn warm_white(&self) -> u8 {
+        self.warm_white
+    }
+
+    /// Returns the RGB part of the color.
+    #[must_use]
+    pub const fn rgb(&self) -> RgbColor {
+        RgbColor::new(self.red, self.green, self.blue)
+    }
+
+    /// Returns the color as a 10-digit hex string without the hash prefix.
+    #[must_use]
+    pub fn to_hex(&self) -> String {
+        format!(
+            "{:02X}{:02X}{:02X}{:02X}{:02X}",
+            self.red, self.green, self.blue, self.cold_white, self.warm_white
+        )
+    }
+}
+
+impl fmt::Display for RgbwwColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.to_hex())
+    }
+}
+
+impl FromStr for RgbwwColor {
+    type Err = ValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_round_trip() {
+        let color = RgbwwColor::new(0x12, 0x34, 0x56, 0x78, 0x9A);
+        assert_eq!(color.to_hex(), "123456789A");
+        assert_eq!(color.to_string(), "#123456789A");
+        assert_eq!("123456789a".parse::<RgbwwColor>().unwrap(), color);
+    }
+
+    #[test]
+    fn invalid_hex_is_rejected() {
+        for hex in [
+            "",
+            "#FF0000",
+            "FF00000000FF",
+            "GG00000000",
+            "+F00000000",
+            "#FF000000é",
+        ] {
+            assert!(
+                matches!(
+                    RgbwwColor::from_hex(hex),
+                    Err(ValueError::InvalidHexColor(_))
+                ),
+                "{hex:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn from_rgb_keeps_channels() {
+        let color = RgbwwColor::from_rgb(RgbColor::new(255, 128, 0), 10, 20);
+        assert_eq!(color.rgb(), RgbColor::new(255, 128, 0));
+        assert_eq!((color.cold_white(), color.warm_white()), (10, 20));
+    }
+}
@@ -499,7 +499,7 @@ mod tests {
     fn wakeup_duration_valid_values() {
         for v in [1, 60, 300, 1800, 3000] {
             let duration = WakeupDuration::new(Duration::from_secs(v)).unwrap();
-            assert_eq!(duration.seconds(), v as u16);
+            assert_eq!(u64::from(duration.seconds()), v);
         }
     }
 
@@ -0,0 +1,379 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! MQTT topic layout of a Tasmota device.
+
+use crate::error::ValueError;
+
+/// Tasmota's default `FullTopic`.
+pub const DEFAULT_FULL_TOPIC: &str = "%prefix%/%topic%/";
+
+const PREFIX_TOKEN: &str = "%prefix%";
+const TOPIC_TOKEN: &str = "%topic%";
+
+/// The three topic prefixes Tasmota substitutes for `%prefix%`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TopicPrefix {
+    /// Commands sent to the device (`Prefix1`, default `cmnd`).
+    Command,
+    /// Command results and status replies (`Prefix2`, default `stat`).
+    Stat,
+    /// Periodic telemetry (`Prefix3`, default `tele`).
+    Tele,
+}
+
+impl TopicPrefix {
+    /// All prefixes, in `Prefix1`-`Prefix3` order.
+    pub const ALL: [Self; 3] = [Self::Command, Self::Stat, Self::Tele];
+
+    /// Returns Tasmota's default value for this prefix.
+    #[must_use]
+    pub const fn default_value(self) -> &'static str {
+        match self {
+            Self::Command => "cmnd",
+            Self::Stat => "stat",
+            Self::Tele => "tele",
+        }
+    }
+
+    /// Returns the index of the matching `Prefix<n>` command (1-3).
+    #[must_use]
+    pub const fn index(self) -> u8 {
+        match self {
+            Self::Command => 1,
+            Self::Stat => 2,
+            Self::Tele => 3,
+        }
+    }
+}
+
+/// How a device builds its MQTT topics.
+///
+/// Tasmota derives every topic from its `FullTopic` template: `%prefix%` is
+/// replaced by one of the [`TopicPrefix`] values and `%topic%` by the device
+/// topic, then the command or message name is appended. The default
+/// template `%prefix%/%topic%/` gives topics like `stat/tasmota/POWER`;
+/// a device configured with `FullTopic home/%topic%/%prefix%/` publishes
+/// `home/tasmota/stat/POWER` instead.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::types::{TopicPrefix, TopicScheme};
+///
+/// let scheme = TopicScheme::new("home/%topic%/%prefix%/")?;
+/// assert_eq!(
+///     scheme.topic(TopicPrefix::Command, "kitchen", "POWER"),
+///     "home/kitchen/cmnd/POWER"
+/// );
+/// assert_eq!(
+///     scheme.parse("home/kitchen/tele/STATE", "kitchen"),
+///     Some((TopicPrefix::Tele, "STATE"))
+/// );
+/// # Ok::<(), tasmor_lib::ValueError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicScheme {
+    full_topic: String,
+    prefixes: [String; 3],
+}
+
+impl Default for TopicScheme {
+    fn default() -> Self {
+        Self {
+            full_topic: DEFAULT_FULL_TOPIC.to_string(),
+            prefixes: TopicPrefix::ALL.map(|p| p.default_value().to_string()),
+        }
+    }
+}
+
+impl TopicScheme {
+    /// Creates a scheme from a `FullTopic` template, with the default
+    /// prefixes.
+    ///
+    /// Like Tasmota, a missing trailing `/` is added and repeated slashes
+    /// are collapsed. The `%hostname%` and `%id%` placeholders are not
+    /// supported; substitute the device's values before calling this.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidFullTopic`] if the template does not
+    /// contain both `%prefix%` and `%topic%`, contains MQTT wildcards, or
+    /// contains other placeholders.
+    pub fn new(full_topic: &str) -> Result<Self, ValueError> {
+        let mut full_topic = full_topic.trim().to_string();
+        if !full_topic.ends_with('/') {
+            full_topic.push('/');
+        }
+        while full_topic.contains("//") {
+            full_topic = full_topic.replace("//", "/");
+        }
+
+        if !full_topic.contains(PREFIX_TOKEN) || !full_topic.contains(TOPIC_TOKEN) {
+            return Err(ValueError::InvalidFullTopic(format!(
+                "{full_topic} must contain {PREFIX_TOKEN} and {TOPIC_TOKEN}"
+            )));
+        }
+        if full_topic.contains(['+', '#']) {
+            return Err(ValueError::InvalidFullTopic(format!(
+                "{full_topic} contains MQTT wildcards"
+            )));
+        }
+        if full_topic
+            .replace(PREFIX_TOKEN, "")
+            .replace(TOPIC_TOKEN, "")
+            .contains('%')
+        {
+            return Err(ValueError::InvalidFullTopic(format!(
+                "{full_topic} contains unsupported placeholders"
+            )));
+        }
+
+        Ok(Self {
+            full_topic,
+            ..Self::default()
+        })
+    }
+
+    /// Replaces one of the prefixes (Tasmota `Prefix1`-`Prefix3`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidFullTopic`] if the value is empty or
+    /// contains `/` or MQTT wildcards.
+    pub fn with_prefix(
+        mut self,
+        prefix: TopicPrefix,
+        value: impl Into<String>,
+    ) -> Result<Self, ValueError> {
+        let value = value.into();
+        if value.is_empty() || value.contains(['/', '+', '#']) {
+            return Err(ValueError::InvalidFullTopic(format!(
+                "invalid prefix {value:?}"
+            )));
+        }
+        self.prefixes[usize::from(prefix.index() - 1)] = value;
+        Ok(self)
+    }
+
+    /// Returns the normalized `FullTopic` template.
+    #[must_use]
+    pub fn full_topic(&self) -> &str {
+        &self.full_topic
+    }
+
+    /// Returns the value substituted for `%prefix%`.
+    #[must_use]
+    pub fn prefix(&self, prefix: TopicPrefix) -> &str {
+        &self.prefixes[usize::from(prefix.index() - 1)]
+    }
+
+    /// Returns `true` if this is Tasmota's default layout.
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Builds the MQTT topic for a device and message name.
+    ///
+    /// Use `+` as `suffix` to get a subscription filter.
+    #[must_use]
+    pub fn topic(&self, prefix: TopicPrefix, device_topic: &str, suffix: &str) -> String {
+        let mut topic = self
+            .full_topic
+            .replace(PREFIX_TOKEN, self.prefix(prefix))
+            .replace(TOPIC_TOKEN, device_topic);
+        topic.push_str(suffix);
+        topic
+    }
+
+    /// Matches an MQTT topic against this scheme for the given device.
+    ///
+    /// Returns the prefix and the message name (e.g. `POWER`, `STATE`), or
+    /// `None` if the topic does not belong to the device.
+    #[must_use]
+    pub fn parse<'a>(
+        &self,
+        mqtt_topic: &'a str,
+        device_topic: &str,
+    ) -> Option<(TopicPrefix, &'a str)> {
+        TopicPrefix::ALL.into_iter().find_map(|prefix| {
+            let suffix = mqtt_topic.strip_prefix(&self.topic(prefix, device_topic, ""))?;
+            (!suffix.is_empty() && !suffix.contains('/')).then_some((prefix, suffix))
+        })
+    }
+}
+
+/// Maximum length of a button or switch topic.
+const MAX_REMOTE_TOPIC_LEN: usize = 32;
+
+/// Target of a device's `ButtonTopic` or `SwitchTopic`.
+///
+/// While a remote topic is set, the device's buttons (or switches) are
+/// decoupled from its own relays: pressing button `n` no longer switches
+/// relay `n` but publishes `cmnd/<topic>/POWER<n> TOGGLE` (switches publish
+/// the state their `SwitchMode` gives), so another device, or a whole group
+/// sharing that topic, reacts instead. This wires wall switches to lights
+/// entirely on-device.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::types::RemoteTopic;
+///
+/// let topic = RemoteTopic::new("living_room_lights")?;
+/// assert_eq!(topic.payload(), "living_room_lights");
+///
+/// // Tasmota's special values
+/// assert_eq!(RemoteTopic::new("0")?, RemoteTopic::Disabled);
+/// assert_eq!(RemoteTopic::new("1")?, RemoteTopic::DeviceTopic);
+///
+/// // Wildcards and levels are not allowed
+/// assert!(RemoteTopic::new("lights/#").is_err());
+/// # Ok::<(), tasmor_lib::ValueError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RemoteTopic {
+    /// Not set: inputs control the device's own relays (payload `0`).
+    Disabled,
+    /// Publish to the device's own topic (payload `1`).
+    ///
+    /// The device then reports the actual topic, so a read returns it as
+    /// [`Topic`](Self::Topic).
+    DeviceTopic,
+    /// Publish to the given device or group topic.
+    Topic(String),
+}
+
+impl RemoteTopic {
+    /// Parses a remote topic, accepting Tasmota's `0` and `1` special values.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidRemoteTopic`] if the topic is empty,
+    /// longer than 32 characters, another single digit (reserved by
+    /// Tasmota), or contains characters other than ASCII letters, digits,
+    /// `_`, `-` and `.`.
+    pub fn new(topic: impl Into<String>) -> Result<Self, ValueError> {
+        let topic = topic.into();
+        let valid_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.');
+        match topic.as_str() {
+            "0" => Ok(Self::Disabled),
+            "1" => Ok(Self::DeviceTopic),
+            t if t.is_empty()
+                || t.len() > MAX_REMOTE_TOPIC_LEN
+                || (t.len() == 1 && t.starts_with(|c: char| c.is_ascii_digit()))
+                || !t.chars().all(valid_char) =>
+            {
+                Err(ValueError::InvalidRemoteTopic(topic))
+            }
+            _ => Ok(Self::Topic(topic)),
+        }
+    }
+
+    /// Interprets the value a device reports, without validation.
+    pub(crate) fn from_reported(value: &str) -> Self {
+        match value {
+            "" | "0" => Self::Disabled,
+            "1" => Self::DeviceTopic,
+            topic => Self::Topic(topic.to_string()),
+        }
+    }
+
+    /// Returns the payload that sets this topic.
+    #[must_use]
+    pub fn payload(&self) -> &str {
+        match self {
+            Self::Disabled => "0",
+            Self::DeviceTopic => "1",
+            Self::Topic(topic) => topic,
+        }
+    }
+
+    /// Returns `true` if inputs are decoupled from the device's own relays.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, Self::Disabled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_scheme() {
+        let scheme = TopicScheme::default();
+        assert!(scheme.is_default());
+        assert_eq!(TopicScheme::new(DEFAULT_FULL_TOPIC).unwrap(), scheme);
+        assert_eq!(
+            scheme.topic(TopicPrefix::Stat, "tasmota", "+"),
+            "stat/tasmota/+"
+        );
+        assert_eq!(
+            scheme.parse("stat/tasmota/RESULT", "tasmota"),
+            Some((TopicPrefix::Stat, "RESULT"))
+        );
+    }
+
+    #[test]
+    fn custom_layout_and_prefixes() {
+        let scheme = TopicScheme::new("site1/%topic%/%prefix%")
+            .unwrap()
+            .with_prefix(TopicPrefix::Tele, "telemetry")
+            .unwrap();
+        assert_eq!(scheme.full_topic(), "site1/%topic%/%prefix%/");
+        assert!(!scheme.is_default());
+
+        assert_eq!(
+            scheme.topic(TopicPrefix::Tele, "plug", "SENSOR"),
+            "site1/plug/telemetry/SENSOR"
+        );
+        assert_eq!(
+            scheme.parse("site1/plug/telemetry/SENSOR", "plug"),
+            Some((TopicPrefix::Tele, "SENSOR"))
+        );
+        assert_eq!(scheme.parse("site1/plug/tele/SENSOR", "plug"), None);
+        assert_eq!(scheme.parse("site1/other/stat/POWER", "plug"), None);
+    }
+
+    #[test]
+    fn parse_rejects_nested_suffix() {
+        let scheme = TopicScheme::default();
+        assert_eq!(scheme.parse("stat/tasmota/", "tasmota"), None);
+        assert_eq!(scheme.parse("stat/tasmota/a/b", "tasmota"), None);
+    }
+
+    #[test]
+    fn invalid_templates() {
+        assert!(TopicScheme::new("%topic%/").is_err());
+        assert!(TopicScheme::new("%prefix%/%topic%/#").is_err());
+        assert!(TopicScheme::new("%prefix%/%hostname%/%topic%/").is_err());
+        assert!(
+            TopicScheme::default()
+                .with_prefix(TopicPrefix::Command, "a/b")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn remote_topic_validation() {
+        assert_eq!(
+            RemoteTopic::new("hall_lights").unwrap(),
+            RemoteTopic::Topic("hall_lights".to_string())
+        );
+        assert!(RemoteTopic::new("").is_err());
+        assert!(RemoteTopic::new("2").is_err());
+        assert!(RemoteTopic::new("a b").is_err());
+        assert!(RemoteTopic::new("+").is_err());
+        assert!(RemoteTopic::new("x".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn reported_remote_topic() {
+        assert_eq!(RemoteTopic::from_reported("0"), RemoteTopic::Disabled);
+        assert!(RemoteTopic::from_reported("kitchen").is_enabled());
+    }
+}
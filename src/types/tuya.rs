@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tuya MCU data point values.
+
+use std::fmt::{self, Write};
+
+/// Data point type as declared by a Tuya MCU.
+///
+/// The discriminants are the type codes of the Tuya serial protocol, as
+/// reported in the `DpIdType` field of `TuyaReceived` messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum TuyaDpType {
+    /// Raw bytes.
+    Raw = 0,
+    /// Boolean (1 byte).
+    Bool = 1,
+    /// Integer value (4 bytes, big-endian).
+    Value = 2,
+    /// String.
+    String = 3,
+    /// Enumeration (1 byte).
+    Enum = 4,
+    /// Bitmap (1, 2 or 4 bytes).
+    Bitmap = 5,
+}
+
+impl TuyaDpType {
+    /// Returns the type for a Tuya type code, or `None` if unknown.
+    #[must_use]
+    pub const fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(Self::Raw),
+            1 => Some(Self::Bool),
+            2 => Some(Self::Value),
+            3 => Some(Self::String),
+            4 => Some(Self::Enum),
+            5 => Some(Self::Bitmap),
+            _ => None,
+        }
+    }
+
+    /// Returns the Tuya type code.
+    #[must_use]
+    pub const fn code(self) -> u8 {
+        self as u8
+    }
+}
+
+/// A value sent to or received from a Tuya MCU data point.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::types::{TuyaDpType, TuyaValue};
+///
+/// // Decode a 4-byte integer data point
+/// let value = TuyaValue::decode(TuyaDpType::Value, &[0, 0, 0x01, 0xF4]);
+/// assert_eq!(value, TuyaValue::Int(500));
+///
+/// assert_eq!(TuyaValue::Bool(true).dp_type(), TuyaDpType::Bool);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TuyaValue {
+    /// Raw bytes (also used for bitmap data points).
+    Raw(Vec<u8>),
+    /// Boolean, e.g. a switch.
+    Bool(bool),
+    /// Integer, e.g. a dimmer level or a temperature.
+    Int(u32),
+    /// String.
+    String(String),
+    /// Enumeration index, e.g. a fan speed or mode.
+    Enum(u8),
+}
+
+impl TuyaValue {
+    /// Returns the data point type used to send this value.
+    #[must_use]
+    pub fn dp_type(&self) -> TuyaDpType {
+        match self {
+            Self::Raw(_) => TuyaDpType::Raw,
+            Self::Bool(_) => TuyaDpType::Bool,
+            Self::Int(_) => TuyaDpType::Value,
+            Self::String(_) => TuyaDpType::String,
+            Self::Enum(_) => TuyaDpType::Enum,
+        }
+    }
+
+    /// Decodes data point bytes according to their declared type.
+    ///
+    /// Integers use the last 4 bytes (shorter data is zero-extended),
+    /// bitmaps are kept as raw bytes and invalid UTF-8 in strings is
+    /// replaced.
+    #[must_use]
+    pub fn decode(dp_type: TuyaDpType, data: &[u8]) -> Self {
+        match dp_type {
+            TuyaDpType::Raw | TuyaDpType::Bitmap => Self::Raw(data.to_vec()),
+            TuyaDpType::Bool => Self::Bool(data.iter().any(|b| *b != 0)),
+            TuyaDpType::Value => Self::Int(
+                data[data.len().saturating_sub(4)..]
+                    .iter()
+                    .fold(0, |acc, b| (acc << 8) | u32::from(*b)),
+            ),
+            TuyaDpType::String => Self::String(String::from_utf8_lossy(data).into_owned()),
+            TuyaDpType::Enum => Self::Enum(data.last().copied().unwrap_or_default()),
+        }
+    }
+
+    /// Returns the value formatted as a `TuyaSend` argument.
+    ///
+    /// Booleans are sent as `0`/`1` and raw bytes as uppercase hex.
+    #[must_use]
+    pub fn to_payload(&self) -> String {
+        match self {
+            Self::Raw(bytes) => {
+                let mut hex = String::with_capacity(bytes.len() * 2);
+                for b in bytes {
+                    let _ = write!(hex, "{b:02X}");
+                }
+                hex
+            }
+            Self::Bool(b) => u8::from(*b).to_string(),
+            Self::Int(n) => n.to_string(),
+            Self::String(s) => s.clone(),
+            Self::Enum(n) => n.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for TuyaValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_payload())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_codes_round_trip() {
+        for code in 0..=5 {
+            assert_eq!(TuyaDpType::from_code(code).unwrap().code(), code);
+        }
+        assert!(TuyaDpType::from_code(6).is_none());
+    }
+
+    #[test]
+    fn decode_by_type() {
+        assert_eq!(
+            TuyaValue::decode(TuyaDpType::Bool, &[0x01]),
+            TuyaValue::Bool(true)
+        );
+        assert_eq!(
+            TuyaValue::decode(TuyaDpType::Value, &[0x00, 0x00, 0x03, 0xE8]),
+            TuyaValue::Int(1000)
+        );
+        assert_eq!(
+            TuyaValue::decode(TuyaDpType::String, b"auto"),
+            TuyaValue::String("auto".to_string())
+        );
+        assert_eq!(
+            TuyaValue::decode(TuyaDpType::Enum, &[0x02]),
+            TuyaValue::Enum(2)
+        );
+        assert_eq!(
+            TuyaValue::decode(TuyaDpType::Bitmap, &[0x00, 0x04]),
+            TuyaValue::Raw(vec![0x00, 0x04])
+        );
+    }
+
+    #[test]
+    fn payload_encoding() {
+        assert_eq!(TuyaValue::Bool(false).to_payload(), "0");
+        assert_eq!(TuyaValue::Int(255).to_payload(), "255");
+        assert_eq!(TuyaValue::Raw(vec![0x0A, 0xFF]).to_payload(), "0AFF");
+    }
+}
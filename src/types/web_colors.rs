@@ -0,0 +1,197 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Web UI color theme type.
+
+use crate::error::ValueError;
+use crate::types::RgbColor;
+
+/// Number of colors in a Tasmota web UI theme.
+pub const WEB_COLOR_COUNT: usize = 18;
+
+/// A Tasmota web UI color theme.
+///
+/// Tasmota themes its web interface with a fixed array of colors set by the
+/// `WebColor` command. The entries are, in order: text, background, form
+/// background, input text, input background, console text, console
+/// background, warning text, success text, button text, button, button
+/// hover, reset button, reset button hover, save button, save button hover,
+/// timer tab text and timer tab background.
+///
+/// # Examples
+///
+/// ```
+/// use tasmor_lib::types::{RgbColor, WebColors};
+///
+/// let dark = WebColors::dark();
+/// assert_eq!(dark.colors()[1], RgbColor::from_hex("#252525").unwrap());
+///
+/// // Entries must be "#"-prefixed hex colors
+/// assert!(WebColors::from_hex(&["eaeaea"; 18]).is_err());
+/// // And the array must be complete
+/// assert!(WebColors::from_hex(&["#eaeaea"; 3]).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WebColors([RgbColor; WEB_COLOR_COUNT]);
+
+/// Tasmota's default dark theme.
+const DARK: [RgbColor; WEB_COLOR_COUNT] = [
+    RgbColor::new(0xea, 0xea, 0xea),
+    RgbColor::new(0x25, 0x25, 0x25),
+    RgbColor::new(0x4f, 0x4f, 0x4f),
+    RgbColor::new(0x00, 0x00, 0x00),
+    RgbColor::new(0xdd, 0xdd, 0xdd),
+    RgbColor::new(0x65, 0xc1, 0x15),
+    RgbColor::new(0x1f, 0x1f, 0x1f),
+    RgbColor::new(0xff, 0x56, 0x61),
+    RgbColor::new(0x00, 0x80, 0x00),
+    RgbColor::new(0xfa, 0xff, 0xff),
+    RgbColor::new(0x1f, 0xa3, 0xec),
+    RgbColor::new(0x0e, 0x70, 0xa4),
+    RgbColor::new(0xd4, 0x35, 0x35),
+    RgbColor::new(0x93, 0x1f, 0x1f),
+    RgbColor::new(0x47, 0xc2, 0x66),
+    RgbColor::new(0x5a, 0xaf, 0x6f),
+    RgbColor::new(0xfa, 0xff, 0xff),
+    RgbColor::new(0x99, 0x99, 0x99),
+];
+
+/// Tasmota's light theme, as published in the Tasmota documentation.
+const LIGHT: [RgbColor; WEB_COLOR_COUNT] = [
+    RgbColor::new(0x00, 0x00, 0x00),
+    RgbColor::new(0xff, 0xff, 0xff),
+    RgbColor::new(0xf2, 0xf2, 0xf2),
+    RgbColor::new(0x00, 0x00, 0x00),
+    RgbColor::new(0xff, 0xff, 0xff),
+    RgbColor::new(0x00, 0x00, 0x00),
+    RgbColor::new(0xff, 0xff, 0xff),
+    RgbColor::new(0xff, 0x00, 0x00),
+    RgbColor::new(0x00, 0x80, 0x00),
+    RgbColor::new(0xff, 0xff, 0xff),
+    RgbColor::new(0x1f, 0xa3, 0xec),
+    RgbColor::new(0x0e, 0x70, 0xa4),
+    RgbColor::new(0xd4, 0x35, 0x35),
+    RgbColor::new(0x93, 0x1f, 0x1f),
+    RgbColor::new(0x47, 0xc2, 0x66),
+    RgbColor::new(0x5a, 0xaf, 0x6f),
+    RgbColor::new(0xff, 0xff, 0xff),
+    RgbColor::new(0x99, 0x99, 0x99),
+];
+
+impl WebColors {
+    /// Creates a theme from an array of colors.
+    #[must_use]
+    pub const fn new(colors: [RgbColor; WEB_COLOR_COUNT]) -> Self {
+        Self(colors)
+    }
+
+    /// Creates a theme from `#RRGGBB` or `#RGB` hex strings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::InvalidColorCount`] if `colors` does not contain
+    /// exactly [`WEB_COLOR_COUNT`] entries, or [`ValueError::InvalidHexColor`]
+    /// if an entry is not a `#`-prefixed hex color.
+    pub fn from_hex<S: AsRef<str>>(colors: &[S]) -> Result<Self, ValueError> {
+        if colors.len() != WEB_COLOR_COUNT {
+            return Err(ValueError::InvalidColorCount {
+                expected: WEB_COLOR_COUNT,
+                actual: colors.len(),
+            });
+        }
+
+        let mut parsed = [RgbColor::default(); WEB_COLOR_COUNT];
+        for (slot, hex) in parsed.iter_mut().zip(colors) {
+            let hex = hex.as_ref();
+            if !hex.starts_with('#') {
+                return Err(ValueError::InvalidHexColor(hex.to_string()));
+            }
+            *slot = RgbColor::from_hex(hex)?;
+        }
+        Ok(Self(parsed))
+    }
+
+    /// Returns Tasmota's default dark theme.
+    #[must_use]
+    pub const fn dark() -> Self {
+        Self(DARK)
+    }
+
+    /// Returns Tasmota's light theme.
+    #[must_use]
+    pub const fn light() -> Self {
+        Self(LIGHT)
+    }
+
+    /// Returns the theme colors in `WebColor` order.
+    #[must_use]
+    pub const fn colors(&self) -> &[RgbColor; WEB_COLOR_COUNT] {
+        &self.0
+    }
+
+    /// Returns the JSON payload for the `WebColor` command.
+    ///
+    /// Format: `{"WebColor":["#RRGGBB",...]}`
+    #[must_use]
+    pub fn to_command_string(&self) -> String {
+        let colors: Vec<String> = self.0.iter().map(RgbColor::to_hex_with_hash).collect();
+        serde_json::json!({ "WebColor": colors }).to_string()
+    }
+}
+
+impl Default for WebColors {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_are_valid() {
+        assert_eq!(
+            WebColors::dark().colors()[0],
+            RgbColor::new(0xea, 0xea, 0xea)
+        );
+        assert_eq!(WebColors::light().colors()[1], RgbColor::white());
+        assert_eq!(WebColors::default(), WebColors::dark());
+    }
+
+    #[test]
+    fn from_hex_rejects_wrong_length() {
+        let err = WebColors::from_hex(&["#000000"; 19]).unwrap_err();
+        assert_eq!(
+            err,
+            ValueError::InvalidColorCount {
+                expected: 18,
+                actual: 19
+            }
+        );
+    }
+
+    #[test]
+    fn from_hex_rejects_invalid_entries() {
+        let mut colors = ["#000000"; WEB_COLOR_COUNT];
+        colors[5] = "#12345";
+        assert!(WebColors::from_hex(&colors).is_err());
+
+        colors[5] = "123456";
+        assert!(WebColors::from_hex(&colors).is_err());
+
+        colors[5] = "#abc";
+        assert!(WebColors::from_hex(&colors).is_ok());
+    }
+
+    #[test]
+    fn command_string_format() {
+        let payload = WebColors::light().to_command_string();
+        assert!(payload.starts_with(r##"{"WebColor":["#000000","#FFFFFF","#F2F2F2""##));
+
+        let value: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(value["WebColor"].as_array().unwrap().len(), WEB_COLOR_COUNT);
+    }
+}
@@ -12,8 +12,10 @@ use tasmor_lib::command::{
     ColorTemperatureCommand, DimmerCommand, EnergyCommand, FadeCommand, FadeDurationCommand,
     HsbColorCommand, PowerCommand, StartupFadeCommand, StatusCommand,
 };
-use tasmor_lib::protocol::{HttpClient, HttpClientBuilder, Protocol};
-use tasmor_lib::types::{ColorTemperature, Dimmer, FadeDuration, HsbColor, PowerIndex, PowerState};
+use tasmor_lib::protocol::{HttpClient, HttpClientBuilder, HttpConfig, Protocol};
+use tasmor_lib::types::{
+    ColorTemperature, Dimmer, FadeDuration, HsbColor, PowerIndex, PowerQuery, PowerState,
+};
 use tasmor_lib::{Capabilities, Device};
 use wiremock::matchers::{method, query_param, query_param_contains};
 use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -432,6 +434,61 @@ mod device_auto_detection {
         assert!(device.capabilities().supports_energy_monitoring());
     }
 
+    #[tokio::test]
+    async fn build_device_with_custom_detector() {
+        use std::sync::Arc;
+        use tasmor_lib::response::{DeviceTemplate, StatusResponse};
+        use tasmor_lib::{CapabilitiesBuilder, CapabilityDetector, DefaultDetector};
+
+        struct DualRelayDetector;
+
+        impl CapabilityDetector for DualRelayDetector {
+            fn detect(&self, status: &StatusResponse, template: &DeviceTemplate) -> Capabilities {
+                if template.name() == "Dual Relay" {
+                    return CapabilitiesBuilder::new().power_channels(2).build();
+                }
+                DefaultDetector.detect(status, template)
+            }
+        }
+
+        let mock_server = MockServer::start().await;
+
+        // Once for the detector probe, once for the initial state query
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(create_full_status_response()))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Template"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "NAME": "Dual Relay",
+                "GPIO": [224, 225, 0, 0],
+                "FLAG": 0,
+                "BASE": 18
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(query_param_contains("cmnd", "Power"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(mock_power_response()))
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _state) = Device::http(&host)
+            .with_detector(Arc::new(DualRelayDetector))
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(device.capabilities().power_channels(), 2);
+    }
+
     #[tokio::test]
     async fn build_device_without_probe() {
         let mock_server = MockServer::start().await;
@@ -619,6 +676,114 @@ mod device_power_commands {
 
         assert_eq!(response.power_state(2).unwrap().unwrap(), PowerState::On);
     }
+
+    async fn mount_two_relays(mock_server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 11"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "StatusSTS": {"Uptime": "0T01:00:00", "POWER1": "ON", "POWER2": "OFF"}
+            })))
+            .expect(1)
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn relay_count_is_queried_with_manual_capabilities() {
+        let mock_server = MockServer::start().await;
+        mount_two_relays(&mock_server).await;
+
+        let device = create_device_with_mock(&mock_server).await;
+
+        assert_eq!(device.capabilities().relay_count(), None);
+        assert_eq!(device.relay_count().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn validated_index_beyond_relays_is_rejected() {
+        let mock_server = MockServer::start().await;
+        mount_two_relays(&mock_server).await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Power5 ON"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device_with_mock(&mock_server)
+            .await
+            .validate_relay_index(true);
+        let result = device.power_on_index(PowerIndex::new(5).unwrap()).await;
+
+        assert!(matches!(
+            result,
+            Err(tasmor_lib::Error::Device(
+                tasmor_lib::error::DeviceError::NoSuchRelay {
+                    index: 5,
+                    available: 2
+                }
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn validated_index_within_relays_is_sent() {
+        let mock_server = MockServer::start().await;
+        mount_two_relays(&mock_server).await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Power2 TOGGLE"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"POWER2": "ON"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device_with_mock(&mock_server)
+            .await
+            .validate_relay_index(true);
+        device
+            .power_toggle_index(PowerIndex::new(2).unwrap())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn query_power_reports_state() {
+        let mock_server = MockServer::start().await;
+
+        let device = create_device_with_mock(&mock_server).await;
+
+        assert_eq!(device.query_power().await.unwrap(), PowerQuery::Off);
+    }
+
+    #[tokio::test]
+    async fn query_power_without_response_is_unknown() {
+        let mock_server = MockServer::start().await;
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) =
+            Device::http_config(HttpConfig::new(host).with_timeout(Duration::from_millis(100)))
+                .with_capabilities(Capabilities::basic())
+                .build_without_probe()
+                .await
+                .unwrap();
+
+        // The device stops answering after it was built
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Power1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"POWER1": "OFF"}))
+                    .set_delay(Duration::from_secs(1)),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let query = device.query_power().await.unwrap();
+
+        assert_eq!(query, PowerQuery::Unknown);
+        assert_eq!(query.state(), None);
+    }
 }
 
 // ============================================================================
@@ -683,6 +848,108 @@ mod device_light_commands {
         device.set_dimmer(Dimmer::new(75).unwrap()).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn set_dimmer_mixed_case_keys() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Dimmer 75"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "DIMMER": 75,
+                "Power": "ON"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let device = create_light_device(&mock_server).await;
+        let response = device.set_dimmer(Dimmer::new(75).unwrap()).await.unwrap();
+
+        assert_eq!(response.dimmer(), 75);
+        assert_eq!(response.is_on(), Some(true));
+    }
+
+    #[tokio::test]
+    async fn adjust_dimmer_clamps() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Dimmer 100"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "POWER": "ON",
+                "Dimmer": 100
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_light_device(&mock_server).await;
+        let dimmer = device.adjust_dimmer(60).await.unwrap();
+
+        assert_eq!(dimmer, Dimmer::MAX);
+    }
+
+    #[tokio::test]
+    async fn dimmer_up_sends_relative_step() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Dimmer +10"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "POWER": "ON",
+                "Dimmer": 60
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_light_device(&mock_server).await;
+        let response = device.dimmer_up(10).await.unwrap();
+
+        assert_eq!(response.dimmer(), 60);
+    }
+
+    #[tokio::test]
+    async fn adjust_hue_wraps() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "HSBColor1 340"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "POWER": "ON",
+                "Dimmer": 100,
+                "HSBColor": "340,100,100"
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_light_device(&mock_server).await;
+        let hue = device.adjust_hue(-20).await.unwrap();
+
+        assert_eq!(hue, 340);
+    }
+
+    #[tokio::test]
+    async fn adjust_saturation_keeps_hue() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "HSBColor2 70"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "POWER": "ON",
+                "Dimmer": 100,
+                "HSBColor": "0,70,100"
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_light_device(&mock_server).await;
+        let saturation = device.adjust_saturation(-30).await.unwrap();
+
+        assert_eq!(saturation, 70);
+    }
+
     #[tokio::test]
     async fn set_color_temperature() {
         let mock_server = MockServer::start().await;
@@ -702,6 +969,52 @@ mod device_light_commands {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn set_dimmer_verified() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Dimmer 75"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "Dimmer": 75
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let device = create_light_device(&mock_server).await;
+        let response = device
+            .set_dimmer_verified(Dimmer::new(75).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.dimmer(), 75);
+    }
+
+    #[tokio::test]
+    async fn clamped_color_temperature_is_not_verified() {
+        let mock_server = MockServer::start().await;
+
+        // The light only supports CT down to 200
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "CT 153"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "CT": 200
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let device = create_light_device(&mock_server).await;
+        let result = device
+            .set_color_temperature_verified(ColorTemperature::COOL)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(tasmor_lib::Error::Device(
+                tasmor_lib::error::DeviceError::Unverified { .. }
+            ))
+        ));
+    }
+
     #[tokio::test]
     async fn set_hsb_color() {
         let mock_server = MockServer::start().await;
@@ -872,6 +1185,58 @@ mod device_light_commands {
         let result = device.set_hsb_color(HsbColor::red()).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn try_get_returns_none_without_capability() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Dimmer"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"Dimmer": 50})),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "HSBColor"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::cct_light()) // No RGB
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        let dimmer = device.try_get_dimmer().await.unwrap();
+        assert_eq!(dimmer.map(|d| d.dimmer()), Some(50));
+
+        assert!(device.try_get_hsb_color().await.unwrap().is_none());
+        assert!(device.try_get_energy().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn try_get_propagates_errors_when_supported() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Dimmer"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::cct_light())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        assert!(device.try_get_dimmer().await.is_err());
+    }
 }
 
 // ============================================================================
@@ -946,8 +1311,8 @@ mod device_energy_commands {
         let response = device.energy().await.unwrap();
 
         let energy = response.energy().unwrap();
-        assert_eq!(energy.power, 45.0);
-        assert_eq!(energy.voltage, 230.0);
+        assert_abs_diff_eq!(energy.power, 45.0, epsilon = 0.001);
+        assert_abs_diff_eq!(energy.voltage, 230.0, epsilon = 0.001);
     }
 
     #[tokio::test]
@@ -1004,20 +1369,127 @@ mod device_energy_commands {
         let result = device.energy().await;
         assert!(result.is_err());
     }
-}
-
-// ============================================================================
-// Device Status Commands Tests
-// ============================================================================
 
-mod device_status_commands {
-    use super::*;
+    fn sensor_reply(time: &str, power: u32) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "StatusSNS": {"Time": time, "ENERGY": {"Power": power, "Voltage": 230}}
+        }))
+    }
 
     #[tokio::test]
-    async fn get_status() {
+    async fn sample_energy_polls_over_window() {
         let mock_server = MockServer::start().await;
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::neo_coolcam())
+            .build_without_probe()
+            .await
+            .unwrap();
 
-        // Mock Power1 for initial state query
+        // Mounted after the build, which also queries Status 10
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 10"))
+            .respond_with(sensor_reply("2024-01-01T12:00:00", 45))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        // The interval is raised to the one second minimum
+        let samples = device
+            .sample_energy(Duration::from_millis(1500), Duration::from_millis(10))
+            .await
+            .unwrap();
+
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].0.to_string(), "2024-01-01 12:00:00");
+        assert_eq!(samples[0].1.power, Some(45.0));
+    }
+
+    #[tokio::test]
+    async fn sample_energy_returns_partial_data_when_device_drops() {
+        let mock_server = MockServer::start().await;
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::neo_coolcam())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        // Mounted after the build, which also queries Status 10
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 10"))
+            .respond_with(sensor_reply("2024-01-01T12:00:00", 120))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 10"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&mock_server)
+            .await;
+
+        let samples = device
+            .sample_energy(Duration::from_secs(10), Duration::from_secs(1))
+            .await
+            .unwrap();
+
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].1.power, Some(120.0));
+    }
+
+    #[tokio::test]
+    async fn set_energy_total_sends_watt_hours() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "EnergyTotal 1234568"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "EnergyTotal": {"Total": 1234.568, "Yesterday": 2.0, "Today": 1.0}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_energy_device(&mock_server).await;
+        let response = device.set_energy_total(1234.5678).await.unwrap();
+
+        assert!(response.energy().is_some());
+    }
+
+    #[tokio::test]
+    async fn set_energy_total_rejects_negative_value() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param_contains("cmnd", "EnergyTotal"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_energy_device(&mock_server).await;
+        let result = device.set_energy_total(-1.0).await;
+
+        assert!(matches!(
+            result,
+            Err(tasmor_lib::Error::Value(
+                tasmor_lib::ValueError::InvalidEnergyTotal(_)
+            ))
+        ));
+    }
+}
+
+// ============================================================================
+// Device Status Commands Tests
+// ============================================================================
+
+mod device_status_commands {
+    use super::*;
+
+    #[tokio::test]
+    async fn get_status() {
+        let mock_server = MockServer::start().await;
+
+        // Mock Power1 for initial state query
         Mock::given(method("GET"))
             .and(query_param("cmnd", "Power1"))
             .respond_with(
@@ -1060,6 +1532,167 @@ mod device_status_commands {
         assert_eq!(status.firmware_version(), Some("13.1.0"));
         assert_eq!(status.ip_address(), Some("192.168.1.100"));
     }
+
+    #[tokio::test]
+    async fn status_parameters() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "StatusPRM": {
+                    "Baudrate": 115_200,
+                    "RestartReason": "Power On",
+                    "Uptime": "0T00:10:00",
+                    "StartupUTC": "2024-01-15T08:30:00",
+                    "Sleep": 50,
+                    "BootCount": 7,
+                    "SaveCount": 120,
+                    "SaveAddress": "F9000"
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        let params = device.status_parameters().await.unwrap();
+
+        assert_eq!(params.restart_reason, "Power On");
+        assert_eq!(params.boot_count, 7);
+        assert_eq!(params.save_count, Some(120));
+        assert_eq!(params.uptime(), Some(Duration::from_secs(600)));
+    }
+
+    #[tokio::test]
+    async fn status_memory() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 4"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "StatusMEM": {
+                    "ProgramSize": 616,
+                    "Free": 384,
+                    "Heap": 25,
+                    "ProgramFlashSize": 1024,
+                    "FlashSize": 4096,
+                    "FlashChipId": "1640EF",
+                    "FlashFrequency": 40,
+                    "FlashMode": 3
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        let memory = device.status_memory().await.unwrap();
+
+        assert_eq!(memory.program_size, 616);
+        assert_eq!(memory.flash_size, 4096);
+        assert_eq!(memory.flash_chip_id, "1640EF");
+        assert!(memory.fits_firmware(384));
+        assert!(!memory.fits_firmware(640));
+    }
+
+    #[tokio::test]
+    async fn wifi_info() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 11"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "StatusSTS": {
+                    "POWER": "ON",
+                    "Wifi": {
+                        "AP": 1,
+                        "SSId": "office",
+                        "BSSId": "30:B5:C2:5D:70:72",
+                        "Channel": 11,
+                        "Mode": "11n",
+                        "RSSI": 62,
+                        "Signal": -69,
+                        "LinkCount": 1,
+                        "Downtime": "0T00:00:03"
+                    }
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        let wifi = device.wifi_info().await.unwrap();
+
+        assert_eq!(wifi.ssid.as_deref(), Some("office"));
+        assert_eq!(wifi.bssid.as_deref(), Some("30:B5:C2:5D:70:72"));
+        assert_eq!(wifi.channel, Some(11));
+        assert_eq!(wifi.signal_dbm(), Some(-69));
+        assert_eq!(wifi.quality(), Some(62));
+    }
+
+    #[tokio::test]
+    async fn full_report_fills_missing_sections() {
+        let mock_server = MockServer::start().await;
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        // Mounted after the build so that only the report's queries are counted
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "Status": {"Module": 18, "DeviceName": "Kitchen", "Topic": "kitchen"},
+                "StatusPRM": {"BootCount": 12, "RestartReason": "Software/System restart"},
+                "StatusFWR": {"Version": "14.2.0(tasmota)"},
+                "StatusNET": {"Hostname": "kitchen", "IPAddress": "192.168.1.100"},
+                "StatusSNS": {"Time": "2024-01-01T12:00:00", "Temperature": 21.5},
+                "StatusSTS": {"POWER": "ON", "Dimmer": 40}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 6"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "StatusMQT": {"MqttHost": "broker.lan", "MqttPort": 1883}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let report = device.full_report().await.unwrap();
+
+        assert_eq!(report.device().unwrap().device_name, "Kitchen");
+        assert_eq!(report.parameters().unwrap().boot_count, 12);
+        assert_eq!(report.firmware().unwrap().version, "14.2.0(tasmota)");
+        assert_eq!(report.network().unwrap().ip_address, "192.168.1.100");
+        assert_eq!(report.mqtt().unwrap().host, "broker.lan");
+        assert_eq!(report.state().unwrap().dimmer(), Some(40));
+        assert_abs_diff_eq!(report.sensors().unwrap().temperature().unwrap(), 21.5);
+    }
 }
 
 // ============================================================================
@@ -1204,3 +1837,2674 @@ mod error_handling {
         assert!(cmd_result.is_err());
     }
 }
+
+// ============================================================================
+// Device Light Update Tests
+// ============================================================================
+
+mod device_light_update {
+    use super::*;
+    use tasmor_lib::state::LightState;
+    use tasmor_lib::{CapabilitiesBuilder, LightChoice};
+
+    async fn create_dimmable_device(mock_server: &MockServer) -> Device<HttpClient> {
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Power1"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"POWER1": "ON"})),
+            )
+            .mount(mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Dimmer"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"POWER": "ON", "Dimmer": 80})),
+            )
+            .mount(mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(CapabilitiesBuilder::new().with_dimmer_control().build())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn writes_only_changed_fields_as_backlog() {
+        let mock_server = MockServer::start().await;
+        let device = create_dimmable_device(&mock_server).await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Backlog0 Dimmer 60"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"POWER": "ON", "Dimmer": 60})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let state = device
+            .update_light(|light| {
+                let dimmer = light.dimmer().unwrap().value();
+                light.with_dimmer(Dimmer::clamped(dimmer - 20))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(state.dimmer(), Some(Dimmer::new(60).unwrap()));
+        assert_eq!(state.power(1), Some(PowerState::On));
+    }
+
+    #[tokio::test]
+    async fn unchanged_state_sends_nothing() {
+        let mock_server = MockServer::start().await;
+        let device = create_dimmable_device(&mock_server).await;
+
+        Mock::given(method("GET"))
+            .and(query_param_contains("cmnd", "Backlog0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let state = device.update_light(|light| light).await.unwrap();
+
+        assert_eq!(state.dimmer(), Some(Dimmer::new(80).unwrap()));
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_field() {
+        let mock_server = MockServer::start().await;
+        let device = create_dimmable_device(&mock_server).await;
+
+        let result = device
+            .update_light(|light| light.with_hsb_color(HsbColor::red()))
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_non_light_device() {
+        let mock_server = MockServer::start().await;
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        let result = device.update_light(|light| light).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn toggle_between_applies_the_other_state() {
+        let mock_server = MockServer::start().await;
+        let device = create_dimmable_device(&mock_server).await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Backlog0 Dimmer 20"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"POWER": "ON", "Dimmer": 20})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        // The light is at 78-80%: it matches `normal`, so `movie` is applied
+        let normal = LightState::new().with_dimmer(Dimmer::new(78).unwrap());
+        let movie = LightState::new().with_dimmer(Dimmer::new(20).unwrap());
+
+        let choice = device.toggle_between(&normal, &movie).await.unwrap();
+
+        assert_eq!(choice, LightChoice::B);
+    }
+
+    #[tokio::test]
+    async fn toggle_between_matching_neither_applies_first() {
+        let mock_server = MockServer::start().await;
+        let device = create_dimmable_device(&mock_server).await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Backlog0 Dimmer 30"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"POWER": "ON", "Dimmer": 30})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let a = LightState::new().with_dimmer(Dimmer::new(30).unwrap());
+        let b = LightState::new().with_dimmer(Dimmer::new(50).unwrap());
+
+        let choice = device.toggle_between(&a, &b).await.unwrap();
+
+        assert_eq!(choice, LightChoice::A);
+    }
+}
+
+// ============================================================================
+// Smart Color Tests
+// ============================================================================
+
+mod device_color_smart {
+    use super::*;
+
+    async fn create_device(
+        mock_server: &MockServer,
+        capabilities: Capabilities,
+    ) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(capabilities)
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn white_is_sent_as_color_temperature() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Backlog0 CT 250; Dimmer 80"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"POWER": "ON", "CT": 250, "Dimmer": 80})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server, Capabilities::rgbcct_light()).await;
+        device
+            .set_color_smart(HsbColor::new(30, 36, 80).unwrap())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn saturated_color_is_sent_as_hsb() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Backlog0 HSBColor 240,100,80"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"POWER": "ON", "HSBColor": "240,100,80", "Dimmer": 80}),
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server, Capabilities::rgbcct_light()).await;
+        device
+            .set_color_smart(HsbColor::new(240, 100, 80).unwrap())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn white_on_rgb_only_fixture_is_sent_as_hsb() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Backlog0 HSBColor 0,0,100"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"POWER": "ON", "HSBColor": "0,0,100", "Dimmer": 100}),
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server, Capabilities::rgb_light()).await;
+        device.set_color_smart(HsbColor::white()).await.unwrap();
+    }
+
+    async fn mount_firmware(mock_server: &MockServer, version: &str) {
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"StatusFWR": {"Version": version, "Hardware": "ESP8266EX"}}),
+            ))
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn transition_uses_one_shot_commands_on_new_firmware() {
+        let mock_server = MockServer::start().await;
+        mount_firmware(&mock_server, "13.1.0(tasmota)").await;
+        Mock::given(method("GET"))
+            .and(query_param(
+                "cmnd",
+                "Backlog0 Fade2 1; Speed2 6; HSBColor 240,100,80",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"POWER": "ON", "HSBColor": "240,100,80", "Dimmer": 80}),
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server, Capabilities::rgb_light()).await;
+        device
+            .set_color_with_transition(HsbColor::new(240, 100, 80).unwrap(), Duration::from_secs(3))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn transition_falls_back_to_speed_on_old_firmware() {
+        let mock_server = MockServer::start().await;
+        mount_firmware(&mock_server, "8.5.1(tasmota)").await;
+        Mock::given(method("GET"))
+            .and(query_param(
+                "cmnd",
+                "Backlog0 Fade 1; Speed 6; HSBColor 240,100,80",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"POWER": "ON", "HSBColor": "240,100,80", "Dimmer": 80}),
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server, Capabilities::rgb_light()).await;
+        device
+            .set_color_with_transition(HsbColor::new(240, 100, 80).unwrap(), Duration::from_secs(3))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn transition_rejects_out_of_range_duration() {
+        let mock_server = MockServer::start().await;
+
+        let device = create_device(&mock_server, Capabilities::rgb_light()).await;
+        let result = device
+            .set_color_with_transition(HsbColor::red(), Duration::from_secs(60))
+            .await;
+
+        assert!(result.is_err());
+    }
+}
+
+// ============================================================================
+// Sunrise Tests
+// ============================================================================
+
+mod device_sunrise {
+    use super::*;
+    use std::time::Duration;
+    use tasmor_lib::CapabilitiesBuilder;
+    use tasmor_lib::state::LightState;
+
+    async fn create_device(
+        mock_server: &MockServer,
+        capabilities: Capabilities,
+    ) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(capabilities)
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn cct_fixture_ramps_color_temperature() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param(
+                "cmnd",
+                "Backlog0 Fade 1; Speed 8; CT 500; Dimmer 1; \
+                 Delay 10; CT 438; Dimmer 25; Delay 10; CT 375; Dimmer 50; \
+                 Delay 10; CT 313; Dimmer 75; Delay 10; CT 250; Dimmer 100",
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"POWER": "ON", "CT": 250, "Dimmer": 100})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server, Capabilities::cct_light()).await;
+        device
+            .sunrise(Duration::from_secs(4), LightState::new())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn dimmer_only_fixture_uses_native_wakeup() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param(
+                "cmnd",
+                "Backlog0 WakeupDuration 600; Dimmer 70; Scheme 1",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"WakeupDuration": 600, "Dimmer": 70, "Scheme": 1}),
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let capabilities = CapabilitiesBuilder::new().with_dimmer_control().build();
+        let device = create_device(&mock_server, capabilities).await;
+        device
+            .sunrise(
+                Duration::from_secs(600),
+                LightState::new().with_dimmer(Dimmer::new(70).unwrap()),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn color_target_needs_rgb() {
+        let mock_server = MockServer::start().await;
+        let device = create_device(&mock_server, Capabilities::cct_light()).await;
+
+        let result = device
+            .sunrise(
+                Duration::from_secs(60),
+                LightState::new().with_hsb_color(HsbColor::blue()),
+            )
+            .await;
+        assert!(result.is_err());
+    }
+}
+
+// ============================================================================
+// Light Snapshot Tests
+// ============================================================================
+
+mod device_light_snapshot {
+    use super::*;
+    use tasmor_lib::state::LightSnapshot;
+
+    const RGBCCT_STATE: &str = r#"{"POWER":"ON","Dimmer":60,"Color":"994D00000F",
+        "HSBColor":"30,100,60","Channel":[60,30,0,0,6],"CT":153,"Scheme":0}"#;
+
+    async fn create_device(
+        mock_server: &MockServer,
+        capabilities: Capabilities,
+    ) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(capabilities)
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    async fn mount_state(mock_server: &MockServer, body: &str) {
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "State"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(body))
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn snapshot_reads_state_once() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "State"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(RGBCCT_STATE))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server, Capabilities::rgbcct_light()).await;
+        let snapshot = device.get_light_snapshot().await.unwrap();
+
+        assert_eq!(snapshot.power(), Some(PowerState::On));
+        assert_eq!(snapshot.dimmer(), Some(Dimmer::new(60).unwrap()));
+        assert_eq!(snapshot.channels(), [60, 30, 0, 0, 6]);
+        assert_eq!(
+            snapshot.hsb_color(),
+            Some(HsbColor::new(30, 100, 60).unwrap())
+        );
+        assert!(!snapshot.is_white());
+    }
+
+    #[tokio::test]
+    async fn same_channel_count_replays_channels() {
+        let mock_server = MockServer::start().await;
+        mount_state(&mock_server, RGBCCT_STATE).await;
+        Mock::given(method("GET"))
+            .and(query_param(
+                "cmnd",
+                "Backlog0 Scheme 0; Channel1 10; Channel2 0; Channel3 0; \
+                 Channel4 20; Channel5 80; Power1 OFF",
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(
+                    serde_json::json!({"Scheme": 0, "Channel5": 80, "POWER": "OFF"}),
+                ),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let snapshot = LightSnapshot::new()
+            .with_scheme(tasmor_lib::types::Scheme::SINGLE)
+            .with_channels(vec![10, 0, 0, 20, 80])
+            .with_power(PowerState::Off);
+
+        let device = create_device(&mock_server, Capabilities::rgbcct_light()).await;
+        device.apply_light_snapshot(&snapshot).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn different_channel_count_falls_back_to_hsb() {
+        let mock_server = MockServer::start().await;
+        mount_state(
+            &mock_server,
+            r#"{"POWER":"OFF","Dimmer":0,"HSBColor":"0,0,0","Channel":[0,0,0]}"#,
+        )
+        .await;
+        Mock::given(method("GET"))
+            .and(query_param(
+                "cmnd",
+                "Backlog0 Scheme 0; HSBColor 30,100,60; Dimmer 60; Power1 ON",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"HSBColor": "30,100,60", "Dimmer": 60, "POWER": "ON"}),
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let state = serde_json::from_str(RGBCCT_STATE).unwrap();
+        let snapshot = LightSnapshot::from_telemetry(&state);
+
+        let device = create_device(&mock_server, Capabilities::rgb_light()).await;
+        device.apply_light_snapshot(&snapshot).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn white_snapshot_falls_back_to_color_temperature() {
+        let mock_server = MockServer::start().await;
+        mount_state(
+            &mock_server,
+            r#"{"POWER":"ON","Dimmer":100,"CT":153,"Channel":[100,0]}"#,
+        )
+        .await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Backlog0 CT 400; Dimmer 35"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"CT": 400, "Dimmer": 35})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let snapshot = LightSnapshot::new()
+            .with_channels(vec![0, 0, 0, 9, 26])
+            .with_color_temperature(ColorTemperature::new(400).unwrap())
+            .with_dimmer(Dimmer::new(35).unwrap());
+
+        let device = create_device(&mock_server, Capabilities::cct_light()).await;
+        device.apply_light_snapshot(&snapshot).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn non_light_is_rejected() {
+        let mock_server = MockServer::start().await;
+        let device = create_device(&mock_server, Capabilities::basic()).await;
+
+        let result = device.get_light_snapshot().await;
+        assert!(matches!(
+            result,
+            Err(tasmor_lib::Error::Device(
+                tasmor_lib::error::DeviceError::UnsupportedCapability { .. }
+            ))
+        ));
+    }
+}
+
+// ============================================================================
+// Dry-Run Mode Tests
+// ============================================================================
+
+mod device_dry_run {
+    use super::*;
+    use tasmor_lib::CapabilitiesBuilder;
+    use tasmor_lib::command::Routine;
+
+    async fn create_dry_run_device(mock_server: &MockServer) -> Device<HttpClient> {
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Dimmer"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"POWER": "ON", "Dimmer": 80})),
+            )
+            .mount(mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(CapabilitiesBuilder::new().with_dimmer_control().build())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device.dry_run(true)
+    }
+
+    #[tokio::test]
+    async fn set_dimmer_echoes_value_without_sending() {
+        let mock_server = MockServer::start().await;
+        let device = create_dry_run_device(&mock_server).await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Dimmer 50"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let response = device.set_dimmer(Dimmer::new(50).unwrap()).await.unwrap();
+
+        assert!(device.is_dry_run());
+        assert_eq!(response.dimmer(), 50);
+    }
+
+    #[tokio::test]
+    async fn send_command_marks_response_as_dry_run() {
+        let mock_server = MockServer::start().await;
+        let device = create_dry_run_device(&mock_server).await;
+
+        let response = device
+            .send_command(&PowerCommand::Set {
+                index: PowerIndex::one(),
+                state: PowerState::Off,
+            })
+            .await
+            .unwrap();
+
+        assert!(response.is_dry_run());
+        assert_eq!(response.body(), r#"{"POWER1":"OFF"}"#);
+    }
+
+    #[tokio::test]
+    async fn queries_are_still_sent() {
+        let mock_server = MockServer::start().await;
+        let device = create_dry_run_device(&mock_server).await;
+
+        let response = device.get_dimmer().await.unwrap();
+
+        assert_eq!(response.dimmer(), 80);
+    }
+
+    #[tokio::test]
+    async fn routine_is_synthesized() {
+        let mock_server = MockServer::start().await;
+        let device = create_dry_run_device(&mock_server).await;
+
+        Mock::given(method("GET"))
+            .and(query_param_contains("cmnd", "Backlog0"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let routine = Routine::builder()
+            .power_on(PowerIndex::one())
+            .set_dimmer(Dimmer::new(30).unwrap())
+            .build()
+            .unwrap();
+        let response = device.run(&routine).await.unwrap();
+
+        assert!(response.is_dry_run());
+        assert_eq!(response.get_as::<u8>("Dimmer").unwrap(), 30);
+        assert_eq!(response.get_as::<String>("POWER1").unwrap(), "ON");
+    }
+}
+
+// ============================================================================
+// Web UI Tests
+// ============================================================================
+
+mod device_web_ui {
+    use super::*;
+    use tasmor_lib::types::WebColors;
+
+    #[tokio::test]
+    async fn set_dark_theme_sends_color_array() {
+        let mock_server = MockServer::start().await;
+
+        let dark = WebColors::dark();
+        let mut reported: Vec<String> = dark
+            .colors()
+            .iter()
+            .map(|c| c.to_hex_with_hash().to_lowercase())
+            .collect();
+        reported.push("#eaeaea".to_string());
+
+        Mock::given(method("GET"))
+            .and(query_param(
+                "cmnd",
+                format!("WebColor {}", dark.to_command_string()),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "WebColor": reported })),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        let response = device.set_dark_theme().await.unwrap();
+
+        assert_eq!(response.colors().unwrap(), dark);
+    }
+}
+
+// ============================================================================
+// Device Name Tests
+// ============================================================================
+
+mod device_name {
+    use super::*;
+
+    async fn create_device(mock_server: &MockServer) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn set_device_name() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "DeviceName Kitchen Hub"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"DeviceName": "Kitchen Hub"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        let name = device.set_device_name("Kitchen Hub").await.unwrap();
+
+        assert_eq!(name, "Kitchen Hub");
+    }
+
+    #[tokio::test]
+    async fn get_device_name() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "DeviceName"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"DeviceName": "Tasmota"})),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+
+        assert_eq!(device.get_device_name().await.unwrap(), "Tasmota");
+    }
+
+    #[tokio::test]
+    async fn overlong_name_is_rejected_without_sending() {
+        let mock_server = MockServer::start().await;
+
+        let device = create_device(&mock_server).await;
+        let result = device.set_device_name(&"x".repeat(34)).await;
+
+        assert!(matches!(
+            result,
+            Err(tasmor_lib::Error::Value(
+                tasmor_lib::ValueError::InvalidDeviceName(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn initial_state_includes_device_name() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "Status": {"Module": 1, "DeviceName": "Kitchen Hub", "FriendlyName": ["Plug"]}
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (_, state) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        assert_eq!(state.device_name(), Some("Kitchen Hub"));
+    }
+}
+
+// ============================================================================
+// Time Synchronization Tests
+// ============================================================================
+
+mod device_ntp {
+    use super::*;
+
+    async fn create_device(mock_server: &MockServer) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    async fn mount_servers(mock_server: &MockServer, servers: [&str; 3]) {
+        for (index, server) in (1..).zip(servers) {
+            let name = format!("NtpServer{index}");
+            let mut body = serde_json::Map::new();
+            body.insert(name.clone(), server.into());
+
+            Mock::given(method("GET"))
+                .and(query_param("cmnd", name.as_str()))
+                .respond_with(ResponseTemplate::new(200).set_body_json(body))
+                .expect(1)
+                .mount(mock_server)
+                .await;
+        }
+    }
+
+    async fn mount_utc(mock_server: &MockServer, utc: &str) {
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 7"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "StatusTIM": {"UTC": utc, "Local": utc, "Timezone": 0}
+            })))
+            .expect(1)
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn get_ntp_config_reads_servers_and_sync_state() {
+        let mock_server = MockServer::start().await;
+        mount_servers(&mock_server, ["ntp.lan", "", "pool.ntp.org"]).await;
+        mount_utc(&mock_server, "2025-03-10T07:45:00").await;
+
+        let device = create_device(&mock_server).await;
+        let ntp = device.get_ntp_config().await.unwrap();
+
+        assert_eq!(ntp.server(1), Some("ntp.lan"));
+        assert_eq!(ntp.server(2), None);
+        assert!(ntp.time_synced());
+    }
+
+    #[tokio::test]
+    async fn unset_clock_is_not_synced() {
+        let mock_server = MockServer::start().await;
+        mount_servers(&mock_server, ["", "", ""]).await;
+        mount_utc(&mock_server, "1970-01-01T00:02:41").await;
+
+        let device = create_device(&mock_server).await;
+        let ntp = device.get_ntp_config().await.unwrap();
+
+        assert_eq!(ntp.servers().count(), 0);
+        assert!(!ntp.time_synced());
+    }
+
+    #[tokio::test]
+    async fn set_ntp_server() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "NtpServer1 ntp.lan"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"NtpServer1": "ntp.lan"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        let response = device.set_ntp_server(1, "ntp.lan").await.unwrap();
+
+        assert_eq!(response.server(1), Some("ntp.lan"));
+    }
+
+    #[tokio::test]
+    async fn set_ntp_server_rejects_invalid_host() {
+        let mock_server = MockServer::start().await;
+        let device = create_device(&mock_server).await;
+
+        let result = device.set_ntp_server(1, "ntp server").await;
+
+        assert!(matches!(result, Err(tasmor_lib::Error::Value(_))));
+    }
+}
+
+// ============================================================================
+// Hardware Module Tests
+// ============================================================================
+
+mod device_module {
+    use super::*;
+
+    async fn create_device(mock_server: &MockServer) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn set_module_parses_reply() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Module 1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"Module": {"1": "Sonoff Basic"}})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        let response = device.set_module(1).await.unwrap();
+
+        assert_eq!(response.id().unwrap(), 1);
+        assert_eq!(response.name(), Some("Sonoff Basic"));
+    }
+
+    async fn create_device_at(host: &str) -> Device<HttpClient> {
+        let (device, _) = Device::http(host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn set_module_assumes_success_when_device_drops_connection() {
+        // Reads each request, then closes without replying, like a device
+        // restarting right after the module change
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut socket in listener.incoming().flatten() {
+                let mut buf = [0_u8; 1024];
+                let _ = std::io::Read::read(&mut socket, &mut buf);
+            }
+        });
+
+        let device = create_device_at(&address.to_string()).await;
+        let response = device.set_module(18).await.unwrap();
+
+        assert_eq!(response.id().unwrap(), 18);
+        assert_eq!(response.name(), None);
+    }
+
+    #[tokio::test]
+    async fn set_module_fails_when_device_is_unreachable() {
+        // Nothing listens on the port once the listener is dropped
+        let address = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let device = create_device_at(&address.to_string()).await;
+        let result = device.set_module(18).await;
+
+        assert!(matches!(result, Err(tasmor_lib::Error::Protocol(_))));
+    }
+
+    #[tokio::test]
+    async fn set_module_rejects_out_of_range_id() {
+        let mock_server = MockServer::start().await;
+        let device = create_device(&mock_server).await;
+
+        let result = device.set_module(1000).await;
+
+        assert!(matches!(result, Err(tasmor_lib::Error::Value(_))));
+    }
+
+    #[tokio::test]
+    async fn list_modules() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Modules"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "Modules": {"0": "Generic Template", "1": "Sonoff Basic", "18": "Generic"}
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        let modules = device.list_modules().await.unwrap();
+
+        assert_eq!(modules.modules().len(), 3);
+        assert_eq!(modules.name(18), Some("Generic"));
+    }
+
+    #[tokio::test]
+    async fn read_gpio() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Gpio12"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"GPIO12": {"224": "Relay1"}})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        let pin = device.read_gpio(12).await.unwrap();
+
+        assert_eq!(pin.component(), 224);
+        assert_eq!(pin.name(), "Relay1");
+    }
+
+    #[tokio::test]
+    async fn read_gpio_rejects_unknown_pin() {
+        let mock_server = MockServer::start().await;
+        let device = create_device(&mock_server).await;
+
+        let result = device.read_gpio(60).await;
+
+        assert!(matches!(result, Err(tasmor_lib::Error::Value(_))));
+    }
+
+    #[tokio::test]
+    async fn read_gpio_not_reported() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Gpio40"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"Command": "Unknown"})),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        let result = device.read_gpio(40).await;
+
+        assert!(matches!(result, Err(tasmor_lib::Error::Parse(_))));
+    }
+
+    #[tokio::test]
+    async fn read_all_gpios() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Gpio 255"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "GPIO0": {"0": "None"},
+                "GPIO1": {"0": "None"},
+                "GPIO12": {"224": "Relay1"},
+                "GPIO13": {"320": "Led_i1"}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        let gpios = device.read_all_gpios().await.unwrap();
+
+        assert_eq!(gpios.pins().len(), 4);
+        assert_eq!(gpios.used_pins().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn i2c_scan_lists_addresses() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "I2CScan"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"I2CScan": "Device(s) found at 0x44 0x76"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        assert_eq!(device.i2c_scan().await.unwrap(), vec![0x44, 0x76]);
+    }
+
+    #[tokio::test]
+    async fn i2c_scan_without_devices_is_empty() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "I2CScan"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"I2CScan": "No devices found"})),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        assert!(device.i2c_scan().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn i2c_scan_bus_error_is_rejected() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "I2CScan"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"I2CScan": "Error 4 at 0x20"})),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        assert!(matches!(
+            device.i2c_scan().await,
+            Err(tasmor_lib::Error::Device(
+                tasmor_lib::error::DeviceError::CommandRejected(_)
+            ))
+        ));
+    }
+}
+
+// ============================================================================
+// Security Tests
+// ============================================================================
+
+mod device_security {
+    use super::*;
+
+    async fn create_device(mock_server: &MockServer) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn set_web_password_uses_new_credentials() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "WebPassword s3cret"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"WebPassword": "****"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Power1 ON"))
+            .and(query_param("user", "admin"))
+            .and(query_param("password", "s3cret"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"POWER": "ON"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+
+        let response = device.set_web_password("s3cret").await.unwrap();
+        assert_eq!(response.web_password(), Some("****"));
+
+        device.power_on().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn set_web_password_rejects_invalid_password() {
+        let mock_server = MockServer::start().await;
+        let device = create_device(&mock_server).await;
+
+        let result = device.set_web_password("1").await;
+
+        assert!(matches!(result, Err(tasmor_lib::Error::Value(_))));
+    }
+
+    #[tokio::test]
+    async fn set_cors_parses_confirmation() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "CORS *"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"CORS": "*"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+
+        let response = device.set_cors("*").await.unwrap();
+        assert_eq!(response.cors(), Some("*"));
+    }
+
+    #[tokio::test]
+    async fn set_mqtt_password_requires_confirmation() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "MqttPassword broker"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"Command": "Unknown"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+
+        let result = device.set_mqtt_password("broker").await;
+        assert!(matches!(result, Err(tasmor_lib::Error::Parse(_))));
+    }
+
+    #[tokio::test]
+    async fn configure_mqtt_sends_backlog() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param(
+                "cmnd",
+                "Backlog0 MqttHost broker.lan; MqttPort 1883; MqttUser tasmota; \
+                 MqttPassword s3cret; SetOption3 1",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "MqttHost": "broker.lan",
+                "MqttPort": 1883,
+                "MqttUser": "tasmota",
+                "MqttPassword": "****",
+                "SetOption3": "ON"
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        let response = device
+            .configure_mqtt("broker.lan", 1883, "tasmota", "s3cret")
+            .await
+            .unwrap();
+
+        assert_eq!(response.host(), Some("broker.lan"));
+        assert_eq!(response.port(), Some(1883));
+        assert_eq!(response.enabled(), Some(true));
+        assert!(response.is_confirmed());
+    }
+
+    #[tokio::test]
+    async fn configure_mqtt_rejects_invalid_host() {
+        let mock_server = MockServer::start().await;
+        let device = create_device(&mock_server).await;
+
+        let result = device
+            .configure_mqtt("broker;Restart 1", 1883, "tasmota", "s3cret")
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(tasmor_lib::Error::Value(
+                tasmor_lib::ValueError::InvalidHostname(_)
+            ))
+        ));
+    }
+}
+
+// ============================================================================
+// Tuya MCU Tests
+// ============================================================================
+
+mod device_tuya {
+    use super::*;
+    use tasmor_lib::types::TuyaValue;
+
+    async fn create_device(mock_server: &MockServer) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn tuya_send_encodes_value() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "TuyaSend2 2,500"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"TuyaSend2": "Done"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+
+        device.tuya_send(2, TuyaValue::Int(500)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn tuya_send_without_mcu_is_rejected() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "TuyaSend1 1,1"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"Command": "Unknown"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+
+        let result = device.tuya_send(1, TuyaValue::Bool(true)).await;
+        assert!(matches!(result, Err(tasmor_lib::Error::Device(_))));
+    }
+
+    #[tokio::test]
+    async fn get_tuya_mappings() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "TuyaMCU"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "TuyaMCU": [{"fnId": 11, "dpId": 1}, {"fnId": 21, "dpId": 2}]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+
+        let mappings = device.get_tuya_mappings().await.unwrap();
+        assert_eq!(mappings.function_for(2), Some(21));
+        assert_eq!(mappings.dp_for(11), Some(1));
+    }
+
+    #[tokio::test]
+    async fn set_and_remove_tuya_mapping() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "TuyaMCU 71,101"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "TuyaMCU": [{"fnId": 11, "dpId": 1}, {"fnId": 71, "dpId": 101}]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "TuyaMCU 71,0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "TuyaMCU": [{"fnId": 11, "dpId": 1}]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+
+        let mappings = device.set_tuya_mapping(71, Some(101)).await.unwrap();
+        assert_eq!(mappings.function_for(101), Some(71));
+
+        let mappings = device.set_tuya_mapping(71, None).await.unwrap();
+        assert_eq!(mappings.dp_for(71), None);
+
+        assert!(device.set_tuya_mapping(120, Some(1)).await.is_err());
+    }
+}
+
+// ============================================================================
+// Device MQTT Topic Tests
+// ============================================================================
+
+mod device_topic_scheme {
+    use super::*;
+    use tasmor_lib::types::{RemoteTopic, TopicPrefix};
+
+    async fn create_device(mock_server: &MockServer) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    async fn mount_reply(mock_server: &MockServer, command: &str, value: &str) {
+        let mut body = serde_json::Map::new();
+        body.insert(command.to_string(), value.into());
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", command))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .expect(1)
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn topic_scheme_reads_full_topic_and_prefixes() {
+        let mock_server = MockServer::start().await;
+        mount_reply(&mock_server, "FullTopic", "home/%topic%/%prefix%/").await;
+        mount_reply(&mock_server, "Prefix1", "cmnd").await;
+        mount_reply(&mock_server, "Prefix2", "stat").await;
+        mount_reply(&mock_server, "Prefix3", "telemetry").await;
+
+        let device = create_device(&mock_server).await;
+
+        let scheme = device.topic_scheme().await.unwrap();
+        assert_eq!(scheme.full_topic(), "home/%topic%/%prefix%/");
+        assert_eq!(
+            scheme.topic(TopicPrefix::Tele, "kitchen", "STATE"),
+            "home/kitchen/telemetry/STATE"
+        );
+    }
+
+    #[tokio::test]
+    async fn group_topic_is_read() {
+        let mock_server = MockServer::start().await;
+        mount_reply(&mock_server, "GroupTopic", "living_room").await;
+
+        let device = create_device(&mock_server).await;
+
+        assert_eq!(device.group_topic().await.unwrap(), "living_room");
+    }
+
+    #[tokio::test]
+    async fn topic_scheme_rejects_hostname_placeholder() {
+        let mock_server = MockServer::start().await;
+        mount_reply(&mock_server, "FullTopic", "%prefix%/%hostname%/").await;
+
+        let device = create_device(&mock_server).await;
+
+        let result = device.topic_scheme().await;
+        assert!(matches!(result, Err(tasmor_lib::Error::Value(_))));
+    }
+
+    #[tokio::test]
+    async fn set_button_topic() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "ButtonTopic hall_lights"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"ButtonTopic": "hall_lights"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        let topic = RemoteTopic::new("hall_lights").unwrap();
+
+        assert_eq!(device.set_button_topic(topic.clone()).await.unwrap(), topic);
+    }
+
+    #[tokio::test]
+    async fn disabled_switch_topic_is_read() {
+        let mock_server = MockServer::start().await;
+        mount_reply(&mock_server, "SwitchTopic", "0").await;
+
+        let device = create_device(&mock_server).await;
+
+        assert_eq!(device.switch_topic().await.unwrap(), RemoteTopic::Disabled);
+    }
+}
+
+// ============================================================================
+// Device Capability Reprobe Tests
+// ============================================================================
+
+mod device_reprobe {
+    use super::*;
+
+    async fn mount_status(mock_server: &MockServer, command: &str, body: serde_json::Value) {
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", command))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .expect(1)
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn reprobe_reports_and_applies_changes() {
+        let mock_server = MockServer::start().await;
+        mount_status(
+            &mock_server,
+            "Status 1",
+            serde_json::json!({
+                "Status": {
+                    "Module": 0,
+                    "FriendlyName": ["Plug 1", "Plug 2"],
+                    "Topic": "plug"
+                }
+            }),
+        )
+        .await;
+        mount_status(
+            &mock_server,
+            "Status 11",
+            serde_json::json!({
+                "StatusSTS": { "POWER1": "ON", "POWER2": "OFF" }
+            }),
+        )
+        .await;
+        mount_status(
+            &mock_server,
+            "Status 10",
+            serde_json::json!({
+                "StatusSNS": {
+                    "Time": "2024-01-01T00:00:00",
+                    "ENERGY": { "Power": 12, "Voltage": 230 }
+                }
+            }),
+        )
+        .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (mut device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::cct_light())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        let diff = device.reprobe_capabilities().await.unwrap();
+
+        assert_eq!(diff.added(), ["energy_monitoring"]);
+        assert_eq!(
+            diff.removed(),
+            ["dimmer_control", "color_temperature_control"]
+        );
+        assert_eq!(diff.power_channels_changed(), Some((1, 2)));
+        assert_eq!(device.capabilities().power_channels(), 2);
+        assert!(device.capabilities().supports_energy_monitoring());
+        assert!(!device.capabilities().supports_dimmer_control());
+    }
+
+    #[tokio::test]
+    async fn reprobe_failure_keeps_capabilities() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 1"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (mut device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::rgbcct_light())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        assert!(device.reprobe_capabilities().await.is_err());
+        assert_eq!(device.capabilities(), &Capabilities::rgbcct_light());
+    }
+}
+
+// ============================================================================
+// Device Sensor Discovery Tests
+// ============================================================================
+
+mod device_sensors {
+    use super::*;
+    use tasmor_lib::telemetry::SensorKind;
+
+    #[tokio::test]
+    async fn available_sensors_lists_sensor_blocks() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 10"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "StatusSNS": {
+                    "Time": "2024-01-01T12:00:00",
+                    "BME280": { "Temperature": 21.5, "Humidity": 48.0 },
+                    "ENERGY": { "Power": 12 },
+                    "PressureUnit": "hPa",
+                    "TempUnit": "C"
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        let sensors = device.available_sensors().await.unwrap();
+        assert_eq!(sensors, vec![SensorKind::Bme280, SensorKind::Energy]);
+    }
+}
+
+// ============================================================================
+// Device SetOption Tests
+// ============================================================================
+
+mod device_set_options {
+    use super::*;
+
+    #[tokio::test]
+    async fn get_all_set_options_decodes_status_log() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "StatusLOG": {
+                    "SerialLog": 2,
+                    "WebLog": 2,
+                    "MqttLog": 0,
+                    "SysLog": 0,
+                    "LogHost": "",
+                    "LogPort": 514,
+                    "SSId": ["MyWiFi", ""],
+                    "TelePeriod": 300,
+                    "Resolution": "558180C0",
+                    "SetOption": [
+                        "00088009",
+                        "2805C80001000600003C5A0A190000000000",
+                        "00000080",
+                        "00006000",
+                        "00004000",
+                        "00000000"
+                    ]
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        let flags = device.get_all_set_options().await.unwrap();
+        assert_eq!(flags.save_power_state(), Some(true));
+        assert_eq!(flags.home_assistant_discovery(), Some(true));
+        assert_eq!(flags.boot_loop_threshold(), Some(1));
+        assert_eq!(flags.is_enabled(128), Some(true));
+        assert_eq!(flags.raw().len(), 178);
+    }
+
+    #[tokio::test]
+    async fn apply_light_defaults_sends_set_options() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Backlog0 SetOption20 1; SetOption91 1"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"SetOption91": "ON"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        let defaults = tasmor_lib::LightDefaults::new()
+            .with_power_on_with_change(false)
+            .with_fade_at_startup(true);
+        device.apply_light_defaults(&defaults).await.unwrap();
+
+        // Nothing to change, nothing sent
+        device
+            .apply_light_defaults(&tasmor_lib::LightDefaults::new())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn buttons_can_be_detached() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "SetOption73 1"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"SetOption73": "ON"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "StatusLOG": {
+                    "SetOption": [
+                        "00088009",
+                        "2805C80001000600003C5A0A190000000000",
+                        "00800000",
+                        "00006000",
+                        "00004000",
+                        "00000000"
+                    ]
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        device.set_buttons_detached(true).await.unwrap();
+        assert!(device.buttons_detached().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn persistence_settings() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "SetOption0 0"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"SetOption0": "OFF"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "SaveData 600"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"SaveData": "Every 600 sec"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        assert!(!device.set_persist_power_state(false).await.unwrap());
+        let save_data = device.set_save_data(600).await.unwrap();
+        assert_eq!(
+            save_data.interval().unwrap(),
+            Some(std::time::Duration::from_secs(600))
+        );
+        assert!(matches!(
+            device.set_save_data(7200).await,
+            Err(tasmor_lib::Error::Value(_))
+        ));
+    }
+}
+
+// ============================================================================
+// Device Power Cycle Tests
+// ============================================================================
+
+mod device_power_cycle {
+    use super::*;
+
+    async fn mount_power(mock_server: &MockServer, command: &str, state: &str) {
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", command))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "POWER1": state })),
+            )
+            .expect(1)
+            .mount(mock_server)
+            .await;
+    }
+
+    async fn create_device(mock_server: &MockServer) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn short_cycle_uses_client_delay() {
+        let mock_server = MockServer::start().await;
+        mount_power(&mock_server, "Power1 OFF", "OFF").await;
+        mount_power(&mock_server, "Power1 ON", "ON").await;
+
+        let device = create_device(&mock_server).await;
+
+        let response = device.power_cycle(Duration::from_millis(50)).await.unwrap();
+        assert_eq!(response.first_power_state().unwrap(), PowerState::On);
+    }
+
+    #[tokio::test]
+    async fn long_cycle_runs_on_device() {
+        let mock_server = MockServer::start().await;
+        // Mounted after the build, which also queries Power1
+        let device = create_device(&mock_server).await;
+        mount_power(
+            &mock_server,
+            "Backlog0 Power1 OFF; Delay 10; Power1 ON",
+            "OFF",
+        )
+        .await;
+        mount_power(&mock_server, "Power1", "ON").await;
+
+        let response = device.power_cycle(Duration::from_secs(1)).await.unwrap();
+        assert_eq!(response.first_power_state().unwrap(), PowerState::On);
+    }
+
+    #[tokio::test]
+    async fn set_pulse_time_encodes_duration() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "PulseTime1 1000"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(
+                    serde_json::json!({"PulseTime1": {"Set": 1000, "Remaining": 0}}),
+                ),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+
+        let response = device
+            .set_pulse_time(PowerIndex::one(), Duration::from_secs(900))
+            .await
+            .unwrap();
+        assert_eq!(response.duration(), Some(Duration::from_secs(900)));
+        assert_eq!(response.remaining(), Some(Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn set_timer_sends_json() {
+        use tasmor_lib::command::{Timer, TimerAction};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param(
+                "cmnd",
+                r#"Timer2 {"Enable":1,"Mode":0,"Time":"06:45","Window":0,"Days":"0111110","Repeat":1,"Output":1,"Action":1}"#,
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "Timers": "ON",
+                "Timer2": {
+                    "Enable": 1, "Mode": 0, "Time": "06:45", "Window": 0,
+                    "Days": "0111110", "Repeat": 1, "Output": 1, "Action": 1
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        let timer = Timer {
+            days: Timer::WEEKDAYS,
+            ..Timer::new(
+                chrono::NaiveTime::from_hms_opt(6, 45, 0).unwrap(),
+                PowerIndex::one(),
+                TimerAction::On,
+            )
+        };
+
+        let response = device.set_timer(2, timer).await.unwrap();
+        assert_eq!(response.index(), 2);
+        assert_eq!(response.timer(), timer);
+        assert_eq!(response.timers_enabled(), Some(true));
+
+        assert!(matches!(
+            device.get_timer(17).await,
+            Err(tasmor_lib::Error::Value(_))
+        ));
+    }
+}
+
+// ============================================================================
+// Apply State Changes Tests
+// ============================================================================
+
+mod device_apply_changes {
+    use super::*;
+    use tasmor_lib::CapabilitiesBuilder;
+    use tasmor_lib::state::StateChange;
+
+    async fn create_dimmable_device(mock_server: &MockServer) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(CapabilitiesBuilder::new().with_dimmer_control().build())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn sends_changes_in_order_as_backlog() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param(
+                "cmnd",
+                "Backlog0 Dimmer 30; Power1 ON; Dimmer 40",
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"POWER": "ON", "Dimmer": 40})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_dimmable_device(&mock_server).await;
+        let changes = [
+            StateChange::dimmer(Dimmer::new(30).unwrap()),
+            StateChange::Batch(vec![
+                StateChange::power_on(),
+                StateChange::dimmer(Dimmer::new(40).unwrap()),
+            ]),
+        ];
+
+        let response = device.apply_changes(&changes).await.unwrap();
+
+        assert_eq!(response.try_get_as::<u8>("Dimmer"), Some(40));
+    }
+
+    #[tokio::test]
+    async fn unsupported_changes_are_skipped() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Backlog0 Dimmer 50"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"POWER": "ON", "Dimmer": 50})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_dimmable_device(&mock_server).await;
+        let changes = [
+            StateChange::hsb_color(HsbColor::red()),
+            StateChange::power(2, PowerState::On),
+            StateChange::dimmer(Dimmer::new(50).unwrap()),
+        ];
+
+        device.apply_changes(&changes).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn nothing_sent_when_all_changes_are_skipped() {
+        let mock_server = MockServer::start().await;
+        let device = create_dimmable_device(&mock_server).await;
+
+        // Mounted after the build so that only the changes are counted
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let changes = [StateChange::color_temperature(ColorTemperature::WARM)];
+
+        let response = device.apply_changes(&changes).await.unwrap();
+
+        assert!(response.is_empty());
+    }
+}
+
+// ============================================================================
+// Custom Command Tests
+// ============================================================================
+
+mod device_custom_commands {
+    use super::*;
+    use tasmor_lib::command::Command;
+
+    struct LedPower;
+
+    impl Command for LedPower {
+        fn name(&self) -> String {
+            "LedPower1".to_string()
+        }
+
+        fn payload(&self) -> Option<String> {
+            None
+        }
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct LedPowerResponse {
+        #[serde(rename = "LedPower1")]
+        state: String,
+    }
+
+    async fn create_device(mock_server: &MockServer) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn send_and_parse_custom_response() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "LedPower1"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"LedPower1": "ON"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        let response: LedPowerResponse = device.send_and_parse(&LedPower).await.unwrap();
+
+        assert_eq!(response.state, "ON");
+    }
+
+    #[tokio::test]
+    async fn send_and_parse_reports_mismatched_response() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "LedPower1"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"Command": "Unknown"})),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        let result = device
+            .send_and_parse::<_, LedPowerResponse>(&LedPower)
+            .await;
+
+        assert!(matches!(result, Err(tasmor_lib::Error::Parse(_))));
+    }
+}
+
+// ============================================================================
+// Raw PWM Tests
+// ============================================================================
+
+mod device_pwm {
+    use super::*;
+    use tasmor_lib::CapabilitiesBuilder;
+
+    async fn create_pwm_device(mock_server: &MockServer, outputs: u8) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(CapabilitiesBuilder::new().pwm_channels(outputs).build())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn set_pwm_sends_duty_cycle() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "PWM2 512"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"PWM": {"PWM1": 0, "PWM2": 512}})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_pwm_device(&mock_server, 2).await;
+        let response = device.set_pwm(2, 512).await.unwrap();
+
+        assert_eq!(response.value(2), Some(512));
+    }
+
+    #[tokio::test]
+    async fn get_pwm_reads_flat_response() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "PWM1"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"PWM1": 300})),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let device = create_pwm_device(&mock_server, 1).await;
+
+        assert_eq!(device.get_pwm(1).await.unwrap(), 300);
+    }
+
+    #[tokio::test]
+    async fn channel_beyond_outputs_is_rejected() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param_contains("cmnd", "PWM"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_pwm_device(&mock_server, 2).await;
+        let result = device.set_pwm(3, 100).await;
+
+        assert!(matches!(
+            result,
+            Err(tasmor_lib::Error::Value(
+                tasmor_lib::ValueError::OutOfRange { max: 2, .. }
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn pwm_requires_capability() {
+        let mock_server = MockServer::start().await;
+        let device = create_pwm_device(&mock_server, 0).await;
+
+        let result = device.set_pwm(1, 100).await;
+
+        assert!(matches!(result, Err(tasmor_lib::Error::Device(_))));
+    }
+}
+
+// ============================================================================
+// Rule Event Tests
+// ============================================================================
+
+mod device_rule_events {
+    use super::*;
+
+    async fn create_device(mock_server: &MockServer) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn fire_event_with_value() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Event level=42"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"Event": "Done"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+
+        device.fire_event_with_value("level", "42").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn invalid_event_name_is_not_sent() {
+        let mock_server = MockServer::start().await;
+        let device = create_device(&mock_server).await;
+
+        // Mounted after the build so that only the event is counted
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let result = device.fire_event("night;Restart 1").await;
+
+        assert!(matches!(
+            result,
+            Err(tasmor_lib::Error::Value(
+                tasmor_lib::ValueError::InvalidEvent(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn unacknowledged_event_is_rejected() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Event night"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"Event": "Error"})),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        let result = device.fire_event("night").await;
+
+        assert!(matches!(
+            result,
+            Err(tasmor_lib::Error::Device(
+                tasmor_lib::error::DeviceError::CommandRejected(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn set_rule_stores_and_enables() {
+        let mock_server = MockServer::start().await;
+        let rule_json = serde_json::json!({"Rule2": {
+            "State": "ON", "Once": "OFF", "StopOnError": "OFF",
+            "Length": 39, "Free": 472, "Rules": "ON Event#night DO Power1 OFF ENDON"
+        }});
+        Mock::given(method("GET"))
+            .and(query_param(
+                "cmnd",
+                "Rule2 ON Event#night DO Power1 OFF ENDON",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rule_json.clone()))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Rule2 1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rule_json))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server).await;
+        let rule = tasmor_lib::RuleBuilder::new()
+            .on("Event#night")
+            .then_raw("Power1 OFF")
+            .build()
+            .unwrap();
+
+        device.set_rule(2, &rule).await.unwrap();
+        assert!(matches!(
+            device.set_rule(4, &rule).await,
+            Err(tasmor_lib::Error::Value(_))
+        ));
+    }
+}
+
+// ============================================================================
+// Busy Device Tests
+// ============================================================================
+
+mod device_busy {
+    use super::*;
+    use tasmor_lib::ProtocolError;
+
+    async fn create_device(config: HttpConfig) -> Device<HttpClient> {
+        let (device, _) = Device::http_config(config)
+            .with_capabilities(Capabilities::basic())
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    fn config(mock_server: &MockServer) -> HttpConfig {
+        HttpConfig::new(mock_server.uri().replace("http://", ""))
+    }
+
+    fn is_busy(error: &tasmor_lib::Error) -> bool {
+        matches!(error, tasmor_lib::Error::Protocol(ProtocolError::Busy))
+    }
+
+    #[tokio::test]
+    async fn too_many_requests_is_busy() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Power1 ON"))
+            .respond_with(ResponseTemplate::new(429))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(config(&mock_server)).await;
+        let error = device.power_on().await.unwrap_err();
+
+        assert!(is_busy(&error), "unexpected error: {error}");
+    }
+
+    #[tokio::test]
+    async fn busy_reply_is_busy() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Power1 ON"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"Command": "Busy"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(config(&mock_server)).await;
+        let error = device.power_on().await.unwrap_err();
+
+        assert!(is_busy(&error), "unexpected error: {error}");
+    }
+
+    #[tokio::test]
+    async fn busy_command_is_retried() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Power1 ON"))
+            .respond_with(ResponseTemplate::new(429))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Power1 ON"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"POWER1": "ON"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = config(&mock_server).with_busy_retry(2, Duration::from_millis(10));
+        let device = create_device(config).await;
+
+        device.power_on().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn retries_are_bounded() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Power1 ON"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"Command": "Busy"})),
+            )
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let config = config(&mock_server).with_busy_retry(1, Duration::from_millis(10));
+        let device = create_device(config).await;
+        let error = device.power_on().await.unwrap_err();
+
+        assert!(is_busy(&error), "unexpected error: {error}");
+    }
+}
+
+// ============================================================================
+// RGBWW Color Tests
+// ============================================================================
+
+mod device_rgbww_color {
+    use super::*;
+    use tasmor_lib::RgbwwColor;
+
+    async fn create_device(
+        mock_server: &MockServer,
+        capabilities: Capabilities,
+    ) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(capabilities)
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn sets_all_channels() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Color FF00000080"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "POWER": "ON",
+                "Dimmer": 100,
+                "Color": "FF00000080",
+                "HSBColor": "0,100,100"
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server, Capabilities::rgbcct_light()).await;
+        let color = RgbwwColor::new(255, 0, 0, 0, 128);
+        let response = device.set_rgbww_color(color).await.unwrap();
+
+        assert_eq!(response.rgbww_color().unwrap(), color);
+        assert_eq!(response.is_on(), Some(true));
+    }
+
+    #[tokio::test]
+    async fn rgb_only_light_is_rejected() {
+        let mock_server = MockServer::start().await;
+        let device = create_device(&mock_server, Capabilities::rgb_light()).await;
+
+        let result = device
+            .set_rgbww_color(RgbwwColor::new(255, 0, 0, 0, 128))
+            .await;
+        assert!(matches!(
+            result,
+            Err(tasmor_lib::Error::Device(
+                tasmor_lib::error::DeviceError::UnsupportedCapability { .. }
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn detects_light_type() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Color"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "POWER": "ON",
+                "Dimmer": 80,
+                "Color": "CC66",
+                "CT": 300,
+                "Channel": [80, 40]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        // The generic capabilities know nothing about the light
+        let device = create_device(&mock_server, Capabilities::basic()).await;
+        let light_type = device.detect_light_type().await.unwrap();
+
+        assert_eq!(light_type, tasmor_lib::LightType::Cct);
+    }
+
+    #[tokio::test]
+    async fn sets_white_channel() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Channel4 80"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "POWER": "ON",
+                "Dimmer": 80,
+                "Color": "000000CC00",
+                "Channel": [0, 0, 0, 80, 0]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server, Capabilities::rgbcct_light()).await;
+        let response = device
+            .set_channel(4, Dimmer::new(80).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.channel(4), Some(80));
+        assert_eq!(response.channel(5), Some(0));
+    }
+
+    #[tokio::test]
+    async fn channel_requires_dimmer() {
+        let mock_server = MockServer::start().await;
+        let device = create_device(&mock_server, Capabilities::basic()).await;
+
+        let result = device.get_channel(1).await;
+        assert!(matches!(result, Err(tasmor_lib::Error::Device(_))));
+
+        let device = create_device(&mock_server, Capabilities::rgbcct_light()).await;
+        let result = device.get_channel(6).await;
+        assert!(matches!(result, Err(tasmor_lib::Error::Value(_))));
+    }
+
+    #[tokio::test]
+    async fn sets_color_from_hex() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Color #FF5733"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "POWER": "ON",
+                "Dimmer": 100,
+                "Color": "FF5733",
+                "HSBColor": "11,80,100",
+                "Channel": [100, 34, 20]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server, Capabilities::rgb_light()).await;
+        let color = tasmor_lib::RgbColor::from_hex("#FF5733").unwrap();
+        let response = device.set_color_hex(color).await.unwrap();
+
+        assert_eq!(response.rgb_color(), color);
+        assert_eq!(response.hsb_color().hue(), 11);
+        assert_eq!(response.dimmer(), Some(100));
+    }
+}
+
+// ============================================================================
+// Build Timeout Tests
+// ============================================================================
+
+mod device_build_timeout {
+    use super::*;
+
+    #[tokio::test]
+    async fn stalled_detection_reports_phase() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Status 0"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({}))
+                    .set_delay(Duration::from_secs(2)),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let result = Device::http(&host)
+            .with_build_timeout(Duration::from_millis(200))
+            .build()
+            .await;
+
+        match result {
+            Err(tasmor_lib::Error::DeadlineExceeded { phase }) => {
+                assert_eq!(phase, "capability detection");
+            }
+            other => panic!("expected DeadlineExceeded, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn stalled_state_query_keeps_device() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Power1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"POWER1": "ON"}))
+                    .set_delay(Duration::from_secs(2)),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "Power1 OFF"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"POWER1": "OFF"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let host = mock_server.uri().replace("http://", "");
+        let (device, state) = Device::http(&host)
+            .with_capabilities(Capabilities::basic())
+            .with_build_timeout(Duration::from_millis(200))
+            .build_without_probe()
+            .await
+            .unwrap();
+
+        assert_eq!(state.power(1), None);
+        device.power_off().await.unwrap();
+    }
+}
+
+// ============================================================================
+// Shutter Tests
+// ============================================================================
+
+mod device_shutter {
+    use super::*;
+
+    async fn create_device(
+        mock_server: &MockServer,
+        capabilities: Capabilities,
+    ) -> Device<HttpClient> {
+        let host = mock_server.uri().replace("http://", "");
+        let (device, _) = Device::http(&host)
+            .with_capabilities(capabilities)
+            .build_without_probe()
+            .await
+            .unwrap();
+        device
+    }
+
+    #[tokio::test]
+    async fn set_position_sends_percent() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "ShutterPosition1 50"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "Shutter1": {"Position": 20, "Direction": 1, "Target": 50, "Tilt": 0}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server, Capabilities::shutter()).await;
+        let response = device.set_shutter_position(1, 50).await.unwrap();
+
+        assert_eq!(response.target(), 50);
+        assert_eq!(response.position(), Some(20));
+        assert_eq!(
+            response.direction(),
+            Some(tasmor_lib::response::ShutterDirection::Opening)
+        );
+    }
+
+    #[tokio::test]
+    async fn stop_accepts_done() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(query_param("cmnd", "ShutterStop2"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"ShutterStop2": "Done"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let device = create_device(&mock_server, Capabilities::shutter()).await;
+        device.stop_shutter(2).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn device_without_shutter_is_rejected() {
+        let mock_server = MockServer::start().await;
+        let device = create_device(&mock_server, Capabilities::basic()).await;
+
+        let result = device.open_shutter(1).await;
+        assert!(matches!(
+            result,
+            Err(tasmor_lib::Error::Device(
+                tasmor_lib::error::DeviceError::UnsupportedCapability { .. }
+            ))
+        ));
+    }
+}
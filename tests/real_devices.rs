@@ -10,7 +10,8 @@
 //!
 //! # Environment Variables
 //!
-//! Required environment variables:
+//! A test is skipped when the broker or a device it uses is not configured,
+//! so only the devices at hand need to be set (e.g. just `LIGHT_1`).
 //!
 //! ## MQTT Broker
 //! - `MQTT_BROKER_IP` - Broker IP address
@@ -18,7 +19,7 @@
 //! - `MQTT_USER` - MQTT username
 //! - `MQTT_PASSWORD` - MQTT password
 //!
-//! ## Devices (LIGHT_1, LIGHT_2, LIGHT_3, PLUG_1, PLUG_2)
+//! ## Devices (`LIGHT_1`, `LIGHT_2`, `LIGHT_3`, `PLUG_1`, `PLUG_2`)
 //! For each device, set:
 //! - `{DEVICE}_HTTP_IP` - Device IP address
 //! - `{DEVICE}_HTTP_USER` - HTTP username
@@ -40,7 +41,6 @@
 //! cargo test --test real_devices -- --ignored --test-threads=1
 //! ```
 
-use std::env;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
@@ -52,79 +52,9 @@ use tasmor_lib::types::{
 use tasmor_lib::{Capabilities, Device, MqttBroker};
 use tokio::time::sleep;
 
-// =============================================================================
-// Test Configuration from Environment Variables
-// =============================================================================
-
-/// MQTT Broker configuration loaded from environment variables.
-struct BrokerConfig {
-    ip: String,
-    port: u16,
-    user: String,
-    password: String,
-}
-
-impl BrokerConfig {
-    fn from_env() -> Self {
-        Self {
-            ip: env::var("MQTT_BROKER_IP").expect("MQTT_BROKER_IP not set"),
-            port: env::var("MQTT_BROKER_PORT")
-                .unwrap_or_else(|_| "1883".to_string())
-                .parse()
-                .expect("Invalid MQTT_BROKER_PORT"),
-            user: env::var("MQTT_USER").expect("MQTT_USER not set"),
-            password: env::var("MQTT_PASSWORD").expect("MQTT_PASSWORD not set"),
-        }
-    }
-}
-
-/// Device configuration loaded from environment variables.
-struct DeviceConfig {
-    http_ip: String,
-    http_user: String,
-    http_password: String,
-    mqtt_topic: String,
-}
-
-impl DeviceConfig {
-    fn from_env(prefix: &str) -> Self {
-        Self {
-            http_ip: env::var(format!("{prefix}_HTTP_IP"))
-                .unwrap_or_else(|_| panic!("{prefix}_HTTP_IP not set")),
-            http_user: env::var(format!("{prefix}_HTTP_USER"))
-                .unwrap_or_else(|_| panic!("{prefix}_HTTP_USER not set")),
-            http_password: env::var(format!("{prefix}_HTTP_PASSWORD"))
-                .unwrap_or_else(|_| panic!("{prefix}_HTTP_PASSWORD not set")),
-            mqtt_topic: env::var(format!("{prefix}_MQTT_TOPIC"))
-                .unwrap_or_else(|_| panic!("{prefix}_MQTT_TOPIC not set")),
-        }
-    }
-}
-
-// Helper functions to load device configs
-fn cfg_broker() -> BrokerConfig {
-    BrokerConfig::from_env()
-}
+mod support;
 
-fn cfg_light_1() -> DeviceConfig {
-    DeviceConfig::from_env("LIGHT_1")
-}
-
-fn cfg_light_2() -> DeviceConfig {
-    DeviceConfig::from_env("LIGHT_2")
-}
-
-fn cfg_light_3() -> DeviceConfig {
-    DeviceConfig::from_env("LIGHT_3")
-}
-
-fn cfg_plug_1() -> DeviceConfig {
-    DeviceConfig::from_env("PLUG_1")
-}
-
-fn cfg_plug_2() -> DeviceConfig {
-    DeviceConfig::from_env("PLUG_2")
-}
+use support::{skip_if_no_broker, skip_if_no_device};
 
 // =============================================================================
 // HTTP Protocol Tests
@@ -138,9 +68,9 @@ mod http_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn connect_to_light_with_auto_detection() {
-        let config = cfg_light_1();
+        let config = skip_if_no_device!("LIGHT_1");
         let (device, initial_state) = Device::http(&config.http_ip)
             .with_credentials(&config.http_user, &config.http_password)
             .build()
@@ -148,7 +78,7 @@ mod http_protocol {
             .expect("Failed to connect to light");
 
         println!("Device capabilities: {:?}", device.capabilities());
-        println!("Initial state: {:?}", initial_state);
+        println!("Initial state: {initial_state:?}");
 
         // Light should support dimmer
         assert!(
@@ -158,16 +88,17 @@ mod http_protocol {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn connect_to_plug_with_auto_detection() {
-        let (device, initial_state) = Device::http(cfg_plug_1().http_ip)
-            .with_credentials(cfg_plug_1().http_user, cfg_plug_1().http_password)
+        let plug_1_cfg = skip_if_no_device!("PLUG_1");
+        let (device, initial_state) = Device::http(&plug_1_cfg.http_ip)
+            .with_credentials(&plug_1_cfg.http_user, &plug_1_cfg.http_password)
             .build()
             .await
             .expect("Failed to connect to plug");
 
         println!("Device capabilities: {:?}", device.capabilities());
-        println!("Initial state: {:?}", initial_state);
+        println!("Initial state: {initial_state:?}");
 
         // Plug should support energy monitoring
         assert!(
@@ -177,10 +108,11 @@ mod http_protocol {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn connect_with_manual_capabilities() {
-        let (device, _) = Device::http(cfg_light_1().http_ip)
-            .with_credentials(cfg_light_1().http_user, cfg_light_1().http_password)
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
+        let (device, _) = Device::http(&light_1_cfg.http_ip)
+            .with_credentials(&light_1_cfg.http_user, &light_1_cfg.http_password)
             .with_capabilities(Capabilities::rgbcct_light())
             .build_without_probe()
             .await
@@ -195,10 +127,11 @@ mod http_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn power_on_off_toggle_light() {
-        let (device, _) = Device::http(cfg_light_1().http_ip)
-            .with_credentials(cfg_light_1().http_user, cfg_light_1().http_password)
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
+        let (device, _) = Device::http(&light_1_cfg.http_ip)
+            .with_credentials(&light_1_cfg.http_user, &light_1_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -242,10 +175,11 @@ mod http_protocol {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn power_control_plug() {
-        let (device, _) = Device::http(cfg_plug_2().http_ip)
-            .with_credentials(cfg_plug_2().http_user, cfg_plug_2().http_password)
+        let plug_2_cfg = skip_if_no_device!("PLUG_2");
+        let (device, _) = Device::http(&plug_2_cfg.http_ip)
+            .with_credentials(&plug_2_cfg.http_user, &plug_2_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -272,16 +206,17 @@ mod http_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn get_full_status() {
-        let (device, _) = Device::http(cfg_light_1().http_ip)
-            .with_credentials(cfg_light_1().http_user, cfg_light_1().http_password)
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
+        let (device, _) = Device::http(&light_1_cfg.http_ip)
+            .with_credentials(&light_1_cfg.http_user, &light_1_cfg.http_password)
             .build()
             .await
             .unwrap();
 
         let status = device.status().await.unwrap();
-        println!("Full status: {:?}", status);
+        println!("Full status: {status:?}");
 
         // Should have basic status info
         assert!(status.device_name().is_some(), "Should have device name");
@@ -290,16 +225,17 @@ mod http_protocol {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn get_abbreviated_status() {
-        let (device, _) = Device::http(cfg_plug_1().http_ip)
-            .with_credentials(cfg_plug_1().http_user, cfg_plug_1().http_password)
+        let plug_1_cfg = skip_if_no_device!("PLUG_1");
+        let (device, _) = Device::http(&plug_1_cfg.http_ip)
+            .with_credentials(&plug_1_cfg.http_user, &plug_1_cfg.http_password)
             .build()
             .await
             .unwrap();
 
         let status = device.status_abbreviated().await.unwrap();
-        println!("Abbreviated status: {:?}", status);
+        println!("Abbreviated status: {status:?}");
     }
 
     // -------------------------------------------------------------------------
@@ -307,10 +243,11 @@ mod http_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn dimmer_control() {
-        let (device, _) = Device::http(cfg_light_1().http_ip)
-            .with_credentials(cfg_light_1().http_user, cfg_light_1().http_password)
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
+        let (device, _) = Device::http(&light_1_cfg.http_ip)
+            .with_credentials(&light_1_cfg.http_user, &light_1_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -341,10 +278,11 @@ mod http_protocol {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn dimmer_unsupported_on_plug() {
-        let (device, _) = Device::http(cfg_plug_1().http_ip)
-            .with_credentials(cfg_plug_1().http_user, cfg_plug_1().http_password)
+        let plug_1_cfg = skip_if_no_device!("PLUG_1");
+        let (device, _) = Device::http(&plug_1_cfg.http_ip)
+            .with_credentials(&plug_1_cfg.http_user, &plug_1_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -353,8 +291,7 @@ mod http_protocol {
         let result = device.set_dimmer(Dimmer::new(50).unwrap()).await;
         assert!(
             result.is_err(),
-            "Dimmer should not be supported on plug: {:?}",
-            result
+            "Dimmer should not be supported on plug: {result:?}"
         );
     }
 
@@ -363,10 +300,11 @@ mod http_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn color_temperature_control() {
-        let (device, _) = Device::http(cfg_light_2().http_ip)
-            .with_credentials(cfg_light_2().http_user, cfg_light_2().http_password)
+        let light_2_cfg = skip_if_no_device!("LIGHT_2");
+        let (device, _) = Device::http(&light_2_cfg.http_ip)
+            .with_credentials(&light_2_cfg.http_user, &light_2_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -400,10 +338,11 @@ mod http_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn hsb_color_control() {
-        let (device, _) = Device::http(cfg_light_1().http_ip)
-            .with_credentials(cfg_light_1().http_user, cfg_light_1().http_password)
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
+        let (device, _) = Device::http(&light_1_cfg.http_ip)
+            .with_credentials(&light_1_cfg.http_user, &light_1_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -448,10 +387,11 @@ mod http_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn rgb_color_control() {
-        let (device, _) = Device::http(cfg_light_3().http_ip)
-            .with_credentials(cfg_light_3().http_user, cfg_light_3().http_password)
+        let light_3_cfg = skip_if_no_device!("LIGHT_3");
+        let (device, _) = Device::http(&light_3_cfg.http_ip)
+            .with_credentials(&light_3_cfg.http_user, &light_3_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -476,10 +416,11 @@ mod http_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn scheme_control() {
-        let (device, _) = Device::http(cfg_light_1().http_ip)
-            .with_credentials(cfg_light_1().http_user, cfg_light_1().http_password)
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
+        let (device, _) = Device::http(&light_1_cfg.http_ip)
+            .with_credentials(&light_1_cfg.http_user, &light_1_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -508,10 +449,11 @@ mod http_protocol {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn wakeup_duration_control() {
-        let (device, _) = Device::http(cfg_light_1().http_ip)
-            .with_credentials(cfg_light_1().http_user, cfg_light_1().http_password)
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
+        let (device, _) = Device::http(&light_1_cfg.http_ip)
+            .with_credentials(&light_1_cfg.http_user, &light_1_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -541,10 +483,11 @@ mod http_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn fade_control() {
-        let (device, _) = Device::http(cfg_light_2().http_ip)
-            .with_credentials(cfg_light_2().http_user, cfg_light_2().http_password)
+        let light_2_cfg = skip_if_no_device!("LIGHT_2");
+        let (device, _) = Device::http(&light_2_cfg.http_ip)
+            .with_credentials(&light_2_cfg.http_user, &light_2_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -588,10 +531,11 @@ mod http_protocol {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn fade_at_startup_control() {
-        let (device, _) = Device::http(cfg_light_1().http_ip)
-            .with_credentials(cfg_light_1().http_user, cfg_light_1().http_password)
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
+        let (device, _) = Device::http(&light_1_cfg.http_ip)
+            .with_credentials(&light_1_cfg.http_user, &light_1_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -625,10 +569,11 @@ mod http_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn energy_monitoring() {
-        let (device, _) = Device::http(cfg_plug_1().http_ip)
-            .with_credentials(cfg_plug_1().http_user, cfg_plug_1().http_password)
+        let plug_1_cfg = skip_if_no_device!("PLUG_1");
+        let (device, _) = Device::http(&plug_1_cfg.http_ip)
+            .with_credentials(&plug_1_cfg.http_user, &plug_1_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -652,10 +597,11 @@ mod http_protocol {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn energy_unsupported_on_light() {
-        let (device, _) = Device::http(cfg_light_1().http_ip)
-            .with_credentials(cfg_light_1().http_user, cfg_light_1().http_password)
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
+        let (device, _) = Device::http(&light_1_cfg.http_ip)
+            .with_credentials(&light_1_cfg.http_user, &light_1_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -663,8 +609,7 @@ mod http_protocol {
         let result = device.energy().await;
         assert!(
             result.is_err(),
-            "Energy should not be supported on light: {:?}",
-            result
+            "Energy should not be supported on light: {result:?}"
         );
     }
 
@@ -673,12 +618,13 @@ mod http_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn run_routine() {
         use tasmor_lib::command::Routine;
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
 
-        let (device, _) = Device::http(cfg_light_1().http_ip)
-            .with_credentials(cfg_light_1().http_user, cfg_light_1().http_password)
+        let (device, _) = Device::http(&light_1_cfg.http_ip)
+            .with_credentials(&light_1_cfg.http_user, &light_1_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -693,7 +639,7 @@ mod http_protocol {
             .unwrap();
 
         let response = device.run(&routine).await.unwrap();
-        println!("Routine response: {:?}", response);
+        println!("Routine response: {response:?}");
 
         // Check final dimmer value
         if let Ok(dimmer) = response.get_as::<u8>("Dimmer") {
@@ -705,12 +651,13 @@ mod http_protocol {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn run_color_routine() {
         use tasmor_lib::command::Routine;
+        let light_3_cfg = skip_if_no_device!("LIGHT_3");
 
-        let (device, _) = Device::http(cfg_light_3().http_ip)
-            .with_credentials(cfg_light_3().http_user, cfg_light_3().http_password)
+        let (device, _) = Device::http(&light_3_cfg.http_ip)
+            .with_credentials(&light_3_cfg.http_user, &light_3_cfg.http_password)
             .build()
             .await
             .unwrap();
@@ -727,7 +674,7 @@ mod http_protocol {
             .unwrap();
 
         let response = device.run(&routine).await.unwrap();
-        println!("Color routine response: {:?}", response);
+        println!("Color routine response: {response:?}");
 
         sleep(Duration::from_secs(1)).await;
         device.power_off().await.unwrap();
@@ -746,44 +693,46 @@ mod mqtt_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn connect_to_broker() {
+        let broker_cfg = skip_if_no_broker!();
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .expect("Failed to connect to MQTT broker");
 
         println!(
             "Connected to MQTT broker at {}:{}",
-            cfg_broker().ip,
-            cfg_broker().port
+            &broker_cfg.ip, broker_cfg.port
         );
 
         broker.disconnect().await.unwrap();
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn connect_to_device_via_mqtt() {
+        let broker_cfg = skip_if_no_broker!();
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (device, initial_state) = broker
-            .device(cfg_light_1().mqtt_topic)
+            .device(&light_1_cfg.mqtt_topic)
             .build()
             .await
             .expect("Failed to connect to device via MQTT");
 
         println!("Device topic: {}", device.topic());
-        println!("Initial state: {:?}", initial_state);
+        println!("Initial state: {initial_state:?}");
         println!("Capabilities: {:?}", device.capabilities());
 
         device.disconnect().await;
@@ -795,18 +744,20 @@ mod mqtt_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn mqtt_power_control() {
+        let broker_cfg = skip_if_no_broker!();
+        let light_2_cfg = skip_if_no_device!("LIGHT_2");
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (device, _) = broker
-            .device(cfg_light_2().mqtt_topic)
+            .device(&light_2_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
@@ -833,18 +784,20 @@ mod mqtt_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn power_change_subscription() {
+        let broker_cfg = skip_if_no_broker!();
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (device, _) = broker
-            .device(cfg_light_1().mqtt_topic)
+            .device(&light_1_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
@@ -854,7 +807,7 @@ mod mqtt_protocol {
 
         // Subscribe to power changes
         let _sub_id = device.on_power_changed(move |idx, state| {
-            println!("Power callback: relay {} = {:?}", idx, state);
+            println!("Power callback: relay {idx} = {state:?}");
             count_clone.fetch_add(1, Ordering::SeqCst);
         });
 
@@ -866,7 +819,7 @@ mod mqtt_protocol {
 
         // Check callbacks were triggered
         let count = callback_count.load(Ordering::SeqCst);
-        println!("Power callbacks received: {}", count);
+        println!("Power callbacks received: {count}");
         assert!(
             count >= 2,
             "Should have received at least 2 power callbacks"
@@ -877,18 +830,20 @@ mod mqtt_protocol {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn dimmer_change_subscription() {
+        let broker_cfg = skip_if_no_broker!();
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (device, _) = broker
-            .device(cfg_light_1().mqtt_topic)
+            .device(&light_1_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
@@ -911,7 +866,7 @@ mod mqtt_protocol {
         sleep(Duration::from_millis(300)).await;
 
         let count = callback_count.load(Ordering::SeqCst);
-        println!("Dimmer callbacks received: {}", count);
+        println!("Dimmer callbacks received: {count}");
         assert!(
             count >= 2,
             "Should have received at least 2 dimmer callbacks"
@@ -923,18 +878,20 @@ mod mqtt_protocol {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn color_change_subscription() {
+        let broker_cfg = skip_if_no_broker!();
+        let light_3_cfg = skip_if_no_device!("LIGHT_3");
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (device, _) = broker
-            .device(cfg_light_3().mqtt_topic)
+            .device(&light_3_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
@@ -967,7 +924,7 @@ mod mqtt_protocol {
         sleep(Duration::from_millis(300)).await;
 
         let count = callback_count.load(Ordering::SeqCst);
-        println!("Color callbacks received: {}", count);
+        println!("Color callbacks received: {count}");
         assert!(
             count >= 2,
             "Should have received at least 2 color callbacks"
@@ -979,18 +936,20 @@ mod mqtt_protocol {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn unsubscribe_callback() {
+        let broker_cfg = skip_if_no_broker!();
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (device, _) = broker
-            .device(cfg_light_1().mqtt_topic)
+            .device(&light_1_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
@@ -1029,33 +988,33 @@ mod mqtt_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn multiple_devices_same_broker() {
+        let broker_cfg = skip_if_no_broker!();
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
+        let light_2_cfg = skip_if_no_device!("LIGHT_2");
+        let plug_1_cfg = skip_if_no_device!("PLUG_1");
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (device1, _) = broker
-            .device(cfg_light_1().mqtt_topic)
+            .device(&light_1_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
 
         let (device2, _) = broker
-            .device(cfg_light_2().mqtt_topic)
+            .device(&light_2_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
 
-        let (device3, _) = broker
-            .device(cfg_plug_1().mqtt_topic)
-            .build()
-            .await
-            .unwrap();
+        let (device3, _) = broker.device(&plug_1_cfg.mqtt_topic).build().await.unwrap();
 
         println!("Device 1 topic: {}", device1.topic());
         println!("Device 2 topic: {}", device2.topic());
@@ -1083,18 +1042,20 @@ mod mqtt_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn mqtt_dimmer_control() {
+        let broker_cfg = skip_if_no_broker!();
+        let light_2_cfg = skip_if_no_device!("LIGHT_2");
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (device, _) = broker
-            .device(cfg_light_2().mqtt_topic)
+            .device(&light_2_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
@@ -1123,18 +1084,20 @@ mod mqtt_protocol {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn mqtt_color_temperature_control() {
+        let broker_cfg = skip_if_no_broker!();
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (device, _) = broker
-            .device(cfg_light_1().mqtt_topic)
+            .device(&light_1_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
@@ -1166,18 +1129,20 @@ mod mqtt_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn mqtt_fade_control() {
+        let broker_cfg = skip_if_no_broker!();
+        let light_2_cfg = skip_if_no_device!("LIGHT_2");
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (device, _) = broker
-            .device(cfg_light_2().mqtt_topic)
+            .device(&light_2_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
@@ -1221,18 +1186,20 @@ mod mqtt_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn mqtt_scheme_control() {
+        let broker_cfg = skip_if_no_broker!();
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (device, _) = broker
-            .device(cfg_light_1().mqtt_topic)
+            .device(&light_1_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
@@ -1269,18 +1236,20 @@ mod mqtt_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn mqtt_wakeup_duration_control() {
+        let broker_cfg = skip_if_no_broker!();
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (device, _) = broker
-            .device(cfg_light_1().mqtt_topic)
+            .device(&light_1_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
@@ -1313,20 +1282,22 @@ mod mqtt_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn mqtt_run_routine() {
         use tasmor_lib::command::Routine;
+        let broker_cfg = skip_if_no_broker!();
+        let light_3_cfg = skip_if_no_device!("LIGHT_3");
 
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (device, _) = broker
-            .device(cfg_light_3().mqtt_topic)
+            .device(&light_3_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
@@ -1341,7 +1312,7 @@ mod mqtt_protocol {
             .unwrap();
 
         let response = device.run(&routine).await.unwrap();
-        println!("MQTT Routine response: {:?}", response);
+        println!("MQTT Routine response: {response:?}");
 
         sleep(Duration::from_millis(500)).await;
 
@@ -1356,21 +1327,19 @@ mod mqtt_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn mqtt_energy_monitoring() {
+        let broker_cfg = skip_if_no_broker!();
+        let plug_2_cfg = skip_if_no_device!("PLUG_2");
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
-        let (device, _) = broker
-            .device(cfg_plug_2().mqtt_topic)
-            .build()
-            .await
-            .unwrap();
+        let (device, _) = broker.device(&plug_2_cfg.mqtt_topic).build().await.unwrap();
 
         let response = device.energy().await.unwrap();
         let energy = response.energy().expect("Should have energy data");
@@ -1389,18 +1358,20 @@ mod mqtt_protocol {
     // -------------------------------------------------------------------------
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn device_disconnect_is_idempotent() {
+        let broker_cfg = skip_if_no_broker!();
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (device, _) = broker
-            .device(cfg_light_1().mqtt_topic)
+            .device(&light_1_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
@@ -1426,26 +1397,28 @@ mod cross_protocol {
     use super::*;
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn http_and_mqtt_see_same_state() {
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
+        let broker_cfg = skip_if_no_broker!();
         // Connect via HTTP
-        let (http_device, _) = Device::http(cfg_light_1().http_ip)
-            .with_credentials(cfg_light_1().http_user, cfg_light_1().http_password)
+        let (http_device, _) = Device::http(&light_1_cfg.http_ip)
+            .with_credentials(&light_1_cfg.http_user, &light_1_cfg.http_password)
             .build()
             .await
             .unwrap();
 
         // Connect via MQTT
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
 
         let (mqtt_device, _) = broker
-            .device(cfg_light_1().mqtt_topic)
+            .device(&light_1_cfg.mqtt_topic)
             .build()
             .await
             .unwrap();
@@ -1491,7 +1464,7 @@ mod error_handling {
     use super::*;
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn http_connection_to_invalid_ip() {
         let result = Device::http("192.168.11.254") // Non-existent IP
             .with_credentials("admin", "password")
@@ -1503,9 +1476,10 @@ mod error_handling {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn http_wrong_credentials() {
-        let result = Device::http(cfg_light_1().http_ip)
+        let light_1_cfg = skip_if_no_device!("LIGHT_1");
+        let result = Device::http(&light_1_cfg.http_ip)
             .with_credentials("wrong_user", "wrong_password")
             .build()
             .await;
@@ -1515,7 +1489,7 @@ mod error_handling {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn mqtt_connection_to_invalid_broker() {
         let result = MqttBroker::builder()
             .host("192.168.11.254") // Non-existent broker
@@ -1529,11 +1503,12 @@ mod error_handling {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn mqtt_wrong_credentials() {
+        let broker_cfg = skip_if_no_broker!();
         let result = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
             .credentials("wrong_user", "wrong_password")
             .build()
             .await;
@@ -1543,12 +1518,13 @@ mod error_handling {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[ignore = "requires real Tasmota devices"]
     async fn mqtt_invalid_device_topic() {
+        let broker_cfg = skip_if_no_broker!();
         let broker = MqttBroker::builder()
-            .host(cfg_broker().ip)
-            .port(cfg_broker().port)
-            .credentials(cfg_broker().user, cfg_broker().password)
+            .host(&broker_cfg.ip)
+            .port(broker_cfg.port)
+            .credentials(&broker_cfg.user, &broker_cfg.password)
             .build()
             .await
             .unwrap();
@@ -1561,7 +1537,7 @@ mod error_handling {
         // This might timeout or fail depending on implementation
         match result {
             Ok(_) => println!("Unexpectedly succeeded"),
-            Err(e) => println!("Error for invalid topic: {:?}", e),
+            Err(e) => println!("Error for invalid topic: {e:?}"),
         }
 
         broker.disconnect().await.unwrap();
@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: MPL-2.0
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Test configuration loaded from environment variables.
+//!
+//! Configurations are optional: a test whose broker or device is not
+//! configured is skipped instead of failing, so a subset of the real
+//! device tests can be run with a partial setup.
+
+use std::env;
+
+/// MQTT Broker configuration loaded from environment variables.
+pub struct TestBrokerConfig {
+    pub ip: String,
+    pub port: u16,
+    pub user: String,
+    pub password: String,
+}
+
+impl TestBrokerConfig {
+    /// Loads the broker configuration, or returns `None` if a required
+    /// variable is not set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `MQTT_BROKER_PORT` is set but not a valid port.
+    pub fn from_env_opt() -> Option<Self> {
+        Some(Self {
+            ip: env::var("MQTT_BROKER_IP").ok()?,
+            port: env::var("MQTT_BROKER_PORT")
+                .unwrap_or_else(|_| "1883".to_string())
+                .parse()
+                .expect("Invalid MQTT_BROKER_PORT"),
+            user: env::var("MQTT_USER").ok()?,
+            password: env::var("MQTT_PASSWORD").ok()?,
+        })
+    }
+}
+
+/// Device configuration loaded from environment variables.
+pub struct TestDeviceConfig {
+    pub http_ip: String,
+    pub http_user: String,
+    pub http_password: String,
+    pub mqtt_topic: String,
+}
+
+impl TestDeviceConfig {
+    /// Loads the configuration of the device with the given prefix (e.g.
+    /// `LIGHT_1`), or returns `None` if one of its variables is not set.
+    pub fn from_env_opt(prefix: &str) -> Option<Self> {
+        let var = |name: &str| env::var(format!("{prefix}_{name}")).ok();
+        Some(Self {
+            http_ip: var("HTTP_IP")?,
+            http_user: var("HTTP_USER")?,
+            http_password: var("HTTP_PASSWORD")?,
+            mqtt_topic: var("MQTT_TOPIC")?,
+        })
+    }
+}
+
+/// Returns the configuration of a device, or skips the test if the device
+/// is not configured.
+macro_rules! skip_if_no_device {
+    ($prefix:expr) => {
+        match $crate::support::TestDeviceConfig::from_env_opt($prefix) {
+            Some(config) => config,
+            None => {
+                eprintln!("Skipping test: device {} is not configured", $prefix);
+                return;
+            }
+        }
+    };
+}
+
+/// Returns the broker configuration, or skips the test if the broker is
+/// not configured.
+macro_rules! skip_if_no_broker {
+    () => {
+        match $crate::support::TestBrokerConfig::from_env_opt() {
+            Some(config) => config,
+            None => {
+                eprintln!("Skipping test: MQTT broker is not configured");
+                return;
+            }
+        }
+    };
+}
+
+pub(crate) use {skip_if_no_broker, skip_if_no_device};